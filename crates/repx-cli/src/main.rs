@@ -1,16 +1,22 @@
 use clap::{Args, CommandFactory, Parser, Subcommand};
 use clap_complete::{generate, Shell};
 use colored::Colorize;
+use repx_client::Client;
 use repx_core::{
     cache::{CacheStats, CacheStore, FsCache, KNOWN_CACHE_TYPES},
-    model::SchedulerType,
+    constants::targets,
+    engine::{self, JobStatus},
+    lab::LabSource,
+    model::{JobId, SchedulerType},
+    store::{completion_log, outcomes},
 };
 use repx_runner::cli::Commands as RunnerCommands;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use which::which;
+use std::time::Duration;
 
 mod init;
+mod report;
 
 #[derive(Parser)]
 #[command(name = "repx")]
@@ -46,7 +52,7 @@ struct Cli {
     #[arg(
         long,
         global = true,
-        help = "The scheduler to use: 'slurm' or 'local'. Overrides the target's configuration."
+        help = "The scheduler to use: 'slurm', 'sge', 'flux', 'aws-batch' or 'local'. Overrides the target's configuration."
     )]
     pub scheduler: Option<SchedulerType>,
 }
@@ -62,8 +68,10 @@ enum Commands {
     #[command(about = "Visualize the experiment topology")]
     Viz(VizArgs),
 
-    #[command(about = "Debug/Run a job locally with interactive shell")]
-    DebugRun(DebugRunArgs),
+    #[command(
+        about = "Render a static HTML report of job statuses, params, durations and failures"
+    )]
+    Report(ReportArgs),
 
     #[command(about = "Initialize a new repx experiment project")]
     Init(InitArgs),
@@ -77,12 +85,46 @@ enum Commands {
 
 #[derive(Args)]
 struct VizArgs {
-    #[arg(short, long, help = "Output file path")]
+    #[arg(
+        short,
+        long,
+        help = "Output file path, or '-' to write dot, mermaid, plantuml, graphml or json to stdout"
+    )]
     output: Option<PathBuf>,
 
-    #[arg(long, help = "Output format (png, pdf, svg, etc.)")]
+    #[arg(
+        long,
+        help = "Output format (png, pdf, svg, etc., mermaid, plantuml, or term to print a tree)"
+    )]
     format: Option<String>,
 
+    #[arg(
+        long,
+        default_value = "graphviz",
+        help = "Renderer: 'graphviz' (needs 'dot' on PATH) or 'builtin' (svg only, no dependencies)"
+    )]
+    engine: repx_viz::VizEngine,
+
+    #[arg(
+        long,
+        default_value = "dot",
+        help = "Graphviz layout engine: 'dot', 'neato' or 'fdp'"
+    )]
+    graphviz_engine: repx_viz::GraphvizLayout,
+
+    #[arg(long, default_value = "LR", help = "Graph direction: LR, TB, RL or BT")]
+    rankdir: repx_viz::RankDir,
+
+    #[arg(
+        long,
+        value_name = "INCHES",
+        help = "Minimum space between nodes in the same rank"
+    )]
+    nodesep: Option<f64>,
+
+    #[arg(long, value_name = "INCHES", help = "Minimum space between ranks")]
+    ranksep: Option<f64>,
+
     #[arg(
         long,
         default_value_t = true,
@@ -111,6 +153,21 @@ struct VizArgs {
     )]
     show_params: bool,
 
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Draw every job as its own node with its concrete parameters instead of grouping by stage"
+    )]
+    no_aggregate: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 500,
+        help = "Collapse the largest runs into summary nodes until at most N stage nodes remain (0 disables)"
+    )]
+    max_nodes: usize,
+
     #[arg(
         long,
         default_value_t = false,
@@ -124,15 +181,111 @@ struct VizArgs {
         help = "Hide inter-run dependency edges"
     )]
     no_inter_edges: bool,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Label edges with the outputs and inputs they connect"
+    )]
+    edge_labels: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Style file merged over the defaults and the user's viz.toml"
+    )]
+    style: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Color stages by job status on the active target"
+    )]
+    with_status: bool,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with = "diff",
+        help = "Keep polling job statuses and re-render whenever they change (implies --with-status)"
+    )]
+    watch: bool,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "watch",
+        help = "Seconds between status polls in --watch mode"
+    )]
+    interval: u64,
+
+    #[arg(long, help = "Only draw jobs of this run and their direct neighbors")]
+    filter_run: Option<String>,
+
+    #[arg(
+        long,
+        help = "Only draw jobs of this group's runs and their direct neighbors"
+    )]
+    filter_group: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "REGEX",
+        help = "Only draw jobs whose name or ID matches the pattern, and their direct neighbors"
+    )]
+    filter_job: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "OTHER_LAB",
+        help = "Compare against another lab and color added, removed, and changed jobs"
+    )]
+    diff: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Highlight the longest dependency chain using recorded or estimated job durations"
+    )]
+    critical_path: bool,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Annotate stages with elapsed runtime and completion time of finished jobs"
+    )]
+    timings: bool,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Exit with an error if any job is outside every run or references a missing job"
+    )]
+    strict: bool,
 }
 
 #[derive(Args)]
-struct DebugRunArgs {
-    #[arg(help = "Job ID to debug")]
-    job_id: String,
+struct ReportArgs {
+    #[arg(
+        short,
+        long,
+        default_value = "report",
+        help = "Directory to write index.html and topology.svg into"
+    )]
+    output: PathBuf,
 
-    #[arg(short, long, help = "Command to run (defaults to shell)")]
-    command: Option<String>,
+    #[arg(long, default_value_t = false, help = "Leave out the topology graph")]
+    no_topology: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 20,
+        help = "Lines of stderr to show for each failed job"
+    )]
+    excerpt_lines: u32,
 }
 
 #[derive(Args)]
@@ -274,6 +427,10 @@ fn main() {
                     | RunnerCommands::InternalExecute(_)
                     | RunnerCommands::InternalScatterGather(_)
                     | RunnerCommands::InternalGc(_)
+                    | RunnerCommands::InternalImagesPrune(_)
+                    | RunnerCommands::InternalClean(_)
+                    | RunnerCommands::InternalPrune(_)
+                    | RunnerCommands::InternalCasLink(_)
             );
 
             if !is_internal {
@@ -316,12 +473,12 @@ fn main() {
 
             if let Err(e) = repx_runner::run(runner_cli) {
                 eprintln!("{}", format!("[ERROR] {}", e).red());
-                std::process::exit(1);
+                std::process::exit(e.exit_code());
             }
         }
         Commands::Tui(tui_cmd) => {
             let tui_args = repx_tui::TuiArgs {
-                lab: cli.lab,
+                lab: resolve_lab(&cli.lab),
                 screenshot: tui_cmd.screenshot,
                 screenshot_width: tui_cmd.screenshot_width,
                 screenshot_height: tui_cmd.screenshot_height,
@@ -333,30 +490,86 @@ fn main() {
         }
         Commands::Viz(args) => {
             let show_runs = args.runs || args.groups;
+            let lab = resolve_lab(&cli.lab);
+            if args.watch && args.output.as_deref() == Some(Path::new(repx_viz::STDOUT_PATH)) {
+                eprintln!(
+                    "{}",
+                    "[ERROR] --watch needs an output file and cannot write to stdout.".red()
+                );
+                std::process::exit(1);
+            }
+            let job_statuses = if args.with_status || args.watch {
+                match query_job_statuses(&lab, cli.target.as_deref(), cli.scheduler) {
+                    Ok(statuses) => Some(statuses),
+                    Err(e) => {
+                        eprintln!("{}", format!("[ERROR] {}", e).red());
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                None
+            };
+            let job_durations = args
+                .critical_path
+                .then(|| recorded_job_durations(cli.target.as_deref()));
+            let job_timings = args
+                .timings
+                .then(|| recorded_job_timings(&lab, cli.target.as_deref()));
             let viz_args = repx_viz::VizArgs {
-                lab: cli.lab,
+                lab,
                 output: args.output,
                 format: args.format,
+                engine: args.engine,
+                graphviz_layout: args.graphviz_engine,
+                rankdir: args.rankdir,
+                nodesep: args.nodesep,
+                ranksep: args.ranksep,
                 show_pipelines: args.pipelines,
                 show_runs,
                 show_groups: args.groups,
                 show_params: args.show_params,
+                aggregate: !args.no_aggregate,
+                max_nodes: (args.max_nodes > 0).then_some(args.max_nodes),
                 show_intra_edges: !args.no_intra_edges,
                 show_inter_edges: !args.no_inter_edges,
+                edge_labels: args.edge_labels,
+                filter_run: args.filter_run,
+                filter_group: args.filter_group,
+                filter_job: args.filter_job,
+                job_statuses,
+                diff: args.diff.as_deref().map(resolve_lab),
+                style: args.style,
+                critical_path: args.critical_path,
+                job_durations,
+                job_timings,
+                strict: args.strict,
             };
+            if args.watch {
+                watch_viz(
+                    viz_args,
+                    cli.target.as_deref(),
+                    cli.scheduler,
+                    Duration::from_secs(args.interval),
+                );
+            }
             if let Err(e) = repx_viz::run(viz_args) {
                 eprintln!("{}", format!("[ERROR] {}", e).red());
                 std::process::exit(1);
             }
         }
-        Commands::DebugRun(args) => {
-            run_python_tool("repx_py.cli.debug_runner", |cmd| {
-                cmd.arg(&args.job_id);
-                cmd.arg("--lab").arg(&cli.lab);
-                if let Some(c) = &args.command {
-                    cmd.arg("--command").arg(c);
-                }
-            });
+        Commands::Report(args) => {
+            let lab = resolve_lab(&cli.lab);
+            let options = report::ReportOptions {
+                output: args.output,
+                topology: !args.no_topology,
+                excerpt_lines: args.excerpt_lines,
+            };
+            if let Err(e) =
+                report::handle_report(&lab, cli.target.as_deref(), cli.scheduler, options)
+            {
+                eprintln!("{}", format!("[ERROR] {}", e).red());
+                std::process::exit(1);
+            }
         }
         Commands::Init(args) => {
             let path = &args.path;
@@ -391,6 +604,143 @@ fn main() {
     }
 }
 
+fn resolve_lab(lab: &Path) -> PathBuf {
+    match repx_core::workspace::resolve_lab_arg(lab) {
+        Ok(resolved) => resolved.path,
+        Err(e) => {
+            eprintln!("{}", format!("[ERROR] {}", e).red());
+            std::process::exit(1);
+        }
+    }
+}
+
+fn query_job_statuses(
+    lab: &Path,
+    target: Option<&str>,
+    scheduler: Option<SchedulerType>,
+) -> Result<HashMap<JobId, JobStatus>, String> {
+    let config =
+        repx_core::config::load_config().map_err(|e| format!("Failed to load config: {}", e))?;
+    let target_name = target
+        .map(str::to_string)
+        .or_else(|| config.submission_target.clone())
+        .unwrap_or_else(|| targets::LOCAL.to_string());
+    let scheduler = scheduler.or_else(|| {
+        config
+            .targets
+            .get(&target_name)
+            .and_then(|t| t.default_scheduler)
+            .or(config.default_scheduler)
+    });
+    let client = Client::new(config, LabSource::from_path(lab))
+        .map_err(|e| format!("Failed to initialize client: {}", e))?;
+    let found = client
+        .get_statuses_for_active_target(&target_name, scheduler)
+        .map_err(|e| format!("Failed to query job statuses on '{}': {}", target_name, e))?;
+    Ok(engine::determine_job_statuses(client.lab(), found))
+}
+
+fn watch_viz(
+    mut viz_args: repx_viz::VizArgs,
+    target: Option<&str>,
+    scheduler: Option<SchedulerType>,
+    interval: Duration,
+) -> ! {
+    println!(
+        "Watching job statuses every {}s. Press Ctrl-C to stop.",
+        interval.as_secs()
+    );
+    let mut rendered: Option<HashMap<JobId, JobStatus>> = None;
+    loop {
+        if rendered != viz_args.job_statuses {
+            if rendered.is_some() {
+                println!(
+                    "[{}] Job statuses changed, re-rendering.",
+                    chrono::Local::now().format("%H:%M:%S")
+                );
+                if viz_args.job_durations.is_some() {
+                    viz_args.job_durations = Some(recorded_job_durations(target));
+                }
+                if viz_args.job_timings.is_some() {
+                    viz_args.job_timings = Some(recorded_job_timings(&viz_args.lab, target));
+                }
+            }
+            if let Err(e) = repx_viz::run(viz_args.clone()) {
+                eprintln!("{}", format!("[ERROR] {}", e).red());
+                std::process::exit(1);
+            }
+            rendered = viz_args.job_statuses.clone();
+        }
+
+        std::thread::sleep(interval);
+        match query_job_statuses(&viz_args.lab, target, scheduler) {
+            Ok(statuses) => viz_args.job_statuses = Some(statuses),
+            Err(e) => eprintln!(
+                "{}",
+                format!("[WARN] {}, keeping the last rendered graph", e).yellow()
+            ),
+        }
+    }
+}
+
+fn target_base_path(target: Option<&str>) -> Result<Option<(String, PathBuf)>, String> {
+    let config =
+        repx_core::config::load_config().map_err(|e| format!("Failed to load config: {}", e))?;
+    let target_name = target
+        .map(str::to_string)
+        .or_else(|| config.submission_target.clone())
+        .unwrap_or_else(|| targets::LOCAL.to_string());
+    Ok(config
+        .targets
+        .get(&target_name)
+        .map(|t| (target_name, t.base_path.clone())))
+}
+
+fn recorded_job_durations(target: Option<&str>) -> HashMap<JobId, Duration> {
+    let (target_name, base_path) = match target_base_path(target) {
+        Ok(Some(found)) => found,
+        Ok(None) => return HashMap::new(),
+        Err(e) => {
+            eprintln!(
+                "{}",
+                format!("[WARN] {}, using estimated durations", e).yellow()
+            );
+            return HashMap::new();
+        }
+    };
+    completion_log::read_durations(&base_path).unwrap_or_else(|e| {
+        eprintln!(
+            "{}",
+            format!(
+                "[WARN] Failed to read recorded durations on '{}', using estimates: {}",
+                target_name, e
+            )
+            .yellow()
+        );
+        HashMap::new()
+    })
+}
+
+fn recorded_job_timings(lab: &Path, target: Option<&str>) -> HashMap<JobId, outcomes::JobTiming> {
+    let timings = target_base_path(target).and_then(|found| {
+        let Some((target_name, base_path)) = found else {
+            return Ok(HashMap::new());
+        };
+        let lab = repx_core::lab::load_from_path(lab)
+            .map_err(|e| format!("Failed to load lab: {}", e))?;
+        let job_ids: Vec<JobId> = lab.jobs.into_keys().collect();
+        outcomes::get_job_timings(&base_path, &job_ids)
+            .map_err(|e| format!("Failed to read job timings on '{}': {}", target_name, e))
+    });
+    timings.unwrap_or_else(|e| {
+        eprintln!(
+            "{}",
+            format!("[WARN] {}, drawing without timings", e).yellow()
+        );
+        HashMap::new()
+    })
+}
+
 fn get_cache_root() -> Result<PathBuf, String> {
     let config =
         repx_core::config::load_config().map_err(|e| format!("Failed to load config: {}", e))?;
@@ -619,37 +969,3 @@ fn format_age(timestamp: chrono::DateTime<chrono::Utc>) -> String {
         format!("{}m", mins)
     }
 }
-
-fn run_python_tool<F>(module: &str, setup_args: F)
-where
-    F: FnOnce(&mut Command),
-{
-    let python = if which("python3").is_ok() {
-        "python3"
-    } else if which("python").is_ok() {
-        "python"
-    } else {
-        eprintln!("{}", "[ERROR] Python is not installed or not in PATH. 'repx viz/trace/debug' requires Python.".red());
-        std::process::exit(1);
-    };
-
-    let mut cmd = Command::new(python);
-    cmd.arg("-m").arg(module);
-    setup_args(&mut cmd);
-
-    let status = cmd.status();
-    match status {
-        Ok(s) => {
-            if !s.success() {
-                std::process::exit(s.code().unwrap_or(1));
-            }
-        }
-        Err(e) => {
-            eprintln!(
-                "{}",
-                format!("[ERROR] Failed to execute python tool: {}", e).red()
-            );
-            std::process::exit(1);
-        }
-    }
-}
@@ -0,0 +1,388 @@
+use colored::Colorize;
+use repx_client::{client::LogType, Client};
+use repx_core::{
+    config::Config,
+    constants::{dirs, targets},
+    engine::{self, JobStatus},
+    lab::LabSource,
+    model::{JobId, Lab, RunId, SchedulerType},
+    store::outcomes::{self, FailureInfo, JobTiming},
+};
+use repx_runner::commands::{list::format_job_status, trace::compute_all_effective_params};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:2em}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#f4f4f4}\
+code,pre{font-family:monospace;font-size:90%}\
+pre{background:#f8f8f8;padding:8px;overflow-x:auto}\
+.succeeded{color:#1a7f37}.failed,.timed-out,.lost{color:#cf222e}\
+.cancelled,.blocked{color:#9a6700}.running,.queued{color:#0969da}\
+img{max-width:100%}";
+
+pub struct ReportOptions {
+    pub output: PathBuf,
+    pub topology: bool,
+    pub excerpt_lines: u32,
+}
+
+struct ReportData<'a> {
+    lab: &'a Lab,
+    target_name: &'a str,
+    base_path: Option<&'a Path>,
+    statuses: &'a HashMap<JobId, JobStatus>,
+    timings: &'a HashMap<JobId, JobTiming>,
+    params: &'a HashMap<JobId, serde_json::Value>,
+    excerpts: &'a BTreeMap<JobId, Vec<String>>,
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn format_params(params: Option<&serde_json::Value>) -> String {
+    match params {
+        Some(serde_json::Value::Object(map)) if !map.is_empty() => map
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Some(serde_json::Value::Object(_)) | Some(serde_json::Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn format_elapsed(timing: Option<&JobTiming>) -> String {
+    match timing.and_then(|t| t.elapsed) {
+        Some(elapsed) => {
+            let secs = elapsed.as_secs();
+            format!("{}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+        }
+        None => String::new(),
+    }
+}
+
+fn output_links(data: &ReportData, job_id: &JobId) -> String {
+    let Some(base_path) = data.base_path else {
+        return String::new();
+    };
+    let out_dir = base_path
+        .join(dirs::OUTPUTS)
+        .join(job_id.as_str())
+        .join(dirs::OUT);
+    if !out_dir.is_dir() {
+        return String::new();
+    }
+    let mut links = vec![format!(
+        "<a href=\"file://{}/\">out/</a>",
+        escape(&out_dir.to_string_lossy())
+    )];
+    if let Some(job) = data.lab.jobs.get(job_id) {
+        let mut declared: Vec<(&String, String)> = job
+            .executables
+            .values()
+            .flat_map(|exe| exe.outputs.iter())
+            .filter_map(|(name, template)| {
+                let path = template
+                    .as_str()?
+                    .replace("$out", &out_dir.to_string_lossy());
+                Path::new(&path).exists().then_some((name, path))
+            })
+            .collect();
+        declared.sort();
+        declared.dedup();
+        links.extend(declared.into_iter().map(|(name, path)| {
+            format!("<a href=\"file://{}\">{}</a>", escape(&path), escape(name))
+        }));
+    }
+    links.join(" ")
+}
+
+fn render_summary(html: &mut String, data: &ReportData) {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for job_id in data.lab.jobs.keys() {
+        *counts
+            .entry(format_job_status(data.statuses.get(job_id)))
+            .or_default() += 1;
+    }
+    html.push_str("<h2>Status</h2>\n<table><tr><th>Status</th><th>Jobs</th></tr>\n");
+    for (status, count) in &counts {
+        let _ = writeln!(
+            html,
+            "<tr><td class=\"{0}\">{0}</td><td>{1}</td></tr>",
+            status, count
+        );
+    }
+    let _ = writeln!(
+        html,
+        "<tr><th>total</th><th>{}</th></tr>\n</table>",
+        data.lab.jobs.len()
+    );
+}
+
+fn render_run(html: &mut String, data: &ReportData, run_id: &RunId) {
+    let Some(run) = data.lab.runs.get(run_id) else {
+        return;
+    };
+    let mut job_ids: Vec<&JobId> = run.jobs.iter().collect();
+    job_ids.sort();
+    let _ = writeln!(
+        html,
+        "<h3 id=\"run-{0}\">{0}</h3>\n<table><tr><th>Job</th><th>Status</th><th>Params</th><th>Duration</th><th>Outputs</th></tr>",
+        escape(run_id.as_str())
+    );
+    for job_id in job_ids {
+        let status = format_job_status(data.statuses.get(job_id));
+        let job_cell = if data.excerpts.contains_key(job_id) {
+            format!(
+                "<a href=\"#fail-{0}\"><code>{1}</code></a>",
+                escape(job_id.as_str()),
+                escape(&job_id.short_id())
+            )
+        } else {
+            format!("<code>{}</code>", escape(&job_id.short_id()))
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"{status}\">{status}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            job_cell,
+            escape(&format_params(data.params.get(job_id))),
+            format_elapsed(data.timings.get(job_id)),
+            output_links(data, job_id),
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn render_failures(html: &mut String, data: &ReportData) {
+    if data.excerpts.is_empty() {
+        return;
+    }
+    html.push_str("<h2>Failures</h2>\n");
+    for (job_id, lines) in data.excerpts {
+        let _ = writeln!(
+            html,
+            "<h3 id=\"fail-{0}\"><code>{0}</code> <span class=\"{1}\">{1}</span></h3>",
+            escape(job_id.as_str()),
+            format_job_status(data.statuses.get(job_id))
+        );
+        let summary = data
+            .base_path
+            .map(|base| {
+                base.join(dirs::OUTPUTS)
+                    .join(job_id.as_str())
+                    .join(dirs::REPX)
+            })
+            .and_then(|repx_dir| FailureInfo::read_marker(&repx_dir))
+            .and_then(|info| info.summary);
+        if let Some(summary) = summary {
+            let _ = writeln!(html, "<p>{}</p>", escape(&summary));
+        }
+        if lines.is_empty() {
+            html.push_str("<p><em>No stderr output.</em></p>\n");
+        } else {
+            let _ = writeln!(html, "<pre>{}</pre>", escape(&lines.join("\n")));
+        }
+    }
+}
+
+fn render(data: &ReportData, topology: Option<&str>) -> String {
+    let lab = data.lab;
+    let mut html = String::new();
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>RepX report {0}</title><style>{1}</style></head><body>\n<h1>RepX report</h1>",
+        escape(&lab.lab_version),
+        STYLE
+    );
+    let _ = writeln!(
+        html,
+        "<table><tr><th>Lab version</th><td>{}</td></tr><tr><th>Git hash</th><td><code>{}</code></td></tr><tr><th>Content hash</th><td><code>{}</code></td></tr><tr><th>Target</th><td>{}</td></tr><tr><th>Generated</th><td>{}</td></tr></table>",
+        escape(&lab.lab_version),
+        escape(&lab.git_hash),
+        escape(&lab.content_hash),
+        escape(data.target_name),
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S %Z"),
+    );
+
+    render_summary(&mut html, data);
+
+    if let Some(svg) = topology {
+        let _ = writeln!(
+            html,
+            "<h2>Topology</h2>\n<p><a href=\"{0}\"><img src=\"{0}\" alt=\"Lab topology\"></a></p>",
+            svg
+        );
+    }
+
+    let mut run_ids: Vec<&RunId> = lab.runs.keys().collect();
+    run_ids.sort();
+    html.push_str("<h2>Runs</h2>\n<ul>\n");
+    for run_id in &run_ids {
+        let _ = writeln!(
+            html,
+            "<li><a href=\"#run-{0}\">{0}</a></li>",
+            escape(run_id.as_str())
+        );
+    }
+    html.push_str("</ul>\n");
+    for run_id in run_ids {
+        render_run(&mut html, data, run_id);
+    }
+
+    render_failures(&mut html, data);
+    html.push_str("</body></html>\n");
+    html
+}
+
+fn render_topology(
+    lab_path: &Path,
+    output: &Path,
+    statuses: &HashMap<JobId, JobStatus>,
+) -> Result<(), String> {
+    let viz_args = repx_viz::VizArgs {
+        lab: lab_path.to_path_buf(),
+        output: Some(output.join("topology")),
+        format: Some("svg".to_string()),
+        engine: repx_viz::VizEngine::Builtin,
+        graphviz_layout: repx_viz::GraphvizLayout::Dot,
+        rankdir: repx_viz::RankDir::LeftRight,
+        nodesep: None,
+        ranksep: None,
+        show_pipelines: true,
+        show_runs: false,
+        show_groups: false,
+        show_params: false,
+        aggregate: true,
+        max_nodes: Some(500),
+        show_intra_edges: true,
+        show_inter_edges: true,
+        edge_labels: false,
+        filter_run: None,
+        filter_group: None,
+        filter_job: None,
+        job_statuses: Some(statuses.clone()),
+        diff: None,
+        style: None,
+        critical_path: false,
+        job_durations: None,
+        job_timings: None,
+        strict: false,
+    };
+    repx_viz::run(viz_args).map_err(|e| e.to_string())
+}
+
+pub fn handle_report(
+    lab_path: &Path,
+    target: Option<&str>,
+    scheduler: Option<SchedulerType>,
+    options: ReportOptions,
+) -> Result<(), String> {
+    let config: Config =
+        repx_core::config::load_config().map_err(|e| format!("Failed to load config: {}", e))?;
+    let target_name = target
+        .map(str::to_string)
+        .or_else(|| config.submission_target.clone())
+        .unwrap_or_else(|| targets::LOCAL.to_string());
+    let scheduler = scheduler.or_else(|| {
+        config
+            .targets
+            .get(&target_name)
+            .and_then(|t| t.default_scheduler)
+            .or(config.default_scheduler)
+    });
+    let base_path = config
+        .targets
+        .get(&target_name)
+        .map(|t| t.base_path.clone());
+    let client = Client::new(config, LabSource::from_path(lab_path))
+        .map_err(|e| format!("Failed to initialize client: {}", e))?;
+    let lab = client.lab();
+
+    let found = client
+        .get_statuses_for_active_target(&target_name, scheduler)
+        .map_err(|e| format!("Failed to query job statuses on '{}': {}", target_name, e))?;
+    let statuses = engine::determine_job_statuses(lab, found);
+    let job_ids: Vec<JobId> = lab.jobs.keys().cloned().collect();
+    let timings = match &base_path {
+        Some(base) => outcomes::get_job_timings(base, &job_ids).unwrap_or_else(|e| {
+            eprintln!(
+                "{}",
+                format!(
+                    "[WARN] Failed to read job timings on '{}': {}",
+                    target_name, e
+                )
+                .yellow()
+            );
+            HashMap::new()
+        }),
+        None => HashMap::new(),
+    };
+    let params = compute_all_effective_params(lab);
+
+    let mut excerpts = BTreeMap::new();
+    for (job_id, status) in &statuses {
+        if !matches!(
+            status,
+            JobStatus::Failed { .. } | JobStatus::TimedOut { .. } | JobStatus::Lost { .. }
+        ) {
+            continue;
+        }
+        let lines = client
+            .get_log_tail(
+                job_id.clone(),
+                &target_name,
+                options.excerpt_lines,
+                LogType::Stderr,
+            )
+            .unwrap_or_default();
+        excerpts.insert(job_id.clone(), lines);
+    }
+
+    fs::create_dir_all(&options.output)
+        .map_err(|e| format!("Failed to create {}: {}", options.output.display(), e))?;
+    let topology = if options.topology {
+        match render_topology(lab_path, &options.output, &statuses) {
+            Ok(()) => Some("topology.svg"),
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    format!("[WARN] Failed to render topology, omitting it: {}", e).yellow()
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let data = ReportData {
+        lab,
+        target_name: &target_name,
+        base_path: base_path.as_deref(),
+        statuses: &statuses,
+        timings: &timings,
+        params: &params,
+        excerpts: &excerpts,
+    };
+    let index = options.output.join("index.html");
+    fs::write(&index, render(&data, topology))
+        .map_err(|e| format!("Failed to write {}: {}", index.display(), e))?;
+    println!("Wrote {}.", index.display());
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use crate::client::status_cache::StatusCache;
+use crate::error::Result;
+use crate::targets::{SlurmJobInfo, SlurmState, Target};
+use repx_core::{
+    engine::JobStatus,
+    model::{JobId, SchedulerType},
+    protocol::{AgentEvent, AgentQueuedJob},
+};
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+fn queued_job(info: &SlurmJobInfo) -> AgentQueuedJob {
+    AgentQueuedJob {
+        job_id: info.repx_id.clone(),
+        scheduler_id: info.slurm_id,
+        state: match &info.state {
+            SlurmState::Pending => "PD".to_string(),
+            SlurmState::Running => "R".to_string(),
+            SlurmState::Other(code) => code.clone(),
+        },
+        reason: info.reason.clone(),
+    }
+}
+
+pub(crate) fn job_info(job: AgentQueuedJob) -> SlurmJobInfo {
+    SlurmJobInfo {
+        slurm_id: job.scheduler_id,
+        state: match job.state.as_str() {
+            "PD" => SlurmState::Pending,
+            "R" => SlurmState::Running,
+            _ => SlurmState::Other(job.state),
+        },
+        repx_id: job.job_id,
+        reason: job.reason,
+    }
+}
+
+fn queue(target: &dyn Target, scheduler: SchedulerType) -> Result<Vec<AgentQueuedJob>> {
+    let queued = match scheduler {
+        SchedulerType::Sge => target.qstat()?,
+        SchedulerType::Flux => target.flux_jobs()?,
+        _ => target.squeue()?,
+    };
+    let mut jobs: Vec<AgentQueuedJob> = queued.values().map(queued_job).collect();
+    jobs.sort_by(|a, b| a.job_id.cmp(&b.job_id));
+    Ok(jobs)
+}
+
+fn outcome_changes(
+    sent: &HashMap<JobId, JobStatus>,
+    current: &HashMap<JobId, JobStatus>,
+) -> Option<AgentEvent> {
+    let changed: HashMap<JobId, JobStatus> = current
+        .iter()
+        .filter(|(job_id, status)| sent.get(*job_id) != Some(*status))
+        .map(|(job_id, status)| (job_id.clone(), status.clone()))
+        .collect();
+    let mut cleared: Vec<JobId> = sent
+        .keys()
+        .filter(|job_id| !current.contains_key(*job_id))
+        .cloned()
+        .collect();
+    cleared.sort();
+    (!changed.is_empty() || !cleared.is_empty())
+        .then_some(AgentEvent::Outcomes { changed, cleared })
+}
+
+pub fn run(
+    target: &dyn Target,
+    schedulers: &[SchedulerType],
+    interval: Duration,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let cache = StatusCache::default();
+    let mut outcomes = HashMap::new();
+    let mut queues: HashMap<SchedulerType, Vec<AgentQueuedJob>> = HashMap::new();
+    let mut heartbeats_sent = false;
+
+    loop {
+        let mut events = Vec::new();
+        let mut complete = true;
+
+        match cache.outcomes(target) {
+            Ok(current) => {
+                events.extend(outcome_changes(&outcomes, &current));
+                outcomes = current;
+            }
+            Err(e) => {
+                tracing::warn!("Agent could not read outcomes: {}", e);
+                complete = false;
+            }
+        }
+
+        for &scheduler in schedulers {
+            match queue(target, scheduler) {
+                Ok(jobs) if queues.get(&scheduler) != Some(&jobs) => {
+                    events.push(AgentEvent::Queue {
+                        scheduler,
+                        jobs: jobs.clone(),
+                    });
+                    queues.insert(scheduler, jobs);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("Agent could not read the {} queue: {}", scheduler, e);
+                    complete = false;
+                }
+            }
+        }
+
+        match target.check_heartbeats() {
+            Ok(ages) if !ages.is_empty() || heartbeats_sent => {
+                heartbeats_sent = !ages.is_empty();
+                events.push(AgentEvent::Heartbeats {
+                    ages: ages
+                        .into_iter()
+                        .map(|(job_id, age)| (job_id, age.as_secs()))
+                        .collect(),
+                });
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!("Agent could not read heartbeats: {}", e);
+                complete = false;
+            }
+        }
+
+        events.push(AgentEvent::Synced { complete });
+        for event in &events {
+            let line = serde_json::to_string(event)?;
+            if writeln!(out, "{}", line).is_err() {
+                return Ok(());
+            }
+        }
+        if out.flush().is_err() {
+            return Ok(());
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_changes() {
+        let done = JobId::from("aaa-done-1.0");
+        let redone = JobId::from("bbb-redone-1.0");
+        let succeeded = JobStatus::Succeeded {
+            location: "cluster".to_string(),
+        };
+        let sent: HashMap<JobId, JobStatus> = [
+            (done.clone(), succeeded.clone()),
+            (redone.clone(), succeeded.clone()),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(outcome_changes(&sent, &sent), None);
+
+        let failed = JobStatus::Failed {
+            location: "cluster".to_string(),
+            class: None,
+            reason: None,
+        };
+        let current: HashMap<JobId, JobStatus> =
+            [(redone.clone(), failed.clone())].into_iter().collect();
+        assert_eq!(
+            outcome_changes(&sent, &current),
+            Some(AgentEvent::Outcomes {
+                changed: current.clone(),
+                cleared: vec![done],
+            })
+        );
+    }
+
+    struct OneScan(Vec<u8>);
+
+    impl Write for OneScan {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    #[test]
+    fn test_outcomes_report_the_target_name_as_location() {
+        let base = tempfile::tempdir().expect("tempdir");
+        let job_id = JobId::from("aaa-train-1.0");
+        repx_core::store::completion_log::append_completion(base.path(), &job_id, true, None)
+            .expect("append completion");
+        let target = crate::targets::local::LocalTarget::new(
+            "cluster".to_string(),
+            repx_core::config::Target {
+                base_path: base.path().to_path_buf(),
+                ..Default::default()
+            },
+            std::path::PathBuf::new(),
+        );
+
+        let mut out = OneScan(Vec::new());
+        run(&target, &[], Duration::ZERO, &mut out).expect("agent scan");
+        let first = String::from_utf8(out.0).expect("utf8");
+        let first = first.lines().next().expect("an event");
+        let Ok(AgentEvent::Outcomes { changed, .. }) = serde_json::from_str(first) else {
+            panic!("not an outcomes event: {}", first);
+        };
+        assert_eq!(
+            changed[&job_id],
+            JobStatus::Succeeded {
+                location: "cluster".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_queue_entries_roundtrip() {
+        let info = SlurmJobInfo {
+            slurm_id: 42,
+            repx_id: JobId::from("aaa-train-1.0"),
+            state: SlurmState::Other("CG".to_string()),
+            reason: Some("Resources".to_string()),
+        };
+        let line = serde_json::to_string(&AgentEvent::Queue {
+            scheduler: SchedulerType::Slurm,
+            jobs: vec![queued_job(&info)],
+        })
+        .expect("serialize");
+        let Ok(AgentEvent::Queue { mut jobs, .. }) = serde_json::from_str(&line) else {
+            panic!("not a queue event: {}", line);
+        };
+        let back = job_info(jobs.remove(0));
+        assert_eq!(back.slurm_id, 42);
+        assert_eq!(back.state, info.state);
+        assert_eq!(back.reason, info.reason);
+    }
+}
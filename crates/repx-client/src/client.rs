@@ -1,18 +1,25 @@
 use crate::{
     error::{ClientError, Result},
-    targets::{local::LocalTarget, ssh::SshTarget, Target},
+    object_store::ObjectStore,
+    targets::{local::LocalTarget, ssh::SshTarget, ObjectStoreTarget, Target},
 };
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets, Attribute, Cell, Color, Table};
 use fs_err;
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
 use repx_core::{
     cache::{CacheKey, CacheMetadata, CacheStore, CacheStoreExt, FsCache},
     config::{Config, Resources},
-    constants::{dirs, logs, targets},
+    constants::{dirs, logs, markers, targets},
     engine,
     errors::CoreError,
     lab,
     lab::LabSource,
-    model::{Job, JobId, Lab, RunId, SchedulerType},
+    model::{Job, JobId, Lab, RunId, SchedulerType, StageType},
+    store::{
+        completion_log,
+        progress::{self, ScatterProgress},
+    },
 };
 use sha2::{Digest, Sha256};
 use std::path::Path;
@@ -23,10 +30,22 @@ use std::{
     sync::{atomic::AtomicBool, mpsc::Sender, Arc, Mutex},
 };
 
+pub(crate) mod agents;
+pub mod aws_batch;
+pub mod flux;
+pub(crate) mod host_ledger;
+pub(crate) mod in_flight;
 pub mod local;
+pub(crate) mod local_pids;
+pub(crate) mod local_queue;
 pub mod scheduler;
+pub mod sge;
 pub mod slurm;
+pub(crate) mod slurm_failures;
 pub mod status;
+pub(crate) mod status_cache;
+
+pub use local_queue::{LocalQueueReport, RunningUnit, WaitingUnit};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogType {
@@ -76,6 +95,10 @@ pub enum ClientEvent {
     SyncingArtifactProgress {
         path: PathBuf,
     },
+    SyncProgress {
+        bytes_done: u64,
+        bytes_total: u64,
+    },
     SyncingFinished,
     SubmittingJobs {
         total: usize,
@@ -83,7 +106,13 @@ pub enum ClientEvent {
     },
     JobSubmitted {
         job_id: JobId,
-        slurm_id: u32,
+        slurm_id: u64,
+        total: usize,
+        current: usize,
+    },
+    CloudJobSubmitted {
+        job_id: JobId,
+        cloud_id: String,
         total: usize,
         current: usize,
     },
@@ -122,11 +151,27 @@ pub enum ClientEvent {
         wave: usize,
         num_jobs: usize,
     },
+    SubmissionHeld {
+        target: String,
+        reason: String,
+        opens_at: chrono::DateTime<chrono::Local>,
+    },
+    SubmissionPaced {
+        target: String,
+        in_flight: usize,
+        limit: usize,
+    },
 }
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub(crate) struct SlurmJobEntry {
     pub target_name: String,
-    pub slurm_id: u32,
+    pub slurm_id: u64,
+    #[serde(default = "default_batch_scheduler")]
+    pub scheduler: SchedulerType,
+}
+
+fn default_batch_scheduler() -> SchedulerType {
+    SchedulerType::Slurm
 }
 
 type SlurmIdMap = Arc<Mutex<HashMap<JobId, SlurmJobEntry>>>;
@@ -144,11 +189,12 @@ pub(crate) fn lock_slurm_map(
     })
 }
 
-pub(crate) fn resolve_execution_type(
+pub fn resolve_execution_type(
     image_tag: Option<&str>,
     explicit_execution_type: Option<&str>,
     target_config: &repx_core::config::Target,
     scheduler_config: Option<&repx_core::config::SchedulerConfig>,
+    capabilities: Option<&crate::targets::RuntimeCapabilities>,
 ) -> String {
     use repx_core::model::ExecutionType;
 
@@ -162,11 +208,28 @@ pub(crate) fn resolve_execution_type(
                 Some(cfg) => cfg,
                 None => return ExecutionType::Native.to_string(),
             };
-            target_config
+            let configured = target_config
                 .default_execution_type
                 .filter(|et| sched_config.execution_types.contains(et))
+                .or_else(|| sched_config.execution_types.first().copied());
+            let available = capabilities.and_then(|caps| {
+                configured
+                    .into_iter()
+                    .chain(sched_config.execution_types.iter().copied())
+                    .find(|et| caps.supports(*et))
+            });
+            if let (Some(configured), Some(available)) = (configured, available) {
+                if configured != available {
+                    tracing::debug!(
+                        "Runtime '{}' is not available on the target; using '{}'",
+                        configured,
+                        available
+                    );
+                }
+            }
+            available
+                .or(configured)
                 .map(|et| et.to_string())
-                .or_else(|| sched_config.execution_types.first().map(|s| s.to_string()))
                 .unwrap_or_else(|| ExecutionType::Native.to_string())
         })
 }
@@ -190,6 +253,62 @@ pub struct SubmitOptions {
     pub verbose: repx_core::logging::Verbosity,
     pub cancel_flag: Option<Arc<AtomicBool>>,
     pub artifact_store: repx_core::model::ArtifactStore,
+    pub ignore_submission_windows: bool,
+    pub retry_failed: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CancelReport {
+    pub scheduler: Option<SchedulerType>,
+    pub slurm_ids: Vec<u64>,
+    pub pids: Vec<u32>,
+    pub cloud_ids: Vec<String>,
+}
+
+impl CancelReport {
+    pub fn is_empty(&self) -> bool {
+        self.slurm_ids.is_empty() && self.pids.is_empty() && self.cloud_ids.is_empty()
+    }
+
+    pub fn describe_handles(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.slurm_ids.is_empty() {
+            let scheduler = match self.scheduler {
+                Some(SchedulerType::Sge) => "SGE",
+                Some(SchedulerType::Flux) => "Flux",
+                _ => "SLURM",
+            };
+            let ids: Vec<String> = self.slurm_ids.iter().map(u64::to_string).collect();
+            parts.push(format!("{} {}", scheduler, ids.join(", ")));
+        }
+        if !self.pids.is_empty() {
+            let pids: Vec<String> = self.pids.iter().map(u32::to_string).collect();
+            parts.push(format!("PID {}", pids.join(", ")));
+        }
+        if !self.cloud_ids.is_empty() {
+            parts.push(format!("AWS Batch {}", self.cloud_ids.join(", ")));
+        }
+        parts.join(", ")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FetchReport {
+    pub fetched: Vec<(JobId, PathBuf)>,
+    pub failed: Vec<(JobId, ClientError)>,
+}
+
+fn worker_slurm_ids(target: &dyn Target, job_id: &JobId) -> Option<Vec<u64>> {
+    let manifest_path = target
+        .base_path()
+        .join(dirs::OUTPUTS)
+        .join(job_id.as_str())
+        .join(dirs::REPX)
+        .join(repx_core::constants::manifests::WORKER_SLURM_IDS);
+    let content = target.read_remote_file_tail(&manifest_path, 10000).ok()?;
+    serde_json::from_str::<Vec<u64>>(&content.join("\n"))
+        .ok()
+        .filter(|ids| !ids.is_empty())
 }
 
 pub struct SubmissionTarget {
@@ -206,7 +325,13 @@ pub struct Client {
     pub(crate) targets: HashMap<String, Arc<dyn Target>>,
     pub(crate) slurm_map: SlurmIdMap,
     pub(crate) slurm_map_path: PathBuf,
+    pub(crate) local_pids_path: PathBuf,
+    pub(crate) local_queue_path: PathBuf,
+    pub(crate) slurm_failures_path: PathBuf,
+    pub(crate) aws_jobs_path: PathBuf,
     pub(crate) cache: Arc<FsCache>,
+    pub(crate) status_cache: Arc<status_cache::StatusCache>,
+    pub(crate) agents: Arc<agents::AgentPool>,
 }
 
 impl Client {
@@ -248,21 +373,55 @@ impl Client {
 
         let mut targets: HashMap<String, Arc<dyn Target>> = HashMap::new();
         for (name, target_config) in &config.targets {
-            let target: Arc<dyn Target> = if name == targets::LOCAL {
+            if target_config.aws_batch.is_some() && target_config.address.is_some() {
+                return Err(ClientError::Config(CoreError::InvalidConfig {
+                    detail: format!(
+                        "AWS Batch target '{}' stages through a local directory and cannot have an 'address'.",
+                        name
+                    ),
+                }));
+            }
+            if let Some(container) = &target_config.container {
+                if target_config.address.is_some() {
+                    return Err(ClientError::Config(CoreError::InvalidConfig {
+                        detail: format!(
+                            "Container target '{}' runs on this machine and cannot have an 'address'.",
+                            name
+                        ),
+                    }));
+                }
+                if !matches!(
+                    container.engine,
+                    repx_core::model::ExecutionType::Podman
+                        | repx_core::model::ExecutionType::Docker
+                ) {
+                    return Err(ClientError::Config(CoreError::InvalidConfig {
+                        detail: format!(
+                            "Container target '{}' needs engine = \"podman\" or \"docker\", got '{}'.",
+                            name, container.engine
+                        ),
+                    }));
+                }
+            }
+            let target: Arc<dyn Target> = if name == targets::LOCAL
+                || target_config.aws_batch.is_some()
+                || target_config.container.is_some()
+            {
                 Arc::new(LocalTarget {
                     name: name.clone(),
                     config: target_config.clone(),
                     local_tools_path: local_tools_path.clone(),
+                    capabilities: Default::default(),
                 })
             } else if let Some(address) = &target_config.address {
-                Arc::new(SshTarget {
-                    name: name.clone(),
-                    address: address.clone(),
-                    config: target_config.clone(),
-                    local_tools_path: local_tools_path.clone(),
-                    local_temp_path: client_temp_dir.clone(),
-                    host_tools_dir_name: lab.host_tools_dir_name.clone(),
-                })
+                Arc::new(SshTarget::new(
+                    name.clone(),
+                    address.clone(),
+                    target_config.clone(),
+                    local_tools_path.clone(),
+                    client_temp_dir.clone(),
+                    lab.host_tools_dir_name.clone(),
+                ))
             } else {
                 return Err(ClientError::Config(CoreError::InvalidConfig {
                     detail: format!(
@@ -271,6 +430,13 @@ impl Client {
                     ),
                 }));
             };
+            let target: Arc<dyn Target> = match &target_config.object_store {
+                Some(store) => Arc::new(ObjectStoreTarget::new(
+                    target,
+                    ObjectStore::new(store.clone())?,
+                )),
+                None => target,
+            };
             targets.insert(name.clone(), target);
         }
 
@@ -284,6 +450,11 @@ impl Client {
         };
         let map_filename = format!("slurm_map_{}.json", lab_hash);
         let map_path = client_state_dir.join(map_filename);
+        let local_pids_path = client_state_dir.join(format!("local_pids_{}.json", lab_hash));
+        let local_queue_path = client_state_dir.join(format!("local_queue_{}.json", lab_hash));
+        let slurm_failures_path =
+            client_state_dir.join(format!("slurm_failures_{}.json", lab_hash));
+        let aws_jobs_path = client_state_dir.join(format!("aws_batch_jobs_{}.json", lab_hash));
 
         let slurm_map_data = match fs_err::read_to_string(&map_path) {
             Ok(content) => match serde_json::from_str(&content) {
@@ -315,7 +486,13 @@ impl Client {
             targets,
             slurm_map: Arc::new(Mutex::new(slurm_map_data)),
             slurm_map_path: map_path,
+            local_pids_path,
+            local_queue_path,
+            slurm_failures_path,
+            aws_jobs_path,
             cache,
+            status_cache: Default::default(),
+            agents: Default::default(),
         })
     }
 
@@ -323,6 +500,10 @@ impl Client {
         &self.config
     }
 
+    pub fn enable_status_agents(&self) {
+        self.agents.enable();
+    }
+
     pub fn lab(&self) -> &Lab {
         &self.lab
     }
@@ -371,6 +552,10 @@ impl Client {
         status::get_statuses_for_active_target(self, active_target_name, active_scheduler)
     }
 
+    pub fn get_federated_status(&self, label: &str) -> crate::federation::LabStatus {
+        crate::federation::LabStatus::collect(label, self)
+    }
+
     pub fn submit_run(
         &self,
         run_spec: String,
@@ -411,6 +596,20 @@ impl Client {
             );
         }
 
+        if let Some(windows) = &target.config().submission_windows {
+            if options.ignore_submission_windows {
+                tracing::warn!("Ignoring submission windows for target '{}'", target_name);
+            } else {
+                submission::wait_for_submission_window(
+                    target_name,
+                    windows,
+                    full_dependency_set.len(),
+                    options.cancel_flag.as_deref(),
+                    &send,
+                )?;
+            }
+        }
+
         send(ClientEvent::DeployingBinary);
         let remote_repx_binary_path = target.deploy_repx_binary()?;
         tracing::info!(
@@ -474,7 +673,11 @@ impl Client {
             let remote_tar_dir = target.base_path().join("lab-tars");
             let remote_tar_path = remote_tar_dir.join(&tar_filename);
             if !remote_tar_path.exists() {
-                target.sync_file(&local_tar_path, &remote_tar_path)?;
+                target.sync_file(
+                    &local_tar_path,
+                    &remote_tar_path,
+                    options.event_sender.as_ref(),
+                )?;
             } else {
                 tracing::info!(
                     "Lab tar already exists at {:?}, skipping copy",
@@ -526,14 +729,14 @@ impl Client {
                     let remote_ht_dest = target.artifacts_base_path().join("host-tools");
                     target.sync_directory(&local_ht_cache.join("host-tools"), &remote_ht_dest)?;
                 } else {
-                    target.sync_lab_from_tar_via_rsync(tar_path)?;
+                    target.sync_lab_from_tar_via_rsync(tar_path, options.event_sender.as_ref())?;
                 }
             }
             LabSource::Directory(dir_path) => {
                 if use_node_local {
-                    target.sync_lab_root_metadata_only(dir_path)?;
+                    target.sync_lab_root_metadata_only(dir_path, options.event_sender.as_ref())?;
                 } else {
-                    target.sync_lab_root(dir_path)?;
+                    target.sync_lab_root(dir_path, options.event_sender.as_ref())?;
                 }
             }
         }
@@ -583,7 +786,14 @@ impl Client {
             }
         }
 
-        let jobs_to_submit: HashMap<JobId, &Job> = if scheduler == SchedulerType::Slurm {
+        let batch = matches!(
+            scheduler,
+            SchedulerType::Slurm
+                | SchedulerType::Sge
+                | SchedulerType::Flux
+                | SchedulerType::AwsBatch
+        );
+        let jobs_to_submit: HashMap<JobId, &Job> = if batch {
             jobs_to_run
                 .iter()
                 .map(|(id, job)| (id.clone(), *job))
@@ -592,13 +802,13 @@ impl Client {
             submission::filter_jobs_for_local_submission(&jobs_to_run, &jobs_to_run_ids)?
         };
 
-        if jobs_to_submit.is_empty() && scheduler == SchedulerType::Slurm {
+        if jobs_to_submit.is_empty() && batch {
             return Ok(
                 "All schedulable jobs for this submission are already complete.".to_string(),
             );
         }
 
-        if scheduler != SchedulerType::Slurm {
+        if !batch {
             send(ClientEvent::PreparingInputs {
                 num_jobs: jobs_to_run.len(),
             });
@@ -626,6 +836,25 @@ impl Client {
                 lab_tar_remote_path.as_ref(),
                 send,
             ),
+            SchedulerType::Sge => sge::submit_sge_batch_run(
+                self,
+                jobs_to_submit,
+                &sub_target,
+                &options,
+                lab_tar_remote_path.as_ref(),
+                send,
+            ),
+            SchedulerType::Flux => flux::submit_flux_batch_run(
+                self,
+                jobs_to_submit,
+                &sub_target,
+                &options,
+                lab_tar_remote_path.as_ref(),
+                send,
+            ),
+            SchedulerType::AwsBatch => {
+                aws_batch::submit_aws_batch_run(self, jobs_to_submit, &sub_target, &options, send)
+            }
             SchedulerType::Local => {
                 let local_artifacts = if let Some(ref info) = lab_tar_remote_path {
                     let local_base = &info.node_local_base;
@@ -682,72 +911,202 @@ impl Client {
             Err(e) => Err(e),
         }
     }
-    pub fn get_log_tail(
+    pub fn failed_branch_steps(
         &self,
-        job_id: JobId,
+        job_id: &JobId,
+        target_name: &str,
+    ) -> Result<Vec<(usize, String)>> {
+        let target = self
+            .targets
+            .get(target_name)
+            .ok_or_else(|| ClientError::TargetNotFound(target_name.to_string()))?;
+        let mut failed: Vec<_> = target
+            .find_step_markers(job_id, repx_core::constants::markers::FAIL)?
+            .into_iter()
+            .collect();
+        failed.sort();
+        Ok(failed)
+    }
+
+    pub fn log_path(
+        &self,
+        job_id: &JobId,
         target_name: &str,
-        line_count: u32,
         log_type: LogType,
-    ) -> Result<Vec<String>> {
+        step: Option<(usize, &str)>,
+    ) -> Result<PathBuf> {
         let target = self
             .targets
             .get(target_name)
             .ok_or_else(|| ClientError::TargetNotFound(target_name.to_string()))?;
 
+        let job_root = target.base_path().join(dirs::OUTPUTS).join(job_id.as_str());
+        let repx_dir = match step {
+            Some((branch, step_name)) => job_root
+                .join(format!("branch-{}", branch))
+                .join(format!("step-{}", step_name))
+                .join(dirs::REPX),
+            None => job_root.join(dirs::REPX),
+        };
+
         let log_path = match log_type {
-            LogType::Stderr => target
-                .base_path()
-                .join(dirs::OUTPUTS)
-                .join(job_id.as_str())
-                .join(dirs::REPX)
-                .join(logs::STDERR),
-            LogType::Stdout => target
-                .base_path()
-                .join(dirs::OUTPUTS)
-                .join(job_id.as_str())
-                .join(dirs::REPX)
-                .join(logs::STDOUT),
+            LogType::Stderr => repx_dir.join(logs::STDERR),
+            LogType::Stdout => repx_dir.join(logs::STDOUT),
             LogType::Auto => {
                 let slurm_info = {
                     let slurm_map_guard = lock_slurm_map(&self.slurm_map);
-                    slurm_map_guard.get(&job_id).cloned()
+                    slurm_map_guard.get(job_id).cloned()
                 };
 
-                if let Some(entry) = slurm_info {
-                    if entry.target_name == target_name {
-                        target
-                            .base_path()
-                            .join(dirs::OUTPUTS)
-                            .join(job_id.as_str())
-                            .join(dirs::REPX)
-                            .join(format!("slurm-{}.out", entry.slurm_id))
-                    } else {
-                        target
-                            .base_path()
-                            .join(dirs::OUTPUTS)
-                            .join(job_id.as_str())
-                            .join(dirs::REPX)
-                            .join(logs::STDOUT)
+                match slurm_info {
+                    Some(entry) if step.is_none() && entry.target_name == target_name => {
+                        repx_dir.join(format!("slurm-{}.out", entry.slurm_id))
                     }
-                } else {
-                    target
-                        .base_path()
-                        .join(dirs::OUTPUTS)
-                        .join(job_id.as_str())
-                        .join(dirs::REPX)
-                        .join(logs::STDOUT)
+                    _ => repx_dir.join(logs::STDOUT),
                 }
             }
         };
+        Ok(log_path)
+    }
 
+    pub fn get_log_tail(
+        &self,
+        job_id: JobId,
+        target_name: &str,
+        line_count: u32,
+        log_type: LogType,
+    ) -> Result<Vec<String>> {
+        let log_path = self.log_path(&job_id, target_name, log_type, None)?;
+        let target = self
+            .targets
+            .get(target_name)
+            .ok_or_else(|| ClientError::TargetNotFound(target_name.to_string()))?;
         target.read_remote_file_tail(&log_path, line_count)
     }
 
-    pub fn cancel_job(&self, job_id: JobId) -> Result<()> {
+    pub fn get_scatter_progress(
+        &self,
+        job_id: &JobId,
+        target_name: &str,
+    ) -> Result<Option<ScatterProgress>> {
+        let target = self
+            .targets
+            .get(target_name)
+            .ok_or_else(|| ClientError::TargetNotFound(target_name.to_string()))?;
+        let path = progress::path(
+            &target
+                .base_path()
+                .join(dirs::OUTPUTS)
+                .join(job_id.as_str())
+                .join(dirs::REPX),
+        );
+        if target.remote_file_size(&path)?.is_none() {
+            return Ok(None);
+        }
+        let content = target.read_remote_file(&path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    pub fn fetch_outputs(
+        &self,
+        job_ids: &[JobId],
+        target_name: &str,
+        dest: &Path,
+    ) -> Result<FetchReport> {
+        let target = self
+            .targets
+            .get(target_name)
+            .ok_or_else(|| ClientError::TargetNotFound(target_name.to_string()))?;
+        let outputs = target.base_path().join(dirs::OUTPUTS);
+        let mut report = FetchReport::default();
+        for job_id in job_ids {
+            let remote = outputs.join(job_id.as_str()).join(dirs::OUT);
+            let local = dest.join(job_id.as_str());
+            tracing::debug!(
+                "Fetching '{}' from '{}' into '{}'",
+                remote.display(),
+                target_name,
+                local.display()
+            );
+            match target.fetch_directory(&remote, &local) {
+                Ok(()) => report.fetched.push((job_id.clone(), local)),
+                Err(e) => report.failed.push((job_id.clone(), e)),
+            }
+        }
+        Ok(report)
+    }
+
+    pub fn tracked_batch_job(&self, job_id: &JobId) -> Option<(String, SchedulerType, u64)> {
+        lock_slurm_map(&self.slurm_map)
+            .get(job_id)
+            .map(|entry| (entry.target_name.clone(), entry.scheduler, entry.slurm_id))
+    }
+
+    pub fn local_queue_report(
+        &self,
+        job_id: &JobId,
+        target_name: &str,
+    ) -> Option<LocalQueueReport> {
+        let state = local_queue::LocalQueueFile::new(self.local_queue_path.clone()).load()?;
+        if state.target_name != target_name {
+            return None;
+        }
+        let mut report = state.report(job_id)?;
+        let mut sys = sysinfo::System::new();
+        sys.refresh_memory();
+        report.host_mem_bytes = sys.total_memory();
+        report.host_cpus = num_cpus::get();
+        Some(report)
+    }
+
+    pub fn cancel_preview(&self, job_id: &JobId) -> Result<CancelReport> {
+        let mut report = CancelReport::default();
+        let slurm_info = lock_slurm_map(&self.slurm_map).get(job_id).cloned();
+        if let Some(entry) = slurm_info {
+            report.scheduler = Some(entry.scheduler);
+            report.slurm_ids.push(entry.slurm_id);
+            let scatter_gather = self
+                .lab
+                .jobs
+                .get(job_id)
+                .is_some_and(|job| job.stage_type == StageType::ScatterGather);
+            if let (true, Some(target)) = (scatter_gather, self.targets.get(&entry.target_name)) {
+                report
+                    .slurm_ids
+                    .extend(worker_slurm_ids(target.as_ref(), job_id).unwrap_or_default());
+            }
+        }
+        report.pids = local_pids::LocalPidTable::new(self.local_pids_path.clone())
+            .get(job_id)
+            .map_err(ClientError::Io)?
+            .into_iter()
+            .map(|entry| entry.pid)
+            .collect();
+        report
+            .cloud_ids
+            .extend(aws_batch::tracked(self, job_id).map(|(_, aws_job_id)| aws_job_id));
+        Ok(report)
+    }
+
+    pub fn cancel_job(&self, job_id: JobId) -> Result<CancelReport> {
+        let mut report = CancelReport::default();
         let slurm_info = {
             let slurm_map_guard = lock_slurm_map(&self.slurm_map);
             slurm_map_guard.get(&job_id).cloned()
         };
+        let local_pids = local_pids::LocalPidTable::new(self.local_pids_path.clone())
+            .take(&job_id)
+            .map_err(ClientError::Io)?;
+
+        let aws_job = aws_batch::cancel(self, &job_id)?;
+
+        let mut stopped_on = HashSet::new();
+        let mut scheduler_error = None;
+
+        if let Some((target_name, aws_job_id)) = aws_job {
+            report.cloud_ids.push(aws_job_id);
+            stopped_on.insert(target_name);
+        }
 
         if let Some(entry) = slurm_info {
             let target = self.targets.get(&entry.target_name).ok_or_else(|| {
@@ -756,32 +1115,77 @@ impl Client {
                 })
             })?;
 
-            target.scancel(entry.slurm_id)?;
+            let cancel = |ids: &[u64]| match entry.scheduler {
+                SchedulerType::Sge => target.qdel(ids),
+                SchedulerType::Flux => target.flux_cancel(ids),
+                _ => target.scancel_batch(ids),
+            };
+            match cancel(&[entry.slurm_id]) {
+                Ok(()) => {
+                    report.scheduler = Some(entry.scheduler);
+                    report.slurm_ids.push(entry.slurm_id);
+                    stopped_on.insert(entry.target_name.clone());
+                }
+                Err(e) => scheduler_error = Some(e),
+            }
+
+            if let Some(worker_ids) = worker_slurm_ids(target.as_ref(), &job_id) {
+                tracing::info!(
+                    "Cancelling {} scatter-gather worker jobs for {}",
+                    worker_ids.len(),
+                    job_id
+                );
+                if let Err(e) = cancel(&worker_ids) {
+                    tracing::warn!("Failed to cancel worker jobs for {}: {}", job_id, e);
+                } else {
+                    report.slurm_ids.extend(worker_ids);
+                }
+            }
+        }
 
-            let manifest_path = target
-                .base_path()
-                .join(dirs::OUTPUTS)
-                .join(job_id.as_str())
-                .join(dirs::REPX)
-                .join(repx_core::constants::manifests::WORKER_SLURM_IDS);
-
-            if let Ok(content) = target.read_remote_file_tail(&manifest_path, 10000) {
-                let full_content = content.join("\n");
-                if let Ok(worker_ids) = serde_json::from_str::<Vec<u32>>(&full_content) {
-                    if !worker_ids.is_empty() {
-                        tracing::info!(
-                            "Cancelling {} scatter-gather worker jobs for {}",
-                            worker_ids.len(),
-                            job_id
-                        );
-                        if let Err(e) = target.scancel_batch(&worker_ids) {
-                            tracing::warn!("Failed to cancel worker jobs for {}: {}", job_id, e);
-                        }
-                    }
+        for entry in local_pids {
+            let Ok(raw) = i32::try_from(entry.pid) else {
+                continue;
+            };
+            match kill(Pid::from_raw(raw), Signal::SIGTERM) {
+                Ok(()) => {
+                    report.pids.push(entry.pid);
+                    stopped_on.insert(entry.target_name);
                 }
+                Err(e) => tracing::warn!(
+                    "Failed to send SIGTERM to PID {} of job {}: {}",
+                    entry.pid,
+                    job_id,
+                    e
+                ),
             }
+        }
+
+        for target_name in &stopped_on {
+            self.mark_cancelled(target_name, &job_id)?;
+        }
+        if let Some(e) = scheduler_error {
+            return Err(e);
+        }
+
+        Ok(report)
+    }
 
-            return Ok(());
+    fn mark_cancelled(&self, target_name: &str, job_id: &JobId) -> Result<()> {
+        let target = self.targets.get(target_name).ok_or_else(|| {
+            ClientError::Config(CoreError::TargetNotConfigured {
+                name: target_name.to_string(),
+            })
+        })?;
+        let marker = target
+            .base_path()
+            .join(dirs::OUTPUTS)
+            .join(job_id.as_str())
+            .join(dirs::REPX)
+            .join(markers::CANCELLED);
+        target.write_remote_file(&marker, "")?;
+        if target.config().address.is_none() {
+            completion_log::append_cancelled(target.base_path(), job_id)?;
         }
         Ok(())
     }
@@ -0,0 +1,265 @@
+use crate::agent::job_info;
+use crate::error::Result;
+use crate::targets::{RemoteCommand, SlurmJobInfo, Target};
+use repx_core::{
+    engine::JobStatus,
+    model::{JobId, SchedulerType},
+    protocol::AgentEvent,
+};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const STALE_AFTER: Duration = Duration::from_secs(30);
+
+const RESTART_DELAY: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+pub(crate) struct AgentPool {
+    enabled: AtomicBool,
+    agents: Mutex<HashMap<String, AgentSlot>>,
+}
+
+enum AgentSlot {
+    Running(RunningAgent),
+    Failed { at: Instant },
+}
+
+struct RunningAgent {
+    child: Child,
+    started: Instant,
+    state: Arc<Mutex<AgentState>>,
+}
+
+#[derive(Default)]
+struct AgentState {
+    outcomes: HashMap<JobId, JobStatus>,
+    queues: HashMap<SchedulerType, HashMap<JobId, SlurmJobInfo>>,
+    heartbeats: HashMap<JobId, u64>,
+    heartbeats_at: Option<Instant>,
+    synced_at: Option<Instant>,
+    complete: bool,
+    closed: bool,
+}
+
+impl AgentState {
+    fn apply(&mut self, event: AgentEvent) {
+        match event {
+            AgentEvent::Outcomes { changed, cleared } => {
+                for job_id in cleared {
+                    self.outcomes.remove(&job_id);
+                }
+                self.outcomes.extend(changed);
+            }
+            AgentEvent::Queue { scheduler, jobs } => {
+                let queue = jobs
+                    .into_iter()
+                    .map(|job| (job.job_id.clone(), job_info(job)))
+                    .collect();
+                self.queues.insert(scheduler, queue);
+            }
+            AgentEvent::Heartbeats { ages } => {
+                self.heartbeats = ages;
+                self.heartbeats_at = Some(Instant::now());
+            }
+            AgentEvent::Synced { complete } => {
+                self.synced_at = Some(Instant::now());
+                self.complete = complete;
+            }
+        }
+    }
+}
+
+pub(crate) struct AgentView {
+    pub(crate) outcomes: HashMap<JobId, JobStatus>,
+    queues: HashMap<SchedulerType, HashMap<JobId, SlurmJobInfo>>,
+    pub(crate) heartbeats: HashMap<JobId, Duration>,
+}
+
+impl AgentView {
+    pub(crate) fn queue(&self, scheduler: SchedulerType) -> Option<HashMap<JobId, SlurmJobInfo>> {
+        self.queues.get(&scheduler).cloned()
+    }
+}
+
+impl AgentPool {
+    pub(crate) fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn view(&self, target: &dyn Target) -> Option<AgentView> {
+        let config = target.config();
+        if !self.enabled.load(Ordering::Relaxed) || !config.agent || config.address.is_none() {
+            return None;
+        }
+
+        let mut agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = agents.get(target.name());
+        let restart = match slot {
+            None => true,
+            Some(AgentSlot::Failed { at }) => at.elapsed() >= RESTART_DELAY,
+            Some(AgentSlot::Running(_)) => false,
+        };
+        if restart {
+            let slot = match start(target) {
+                Ok(agent) => AgentSlot::Running(agent),
+                Err(e) => {
+                    tracing::warn!("Failed to start agent on '{}': {}", target.name(), e);
+                    AgentSlot::Failed { at: Instant::now() }
+                }
+            };
+            agents.insert(target.name().to_string(), slot);
+        }
+
+        let Some(AgentSlot::Running(agent)) = agents.get_mut(target.name()) else {
+            return None;
+        };
+        let state = agent.state.lock().unwrap_or_else(|e| e.into_inner());
+        let last_sync = state.synced_at.unwrap_or(agent.started);
+        if state.closed || last_sync.elapsed() > STALE_AFTER {
+            tracing::warn!(
+                "Agent on '{}' stopped reporting, polling the target directly",
+                target.name()
+            );
+            drop(state);
+            if let Some(AgentSlot::Running(mut agent)) = agents.insert(
+                target.name().to_string(),
+                AgentSlot::Failed { at: Instant::now() },
+            ) {
+                let _ = agent.child.kill();
+                let _ = agent.child.wait();
+            }
+            return None;
+        }
+        if state.synced_at.is_none() || !state.complete {
+            return None;
+        }
+
+        let received = state
+            .heartbeats_at
+            .map_or(Duration::ZERO, |at| at.elapsed());
+        Some(AgentView {
+            outcomes: state.outcomes.clone(),
+            queues: state.queues.clone(),
+            heartbeats: state
+                .heartbeats
+                .iter()
+                .map(|(job_id, age)| (job_id.clone(), Duration::from_secs(*age) + received))
+                .collect(),
+        })
+    }
+}
+
+impl Drop for AgentPool {
+    fn drop(&mut self) {
+        let agents = self.agents.get_mut().unwrap_or_else(|e| e.into_inner());
+        for slot in agents.values_mut() {
+            if let AgentSlot::Running(agent) = slot {
+                let _ = agent.child.kill();
+                let _ = agent.child.wait();
+            }
+        }
+    }
+}
+
+fn start(target: &dyn Target) -> Result<RunningAgent> {
+    let config = target.config();
+    let repx_bin = target.deploy_repx_binary()?;
+    let mut command = RemoteCommand::new(&repx_bin.to_string_lossy())
+        .arg("agent")
+        .arg("--target-name")
+        .arg(target.name())
+        .arg("--base-path")
+        .arg(&config.base_path.to_string_lossy());
+    for (scheduler, configured) in [
+        (SchedulerType::Slurm, config.slurm.is_some()),
+        (SchedulerType::Sge, config.sge.is_some()),
+        (SchedulerType::Flux, config.flux.is_some()),
+    ] {
+        if configured {
+            command = command.arg("--queue").arg(&scheduler.to_string());
+        }
+    }
+    let shell = config.with_setup_commands(&command.to_shell_string());
+    let mut child = target.spawn_command("sh", &["-c", &shell])?;
+    tracing::info!("Started status agent on '{}'", target.name());
+
+    let state = Arc::new(Mutex::new(AgentState::default()));
+    if let Some(stdout) = child.stdout.take() {
+        let state = Arc::clone(&state);
+        let name = target.name().to_string();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                match serde_json::from_str::<AgentEvent>(&line) {
+                    Ok(event) => state.lock().unwrap_or_else(|e| e.into_inner()).apply(event),
+                    Err(e) => tracing::debug!("Agent on '{}' sent '{}': {}", name, line, e),
+                }
+            }
+            state.lock().unwrap_or_else(|e| e.into_inner()).closed = true;
+        });
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let name = target.name().to_string();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(|line| line.ok()) {
+                tracing::debug!("[agent {}] {}", name, line);
+            }
+        });
+    }
+
+    Ok(RunningAgent {
+        child,
+        started: Instant::now(),
+        state,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repx_core::protocol::AgentQueuedJob;
+
+    #[test]
+    fn test_state_follows_events() {
+        let kept = JobId::from("aaa-kept-1.0");
+        let cleared = JobId::from("bbb-cleared-1.0");
+        let succeeded = JobStatus::Succeeded {
+            location: "cluster".to_string(),
+        };
+        let mut state = AgentState::default();
+        state.apply(AgentEvent::Outcomes {
+            changed: [
+                (kept.clone(), succeeded.clone()),
+                (cleared.clone(), succeeded.clone()),
+            ]
+            .into_iter()
+            .collect(),
+            cleared: Vec::new(),
+        });
+        state.apply(AgentEvent::Outcomes {
+            changed: HashMap::new(),
+            cleared: vec![cleared.clone()],
+        });
+        state.apply(AgentEvent::Queue {
+            scheduler: SchedulerType::Slurm,
+            jobs: vec![AgentQueuedJob {
+                job_id: cleared.clone(),
+                scheduler_id: 7,
+                state: "PD".to_string(),
+                reason: None,
+            }],
+        });
+        state.apply(AgentEvent::Synced { complete: true });
+
+        assert_eq!(state.outcomes.len(), 1);
+        assert_eq!(state.outcomes.get(&kept), Some(&succeeded));
+        assert!(state.queues[&SchedulerType::Slurm].contains_key(&cleared));
+        assert!(state.complete && state.synced_at.is_some());
+    }
+}
@@ -0,0 +1,924 @@
+use super::{Client, ClientEvent, SubmitOptions};
+use crate::error::{ClientError, Result};
+use crate::inputs;
+use crate::resources::SbatchDirectives;
+use crate::targets::{common::shell_quote, Target};
+use repx_core::{
+    config::AwsBatchConfig,
+    constants::{dirs, markers},
+    engine,
+    errors::CoreError,
+    fs_utils,
+    model::{Job, JobId, SchedulerType, StageType},
+    store::{completion_log, outcomes::FailureInfo},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const MAX_DEPENDENCIES: usize = 20;
+const DESCRIBE_CHUNK: usize = 100;
+const SCRIPTS_DIR: &str = "batch-scripts";
+const LOG_FILE: &str = "aws-batch.log";
+const DEFAULT_LOG_GROUP: &str = "/aws/batch/job";
+const SCRIPT_URI_VAR: &str = "REPX_SCRIPT_URI";
+const CONTAINER_COMMAND: &str =
+    "aws s3 cp --only-show-errors \"$REPX_SCRIPT_URI\" /tmp/repx-job.sh && exec bash /tmp/repx-job.sh";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct AwsJobEntry {
+    pub target_name: String,
+    pub aws_job_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
+
+pub(crate) struct AwsJobTable {
+    path: PathBuf,
+}
+
+impl AwsJobTable {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub(crate) fn load(&self) -> BTreeMap<JobId, AwsJobEntry> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return BTreeMap::new(),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to read AWS Batch job table '{}': {}",
+                    self.path.display(),
+                    e
+                );
+                return BTreeMap::new();
+            }
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!(
+                "Discarding unreadable AWS Batch job table '{}': {}",
+                self.path.display(),
+                e
+            );
+            BTreeMap::new()
+        })
+    }
+
+    pub(crate) fn save(&self, jobs: &BTreeMap<JobId, AwsJobEntry>) -> io::Result<()> {
+        let serialized = serde_json::to_vec(jobs).map_err(io::Error::other)?;
+        fs_utils::write_atomic_nosync(&self.path, &serialized)
+    }
+}
+
+struct AwsCli<'a> {
+    config: &'a AwsBatchConfig,
+}
+
+impl AwsCli<'_> {
+    fn run(&self, args: &[&str]) -> Result<String> {
+        let mut command = Command::new("aws");
+        command.args(args);
+        if let Some(region) = &self.config.region {
+            command.args(["--region", region]);
+        }
+        if let Some(profile) = &self.config.profile {
+            command.args(["--profile", profile]);
+        }
+        let failed = |detail: String| {
+            ClientError::Config(CoreError::CommandFailed(format!(
+                "aws {} failed: {}",
+                args.iter().take(2).copied().collect::<Vec<_>>().join(" "),
+                detail
+            )))
+        };
+        let output = command
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| failed(e.to_string()))?;
+        if !output.status.success() {
+            return Err(failed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn json(&self, args: &[&str]) -> Result<Value> {
+        let mut args = args.to_vec();
+        args.extend(["--output", "json"]);
+        let stdout = self.run(&args)?;
+        serde_json::from_str(&stdout).map_err(ClientError::Json)
+    }
+
+    fn sync(&self, from: &str, to: &str, delete: bool) -> Result<()> {
+        let mut args = vec!["s3", "sync", "--only-show-errors", from, to];
+        if delete {
+            args.push("--delete");
+        }
+        self.run(&args).map(drop)
+    }
+}
+
+fn aws_batch_config(target: &dyn Target) -> Result<&AwsBatchConfig> {
+    target.config().aws_batch.as_ref().ok_or_else(|| {
+        ClientError::Config(CoreError::InvalidConfig {
+            detail: format!("Target '{}' has no [aws_batch] section", target.name()),
+        })
+    })
+}
+
+fn s3_uri(config: &AwsBatchConfig, base_path: &Path, path: &Path) -> Result<String> {
+    let relative = path
+        .strip_prefix(base_path)
+        .map_err(|_| ClientError::InvalidPath {
+            path: path.to_path_buf(),
+            reason: format!("not under the staging directory {}", base_path.display()),
+        })?;
+    Ok(format!(
+        "{}/{}",
+        config.s3_prefix.trim_end_matches('/'),
+        relative.display()
+    ))
+}
+
+fn job_output_dir(base_path: &Path, job_id: &JobId) -> PathBuf {
+    base_path.join(dirs::OUTPUTS).join(job_id.as_str())
+}
+
+pub(super) fn batch_job_name(job_id: &JobId) -> String {
+    let name: String = format!("repx-{}", job_id.as_str())
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    name.chars().take(128).collect()
+}
+
+pub(super) fn resource_requirements(directives: &SbatchDirectives) -> Vec<Value> {
+    let mut requirements = Vec::new();
+    if let Some(cpus) = directives.cpus_per_task {
+        requirements.push(json!({ "type": "VCPU", "value": cpus.to_string() }));
+    }
+    if let Some(bytes) = directives.mem.as_ref().and_then(|mem| mem.to_bytes()) {
+        let mib = bytes.div_ceil(1024 * 1024);
+        requirements.push(json!({ "type": "MEMORY", "value": mib.to_string() }));
+    }
+    requirements
+}
+
+fn container_properties(config: &AwsBatchConfig) -> Value {
+    let mut properties = json!({
+        "image": config.image,
+        "command": ["bash", "-c", CONTAINER_COMMAND],
+        "resourceRequirements": [
+            { "type": "VCPU", "value": "1" },
+            { "type": "MEMORY", "value": "2048" },
+        ],
+    });
+    if let Some(role) = &config.job_role_arn {
+        properties["jobRoleArn"] = json!(role);
+    }
+    if let Some(group) = &config.log_group {
+        properties["logConfiguration"] = json!({
+            "logDriver": "awslogs",
+            "options": { "awslogs-group": group },
+        });
+    }
+    properties
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn generate_aws_batch_script(
+    config: &AwsBatchConfig,
+    base_path: &Path,
+    repx_binary: &Path,
+    job_id: &JobId,
+    deps: &[&JobId],
+    repx_command_to_wrap: String,
+    inputs_json: &str,
+    parameters_json: &str,
+) -> Result<String> {
+    let quoted_uri = |path: &Path| s3_uri(config, base_path, path).map(|uri| shell_quote(&uri));
+    let quoted_path = |path: &Path| shell_quote(&path.to_string_lossy());
+    let artifacts = base_path.join("artifacts");
+    let job_dir = job_output_dir(base_path, job_id);
+
+    let mut s = String::with_capacity(4096);
+    s.push_str("#!/usr/bin/env bash\n");
+    s.push_str("set -e\n\n");
+    s.push_str(&format!("mkdir -p {}\n", quoted_path(&job_dir)));
+    s.push_str(&format!(
+        "aws s3 sync --only-show-errors {} {}\n",
+        quoted_uri(&artifacts)?,
+        quoted_path(&artifacts)
+    ));
+    s.push_str(&format!(
+        "aws s3 cp --only-show-errors {} {}\n",
+        quoted_uri(repx_binary)?,
+        quoted_path(repx_binary)
+    ));
+    s.push_str(&format!("chmod +x {}\n", quoted_path(repx_binary)));
+    for dep in deps {
+        let dep_dir = job_output_dir(base_path, dep);
+        s.push_str(&format!(
+            "aws s3 sync --only-show-errors {} {}\n",
+            quoted_uri(&dep_dir)?,
+            quoted_path(&dep_dir)
+        ));
+    }
+    s.push_str("\n_repx_upload() {\n");
+    s.push_str(&format!(
+        "  aws s3 sync --only-show-errors --delete {} {} || true\n",
+        quoted_path(&job_dir),
+        quoted_uri(&job_dir)?
+    ));
+    s.push_str("}\n");
+    s.push_str("trap _repx_upload EXIT\n\n");
+
+    super::slurm::push_invoker_body(
+        &mut s,
+        &[],
+        "true",
+        repx_command_to_wrap,
+        None,
+        inputs_json,
+        parameters_json,
+    );
+    Ok(s)
+}
+
+pub fn submit_aws_batch_run(
+    client: &Client,
+    jobs_to_submit: HashMap<JobId, &Job>,
+    sub_target: &super::SubmissionTarget,
+    options: &SubmitOptions,
+    send: impl Fn(ClientEvent),
+) -> Result<String> {
+    let target = &sub_target.target;
+    let target_name = &sub_target.target_name;
+    let config = aws_batch_config(target.as_ref())?;
+    if let Some((job_id, _)) = jobs_to_submit
+        .iter()
+        .find(|(_, job)| job.stage_type == StageType::ScatterGather)
+    {
+        return Err(ClientError::Config(CoreError::InvalidConfig {
+            detail: format!(
+                "Scatter-gather job '{}' cannot run on AWS Batch target '{}'; submit it to a local, SLURM or Grid Engine target",
+                job_id, target_name
+            ),
+        }));
+    }
+    let cli = AwsCli { config };
+    let base_path = target.base_path();
+    let repx_binary = &sub_target.repx_binary_path;
+
+    let job_ids_in_batch: HashSet<JobId> = jobs_to_submit.keys().cloned().collect();
+    for (job_id, job) in &jobs_to_submit {
+        let in_batch = job
+            .all_dependencies()
+            .filter(|dep_id| job_ids_in_batch.contains(*dep_id))
+            .count();
+        if in_batch > MAX_DEPENDENCIES {
+            return Err(ClientError::Config(CoreError::InvalidConfig {
+                detail: format!(
+                    "Job '{}' depends on {} jobs in this submission, more than the {} AWS Batch allows",
+                    job_id, in_batch, MAX_DEPENDENCIES
+                ),
+            }));
+        }
+    }
+    let external_deps: HashSet<&JobId> = jobs_to_submit
+        .values()
+        .flat_map(|job| job.all_dependencies())
+        .filter(|dep_id| !job_ids_in_batch.contains(*dep_id))
+        .collect();
+
+    send(ClientEvent::SyncingArtifacts {
+        total: 2 + external_deps.len() as u64,
+    });
+    let artifacts = target.artifacts_base_path();
+    cli.sync(
+        &artifacts.to_string_lossy(),
+        &s3_uri(config, base_path, &artifacts)?,
+        false,
+    )?;
+    send(ClientEvent::SyncingArtifactProgress {
+        path: PathBuf::from("artifacts"),
+    });
+    let binary = repx_binary.to_string_lossy();
+    cli.run(&[
+        "s3",
+        "cp",
+        "--only-show-errors",
+        &binary,
+        &s3_uri(config, base_path, repx_binary)?,
+    ])?;
+    send(ClientEvent::SyncingArtifactProgress {
+        path: PathBuf::from("repx"),
+    });
+    for dep_id in &external_deps {
+        let dep_dir = job_output_dir(base_path, dep_id);
+        if dep_dir.exists() {
+            cli.sync(
+                &dep_dir.to_string_lossy(),
+                &s3_uri(config, base_path, &dep_dir)?,
+                true,
+            )?;
+        }
+        send(ClientEvent::SyncingArtifactProgress {
+            path: PathBuf::from(dep_id.as_str()),
+        });
+    }
+    send(ClientEvent::SyncingFinished);
+
+    let definition_name = batch_job_name(&JobId::from(target_name.clone()));
+    let properties = container_properties(config).to_string();
+    let registered = cli.json(&[
+        "batch",
+        "register-job-definition",
+        "--job-definition-name",
+        &definition_name,
+        "--type",
+        "container",
+        "--container-properties",
+        &properties,
+    ])?;
+    let definition_arn = registered["jobDefinitionArn"]
+        .as_str()
+        .ok_or_else(|| {
+            ClientError::Config(CoreError::CommandFailed(format!(
+                "register-job-definition returned no jobDefinitionArn: {}",
+                registered
+            )))
+        })?
+        .to_string();
+
+    let total_to_submit = jobs_to_submit.len();
+    send(ClientEvent::GeneratingSlurmScripts {
+        num_jobs: total_to_submit,
+    });
+    let waves = super::slurm::compute_waves(&jobs_to_submit, &job_ids_in_batch)?;
+    send(ClientEvent::SubmittingJobs {
+        total: total_to_submit,
+        concurrency: None,
+    });
+
+    let table = AwsJobTable::new(client.aws_jobs_path.clone());
+    let mut tracked = table.load();
+    let remote_repx_command = format!(
+        "{} {}",
+        repx_binary.to_string_lossy(),
+        options.verbose.as_flag_str()
+    );
+    let mut aws_ids: HashMap<JobId, String> = HashMap::new();
+    let mut submitted_count = 0;
+
+    for job_id in waves.iter().flatten() {
+        let Some(job) = jobs_to_submit.get(job_id) else {
+            continue;
+        };
+        let inputs_json = inputs::generate_inputs_json_content(
+            &client.lab,
+            &client.lab_source,
+            job,
+            job_id,
+            base_path,
+            &artifacts,
+            "main",
+        )?;
+        let parameters_json = inputs::generate_parameters_json_content(job)?;
+        let (repx_command, directives) = super::slurm::build_job_command_and_directives(
+            client,
+            job_id,
+            job,
+            target.as_ref(),
+            target_name,
+            remote_repx_command.trim_end(),
+            options,
+            None,
+            SchedulerType::AwsBatch,
+        )?;
+        let deps: Vec<&JobId> = job.all_dependencies().collect();
+        let depends_on: Vec<String> = job
+            .all_dependencies()
+            .filter_map(|dep_id| aws_ids.get(dep_id))
+            .map(|id| format!("jobId={}", id))
+            .collect();
+        let script = generate_aws_batch_script(
+            config,
+            base_path,
+            repx_binary,
+            job_id,
+            &deps,
+            repx_command,
+            &inputs_json,
+            &parameters_json,
+        )?;
+        let script_path = base_path
+            .join(SCRIPTS_DIR)
+            .join(format!("{}.sh", job_id.as_str()));
+        let script_uri = s3_uri(config, base_path, &script_path)?;
+        target.write_remote_file(&script_path, &script)?;
+        cli.run(&[
+            "s3",
+            "cp",
+            "--only-show-errors",
+            &script_path.to_string_lossy(),
+            &script_uri,
+        ])?;
+
+        // A rerun must not see the outcome of an earlier attempt, locally
+        // or in S3, before its own has been uploaded.
+        let job_dir = job_output_dir(base_path, job_id);
+        fs_utils::force_remove_dir(&job_dir).map_err(ClientError::Io)?;
+        completion_log::append_cleared(base_path, job_id)?;
+        cli.run(&[
+            "s3",
+            "rm",
+            "--only-show-errors",
+            "--recursive",
+            &s3_uri(config, base_path, &job_dir)?,
+        ])?;
+
+        let mut environment = vec![json!({ "name": SCRIPT_URI_VAR, "value": script_uri })];
+        if let Some(region) = &config.region {
+            environment.push(json!({ "name": "AWS_DEFAULT_REGION", "value": region }));
+        }
+        let mut overrides = json!({ "environment": environment });
+        let requirements = resource_requirements(&directives);
+        if !requirements.is_empty() {
+            overrides["resourceRequirements"] = Value::Array(requirements);
+        }
+        let overrides = overrides.to_string();
+        let job_name = batch_job_name(job_id);
+        let mut args = vec![
+            "batch",
+            "submit-job",
+            "--job-name",
+            &job_name,
+            "--job-queue",
+            &config.job_queue,
+            "--job-definition",
+            &definition_arn,
+            "--container-overrides",
+            &overrides,
+        ];
+        if !depends_on.is_empty() {
+            args.push("--depends-on");
+            args.extend(depends_on.iter().map(String::as_str));
+        }
+        let timeout = directives
+            .time
+            .as_ref()
+            .and_then(|time| time.to_seconds())
+            .map(|secs| format!("attemptDurationSeconds={}", secs.max(60)));
+        if let Some(timeout) = &timeout {
+            args.extend(["--timeout", timeout]);
+        }
+        let submitted = cli.json(&args)?;
+        let aws_job_id = submitted["jobId"]
+            .as_str()
+            .ok_or_else(|| {
+                ClientError::Config(CoreError::CommandFailed(format!(
+                    "submit-job returned no jobId for '{}': {}",
+                    job_id, submitted
+                )))
+            })?
+            .to_string();
+
+        aws_ids.insert(job_id.clone(), aws_job_id.clone());
+        tracked.insert(
+            job_id.clone(),
+            AwsJobEntry {
+                target_name: target_name.clone(),
+                aws_job_id: aws_job_id.clone(),
+                failure: None,
+            },
+        );
+        table.save(&tracked).map_err(ClientError::Io)?;
+        submitted_count += 1;
+        send(ClientEvent::CloudJobSubmitted {
+            job_id: job_id.clone(),
+            cloud_id: aws_job_id,
+            total: total_to_submit,
+            current: submitted_count,
+        });
+    }
+
+    Ok(format!(
+        "Successfully submitted {} jobs to AWS Batch queue '{}'.",
+        submitted_count, config.job_queue
+    ))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ContainerDetail {
+    #[serde(default)]
+    pub log_stream_name: Option<String>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct BatchJob {
+    pub job_id: String,
+    pub status: String,
+    #[serde(default)]
+    pub status_reason: Option<String>,
+    #[serde(default)]
+    pub container: Option<ContainerDetail>,
+}
+
+impl BatchJob {
+    fn live_status(&self) -> Option<engine::JobStatus> {
+        match self.status.as_str() {
+            "SUBMITTED" | "PENDING" | "RUNNABLE" | "STARTING" => Some(engine::JobStatus::Queued),
+            "RUNNING" => Some(engine::JobStatus::Running),
+            _ => None,
+        }
+    }
+
+    fn failure_summary(&self) -> String {
+        let container = self.container.as_ref();
+        let parts: Vec<String> = [
+            self.status_reason.clone(),
+            container.and_then(|c| c.reason.clone()),
+            container
+                .and_then(|c| c.exit_code)
+                .map(|code| format!("exit code {}", code)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if parts.is_empty() {
+            "AWS Batch failed the job".to_string()
+        } else {
+            parts.join("; ")
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DescribeJobs {
+    #[serde(default)]
+    jobs: Vec<BatchJob>,
+}
+
+pub(super) fn parse_describe_jobs(output: &str) -> Result<HashMap<String, BatchJob>> {
+    let described: DescribeJobs = serde_json::from_str(output).map_err(ClientError::Json)?;
+    Ok(described
+        .jobs
+        .into_iter()
+        .map(|job| (job.job_id.clone(), job))
+        .collect())
+}
+
+fn record_pulled_outcome(
+    base_path: &Path,
+    job_id: &JobId,
+    location: &str,
+) -> Result<Option<engine::JobStatus>> {
+    let repx_dir = job_output_dir(base_path, job_id).join(dirs::REPX);
+    let location = location.to_string();
+    let status = if repx_dir.join(markers::SUCCESS).exists() {
+        completion_log::append_completion(base_path, job_id, true, None)?;
+        engine::JobStatus::Succeeded { location }
+    } else if repx_dir.join(markers::TIMEOUT).exists() {
+        completion_log::append_timeout(base_path, job_id, None)?;
+        engine::JobStatus::TimedOut { location }
+    } else if repx_dir.join(markers::CANCELLED).exists() {
+        completion_log::append_cancelled(base_path, job_id)?;
+        engine::JobStatus::Cancelled { location }
+    } else if let Some(info) = FailureInfo::read_marker(&repx_dir) {
+        completion_log::append_failure(base_path, job_id, info.class, None)?;
+        engine::JobStatus::Failed {
+            location,
+            class: info.class,
+            reason: None,
+        }
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(status))
+}
+
+fn fetch_log(cli: &AwsCli<'_>, base_path: &Path, job_id: &JobId, job: &BatchJob) -> Result<()> {
+    let Some(stream) = job
+        .container
+        .as_ref()
+        .and_then(|c| c.log_stream_name.as_deref())
+    else {
+        return Ok(());
+    };
+    let group = cli.config.log_group.as_deref().unwrap_or(DEFAULT_LOG_GROUP);
+    let events = cli.json(&[
+        "logs",
+        "get-log-events",
+        "--log-group-name",
+        group,
+        "--log-stream-name",
+        stream,
+        "--start-from-head",
+    ])?;
+    let mut log = String::new();
+    for event in events["events"].as_array().into_iter().flatten() {
+        if let Some(message) = event["message"].as_str() {
+            log.push_str(message);
+            log.push('\n');
+        }
+    }
+    let repx_dir = job_output_dir(base_path, job_id).join(dirs::REPX);
+    std::fs::create_dir_all(&repx_dir).map_err(ClientError::Io)?;
+    std::fs::write(repx_dir.join(LOG_FILE), log).map_err(ClientError::Io)
+}
+
+pub(super) fn apply_aws_batch(
+    client: &Client,
+    target: &dyn Target,
+    statuses: &mut HashMap<JobId, engine::JobStatus>,
+) {
+    let Some(config) = target.config().aws_batch.as_ref() else {
+        return;
+    };
+    let cli = AwsCli { config };
+    let base_path = target.base_path();
+    let table = AwsJobTable::new(client.aws_jobs_path.clone());
+    let mut tracked = table.load();
+    let mut changed = false;
+
+    let active: Vec<(JobId, String)> = tracked
+        .iter()
+        .filter(|(_, entry)| entry.target_name == target.name() && entry.failure.is_none())
+        .map(|(job_id, entry)| (job_id.clone(), entry.aws_job_id.clone()))
+        .collect();
+
+    let mut described = HashMap::new();
+    for chunk in active.chunks(DESCRIBE_CHUNK) {
+        let mut args = vec!["batch", "describe-jobs", "--output", "json", "--jobs"];
+        args.extend(chunk.iter().map(|(_, id)| id.as_str()));
+        match cli
+            .run(&args)
+            .and_then(|output| parse_describe_jobs(&output))
+        {
+            Ok(jobs) => described.extend(jobs),
+            Err(e) => {
+                tracing::warn!("AWS Batch lookup on '{}' failed: {}", target.name(), e);
+                return;
+            }
+        }
+    }
+
+    for (job_id, aws_job_id) in active {
+        let Some(job) = described.get(&aws_job_id) else {
+            tracing::debug!("AWS Batch no longer knows job {} ({})", job_id, aws_job_id);
+            tracked.remove(&job_id);
+            changed = true;
+            continue;
+        };
+        if let Some(status) = job.live_status() {
+            statuses.insert(job_id, status);
+            continue;
+        }
+        if job.status != "SUCCEEDED" && job.status != "FAILED" {
+            continue;
+        }
+
+        let job_dir = job_output_dir(base_path, &job_id);
+        let pulled = s3_uri(config, base_path, &job_dir)
+            .and_then(|uri| cli.sync(&uri, &job_dir.to_string_lossy(), true));
+        if let Err(e) = pulled {
+            tracing::warn!("Failed to pull outputs of '{}' from S3: {}", job_id, e);
+            continue;
+        }
+        if job.status == "FAILED" {
+            if let Err(e) = fetch_log(&cli, base_path, &job_id, job) {
+                tracing::warn!("Failed to fetch CloudWatch log of '{}': {}", job_id, e);
+            }
+        }
+        match record_pulled_outcome(base_path, &job_id, target.name()) {
+            Ok(Some(status)) => {
+                statuses.insert(job_id.clone(), status);
+                tracked.remove(&job_id);
+            }
+            Ok(None) if job.status == "FAILED" => {
+                if let Err(e) = completion_log::append_failure(base_path, &job_id, None, None) {
+                    tracing::warn!("Failed to record failure of '{}': {}", job_id, e);
+                }
+                if let Some(entry) = tracked.get_mut(&job_id) {
+                    entry.failure = Some(job.failure_summary());
+                }
+            }
+            Ok(None) => {
+                tracing::warn!(
+                    "AWS Batch job {} of '{}' succeeded without an outcome marker",
+                    aws_job_id,
+                    job_id
+                );
+                tracked.remove(&job_id);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to record outcome of '{}': {}", job_id, e);
+                continue;
+            }
+        }
+        changed = true;
+    }
+
+    tracked.retain(|job_id, entry| {
+        let Some(failure) = entry
+            .failure
+            .as_ref()
+            .filter(|_| entry.target_name == target.name())
+        else {
+            return true;
+        };
+        let status = statuses
+            .entry(job_id.clone())
+            .or_insert_with(|| engine::JobStatus::Failed {
+                location: target.name().to_string(),
+                class: None,
+                reason: None,
+            });
+        match status {
+            engine::JobStatus::Failed { reason, .. } => {
+                *reason = Some(failure.clone());
+                true
+            }
+            _ => {
+                changed = true;
+                false
+            }
+        }
+    });
+
+    if changed {
+        if let Err(e) = table.save(&tracked) {
+            tracing::warn!("Failed to save AWS Batch job table: {}", e);
+        }
+    }
+}
+
+pub(super) fn tracked(client: &Client, job_id: &JobId) -> Option<(String, String)> {
+    AwsJobTable::new(client.aws_jobs_path.clone())
+        .load()
+        .remove(job_id)
+        .filter(|entry| entry.failure.is_none())
+        .map(|entry| (entry.target_name, entry.aws_job_id))
+}
+
+pub(super) fn cancel(client: &Client, job_id: &JobId) -> Result<Option<(String, String)>> {
+    let table = AwsJobTable::new(client.aws_jobs_path.clone());
+    let mut tracked = table.load();
+    let Some(entry) = tracked.remove(job_id) else {
+        return Ok(None);
+    };
+    table.save(&tracked).map_err(ClientError::Io)?;
+    if entry.failure.is_some() {
+        return Ok(None);
+    }
+    let target = client.targets.get(&entry.target_name).ok_or_else(|| {
+        ClientError::Config(CoreError::TargetNotConfigured {
+            name: entry.target_name.clone(),
+        })
+    })?;
+    let cli = AwsCli {
+        config: aws_batch_config(target.as_ref())?,
+    };
+    cli.run(&[
+        "batch",
+        "terminate-job",
+        "--job-id",
+        &entry.aws_job_id,
+        "--reason",
+        "Cancelled with repx",
+    ])?;
+    Ok(Some((entry.target_name, entry.aws_job_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repx_core::model::{Memory, SlurmTime};
+
+    fn config() -> AwsBatchConfig {
+        AwsBatchConfig {
+            job_queue: "burst".to_string(),
+            image: "example/repx-runner:latest".to_string(),
+            s3_prefix: "s3://bucket/repx/".to_string(),
+            region: Some("eu-west-1".to_string()),
+            profile: None,
+            job_role_arn: None,
+            log_group: None,
+        }
+    }
+
+    #[test]
+    fn test_batch_job_name_replaces_invalid_characters() {
+        let name = batch_job_name(&JobId::from("abc123-train.v1.0"));
+        assert_eq!(name, "repx-abc123-train_v1_0");
+        let long = batch_job_name(&JobId::from("x".repeat(200)));
+        assert_eq!(long.len(), 128);
+    }
+
+    #[test]
+    fn test_resource_requirements_in_vcpus_and_mib() {
+        let directives = SbatchDirectives {
+            cpus_per_task: Some(4),
+            mem: Some(Memory::from("8G")),
+            time: Some(SlurmTime::from("01:00:00")),
+            ..Default::default()
+        };
+        let requirements = resource_requirements(&directives);
+        assert_eq!(
+            requirements,
+            vec![
+                json!({ "type": "VCPU", "value": "4" }),
+                json!({ "type": "MEMORY", "value": "8192" }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_script_mirrors_staging_layout_through_s3() {
+        let base = Path::new("/stage");
+        let script = generate_aws_batch_script(
+            &config(),
+            base,
+            Path::new("/stage/bin/repx"),
+            &JobId::from("job-b"),
+            &[&JobId::from("job-a")],
+            "/stage/bin/repx internal-execute --job-id 'job-b'".to_string(),
+            "{}",
+            "{}",
+        )
+        .expect("script must render");
+
+        assert!(script.contains(
+            "aws s3 sync --only-show-errors 's3://bucket/repx/artifacts' '/stage/artifacts'"
+        ));
+        assert!(script.contains(
+            "aws s3 cp --only-show-errors 's3://bucket/repx/bin/repx' '/stage/bin/repx'"
+        ));
+        assert!(script.contains("'s3://bucket/repx/outputs/job-a' '/stage/outputs/job-a'"));
+        assert!(script.contains(
+            "aws s3 sync --only-show-errors --delete '/stage/outputs/job-b' 's3://bucket/repx/outputs/job-b'"
+        ));
+        let trap = script.find("trap _repx_upload EXIT").expect("upload trap");
+        let run = script.find("internal-execute").expect("repx command");
+        assert!(trap < run);
+    }
+
+    #[test]
+    fn test_script_rejects_paths_outside_staging() {
+        let result = generate_aws_batch_script(
+            &config(),
+            Path::new("/stage"),
+            Path::new("/usr/bin/repx"),
+            &JobId::from("job"),
+            &[],
+            String::new(),
+            "{}",
+            "{}",
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_describe_jobs() {
+        let output = r#"{"jobs": [
+            {"jobId": "1111", "jobName": "repx-a", "status": "RUNNABLE"},
+            {"jobId": "2222", "jobName": "repx-b", "status": "FAILED",
+             "statusReason": "Essential container in task exited",
+             "container": {"exitCode": 137, "reason": "OutOfMemoryError: Container killed due to memory usage",
+                           "logStreamName": "repx-target/default/abc"}}
+        ]}"#;
+        let jobs = parse_describe_jobs(output).expect("valid output");
+        assert_eq!(jobs["1111"].live_status(), Some(engine::JobStatus::Queued));
+        let failed = &jobs["2222"];
+        assert_eq!(failed.live_status(), None);
+        assert_eq!(
+            failed.failure_summary(),
+            "Essential container in task exited; OutOfMemoryError: Container killed due to memory usage; exit code 137"
+        );
+        assert_eq!(
+            failed
+                .container
+                .as_ref()
+                .and_then(|c| c.log_stream_name.as_deref()),
+            Some("repx-target/default/abc")
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use super::{Client, ClientEvent, SubmitOptions};
+use crate::error::{ClientError, Result};
+use crate::resources::SbatchDirectives;
+use repx_core::{
+    errors::CoreError,
+    model::{Job, JobId, SchedulerType, StageType},
+};
+use std::collections::HashMap;
+
+pub fn submit_flux_batch_run(
+    client: &Client,
+    jobs_to_submit: HashMap<JobId, &Job>,
+    sub_target: &super::SubmissionTarget,
+    options: &SubmitOptions,
+    lab_tar_info: Option<&super::LabTarInfo>,
+    send: impl Fn(ClientEvent),
+) -> Result<String> {
+    if let Some((job_id, _)) = jobs_to_submit
+        .iter()
+        .find(|(_, job)| job.stage_type == StageType::ScatterGather)
+    {
+        return Err(ClientError::Config(CoreError::InvalidConfig {
+            detail: format!(
+                "Scatter-gather job '{}' cannot run on Flux target '{}'; submit it to a local, SLURM or Grid Engine target",
+                job_id, sub_target.target_name
+            ),
+        }));
+    }
+    super::slurm::submit_via_orchestrator(
+        client,
+        jobs_to_submit,
+        sub_target,
+        options,
+        lab_tar_info,
+        SchedulerType::Flux,
+        send,
+    )
+}
+
+pub(super) fn generate_flux_invoker_script(
+    job_id: &JobId,
+    prologue: &[String],
+    directives: &SbatchDirectives,
+    repx_command_to_wrap: String,
+    lab_tar_info: Option<&super::LabTarInfo>,
+    inputs_json: &str,
+    parameters_json: &str,
+) -> String {
+    let mut s = String::with_capacity(4096);
+    s.push_str("#!/usr/bin/env bash\n");
+    s.push_str(&format!("# flux: --job-name={}\n", job_id.as_str()));
+    s.push_str("# flux: --nslots=1\n");
+    s.push_str("# flux: --cwd=/tmp\n");
+    s.push_str("# flux: --output=/dev/null\n");
+    s.push_str("# flux: --error=/dev/null\n");
+    for opt in directives.to_flux_args() {
+        s.push_str(&format!("# flux: {}\n", opt));
+    }
+
+    s.push_str("\nset -e\n\n");
+    super::slurm::push_invoker_body(
+        &mut s,
+        prologue,
+        "flux cancel",
+        repx_command_to_wrap,
+        lab_tar_info,
+        inputs_json,
+        parameters_json,
+    );
+    s
+}
@@ -0,0 +1,218 @@
+use nix::errno::Errno;
+use nix::fcntl::{Flock, FlockArg};
+use nix::sys::signal::kill;
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const LEDGER_FILE_MODE: u32 = 0o666;
+
+static NEXT_INSTANCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Reservation {
+    pid: u32,
+    #[serde(default)]
+    instance: u64,
+    unit: String,
+    mem_bytes: u64,
+    cpus: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LedgerFile {
+    #[serde(default)]
+    reservations: Vec<Reservation>,
+}
+
+impl LedgerFile {
+    fn used(&self) -> (u64, usize) {
+        self.reservations.iter().fold((0, 0), |(mem, cpus), r| {
+            (mem + r.mem_bytes, cpus + r.cpus as usize)
+        })
+    }
+}
+
+pub(crate) struct HostLedger {
+    path: PathBuf,
+    pid: u32,
+    instance: u64,
+}
+
+impl HostLedger {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            pid: std::process::id(),
+            instance: NEXT_INSTANCE.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    fn open_shared(path: &Path) -> io::Result<fs::File> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .mode(LEDGER_FILE_MODE)
+            .open(path)?;
+        if let Ok(meta) = file.metadata() {
+            if meta.permissions().mode() & 0o777 != LEDGER_FILE_MODE {
+                let _ = file.set_permissions(fs::Permissions::from_mode(LEDGER_FILE_MODE));
+            }
+        }
+        Ok(file)
+    }
+
+    fn owns(&self, r: &Reservation) -> bool {
+        r.pid == self.pid && r.instance == self.instance
+    }
+
+    fn with_locked<T>(&self, f: impl FnOnce(&mut LedgerFile) -> T) -> io::Result<T> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = Self::open_shared(&self.path)?;
+        let mut file = Flock::lock(file, FlockArg::LockExclusive)
+            .map_err(|(_, errno)| io::Error::from(errno))?;
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        let mut ledger: LedgerFile = if content.trim().is_empty() {
+            LedgerFile::default()
+        } else {
+            serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Discarding unreadable host ledger '{}': {}",
+                    self.path.display(),
+                    e
+                );
+                LedgerFile::default()
+            })
+        };
+        ledger
+            .reservations
+            .retain(|r| r.pid == self.pid || process_alive(r.pid));
+
+        let result = f(&mut ledger);
+
+        let serialized = serde_json::to_vec(&ledger).map_err(io::Error::other)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.set_len(0)?;
+        file.write_all(&serialized)?;
+        Ok(result)
+    }
+
+    pub(crate) fn try_reserve(
+        &self,
+        unit: &str,
+        mem_bytes: u64,
+        cpus: u32,
+        limits: (u64, usize),
+        force: bool,
+    ) -> io::Result<bool> {
+        let (total_mem, total_cpus) = limits;
+        self.with_locked(|ledger| {
+            let (used_mem, used_cpus) = ledger.used();
+            let fits = used_mem + mem_bytes <= total_mem && used_cpus + cpus as usize <= total_cpus;
+            if !fits && !force {
+                return false;
+            }
+            ledger.reservations.push(Reservation {
+                pid: self.pid,
+                instance: self.instance,
+                unit: unit.to_string(),
+                mem_bytes,
+                cpus,
+            });
+            true
+        })
+    }
+
+    pub(crate) fn release(&self, unit: &str) -> io::Result<()> {
+        self.with_locked(|ledger| {
+            ledger
+                .reservations
+                .retain(|r| !(self.owns(r) && r.unit == unit));
+        })
+    }
+}
+
+impl Drop for HostLedger {
+    fn drop(&mut self) {
+        let cleared = self.with_locked(|ledger| {
+            ledger.reservations.retain(|r| !self.owns(r));
+        });
+        if let Err(e) = cleared {
+            tracing::debug!("Failed to clear host ledger reservations: {}", e);
+        }
+    }
+}
+
+pub(crate) fn process_alive(pid: u32) -> bool {
+    let Ok(raw) = i32::try_from(pid) else {
+        return false;
+    };
+    match kill(Pid::from_raw(raw), None) {
+        Ok(()) => true,
+        Err(errno) => errno == Errno::EPERM,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn test_reservations_are_shared_between_ledger_handles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ledger.json");
+        let first = HostLedger::new(path.clone());
+        let second = HostLedger::new(path.clone());
+        let limits = (8 * GIB, 4);
+
+        assert!(first.try_reserve("a", 4 * GIB, 2, limits, false).unwrap());
+        assert!(second.try_reserve("b", 4 * GIB, 2, limits, false).unwrap());
+        assert!(!second.try_reserve("c", GIB, 1, limits, false).unwrap());
+        assert!(second.try_reserve("c", GIB, 1, limits, true).unwrap());
+
+        first.release("a").unwrap();
+        second.release("c").unwrap();
+        assert!(second.try_reserve("d", 2 * GIB, 2, limits, false).unwrap());
+
+        drop(second);
+        assert!(first.try_reserve("e", 8 * GIB, 4, limits, false).unwrap());
+    }
+
+    #[test]
+    fn test_stale_reservations_are_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ledger.json");
+        let stale = LedgerFile {
+            reservations: vec![Reservation {
+                pid: i32::MAX as u32,
+                instance: 0,
+                unit: "ghost".to_string(),
+                mem_bytes: 64 * GIB,
+                cpus: 64,
+            }],
+        };
+        fs::write(&path, serde_json::to_vec(&stale).unwrap()).unwrap();
+
+        let ledger = HostLedger::new(path.clone());
+        assert!(ledger
+            .try_reserve("a", GIB, 1, (8 * GIB, 4), false)
+            .unwrap());
+        drop(ledger);
+
+        let remaining: LedgerFile = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert!(remaining.reservations.is_empty());
+    }
+}
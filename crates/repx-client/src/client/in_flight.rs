@@ -0,0 +1,141 @@
+use super::ClientEvent;
+use crate::error::{ClientError, Result};
+use crate::targets::{SlurmJobInfo, Target};
+use repx_core::{engine::JobStatus, errors::CoreError, model::JobId, model::SchedulerType};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+const QUEUE_RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+pub(super) struct InFlightLimit<'a> {
+    target: &'a dyn Target,
+    scheduler: SchedulerType,
+    limit: usize,
+    cancel_flag: Option<&'a AtomicBool>,
+}
+
+impl<'a> InFlightLimit<'a> {
+    pub(super) fn new(
+        target: &'a dyn Target,
+        scheduler: SchedulerType,
+        limit: usize,
+        cancel_flag: Option<&'a AtomicBool>,
+    ) -> Self {
+        Self {
+            target,
+            scheduler,
+            limit,
+            cancel_flag,
+        }
+    }
+
+    pub(super) fn wait_for_slots(
+        &self,
+        send: &dyn Fn(ClientEvent),
+    ) -> Result<(usize, HashSet<u64>)> {
+        let mut announced = false;
+        loop {
+            let queued = self.queued()?;
+            let in_flight = queued.len();
+            if in_flight < self.limit {
+                let ids = queued.values().map(|info| info.slurm_id).collect();
+                return Ok((self.limit - in_flight, ids));
+            }
+
+            if !announced {
+                tracing::info!(
+                    "Holding submission to '{}': {} of at most {} jobs in flight",
+                    self.target.name(),
+                    in_flight,
+                    self.limit
+                );
+                send(ClientEvent::SubmissionPaced {
+                    target: self.target.name().to_string(),
+                    in_flight,
+                    limit: self.limit,
+                });
+                announced = true;
+            }
+
+            let deadline = Instant::now() + QUEUE_RECHECK_INTERVAL;
+            while Instant::now() < deadline {
+                if self.cancel_flag.is_some_and(|f| f.load(Ordering::SeqCst)) {
+                    return Err(ClientError::Config(CoreError::CommandFailed(
+                        "Held submission cancelled by user".to_string(),
+                    )));
+                }
+                std::thread::sleep(Duration::from_millis(500).min(deadline - Instant::now()));
+            }
+        }
+    }
+
+    fn queued(&self) -> Result<HashMap<JobId, SlurmJobInfo>> {
+        match self.scheduler {
+            SchedulerType::Sge => self.target.qstat(),
+            SchedulerType::Flux => self.target.flux_jobs(),
+            _ => self.target.squeue(),
+        }
+    }
+}
+
+pub(super) fn live_dependencies(
+    deps: &[(&JobId, u64)],
+    queued: &HashSet<u64>,
+    outcomes: &HashMap<JobId, JobStatus>,
+) -> Option<Vec<u64>> {
+    let mut live = Vec::with_capacity(deps.len());
+    for (dep_id, scheduler_id) in deps {
+        if queued.contains(scheduler_id) {
+            live.push(*scheduler_id);
+        } else if !matches!(outcomes.get(*dep_id), Some(JobStatus::Succeeded { .. })) {
+            return None;
+        }
+    }
+    Some(live)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_dependencies_drop_finished_jobs() {
+        let queued_dep = JobId::from("aaa-queued-1.0");
+        let done_dep = JobId::from("bbb-done-1.0");
+        let failed_dep = JobId::from("ccc-failed-1.0");
+        let queued: HashSet<u64> = [10].into_iter().collect();
+        let outcomes: HashMap<JobId, JobStatus> = [
+            (
+                done_dep.clone(),
+                JobStatus::Succeeded {
+                    location: "cluster".to_string(),
+                },
+            ),
+            (
+                failed_dep.clone(),
+                JobStatus::Failed {
+                    location: "cluster".to_string(),
+                    class: None,
+                    reason: None,
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            live_dependencies(&[(&queued_dep, 10), (&done_dep, 11)], &queued, &outcomes),
+            Some(vec![10])
+        );
+        assert_eq!(
+            live_dependencies(&[(&queued_dep, 10), (&failed_dep, 12)], &queued, &outcomes),
+            None
+        );
+        assert_eq!(
+            live_dependencies(&[(&JobId::from("ddd-gone-1.0"), 13)], &queued, &outcomes),
+            None,
+            "a job that left the queue without an outcome did not succeed"
+        );
+    }
+}
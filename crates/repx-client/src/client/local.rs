@@ -1,18 +1,22 @@
+use super::host_ledger::HostLedger;
+use super::local_pids::LocalPidTable;
+use super::local_queue::{LocalQueueFile, LocalQueueState, QueuedUnit, Reservation};
 use super::{Client, ClientEvent, SubmitOptions, WorkUnitPhase};
 use crate::error::{ClientError, Result};
 use crate::resources;
 use crate::targets::Target;
 use num_cpus;
 use repx_core::{
-    constants::{dirs, targets},
+    constants::{dirs, markers, targets},
     engine,
     errors::CoreError,
     fs_utils::path_to_string,
-    model::{Job, JobId, Lab},
+    model::{ExecutionType, Job, JobId, Lab},
+    store::work_items,
 };
 use serde_json::Value;
 use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::process::Child;
 use std::sync::atomic::Ordering;
@@ -24,6 +28,7 @@ use sysinfo::System;
 const DEFAULT_JOB_MEM_BYTES: u64 = 1024 * 1024 * 1024;
 const DEFAULT_JOB_CPUS: u32 = 1;
 const POLL_INTERVAL_MS: u64 = 50;
+const STREAM_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 struct LocalJobContext<'job, 'run> {
     job_id: &'run JobId,
@@ -58,6 +63,17 @@ fn get_job_mem_bytes(
         .unwrap_or(DEFAULT_JOB_MEM_BYTES)
 }
 
+fn get_job_priority(
+    job_id: &JobId,
+    target_name: &str,
+    job: &Job,
+    resources_config: &Option<repx_core::config::Resources>,
+) -> i32 {
+    let hints = job.resource_hints.as_ref();
+    let directives = resources::resolve_for_job(job_id, target_name, resources_config, hints);
+    directives.priority.unwrap_or(0)
+}
+
 fn get_job_cpus(
     job_id: &JobId,
     target_name: &str,
@@ -144,6 +160,17 @@ pub(crate) fn build_steps_json(
                 step_obj.insert("resource_hints".into(), hints_val);
             }
         }
+        if let Some(tag) = exe
+            .image
+            .as_deref()
+            .and_then(|p| p.file_stem())
+            .and_then(|s| s.to_str())
+        {
+            step_obj.insert("image_tag".into(), json!(tag));
+        }
+        if let Some(runtime) = exe.runtime {
+            step_obj.insert("runtime".into(), json!(runtime));
+        }
 
         steps.insert(step_name, serde_json::Value::Object(step_obj));
     }
@@ -202,10 +229,11 @@ struct ResourceTracker {
     used_mem_bytes: u64,
     used_cpus: usize,
     in_flight: HashMap<WorkUnitId, (u64, u32)>,
+    ledger: Option<HostLedger>,
 }
 
 impl ResourceTracker {
-    fn new(mem_override: Option<u64>) -> Self {
+    fn new(mem_override: Option<u64>, ledger: Option<HostLedger>) -> Self {
         let total_cpus = num_cpus::get();
         let total_mem_bytes = match mem_override {
             Some(m) => {
@@ -238,6 +266,7 @@ impl ResourceTracker {
             used_mem_bytes: 0,
             used_cpus: 0,
             in_flight: HashMap::new(),
+            ledger,
         }
     }
 
@@ -261,6 +290,31 @@ impl ResourceTracker {
         mem_fits && cpus_fit
     }
 
+    fn try_reserve(&mut self, id: &WorkUnitId, mem_bytes: u64, cpus: u32) -> bool {
+        if !self.can_fit(id, mem_bytes, cpus) {
+            return false;
+        }
+        if let Some(ledger) = &self.ledger {
+            let limits = (self.total_mem_bytes, self.total_cpus);
+            match ledger.try_reserve(&id.0, mem_bytes, cpus, limits, self.in_flight.is_empty()) {
+                Ok(true) => {}
+                Ok(false) => {
+                    tracing::debug!(
+                        "Unit '{}' waiting for resources held by other repx processes",
+                        id.short_id()
+                    );
+                    return false;
+                }
+                Err(e) => {
+                    tracing::warn!("Host ledger unavailable, scheduling without it: {}", e);
+                    self.ledger = None;
+                }
+            }
+        }
+        self.reserve(id.clone(), mem_bytes, cpus);
+        true
+    }
+
     fn reserve(&mut self, id: WorkUnitId, mem_bytes: u64, cpus: u32) {
         self.used_mem_bytes += mem_bytes;
         self.used_cpus += cpus as usize;
@@ -271,6 +325,11 @@ impl ResourceTracker {
         if let Some((mem, cpus)) = self.in_flight.remove(id) {
             self.used_mem_bytes = self.used_mem_bytes.saturating_sub(mem);
             self.used_cpus = self.used_cpus.saturating_sub(cpus as usize);
+            if let Some(ledger) = &self.ledger {
+                if let Err(e) = ledger.release(&id.0) {
+                    tracing::warn!("Failed to release host ledger reservation: {}", e);
+                }
+            }
         }
     }
 }
@@ -331,11 +390,57 @@ struct WorkUnit<'a> {
     deps: Vec<WorkUnitId>,
     mem_bytes: u64,
     cpus: u32,
+    priority: i32,
     job: &'a Job,
     job_id: JobId,
     extra_args: Vec<String>,
 }
 
+impl WorkUnit<'_> {
+    fn to_queued(&self) -> QueuedUnit {
+        QueuedUnit {
+            job_id: self.job_id.clone(),
+            deps: self.deps.iter().map(|dep| dep.0.clone()).collect(),
+            mem_bytes: self.mem_bytes,
+            cpus: self.cpus,
+            priority: self.priority,
+            extra_args: self.extra_args.clone(),
+        }
+    }
+}
+
+type WorkGraph<'a> = (HashMap<WorkUnitId, WorkUnit<'a>>, HashSet<WorkUnitId>);
+
+fn restore_work_units<'a>(
+    state: &LocalQueueState,
+    jobs_in_batch: &HashMap<JobId, &'a Job>,
+) -> Option<WorkGraph<'a>> {
+    let mut units = HashMap::with_capacity(state.units.len());
+    for (id, queued) in &state.units {
+        let job = *jobs_in_batch.get(&queued.job_id)?;
+        units.insert(
+            WorkUnitId(id.clone()),
+            WorkUnit {
+                deps: queued.deps.iter().cloned().map(WorkUnitId).collect(),
+                mem_bytes: queued.mem_bytes,
+                cpus: queued.cpus,
+                priority: queued.priority,
+                job,
+                job_id: queued.job_id.clone(),
+                extra_args: queued.extra_args.clone(),
+            },
+        );
+    }
+    let completed = state.completed.iter().cloned().map(WorkUnitId).collect();
+    Some((units, completed))
+}
+
+fn save_queue_state(file: &LocalQueueFile, state: &LocalQueueState) {
+    if let Err(e) = file.save(state) {
+        tracing::warn!("Failed to save local run queue: {}", e);
+    }
+}
+
 fn format_bytes(bytes: u64) -> String {
     if bytes >= 1024 * 1024 * 1024 * 1024 {
         format!("{}T", bytes / (1024 * 1024 * 1024 * 1024))
@@ -350,6 +455,50 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct QueueRank {
+    priority: Reverse<i32>,
+    depth: Reverse<usize>,
+    run_idx: usize,
+}
+
+impl QueueRank {
+    const LAST: Self = Self {
+        priority: Reverse(i32::MIN),
+        depth: Reverse(0),
+        run_idx: usize::MAX,
+    };
+}
+
+fn critical_path_depth(
+    unit_id: &WorkUnitId,
+    dependents: &HashMap<WorkUnitId, Vec<WorkUnitId>>,
+    memo: &mut HashMap<WorkUnitId, usize>,
+) -> usize {
+    if let Some(&depth) = memo.get(unit_id) {
+        return depth;
+    }
+    memo.insert(unit_id.clone(), 0);
+    let gather = unit_id
+        .0
+        .strip_suffix("::scatter")
+        .map(|job| WorkUnitId::gather(&JobId::from(job)));
+    let children: Vec<WorkUnitId> = dependents
+        .get(unit_id)
+        .into_iter()
+        .chain(gather.as_ref().and_then(|g| dependents.get(g)))
+        .flatten()
+        .cloned()
+        .collect();
+    let depth = children
+        .iter()
+        .map(|child| 1 + critical_path_depth(child, dependents, memo))
+        .max()
+        .unwrap_or(0);
+    memo.insert(unit_id.clone(), depth);
+    depth
+}
+
 fn build_run_affinity_index(lab: &Lab) -> HashMap<JobId, usize> {
     let mut sorted_run_ids: Vec<_> = lab.runs.keys().collect();
     sorted_run_ids.sort();
@@ -411,12 +560,54 @@ fn build_common_args(
         }
         repx_core::model::MountPolicy::Isolated => {}
     }
+    for mount in &ctx.job.data_mounts {
+        args.push("--data-mount".to_string());
+        args.push(mount.to_string());
+    }
+    if let Some(network) = ctx.target.config().network {
+        args.push("--network".to_string());
+        args.push(network.to_string());
+    }
+    if let Some(seccomp) = &ctx.target.config().seccomp {
+        args.push("--seccomp".to_string());
+        args.push(seccomp.to_string());
+    }
+    for (flag, hook) in [
+        ("--pre-run", &ctx.target.config().pre_run),
+        ("--post-run", &ctx.target.config().post_run),
+    ] {
+        if let Some(path) = hook {
+            args.push(flag.to_string());
+            args.push(path_to_string(path));
+        }
+    }
+    if let Some(max_bytes) = ctx.target.config().image_cache_max_bytes() {
+        args.push("--image-cache-max-bytes".to_string());
+        args.push(max_bytes.to_string());
+    }
+    for (key, value) in &ctx.target.config().env {
+        args.push("--env".to_string());
+        args.push(format!("{}={}", key, value));
+    }
+    for name in &ctx.target.config().env_passthrough {
+        args.push("--env-passthrough".to_string());
+        args.push(name.clone());
+    }
+    if let Some(store) = &ctx.target.config().object_store {
+        args.push("--object-store".to_string());
+        args.push(store.uri.clone());
+        if let Some(endpoint) = &store.endpoint_url {
+            args.push("--object-store-endpoint".to_string());
+            args.push(endpoint.clone());
+        }
+    }
     args
 }
 
 fn build_sg_common_args(
     ctx: &LocalJobContext<'_, '_>,
     verbose: repx_core::logging::Verbosity,
+    resources_config: &Option<repx_core::config::Resources>,
 ) -> std::result::Result<Vec<String>, ClientError> {
     let artifacts_base = ctx.target.artifacts_base_path();
     let scatter_exe = ctx.job.executables.get("scatter").ok_or_else(|| {
@@ -453,6 +644,17 @@ fn build_sg_common_args(
         "--step-sbatch-opts".to_string(),
         String::new(),
     ]);
+    let directives = resources::resolve_for_job(
+        ctx.job_id,
+        ctx.target.name(),
+        resources_config,
+        ctx.job.resource_hints.as_ref(),
+    );
+    args.extend(directives.disk_args());
+    args.extend(directives.min_branch_success_args());
+    if ctx.target.config().auto_prune_steps {
+        args.push("--auto-prune-steps".to_string());
+    }
     Ok(args)
 }
 
@@ -473,19 +675,106 @@ fn build_simple_job_args(
         "--executable-path".to_string(),
         path_to_string(&executable_path),
     ]);
+    if let Some(interpreter) = &main_exe.interpreter {
+        args.push("--interpreter".to_string());
+        args.push(interpreter.clone());
+    }
+    for arg in main_exe.resolve_args(ctx.job_id, &ctx.job.params)? {
+        args.push(format!("--arg-template={}", arg));
+    }
+    if ctx.target.config().structured_logs {
+        args.push("--structured-logs".to_string());
+    }
+    if let Some(secs) = ctx.target.config().heartbeat_interval_secs {
+        args.push("--heartbeat-interval".to_string());
+        args.push(secs.to_string());
+    }
+    for rule in &ctx.target.config().failure_rules {
+        args.push("--failure-rule".to_string());
+        args.push(rule.to_string());
+    }
     Ok(args)
 }
 
+fn build_resource_args(
+    ctx: &LocalJobContext<'_, '_>,
+    resources_config: &Option<repx_core::config::Resources>,
+) -> Vec<String> {
+    let local = ctx.target.config().local.as_ref();
+    let hints = ctx.job.resource_hints.as_ref();
+    let directives =
+        resources::resolve_for_job(ctx.job_id, ctx.target.name(), resources_config, hints);
+    let mut args = directives.retry_args();
+    args.extend(directives.checkpoint_args());
+    args.extend(directives.disk_args());
+
+    if let Some(secs) = directives.time.as_ref().and_then(|t| t.to_seconds()) {
+        args.push("--time-limit".to_string());
+        args.push(secs.to_string());
+        if let Some(grace) = local.and_then(|local| local.kill_grace_secs) {
+            args.push("--kill-grace".to_string());
+            args.push(grace.to_string());
+        }
+    }
+
+    let execution_type = match ctx.execution_type.parse::<ExecutionType>() {
+        Ok(execution_type) => execution_type,
+        Err(e) => {
+            tracing::warn!("Not enforcing resource limits for '{}': {}", ctx.job_id, e);
+            return args;
+        }
+    };
+    let enforce = match &ctx.target.config().container {
+        Some(container) => execution_type == container.engine,
+        None => {
+            local.is_some_and(|local| local.enforce_limits)
+                && execution_type == ExecutionType::Native
+        }
+    };
+    if !enforce {
+        return args;
+    }
+    if let Some(mem) = directives.mem.as_ref().and_then(|m| m.to_bytes()) {
+        args.push("--limit-mem".to_string());
+        args.push(mem.to_string());
+    }
+    if let Some(cpus) = directives.cpus_per_task {
+        args.push("--limit-cpus".to_string());
+        args.push(cpus.to_string());
+    }
+    args
+}
+
 fn resolve_local_execution_type(
     image_tag: Option<&str>,
     options: &SubmitOptions,
     target: &dyn Target,
 ) -> String {
+    if let (Some(container), Some(_)) = (&target.config().container, image_tag) {
+        let engine = container.engine.to_string();
+        return match options.execution_type.as_deref() {
+            Some(explicit @ ("podman" | "docker")) => explicit.to_string(),
+            Some(explicit) => {
+                tracing::warn!(
+                    "Ignoring runtime '{}' on container target '{}'; using {}",
+                    explicit,
+                    target.name(),
+                    engine
+                );
+                engine
+            }
+            None => engine,
+        };
+    }
+    let capabilities = (image_tag.is_some() && options.execution_type.is_none())
+        .then(|| target.runtime_capabilities())
+        .flatten();
     super::resolve_execution_type(
         image_tag,
         options.execution_type.as_deref(),
         target.config(),
         target.config().local.as_ref(),
+        capabilities.as_ref(),
     )
 }
 
@@ -527,29 +816,36 @@ fn get_step_resources(
     (mem, cpus)
 }
 
-#[allow(clippy::expect_used)]
-fn expand_scatter_gather<'job>(
-    ctx: &LocalJobContext<'job, '_>,
-    options: &SubmitOptions,
-) -> std::result::Result<Vec<(WorkUnitId, WorkUnit<'job>)>, ClientError> {
-    let base_path = ctx.target.base_path();
-    let job_root = base_path.join(dirs::OUTPUTS).join(ctx.job_id.as_str());
-    let work_items_path = job_root.join("scatter").join("out").join("work_items.json");
-    let work_items_str = ctx.target.read_remote_file(&work_items_path).map_err(|e| {
-        ClientError::Config(CoreError::CommandFailed(format!(
-            "Failed to read work_items.json after scatter for '{}': {}",
-            ctx.job_id, e
-        )))
-    })?;
-    let work_items: Vec<Value> = serde_json::from_str(&work_items_str).map_err(|e| {
-        ClientError::Config(CoreError::SerializationError(format!(
-            "Failed to parse work_items.json for '{}': {}",
-            ctx.job_id, e
-        )))
-    })?;
+fn retained_branch_steps(
+    topo_order: &[String],
+    step_deps: &HashMap<String, Vec<String>>,
+    num_branches: usize,
+    succeeded: &HashSet<(usize, String)>,
+) -> HashSet<(usize, String)> {
+    let mut retained = HashSet::new();
+    for branch in 0..num_branches {
+        for step in topo_order {
+            let deps_retained = step_deps.get(step).is_none_or(|deps| {
+                deps.iter()
+                    .all(|dep| retained.contains(&(branch, dep.clone())))
+            });
+            if deps_retained && succeeded.contains(&(branch, step.clone())) {
+                retained.insert((branch, step.clone()));
+            }
+        }
+    }
+    retained
+}
+
+struct StepGraph {
+    topo_order: Vec<String>,
+    step_deps: HashMap<String, Vec<String>>,
+    sink_step: String,
+}
 
-    let step_exes: Vec<(String, &repx_core::model::Executable)> = ctx
-        .job
+#[allow(clippy::expect_used)]
+fn step_graph(job: &Job) -> std::result::Result<StepGraph, ClientError> {
+    let step_exes: Vec<(String, &repx_core::model::Executable)> = job
         .executables
         .iter()
         .filter(|(k, _)| k.starts_with("step-"))
@@ -620,25 +916,47 @@ fn expand_scatter_gather<'job>(
         })?
         .to_string();
 
-    let scatter_id = WorkUnitId::scatter(ctx.job_id);
+    let step_deps = step_exes
+        .iter()
+        .map(|(name, exe)| (name.clone(), exe.deps.clone()))
+        .collect();
+    Ok(StepGraph {
+        topo_order,
+        step_deps,
+        sink_step,
+    })
+}
 
-    let sg_common = build_sg_common_args(ctx, options.verbose)?;
+fn branch_units<'job>(
+    ctx: &LocalJobContext<'job, '_>,
+    options: &SubmitOptions,
+    graph: &StepGraph,
+    branches: std::ops::Range<usize>,
+    retained: &HashSet<(usize, String)>,
+    root_dep: Option<&WorkUnitId>,
+) -> std::result::Result<Vec<(WorkUnitId, WorkUnit<'job>)>, ClientError> {
+    let sg_common = build_sg_common_args(ctx, options.verbose, &options.resources)?;
+    let priority = get_job_priority(ctx.job_id, ctx.target.name(), ctx.job, &options.resources);
 
     let mut units = Vec::new();
-
-    for branch_idx in 0..work_items.len() {
-        for step_name in &topo_order {
+    for branch_idx in branches {
+        for step_name in &graph.topo_order {
+            if retained.contains(&(branch_idx, step_name.clone())) {
+                continue;
+            }
             let step_id = WorkUnitId::step(ctx.job_id, branch_idx, step_name);
             let exe_key = format!("step-{}", step_name);
 
-            let step_exe = ctx.job.executables.get(&exe_key);
-            let intra_deps: Vec<String> = step_exe.map(|e| e.deps.clone()).unwrap_or_default();
-            let mut deps: Vec<WorkUnitId> = intra_deps
-                .iter()
+            let mut deps: Vec<WorkUnitId> = graph
+                .step_deps
+                .get(step_name)
+                .into_iter()
+                .flatten()
+                .filter(|dep| !retained.contains(&(branch_idx, (*dep).clone())))
                 .map(|dep| WorkUnitId::step(ctx.job_id, branch_idx, dep))
                 .collect();
             if deps.is_empty() {
-                deps.push(scatter_id.clone());
+                deps.extend(root_dep.cloned());
             }
 
             let (mem, cpus) = get_step_resources(ctx.job, &exe_key, &options.resources, ctx.job_id);
@@ -659,6 +977,7 @@ fn expand_scatter_gather<'job>(
                     deps,
                     mem_bytes: mem,
                     cpus,
+                    priority,
                     job: ctx.job,
                     job_id: ctx.job_id.clone(),
                     extra_args,
@@ -666,14 +985,109 @@ fn expand_scatter_gather<'job>(
             ));
         }
     }
+    Ok(units)
+}
+
+fn expand_streamed_branches<'job>(
+    ctx: &LocalJobContext<'job, '_>,
+    options: &SubmitOptions,
+    branches: std::ops::Range<usize>,
+) -> std::result::Result<Vec<(WorkUnitId, WorkUnit<'job>)>, ClientError> {
+    let graph = step_graph(ctx.job)?;
+    branch_units(ctx, options, &graph, branches, &HashSet::new(), None)
+}
+
+fn expand_scatter_gather<'job>(
+    ctx: &LocalJobContext<'job, '_>,
+    options: &SubmitOptions,
+    streamed: usize,
+) -> std::result::Result<Vec<(WorkUnitId, WorkUnit<'job>)>, ClientError> {
+    let base_path = ctx.target.base_path();
+    let job_root = base_path.join(dirs::OUTPUTS).join(ctx.job_id.as_str());
+    let work_items_path = job_root
+        .join("scatter")
+        .join(dirs::OUT)
+        .join(work_items::WORK_ITEMS_FILE);
+    let work_items_str = ctx.target.read_remote_file(&work_items_path).map_err(|e| {
+        ClientError::Config(CoreError::CommandFailed(format!(
+            "Failed to read work_items.json after scatter for '{}': {}",
+            ctx.job_id, e
+        )))
+    })?;
+    let work_items: Vec<Value> = serde_json::from_str(&work_items_str).map_err(|e| {
+        ClientError::Config(CoreError::SerializationError(format!(
+            "Failed to parse work_items.json for '{}': {}",
+            ctx.job_id, e
+        )))
+    })?;
+
+    let graph = step_graph(ctx.job)?;
+    let scatter_id = WorkUnitId::scatter(ctx.job_id);
+
+    let retained = if options.retry_failed {
+        let mut succeeded = ctx.target.find_step_markers(ctx.job_id, markers::SUCCESS)?;
+        let branches: HashSet<usize> = succeeded
+            .iter()
+            .map(|(branch, _)| *branch)
+            .filter(|&branch| branch >= streamed)
+            .collect();
+        let unchanged: HashSet<usize> = branches
+            .into_iter()
+            .filter(|&branch| {
+                let previous_item_path = job_root
+                    .join(format!("branch-{}", branch))
+                    .join(dirs::REPX)
+                    .join("work_item.json");
+                match (
+                    ctx.target.read_remote_file(&previous_item_path),
+                    work_items.get(branch),
+                ) {
+                    (Ok(previous), Some(item)) => {
+                        serde_json::to_string(item).is_ok_and(|current| current == previous)
+                    }
+                    _ => false,
+                }
+            })
+            .collect();
+        succeeded.retain(|(branch, _)| unchanged.contains(branch));
+        let retained = retained_branch_steps(
+            &graph.topo_order,
+            &graph.step_deps,
+            work_items.len(),
+            &succeeded,
+        );
+        tracing::info!(
+            "Keeping {} succeeded branch steps of '{}' from the previous attempt",
+            retained.len(),
+            ctx.job_id
+        );
+        retained
+    } else {
+        HashSet::new()
+    };
+
+    let mut units = branch_units(
+        ctx,
+        options,
+        &graph,
+        streamed.min(work_items.len())..work_items.len(),
+        &retained,
+        Some(&scatter_id),
+    )?;
 
-    let gather_deps: Vec<WorkUnitId> = (0..work_items.len())
-        .map(|b| WorkUnitId::step(ctx.job_id, b, &sink_step))
+    let sink_step = &graph.sink_step;
+    let mut gather_deps: Vec<WorkUnitId> = (0..work_items.len())
+        .filter(|b| !retained.contains(&(*b, sink_step.clone())))
+        .map(|b| WorkUnitId::step(ctx.job_id, b, sink_step))
         .collect();
+    if gather_deps.is_empty() {
+        gather_deps.push(scatter_id.clone());
+    }
     let gather_mem = get_job_mem_bytes(ctx.job_id, ctx.target.name(), ctx.job, &options.resources);
     let gather_cpus = get_job_cpus(ctx.job_id, ctx.target.name(), ctx.job, &options.resources);
+    let priority = get_job_priority(ctx.job_id, ctx.target.name(), ctx.job, &options.resources);
 
-    let mut gather_extra = sg_common;
+    let mut gather_extra = build_sg_common_args(ctx, options.verbose, &options.resources)?;
     gather_extra.extend_from_slice(&["--phase".to_string(), "gather".to_string()]);
 
     units.push((
@@ -682,6 +1096,7 @@ fn expand_scatter_gather<'job>(
             deps: gather_deps,
             mem_bytes: gather_mem,
             cpus: gather_cpus,
+            priority,
             job: ctx.job,
             job_id: ctx.job_id.clone(),
             extra_args: gather_extra,
@@ -691,6 +1106,95 @@ fn expand_scatter_gather<'job>(
     Ok(units)
 }
 
+#[derive(Debug, Default)]
+struct ScatterStream {
+    offset: u64,
+    branches: usize,
+}
+
+fn open_scatter_stream(target: &dyn Target, job_id: &JobId) -> Option<ScatterStream> {
+    let scatter_root = target
+        .base_path()
+        .join(dirs::OUTPUTS)
+        .join(job_id.as_str())
+        .join("scatter");
+    match target.remote_file_size(&scatter_root.join(dirs::REPX).join(markers::SUCCESS)) {
+        Ok(None) => {}
+        Ok(Some(_)) => return None,
+        Err(e) => {
+            tracing::debug!("Not streaming work items of '{}': {}", job_id, e);
+            return None;
+        }
+    }
+    let stream_path = scatter_root
+        .join(dirs::OUT)
+        .join(work_items::WORK_ITEMS_STREAM_FILE);
+    if let Err(e) = target.write_remote_file(&stream_path, "") {
+        tracing::debug!("Not streaming work items of '{}': {}", job_id, e);
+        return None;
+    }
+    Some(ScatterStream::default())
+}
+
+fn read_scatter_stream(
+    target: &dyn Target,
+    job_id: &JobId,
+    stream: &mut ScatterStream,
+) -> Result<usize> {
+    let stream_path = target
+        .base_path()
+        .join(dirs::OUTPUTS)
+        .join(job_id.as_str())
+        .join("scatter")
+        .join(dirs::OUT)
+        .join(work_items::WORK_ITEMS_STREAM_FILE);
+    let content = target.read_remote_file_from(&stream_path, stream.offset)?;
+    let (items, consumed) = work_items::parse_stream(&content)?;
+    stream.offset += consumed as u64;
+    Ok(items.len())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn enqueue_units<'a>(
+    units: Vec<(WorkUnitId, WorkUnit<'a>)>,
+    work_units: &mut HashMap<WorkUnitId, WorkUnit<'a>>,
+    dependents: &mut HashMap<WorkUnitId, Vec<WorkUnitId>>,
+    unit_ranks: &mut HashMap<WorkUnitId, QueueRank>,
+    units_left: &mut HashSet<WorkUnitId>,
+    ready_queue: &mut BinaryHeap<Reverse<(QueueRank, WorkUnitId)>>,
+    in_ready_queue: &mut HashSet<WorkUnitId>,
+    completed: &HashSet<WorkUnitId>,
+    run_affinity: &HashMap<JobId, usize>,
+) {
+    for (new_id, new_unit) in &units {
+        for dep in &new_unit.deps {
+            dependents
+                .entry(dep.clone())
+                .or_default()
+                .push(new_id.clone());
+        }
+    }
+    let mut expanded_memo: HashMap<WorkUnitId, usize> = HashMap::new();
+    for (new_id, new_unit) in units {
+        let deps_met = new_unit.deps.iter().all(|d| completed.contains(d));
+        let rank = QueueRank {
+            priority: Reverse(new_unit.priority),
+            depth: Reverse(critical_path_depth(&new_id, dependents, &mut expanded_memo)),
+            run_idx: run_affinity
+                .get(&new_unit.job_id)
+                .copied()
+                .unwrap_or(usize::MAX),
+        };
+        unit_ranks.insert(new_id.clone(), rank);
+        units_left.insert(new_id.clone());
+        work_units.insert(new_id.clone(), new_unit);
+        if deps_met && !in_ready_queue.contains(&new_id) {
+            ready_queue.push(Reverse((rank, new_id.clone())));
+            in_ready_queue.insert(new_id);
+        }
+    }
+}
+
 pub fn submit_local_batch_run(
     client: &Client,
     jobs_in_batch: HashMap<JobId, &Job>,
@@ -702,7 +1206,10 @@ pub fn submit_local_batch_run(
     let target = &sub_target.target;
     let repx_binary_path = &sub_target.repx_binary_path;
     let total_jobs = jobs_in_batch.len();
-    let concurrency = options.num_jobs.unwrap_or_else(num_cpus::get);
+    let mut concurrency = options.num_jobs.unwrap_or_else(num_cpus::get);
+    if let Some(limit) = target.config().max_in_flight() {
+        concurrency = concurrency.min(limit);
+    }
     send(ClientEvent::SubmittingJobs {
         total: total_jobs,
         concurrency: Some(concurrency),
@@ -710,28 +1217,25 @@ pub fn submit_local_batch_run(
     let mut succeeded_work_units: usize = 0;
     let run_affinity = build_run_affinity_index(&client.lab);
 
-    let all_deps: HashSet<JobId> = jobs_in_batch
-        .values()
-        .flat_map(|job| {
-            job.executables
-                .values()
-                .flat_map(|exe| exe.inputs.iter().filter_map(|m| m.job_id.as_ref()))
-        })
-        .cloned()
-        .collect();
-    let raw_statuses = client.get_statuses_for_active_target(
-        target.name(),
-        Some(repx_core::model::SchedulerType::Local),
-    )?;
-    let all_job_statuses = engine::determine_job_statuses(&client.lab, raw_statuses);
-    let completed_job_ids: HashSet<JobId> = all_job_statuses
-        .into_iter()
-        .filter(|(id, status)| {
-            matches!(status, repx_core::engine::JobStatus::Succeeded { .. })
-                && (all_deps.contains(id) || jobs_in_batch.contains_key(id))
-        })
-        .map(|(id, _)| id)
-        .collect();
+    let queue_file = LocalQueueFile::new(client.local_queue_path.clone());
+    let batch: BTreeSet<JobId> = jobs_in_batch.keys().cloned().collect();
+    let saved_state = queue_file
+        .load()
+        .filter(|state| state.matches(target.name(), &batch));
+    if let Some((unit, pid)) = saved_state
+        .as_ref()
+        .and_then(|state| state.live_reservations().first().copied())
+    {
+        return Err(ClientError::Config(CoreError::CommandFailed(format!(
+            "A previous local run of this batch is still running unit '{}' (PID {}). \
+             Wait for it to finish or stop it with 'repx cancel' before running again.",
+            unit, pid
+        ))));
+    }
+    let restored = saved_state
+        .as_ref()
+        .filter(|_| !options.retry_failed)
+        .and_then(|state| restore_work_units(state, &jobs_in_batch));
 
     let mut completion_map: HashMap<JobId, WorkUnitId> = HashMap::new();
 
@@ -739,125 +1243,180 @@ pub fn submit_local_batch_run(
     let mut units_left: HashSet<WorkUnitId> = HashSet::new();
     let mut completed: HashSet<WorkUnitId> = HashSet::new();
 
-    for (job_id_ref, &job) in &jobs_in_batch {
-        let job_id = job_id_ref.clone();
-
-        if job.stage_type == repx_core::model::StageType::Worker
-            || job.stage_type == repx_core::model::StageType::Gather
-        {
-            continue;
+    if let Some((units, done)) = restored {
+        tracing::info!(
+            "Resuming interrupted local run: {} of {} work unit(s) already finished",
+            units.keys().filter(|id| done.contains(*id)).count(),
+            units.len()
+        );
+        for (job_id, job) in &jobs_in_batch {
+            match job.stage_type {
+                repx_core::model::StageType::Worker | repx_core::model::StageType::Gather => {}
+                repx_core::model::StageType::ScatterGather => {
+                    completion_map.insert(job_id.clone(), WorkUnitId::gather(job_id));
+                }
+                _ => {
+                    completion_map.insert(job_id.clone(), WorkUnitId::from_job(job_id));
+                }
+            }
         }
+        units_left = units
+            .keys()
+            .filter(|id| !done.contains(*id))
+            .cloned()
+            .collect();
+        work_units = units;
+        completed = done;
+    } else {
+        let all_deps: HashSet<JobId> = jobs_in_batch
+            .values()
+            .flat_map(|job| {
+                job.executables
+                    .values()
+                    .flat_map(|exe| exe.inputs.iter().filter_map(|m| m.job_id.as_ref()))
+            })
+            .cloned()
+            .collect();
+        let raw_statuses = client.get_statuses_for_active_target(
+            target.name(),
+            Some(repx_core::model::SchedulerType::Local),
+        )?;
+        let all_job_statuses = engine::determine_job_statuses(&client.lab, raw_statuses);
+        let completed_job_ids: HashSet<JobId> = all_job_statuses
+            .into_iter()
+            .filter(|(id, status)| {
+                matches!(status, repx_core::engine::JobStatus::Succeeded { .. })
+                    && (all_deps.contains(id) || jobs_in_batch.contains_key(id))
+            })
+            .map(|(id, _)| id)
+            .collect();
 
-        if completed_job_ids.contains(&job_id) {
-            let unit_id = if job.stage_type == repx_core::model::StageType::ScatterGather {
-                WorkUnitId::gather(&job_id)
-            } else {
-                WorkUnitId::from_job(&job_id)
-            };
-            completed.insert(unit_id.clone());
-            completion_map.insert(job_id, unit_id);
-            continue;
-        }
+        for (job_id_ref, &job) in &jobs_in_batch {
+            let job_id = job_id_ref.clone();
 
-        let image_tag = resolve_image_tag(&job_id, client);
-        let execution_type = resolve_local_execution_type(image_tag, options, target.as_ref());
-
-        let entrypoint_exe = job
-            .executables
-            .get("main")
-            .or_else(|| job.executables.get("scatter"))
-            .ok_or_else(|| {
-                ClientError::Config(CoreError::MissingExecutable {
-                    job_id: job_id.to_string(),
-                    executable: "main or scatter".to_string(),
-                })
-            })?;
-        let job_deps: Vec<WorkUnitId> = entrypoint_exe
-            .inputs
-            .iter()
-            .filter_map(|m| m.job_id.as_ref())
-            .map(|dep_job_id| {
-                if let Some(dep_job) = jobs_in_batch.get(dep_job_id) {
-                    if dep_job.stage_type == repx_core::model::StageType::ScatterGather {
-                        WorkUnitId::gather(dep_job_id)
+            if job.stage_type == repx_core::model::StageType::Worker
+                || job.stage_type == repx_core::model::StageType::Gather
+            {
+                continue;
+            }
+
+            if completed_job_ids.contains(&job_id) {
+                let unit_id = if job.stage_type == repx_core::model::StageType::ScatterGather {
+                    WorkUnitId::gather(&job_id)
+                } else {
+                    WorkUnitId::from_job(&job_id)
+                };
+                completed.insert(unit_id.clone());
+                completion_map.insert(job_id, unit_id);
+                continue;
+            }
+
+            let image_tag = resolve_image_tag(&job_id, client);
+            let execution_type = resolve_local_execution_type(image_tag, options, target.as_ref());
+
+            let entrypoint_exe = job
+                .executables
+                .get("main")
+                .or_else(|| job.executables.get("scatter"))
+                .ok_or_else(|| {
+                    ClientError::Config(CoreError::MissingExecutable {
+                        job_id: job_id.to_string(),
+                        executable: "main or scatter".to_string(),
+                    })
+                })?;
+            let job_deps: Vec<WorkUnitId> = entrypoint_exe
+                .inputs
+                .iter()
+                .filter_map(|m| m.job_id.as_ref())
+                .map(|dep_job_id| {
+                    if let Some(dep_job) = jobs_in_batch.get(dep_job_id) {
+                        if dep_job.stage_type == repx_core::model::StageType::ScatterGather {
+                            WorkUnitId::gather(dep_job_id)
+                        } else {
+                            WorkUnitId::from_job(dep_job_id)
+                        }
                     } else {
                         WorkUnitId::from_job(dep_job_id)
                     }
-                } else {
-                    WorkUnitId::from_job(dep_job_id)
-                }
-            })
-            .collect();
+                })
+                .collect();
 
-        if job.stage_type == repx_core::model::StageType::ScatterGather {
-            let scatter_id = WorkUnitId::scatter(&job_id);
-            let mem = get_job_mem_bytes(&job_id, target.name(), job, &options.resources);
-            let cpus = get_job_cpus(&job_id, target.name(), job, &options.resources);
+            if job.stage_type == repx_core::model::StageType::ScatterGather {
+                let scatter_id = WorkUnitId::scatter(&job_id);
+                let mem = get_job_mem_bytes(&job_id, target.name(), job, &options.resources);
+                let cpus = get_job_cpus(&job_id, target.name(), job, &options.resources);
+                let priority = get_job_priority(&job_id, target.name(), job, &options.resources);
 
-            let ctx = LocalJobContext {
-                job_id: &job_id,
-                job,
-                target: target.as_ref(),
-                client,
-                execution_type: &execution_type,
-                image_tag,
-                local_artifacts_path,
-            };
-            let mut extra_args = build_sg_common_args(&ctx, options.verbose)?;
-            extra_args.extend_from_slice(&["--phase".to_string(), "scatter-only".to_string()]);
-
-            work_units.insert(
-                scatter_id.clone(),
-                WorkUnit {
-                    deps: job_deps,
-                    mem_bytes: mem,
-                    cpus,
+                let ctx = LocalJobContext {
+                    job_id: &job_id,
                     job,
-                    job_id: job_id.clone(),
-                    extra_args,
-                },
-            );
-            units_left.insert(scatter_id);
+                    target: target.as_ref(),
+                    client,
+                    execution_type: &execution_type,
+                    image_tag,
+                    local_artifacts_path,
+                };
+                let mut extra_args =
+                    build_sg_common_args(&ctx, options.verbose, &options.resources)?;
+                extra_args.extend_from_slice(&["--phase".to_string(), "scatter-only".to_string()]);
+
+                work_units.insert(
+                    scatter_id.clone(),
+                    WorkUnit {
+                        deps: job_deps,
+                        mem_bytes: mem,
+                        cpus,
+                        priority,
+                        job,
+                        job_id: job_id.clone(),
+                        extra_args,
+                    },
+                );
+                units_left.insert(scatter_id);
 
-            completion_map.insert(job_id, WorkUnitId::gather(job_id_ref));
-        } else {
-            let unit_id = WorkUnitId::from_job(&job_id);
-            let mem = get_job_mem_bytes(&job_id, target.name(), job, &options.resources);
-            let cpus = get_job_cpus(&job_id, target.name(), job, &options.resources);
+                completion_map.insert(job_id, WorkUnitId::gather(job_id_ref));
+            } else {
+                let unit_id = WorkUnitId::from_job(&job_id);
+                let mem = get_job_mem_bytes(&job_id, target.name(), job, &options.resources);
+                let cpus = get_job_cpus(&job_id, target.name(), job, &options.resources);
+                let priority = get_job_priority(&job_id, target.name(), job, &options.resources);
 
-            let ctx = LocalJobContext {
-                job_id: &job_id,
-                job,
-                target: target.as_ref(),
-                client,
-                execution_type: &execution_type,
-                image_tag,
-                local_artifacts_path,
-            };
-            let extra_args = build_simple_job_args(&ctx, options.verbose)?;
-
-            work_units.insert(
-                unit_id.clone(),
-                WorkUnit {
-                    deps: job_deps,
-                    mem_bytes: mem,
-                    cpus,
+                let ctx = LocalJobContext {
+                    job_id: &job_id,
                     job,
-                    job_id: job_id.clone(),
-                    extra_args,
-                },
-            );
-            units_left.insert(unit_id.clone());
+                    target: target.as_ref(),
+                    client,
+                    execution_type: &execution_type,
+                    image_tag,
+                    local_artifacts_path,
+                };
+                let mut extra_args = build_simple_job_args(&ctx, options.verbose)?;
+                extra_args.extend(build_resource_args(&ctx, &options.resources));
+
+                work_units.insert(
+                    unit_id.clone(),
+                    WorkUnit {
+                        deps: job_deps,
+                        mem_bytes: mem,
+                        cpus,
+                        priority,
+                        job,
+                        job_id: job_id.clone(),
+                        extra_args,
+                    },
+                );
+                units_left.insert(unit_id.clone());
 
-            completion_map.insert(job_id, unit_id);
+                completion_map.insert(job_id, unit_id);
+            }
         }
-    }
 
-    for dep_id in &completed_job_ids {
-        if !completion_map.contains_key(dep_id) {
-            let unit_id = WorkUnitId::from_job(dep_id);
-            completed.insert(unit_id.clone());
-            completion_map.insert(dep_id.clone(), unit_id);
+        for dep_id in &completed_job_ids {
+            if !completion_map.contains_key(dep_id) {
+                let unit_id = WorkUnitId::from_job(dep_id);
+                completed.insert(unit_id.clone());
+                completion_map.insert(dep_id.clone(), unit_id);
+            }
         }
     }
 
@@ -881,23 +1440,40 @@ pub fn submit_local_batch_run(
         }
     }
 
-    let mut unit_priorities: HashMap<WorkUnitId, usize> = HashMap::new();
+    let mut depth_memo: HashMap<WorkUnitId, usize> = HashMap::new();
+    let mut unit_ranks: HashMap<WorkUnitId, QueueRank> = HashMap::new();
     for (uid, unit) in &work_units {
-        let run_idx = run_affinity
-            .get(&unit.job_id)
-            .copied()
-            .unwrap_or(usize::MAX);
-        unit_priorities.insert(uid.clone(), run_idx);
+        let rank = QueueRank {
+            priority: Reverse(unit.priority),
+            depth: Reverse(critical_path_depth(uid, &dependents, &mut depth_memo)),
+            run_idx: run_affinity
+                .get(&unit.job_id)
+                .copied()
+                .unwrap_or(usize::MAX),
+        };
+        unit_ranks.insert(uid.clone(), rank);
     }
 
-    let mut ready_queue: BinaryHeap<Reverse<(usize, WorkUnitId)>> = BinaryHeap::new();
+    let mut queue_state = LocalQueueState {
+        target_name: target.name().to_string(),
+        batch,
+        units: work_units
+            .iter()
+            .map(|(id, unit)| (id.0.clone(), unit.to_queued()))
+            .collect(),
+        completed: completed.iter().map(|id| id.0.clone()).collect(),
+        running: BTreeMap::new(),
+    };
+    save_queue_state(&queue_file, &queue_state);
+
+    let mut ready_queue: BinaryHeap<Reverse<(QueueRank, WorkUnitId)>> = BinaryHeap::new();
     let mut in_ready_queue: HashSet<WorkUnitId> = HashSet::new();
     for uid in &units_left {
         if let Some(unit) = work_units.get(uid) {
             let deps_met = unit.deps.iter().all(|d| completed.contains(d));
             if deps_met {
-                let prio = *unit_priorities.get(uid).unwrap_or(&usize::MAX);
-                ready_queue.push(Reverse((prio, uid.clone())));
+                let rank = unit_ranks.get(uid).copied().unwrap_or(QueueRank::LAST);
+                ready_queue.push(Reverse((rank, uid.clone())));
                 in_ready_queue.insert(uid.clone());
             }
         }
@@ -905,12 +1481,25 @@ pub fn submit_local_batch_run(
 
     let mut total_work_units = units_left.len();
 
-    let mut resource_tracker = ResourceTracker::new(options.mem_override);
+    let host_ledger = target
+        .config()
+        .local
+        .as_ref()
+        .and_then(|c| c.host_ledger.clone())
+        .map(HostLedger::new);
+    let mut resource_tracker = ResourceTracker::new(options.mem_override, host_ledger);
+    // On a container target the engine caps each plain job that has an
+    // image, so only the other units still need room on the host.
+    let engine_limited = target.config().container.is_some();
+    let pid_table = LocalPidTable::new(client.local_pids_path.clone());
+    let mut unit_pids: HashMap<WorkUnitId, u32> = HashMap::new();
     let mut active_handles: Vec<ActiveHandle> = vec![];
     let mut failed_units: Vec<(WorkUnitId, String)> = vec![];
     let mut failed_ids: HashSet<WorkUnitId> = HashSet::new();
     let mut blocked_units: HashSet<WorkUnitId> = HashSet::new();
     let mut submitted_count: usize = 0;
+    let mut scatter_streams: HashMap<JobId, ScatterStream> = HashMap::new();
+    let mut last_stream_poll = Instant::now();
 
     loop {
         if let Some(ref flag) = options.cancel_flag {
@@ -945,6 +1534,18 @@ pub fn submit_local_batch_run(
         for i in finished_indices.into_iter().rev() {
             let (unit_id, _, handle, started_at) = active_handles.remove(i);
             resource_tracker.release(&unit_id);
+            queue_state.running.remove(&unit_id.0);
+            if let (Some(pid), Some(unit)) = (unit_pids.remove(&unit_id), work_units.get(&unit_id))
+            {
+                if let Err(e) = pid_table.forget(&unit.job_id, pid) {
+                    tracing::debug!("Failed to forget PID {} of unit {}: {}", pid, unit_id, e);
+                }
+            }
+
+            let scatter_stream = work_units
+                .get(&unit_id)
+                .filter(|u| unit_id == WorkUnitId::scatter(&u.job_id))
+                .and_then(|u| scatter_streams.remove(&u.job_id));
 
             match handle.join() {
                 Ok(output_res) => {
@@ -975,6 +1576,16 @@ pub fn submit_local_batch_run(
                             }
 
                             let failed_job_id = work_units.get(&unit_id).map(|u| u.job_id.clone());
+                            if let (Some(_), Some(job_id)) = (&scatter_stream, &failed_job_id) {
+                                blocked_units.extend(
+                                    units_left
+                                        .iter()
+                                        .filter(|id| {
+                                            work_units.get(id).is_some_and(|u| &u.job_id == job_id)
+                                        })
+                                        .cloned(),
+                                );
+                            }
                             if let Some(dependent_ids) = dependents.get(&unit_id) {
                                 for candidate_id in dependent_ids {
                                     if !units_left.contains(candidate_id) {
@@ -1014,6 +1625,7 @@ pub fn submit_local_batch_run(
                         }
                     } else {
                         completed.insert(unit_id.clone());
+                        queue_state.completed.insert(unit_id.0.clone());
                         succeeded_work_units += 1;
 
                         let scatter_expand_job = work_units.get(&unit_id).and_then(|u| {
@@ -1036,29 +1648,28 @@ pub fn submit_local_batch_run(
                                 image_tag,
                                 local_artifacts_path,
                             };
-                            let expanded = expand_scatter_gather(&ctx, options)?;
-                            let num_expanded = expanded.len();
-                            for (new_id, new_unit) in expanded {
-                                for dep in &new_unit.deps {
-                                    dependents
-                                        .entry(dep.clone())
-                                        .or_default()
-                                        .push(new_id.clone());
-                                }
-                                let deps_met = new_unit.deps.iter().all(|d| completed.contains(d));
-                                let run_idx = run_affinity
-                                    .get(&new_unit.job_id)
-                                    .copied()
-                                    .unwrap_or(usize::MAX);
-                                unit_priorities.insert(new_id.clone(), run_idx);
-                                units_left.insert(new_id.clone());
-                                work_units.insert(new_id.clone(), new_unit);
-                                if deps_met && !in_ready_queue.contains(&new_id) {
-                                    ready_queue.push(Reverse((run_idx, new_id.clone())));
-                                    in_ready_queue.insert(new_id);
+                            let streamed = scatter_stream.map_or(0, |stream| stream.branches);
+                            let expanded = expand_scatter_gather(&ctx, options, streamed)?;
+                            total_work_units += expanded.len();
+                            enqueue_units(
+                                expanded,
+                                &mut work_units,
+                                &mut dependents,
+                                &mut unit_ranks,
+                                &mut units_left,
+                                &mut ready_queue,
+                                &mut in_ready_queue,
+                                &completed,
+                                &run_affinity,
+                            );
+                            for (id, unit) in &work_units {
+                                if unit.job_id == scat_job_id {
+                                    queue_state
+                                        .units
+                                        .entry(id.0.clone())
+                                        .or_insert_with(|| unit.to_queued());
                                 }
                             }
-                            total_work_units += num_expanded;
                         }
 
                         if let Some(dep_list) = dependents.get(&unit_id) {
@@ -1074,10 +1685,11 @@ pub fn submit_local_batch_run(
                                     let no_failed =
                                         candidate.deps.iter().all(|d| !failed_ids.contains(d));
                                     if deps_met && no_failed {
-                                        let prio = *unit_priorities
+                                        let rank = unit_ranks
                                             .get(candidate_id)
-                                            .unwrap_or(&usize::MAX);
-                                        ready_queue.push(Reverse((prio, candidate_id.clone())));
+                                            .copied()
+                                            .unwrap_or(QueueRank::LAST);
+                                        ready_queue.push(Reverse((rank, candidate_id.clone())));
                                         in_ready_queue.insert(candidate_id.clone());
                                     }
                                 }
@@ -1137,6 +1749,63 @@ pub fn submit_local_batch_run(
             break;
         }
 
+        if !scatter_streams.is_empty() && last_stream_poll.elapsed() >= STREAM_POLL_INTERVAL {
+            last_stream_poll = Instant::now();
+            let mut streamed_units = Vec::new();
+            for (job_id, stream) in scatter_streams.iter_mut() {
+                let Some(&job) = jobs_in_batch.get(job_id) else {
+                    continue;
+                };
+                let new_items = match read_scatter_stream(target.as_ref(), job_id, stream) {
+                    Ok(n) => n,
+                    Err(e) => {
+                        tracing::warn!("Failed to read streamed work items of '{}': {}", job_id, e);
+                        continue;
+                    }
+                };
+                if new_items == 0 {
+                    continue;
+                }
+                let image_tag = resolve_image_tag(job_id, client);
+                let execution_type =
+                    resolve_local_execution_type(image_tag, options, target.as_ref());
+                let ctx = LocalJobContext {
+                    job_id,
+                    job,
+                    target: target.as_ref(),
+                    client,
+                    execution_type: &execution_type,
+                    image_tag,
+                    local_artifacts_path,
+                };
+                let first = stream.branches;
+                stream.branches += new_items;
+                tracing::debug!(
+                    "Scatter of '{}' streamed branches {}..{}",
+                    job_id,
+                    first,
+                    stream.branches
+                );
+                streamed_units.extend(expand_streamed_branches(
+                    &ctx,
+                    options,
+                    first..stream.branches,
+                )?);
+            }
+            total_work_units += streamed_units.len();
+            enqueue_units(
+                streamed_units,
+                &mut work_units,
+                &mut dependents,
+                &mut unit_ranks,
+                &mut units_left,
+                &mut ready_queue,
+                &mut in_ready_queue,
+                &completed,
+                &run_affinity,
+            );
+        }
+
         for blocked_id in &blocked_units {
             units_left.remove(blocked_id);
             in_ready_queue.remove(blocked_id);
@@ -1144,6 +1813,8 @@ pub fn submit_local_batch_run(
         blocked_units.clear();
 
         if any_finished {
+            save_queue_state(&queue_file, &queue_state);
+
             let failed_count = failed_units.len();
             let running_count = active_handles.len();
             let blocked_count = total_work_units.saturating_sub(
@@ -1164,10 +1835,10 @@ pub fn submit_local_batch_run(
         let mut spawned = 0;
         let slots_available = concurrency.saturating_sub(active_handles.len());
         if slots_available > 0 && !ready_queue.is_empty() {
-            let mut resource_deferred: Vec<Reverse<(usize, WorkUnitId)>> = Vec::new();
+            let mut resource_deferred: Vec<Reverse<(QueueRank, WorkUnitId)>> = Vec::new();
 
             while spawned < slots_available {
-                let Some(Reverse((_prio, uid))) = ready_queue.pop() else {
+                let Some(Reverse((_rank, uid))) = ready_queue.pop() else {
                     break;
                 };
                 in_ready_queue.remove(&uid);
@@ -1181,27 +1852,47 @@ pub fn submit_local_batch_run(
                     None => continue,
                 };
 
-                if !resource_tracker.can_fit(&uid, unit.mem_bytes, unit.cpus) {
+                let admitted = (engine_limited
+                    && uid == WorkUnitId::from_job(&unit.job_id)
+                    && resolve_image_tag(&unit.job_id, client).is_some())
+                    || resource_tracker.try_reserve(&uid, unit.mem_bytes, unit.cpus);
+                if !admitted {
                     tracing::debug!(
                         "Unit '{}' waiting for resources ({} RAM, {} CPUs needed)",
                         uid.short_id(),
                         format_bytes(unit.mem_bytes),
                         unit.cpus
                     );
-                    let prio = *unit_priorities.get(&uid).unwrap_or(&usize::MAX);
-                    resource_deferred.push(Reverse((prio, uid.clone())));
+                    let rank = unit_ranks.get(&uid).copied().unwrap_or(QueueRank::LAST);
+                    resource_deferred.push(Reverse((rank, uid.clone())));
                     in_ready_queue.insert(uid);
                     continue;
                 }
 
                 units_left.remove(&uid);
-                resource_tracker.reserve(uid.clone(), unit.mem_bytes, unit.cpus);
 
+                if uid == WorkUnitId::scatter(&unit.job_id) {
+                    if let Some(stream) = open_scatter_stream(target.as_ref(), &unit.job_id) {
+                        scatter_streams.insert(unit.job_id.clone(), stream);
+                    }
+                }
                 let child = target.spawn_repx_job(repx_binary_path, &unit.extra_args)?;
                 submitted_count += 1;
                 spawned += 1;
 
                 let pid = child.id();
+                queue_state.running.insert(
+                    uid.0.clone(),
+                    Reservation {
+                        pid,
+                        mem_bytes: unit.mem_bytes,
+                        cpus: unit.cpus,
+                    },
+                );
+                if let Err(e) = pid_table.record(&unit.job_id, target.name(), pid) {
+                    tracing::debug!("Failed to record PID {} of unit {}: {}", pid, uid, e);
+                }
+                unit_pids.insert(uid.clone(), pid);
                 send(ClientEvent::JobStarted {
                     job_id: unit.job_id.clone(),
                     pid,
@@ -1231,6 +1922,10 @@ pub fn submit_local_batch_run(
                 active_handles.push((uid, child_handle, handle, Instant::now()));
             }
 
+            if spawned > 0 {
+                save_queue_state(&queue_file, &queue_state);
+            }
+
             for item in resource_deferred {
                 ready_queue.push(item);
             }
@@ -1254,6 +1949,7 @@ pub fn submit_local_batch_run(
     }
 
     if !failed_units.is_empty() {
+        save_queue_state(&queue_file, &queue_state);
         let num_failed = failed_units.len();
         let mut error_msg = format!("{} unit(s) failed:\n", num_failed);
         for (uid, stderr) in &failed_units {
@@ -1262,6 +1958,10 @@ pub fn submit_local_batch_run(
         return Err(ClientError::Config(CoreError::CommandFailed(error_msg)));
     }
 
+    if let Err(e) = queue_file.clear() {
+        tracing::warn!("Failed to remove finished local run queue: {}", e);
+    }
+
     Ok(format!(
         "Successfully executed {} work unit(s) locally.",
         succeeded_work_units
@@ -1315,6 +2015,7 @@ mod tests {
             used_mem_bytes: 0,
             used_cpus: 0,
             in_flight: HashMap::new(),
+            ledger: None,
         };
 
         let u1 = WorkUnitId::from_job(&JobId::from("job1"));
@@ -1345,6 +2046,7 @@ mod tests {
             used_mem_bytes: 0,
             used_cpus: 0,
             in_flight: HashMap::new(),
+            ledger: None,
         };
 
         let u1 = WorkUnitId::from_job(&JobId::from("job1"));
@@ -1379,6 +2081,7 @@ mod tests {
             used_mem_bytes: 0,
             used_cpus: 0,
             in_flight: HashMap::new(),
+            ledger: None,
         };
 
         let big = WorkUnitId::from_job(&JobId::from("big_job"));
@@ -1394,6 +2097,7 @@ mod tests {
             used_mem_bytes: 0,
             used_cpus: 0,
             in_flight: HashMap::new(),
+            ledger: None,
         };
 
         let small = WorkUnitId::from_job(&JobId::from("small_job"));
@@ -1412,6 +2116,7 @@ mod tests {
             used_mem_bytes: 4 * 1024 * 1024 * 1024,
             used_cpus: 2,
             in_flight: HashMap::new(),
+            ledger: None,
         };
 
         let unknown = WorkUnitId::from_job(&JobId::from("unknown"));
@@ -1455,6 +2160,115 @@ mod tests {
         lab
     }
 
+    fn test_job() -> Job {
+        Job {
+            name: None,
+            params: serde_json::Value::Null,
+            path_in_lab: PathBuf::new(),
+            stage_type: repx_core::model::StageType::Simple,
+            executables: HashMap::new(),
+            resource_hints: None,
+            data_mounts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_restore_work_units_from_saved_queue() {
+        let job = test_job();
+        let train = JobId::from("a-train-1.0");
+        let eval = JobId::from("b-eval-1.0");
+        let unit = |deps: Vec<WorkUnitId>, job_id: &JobId| WorkUnit {
+            deps,
+            mem_bytes: 1024,
+            cpus: 1,
+            priority: 3,
+            job: &job,
+            job_id: job_id.clone(),
+            extra_args: vec!["internal-execute".to_string()],
+        };
+        let train_unit = unit(vec![], &train);
+        let eval_unit = unit(vec![WorkUnitId::from_job(&train)], &eval);
+
+        let state = LocalQueueState {
+            target_name: "local".to_string(),
+            batch: [train.clone(), eval.clone()].into_iter().collect(),
+            units: [
+                (train.to_string(), train_unit.to_queued()),
+                (eval.to_string(), eval_unit.to_queued()),
+            ]
+            .into_iter()
+            .collect(),
+            completed: [train.to_string()].into_iter().collect(),
+            running: BTreeMap::new(),
+        };
+
+        let jobs: HashMap<JobId, &Job> = [(train.clone(), &job), (eval.clone(), &job)]
+            .into_iter()
+            .collect();
+        let (units, completed) = restore_work_units(&state, &jobs).expect("restorable");
+        assert_eq!(units.len(), 2);
+        assert!(completed.contains(&WorkUnitId::from_job(&train)));
+        let restored_eval = &units[&WorkUnitId::from_job(&eval)];
+        assert_eq!(restored_eval.deps, vec![WorkUnitId::from_job(&train)]);
+        assert_eq!(restored_eval.priority, 3);
+
+        let only_train: HashMap<JobId, &Job> = [(train, &job)].into_iter().collect();
+        assert!(restore_work_units(&state, &only_train).is_none());
+    }
+
+    #[test]
+    fn test_critical_path_depth_follows_gather_dependents() {
+        let sg = JobId::from("sweep");
+        let mut dependents: HashMap<WorkUnitId, Vec<WorkUnitId>> = HashMap::new();
+        dependents.insert(
+            WorkUnitId::gather(&sg),
+            vec![WorkUnitId::from_job(&JobId::from("plot"))],
+        );
+        dependents.insert(
+            WorkUnitId::from_job(&JobId::from("plot")),
+            vec![WorkUnitId::from_job(&JobId::from("report"))],
+        );
+        let mut memo = HashMap::new();
+
+        assert_eq!(
+            critical_path_depth(&WorkUnitId::scatter(&sg), &dependents, &mut memo),
+            2
+        );
+        assert_eq!(
+            critical_path_depth(
+                &WorkUnitId::from_job(&JobId::from("report")),
+                &dependents,
+                &mut memo
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn test_queue_rank_orders_priority_then_depth() {
+        let rank = |priority, depth, run_idx| QueueRank {
+            priority: Reverse(priority),
+            depth: Reverse(depth),
+            run_idx,
+        };
+        let mut queue: BinaryHeap<Reverse<(QueueRank, WorkUnitId)>> = BinaryHeap::new();
+        for (id, r) in [
+            ("a-short", rank(0, 0, 0)),
+            ("b-long-pole", rank(0, 3, 1)),
+            ("c-urgent", rank(10, 0, 2)),
+            ("d-background", rank(-5, 9, 0)),
+        ] {
+            queue.push(Reverse((r, WorkUnitId(id.to_string()))));
+        }
+
+        let order: Vec<String> =
+            std::iter::from_fn(|| queue.pop().map(|Reverse((_, id))| id.0)).collect();
+        assert_eq!(
+            order,
+            vec!["c-urgent", "b-long-pole", "a-short", "d-background"]
+        );
+    }
+
     #[test]
     fn test_run_affinity_basic_ordering() {
         let lab = make_test_lab(vec![
@@ -1611,4 +2425,34 @@ mod tests {
             .collect();
         assert_eq!(run_indices, vec![0, 0, 0, 1]);
     }
+
+    #[test]
+    fn test_retained_branch_steps_rerun_downstream_of_failure() {
+        let topo_order = vec!["prep".to_string(), "train".to_string(), "eval".to_string()];
+        let step_deps: HashMap<String, Vec<String>> = [
+            ("prep".to_string(), vec![]),
+            ("train".to_string(), vec!["prep".to_string()]),
+            ("eval".to_string(), vec!["train".to_string()]),
+        ]
+        .into_iter()
+        .collect();
+        let succeeded: HashSet<(usize, String)> = [
+            (0, "prep"),
+            (0, "train"),
+            (0, "eval"),
+            (1, "prep"),
+            (1, "eval"),
+        ]
+        .into_iter()
+        .map(|(b, s)| (b, s.to_string()))
+        .collect();
+
+        let retained = retained_branch_steps(&topo_order, &step_deps, 2, &succeeded);
+
+        assert_eq!(retained.len(), 4);
+        assert!(retained.contains(&(0, "eval".to_string())));
+        assert!(retained.contains(&(1, "prep".to_string())));
+        assert!(!retained.contains(&(1, "train".to_string())));
+        assert!(!retained.contains(&(1, "eval".to_string())));
+    }
 }
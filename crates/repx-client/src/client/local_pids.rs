@@ -0,0 +1,136 @@
+use super::host_ledger::process_alive;
+use nix::fcntl::{Flock, FlockArg};
+use repx_core::model::JobId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct LocalPid {
+    pub target_name: String,
+    pub pid: u32,
+}
+
+type PidMap = HashMap<JobId, Vec<LocalPid>>;
+
+pub(crate) struct LocalPidTable {
+    path: PathBuf,
+}
+
+impl LocalPidTable {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn with_locked<T>(&self, f: impl FnOnce(&mut PidMap) -> T) -> io::Result<T> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)?;
+        let mut file = Flock::lock(file, FlockArg::LockExclusive)
+            .map_err(|(_, errno)| io::Error::from(errno))?;
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        let mut pids: PidMap = if content.trim().is_empty() {
+            PidMap::new()
+        } else {
+            serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Discarding unreadable local PID table '{}': {}",
+                    self.path.display(),
+                    e
+                );
+                PidMap::new()
+            })
+        };
+        for entries in pids.values_mut() {
+            entries.retain(|entry| process_alive(entry.pid));
+        }
+        pids.retain(|_, entries| !entries.is_empty());
+
+        let result = f(&mut pids);
+
+        let serialized = serde_json::to_vec(&pids).map_err(io::Error::other)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.set_len(0)?;
+        file.write_all(&serialized)?;
+        Ok(result)
+    }
+
+    pub(crate) fn record(&self, job_id: &JobId, target_name: &str, pid: u32) -> io::Result<()> {
+        self.with_locked(|pids| {
+            pids.entry(job_id.clone()).or_default().push(LocalPid {
+                target_name: target_name.to_string(),
+                pid,
+            });
+        })
+    }
+
+    pub(crate) fn forget(&self, job_id: &JobId, pid: u32) -> io::Result<()> {
+        self.with_locked(|pids| {
+            if let Some(entries) = pids.get_mut(job_id) {
+                entries.retain(|entry| entry.pid != pid);
+                if entries.is_empty() {
+                    pids.remove(job_id);
+                }
+            }
+        })
+    }
+
+    pub(crate) fn get(&self, job_id: &JobId) -> io::Result<Vec<LocalPid>> {
+        self.with_locked(|pids| pids.get(job_id).cloned().unwrap_or_default())
+    }
+
+    pub(crate) fn take(&self, job_id: &JobId) -> io::Result<Vec<LocalPid>> {
+        self.with_locked(|pids| pids.remove(job_id).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_returns_live_pids_and_clears_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let table = LocalPidTable::new(dir.path().join("pids.json"));
+        let job = JobId::from("abc-job");
+        let own = std::process::id();
+
+        table.record(&job, "local", own).unwrap();
+        table.record(&job, "local", i32::MAX as u32).unwrap();
+
+        let taken = LocalPidTable::new(dir.path().join("pids.json"))
+            .take(&job)
+            .unwrap();
+        assert_eq!(
+            taken,
+            vec![LocalPid {
+                target_name: "local".to_string(),
+                pid: own,
+            }]
+        );
+        assert!(table.take(&job).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_forget_drops_finished_unit() {
+        let dir = tempfile::tempdir().unwrap();
+        let table = LocalPidTable::new(dir.path().join("pids.json"));
+        let job = JobId::from("abc-job");
+        let own = std::process::id();
+
+        table.record(&job, "local", own).unwrap();
+        table.forget(&job, own).unwrap();
+        assert!(table.take(&job).unwrap().is_empty());
+    }
+}
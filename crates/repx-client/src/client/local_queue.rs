@@ -0,0 +1,253 @@
+use super::host_ledger::process_alive;
+use repx_core::{fs_utils, model::JobId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct QueuedUnit {
+    pub job_id: JobId,
+    pub deps: Vec<String>,
+    pub mem_bytes: u64,
+    pub cpus: u32,
+    pub priority: i32,
+    pub extra_args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Reservation {
+    pub pid: u32,
+    pub mem_bytes: u64,
+    pub cpus: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalQueueReport {
+    pub waiting: Vec<WaitingUnit>,
+    pub running: Vec<RunningUnit>,
+    pub host_mem_bytes: u64,
+    pub host_cpus: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaitingUnit {
+    pub unit: String,
+    pub unmet_deps: Vec<String>,
+    pub mem_bytes: u64,
+    pub cpus: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunningUnit {
+    pub unit: String,
+    pub pid: u32,
+    pub mem_bytes: u64,
+    pub cpus: u32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct LocalQueueState {
+    pub target_name: String,
+    pub batch: BTreeSet<JobId>,
+    pub units: BTreeMap<String, QueuedUnit>,
+    pub completed: BTreeSet<String>,
+    pub running: BTreeMap<String, Reservation>,
+}
+
+impl LocalQueueState {
+    pub(crate) fn matches(&self, target_name: &str, batch: &BTreeSet<JobId>) -> bool {
+        self.target_name == target_name && &self.batch == batch
+    }
+
+    pub(crate) fn live_reservations(&self) -> Vec<(&str, u32)> {
+        self.running
+            .iter()
+            .filter(|(_, reservation)| process_alive(reservation.pid))
+            .map(|(unit, reservation)| (unit.as_str(), reservation.pid))
+            .collect()
+    }
+
+    pub(crate) fn report(&self, job_id: &JobId) -> Option<LocalQueueReport> {
+        if !self.units.values().any(|unit| &unit.job_id == job_id) {
+            return None;
+        }
+        let waiting = self
+            .units
+            .iter()
+            .filter(|(id, unit)| {
+                &unit.job_id == job_id
+                    && !self.completed.contains(*id)
+                    && !self.running.contains_key(*id)
+            })
+            .map(|(id, unit)| WaitingUnit {
+                unit: id.clone(),
+                unmet_deps: unit
+                    .deps
+                    .iter()
+                    .filter(|dep| !self.completed.contains(*dep))
+                    .cloned()
+                    .collect(),
+                mem_bytes: unit.mem_bytes,
+                cpus: unit.cpus,
+            })
+            .collect();
+        let running = self
+            .running
+            .iter()
+            .filter(|(_, reservation)| process_alive(reservation.pid))
+            .map(|(unit, reservation)| RunningUnit {
+                unit: unit.clone(),
+                pid: reservation.pid,
+                mem_bytes: reservation.mem_bytes,
+                cpus: reservation.cpus,
+            })
+            .collect();
+        Some(LocalQueueReport {
+            waiting,
+            running,
+            ..Default::default()
+        })
+    }
+}
+
+pub(crate) struct LocalQueueFile {
+    path: PathBuf,
+}
+
+impl LocalQueueFile {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub(crate) fn load(&self) -> Option<LocalQueueState> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to read local run queue '{}': {}",
+                    self.path.display(),
+                    e
+                );
+                return None;
+            }
+        };
+        serde_json::from_str(&content)
+            .map_err(|e| {
+                tracing::warn!(
+                    "Discarding unreadable local run queue '{}': {}",
+                    self.path.display(),
+                    e
+                );
+            })
+            .ok()
+    }
+
+    pub(crate) fn save(&self, state: &LocalQueueState) -> io::Result<()> {
+        let serialized = serde_json::to_vec(state).map_err(io::Error::other)?;
+        fs_utils::write_atomic_nosync(&self.path, &serialized)
+    }
+
+    pub(crate) fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_queue_state_roundtrip_and_clear() {
+        let dir = tempdir().expect("tempdir");
+        let file = LocalQueueFile::new(dir.path().join("state").join("local_queue_abc.json"));
+        assert!(file.load().is_none());
+
+        let batch: BTreeSet<JobId> = [JobId::from("a-train-1.0")].into_iter().collect();
+        let mut state = LocalQueueState {
+            target_name: "local".to_string(),
+            batch: batch.clone(),
+            ..Default::default()
+        };
+        state.units.insert(
+            "a-train-1.0".to_string(),
+            QueuedUnit {
+                job_id: JobId::from("a-train-1.0"),
+                deps: vec![],
+                mem_bytes: 1024,
+                cpus: 2,
+                priority: 5,
+                extra_args: vec!["internal-execute".to_string()],
+            },
+        );
+        state.running.insert(
+            "a-train-1.0".to_string(),
+            Reservation {
+                pid: u32::MAX,
+                mem_bytes: 1024,
+                cpus: 2,
+            },
+        );
+        file.save(&state).expect("save");
+
+        let loaded = file.load().expect("load");
+        assert_eq!(loaded, state);
+        assert!(loaded.matches("local", &batch));
+        assert!(!loaded.matches("cluster", &batch));
+        assert!(loaded.live_reservations().is_empty());
+
+        file.clear().expect("clear");
+        assert!(file.load().is_none());
+        file.clear().expect("clearing twice is fine");
+    }
+
+    #[test]
+    fn test_report_lists_waiting_units_and_unmet_deps() {
+        let unit = |job: &str, deps: &[&str]| QueuedUnit {
+            job_id: JobId::from(job),
+            deps: deps.iter().map(|d| d.to_string()).collect(),
+            mem_bytes: 512,
+            cpus: 1,
+            priority: 0,
+            extra_args: vec![],
+        };
+        let mut state = LocalQueueState::default();
+        state.units.insert("a".to_string(), unit("a", &[]));
+        state.units.insert("b".to_string(), unit("b", &["a"]));
+        state.units.insert("c".to_string(), unit("c", &["a", "b"]));
+        state.completed.insert("a".to_string());
+        state.running.insert(
+            "b".to_string(),
+            Reservation {
+                pid: std::process::id(),
+                mem_bytes: 512,
+                cpus: 1,
+            },
+        );
+
+        let report = state.report(&JobId::from("c")).expect("c is queued");
+        assert_eq!(
+            report.waiting,
+            vec![WaitingUnit {
+                unit: "c".to_string(),
+                unmet_deps: vec!["b".to_string()],
+                mem_bytes: 512,
+                cpus: 1,
+            }]
+        );
+        assert_eq!(report.running.len(), 1);
+        assert_eq!(report.running[0].unit, "b");
+        assert!(state
+            .report(&JobId::from("b"))
+            .expect("b is queued")
+            .waiting
+            .is_empty());
+        assert!(state.report(&JobId::from("missing")).is_none());
+    }
+}
@@ -0,0 +1,96 @@
+use super::{Client, ClientEvent, SubmitOptions};
+use crate::error::Result;
+use crate::targets::common::shell_quote;
+use repx_core::{
+    config::SchedulerConfig,
+    constants::{dirs, markers, sge},
+    model::{Job, JobId, SchedulerType},
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+const DEFAULT_PARALLEL_ENVIRONMENT: &str = "smp";
+
+pub(super) fn parallel_environment(config: Option<&SchedulerConfig>) -> &str {
+    config
+        .and_then(|c| c.parallel_environment.as_deref())
+        .unwrap_or(DEFAULT_PARALLEL_ENVIRONMENT)
+}
+
+pub fn submit_sge_batch_run(
+    client: &Client,
+    jobs_to_submit: HashMap<JobId, &Job>,
+    sub_target: &super::SubmissionTarget,
+    options: &SubmitOptions,
+    lab_tar_info: Option<&super::LabTarInfo>,
+    send: impl Fn(ClientEvent),
+) -> Result<String> {
+    super::slurm::submit_via_orchestrator(
+        client,
+        jobs_to_submit,
+        sub_target,
+        options,
+        lab_tar_info,
+        SchedulerType::Sge,
+        send,
+    )
+}
+
+// `-hold_jid` releases a job whether or not its dependencies succeeded, so
+// the script checks their SUCCESS markers itself, as SLURM's `afterok` would.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn generate_sge_invoker_script(
+    job_id: &JobId,
+    prologue: &[String],
+    qsub_args: &[String],
+    in_batch_deps: &[&JobId],
+    base_path: &Path,
+    repx_command_to_wrap: String,
+    lab_tar_info: Option<&super::LabTarInfo>,
+    inputs_json: &str,
+    parameters_json: &str,
+) -> String {
+    let mut s = String::with_capacity(4096);
+    s.push_str("#!/usr/bin/env bash\n");
+    s.push_str(&format!(
+        "#$ -N {}{}\n",
+        sge::JOB_NAME_PREFIX,
+        job_id.as_str()
+    ));
+    s.push_str("#$ -S /bin/bash\n");
+    s.push_str("#$ -V\n");
+    s.push_str("#$ -wd /tmp\n");
+    s.push_str("#$ -o /dev/null\n");
+    s.push_str("#$ -e /dev/null\n");
+    if !qsub_args.is_empty() {
+        s.push_str(&format!("#$ {}\n", qsub_args.join(" ")));
+    }
+
+    s.push_str("\nset -e\n\n");
+    for dep in in_batch_deps {
+        let marker = base_path
+            .join(dirs::OUTPUTS)
+            .join(dep.as_str())
+            .join(dirs::REPX)
+            .join(markers::SUCCESS);
+        s.push_str(&format!(
+            "[ -f {} ] || {{ echo \"[repx] dependency {} did not succeed\" >&2; exit 1; }}\n",
+            shell_quote(&marker.to_string_lossy()),
+            dep.as_str()
+        ));
+    }
+    if !in_batch_deps.is_empty() {
+        s.push('\n');
+    }
+
+    super::slurm::push_invoker_body(
+        &mut s,
+        prologue,
+        "qdel",
+        repx_command_to_wrap,
+        lab_tar_info,
+        inputs_json,
+        parameters_json,
+    );
+    s
+}
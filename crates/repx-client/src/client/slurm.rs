@@ -1,22 +1,21 @@
-use super::{Client, ClientEvent, SubmitOptions};
+use super::{in_flight, Client, ClientEvent, SubmitOptions};
 use crate::error::{ClientError, Result};
 use crate::inputs;
 use crate::resources::{self, SbatchDirectives};
 use crate::targets::common::shell_quote;
 use repx_core::{
-    constants::dirs,
+    config::SchedulerConfig,
     errors::CoreError,
-    model::{Job, JobId, StageType},
+    model::{Job, JobId, SchedulerType, StageType},
     protocol::{self, StreamJob, StreamJobResult, StreamJobType},
 };
 use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader, BufWriter, Write};
-use std::path::Path;
 use std::str::FromStr;
 
 fn generate_repx_invoker_script(
     job_id: &JobId,
-    _job_root_on_target: &Path,
+    prologue: &[String],
     directives: &SbatchDirectives,
     repx_command_to_wrap: String,
     lab_tar_info: Option<&super::LabTarInfo>,
@@ -30,23 +29,39 @@ fn generate_repx_invoker_script(
     s.push_str("#SBATCH --output=/dev/null\n");
     s.push_str("#SBATCH --error=/dev/null\n");
 
-    if let Some(p) = &directives.partition {
-        s.push_str(&format!("#SBATCH --partition={}\n", p));
-    }
-    if let Some(c) = directives.cpus_per_task {
-        s.push_str(&format!("#SBATCH --cpus-per-task={}\n", c));
-    }
-    if let Some(m) = &directives.mem {
-        s.push_str(&format!("#SBATCH --mem={}\n", m.as_str()));
-    }
-    if let Some(t) = &directives.time {
-        s.push_str(&format!("#SBATCH --time={}\n", t.as_str()));
-    }
-    for opt in &directives.sbatch_opts {
+    for opt in directives.to_args() {
         s.push_str(&format!("#SBATCH {}\n", opt));
     }
 
     s.push_str("\nset -e\n\n");
+    push_invoker_body(
+        &mut s,
+        prologue,
+        "scancel",
+        repx_command_to_wrap,
+        lab_tar_info,
+        inputs_json,
+        parameters_json,
+    );
+    Ok(s)
+}
+
+pub(super) fn push_invoker_body(
+    s: &mut String,
+    prologue: &[String],
+    cancel_command: &str,
+    repx_command_to_wrap: String,
+    lab_tar_info: Option<&super::LabTarInfo>,
+    inputs_json: &str,
+    parameters_json: &str,
+) {
+    for line in prologue {
+        s.push_str(line);
+        s.push('\n');
+    }
+    if !prologue.is_empty() {
+        s.push('\n');
+    }
 
     s.push_str("exec 3<<'__REPX_INPUTS_EOF__'\n");
     s.push_str(inputs_json);
@@ -97,7 +112,9 @@ fn generate_repx_invoker_script(
     }
 
     s.push_str("if [ -n \"$REPX_ANCHOR_ID\" ]; then\n");
-    s.push_str("  trap 'scancel \"$REPX_ANCHOR_ID\" 2>/dev/null || true' EXIT\n");
+    s.push_str("  trap '");
+    s.push_str(cancel_command);
+    s.push_str(" \"$REPX_ANCHOR_ID\" 2>/dev/null || true' EXIT\n");
     s.push_str("fi\n\n");
 
     s.push_str(&repx_command_to_wrap);
@@ -109,11 +126,9 @@ fn generate_repx_invoker_script(
     s.push_str("  trap - EXIT\n");
     s.push_str("fi\n");
     s.push_str("exit $_repx_rc\n");
-
-    Ok(s)
 }
 
-fn compute_waves(
+pub(super) fn compute_waves(
     jobs: &HashMap<JobId, &Job>,
     batch_job_ids: &HashSet<JobId>,
 ) -> Result<Vec<Vec<JobId>>> {
@@ -132,7 +147,7 @@ fn compute_waves(
 }
 
 #[allow(clippy::too_many_arguments)]
-fn build_job_command_and_directives(
+pub(super) fn build_job_command_and_directives(
     client: &Client,
     job_id: &JobId,
     job: &Job,
@@ -141,6 +156,7 @@ fn build_job_command_and_directives(
     remote_repx_command: &str,
     options: &SubmitOptions,
     lab_tar_info: Option<&super::LabTarInfo>,
+    scheduler: SchedulerType,
 ) -> Result<(String, SbatchDirectives)> {
     let image_path_opt = client
         .lab
@@ -152,11 +168,16 @@ fn build_job_command_and_directives(
         .and_then(|p| p.file_stem())
         .and_then(|s| s.to_str());
 
+    let batch_config = batch_scheduler_config(target.config(), scheduler);
+    let capabilities = (image_tag.is_some() && options.execution_type.is_none())
+        .then(|| target.runtime_capabilities())
+        .flatten();
     let execution_type = super::resolve_execution_type(
         image_tag,
         options.execution_type.as_deref(),
         target.config(),
-        target.config().slurm.as_ref(),
+        batch_config,
+        capabilities.as_ref(),
     );
     let mut repx_args = format!(
         "--job-id {} --runtime {} {} --base-path {} --host-tools-dir {}",
@@ -188,6 +209,53 @@ fn build_job_command_and_directives(
         }
         repx_core::model::MountPolicy::Isolated => {}
     }
+    if let Some(job) = client.lab.jobs.get(job_id) {
+        for mount in &job.data_mounts {
+            repx_args.push_str(&format!(
+                " --data-mount {}",
+                shell_quote(&mount.to_string())
+            ));
+        }
+    }
+    if let Some(network) = target.config().network {
+        repx_args.push_str(&format!(" --network {}", network));
+    }
+    if let Some(seccomp) = &target.config().seccomp {
+        repx_args.push_str(&format!(" --seccomp {}", shell_quote(&seccomp.to_string())));
+    }
+    for (flag, hook) in [
+        ("--pre-run", &target.config().pre_run),
+        ("--post-run", &target.config().post_run),
+    ] {
+        if let Some(path) = hook {
+            repx_args.push_str(&format!(
+                " {} {}",
+                flag,
+                shell_quote(&path.to_string_lossy())
+            ));
+        }
+    }
+    if let Some(max_bytes) = target.config().image_cache_max_bytes() {
+        repx_args.push_str(&format!(" --image-cache-max-bytes {}", max_bytes));
+    }
+    for (key, value) in &target.config().env {
+        repx_args.push_str(&format!(
+            " --env {}",
+            shell_quote(&format!("{}={}", key, value))
+        ));
+    }
+    for name in &target.config().env_passthrough {
+        repx_args.push_str(&format!(" --env-passthrough {}", shell_quote(name)));
+    }
+    if let Some(store) = &target.config().object_store {
+        repx_args.push_str(&format!(" --object-store {}", shell_quote(&store.uri)));
+        if let Some(endpoint) = &store.endpoint_url {
+            repx_args.push_str(&format!(
+                " --object-store-endpoint {}",
+                shell_quote(endpoint)
+            ));
+        }
+    }
 
     if job.stage_type == StageType::ScatterGather {
         let scatter_exe = job.executables.get("scatter").ok_or_else(|| {
@@ -269,7 +337,12 @@ fn build_job_command_and_directives(
             orchestrator_hints,
             sink_step_hints,
         );
-        let step_opts_str = step_directives.to_shell_string();
+        let step_opts_str = match scheduler {
+            SchedulerType::Sge => step_directives
+                .to_qsub_args(super::sge::parallel_environment(batch_config))
+                .join(" "),
+            _ => step_directives.to_shell_string(),
+        };
 
         let lab_tar_flag = lab_tar_info
             .map(|info| {
@@ -279,10 +352,47 @@ fn build_job_command_and_directives(
                 )
             })
             .unwrap_or_default();
-        let command = format!(
-            "{} internal-scatter-gather {} {}{} --step-sbatch-opts='{}' --scheduler slurm --anchor-id $REPX_ANCHOR_ID",
-            remote_repx_command, repx_args, scatter_gather_args, lab_tar_flag, step_opts_str
+        let mut command = format!(
+            "{} internal-scatter-gather {} {}{} --step-sbatch-opts='{}' --scheduler {} --anchor-id $REPX_ANCHOR_ID",
+            remote_repx_command,
+            repx_args,
+            scatter_gather_args,
+            lab_tar_flag,
+            step_opts_str,
+            scheduler
         );
+        for arg in main_directives
+            .disk_args()
+            .into_iter()
+            .chain(main_directives.min_branch_success_args())
+        {
+            command.push(' ');
+            command.push_str(&arg);
+        }
+        if options.retry_failed {
+            command.push_str(" --retry-failed");
+        }
+        if target.config().auto_prune_steps {
+            command.push_str(" --auto-prune-steps");
+        }
+        for line in &target.config().setup_commands {
+            command.push_str(&format!(" --sbatch-prologue {}", shell_quote(line)));
+        }
+        if let Some(config) = batch_config {
+            if let Some(template) = config
+                .sbatch_template
+                .as_ref()
+                .filter(|_| scheduler == SchedulerType::Slurm)
+            {
+                command.push_str(&format!(
+                    " --sbatch-template {}",
+                    shell_quote(&template.to_string_lossy())
+                ));
+            }
+            for line in &config.sbatch_prologue {
+                command.push_str(&format!(" --sbatch-prologue {}", shell_quote(line)));
+            }
+        }
         Ok((command, main_directives))
     } else {
         let main_exe = job.executables.get("main").ok_or_else(|| {
@@ -293,20 +403,61 @@ fn build_job_command_and_directives(
         })?;
         let executable_path_on_target = target.artifacts_base_path().join(&main_exe.path);
 
-        let repx_args = format!(
+        let mut repx_args = format!(
             "{} --executable-path {}",
             repx_args,
             executable_path_on_target.display()
         );
+        if let Some(interpreter) = &main_exe.interpreter {
+            repx_args.push_str(&format!(" --interpreter {}", shell_quote(interpreter)));
+        }
+        for arg in main_exe.resolve_args(job_id, &job.params)? {
+            repx_args.push_str(&format!(
+                " {}",
+                shell_quote(&format!("--arg-template={}", arg))
+            ));
+        }
+        if target.config().structured_logs {
+            repx_args.push_str(" --structured-logs");
+        }
+        if let Some(secs) = target.config().heartbeat_interval_secs {
+            repx_args.push_str(&format!(" --heartbeat-interval {}", secs));
+        }
+        for rule in &target.config().failure_rules {
+            repx_args.push_str(&format!(
+                " --failure-rule {}",
+                shell_quote(&rule.to_string())
+            ));
+        }
 
         let hints = job.resource_hints.as_ref();
         let directives = resources::resolve_for_job(job_id, target_name, &options.resources, hints);
-        let command = format!("{} internal-execute {}", remote_repx_command, repx_args);
+        let mut command = format!("{} internal-execute {}", remote_repx_command, repx_args);
+        for arg in directives
+            .retry_args()
+            .into_iter()
+            .chain(directives.checkpoint_args())
+            .chain(directives.disk_args())
+        {
+            command.push(' ');
+            command.push_str(&arg);
+        }
         Ok((command, directives))
     }
 }
 
-#[allow(clippy::expect_used)]
+fn batch_scheduler_config(
+    config: &repx_core::config::Target,
+    scheduler: SchedulerType,
+) -> Option<&SchedulerConfig> {
+    match scheduler {
+        SchedulerType::Sge => config.sge.as_ref(),
+        SchedulerType::Flux => config.flux.as_ref(),
+        SchedulerType::AwsBatch => None,
+        _ => config.slurm.as_ref(),
+    }
+}
+
 pub fn submit_slurm_batch_run(
     client: &Client,
     jobs_to_submit: HashMap<JobId, &Job>,
@@ -314,8 +465,37 @@ pub fn submit_slurm_batch_run(
     options: &SubmitOptions,
     lab_tar_info: Option<&super::LabTarInfo>,
     send: impl Fn(ClientEvent),
+) -> Result<String> {
+    submit_via_orchestrator(
+        client,
+        jobs_to_submit,
+        sub_target,
+        options,
+        lab_tar_info,
+        SchedulerType::Slurm,
+        send,
+    )
+}
+
+#[allow(clippy::expect_used)]
+pub(super) fn submit_via_orchestrator(
+    client: &Client,
+    jobs_to_submit: HashMap<JobId, &Job>,
+    sub_target: &super::SubmissionTarget,
+    options: &SubmitOptions,
+    lab_tar_info: Option<&super::LabTarInfo>,
+    scheduler: SchedulerType,
+    send: impl Fn(ClientEvent),
 ) -> Result<String> {
     let target = &sub_target.target;
+    let batch_config = batch_scheduler_config(target.config(), scheduler);
+    let sbatch_prologue: Vec<String> = target
+        .config()
+        .setup_commands
+        .iter()
+        .chain(batch_config.map_or(&[][..], |config| config.sbatch_prologue.as_slice()))
+        .cloned()
+        .collect();
     let target_name = &sub_target.target_name;
     let remote_repx_binary = sub_target.repx_binary_path.to_string_lossy();
     let verbose_flags = options.verbose.as_flag_str();
@@ -343,7 +523,23 @@ pub fn submit_slurm_batch_run(
         concurrency: None,
     });
 
-    let orchestrator_command = format!("{} internal-orchestrate --stream", remote_repx_command);
+    let mut orchestrator_command = format!(
+        "{} internal-orchestrate --stream --scheduler {}",
+        remote_repx_command, scheduler
+    );
+    if let Some(rest) = target
+        .config()
+        .slurm_rest()
+        .filter(|_| scheduler == SchedulerType::Slurm)
+    {
+        orchestrator_command.push_str(&format!(
+            " --rest-url {} --rest-api-version {} --rest-token-env {}",
+            shell_quote(&rest.url),
+            shell_quote(rest.api_version()),
+            shell_quote(rest.token_env())
+        ));
+    }
+    let orchestrator_command = target.config().with_setup_commands(&orchestrator_command);
     let mut child = target.spawn_command("sh", &["-c", &orchestrator_command])?;
 
     let child_stdin = child.stdin.take().ok_or_else(|| {
@@ -360,9 +556,19 @@ pub fn submit_slurm_batch_run(
     let mut writer = BufWriter::new(child_stdin);
     let mut reader = BufReader::new(child_stdout);
 
-    let mut slurm_ids: HashMap<JobId, u32> = HashMap::new();
+    let mut slurm_ids: HashMap<JobId, u64> = HashMap::new();
     let mut submitted_count = 0;
 
+    let in_flight_limit = target.config().max_in_flight().map(|limit| {
+        in_flight::InFlightLimit::new(
+            target.as_ref(),
+            scheduler,
+            limit,
+            options.cancel_flag.as_deref(),
+        )
+    });
+    let mut skipped: HashSet<JobId> = HashSet::new();
+
     let exe_name_for_job = |job: &Job| -> &str {
         if job.stage_type == StageType::ScatterGather {
             "scatter"
@@ -379,122 +585,196 @@ pub fn submit_slurm_batch_run(
             wave.len()
         );
 
-        for job_id in wave {
-            let job = jobs_to_submit.get(job_id).expect("job must exist in batch");
-
-            let inputs_json = inputs::generate_inputs_json_content(
-                &client.lab,
-                &client.lab_source,
-                job,
-                job_id,
-                target.base_path(),
-                &target.artifacts_base_path(),
-                exe_name_for_job(job),
-            )?;
-            let parameters_json = inputs::generate_parameters_json_content(job)?;
-
-            let job_root_on_target = target.base_path().join(dirs::OUTPUTS).join(job_id.as_str());
-            let (repx_command, directives) = build_job_command_and_directives(
-                client,
-                job_id,
-                job,
-                target.as_ref(),
-                target_name,
-                remote_repx_command,
-                options,
-                lab_tar_info,
-            )?;
-
-            let script_content = generate_repx_invoker_script(
-                job_id,
-                &job_root_on_target,
-                &directives,
-                repx_command,
-                lab_tar_info,
-                &inputs_json,
-                &parameters_json,
-            )?;
-
-            let deps: Vec<u32> = job
-                .all_dependencies()
-                .filter(|dep_id| job_ids_in_batch.contains(*dep_id))
-                .filter_map(|dep_id| slurm_ids.get(dep_id))
-                .copied()
-                .collect();
-
-            let stream_job = StreamJob {
-                id: job_id.to_string(),
-                job_type: if job.stage_type == StageType::ScatterGather {
-                    StreamJobType::ScatterGather
-                } else {
-                    StreamJobType::Simple
-                },
-                script: script_content,
-                deps,
+        let mut remaining: &[JobId] = wave;
+        while !remaining.is_empty() {
+            let (slots, queued) = match &in_flight_limit {
+                Some(limit) => {
+                    let (slots, queued) = limit.wait_for_slots(&send)?;
+                    (slots, Some(queued))
+                }
+                None => (remaining.len(), None),
             };
-
-            let line = serde_json::to_string(&stream_job).map_err(ClientError::Json)?;
-            writeln!(writer, "{}", line).map_err(ClientError::Io)?;
-        }
-
-        writeln!(writer, "{}", protocol::WAVE_BOUNDARY).map_err(ClientError::Io)?;
-        writer.flush().map_err(ClientError::Io)?;
-
-        let mut line_buf = String::new();
-        loop {
-            line_buf.clear();
-            let bytes_read = reader.read_line(&mut line_buf).map_err(ClientError::Io)?;
-            if bytes_read == 0 {
-                let status = child.wait().ok();
-                let stderr = child
-                    .stderr
-                    .take()
-                    .map(|mut s| {
-                        let mut buf = String::new();
-                        std::io::Read::read_to_string(&mut s, &mut buf).ok();
-                        buf
-                    })
-                    .unwrap_or_default();
-                return Err(ClientError::Config(CoreError::CommandFailed(format!(
-                    "Orchestrator died unexpectedly (exit={:?}). stderr:\n{}",
-                    status, stderr
-                ))));
-            }
-
-            let trimmed = line_buf.trim();
-            if trimmed == protocol::WAVE_DONE {
-                break;
+            let (chunk, rest) = remaining.split_at(slots.min(remaining.len()));
+            remaining = rest;
+            let mut outcomes = None;
+
+            for job_id in chunk {
+                let job = jobs_to_submit.get(job_id).expect("job must exist in batch");
+                if let Some(blocked_by) = job
+                    .all_dependencies()
+                    .find(|dep_id| skipped.contains(*dep_id))
+                {
+                    tracing::warn!(
+                        "Not submitting {}: its dependency {} was not submitted",
+                        job_id,
+                        blocked_by
+                    );
+                    skipped.insert(job_id.clone());
+                    continue;
+                }
+                let dep_ids: Vec<(&JobId, u64)> = job
+                    .all_dependencies()
+                    .filter(|dep_id| job_ids_in_batch.contains(*dep_id))
+                    .filter_map(|dep_id| slurm_ids.get(dep_id).map(|id| (dep_id, *id)))
+                    .collect();
+                let deps: Vec<u64> = match &queued {
+                    Some(queued) if dep_ids.iter().any(|(_, id)| !queued.contains(id)) => {
+                        if outcomes.is_none() {
+                            outcomes = Some(client.status_cache.outcomes(target.as_ref())?);
+                        }
+                        let finished = outcomes.as_ref().expect("outcomes were just read");
+                        match in_flight::live_dependencies(&dep_ids, queued, finished) {
+                            Some(deps) => deps,
+                            None => {
+                                tracing::warn!(
+                                    "Not submitting {}: a dependency finished without succeeding",
+                                    job_id
+                                );
+                                skipped.insert(job_id.clone());
+                                continue;
+                            }
+                        }
+                    }
+                    _ => dep_ids.iter().map(|(_, id)| *id).collect(),
+                };
+
+                let inputs_json = inputs::generate_inputs_json_content(
+                    &client.lab,
+                    &client.lab_source,
+                    job,
+                    job_id,
+                    target.base_path(),
+                    &target.artifacts_base_path(),
+                    exe_name_for_job(job),
+                )?;
+                let parameters_json = inputs::generate_parameters_json_content(job)?;
+
+                let (repx_command, directives) = build_job_command_and_directives(
+                    client,
+                    job_id,
+                    job,
+                    target.as_ref(),
+                    target_name,
+                    remote_repx_command,
+                    options,
+                    lab_tar_info,
+                    scheduler,
+                )?;
+
+                let script_content = match scheduler {
+                    SchedulerType::Sge => {
+                        let in_batch_deps: Vec<&JobId> = job
+                            .all_dependencies()
+                            .filter(|dep_id| job_ids_in_batch.contains(*dep_id))
+                            .collect();
+                        super::sge::generate_sge_invoker_script(
+                            job_id,
+                            &sbatch_prologue,
+                            &directives
+                                .to_qsub_args(super::sge::parallel_environment(batch_config)),
+                            &in_batch_deps,
+                            target.base_path(),
+                            repx_command,
+                            lab_tar_info,
+                            &inputs_json,
+                            &parameters_json,
+                        )
+                    }
+                    SchedulerType::Flux => super::flux::generate_flux_invoker_script(
+                        job_id,
+                        &sbatch_prologue,
+                        &directives,
+                        repx_command,
+                        lab_tar_info,
+                        &inputs_json,
+                        &parameters_json,
+                    ),
+                    _ => generate_repx_invoker_script(
+                        job_id,
+                        &sbatch_prologue,
+                        &directives,
+                        repx_command,
+                        lab_tar_info,
+                        &inputs_json,
+                        &parameters_json,
+                    )?,
+                };
+
+                let stream_job = StreamJob {
+                    id: job_id.to_string(),
+                    job_type: if job.stage_type == StageType::ScatterGather {
+                        StreamJobType::ScatterGather
+                    } else {
+                        StreamJobType::Simple
+                    },
+                    script: script_content,
+                    deps,
+                };
+
+                let line = serde_json::to_string(&stream_job).map_err(ClientError::Json)?;
+                writeln!(writer, "{}", line).map_err(ClientError::Io)?;
             }
 
-            let result: StreamJobResult = serde_json::from_str(trimmed).map_err(|e| {
-                ClientError::Config(CoreError::CommandFailed(format!(
-                    "Failed to parse orchestrator response '{}': {}",
-                    trimmed, e
-                )))
-            })?;
-
-            let repx_id = JobId::from_str(&result.id).map_err(|_| {
-                ClientError::Config(CoreError::CommandFailed(format!(
-                    "Invalid job ID in orchestrator response: {}",
-                    result.id
-                )))
-            })?;
-
-            slurm_ids.insert(repx_id.clone(), result.slurm_id);
-            super::lock_slurm_map(&client.slurm_map).insert(
-                repx_id.clone(),
-                super::SlurmJobEntry {
-                    target_name: target_name.to_string(),
+            writeln!(writer, "{}", protocol::WAVE_BOUNDARY).map_err(ClientError::Io)?;
+            writer.flush().map_err(ClientError::Io)?;
+
+            let mut line_buf = String::new();
+            loop {
+                line_buf.clear();
+                let bytes_read = reader.read_line(&mut line_buf).map_err(ClientError::Io)?;
+                if bytes_read == 0 {
+                    let status = child.wait().ok();
+                    let stderr = child
+                        .stderr
+                        .take()
+                        .map(|mut s| {
+                            let mut buf = String::new();
+                            std::io::Read::read_to_string(&mut s, &mut buf).ok();
+                            buf
+                        })
+                        .unwrap_or_default();
+                    return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                        "Orchestrator died unexpectedly (exit={:?}). stderr:\n{}",
+                        status, stderr
+                    ))));
+                }
+
+                let trimmed = line_buf.trim();
+                if trimmed == protocol::WAVE_DONE {
+                    break;
+                }
+
+                let result: StreamJobResult = serde_json::from_str(trimmed).map_err(|e| {
+                    ClientError::Config(CoreError::CommandFailed(format!(
+                        "Failed to parse orchestrator response '{}': {}",
+                        trimmed, e
+                    )))
+                })?;
+
+                let repx_id = JobId::from_str(&result.id).map_err(|_| {
+                    ClientError::Config(CoreError::CommandFailed(format!(
+                        "Invalid job ID in orchestrator response: {}",
+                        result.id
+                    )))
+                })?;
+
+                slurm_ids.insert(repx_id.clone(), result.slurm_id);
+                super::lock_slurm_map(&client.slurm_map).insert(
+                    repx_id.clone(),
+                    super::SlurmJobEntry {
+                        target_name: target_name.to_string(),
+                        slurm_id: result.slurm_id,
+                        scheduler,
+                    },
+                );
+                submitted_count += 1;
+                send(ClientEvent::JobSubmitted {
+                    job_id: repx_id,
                     slurm_id: result.slurm_id,
-                },
-            );
-            submitted_count += 1;
-            send(ClientEvent::JobSubmitted {
-                job_id: repx_id,
-                slurm_id: result.slurm_id,
-                total: total_to_submit,
-                current: submitted_count,
-            });
+                    total: total_to_submit,
+                    current: submitted_count,
+                });
+            }
         }
     }
 
@@ -520,8 +800,20 @@ pub fn submit_slurm_batch_run(
     }
 
     client.save_slurm_map()?;
-    Ok(format!(
-        "Successfully submitted {} jobs via SLURM orchestrator.",
-        submitted_count
-    ))
+    let mut message = format!(
+        "Successfully submitted {} jobs via {} orchestrator.",
+        submitted_count,
+        match scheduler {
+            SchedulerType::Sge => "Grid Engine",
+            SchedulerType::Flux => "Flux",
+            _ => "SLURM",
+        }
+    );
+    if !skipped.is_empty() {
+        message.push_str(&format!(
+            " {} jobs were not submitted because a dependency failed.",
+            skipped.len()
+        ));
+    }
+    Ok(message)
 }
@@ -0,0 +1,50 @@
+use repx_core::{fs_utils, model::JobId, store::slurm_failure::SlurmFailure};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct KnownFailure {
+    pub target_name: String,
+    pub failure: SlurmFailure,
+}
+
+pub(crate) struct SlurmFailureTable {
+    path: PathBuf,
+}
+
+impl SlurmFailureTable {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub(crate) fn load(&self) -> BTreeMap<JobId, KnownFailure> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return BTreeMap::new(),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to read SLURM failure table '{}': {}",
+                    self.path.display(),
+                    e
+                );
+                return BTreeMap::new();
+            }
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!(
+                "Discarding unreadable SLURM failure table '{}': {}",
+                self.path.display(),
+                e
+            );
+            BTreeMap::new()
+        })
+    }
+
+    pub(crate) fn save(&self, failures: &BTreeMap<JobId, KnownFailure>) -> io::Result<()> {
+        let serialized = serde_json::to_vec(failures).map_err(io::Error::other)?;
+        fs_utils::write_atomic_nosync(&self.path, &serialized)
+    }
+}
@@ -1,28 +1,36 @@
+use super::slurm_failures::{KnownFailure, SlurmFailureTable};
 use super::Client;
 use crate::error::{ClientError, Result};
-use crate::targets::SlurmState;
+use crate::targets::{SlurmJobInfo, SlurmState, Target};
 use repx_core::{
+    constants::dirs,
     engine,
     model::{JobId, RunId, SchedulerType},
+    store::{heartbeat, slurm_failure::SlurmFailure},
 };
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
+use std::time::Duration;
 
 fn cleanup_slurm_map(
     client: &Client,
     outcomes: &HashMap<JobId, engine::JobStatus>,
-    target_filter: Option<&str>,
+    target_name: &str,
 ) -> Result<()> {
     let mut guard = super::lock_slurm_map(&client.slurm_map);
     let mut changed = false;
     guard.retain(|job_id, entry| {
-        if let Some(target) = target_filter {
-            if entry.target_name != target {
-                return true;
-            }
+        if entry.target_name != target_name {
+            return true;
         }
         let is_done = matches!(
             outcomes.get(job_id),
-            Some(engine::JobStatus::Succeeded { .. }) | Some(engine::JobStatus::Failed { .. })
+            Some(
+                engine::JobStatus::Succeeded { .. }
+                    | engine::JobStatus::Failed { .. }
+                    | engine::JobStatus::TimedOut { .. }
+                    | engine::JobStatus::Cancelled { .. }
+                    | engine::JobStatus::Lost { .. }
+            )
         );
         if is_done {
             changed = true;
@@ -36,31 +44,248 @@ fn cleanup_slurm_map(
     Ok(())
 }
 
+fn write_failure_record(target: &dyn Target, job_id: &JobId, failure: &SlurmFailure) {
+    let repx_dir = target
+        .base_path()
+        .join(dirs::OUTPUTS)
+        .join(job_id.as_str())
+        .join(dirs::REPX);
+    let written = serde_json::to_string_pretty(failure)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            target
+                .write_remote_file(&SlurmFailure::path(&repx_dir), &json)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = written {
+        tracing::warn!("Failed to record SLURM failure of '{}': {}", job_id, e);
+    }
+}
+
+fn query_slurm(
+    client: &Client,
+    target: &dyn Target,
+    statuses: &mut HashMap<JobId, engine::JobStatus>,
+    queue: Option<HashMap<JobId, SlurmJobInfo>>,
+) -> Result<HashMap<JobId, SlurmJobInfo>> {
+    let table = SlurmFailureTable::new(client.slurm_failures_path.clone());
+    let mut known = table.load();
+    let mut changed = false;
+
+    let tracked: HashMap<JobId, u64> = {
+        let guard = super::lock_slurm_map(&client.slurm_map);
+        guard
+            .iter()
+            .filter(|(_, entry)| {
+                entry.target_name == target.name() && entry.scheduler == SchedulerType::Slurm
+            })
+            .map(|(job_id, entry)| (job_id.clone(), entry.slurm_id))
+            .collect()
+    };
+
+    let to_query: Vec<(&JobId, u64)> = tracked
+        .iter()
+        .filter(|(job_id, slurm_id)| {
+            queue
+                .as_ref()
+                .is_none_or(|queued| !queued.contains_key(*job_id))
+                && known
+                    .get(*job_id)
+                    .is_none_or(|entry| entry.failure.slurm_id != **slurm_id)
+                && !client
+                    .status_cache
+                    .is_accounted(target.name(), job_id, **slurm_id)
+                && !matches!(
+                    statuses.get(*job_id),
+                    Some(engine::JobStatus::Succeeded { .. })
+                )
+        })
+        .map(|(job_id, slurm_id)| (job_id, *slurm_id))
+        .collect();
+    let slurm_ids: Vec<u64> = to_query.iter().map(|(_, slurm_id)| *slurm_id).collect();
+    let (queued_jobs, mut records) = match queue {
+        Some(queued) if slurm_ids.is_empty() => (queued, HashMap::new()),
+        Some(queued) => {
+            let records = target.sacct(&slurm_ids).unwrap_or_else(|e| {
+                tracing::debug!("sacct failed on '{}': {}", target.name(), e);
+                HashMap::new()
+            });
+            (queued, records)
+        }
+        None => target.slurm_snapshot(&slurm_ids)?,
+    };
+
+    known.retain(|job_id, entry| {
+        let stale = entry.target_name == target.name()
+            && (queued_jobs.contains_key(job_id)
+                || matches!(
+                    statuses.get(job_id),
+                    Some(engine::JobStatus::Succeeded { .. })
+                )
+                || tracked
+                    .get(job_id)
+                    .is_some_and(|slurm_id| *slurm_id != entry.failure.slurm_id));
+        changed |= stale;
+        !stale
+    });
+
+    for (job_id, slurm_id) in to_query {
+        if queued_jobs.contains_key(job_id) {
+            continue;
+        }
+        let Some(failure) = records.remove(&slurm_id) else {
+            continue;
+        };
+        if !failure.is_failure() {
+            if failure.state == "COMPLETED" {
+                client
+                    .status_cache
+                    .set_accounted(target.name(), job_id.clone(), slurm_id);
+            }
+            continue;
+        }
+        write_failure_record(target, job_id, &failure);
+        known.insert(
+            job_id.clone(),
+            KnownFailure {
+                target_name: target.name().to_string(),
+                failure,
+            },
+        );
+        changed = true;
+    }
+
+    for (job_id, entry) in &known {
+        if entry.target_name != target.name() {
+            continue;
+        }
+        match statuses.get_mut(job_id) {
+            Some(engine::JobStatus::Failed { reason, .. }) => {
+                *reason = Some(entry.failure.summary());
+            }
+            Some(_) => {}
+            None => {
+                statuses.insert(job_id.clone(), entry.failure.status(target.name()));
+            }
+        }
+    }
+
+    if changed {
+        if let Err(e) = table.save(&known) {
+            tracing::warn!("Failed to save SLURM failure table: {}", e);
+        }
+    }
+    Ok(queued_jobs)
+}
+
+fn apply_heartbeats(
+    target: &dyn Target,
+    agent_ages: Option<HashMap<JobId, Duration>>,
+    statuses: &mut HashMap<JobId, engine::JobStatus>,
+) {
+    let ages = match agent_ages.map_or_else(|| target.check_heartbeats(), Ok) {
+        Ok(ages) => ages,
+        Err(e) => {
+            tracing::debug!("Failed to read heartbeats on '{}': {}", target.name(), e);
+            return;
+        }
+    };
+    let interval = target.config().heartbeat_interval();
+    for (job_id, age) in ages {
+        statuses
+            .entry(job_id)
+            .or_insert_with(|| match heartbeat::liveness(age, interval) {
+                heartbeat::Liveness::Alive => engine::JobStatus::Running,
+                heartbeat::Liveness::Lost => engine::JobStatus::Lost {
+                    location: target.name().to_string(),
+                },
+            });
+    }
+}
+
+fn apply_queued(
+    queued_jobs: HashMap<JobId, SlurmJobInfo>,
+    statuses: &mut HashMap<JobId, engine::JobStatus>,
+) {
+    for (job_id, info) in queued_jobs {
+        statuses
+            .entry(job_id)
+            .or_insert(if info.state == SlurmState::Running {
+                engine::JobStatus::Running
+            } else {
+                engine::JobStatus::Queued
+            });
+    }
+}
+
+fn is_outcome(status: &engine::JobStatus) -> bool {
+    matches!(
+        status,
+        engine::JobStatus::Succeeded { .. }
+            | engine::JobStatus::Failed { .. }
+            | engine::JobStatus::TimedOut { .. }
+            | engine::JobStatus::Cancelled { .. }
+    )
+}
+
+pub(crate) fn target_statuses(
+    client: &Client,
+    target: &dyn Target,
+    query: impl Fn(SchedulerType) -> bool,
+) -> Result<HashMap<JobId, engine::JobStatus>> {
+    let mut job_statuses = HashMap::new();
+    let view = client.agents.view(target);
+    match &view {
+        Some(view) => job_statuses.extend(view.outcomes.clone()),
+        None => job_statuses.extend(client.status_cache.outcomes(target)?),
+    }
+    let agent_queue = |scheduler| view.as_ref().and_then(|view| view.queue(scheduler));
+
+    if target.config().slurm.is_some() && query(SchedulerType::Slurm) {
+        let queued_jobs = query_slurm(
+            client,
+            target,
+            &mut job_statuses,
+            agent_queue(SchedulerType::Slurm),
+        )?;
+        apply_queued(queued_jobs, &mut job_statuses);
+    }
+    if target.config().sge.is_some() && query(SchedulerType::Sge) {
+        let queued_jobs = agent_queue(SchedulerType::Sge).map_or_else(|| target.qstat(), Ok)?;
+        apply_queued(queued_jobs, &mut job_statuses);
+    }
+    if target.config().flux.is_some() && query(SchedulerType::Flux) {
+        let queued_jobs =
+            agent_queue(SchedulerType::Flux).map_or_else(|| target.flux_jobs(), Ok)?;
+        apply_queued(queued_jobs, &mut job_statuses);
+    }
+
+    cleanup_slurm_map(client, &job_statuses, target.name())?;
+
+    super::aws_batch::apply_aws_batch(client, target, &mut job_statuses);
+    apply_heartbeats(target, view.map(|view| view.heartbeats), &mut job_statuses);
+
+    Ok(job_statuses)
+}
+
 pub fn get_statuses(
     client: &Client,
 ) -> Result<(
     BTreeMap<RunId, engine::JobStatus>,
     HashMap<JobId, engine::JobStatus>,
 )> {
-    let mut job_statuses = HashMap::new();
+    let mut job_statuses: HashMap<JobId, engine::JobStatus> = HashMap::new();
     for target in client.targets.values() {
-        let outcomes = target.check_outcome_markers()?;
-        job_statuses.extend(outcomes);
-    }
-
-    cleanup_slurm_map(client, &job_statuses, None)?;
-
-    for target in client.targets.values() {
-        if target.config().slurm.is_some() {
-            let queued_jobs = target.squeue()?;
-            for (job_id, squeue_info) in queued_jobs {
-                job_statuses
-                    .entry(job_id)
-                    .or_insert(if squeue_info.state == SlurmState::Running {
-                        engine::JobStatus::Running
-                    } else {
-                        engine::JobStatus::Queued
-                    });
+        for (job_id, status) in target_statuses(client, target.as_ref(), |_| true)? {
+            match job_statuses.entry(job_id) {
+                Entry::Vacant(entry) => {
+                    entry.insert(status);
+                }
+                Entry::Occupied(mut entry) => {
+                    if is_outcome(&status) && !is_outcome(entry.get()) {
+                        entry.insert(status);
+                    }
+                }
             }
         }
     }
@@ -76,43 +301,23 @@ pub fn get_statuses_for_active_target(
     active_target_name: &str,
     active_scheduler: Option<SchedulerType>,
 ) -> Result<HashMap<JobId, engine::JobStatus>> {
-    let mut job_statuses = HashMap::new();
     let target = client
         .targets
         .get(active_target_name)
         .ok_or_else(|| ClientError::TargetNotFound(active_target_name.to_string()))?;
 
-    let outcomes = target.check_outcome_markers()?;
-    job_statuses.extend(outcomes);
-
-    cleanup_slurm_map(client, &job_statuses, Some(active_target_name))?;
-
-    let has_tracked_slurm_jobs = {
+    let has_tracked_jobs = |scheduler: SchedulerType| {
         let guard = super::lock_slurm_map(&client.slurm_map);
         guard
             .values()
-            .any(|entry| entry.target_name == active_target_name)
+            .any(|entry| entry.target_name == active_target_name && entry.scheduler == scheduler)
     };
-
-    let should_query_slurm = target.config().slurm.is_some()
-        && match active_scheduler {
-            Some(SchedulerType::Slurm) => true,
-            Some(_) => false,
-            None => has_tracked_slurm_jobs,
-        };
-
-    if should_query_slurm {
-        let queued_jobs = target.squeue()?;
-        for (job_id, squeue_info) in queued_jobs {
-            job_statuses
-                .entry(job_id)
-                .or_insert(if squeue_info.state == SlurmState::Running {
-                    engine::JobStatus::Running
-                } else {
-                    engine::JobStatus::Queued
-                });
-        }
-    }
-
-    Ok(job_statuses)
+    target_statuses(
+        client,
+        target.as_ref(),
+        |scheduler| match active_scheduler {
+            Some(active) => active == scheduler,
+            None => has_tracked_jobs(scheduler),
+        },
+    )
 }
@@ -0,0 +1,131 @@
+use crate::error::Result;
+use crate::targets::Target;
+use repx_core::{engine::JobStatus, model::JobId, store::completion_log};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Default)]
+pub(crate) struct StatusCache {
+    targets: Mutex<HashMap<String, TargetStatusCache>>,
+}
+
+#[derive(Default)]
+struct TargetStatusCache {
+    log_offset: u64,
+    outcomes: HashMap<JobId, JobStatus>,
+    accounted: HashMap<JobId, u64>,
+}
+
+impl StatusCache {
+    pub(crate) fn outcomes(&self, target: &dyn Target) -> Result<HashMap<JobId, JobStatus>> {
+        let log_path = completion_log::completions_path(target.base_path());
+        let Some(size) = target.remote_file_size(&log_path)? else {
+            self.forget_log(target.name());
+            return target.check_outcome_markers();
+        };
+
+        let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+        let cached = targets.entry(target.name().to_string()).or_default();
+        if size < cached.log_offset {
+            tracing::debug!(
+                "Completion log on '{}' shrank, reading it again",
+                target.name()
+            );
+            cached.log_offset = 0;
+            cached.outcomes.clear();
+        }
+        if size > cached.log_offset {
+            let chunk = target.read_remote_file_from(&log_path, cached.log_offset)?;
+            let consumed =
+                completion_log::apply_completions(&chunk, target.name(), &mut cached.outcomes);
+            cached.log_offset += consumed as u64;
+        }
+        if cached.outcomes.is_empty() {
+            drop(targets);
+            return target.check_outcome_markers();
+        }
+        Ok(cached.outcomes.clone())
+    }
+
+    fn forget_log(&self, target_name: &str) {
+        let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = targets.get_mut(target_name) {
+            cached.log_offset = 0;
+            cached.outcomes.clear();
+        }
+    }
+
+    pub(crate) fn is_accounted(&self, target_name: &str, job_id: &JobId, slurm_id: u64) -> bool {
+        let targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+        targets
+            .get(target_name)
+            .and_then(|cached| cached.accounted.get(job_id))
+            .is_some_and(|accounted| *accounted == slurm_id)
+    }
+
+    pub(crate) fn set_accounted(&self, target_name: &str, job_id: JobId, slurm_id: u64) {
+        let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+        targets
+            .entry(target_name.to_string())
+            .or_default()
+            .accounted
+            .insert(job_id, slurm_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::targets::{local::LocalTarget, TargetInfo};
+    use repx_core::config::Target as TargetConfig;
+
+    fn local_target(base: &std::path::Path) -> LocalTarget {
+        let config: TargetConfig =
+            toml::from_str(&format!("base_path = \"{}\"\n", base.display())).expect("config");
+        LocalTarget {
+            name: "local".to_string(),
+            config,
+            local_tools_path: base.join("bin"),
+            capabilities: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_reads_only_new_completions() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let target = local_target(dir.path());
+        let cache = StatusCache::default();
+        let first = JobId::from("abc123-first-1.0");
+        let second = JobId::from("def456-second-1.0");
+
+        completion_log::append_completion(target.base_path(), &first, true, None).expect("append");
+        assert_eq!(cache.outcomes(&target).expect("outcomes").len(), 1);
+
+        completion_log::append_completion(target.base_path(), &second, false, None)
+            .expect("append");
+        completion_log::append_cleared(target.base_path(), &first).expect("append");
+        let outcomes = cache.outcomes(&target).expect("outcomes");
+        assert!(!outcomes.contains_key(&first));
+        assert!(matches!(
+            outcomes.get(&second),
+            Some(JobStatus::Failed { .. })
+        ));
+
+        std::fs::remove_file(completion_log::completions_path(target.base_path()))
+            .expect("remove log");
+        completion_log::append_completion(target.base_path(), &first, true, None).expect("append");
+        let outcomes = cache.outcomes(&target).expect("outcomes");
+        assert_eq!(outcomes.len(), 1);
+        assert!(outcomes.contains_key(&first));
+    }
+
+    #[test]
+    fn test_accounted_follows_scheduler_id() {
+        let cache = StatusCache::default();
+        let job = JobId::from("abc123-job-1.0");
+        cache.set_accounted("cluster", job.clone(), 41);
+        assert!(cache.is_accounted("cluster", &job, 41));
+        assert!(!cache.is_accounted("cluster", &job, 42));
+        assert!(!cache.is_accounted("other", &job, 41));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::client::Client;
+use crate::error::{ClientError, Result};
+use crate::targets::Target;
+use repx_core::{engine, engine::JobStatus, model::JobId};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatusCounts {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub running: usize,
+    pub queued: usize,
+    pub pending: usize,
+    pub blocked: usize,
+}
+
+impl StatusCounts {
+    pub fn add(&mut self, status: &JobStatus) {
+        match status {
+            JobStatus::Succeeded { .. } => self.succeeded += 1,
+            JobStatus::Failed { .. }
+            | JobStatus::TimedOut { .. }
+            | JobStatus::Cancelled { .. }
+            | JobStatus::Lost { .. } => self.failed += 1,
+            JobStatus::Running => self.running += 1,
+            JobStatus::Queued => self.queued += 1,
+            JobStatus::Pending => self.pending += 1,
+            JobStatus::Blocked { .. } => self.blocked += 1,
+        }
+    }
+
+    pub fn merge(&mut self, other: &StatusCounts) {
+        self.succeeded += other.succeeded;
+        self.failed += other.failed;
+        self.running += other.running;
+        self.queued += other.queued;
+        self.pending += other.pending;
+        self.blocked += other.blocked;
+    }
+
+    pub fn total(&self) -> usize {
+        self.succeeded + self.failed + self.running + self.queued + self.pending + self.blocked
+    }
+}
+
+impl<'a> FromIterator<&'a JobStatus> for StatusCounts {
+    fn from_iter<I: IntoIterator<Item = &'a JobStatus>>(iter: I) -> Self {
+        let mut counts = StatusCounts::default();
+        for status in iter {
+            counts.add(status);
+        }
+        counts
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TargetSnapshot {
+    pub jobs: HashMap<JobId, JobStatus>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LabStatus {
+    pub label: String,
+    pub targets: BTreeMap<String, TargetSnapshot>,
+    pub combined: HashMap<JobId, JobStatus>,
+}
+
+impl LabStatus {
+    pub fn collect(label: impl Into<String>, client: &Client) -> Self {
+        Self::collect_from(label.into(), client, client.targets.iter())
+    }
+
+    pub fn collect_target(label: impl Into<String>, client: &Client, name: &str) -> Result<Self> {
+        let target = client
+            .targets
+            .get_key_value(name)
+            .ok_or_else(|| ClientError::TargetNotFound(name.to_string()))?;
+        Ok(Self::collect_from(
+            label.into(),
+            client,
+            std::iter::once(target),
+        ))
+    }
+
+    fn collect_from<'a>(
+        label: String,
+        client: &Client,
+        selected: impl Iterator<Item = (&'a String, &'a Arc<dyn Target>)>,
+    ) -> Self {
+        let mut targets = BTreeMap::new();
+        let mut observed: HashMap<JobId, JobStatus> = HashMap::new();
+
+        for (name, target) in selected {
+            let (raw, error) =
+                match crate::client::status::target_statuses(client, target.as_ref(), |_| true) {
+                    Ok(raw) => (raw, None),
+                    Err(e) => (HashMap::new(), Some(e.to_string())),
+                };
+
+            for (job_id, status) in &raw {
+                match observed.get(job_id) {
+                    Some(existing) if precedence(existing) >= precedence(status) => {}
+                    _ => {
+                        observed.insert(job_id.clone(), status.clone());
+                    }
+                }
+            }
+
+            targets.insert(
+                name.clone(),
+                TargetSnapshot {
+                    jobs: engine::determine_job_statuses(&client.lab, raw),
+                    error,
+                },
+            );
+        }
+
+        Self {
+            label,
+            targets,
+            combined: engine::determine_job_statuses(&client.lab, observed),
+        }
+    }
+
+    pub fn counts(&self) -> StatusCounts {
+        self.combined.values().collect()
+    }
+}
+
+fn precedence(status: &JobStatus) -> u8 {
+    match status {
+        JobStatus::Succeeded { .. } => 4,
+        JobStatus::Running => 3,
+        JobStatus::Queued => 2,
+        JobStatus::Failed { .. }
+        | JobStatus::TimedOut { .. }
+        | JobStatus::Cancelled { .. }
+        | JobStatus::Lost { .. } => 1,
+        JobStatus::Pending | JobStatus::Blocked { .. } => 0,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FederatedStatus {
+    pub labs: Vec<LabStatus>,
+}
+
+impl FederatedStatus {
+    pub fn collect<'a>(clients: impl IntoIterator<Item = (String, &'a Client)>) -> Self {
+        Self {
+            labs: clients
+                .into_iter()
+                .map(|(label, client)| LabStatus::collect(label, client))
+                .collect(),
+        }
+    }
+
+    pub fn counts_by_target(&self) -> BTreeMap<String, StatusCounts> {
+        let mut by_target: BTreeMap<String, StatusCounts> = BTreeMap::new();
+        for lab in &self.labs {
+            for (name, snapshot) in &lab.targets {
+                by_target
+                    .entry(name.clone())
+                    .or_default()
+                    .merge(&snapshot.jobs.values().collect());
+            }
+        }
+        by_target
+    }
+
+    pub fn counts(&self) -> StatusCounts {
+        let mut counts = StatusCounts::default();
+        for lab in &self.labs {
+            counts.merge(&lab.counts());
+        }
+        counts
+    }
+
+    pub fn locate(&self, job_id: &JobId) -> Vec<(&str, &str, &JobStatus)> {
+        let mut found = Vec::new();
+        for lab in &self.labs {
+            for (name, snapshot) in &lab.targets {
+                if let Some(status) = snapshot.jobs.get(job_id) {
+                    found.push((lab.label.as_str(), name.as_str(), status));
+                }
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_counts_from_statuses() {
+        let statuses = [
+            JobStatus::Succeeded {
+                location: "a".into(),
+            },
+            JobStatus::Failed {
+                location: "b".into(),
+                class: None,
+                reason: None,
+            },
+            JobStatus::Running,
+            JobStatus::Pending,
+        ];
+        let counts: StatusCounts = statuses.iter().collect();
+        assert_eq!(counts.succeeded, 1);
+        assert_eq!(counts.failed, 1);
+        assert_eq!(counts.running, 1);
+        assert_eq!(counts.total(), 4);
+    }
+
+    #[test]
+    fn test_success_on_any_target_wins() {
+        let succeeded = JobStatus::Succeeded {
+            location: "cluster".into(),
+        };
+        let failed = JobStatus::Failed {
+            location: "local".into(),
+            class: None,
+            reason: None,
+        };
+        assert!(precedence(&succeeded) > precedence(&failed));
+        assert!(precedence(&JobStatus::Running) > precedence(&failed));
+        assert!(precedence(&failed) > precedence(&JobStatus::Pending));
+    }
+}
@@ -1,9 +1,13 @@
+pub mod agent;
 pub mod client;
 pub mod error;
+pub mod federation;
 pub mod inputs;
+pub mod object_store;
 pub mod orchestration;
 pub mod resources;
+pub mod slurm_rest;
 pub mod submission;
 pub(crate) mod tar_extract;
 pub mod targets;
-pub use client::{Client, ClientEvent, SubmitOptions, WorkUnitPhase};
+pub use client::{CancelReport, Client, ClientEvent, SubmitOptions, WorkUnitPhase};
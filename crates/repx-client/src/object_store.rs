@@ -0,0 +1,283 @@
+use crate::error::{ClientError, Result};
+use repx_core::{config::ObjectStoreConfig, errors::CoreError};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+const GCS_ENDPOINT_VAR: &str = "CLOUDSDK_API_ENDPOINT_OVERRIDES_STORAGE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Provider {
+    S3,
+    Gcs,
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectStore {
+    provider: Provider,
+    config: ObjectStoreConfig,
+}
+
+impl ObjectStore {
+    pub fn new(config: ObjectStoreConfig) -> Result<Self> {
+        let provider = if config.uri.starts_with("s3://") {
+            Provider::S3
+        } else if config.uri.starts_with("gs://") {
+            Provider::Gcs
+        } else {
+            return Err(ClientError::Config(CoreError::InvalidConfig {
+                detail: format!(
+                    "Object store URI '{}' must start with 's3://' or 'gs://'",
+                    config.uri
+                ),
+            }));
+        };
+        Ok(Self { provider, config })
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.config.uri
+    }
+
+    pub fn key_uri(&self, key: &Path) -> String {
+        let prefix = self.config.uri.trim_end_matches('/');
+        let key = key.to_string_lossy();
+        let key = key.trim_matches('/');
+        if key.is_empty() {
+            prefix.to_string()
+        } else {
+            format!("{}/{}", prefix, key)
+        }
+    }
+
+    pub fn upload_dir(&self, local: &Path, key: &Path) -> Result<()> {
+        let local = local.to_string_lossy();
+        let remote = self.key_uri(key);
+        match self.provider {
+            Provider::S3 => self.run(&[
+                "sync",
+                "--only-show-errors",
+                "--no-follow-symlinks",
+                &local,
+                &remote,
+            ]),
+            Provider::Gcs => {
+                self.run(&["rsync", "--recursive", "--ignore-symlinks", &local, &remote])
+            }
+        }
+        .map(drop)
+    }
+
+    pub fn download_dir(&self, key: &Path, local: &Path) -> Result<()> {
+        let remote = self.key_uri(key);
+        let local_str = local.to_string_lossy();
+        match self.provider {
+            Provider::S3 => self.run(&["sync", "--only-show-errors", &remote, &local_str]),
+            Provider::Gcs => self.run(&["rsync", "--recursive", &remote, &local_str]),
+        }?;
+        if !local.is_dir() {
+            return Err(ClientError::Config(CoreError::OutputNotReady {
+                path: local.to_path_buf(),
+            }));
+        }
+        Ok(())
+    }
+
+    pub fn size(&self, key: &Path) -> Result<Option<u64>> {
+        let remote = self.key_uri(key);
+        let output = match self.provider {
+            Provider::S3 => self.output(&["ls", &remote])?,
+            Provider::Gcs => self.output(&["ls", "-l", &remote])?,
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // `aws s3 ls` exits 1 without a word when nothing matches.
+            if stderr.trim().is_empty() || stderr.contains("matched no objects") {
+                return Ok(None);
+            }
+            return Err(self.failed("ls", stderr.trim().to_string()));
+        }
+        Ok(match self.provider {
+            Provider::S3 => {
+                let name = remote.rsplit('/').next().unwrap_or_default();
+                parse_s3_ls_size(&stdout, name)
+            }
+            Provider::Gcs => parse_gcloud_ls_size(&stdout, &remote),
+        })
+    }
+
+    pub fn read(&self, key: &Path) -> Result<Option<Vec<u8>>> {
+        if self.size(key)?.is_none() {
+            return Ok(None);
+        }
+        let remote = self.key_uri(key);
+        let contents = match self.provider {
+            Provider::S3 => self.run(&["cp", "--only-show-errors", &remote, "-"])?,
+            Provider::Gcs => self.run(&["cat", &remote])?,
+        };
+        Ok(Some(contents))
+    }
+
+    fn command_line(&self, args: &[&str]) -> (&'static str, Vec<String>) {
+        let mut line = Vec::new();
+        let program = match self.provider {
+            Provider::S3 => {
+                if let Some(endpoint) = &self.config.endpoint_url {
+                    line.extend(["--endpoint-url".to_string(), endpoint.clone()]);
+                }
+                if let Some(profile) = &self.config.profile {
+                    line.extend(["--profile".to_string(), profile.clone()]);
+                }
+                if let Some(region) = &self.config.region {
+                    line.extend(["--region".to_string(), region.clone()]);
+                }
+                line.push("s3".to_string());
+                "aws"
+            }
+            Provider::Gcs => {
+                if let Some(profile) = &self.config.profile {
+                    line.extend(["--configuration".to_string(), profile.clone()]);
+                }
+                line.push("storage".to_string());
+                "gcloud"
+            }
+        };
+        line.extend(args.iter().map(|arg| arg.to_string()));
+        (program, line)
+    }
+
+    fn output(&self, args: &[&str]) -> Result<Output> {
+        let (program, line) = self.command_line(args);
+        let mut command = Command::new(program);
+        command.args(&line).stdin(Stdio::null());
+        if let (Provider::Gcs, Some(endpoint)) = (self.provider, &self.config.endpoint_url) {
+            command.env(GCS_ENDPOINT_VAR, endpoint);
+        }
+        repx_core::logging::log_and_print_command(&command);
+        command
+            .output()
+            .map_err(|e| self.failed(args.first().copied().unwrap_or_default(), e.to_string()))
+    }
+
+    fn run(&self, args: &[&str]) -> Result<Vec<u8>> {
+        let output = self.output(args)?;
+        if !output.status.success() {
+            return Err(self.failed(
+                args.first().copied().unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(output.stdout)
+    }
+
+    fn failed(&self, subcommand: &str, detail: String) -> ClientError {
+        let tool = match self.provider {
+            Provider::S3 => "aws s3",
+            Provider::Gcs => "gcloud storage",
+        };
+        ClientError::Config(CoreError::CommandFailed(format!(
+            "{} {} failed: {}",
+            tool, subcommand, detail
+        )))
+    }
+}
+
+fn parse_s3_ls_size(output: &str, name: &str) -> Option<u64> {
+    output.lines().find_map(|line| {
+        let mut rest = line.trim_start();
+        let mut fields = Vec::with_capacity(3);
+        for _ in 0..3 {
+            let (field, tail) = rest.split_once(char::is_whitespace)?;
+            fields.push(field);
+            rest = tail.trim_start();
+        }
+        if rest == name {
+            fields[2].parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+fn parse_gcloud_ls_size(output: &str, uri: &str) -> Option<u64> {
+    output.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let size = fields.next()?.parse().ok()?;
+        (fields.last()? == uri).then_some(size)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(uri: &str) -> ObjectStore {
+        ObjectStore::new(ObjectStoreConfig {
+            uri: uri.to_string(),
+            endpoint_url: Some("http://minio:9000".to_string()),
+            profile: Some("lab".to_string()),
+            region: None,
+        })
+        .expect("valid store")
+    }
+
+    #[test]
+    fn test_rejects_unknown_scheme() {
+        let config = ObjectStoreConfig {
+            uri: "/mnt/bucket".to_string(),
+            endpoint_url: None,
+            profile: None,
+            region: None,
+        };
+        assert!(ObjectStore::new(config).is_err());
+    }
+
+    #[test]
+    fn test_key_uri_and_command_line() {
+        let s3 = store("s3://bucket/runs/");
+        assert_eq!(
+            s3.key_uri(Path::new("outputs/job/repx/stdout.log")),
+            "s3://bucket/runs/outputs/job/repx/stdout.log"
+        );
+        assert_eq!(s3.key_uri(Path::new("")), "s3://bucket/runs");
+        let (program, line) = s3.command_line(&["ls", "s3://bucket/runs"]);
+        assert_eq!(program, "aws");
+        assert_eq!(
+            line,
+            [
+                "--endpoint-url",
+                "http://minio:9000",
+                "--profile",
+                "lab",
+                "s3",
+                "ls",
+                "s3://bucket/runs"
+            ]
+        );
+
+        let (program, line) = store("gs://bucket").command_line(&["cat", "gs://bucket/x"]);
+        assert_eq!(program, "gcloud");
+        assert_eq!(
+            line,
+            ["--configuration", "lab", "storage", "cat", "gs://bucket/x"]
+        );
+    }
+
+    #[test]
+    fn test_parse_ls_sizes() {
+        let s3 = "                           PRE repx/\n\
+                  2024-05-01 10:00:00       1234 stdout.log\n\
+                  2024-05-01 10:00:00         17 stdout.log.1\n";
+        assert_eq!(parse_s3_ls_size(s3, "stdout.log"), Some(1234));
+        assert_eq!(parse_s3_ls_size(s3, "stdout.log.1"), Some(17));
+        assert_eq!(parse_s3_ls_size(s3, "stderr.log"), None);
+
+        let gcs = "      1234  2024-05-01T10:00:00Z  gs://bucket/outputs/job/repx/stdout.log\n\
+                   TOTAL: 1 objects, 1234 bytes (1.21kiB)\n";
+        assert_eq!(
+            parse_gcloud_ls_size(gcs, "gs://bucket/outputs/job/repx/stdout.log"),
+            Some(1234)
+        );
+        assert_eq!(parse_gcloud_ls_size(gcs, "gs://bucket/other"), None);
+    }
+}
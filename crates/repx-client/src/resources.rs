@@ -1,6 +1,6 @@
 use repx_core::{
     config::{ResourceRule, Resources},
-    model::{JobId, Memory, ResourceHints, SlurmTime},
+    model::{BranchQuota, JobId, Memory, ResourceHints, SlurmTime},
 };
 use wildmatch::WildMatch;
 
@@ -10,7 +10,19 @@ pub struct SbatchDirectives {
     pub cpus_per_task: Option<u32>,
     pub mem: Option<Memory>,
     pub time: Option<SlurmTime>,
+    pub qos: Option<String>,
+    pub account: Option<String>,
+    pub constraint: Option<String>,
+    pub gres: Option<String>,
+    pub exclusive: bool,
     pub sbatch_opts: Vec<String>,
+    pub max_retries: Option<u32>,
+    pub retry_backoff: Option<u64>,
+    pub retry_exit_codes: Vec<i32>,
+    pub checkpoint_interval: Option<u64>,
+    pub disk: Option<Memory>,
+    pub priority: Option<i32>,
+    pub min_branch_success: Option<BranchQuota>,
 }
 
 impl SbatchDirectives {
@@ -28,6 +40,19 @@ impl SbatchDirectives {
         if let Some(t) = &self.time {
             opts.push(format!("--time={}", t.as_str()));
         }
+        for (flag, value) in [
+            ("qos", &self.qos),
+            ("account", &self.account),
+            ("constraint", &self.constraint),
+            ("gres", &self.gres),
+        ] {
+            if let Some(value) = value {
+                opts.push(format!("--{}={}", flag, value));
+            }
+        }
+        if self.exclusive {
+            opts.push("--exclusive".to_string());
+        }
         opts.extend(self.sbatch_opts.clone());
         opts
     }
@@ -35,6 +60,104 @@ impl SbatchDirectives {
     pub fn to_shell_string(&self) -> String {
         self.to_args().join(" ")
     }
+
+    // Grid Engine multiplies `h_vmem` by the slot count, so memory is given per slot.
+    pub fn to_qsub_args(&self, parallel_environment: &str) -> Vec<String> {
+        let mut opts = Vec::new();
+        if let Some(queue) = &self.partition {
+            opts.extend(["-q".to_string(), queue.clone()]);
+        }
+        let slots = self.cpus_per_task.filter(|&c| c > 1);
+        if let Some(slots) = slots {
+            opts.extend([
+                "-pe".to_string(),
+                parallel_environment.to_string(),
+                slots.to_string(),
+            ]);
+        }
+        if let Some(mem) = &self.mem {
+            let per_slot = match mem.to_bytes() {
+                Some(bytes) => format!(
+                    "{}M",
+                    bytes
+                        .div_ceil(1024 * 1024)
+                        .div_ceil(u64::from(slots.unwrap_or(1)))
+                ),
+                None => mem.as_str().to_string(),
+            };
+            opts.extend(["-l".to_string(), format!("h_vmem={}", per_slot)]);
+        }
+        if let Some(time) = &self.time {
+            let limit = time
+                .to_seconds()
+                .map_or_else(|| time.as_str().to_string(), |secs| secs.to_string());
+            opts.extend(["-l".to_string(), format!("h_rt={}", limit)]);
+        }
+        if let Some(account) = &self.account {
+            opts.extend(["-A".to_string(), account.clone()]);
+        }
+        opts
+    }
+
+    pub fn to_flux_args(&self) -> Vec<String> {
+        let mut opts = Vec::new();
+        if let Some(queue) = &self.partition {
+            opts.push(format!("--queue={}", queue));
+        }
+        if let Some(cores) = self.cpus_per_task {
+            opts.push(format!("--cores-per-slot={}", cores));
+        }
+        if let Some(time) = &self.time {
+            let limit = time
+                .to_seconds()
+                .map_or_else(|| time.as_str().to_string(), |secs| format!("{}s", secs));
+            opts.push(format!("--time-limit={}", limit));
+        }
+        if let Some(account) = &self.account {
+            opts.push(format!("--bank={}", account));
+        }
+        if self.exclusive {
+            opts.push("--exclusive".to_string());
+        }
+        opts
+    }
+
+    pub fn retry_args(&self) -> Vec<String> {
+        let Some(retries) = self.max_retries.filter(|&n| n > 0) else {
+            return Vec::new();
+        };
+        let mut args = vec!["--max-retries".to_string(), retries.to_string()];
+        if let Some(backoff) = self.retry_backoff {
+            args.push("--retry-backoff".to_string());
+            args.push(backoff.to_string());
+        }
+        for code in &self.retry_exit_codes {
+            args.push("--retry-exit-code".to_string());
+            args.push(code.to_string());
+        }
+        args
+    }
+
+    pub fn checkpoint_args(&self) -> Vec<String> {
+        match self.checkpoint_interval.filter(|&secs| secs > 0) {
+            Some(secs) => vec!["--checkpoint-interval".to_string(), secs.to_string()],
+            None => Vec::new(),
+        }
+    }
+
+    pub fn disk_args(&self) -> Vec<String> {
+        match self.disk.as_ref().and_then(Memory::to_bytes) {
+            Some(bytes) if bytes > 0 => vec!["--min-disk".to_string(), bytes.to_string()],
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn min_branch_success_args(&self) -> Vec<String> {
+        match self.min_branch_success {
+            Some(quota) => vec!["--min-branch-success".to_string(), quota.to_string()],
+            None => Vec::new(),
+        }
+    }
 }
 
 fn merge_hints(current: &mut SbatchDirectives, hints: &ResourceHints) {
@@ -53,6 +176,21 @@ fn merge_hints(current: &mut SbatchDirectives, hints: &ResourceHints) {
     if !hints.sbatch_opts.is_empty() {
         current.sbatch_opts = hints.sbatch_opts.clone();
     }
+    if let Some(val) = &hints.disk {
+        current.disk = Some(val.clone());
+    }
+    if let Some(val) = hints.priority {
+        current.priority = Some(val);
+    }
+}
+
+fn rule_matches(rule: &ResourceRule, job_id: &JobId, target_name: &str) -> bool {
+    let target_matches = rule.target.as_deref().is_none_or(|t| t == target_name);
+    let glob_matches = rule
+        .job_id_glob
+        .as_ref()
+        .is_none_or(|glob| WildMatch::new(glob).matches(job_id.as_str()));
+    target_matches && glob_matches
 }
 
 pub fn resolve_for_job(
@@ -67,7 +205,19 @@ pub fn resolve_for_job(
             cpus_per_task: r.defaults.cpus_per_task,
             mem: r.defaults.mem.clone(),
             time: r.defaults.time.clone(),
+            qos: r.defaults.qos.clone(),
+            account: r.defaults.account.clone(),
+            constraint: r.defaults.constraint.clone(),
+            gres: r.defaults.gres.clone(),
+            exclusive: r.defaults.exclusive.unwrap_or(false),
             sbatch_opts: r.defaults.sbatch_opts.clone(),
+            max_retries: r.defaults.max_retries,
+            retry_backoff: r.defaults.retry_backoff,
+            retry_exit_codes: r.defaults.retry_exit_codes.clone(),
+            checkpoint_interval: r.defaults.checkpoint_interval,
+            disk: r.defaults.disk.clone(),
+            priority: r.defaults.priority,
+            min_branch_success: r.defaults.min_branch_success,
         },
         None => SbatchDirectives::default(),
     };
@@ -78,15 +228,12 @@ pub fn resolve_for_job(
     }
 
     if let Some(r) = resources {
-        for rule in &r.rules {
-            let target_matches = rule.target.as_deref().is_none_or(|t| t == target_name);
-            let glob_matches = rule
-                .job_id_glob
-                .as_ref()
-                .is_none_or(|glob| WildMatch::new(glob).matches(job_id.as_str()));
-            if target_matches && glob_matches {
-                merge_rule(&mut current, rule);
-            }
+        for rule in r
+            .rules
+            .iter()
+            .filter(|rule| rule_matches(rule, job_id, target_name))
+        {
+            merge_rule(&mut current, rule);
         }
     }
 
@@ -123,14 +270,11 @@ pub fn resolve_worker_resources(
     }
 
     if let Some(r) = resources {
-        let final_rule = r.rules.iter().rev().find(|rule| {
-            let target_matches = rule.target.as_deref().is_none_or(|t| t == target_name);
-            let glob_matches = rule
-                .job_id_glob
-                .as_ref()
-                .is_none_or(|glob| WildMatch::new(glob).matches(orchestrator_job_id.as_str()));
-            target_matches && glob_matches
-        });
+        let final_rule = r
+            .rules
+            .iter()
+            .rev()
+            .find(|rule| rule_matches(rule, orchestrator_job_id, target_name));
         if let Some(rule) = final_rule {
             if let Some(worker_rule) = &rule.worker_resources {
                 tracing::debug!(
@@ -150,6 +294,49 @@ pub fn resolve_worker_resources(
     worker_directives
 }
 
+pub fn with_job_overrides(
+    resources: Option<Resources>,
+    job_ids: &[JobId],
+    target_name: &str,
+    overrides: &ResourceRule,
+) -> Resources {
+    let mut resources = resources.unwrap_or_default();
+    let mut added = Vec::with_capacity(job_ids.len());
+    for job_id in job_ids {
+        let mut worker = resources
+            .rules
+            .iter()
+            .rev()
+            .find(|rule| rule_matches(rule, job_id, target_name))
+            .and_then(|rule| rule.worker_resources.as_deref().cloned())
+            .unwrap_or_default();
+        overlay_rule(&mut worker, overrides);
+
+        let mut rule = overrides.clone();
+        rule.job_id_glob = Some(job_id.to_string());
+        rule.target = Some(target_name.to_string());
+        rule.worker_resources = Some(Box::new(worker));
+        added.push(rule);
+    }
+    resources.rules.extend(added);
+    resources
+}
+
+fn overlay_rule(base: &mut ResourceRule, overrides: &ResourceRule) {
+    if overrides.partition.is_some() {
+        base.partition.clone_from(&overrides.partition);
+    }
+    if overrides.cpus_per_task.is_some() {
+        base.cpus_per_task = overrides.cpus_per_task;
+    }
+    if overrides.mem.is_some() {
+        base.mem.clone_from(&overrides.mem);
+    }
+    if overrides.time.is_some() {
+        base.time.clone_from(&overrides.time);
+    }
+}
+
 fn merge_rule(current: &mut SbatchDirectives, rule: &ResourceRule) {
     if let Some(val) = &rule.partition {
         current.partition = Some(val.clone());
@@ -163,9 +350,45 @@ fn merge_rule(current: &mut SbatchDirectives, rule: &ResourceRule) {
     if let Some(val) = &rule.time {
         current.time = Some(val.clone());
     }
+    if let Some(val) = &rule.qos {
+        current.qos = Some(val.clone());
+    }
+    if let Some(val) = &rule.account {
+        current.account = Some(val.clone());
+    }
+    if let Some(val) = &rule.constraint {
+        current.constraint = Some(val.clone());
+    }
+    if let Some(val) = &rule.gres {
+        current.gres = Some(val.clone());
+    }
+    if let Some(val) = rule.exclusive {
+        current.exclusive = val;
+    }
     if !rule.sbatch_opts.is_empty() {
         current.sbatch_opts = rule.sbatch_opts.clone();
     }
+    if let Some(val) = rule.max_retries {
+        current.max_retries = Some(val);
+    }
+    if let Some(val) = rule.retry_backoff {
+        current.retry_backoff = Some(val);
+    }
+    if !rule.retry_exit_codes.is_empty() {
+        current.retry_exit_codes = rule.retry_exit_codes.clone();
+    }
+    if let Some(val) = rule.checkpoint_interval {
+        current.checkpoint_interval = Some(val);
+    }
+    if let Some(val) = &rule.disk {
+        current.disk = Some(val.clone());
+    }
+    if let Some(val) = rule.priority {
+        current.priority = Some(val);
+    }
+    if let Some(val) = rule.min_branch_success {
+        current.min_branch_success = Some(val);
+    }
 }
 
 #[cfg(test)]
@@ -286,6 +509,8 @@ cpus-per-task = 4
             cpus: Some(8),
             time: Some(SlurmTime::from("04:00:00")),
             partition: None,
+            disk: None,
+            priority: None,
             sbatch_opts: vec![],
         };
         let directives = resolve_for_job(&job_id, "any-cluster", &Some(res), Some(&hints));
@@ -304,6 +529,8 @@ cpus-per-task = 4
             cpus: Some(2),
             time: None,
             partition: None,
+            disk: None,
+            priority: None,
             sbatch_opts: vec![],
         };
         let directives = resolve_for_job(&job_id, "any-cluster", &Some(res), Some(&hints));
@@ -320,6 +547,8 @@ cpus-per-task = 4
             cpus: Some(1),
             time: None,
             partition: None,
+            disk: None,
+            priority: None,
             sbatch_opts: vec![],
         };
         let worker_hints = ResourceHints {
@@ -327,6 +556,8 @@ cpus-per-task = 4
             cpus: Some(4),
             time: Some(SlurmTime::from("08:00:00")),
             partition: None,
+            disk: None,
+            priority: None,
             sbatch_opts: vec![],
         };
         let directives = resolve_worker_resources(
@@ -340,4 +571,292 @@ cpus-per-task = 4
         assert_eq!(directives.cpus_per_task, Some(4));
         assert_eq!(directives.time, Some(SlurmTime::from("08:00:00")));
     }
+
+    #[test]
+    fn test_retry_settings_resolve_to_runner_args() {
+        let res: Resources = toml::from_str(
+            r#"
+[defaults]
+max_retries = 1
+
+[[rules]]
+job_id_glob = "*-flaky-*"
+max_retries = 3
+retry_backoff = 10
+retry_exit_codes = [75, 137]
+"#,
+        )
+        .expect("retry config must parse");
+
+        let plain = resolve_for_job(&JobId::from("plain-job"), "local", &Some(res.clone()), None);
+        assert_eq!(plain.retry_args(), vec!["--max-retries", "1"]);
+        assert!(!plain.to_args().iter().any(|a| a.contains("retr")));
+
+        let flaky = resolve_for_job(&JobId::from("a-flaky-job"), "local", &Some(res), None);
+        assert_eq!(
+            flaky.retry_args(),
+            vec![
+                "--max-retries",
+                "3",
+                "--retry-backoff",
+                "10",
+                "--retry-exit-code",
+                "75",
+                "--retry-exit-code",
+                "137"
+            ]
+        );
+    }
+    #[test]
+    fn test_checkpoint_interval_resolves_per_job() {
+        let res: Resources = toml::from_str(
+            r#"
+[[rules]]
+job_id_glob = "*-train-*"
+checkpoint_interval = 1800
+"#,
+        )
+        .expect("checkpoint config must parse");
+
+        let plain = resolve_for_job(&JobId::from("plain-job"), "local", &Some(res.clone()), None);
+        assert!(plain.checkpoint_args().is_empty());
+
+        let train = resolve_for_job(&JobId::from("a-train-job"), "local", &Some(res), None);
+        assert_eq!(
+            train.checkpoint_args(),
+            vec!["--checkpoint-interval", "1800"]
+        );
+    }
+
+    #[test]
+    fn test_min_branch_success_resolves_per_job() {
+        let res: Resources = toml::from_str(
+            r#"
+[defaults]
+min_branch_success = 1.0
+
+[[rules]]
+job_id_glob = "*monte-carlo*"
+min_branch_success = "95%"
+
+[[rules]]
+job_id_glob = "*smoke*"
+min_branch_success = 3
+"#,
+        )
+        .expect("branch quota config must parse");
+
+        let plain = resolve_for_job(&JobId::from("plain-job"), "local", &Some(res.clone()), None);
+        assert_eq!(
+            plain.min_branch_success_args(),
+            vec!["--min-branch-success", "1.0"]
+        );
+
+        let mc = resolve_for_job(
+            &JobId::from("a-monte-carlo-job"),
+            "local",
+            &Some(res.clone()),
+            None,
+        );
+        assert_eq!(mc.min_branch_success, Some(BranchQuota::Fraction(0.95)));
+
+        let smoke = resolve_for_job(&JobId::from("a-smoke-job"), "local", &Some(res), None);
+        assert_eq!(
+            smoke.min_branch_success_args(),
+            vec!["--min-branch-success", "3"]
+        );
+        assert!(SbatchDirectives::default()
+            .min_branch_success_args()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_priority_hint_overridden_by_rule() {
+        let res: Resources = toml::from_str(
+            r#"
+[[rules]]
+job_id_glob = "*-train-*"
+priority = 10
+"#,
+        )
+        .expect("priority config must parse");
+        let hints = ResourceHints {
+            priority: Some(-1),
+            ..Default::default()
+        };
+
+        let eval = resolve_for_job(
+            &JobId::from("a-eval-job"),
+            "local",
+            &Some(res.clone()),
+            Some(&hints),
+        );
+        assert_eq!(eval.priority, Some(-1));
+        let train = resolve_for_job(
+            &JobId::from("a-train-job"),
+            "local",
+            &Some(res),
+            Some(&hints),
+        );
+        assert_eq!(train.priority, Some(10));
+        assert!(!train.to_args().iter().any(|arg| arg.contains("10")));
+    }
+
+    #[test]
+    fn test_cluster_fields_resolve_to_sbatch_args() {
+        let res: Resources = toml::from_str(
+            r#"
+[defaults]
+account = "proj42"
+qos = "normal"
+
+[[rules]]
+job_id_glob = "*-gpu-*"
+qos = "gpu-long"
+constraint = "a100|h100"
+gres = "gpu:2"
+exclusive = true
+"#,
+        )
+        .expect("cluster config must parse");
+
+        let cpu = resolve_for_job(&JobId::from("a-cpu-job"), "hpc", &Some(res.clone()), None);
+        assert_eq!(cpu.to_args(), vec!["--qos=normal", "--account=proj42"]);
+
+        let gpu = resolve_for_job(&JobId::from("a-gpu-job"), "hpc", &Some(res.clone()), None);
+        assert_eq!(
+            gpu.to_args(),
+            vec![
+                "--qos=gpu-long",
+                "--account=proj42",
+                "--constraint=a100|h100",
+                "--gres=gpu:2",
+                "--exclusive",
+            ]
+        );
+
+        let workers =
+            resolve_worker_resources(&JobId::from("a-gpu-job"), "hpc", &Some(res), None, None);
+        assert_eq!(workers.gres.as_deref(), Some("gpu:2"));
+        assert!(workers.exclusive);
+    }
+
+    #[test]
+    fn test_directives_resolve_to_qsub_args() {
+        let directives = SbatchDirectives {
+            partition: Some("long.q".to_string()),
+            cpus_per_task: Some(4),
+            mem: Some(Memory::from("8G".to_string())),
+            time: Some(SlurmTime::from("02:00:00".to_string())),
+            account: Some("proj42".to_string()),
+            gres: Some("gpu:1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            directives.to_qsub_args("mpi"),
+            vec![
+                "-q",
+                "long.q",
+                "-pe",
+                "mpi",
+                "4",
+                "-l",
+                "h_vmem=2048M",
+                "-l",
+                "h_rt=7200",
+                "-A",
+                "proj42",
+            ]
+        );
+
+        let single = SbatchDirectives {
+            cpus_per_task: Some(1),
+            mem: Some(Memory::from("512M".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(single.to_qsub_args("smp"), vec!["-l", "h_vmem=512M"]);
+    }
+
+    #[test]
+    fn test_directives_resolve_to_flux_args() {
+        let directives = SbatchDirectives {
+            partition: Some("pbatch".to_string()),
+            cpus_per_task: Some(8),
+            mem: Some(Memory::from("8G".to_string())),
+            time: Some(SlurmTime::from("1-00:30:00".to_string())),
+            account: Some("proj42".to_string()),
+            exclusive: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            directives.to_flux_args(),
+            vec![
+                "--queue=pbatch",
+                "--cores-per-slot=8",
+                "--time-limit=88200s",
+                "--bank=proj42",
+                "--exclusive",
+            ]
+        );
+        assert!(SbatchDirectives::default().to_flux_args().is_empty());
+    }
+
+    #[test]
+    fn test_disk_hint_overridden_by_rule() {
+        let res: Resources = toml::from_str(
+            r#"
+[[rules]]
+job_id_glob = "*-big-*"
+disk = "2G"
+"#,
+        )
+        .expect("disk config must parse");
+        let hints = ResourceHints {
+            disk: Some(Memory::from("10M")),
+            ..Default::default()
+        };
+
+        let small = resolve_for_job(
+            &JobId::from("a-small-job"),
+            "local",
+            &Some(res.clone()),
+            Some(&hints),
+        );
+        assert_eq!(small.disk_args(), vec!["--min-disk", "10485760"]);
+
+        let big = resolve_for_job(&JobId::from("a-big-job"), "local", &Some(res), Some(&hints));
+        assert_eq!(big.disk_args(), vec!["--min-disk", "2147483648"]);
+    }
+
+    #[test]
+    fn test_job_overrides_win_for_job_and_workers() {
+        let overrides = ResourceRule {
+            mem: Some(Memory::from("64G")),
+            time: Some(SlurmTime::from("12:00:00")),
+            ..Default::default()
+        };
+        let scatter = JobId::from("my-scatter-job");
+        let res = with_job_overrides(
+            Some(get_test_resources()),
+            std::slice::from_ref(&scatter),
+            "cluster",
+            &overrides,
+        );
+
+        let main = resolve_for_job(&scatter, "cluster", &Some(res.clone()), None);
+        assert_eq!(main.mem, Some(Memory::from("64G")));
+        assert_eq!(main.time, Some(SlurmTime::from("12:00:00")));
+
+        let hints = ResourceHints {
+            mem: Some(Memory::from("2G")),
+            ..Default::default()
+        };
+        let worker =
+            resolve_worker_resources(&scatter, "cluster", &Some(res.clone()), None, Some(&hints));
+        assert_eq!(worker.mem, Some(Memory::from("64G")));
+        assert_eq!(worker.cpus_per_task, Some(4));
+
+        let other = resolve_for_job(&JobId::from("my-heavy-job"), "cluster", &Some(res), None);
+        assert_eq!(other.mem, Some(Memory::from("128G")));
+    }
 }
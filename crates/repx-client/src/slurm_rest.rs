@@ -0,0 +1,493 @@
+use crate::error::{ClientError, Result};
+use crate::targets::{common::shell_quote, SlurmJobInfo, SlurmState};
+use repx_core::{
+    config::SlurmRestConfig,
+    errors::CoreError,
+    model::{JobId, Memory, SlurmTime},
+    store::slurm_failure::SlurmFailure,
+};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+const TOKEN_LIFESPAN_SECS: u64 = 3600;
+
+const ACTIVE_STATES: &[&str] = &[
+    "PENDING",
+    "RUNNING",
+    "SUSPENDED",
+    "COMPLETING",
+    "CONFIGURING",
+    "REQUEUED",
+    "RESIZING",
+    "SIGNALING",
+    "STAGE_OUT",
+];
+
+fn rest_error(message: String) -> ClientError {
+    ClientError::Config(CoreError::CommandFailed(message))
+}
+
+fn auth_prelude(rest: &SlurmRestConfig) -> String {
+    format!(
+        "tok=$(printenv {}); [ -n \"$tok\" ] || tok=$(scontrol token lifespan={} | sed -n 's/^SLURM_JWT=//p'); usr=$(id -un)",
+        shell_quote(rest.token_env()),
+        TOKEN_LIFESPAN_SECS
+    )
+}
+
+// The credentials reach curl on stdin so they never show up in a process
+// listing.
+fn curl_call(rest: &SlurmRestConfig, method: &str, path: &str, with_body: bool) -> String {
+    let url = format!(
+        "{}/slurm/{}/{}",
+        rest.url.trim_end_matches('/'),
+        rest.api_version(),
+        path.trim_start_matches('/')
+    );
+    let body = if with_body {
+        "echo 'header = \"Content-Type: application/json\"'; cat; "
+    } else {
+        ""
+    };
+    format!(
+        "{{ printf 'header = \"X-SLURM-USER-NAME: %s\"\\nheader = \"X-SLURM-USER-TOKEN: %s\"\\n' \"$usr\" \"$tok\"; {}}} | curl -sS -K - -X {} {}",
+        body,
+        method,
+        shell_quote(&url)
+    )
+}
+
+pub fn request_command(
+    rest: &SlurmRestConfig,
+    method: &str,
+    path: &str,
+    with_body: bool,
+) -> String {
+    format!(
+        "{}; {}",
+        auth_prelude(rest),
+        curl_call(rest, method, path, with_body)
+    )
+}
+
+pub fn body_config(request: &Value) -> String {
+    let json = request.to_string();
+    format!(
+        "data-binary = \"{}\"\n",
+        json.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+pub fn cancel_command(rest: &SlurmRestConfig, slurm_ids: &[u64]) -> String {
+    let mut command = auth_prelude(rest);
+    for id in slurm_ids {
+        command.push_str("; ");
+        command.push_str(&curl_call(rest, "DELETE", &format!("job/{}", id), false));
+    }
+    command
+}
+
+pub fn response_errors(output: &str) -> Result<Vec<String>> {
+    let mut errors = Vec::new();
+    for response in serde_json::Deserializer::from_str(output).into_iter::<Value>() {
+        let response = response.map_err(|e| {
+            rest_error(format!(
+                "Unreadable slurmrestd response ({}): {}",
+                e,
+                output.trim()
+            ))
+        })?;
+        let reported = response.get("errors").and_then(Value::as_array);
+        for error in reported.into_iter().flatten() {
+            let text = ["description", "error"]
+                .iter()
+                .filter_map(|key| error.get(*key).and_then(Value::as_str))
+                .find(|text| !text.is_empty())
+                .unwrap_or("unknown error");
+            errors.push(text.to_string());
+        }
+    }
+    Ok(errors)
+}
+
+pub fn parse_submit_response(output: &str) -> Result<u64> {
+    let errors = response_errors(output)?;
+    if !errors.is_empty() {
+        return Err(rest_error(format!(
+            "slurmrestd rejected the job: {}",
+            errors.join("; ")
+        )));
+    }
+    let response: Value = serde_json::from_str(output)?;
+    response
+        .get("job_id")
+        .or_else(|| {
+            response
+                .get("result")
+                .and_then(|result| result.get("job_id"))
+        })
+        .and_then(number)
+        .ok_or_else(|| {
+            rest_error(format!(
+                "No job ID in slurmrestd response: {}",
+                output.trim()
+            ))
+        })
+}
+
+fn number(value: &Value) -> Option<u64> {
+    if let Some(n) = value.as_u64() {
+        return Some(n);
+    }
+    if value.get("set").and_then(Value::as_bool) == Some(false) {
+        return None;
+    }
+    value.get("number").and_then(Value::as_u64)
+}
+
+fn job_state(job: &Value) -> Option<&str> {
+    match job.get("job_state")? {
+        Value::String(state) => Some(state),
+        Value::Array(states) => states.first()?.as_str(),
+        _ => None,
+    }
+}
+
+fn exit_code(job: &Value) -> String {
+    let Some(code) = job.get("exit_code") else {
+        return String::new();
+    };
+    if let Some(raw) = code.as_u64() {
+        // Older versions send the wait status.
+        return format!("{}:{}", raw >> 8, raw & 0x7f);
+    }
+    let return_code = code.get("return_code").and_then(number).unwrap_or(0);
+    let signal = code
+        .get("signal")
+        .and_then(|signal| signal.get("id"))
+        .and_then(number)
+        .unwrap_or(0);
+    format!("{}:{}", return_code, signal)
+}
+
+pub fn parse_jobs(
+    output: &str,
+    user: &str,
+) -> Result<(HashMap<JobId, SlurmJobInfo>, HashMap<u64, SlurmFailure>)> {
+    let errors = response_errors(output)?;
+    if !errors.is_empty() {
+        return Err(rest_error(format!(
+            "slurmrestd could not list jobs: {}",
+            errors.join("; ")
+        )));
+    }
+    let response: Value = serde_json::from_str(output)?;
+    let mut queue = HashMap::new();
+    let mut ended = HashMap::new();
+    for job in response
+        .get("jobs")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        if job.get("user_name").and_then(Value::as_str) != Some(user) {
+            continue;
+        }
+        let (Some(slurm_id), Some(name), Some(state)) = (
+            job.get("job_id").and_then(number),
+            job.get("name").and_then(Value::as_str),
+            job_state(job),
+        ) else {
+            continue;
+        };
+        let reason = job
+            .get("state_reason")
+            .and_then(Value::as_str)
+            .filter(|reason| !reason.is_empty() && *reason != "None")
+            .map(str::to_string);
+
+        if ACTIVE_STATES.contains(&state) {
+            let repx_id = JobId::from(name.to_string());
+            queue.insert(
+                repx_id.clone(),
+                SlurmJobInfo {
+                    slurm_id,
+                    repx_id,
+                    state: match state {
+                        "PENDING" => SlurmState::Pending,
+                        "RUNNING" => SlurmState::Running,
+                        other => SlurmState::Other(other.to_string()),
+                    },
+                    reason,
+                },
+            );
+        } else {
+            ended.insert(
+                slurm_id,
+                SlurmFailure {
+                    slurm_id,
+                    state: state.to_string(),
+                    exit_code: exit_code(job),
+                    reason,
+                    max_rss: None,
+                    req_mem: job
+                        .get("memory_per_node")
+                        .and_then(number)
+                        .map(|mib| format!("{}M", mib)),
+                },
+            );
+        }
+    }
+    Ok((queue, ended))
+}
+
+fn megabytes(value: &str) -> Option<u64> {
+    if value.chars().all(|c| c.is_ascii_digit()) {
+        return value.parse().ok();
+    }
+    Memory::from(value)
+        .to_bytes()
+        .map(|bytes| bytes.div_ceil(1024 * 1024))
+}
+
+fn count(option: &str, value: &str) -> Result<Value> {
+    value
+        .parse::<u64>()
+        .map(Value::from)
+        .map_err(|_| rest_error(format!("Invalid #SBATCH --{}={}", option, value)))
+}
+
+fn apply_directive(job: &mut Map<String, Value>, option: &str, value: Option<&str>) -> Result<()> {
+    let text = |field: &str, job: &mut Map<String, Value>| -> Result<()> {
+        let value =
+            value.ok_or_else(|| rest_error(format!("#SBATCH --{} needs a value", option)))?;
+        job.insert(field.to_string(), Value::from(value));
+        Ok(())
+    };
+    let required =
+        || value.ok_or_else(|| rest_error(format!("#SBATCH --{} needs a value", option)));
+    match option {
+        "job-name" => text("name", job)?,
+        "chdir" => text("current_directory", job)?,
+        "output" => text("standard_output", job)?,
+        "error" => text("standard_error", job)?,
+        "partition" => text("partition", job)?,
+        "qos" => text("qos", job)?,
+        "account" => text("account", job)?,
+        "constraint" => text("constraints", job)?,
+        "nodes" => text("nodes", job)?,
+        "cpus-per-task" => {
+            job.insert("cpus_per_task".into(), count(option, required()?)?);
+        }
+        "ntasks" => {
+            job.insert("tasks".into(), count(option, required()?)?);
+        }
+        "ntasks-per-node" => {
+            job.insert("tasks_per_node".into(), count(option, required()?)?);
+        }
+        "mem" | "mem-per-cpu" => {
+            let value = required()?;
+            let mib = megabytes(value)
+                .ok_or_else(|| rest_error(format!("Invalid #SBATCH --{}={}", option, value)))?;
+            let field = if option == "mem" {
+                "memory_per_node"
+            } else {
+                "memory_per_cpu"
+            };
+            job.insert(field.into(), Value::from(mib));
+        }
+        "time" => {
+            let value = required()?;
+            let seconds = SlurmTime::from(value)
+                .to_seconds()
+                .ok_or_else(|| rest_error(format!("Invalid #SBATCH --time={}", value)))?;
+            job.insert("time_limit".into(), Value::from(seconds.div_ceil(60)));
+        }
+        "gres" => {
+            let tres: Vec<String> = required()?
+                .split(',')
+                .map(|gres| format!("gres/{}", gres))
+                .collect();
+            job.insert("tres_per_node".into(), Value::from(tres.join(",")));
+        }
+        "exclusive" => {
+            job.insert("shared".into(), json!(["none"]));
+        }
+        _ => {
+            return Err(rest_error(format!(
+                "#SBATCH --{} has no slurmrestd equivalent; drop it or submit without `rest`",
+                option
+            )))
+        }
+    }
+    Ok(())
+}
+
+fn script_directives(script: &str) -> Result<Map<String, Value>> {
+    let mut job = Map::new();
+    for line in script.lines().skip_while(|line| line.starts_with("#!")) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if !line.starts_with('#') {
+            break;
+        }
+        let Some(args) = line.strip_prefix("#SBATCH") else {
+            continue;
+        };
+        let mut words = args.split_whitespace().peekable();
+        while let Some(word) = words.next() {
+            let Some(option) = word.strip_prefix("--") else {
+                return Err(rest_error(format!(
+                    "#SBATCH {} has no slurmrestd equivalent; use the long option form",
+                    word
+                )));
+            };
+            let (option, value) = match option.split_once('=') {
+                Some((option, value)) => (option, Some(value.to_string())),
+                None if option != "exclusive" => (option, words.next().map(str::to_string)),
+                None => (option, None),
+            };
+            apply_directive(&mut job, option, value.as_deref())?;
+        }
+    }
+    Ok(job)
+}
+
+pub fn submit_request(
+    script: &str,
+    deps: &[u64],
+    anchor_id: Option<u64>,
+    environment: &[String],
+) -> Result<Value> {
+    let mut job = script_directives(script)?;
+    let mut environment = environment.to_vec();
+    if let Some(anchor_id) = anchor_id {
+        environment.push(format!("REPX_ANCHOR_ID={}", anchor_id));
+    }
+    job.insert("environment".into(), Value::from(environment));
+    job.entry("current_directory")
+        .or_insert_with(|| Value::from("/tmp"));
+    if !deps.is_empty() {
+        let deps: Vec<String> = deps.iter().map(u64::to_string).collect();
+        job.insert(
+            "dependency".into(),
+            Value::from(format!("afterok:{}", deps.join(":"))),
+        );
+        job.insert("flags".into(), json!(["KILL_INVALID_DEPENDENCY"]));
+    }
+    Ok(json!({ "script": script, "job": job }))
+}
+
+pub fn anchor_request(job_id: &str, environment: &[String]) -> Value {
+    json!({
+        "script": "#!/bin/sh\nexit 0\n",
+        "job": {
+            "name": format!("anchor-{}", job_id),
+            "hold": true,
+            "time_limit": 1,
+            "current_directory": "/tmp",
+            "standard_output": "/dev/null",
+            "standard_error": "/dev/null",
+            "environment": environment,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submit_request_translates_directives() {
+        let script = "#!/usr/bin/env bash\n#SBATCH --job-name=abc-train-1.0\n#SBATCH --chdir=/tmp\n#SBATCH --mem=4G\n#SBATCH --time=1:30:00\n#SBATCH --gres=gpu:2\n#SBATCH --exclusive\n\nset -e\n#SBATCH --partition=ignored\n";
+        let request =
+            submit_request(script, &[7, 9], Some(5), &["PATH=/bin".to_string()]).expect("request");
+        let job = &request["job"];
+        assert_eq!(job["name"], "abc-train-1.0");
+        assert_eq!(job["memory_per_node"], 4096);
+        assert_eq!(job["time_limit"], 90);
+        assert_eq!(job["tres_per_node"], "gres/gpu:2");
+        assert_eq!(job["shared"], json!(["none"]));
+        assert_eq!(job["dependency"], "afterok:7:9");
+        assert_eq!(job["environment"], json!(["PATH=/bin", "REPX_ANCHOR_ID=5"]));
+        assert!(job.get("partition").is_none());
+        assert_eq!(request["script"], script);
+
+        assert!(submit_request("#SBATCH --licenses=x\n", &[], None, &[]).is_err());
+        assert!(submit_request("#SBATCH -p short\n", &[], None, &[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_jobs_splits_queue_and_ended() {
+        let output = r#"{
+          "jobs": [
+            {"job_id": 11, "name": "abc-train-1.0", "user_name": "me",
+             "job_state": ["PENDING"], "state_reason": "Dependency"},
+            {"job_id": 12, "name": "def-eval-1.0", "user_name": "me",
+             "job_state": ["OUT_OF_MEMORY"], "state_reason": "None",
+             "exit_code": {"status": ["ERROR"], "return_code": {"set": true, "number": 0},
+                           "signal": {"id": {"set": true, "number": 9}}},
+             "memory_per_node": {"set": true, "infinite": false, "number": 4096}},
+            {"job_id": 13, "name": "other", "user_name": "someone",
+             "job_state": "RUNNING"}
+          ],
+          "errors": []
+        }"#;
+        let (queue, ended) = parse_jobs(output, "me").expect("jobs");
+        assert_eq!(queue.len(), 1);
+        let queued = &queue[&JobId::from("abc-train-1.0")];
+        assert_eq!(queued.slurm_id, 11);
+        assert_eq!(queued.state, SlurmState::Pending);
+        assert_eq!(queued.reason.as_deref(), Some("Dependency"));
+
+        let failure = &ended[&12];
+        assert!(failure.is_failure());
+        assert_eq!(failure.exit_code, "0:9");
+        assert_eq!(failure.summary(), "OUT_OF_MEMORY at 4096M");
+    }
+
+    #[test]
+    fn test_submit_response_and_errors() {
+        assert_eq!(
+            parse_submit_response(r#"{"job_id": 42, "errors": []}"#).expect("job id"),
+            42
+        );
+        assert_eq!(
+            parse_submit_response(r#"{"result": {"job_id": 43}}"#).expect("job id"),
+            43
+        );
+        let rejected = parse_submit_response(
+            r#"{"errors": [{"error": "Invalid job", "description": "Invalid partition name specified"}]}"#,
+        );
+        assert!(rejected
+            .expect_err("rejected")
+            .to_string()
+            .contains("Invalid partition name"));
+        assert!(parse_submit_response("curl: (7) Failed to connect").is_err());
+    }
+
+    #[test]
+    fn test_body_config_escapes_json() {
+        let request = json!({"script": "echo \"hi\" \\\n"});
+        let line = body_config(&request);
+        let quoted = line
+            .strip_prefix("data-binary = \"")
+            .and_then(|rest| rest.strip_suffix("\"\n"))
+            .expect("quoted value");
+        let mut unquoted = String::new();
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            unquoted.push(if c == '\\' {
+                chars.next().expect("escaped character")
+            } else {
+                c
+            });
+        }
+        assert_eq!(
+            serde_json::from_str::<Value>(&unquoted).expect("json"),
+            request
+        );
+    }
+}
@@ -1,17 +1,23 @@
 use crate::client::ClientEvent;
-use crate::error::Result;
+use crate::error::{ClientError, Result};
 use crate::targets::Target;
 use repx_core::{
     cache::{CacheKey, CacheMetadata, CacheStore, CacheStoreExt, FsCache},
     engine,
+    errors::CoreError,
     lab::LabSource,
     model::{Job, JobId, Lab, RunId, StageType},
+    submission_window::{SubmissionWindows, WindowDecision},
 };
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const WINDOW_RECHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 pub fn generate_project_id(source: &LabSource) -> String {
     let source_path = source.path();
@@ -96,14 +102,25 @@ pub fn collect_images_to_sync(
         .flat_map(|(run_id, run)| run.jobs.iter().map(move |jid| (jid, run_id)))
         .collect();
 
+    let mut add_image = |image_path: &std::path::PathBuf| {
+        if let Some(stem) = image_path.file_stem().and_then(|s| s.to_str()) {
+            images_to_sync.insert((image_path.clone(), stem.to_string()));
+        }
+    };
+
     for job_id in job_ids {
         if let Some(run_id) = job_to_run.get(job_id) {
-            if let Some(run) = lab.runs.get(*run_id) {
-                if let Some(image_path) = &run.image {
-                    if let Some(stem) = image_path.file_stem().and_then(|s| s.to_str()) {
-                        images_to_sync.insert((image_path.clone(), stem.to_string()));
-                    }
-                }
+            if let Some(image_path) = lab.runs.get(*run_id).and_then(|run| run.image.as_ref()) {
+                add_image(image_path);
+            }
+        }
+        if let Some(job) = lab.jobs.get(job_id) {
+            for image_path in job
+                .executables
+                .values()
+                .filter_map(|exe| exe.image.as_ref())
+            {
+                add_image(image_path);
             }
         }
     }
@@ -233,3 +250,60 @@ pub fn filter_jobs_for_local_submission<'a>(
         .map(|(id, job)| Ok((id.clone(), *job)))
         .collect()
 }
+
+pub fn wait_for_submission_window(
+    target_name: &str,
+    windows: &SubmissionWindows,
+    batch_jobs: usize,
+    cancel_flag: Option<&AtomicBool>,
+    send: &dyn Fn(ClientEvent),
+) -> Result<()> {
+    let mut announced: Option<String> = None;
+    loop {
+        let (reason, opens_at) = match windows.check(chrono::Local::now(), batch_jobs) {
+            WindowDecision::Open => return Ok(()),
+            WindowDecision::Closed {
+                reason,
+                opens_at: None,
+            } => {
+                return Err(ClientError::Config(CoreError::SubmissionWindowClosed {
+                    target: target_name.to_string(),
+                    reason,
+                }))
+            }
+            WindowDecision::Closed {
+                reason,
+                opens_at: Some(at),
+            } => (reason, at),
+        };
+
+        if announced.as_deref() != Some(reason.as_str()) {
+            tracing::info!(
+                "Holding submission to '{}' until {}: {}",
+                target_name,
+                opens_at,
+                reason
+            );
+            send(ClientEvent::SubmissionHeld {
+                target: target_name.to_string(),
+                reason: reason.clone(),
+                opens_at,
+            });
+            announced = Some(reason);
+        }
+
+        let wait = (opens_at - chrono::Local::now())
+            .to_std()
+            .unwrap_or_default()
+            .min(WINDOW_RECHECK_INTERVAL);
+        let deadline = Instant::now() + wait;
+        while Instant::now() < deadline {
+            if cancel_flag.is_some_and(|f| f.load(Ordering::SeqCst)) {
+                return Err(ClientError::Config(CoreError::CommandFailed(
+                    "Held submission cancelled by user".to_string(),
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(500).min(deadline - Instant::now()));
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use super::{common::shell_quote, CommandRunner, FileOps};
+use crate::error::Result;
+use repx_core::{
+    cache::{CacheKey, CacheStore, FsCache},
+    model::ExecutionType,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeCapabilities {
+    pub bwrap: bool,
+    pub user_namespaces: bool,
+    pub overlay: bool,
+    pub podman: bool,
+    pub docker: bool,
+    pub proot: bool,
+    pub checked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl RuntimeCapabilities {
+    pub fn supports(&self, execution_type: ExecutionType) -> bool {
+        match execution_type {
+            ExecutionType::Native => true,
+            ExecutionType::Bwrap => self.bwrap && self.user_namespaces,
+            ExecutionType::Podman => self.podman,
+            ExecutionType::Docker => self.docker,
+            ExecutionType::Proot => self.proot,
+        }
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.checked_at.is_some_and(|at| {
+            (chrono::Utc::now() - at)
+                .to_std()
+                .is_ok_and(|age| age < CACHE_TTL)
+        })
+    }
+
+    fn parse(output: &str) -> Self {
+        let found = |name: &str| output.lines().any(|line| line.trim() == name);
+        Self {
+            bwrap: found("bwrap"),
+            user_namespaces: found("userns"),
+            overlay: found("overlay"),
+            podman: found("podman"),
+            docker: found("docker"),
+            proot: found("proot"),
+            checked_at: Some(chrono::Utc::now()),
+        }
+    }
+}
+
+fn probe_script(tools_dir: &Path) -> String {
+    let tools = shell_quote(&tools_dir.to_string_lossy());
+    format!(
+        r#"t={tools}; b="$t/bwrap"
+[ -d "$t" ] && echo tools
+[ -x "$t/proot" ] && echo proot
+if [ -x "$b" ]; then
+  echo bwrap
+  if "$b" --unshare-user --dev-bind / / true >/dev/null 2>&1; then
+    echo userns
+    d=$(mktemp -d 2>/dev/null) && mkdir "$d/l" "$d/u" "$d/w" "$d/m" && "$b" --unshare-user --dev-bind / / --overlay-src "$d/l" --overlay "$d/u" "$d/w" "$d/m" true >/dev/null 2>&1 && echo overlay
+    [ -n "$d" ] && rm -rf "$d"
+  fi
+fi
+command -v podman >/dev/null 2>&1 && podman info >/dev/null 2>&1 && echo podman
+command -v docker >/dev/null 2>&1 && docker info >/dev/null 2>&1 && echo docker
+true"#
+    )
+}
+
+pub(crate) fn load_or_probe<T: CommandRunner + FileOps + ?Sized>(
+    target: &T,
+    tools_dir: &Path,
+    host_tools: &str,
+) -> Result<(RuntimeCapabilities, bool)> {
+    let cache_path =
+        FsCache::new(target.base_path().to_path_buf()).path(&CacheKey::RuntimeCapabilities {
+            host_tools: host_tools.to_string(),
+        });
+    if target.remote_file_size(&cache_path)?.is_some() {
+        match serde_json::from_str::<RuntimeCapabilities>(&target.read_remote_file(&cache_path)?) {
+            Ok(cached) if cached.is_fresh() => return Ok((cached, true)),
+            Ok(_) => tracing::debug!("Runtime capabilities of '{}' are stale", target.name()),
+            Err(e) => tracing::debug!(
+                "Ignoring unreadable capability cache '{}': {}",
+                cache_path.display(),
+                e
+            ),
+        }
+    }
+
+    let output = target.run_command("sh", &["-c", &probe_script(tools_dir)])?;
+    let capabilities = RuntimeCapabilities::parse(&output);
+    tracing::info!(
+        "Runtimes on target '{}': bwrap={} (user namespaces: {}, overlay: {}), podman={}, docker={}, proot={}",
+        target.name(),
+        capabilities.bwrap,
+        capabilities.user_namespaces,
+        capabilities.overlay,
+        capabilities.podman,
+        capabilities.docker,
+        capabilities.proot
+    );
+    let complete = output.lines().any(|line| line.trim() == "tools");
+    if complete {
+        let content = serde_json::to_string_pretty(&capabilities)?;
+        if let Err(e) = target.write_remote_file(&cache_path, &content) {
+            tracing::debug!("Failed to cache runtime capabilities: {}", e);
+        }
+    }
+    Ok((capabilities, complete))
+}
+
+pub(crate) fn cached<T: CommandRunner + FileOps + ?Sized>(
+    target: &T,
+    cell: &OnceLock<RuntimeCapabilities>,
+    tools_dir: &Path,
+    host_tools: &str,
+) -> Option<RuntimeCapabilities> {
+    if let Some(capabilities) = cell.get() {
+        return Some(capabilities.clone());
+    }
+    match load_or_probe(target, tools_dir, host_tools) {
+        Ok((capabilities, true)) => Some(cell.get_or_init(|| capabilities).clone()),
+        Ok((capabilities, false)) => Some(capabilities),
+        Err(e) => {
+            tracing::warn!(
+                "Could not probe container runtimes on target '{}': {}",
+                target.name(),
+                e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_probe_output() {
+        let caps = RuntimeCapabilities::parse("tools\nbwrap\nuserns\ndocker\n");
+        assert!(caps.supports(ExecutionType::Bwrap));
+        assert!(caps.supports(ExecutionType::Docker));
+        assert!(caps.supports(ExecutionType::Native));
+        assert!(!caps.supports(ExecutionType::Podman));
+        assert!(!caps.overlay);
+        assert!(caps.is_fresh());
+
+        let no_userns = RuntimeCapabilities::parse("bwrap\n");
+        assert!(!no_userns.supports(ExecutionType::Bwrap));
+    }
+
+    #[test]
+    fn test_resolve_skips_unavailable_runtime() {
+        use crate::client::resolve_execution_type;
+        use repx_core::config::{SchedulerConfig, Target};
+
+        let target: Target =
+            toml::from_str("base_path = \"/tmp/repx\"\ndefault_execution_type = \"podman\"\n")
+                .expect("target config");
+        let scheduler = SchedulerConfig {
+            execution_types: vec![
+                ExecutionType::Podman,
+                ExecutionType::Bwrap,
+                ExecutionType::Docker,
+            ],
+            ..Default::default()
+        };
+        let caps = RuntimeCapabilities::parse("bwrap\nuserns\ndocker\n");
+
+        let resolve =
+            |caps| resolve_execution_type(Some("img"), None, &target, Some(&scheduler), caps);
+        assert_eq!(resolve(None), "podman");
+        assert_eq!(resolve(Some(&caps)), "bwrap");
+        assert_eq!(
+            resolve(Some(&RuntimeCapabilities::default())),
+            "podman",
+            "nothing available keeps the configured runtime"
+        );
+    }
+}
@@ -2,8 +2,12 @@ use crate::error::{ClientError, Result};
 use repx_core::{
     cache::{CacheKey, CacheMetadata, CacheStore, CacheStoreExt, FsCache},
     errors::CoreError,
-    fs_utils::path_to_string,
+    fs_utils::{self, path_to_string},
+    store::cas::TreeEntry,
 };
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -11,6 +15,133 @@ pub fn shell_quote(s: &str) -> String {
     format!("'{}'", s.replace('\'', "'\\''"))
 }
 
+pub fn plan_shards(mut files: Vec<(PathBuf, u64)>, shards: usize) -> Vec<Vec<PathBuf>> {
+    let shards = shards.clamp(1, files.len().max(1));
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let mut planned: Vec<(u64, Vec<PathBuf>)> = vec![(0, Vec::new()); shards];
+    for (path, size) in files {
+        if let Some(lightest) = planned.iter_mut().min_by_key(|(total, _)| *total) {
+            lightest.0 += size;
+            lightest.1.push(path);
+        }
+    }
+    planned
+        .into_iter()
+        .map(|(_, paths)| paths)
+        .filter(|paths| !paths.is_empty())
+        .collect()
+}
+
+const SSH_ERROR_EXIT: i32 = 255;
+
+const RSYNC_NETWORK_EXITS: [i32; 4] = [10, 12, 30, 35];
+
+const TRANSIENT_ERRORS: [&str; 10] = [
+    "connection reset",
+    "connection timed out",
+    "connection refused",
+    "connection closed",
+    "broken pipe",
+    "operation timed out",
+    "network is unreachable",
+    "no route to host",
+    "temporary failure in name resolution",
+    "kex_exchange_identification",
+];
+
+pub fn is_transient_failure(code: Option<i32>, stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    let mentions_network = TRANSIENT_ERRORS.iter().any(|e| stderr.contains(e));
+    match code {
+        Some(code) if RSYNC_NETWORK_EXITS.contains(&code) => true,
+        Some(SSH_ERROR_EXIT) => mentions_network,
+        _ => false,
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HashCache {
+    files: HashMap<PathBuf, CachedHash>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedHash {
+    len: u64,
+    mtime: i64,
+    mtime_nsec: i64,
+    hash: String,
+}
+
+pub fn scan_tree(root: &Path, cache_path: &Path) -> Result<Vec<TreeEntry>> {
+    let mut cache: HashCache = fs_err::read_to_string(cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let mut seen = HashMap::new();
+    let mut entries = Vec::new();
+
+    for entry in walkdir::WalkDir::new(root).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| ClientError::Io(e.into()))?;
+        let rel = entry
+            .path()
+            .strip_prefix(root)
+            .map(path_to_string)
+            .map_err(|_| ClientError::InvalidPath {
+                path: entry.path().to_path_buf(),
+                reason: format!("not under '{}'", root.display()),
+            })?;
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            entries.push(TreeEntry::Dir { path: rel });
+        } else if file_type.is_symlink() {
+            let target = fs_err::read_link(entry.path()).map_err(ClientError::Io)?;
+            entries.push(TreeEntry::Symlink {
+                path: rel,
+                target: path_to_string(target),
+            });
+        } else {
+            let meta = entry.metadata().map_err(|e| ClientError::Io(e.into()))?;
+            let cached = cache.files.get(entry.path()).filter(|c| {
+                c.len == meta.len() && c.mtime == meta.mtime() && c.mtime_nsec == meta.mtime_nsec()
+            });
+            let hash = match cached {
+                Some(cached) => cached.hash.clone(),
+                None => super::compute_file_hash(entry.path())?,
+            };
+            seen.insert(
+                entry.path().to_path_buf(),
+                CachedHash {
+                    len: meta.len(),
+                    mtime: meta.mtime(),
+                    mtime_nsec: meta.mtime_nsec(),
+                    hash: hash.clone(),
+                },
+            );
+            entries.push(TreeEntry::File {
+                path: rel,
+                hash,
+                executable: meta.mode() & 0o111 != 0,
+            });
+        }
+    }
+
+    cache.files.retain(|path, _| !path.starts_with(root));
+    cache.files.extend(seen);
+    match serde_json::to_vec(&cache) {
+        Ok(content) => {
+            if let Err(e) = fs_utils::write_atomic(cache_path, &content) {
+                tracing::debug!(
+                    "Failed to save hash cache '{}': {}",
+                    cache_path.display(),
+                    e
+                );
+            }
+        }
+        Err(e) => tracing::debug!("Failed to serialise hash cache: {}", e),
+    }
+    Ok(entries)
+}
+
 pub fn parse_image_hash(filename: &str) -> Result<String> {
     let raw = if let Some(stripped) = filename.strip_suffix(".tar.gz") {
         stripped
@@ -52,6 +183,14 @@ fn validate_image_hash_str(id: &str) -> Result<()> {
     Ok(())
 }
 
+pub fn image_cache_dirs(base_path: &Path, config: &repx_core::config::Target) -> Vec<PathBuf> {
+    let mut dirs = vec![base_path.join("cache").join("images")];
+    if let Some(local) = &config.node_local_path {
+        dirs.push(local.join("repx").join("cache").join("images"));
+    }
+    dirs
+}
+
 pub fn generate_gc_link_name(lab_hash: &str) -> String {
     let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
     format!("{}_{}", timestamp, lab_hash)
@@ -194,6 +333,27 @@ pub fn restructure_layers_for_dedup(image_extract_dir: &Path, layers_cache: &Pat
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_transient_failure() {
+        assert!(is_transient_failure(
+            Some(255),
+            "Connection reset by peer\r\nssh: connect to host hpc port 22"
+        ));
+        assert!(is_transient_failure(
+            Some(30),
+            "rsync error: timeout in data send/receive (code 30)"
+        ));
+        assert!(!is_transient_failure(
+            Some(255),
+            "Permission denied (publickey)."
+        ));
+        assert!(!is_transient_failure(
+            Some(1),
+            "sbatch: error: Connection refused by controller"
+        ));
+        assert!(!is_transient_failure(None, "connection reset"));
+    }
+
     #[test]
     fn test_shell_quote_simple() {
         assert_eq!(shell_quote("hello"), "'hello'");
@@ -244,6 +404,79 @@ mod tests {
         assert!(parse_image_hash(".tar.gz").is_err());
     }
 
+    #[test]
+    fn test_plan_shards_balances_bytes() {
+        let files = vec![
+            (PathBuf::from("a"), 100),
+            (PathBuf::from("b"), 60),
+            (PathBuf::from("c"), 50),
+            (PathBuf::from("d"), 40),
+            (PathBuf::from("e"), 10),
+        ];
+        let shards = plan_shards(files, 2);
+        assert_eq!(shards.len(), 2);
+        assert_eq!(shards[0], vec![PathBuf::from("a"), PathBuf::from("d")]);
+        assert_eq!(
+            shards[1],
+            vec![PathBuf::from("b"), PathBuf::from("c"), PathBuf::from("e")]
+        );
+
+        let few = plan_shards(vec![(PathBuf::from("only"), 1)], 8);
+        assert_eq!(few, vec![vec![PathBuf::from("only")]]);
+        assert!(plan_shards(Vec::new(), 4).is_empty());
+    }
+
+    #[test]
+    fn test_scan_tree_describes_and_caches_hashes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().join("lab");
+        fs_err::create_dir_all(root.join("bin")).expect("create bin");
+        fs_err::create_dir_all(root.join("empty")).expect("create empty");
+        fs_err::write(root.join("bin/tool"), "same").expect("write tool");
+        fs_err::set_permissions(
+            root.join("bin/tool"),
+            std::fs::Permissions::from_mode(0o755),
+        )
+        .expect("chmod tool");
+        fs_err::write(root.join("data"), "same").expect("write data");
+        std::os::unix::fs::symlink("data", root.join("latest")).expect("symlink");
+        let cache_path = dir.path().join("hashes.json");
+
+        let entries = scan_tree(&root, &cache_path).expect("scan");
+        let hash = |wanted: &str| {
+            entries.iter().find_map(|entry| match entry {
+                TreeEntry::File {
+                    path,
+                    hash,
+                    executable,
+                } if path == wanted => Some((hash.clone(), *executable)),
+                _ => None,
+            })
+        };
+        let (tool_hash, tool_exec) = hash("bin/tool").expect("tool entry");
+        let (data_hash, data_exec) = hash("data").expect("data entry");
+        assert_eq!(tool_hash, data_hash);
+        assert!(tool_exec && !data_exec);
+        assert!(entries.contains(&TreeEntry::Dir {
+            path: "empty".to_string()
+        }));
+        assert!(entries.contains(&TreeEntry::Symlink {
+            path: "latest".to_string(),
+            target: "data".to_string()
+        }));
+
+        let cached = fs_err::read_to_string(&cache_path).expect("read cache");
+        fs_err::write(&cache_path, cached.replace(&data_hash, "cafe")).expect("edit cache");
+        let rescanned = scan_tree(&root, &cache_path).expect("rescan");
+        assert!(rescanned.contains(&TreeEntry::File {
+            path: "data".to_string(),
+            hash: "cafe".to_string(),
+            executable: false,
+        }));
+    }
+
     #[test]
     fn test_generate_gc_link_name() {
         let link_name = generate_gc_link_name("abc123");
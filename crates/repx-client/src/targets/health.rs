@@ -0,0 +1,292 @@
+use super::{common::shell_quote, CommandRunner};
+use crate::error::{ClientError, Result};
+use crate::slurm_rest;
+use repx_core::{errors::CoreError, fs_utils::format_bytes, model::SchedulerType};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const SLOW_SCHEDULER: Duration = Duration::from_secs(5);
+const LOW_DISK_BYTES: u64 = 10 * 1024 * 1024 * 1024;
+const CRITICAL_DISK_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthLevel {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl std::fmt::Display for HealthLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HealthLevel::Ok => write!(f, "ok"),
+            HealthLevel::Warn => write!(f, "warn"),
+            HealthLevel::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthCheck {
+    pub name: String,
+    pub level: HealthLevel,
+    pub detail: String,
+}
+
+impl HealthCheck {
+    fn new(name: &str, level: HealthLevel, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            level,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Degraded,
+    Down,
+}
+
+impl std::fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HealthStatus::Up => write!(f, "UP"),
+            HealthStatus::Degraded => write!(f, "DEGRADED"),
+            HealthStatus::Down => write!(f, "DOWN"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TargetHealth {
+    pub checks: Vec<HealthCheck>,
+}
+
+impl TargetHealth {
+    pub fn status(&self) -> HealthStatus {
+        let reachable = self
+            .checks
+            .first()
+            .is_some_and(|check| check.level != HealthLevel::Fail);
+        if !reachable {
+            return HealthStatus::Down;
+        }
+        match self.checks.iter().map(|check| check.level).max() {
+            Some(HealthLevel::Ok) | None => HealthStatus::Up,
+            Some(_) => HealthStatus::Degraded,
+        }
+    }
+}
+
+pub(crate) fn probe<T: CommandRunner + ?Sized>(
+    target: &T,
+    binary_path: Result<PathBuf>,
+) -> TargetHealth {
+    let started = Instant::now();
+    let connectivity = match target.run_command("sh", &["-c", "true"]) {
+        Ok(_) => HealthCheck::new(
+            "connectivity",
+            HealthLevel::Ok,
+            format!("reachable in {:.1}s", started.elapsed().as_secs_f64()),
+        ),
+        Err(e) => HealthCheck::new(
+            "connectivity",
+            HealthLevel::Fail,
+            first_line(&e.to_string()),
+        ),
+    };
+    if connectivity.level == HealthLevel::Fail {
+        return TargetHealth {
+            checks: vec![connectivity],
+        };
+    }
+
+    let mut checks = vec![connectivity];
+    for scheduler in probed_schedulers(target) {
+        checks.push(check_scheduler(target, scheduler));
+    }
+    checks.push(check_disk(target, target.base_path()));
+    checks.push(match binary_path {
+        Ok(path) => check_binary(target, &path),
+        Err(e) => HealthCheck::new("binary", HealthLevel::Fail, first_line(&e.to_string())),
+    });
+    TargetHealth { checks }
+}
+
+fn probed_schedulers<T: CommandRunner + ?Sized>(target: &T) -> Vec<SchedulerType> {
+    let config = target.config();
+    [
+        (SchedulerType::Slurm, config.slurm.is_some()),
+        (SchedulerType::Sge, config.sge.is_some()),
+        (SchedulerType::Flux, config.flux.is_some()),
+    ]
+    .into_iter()
+    .filter(|(scheduler, configured)| *configured || config.default_scheduler == Some(*scheduler))
+    .map(|(scheduler, _)| scheduler)
+    .collect()
+}
+
+fn check_scheduler<T: CommandRunner + ?Sized>(target: &T, scheduler: SchedulerType) -> HealthCheck {
+    let rest = target
+        .config()
+        .slurm_rest()
+        .filter(|_| scheduler == SchedulerType::Slurm);
+    let command = match scheduler {
+        SchedulerType::Sge => "qstat",
+        SchedulerType::Flux => "flux uptime",
+        _ if rest.is_some() => "slurmrestd ping",
+        _ => "scontrol ping",
+    };
+    let name = format!("scheduler ({})", scheduler);
+    let started = Instant::now();
+    let result = match rest {
+        Some(rest) => target
+            .run_command(
+                "sh",
+                &[
+                    "-c",
+                    &slurm_rest::request_command(rest, "GET", "ping", false),
+                ],
+            )
+            .and_then(
+                |output| match slurm_rest::response_errors(&output)?.first() {
+                    Some(error) => {
+                        Err(ClientError::Config(CoreError::CommandFailed(error.clone())))
+                    }
+                    None => Ok(output),
+                },
+            ),
+        None => target.run_command("sh", &["-c", command]),
+    };
+    let elapsed = started.elapsed();
+    match result {
+        Ok(_) if elapsed > SLOW_SCHEDULER => HealthCheck::new(
+            &name,
+            HealthLevel::Warn,
+            format!("'{}' took {:.1}s", command, elapsed.as_secs_f64()),
+        ),
+        Ok(_) => HealthCheck::new(
+            &name,
+            HealthLevel::Ok,
+            format!("'{}' answered in {:.1}s", command, elapsed.as_secs_f64()),
+        ),
+        Err(e) => HealthCheck::new(&name, HealthLevel::Fail, first_line(&e.to_string())),
+    }
+}
+
+fn check_disk<T: CommandRunner + ?Sized>(target: &T, base_path: &Path) -> HealthCheck {
+    let command = format!(
+        "p={}; while [ ! -e \"$p\" ]; do p=$(dirname \"$p\"); done; df -Pk \"$p\" | tail -n 1",
+        shell_quote(&base_path.to_string_lossy())
+    );
+    let output = match target.run_command("sh", &["-c", &command]) {
+        Ok(output) => output,
+        Err(e) => return HealthCheck::new("disk", HealthLevel::Fail, first_line(&e.to_string())),
+    };
+    let Some((total, available)) = parse_df_line(&output) else {
+        return HealthCheck::new(
+            "disk",
+            HealthLevel::Warn,
+            format!("could not parse df output '{}'", output.trim()),
+        );
+    };
+    let level = if available < CRITICAL_DISK_BYTES {
+        HealthLevel::Fail
+    } else if available < LOW_DISK_BYTES {
+        HealthLevel::Warn
+    } else {
+        HealthLevel::Ok
+    };
+    HealthCheck::new(
+        "disk",
+        level,
+        format!(
+            "{} free of {} at {}",
+            format_bytes(available, false),
+            format_bytes(total, false),
+            base_path.display()
+        ),
+    )
+}
+
+fn check_binary<T: CommandRunner + ?Sized>(target: &T, path: &Path) -> HealthCheck {
+    let command = format!(
+        "if [ -x {} ]; then echo deployed; fi",
+        shell_quote(&path.to_string_lossy())
+    );
+    match target.run_command("sh", &["-c", &command]) {
+        Ok(output) if output.trim() == "deployed" => {
+            HealthCheck::new("binary", HealthLevel::Ok, "current repx binary is deployed")
+        }
+        Ok(_) => HealthCheck::new(
+            "binary",
+            HealthLevel::Warn,
+            "current repx binary is not deployed yet; the next submission uploads it",
+        ),
+        Err(e) => HealthCheck::new("binary", HealthLevel::Fail, first_line(&e.to_string())),
+    }
+}
+
+fn parse_df_line(output: &str) -> Option<(u64, u64)> {
+    let line = output.lines().rev().find(|line| !line.trim().is_empty())?;
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let total: u64 = fields.get(1)?.parse().ok()?;
+    let available: u64 = fields.get(3)?.parse().ok()?;
+    Some((total * 1024, available * 1024))
+}
+
+fn first_line(message: &str) -> String {
+    message
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df_line() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/nvme0n1p2   960379496 512345678 399229048      57% /scratch\n";
+        assert_eq!(
+            parse_df_line(output),
+            Some((960379496 * 1024, 399229048 * 1024))
+        );
+        assert_eq!(parse_df_line("df: /nope: No such file\n"), None);
+    }
+
+    #[test]
+    fn test_status_from_checks() {
+        let ok = HealthCheck::new("connectivity", HealthLevel::Ok, "");
+        let warn = HealthCheck::new("binary", HealthLevel::Warn, "");
+        let down = HealthCheck::new("connectivity", HealthLevel::Fail, "");
+        assert_eq!(
+            TargetHealth {
+                checks: vec![ok.clone()]
+            }
+            .status(),
+            HealthStatus::Up
+        );
+        assert_eq!(
+            TargetHealth {
+                checks: vec![ok, warn]
+            }
+            .status(),
+            HealthStatus::Degraded
+        );
+        assert_eq!(
+            TargetHealth { checks: vec![down] }.status(),
+            HealthStatus::Down
+        );
+    }
+}
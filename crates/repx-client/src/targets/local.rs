@@ -1,5 +1,8 @@
-use super::{ArtifactSync, CommandRunner, FileOps, GcOps, JobRunner, SlurmOps, TargetInfo};
+use super::{
+    ArtifactSync, CommandRunner, FileOps, FluxOps, GcOps, JobRunner, SgeOps, SlurmOps, TargetInfo,
+};
 use crate::error::{ClientError, Result};
+use crate::ClientEvent;
 use repx_core::{
     cache::{CacheKey, CacheMetadata, CacheStore, CacheStoreExt, FsCache},
     config,
@@ -7,12 +10,15 @@ use repx_core::{
     errors::CoreError,
     fs_utils::path_to_string,
     model::JobId,
+    store::outcomes::FailureInfo,
 };
 use std::{
     collections::HashSet,
+    io::{Read, Seek, SeekFrom},
     os::unix::fs::{MetadataExt, PermissionsExt},
     path::{Path, PathBuf},
     process::Command,
+    sync::{mpsc::Sender, OnceLock},
 };
 use walkdir::WalkDir;
 
@@ -35,9 +41,19 @@ pub struct LocalTarget {
     pub(crate) name: String,
     pub(crate) config: config::Target,
     pub(crate) local_tools_path: PathBuf,
+    pub(crate) capabilities: OnceLock<super::RuntimeCapabilities>,
 }
 
 impl LocalTarget {
+    pub fn new(name: String, config: config::Target, local_tools_path: PathBuf) -> Self {
+        Self {
+            name,
+            config,
+            local_tools_path,
+            capabilities: Default::default(),
+        }
+    }
+
     fn tool(&self, name: &str) -> PathBuf {
         let tool_path = self.local_tools_path.join(name);
         if tool_path.exists() {
@@ -49,6 +65,31 @@ impl LocalTarget {
 }
 
 impl TargetInfo for LocalTarget {
+    fn health(&self) -> super::TargetHealth {
+        let binary_path = super::find_local_runner_binary()
+            .and_then(|exe| super::compute_file_hash(&exe))
+            .map(|binary_hash| {
+                FsCache::new(self.base_path().to_path_buf())
+                    .path(&CacheKey::LocalBinary { binary_hash })
+            });
+        super::health::probe(self, binary_path)
+    }
+
+    fn runtime_capabilities(&self) -> Option<super::RuntimeCapabilities> {
+        let host_tools = self
+            .local_tools_path
+            .parent()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        super::capabilities::cached(
+            self,
+            &self.capabilities,
+            &self.local_tools_path,
+            &host_tools,
+        )
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
@@ -110,12 +151,15 @@ impl CommandRunner for LocalTarget {
 }
 
 impl SlurmOps for LocalTarget {
-    fn scancel(&self, slurm_id: u32) -> Result<()> {
-        self.run_command("scancel", &[&slurm_id.to_string()])?;
-        Ok(())
+    fn scancel(&self, slurm_id: u64) -> Result<()> {
+        self.scancel_batch(&[slurm_id])
     }
 }
 
+impl SgeOps for LocalTarget {}
+
+impl FluxOps for LocalTarget {}
+
 impl ArtifactSync for LocalTarget {
     fn get_missing_artifacts(&self, artifacts: &HashSet<PathBuf>) -> Result<HashSet<PathBuf>> {
         let artifacts_path = self.artifacts_base_path();
@@ -143,7 +187,11 @@ impl ArtifactSync for LocalTarget {
         Ok(())
     }
 
-    fn sync_lab_root(&self, local_lab_path: &Path) -> Result<()> {
+    fn sync_lab_root(
+        &self,
+        local_lab_path: &Path,
+        _event_sender: Option<&Sender<ClientEvent>>,
+    ) -> Result<()> {
         let dest_path = self.artifacts_base_path();
         fs_err::create_dir_all(&dest_path).map_err(ClientError::Io)?;
 
@@ -166,7 +214,11 @@ impl ArtifactSync for LocalTarget {
         Ok(())
     }
 
-    fn sync_lab_root_metadata_only(&self, local_lab_path: &Path) -> Result<()> {
+    fn sync_lab_root_metadata_only(
+        &self,
+        local_lab_path: &Path,
+        _event_sender: Option<&Sender<ClientEvent>>,
+    ) -> Result<()> {
         let dest_path = self.artifacts_base_path();
         fs_err::create_dir_all(&dest_path).map_err(ClientError::Io)?;
 
@@ -190,7 +242,11 @@ impl ArtifactSync for LocalTarget {
         Ok(())
     }
 
-    fn sync_lab_from_tar_via_rsync(&self, tar_path: &Path) -> Result<()> {
+    fn sync_lab_from_tar_via_rsync(
+        &self,
+        tar_path: &Path,
+        _event_sender: Option<&Sender<ClientEvent>>,
+    ) -> Result<()> {
         let dest_path = self.artifacts_base_path();
         fs_err::create_dir_all(&dest_path).map_err(ClientError::Io)?;
 
@@ -223,7 +279,12 @@ impl ArtifactSync for LocalTarget {
         Ok(())
     }
 
-    fn sync_file(&self, local_path: &Path, remote_path: &Path) -> Result<()> {
+    fn sync_file(
+        &self,
+        local_path: &Path,
+        remote_path: &Path,
+        _event_sender: Option<&Sender<ClientEvent>>,
+    ) -> Result<()> {
         if let Some(parent) = remote_path.parent() {
             fs_err::create_dir_all(parent).map_err(ClientError::Io)?;
         }
@@ -253,6 +314,35 @@ impl ArtifactSync for LocalTarget {
         Ok(())
     }
 
+    #[allow(clippy::expect_used)]
+    fn fetch_directory(&self, remote_path: &Path, local_path: &Path) -> Result<()> {
+        if !remote_path.is_dir() {
+            return Err(ClientError::Config(CoreError::OutputNotReady {
+                path: remote_path.to_path_buf(),
+            }));
+        }
+        for entry in WalkDir::new(remote_path) {
+            let entry = entry?;
+            let path = entry.path();
+            let relative = path
+                .strip_prefix(remote_path)
+                .expect("strip_prefix guaranteed by WalkDir starting from remote_path");
+            let dest_path = local_path.join(relative);
+
+            if entry.file_type().is_dir() {
+                fs_err::create_dir_all(&dest_path).map_err(ClientError::Io)?;
+            } else {
+                // Outputs are often read-only, so a copy from an earlier
+                // fetch cannot be overwritten in place.
+                if dest_path.symlink_metadata().is_ok() {
+                    fs_err::remove_file(&dest_path).map_err(ClientError::Io)?;
+                }
+                fs_err::copy(path, &dest_path).map_err(ClientError::Io)?;
+            }
+        }
+        Ok(())
+    }
+
     fn sync_image_incrementally(
         &self,
         image_path: &Path,
@@ -419,6 +509,27 @@ impl FileOps for LocalTarget {
             .map(String::from)
             .collect())
     }
+
+    fn remote_file_size(&self, path: &Path) -> Result<Option<u64>> {
+        match fs_err::metadata(path) {
+            Ok(meta) => Ok(Some(meta.len())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ClientError::Io(e)),
+        }
+    }
+
+    fn read_remote_file_from(&self, path: &Path, offset: u64) -> Result<String> {
+        let mut file = match fs_err::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(String::new()),
+            Err(e) => return Err(ClientError::Io(e)),
+        };
+        file.seek(SeekFrom::Start(offset))
+            .map_err(ClientError::Io)?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).map_err(ClientError::Io)?;
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
 }
 
 impl JobRunner for LocalTarget {
@@ -467,6 +578,12 @@ impl JobRunner for LocalTarget {
         cmd.spawn().map_err(ClientError::Io)
     }
 
+    fn check_heartbeats(&self) -> Result<std::collections::HashMap<JobId, std::time::Duration>> {
+        Ok(repx_core::store::heartbeat::scan_heartbeats(
+            self.base_path(),
+        )?)
+    }
+
     fn check_outcome_markers(
         &self,
     ) -> Result<std::collections::HashMap<JobId, repx_core::engine::JobStatus>> {
@@ -505,7 +622,14 @@ impl JobRunner for LocalTarget {
             }
 
             let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
-            if file_name != markers::SUCCESS && file_name != markers::FAIL {
+            if ![
+                markers::SUCCESS,
+                markers::FAIL,
+                markers::TIMEOUT,
+                markers::CANCELLED,
+            ]
+            .contains(&file_name)
+            {
                 continue;
             }
 
@@ -521,8 +645,16 @@ impl JobRunner for LocalTarget {
 
                     let status = if file_name == markers::SUCCESS {
                         repx_core::engine::JobStatus::Succeeded { location }
+                    } else if file_name == markers::CANCELLED {
+                        repx_core::engine::JobStatus::Cancelled { location }
+                    } else if file_name == markers::TIMEOUT {
+                        repx_core::engine::JobStatus::TimedOut { location }
                     } else {
-                        repx_core::engine::JobStatus::Failed { location }
+                        repx_core::engine::JobStatus::Failed {
+                            location,
+                            class: FailureInfo::read_marker(repx_dir).and_then(|info| info.class),
+                            reason: None,
+                        }
                     };
                     outcomes.insert(job_id, status);
                 }
@@ -691,6 +823,102 @@ impl GcOps for LocalTarget {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
+    fn clean_outputs(
+        &self,
+        job_ids: &[JobId],
+        older_than: Option<std::time::Duration>,
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String> {
+        let repx_bin = self.deploy_repx_binary()?;
+
+        let mut cmd = Command::new(&repx_bin);
+        cmd.args(verbose.as_args())
+            .arg("internal-clean")
+            .arg("--base-path")
+            .arg(self.base_path());
+        if let Some(age) = older_than {
+            cmd.arg("--older-than-secs").arg(age.as_secs().to_string());
+        }
+        if dry_run {
+            cmd.arg("--dry-run");
+        }
+        cmd.args(job_ids.iter().map(|job_id| job_id.as_str()));
+
+        let output = cmd.output().map_err(ClientError::Io)?;
+
+        if !output.status.success() {
+            return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                "Clean failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn prune_step_outputs(
+        &self,
+        job_ids: &[JobId],
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String> {
+        let repx_bin = self.deploy_repx_binary()?;
+
+        let mut cmd = Command::new(&repx_bin);
+        cmd.args(verbose.as_args())
+            .arg("internal-prune")
+            .arg("--base-path")
+            .arg(self.base_path());
+        if dry_run {
+            cmd.arg("--dry-run");
+        }
+        cmd.args(job_ids.iter().map(|job_id| job_id.as_str()));
+
+        let output = cmd.output().map_err(ClientError::Io)?;
+
+        if !output.status.success() {
+            return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                "Prune failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn prune_images(
+        &self,
+        max_bytes: Option<u64>,
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String> {
+        let repx_bin = self.deploy_repx_binary()?;
+
+        let mut cmd = Command::new(&repx_bin);
+        cmd.args(verbose.as_args()).arg("internal-images-prune");
+        for dir in super::common::image_cache_dirs(self.base_path(), self.config()) {
+            cmd.arg("--cache-dir").arg(dir);
+        }
+        if let Some(max_bytes) = max_bytes {
+            cmd.arg("--max-bytes").arg(max_bytes.to_string());
+        }
+        if dry_run {
+            cmd.arg("--dry-run");
+        }
+
+        let output = cmd.output().map_err(ClientError::Io)?;
+
+        if !output.status.success() {
+            return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                "Image prune failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
     fn remove_auto_roots(&self) -> Result<u64> {
         let auto_dir = self
             .base_path()
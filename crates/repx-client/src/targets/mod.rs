@@ -1,20 +1,33 @@
+pub mod capabilities;
 pub mod common;
+pub mod health;
 pub mod local;
+pub mod object_store;
+pub mod progress;
 pub mod remote_command;
 pub mod ssh;
+pub(crate) mod sync_manifest;
 
+pub use capabilities::RuntimeCapabilities;
 pub use common::*;
+pub use health::{HealthCheck, HealthLevel, HealthStatus, TargetHealth};
 pub use local::LocalTarget;
+pub use object_store::ObjectStoreTarget;
 pub use remote_command::RemoteCommand;
 pub use ssh::SshTarget;
 
 use crate::error::{ClientError, Result};
+use crate::slurm_rest;
 use repx_core::{
     config,
     constants::{dirs, markers},
     engine,
     errors::CoreError,
     model::JobId,
+    store::{
+        heartbeat,
+        slurm_failure::{self, SlurmFailure},
+    },
 };
 use sha2::{Digest, Sha256};
 use std::{
@@ -73,9 +86,10 @@ pub enum SlurmState {
 
 #[derive(Debug, Clone)]
 pub struct SlurmJobInfo {
-    pub slurm_id: u32,
+    pub slurm_id: u64,
     pub repx_id: JobId,
     pub state: SlurmState,
+    pub reason: Option<String>,
 }
 
 pub trait TargetInfo: Send + Sync {
@@ -90,6 +104,10 @@ pub trait TargetInfo: Send + Sync {
     fn artifacts_base_path(&self) -> PathBuf {
         self.base_path().join("artifacts")
     }
+
+    fn health(&self) -> TargetHealth;
+
+    fn runtime_capabilities(&self) -> Option<RuntimeCapabilities>;
 }
 
 pub trait CommandRunner: TargetInfo {
@@ -103,15 +121,34 @@ pub trait ArtifactSync: TargetInfo {
 
     fn sync_artifact(&self, local_path: &Path, relative_path: &Path) -> Result<()>;
 
-    fn sync_lab_root(&self, local_lab_path: &Path) -> Result<()>;
+    fn sync_lab_root(
+        &self,
+        local_lab_path: &Path,
+        event_sender: Option<&Sender<super::ClientEvent>>,
+    ) -> Result<()>;
 
-    fn sync_lab_root_metadata_only(&self, local_lab_path: &Path) -> Result<()>;
+    fn sync_lab_root_metadata_only(
+        &self,
+        local_lab_path: &Path,
+        event_sender: Option<&Sender<super::ClientEvent>>,
+    ) -> Result<()>;
 
-    fn sync_lab_from_tar_via_rsync(&self, tar_path: &Path) -> Result<()>;
+    fn sync_lab_from_tar_via_rsync(
+        &self,
+        tar_path: &Path,
+        event_sender: Option<&Sender<super::ClientEvent>>,
+    ) -> Result<()>;
 
     fn sync_directory(&self, local_path: &Path, remote_path: &Path) -> Result<()>;
 
-    fn sync_file(&self, local_path: &Path, remote_path: &Path) -> Result<()>;
+    fn sync_file(
+        &self,
+        local_path: &Path,
+        remote_path: &Path,
+        event_sender: Option<&Sender<super::ClientEvent>>,
+    ) -> Result<()>;
+
+    fn fetch_directory(&self, remote_path: &Path, local_path: &Path) -> Result<()>;
 
     fn sync_image_incrementally(
         &self,
@@ -147,15 +184,32 @@ pub trait FileOps: TargetInfo {
     fn read_remote_file(&self, path: &Path) -> Result<String>;
 
     fn read_remote_file_tail(&self, path: &Path, line_count: u32) -> Result<Vec<String>>;
+
+    fn remote_file_size(&self, path: &Path) -> Result<Option<u64>>;
+
+    fn read_remote_file_from(&self, path: &Path, offset: u64) -> Result<String>;
 }
 
 pub trait SlurmOps: CommandRunner {
-    fn scancel(&self, slurm_id: u32) -> Result<()>;
+    fn scancel(&self, slurm_id: u64) -> Result<()>;
 
-    fn scancel_batch(&self, slurm_ids: &[u32]) -> Result<()> {
+    fn scancel_batch(&self, slurm_ids: &[u64]) -> Result<()> {
         if slurm_ids.is_empty() {
             return Ok(());
         }
+        if let Some(rest) = self.config().slurm_rest() {
+            let command = slurm_rest::cancel_command(rest, slurm_ids);
+            let output = self.run_command("sh", &["-c", &command])?;
+            let errors = slurm_rest::response_errors(&output)?;
+            if !errors.is_empty() {
+                return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                    "slurmrestd on '{}' could not cancel jobs: {}",
+                    self.name(),
+                    errors.join("; ")
+                ))));
+            }
+            return Ok(());
+        }
         let id_strs: Vec<String> = slurm_ids.iter().map(|id| id.to_string()).collect();
         let id_refs: Vec<&str> = id_strs.iter().map(|s| s.as_str()).collect();
         self.run_command("scancel", &id_refs)?;
@@ -163,16 +217,111 @@ pub trait SlurmOps: CommandRunner {
     }
 
     fn squeue(&self) -> Result<HashMap<JobId, SlurmJobInfo>> {
-        let user = if self.config().address.is_some() {
-            self.run_command("whoami", &[])?.trim().to_string()
-        } else {
-            whoami::username().unwrap_or_else(|_| "unknown".to_string())
-        };
-
-        let squeue_command = format!("squeue -h -o '%i %j %t' -u '{}'", user);
+        if let Some(rest) = self.config().slurm_rest() {
+            return Ok(rest_jobs(self, rest)?.0);
+        }
+        let user = queue_user(self)?;
+        let squeue_command = format!("squeue -h -o '%i %j %t %r' -u '{}'", user);
         let output = self.run_command("sh", &["-c", &squeue_command])?;
         Ok(parse_squeue(&output))
     }
+
+    fn sacct(&self, slurm_ids: &[u64]) -> Result<HashMap<u64, SlurmFailure>> {
+        if slurm_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let ids: Vec<String> = slurm_ids.iter().map(|id| id.to_string()).collect();
+        let sacct_command = format!(
+            "sacct -n -P -o {} -j {}",
+            slurm_failure::SACCT_FIELDS,
+            ids.join(",")
+        );
+        let output = self.run_command("sh", &["-c", &sacct_command])?;
+        Ok(slurm_failure::parse_sacct(&output))
+    }
+
+    fn slurm_snapshot(
+        &self,
+        slurm_ids: &[u64],
+    ) -> Result<(HashMap<JobId, SlurmJobInfo>, HashMap<u64, SlurmFailure>)> {
+        if let Some(rest) = self.config().slurm_rest() {
+            let (queue, mut ended) = rest_jobs(self, rest)?;
+            let queued: HashSet<u64> = queue.values().map(|info| info.slurm_id).collect();
+            ended.retain(|id, _| slurm_ids.contains(id));
+            let forgotten: Vec<u64> = slurm_ids
+                .iter()
+                .filter(|id| !queued.contains(id) && !ended.contains_key(id))
+                .copied()
+                .collect();
+            if !forgotten.is_empty() {
+                match self.sacct(&forgotten) {
+                    Ok(records) => ended.extend(records),
+                    Err(e) => tracing::debug!("sacct lookup on '{}' failed: {}", self.name(), e),
+                }
+            }
+            return Ok((queue, ended));
+        }
+        let user = queue_user(self)?;
+        let command = slurm_snapshot_command(&user, slurm_ids);
+        let output = self.run_command("sh", &["-c", &command])?;
+        Ok(parse_slurm_snapshot(&output, self.name()))
+    }
+}
+
+pub trait SgeOps: CommandRunner {
+    fn qdel(&self, job_ids: &[u64]) -> Result<()> {
+        if job_ids.is_empty() {
+            return Ok(());
+        }
+        let id_strs: Vec<String> = job_ids.iter().map(|id| id.to_string()).collect();
+        let id_refs: Vec<&str> = id_strs.iter().map(|s| s.as_str()).collect();
+        self.run_command("qdel", &id_refs)?;
+        Ok(())
+    }
+
+    fn qstat(&self) -> Result<HashMap<JobId, SlurmJobInfo>> {
+        let user = queue_user(self)?;
+        let qstat_command = format!("qstat -xml -u '{}'", user);
+        let output = self.run_command("sh", &["-c", &qstat_command])?;
+        Ok(parse_qstat_xml(&output))
+    }
+}
+
+pub trait FluxOps: CommandRunner {
+    fn flux_cancel(&self, job_ids: &[u64]) -> Result<()> {
+        if job_ids.is_empty() {
+            return Ok(());
+        }
+        let mut args = vec!["cancel".to_string()];
+        args.extend(job_ids.iter().map(|id| id.to_string()));
+        let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        self.run_command("flux", &arg_refs)?;
+        Ok(())
+    }
+
+    fn flux_jobs(&self) -> Result<HashMap<JobId, SlurmJobInfo>> {
+        let flux_command = "flux jobs --no-header --format='{id.dec} {state} {name}'";
+        let output = self.run_command("sh", &["-c", flux_command])?;
+        Ok(parse_flux_jobs(&output))
+    }
+}
+
+fn rest_jobs<T: CommandRunner + ?Sized>(
+    target: &T,
+    rest: &config::SlurmRestConfig,
+) -> Result<(HashMap<JobId, SlurmJobInfo>, HashMap<u64, SlurmFailure>)> {
+    let user = queue_user(target)?;
+    let command = slurm_rest::request_command(rest, "GET", "jobs", false);
+    let output = target.run_command("sh", &["-c", &command])?;
+    slurm_rest::parse_jobs(&output, &user)
+}
+
+fn queue_user<T: CommandRunner + ?Sized>(target: &T) -> Result<String> {
+    Ok(if target.config().address.is_some() {
+        target.run_command("whoami", &[])?.trim().to_string()
+    } else {
+        whoami::username().unwrap_or_else(|_| "unknown".to_string())
+    })
 }
 
 pub trait JobRunner: CommandRunner {
@@ -203,11 +352,13 @@ pub trait JobRunner: CommandRunner {
 
         let outputs_path = self.base_path().join(dirs::OUTPUTS);
         let find_cmd = format!(
-            "if [ -d \"{}\" ]; then find \"{}\" -mindepth 3 -maxdepth 3 \\( -name {} -o -name {} \\) -path '*/{}/*'; fi",
+            "if [ -d \"{}\" ]; then find \"{}\" -mindepth 3 -maxdepth 3 \\( -name {} -o -name {} -o -name {} -o -name {} \\) -path '*/{}/*'; fi",
             outputs_path.display(),
             outputs_path.display(),
             markers::SUCCESS,
             markers::FAIL,
+            markers::TIMEOUT,
+            markers::CANCELLED,
             dirs::REPX
         );
         let output = self.run_command("sh", &["-c", &find_cmd])?;
@@ -224,8 +375,16 @@ pub trait JobRunner: CommandRunner {
 
                     let status = if file_name == markers::SUCCESS {
                         engine::JobStatus::Succeeded { location }
+                    } else if file_name == markers::CANCELLED {
+                        engine::JobStatus::Cancelled { location }
+                    } else if file_name == markers::TIMEOUT {
+                        engine::JobStatus::TimedOut { location }
                     } else if file_name == markers::FAIL {
-                        engine::JobStatus::Failed { location }
+                        engine::JobStatus::Failed {
+                            location,
+                            class: None,
+                            reason: None,
+                        }
                     } else {
                         continue;
                     };
@@ -235,6 +394,60 @@ pub trait JobRunner: CommandRunner {
         }
         Ok(outcomes)
     }
+
+    fn check_heartbeats(&self) -> Result<HashMap<JobId, std::time::Duration>> {
+        let outputs_path = self.base_path().join(dirs::OUTPUTS);
+        let find_cmd = format!(
+            "if [ -d \"{}\" ]; then date +%s; find \"{}\" -mindepth 3 -maxdepth 3 -name {} -path '*/{}/*' -printf '%T@ %p\\n'; fi",
+            outputs_path.display(),
+            outputs_path.display(),
+            heartbeat::HEARTBEAT_FILE,
+            dirs::REPX
+        );
+        let output = self.run_command("sh", &["-c", &find_cmd])?;
+
+        let mut lines = output.lines();
+        let Some(now) = lines
+            .next()
+            .and_then(|line| line.trim().parse::<f64>().ok())
+        else {
+            return Ok(HashMap::new());
+        };
+        let mut ages = HashMap::new();
+        for line in lines {
+            let Some((mtime, path)) = line.split_once(' ') else {
+                continue;
+            };
+            let Ok(mtime) = mtime.parse::<f64>() else {
+                continue;
+            };
+            let job_id = Path::new(path)
+                .parent()
+                .and_then(|repx_dir| repx_dir.parent())
+                .and_then(|job_dir| job_dir.file_name())
+                .and_then(|s| s.to_str());
+            if let Some(job_id) = job_id {
+                ages.insert(
+                    JobId::from(job_id.to_string()),
+                    std::time::Duration::from_secs_f64((now - mtime).max(0.0)),
+                );
+            }
+        }
+        Ok(ages)
+    }
+
+    fn find_step_markers(&self, job_id: &JobId, marker: &str) -> Result<HashSet<(usize, String)>> {
+        let job_root = self.base_path().join(dirs::OUTPUTS).join(job_id.as_str());
+        let find_cmd = format!(
+            "if [ -d \"{}\" ]; then find \"{}\" -mindepth 4 -maxdepth 4 -name {} -path '*/branch-*/step-*/{}/*'; fi",
+            job_root.display(),
+            job_root.display(),
+            marker,
+            dirs::REPX
+        );
+        let output = self.run_command("sh", &["-c", &find_cmd])?;
+        Ok(parse_step_markers(&output))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -277,18 +490,92 @@ pub trait GcOps: TargetInfo {
     fn list_gc_roots(&self, compute_sizes: bool) -> Result<Vec<GcRootEntry>>;
 
     fn remove_auto_roots(&self) -> Result<u64>;
+
+    fn clean_outputs(
+        &self,
+        job_ids: &[JobId],
+        older_than: Option<std::time::Duration>,
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String>;
+
+    fn prune_step_outputs(
+        &self,
+        job_ids: &[JobId],
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String>;
+
+    fn prune_images(
+        &self,
+        max_bytes: Option<u64>,
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String>;
 }
 
 pub trait Target:
-    TargetInfo + CommandRunner + ArtifactSync + FileOps + SlurmOps + JobRunner + GcOps
+    TargetInfo
+    + CommandRunner
+    + ArtifactSync
+    + FileOps
+    + SlurmOps
+    + SgeOps
+    + FluxOps
+    + JobRunner
+    + GcOps
 {
 }
 
 impl<T> Target for T where
-    T: TargetInfo + CommandRunner + ArtifactSync + FileOps + SlurmOps + JobRunner + GcOps
+    T: TargetInfo
+        + CommandRunner
+        + ArtifactSync
+        + FileOps
+        + SlurmOps
+        + SgeOps
+        + FluxOps
+        + JobRunner
+        + GcOps
 {
 }
 
+const SNAPSHOT_SEPARATOR: &str = "--repx-sacct--";
+const SACCT_FAILED: &str = "--repx-sacct-failed--";
+
+fn slurm_snapshot_command(user: &str, slurm_ids: &[u64]) -> String {
+    let squeue = format!("squeue -h -o '%i %j %t %r' -u '{}'", user);
+    if slurm_ids.is_empty() {
+        return squeue;
+    }
+    let ids: Vec<String> = slurm_ids.iter().map(|id| id.to_string()).collect();
+    format!(
+        "q=$({squeue}) || exit $?; printf '%s\\n' \"$q\"; echo '{separator}'; \
+         gone=$(printf '%s\\n' \"$q\" | awk -v ids='{ids}' \
+         'BEGIN {{ n = split(ids, id, \",\") }} {{ queued[$1] = 1 }} \
+         END {{ s = \"\"; for (i = 1; i <= n; i++) if (!(id[i] in queued)) s = s (s == \"\" ? \"\" : \",\") id[i]; print s }}'); \
+         [ -z \"$gone\" ] || sacct -n -P -o {fields} -j \"$gone\" || echo '{failed}'",
+        squeue = squeue,
+        separator = SNAPSHOT_SEPARATOR,
+        ids = ids.join(","),
+        fields = slurm_failure::SACCT_FIELDS,
+        failed = SACCT_FAILED,
+    )
+}
+
+fn parse_slurm_snapshot(
+    output: &str,
+    target_name: &str,
+) -> (HashMap<JobId, SlurmJobInfo>, HashMap<u64, SlurmFailure>) {
+    let Some((queue, accounting)) = output.split_once(SNAPSHOT_SEPARATOR) else {
+        return (parse_squeue(output), HashMap::new());
+    };
+    if accounting.lines().any(|line| line.trim() == SACCT_FAILED) {
+        tracing::debug!("sacct lookup on '{}' failed", target_name);
+    }
+    (parse_squeue(queue), slurm_failure::parse_sacct(accounting))
+}
+
 fn parse_squeue(output: &str) -> HashMap<JobId, SlurmJobInfo> {
     let mut jobs = HashMap::new();
     for line in output.lines() {
@@ -297,19 +584,24 @@ fn parse_squeue(output: &str) -> HashMap<JobId, SlurmJobInfo> {
             continue;
         }
 
-        if let Ok(slurm_id) = parts[0].parse::<u32>() {
+        if let Ok(slurm_id) = parts[0].parse::<u64>() {
             let repx_id = JobId::from(parts[1].to_string());
             let state = match parts[2] {
                 "PD" => SlurmState::Pending,
                 "R" => SlurmState::Running,
                 s => SlurmState::Other(s.to_string()),
             };
+            let reason = parts
+                .get(3)
+                .filter(|r| !matches!(**r, "None" | "(null)"))
+                .map(|r| r.to_string());
             jobs.insert(
                 repx_id.clone(),
                 SlurmJobInfo {
                     slurm_id,
                     repx_id,
                     state,
+                    reason,
                 },
             );
         }
@@ -317,6 +609,120 @@ fn parse_squeue(output: &str) -> HashMap<JobId, SlurmJobInfo> {
     jobs
 }
 
+fn parse_qstat_xml(output: &str) -> HashMap<JobId, SlurmJobInfo> {
+    let mut jobs = HashMap::new();
+    for entry in output.split("<job_list").skip(1) {
+        let entry = entry.split("</job_list>").next().unwrap_or_default();
+        let (Some(number), Some(name), Some(state)) = (
+            xml_text(entry, "JB_job_number"),
+            xml_text(entry, "JB_name"),
+            xml_text(entry, "state"),
+        ) else {
+            continue;
+        };
+        let Ok(sge_id) = number.parse::<u64>() else {
+            continue;
+        };
+        let Some(repx_id) = name.strip_prefix(repx_core::constants::sge::JOB_NAME_PREFIX) else {
+            continue;
+        };
+        // Codes combine letters: `r` running, `t` transferring, `qw` queued,
+        // `h` held (also while waiting on -hold_jid), `E` error, `d` deleted.
+        let (state, reason) = if state.contains('E') {
+            (SlurmState::Other(state.clone()), Some("error".to_string()))
+        } else if state.contains('r') || state.contains('t') {
+            (SlurmState::Running, None)
+        } else if state.contains('q') {
+            let reason = state.contains('h').then(|| "held".to_string());
+            (SlurmState::Pending, reason)
+        } else {
+            (SlurmState::Other(state.clone()), None)
+        };
+        let repx_id = JobId::from(repx_id.to_string());
+        if jobs
+            .get(&repx_id)
+            .is_some_and(|existing: &SlurmJobInfo| existing.state == SlurmState::Running)
+        {
+            continue;
+        }
+        jobs.insert(
+            repx_id.clone(),
+            SlurmJobInfo {
+                slurm_id: sge_id,
+                repx_id,
+                state,
+                reason,
+            },
+        );
+    }
+    jobs
+}
+
+fn parse_flux_jobs(output: &str) -> HashMap<JobId, SlurmJobInfo> {
+    let mut jobs = HashMap::new();
+    for line in output.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(id), Some(state), Some(name)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        let Ok(flux_id) = id.parse::<u64>() else {
+            continue;
+        };
+        let (state, reason) = match state {
+            "DEPEND" => (SlurmState::Pending, Some("dependency".to_string())),
+            "PRIORITY" => (SlurmState::Pending, Some("priority".to_string())),
+            "SCHED" => (SlurmState::Pending, None),
+            "RUN" | "CLEANUP" => (SlurmState::Running, None),
+            s => (SlurmState::Other(s.to_string()), None),
+        };
+        let repx_id = JobId::from(name.to_string());
+        jobs.insert(
+            repx_id.clone(),
+            SlurmJobInfo {
+                slurm_id: flux_id,
+                repx_id,
+                state,
+                reason,
+            },
+        );
+    }
+    jobs
+}
+
+fn xml_text(entry: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = entry.find(&open)? + open.len();
+    let len = entry[start..].find(&close)?;
+    Some(
+        entry[start..start + len]
+            .trim()
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+fn parse_step_markers(output: &str) -> HashSet<(usize, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let step_dir = Path::new(line.trim()).parent()?.parent()?;
+            let step = step_dir.file_name()?.to_str()?.strip_prefix("step-")?;
+            let branch = step_dir
+                .parent()?
+                .file_name()?
+                .to_str()?
+                .strip_prefix("branch-")?
+                .parse::<usize>()
+                .ok()?;
+            Some((branch, step.to_string()))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -324,11 +730,11 @@ mod tests {
     #[test]
     fn test_parse_squeue_output() {
         let squeue_output = r#"
-12345   job-one-running    R
-12346   job-two-pending    PD
+12345   job-one-running    R    None
+12346   job-two-pending    PD   Resources
 12347   job-three-other    CG
 garbage line to ignore
-12348   job-four-running   R
+12348   job-four-running   R    None
 "#;
         let parsed = parse_squeue(squeue_output);
         assert_eq!(parsed.len(), 4);
@@ -338,12 +744,14 @@ garbage line to ignore
             .expect("job-one-running must be present");
         assert_eq!(job_one.slurm_id, 12345);
         assert_eq!(job_one.state, SlurmState::Running);
+        assert_eq!(job_one.reason, None);
 
         let job_two = parsed
             .get(&JobId::from("job-two-pending"))
             .expect("job-two-pending must be present");
         assert_eq!(job_two.slurm_id, 12346);
         assert_eq!(job_two.state, SlurmState::Pending);
+        assert_eq!(job_two.reason.as_deref(), Some("Resources"));
 
         let job_three = parsed
             .get(&JobId::from("job-three-other"))
@@ -357,4 +765,167 @@ garbage line to ignore
         let parsed = parse_squeue("");
         assert!(parsed.is_empty());
     }
+
+    #[test]
+    fn test_parse_slurm_snapshot() {
+        let output = "4242 repx-train-1.0 R None\n--repx-sacct--\n4241|FAILED|1:0||||\n";
+        let (queued, records) = parse_slurm_snapshot(output, "cluster");
+        assert_eq!(queued[&JobId::from("repx-train-1.0")].slurm_id, 4242);
+        assert_eq!(records[&4241].state, "FAILED");
+
+        let (queued, records) = parse_slurm_snapshot("4242 repx-train-1.0 R None\n", "cluster");
+        assert_eq!(queued.len(), 1);
+        assert!(records.is_empty());
+
+        let (_, records) =
+            parse_slurm_snapshot("--repx-sacct--\n--repx-sacct-failed--\n", "cluster");
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn test_slurm_snapshot_only_accounts_jobs_missing_from_the_queue() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bin = tempfile::tempdir().expect("tempdir");
+        let fake = |name: &str, body: &str| {
+            let path = bin.path().join(name);
+            std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).expect("write fake");
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                .expect("chmod fake");
+        };
+        fake("squeue", "echo '4242 repx-train-1.0 R None'");
+        fake("sacct", "echo \"sacct $*\"");
+        let path = format!(
+            "{}:{}",
+            bin.path().display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        let run = |slurm_ids: &[u64]| {
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(slurm_snapshot_command("alice", slurm_ids))
+                .env("PATH", &path)
+                .output()
+                .expect("run snapshot");
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).expect("utf8")
+        };
+
+        let output = run(&[4241, 4242, 4243]);
+        assert!(output.starts_with("4242 repx-train-1.0 R None\n--repx-sacct--\n"));
+        assert!(output.contains("-j 4241,4243\n"), "{}", output);
+
+        let output = run(&[4242]);
+        assert!(!output.contains("sacct -n"), "{}", output);
+        assert_eq!(parse_slurm_snapshot(&output, "cluster").0.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_qstat_xml() {
+        let output = r#"<?xml version='1.0'?>
+<job_info  xmlns:xsd="http://arc.liv.ac.uk/repos/darcs/sge/source/dist/util/resources/schemas/qstat/qstat.xsd">
+  <queue_info>
+    <job_list state="running">
+      <JB_job_number>101</JB_job_number>
+      <JAT_prio>0.55500</JAT_prio>
+      <JB_name>repx-4f2a-train-1.0</JB_name>
+      <JB_owner>alice</JB_owner>
+      <state>r</state>
+      <queue_name>all.q@node1</queue_name>
+      <slots>4</slots>
+    </job_list>
+    <job_list state="running">
+      <JB_job_number>103</JB_job_number>
+      <JB_name>repx-9c1d-sweep-1.0-align</JB_name>
+      <state>r</state>
+      <tasks>1</tasks>
+    </job_list>
+  </queue_info>
+  <job_info>
+    <job_list state="pending">
+      <JB_job_number>102</JB_job_number>
+      <JB_name>repx-7b3e-eval-1.0</JB_name>
+      <state>hqw</state>
+    </job_list>
+    <job_list state="pending">
+      <JB_job_number>103</JB_job_number>
+      <JB_name>repx-9c1d-sweep-1.0-align</JB_name>
+      <state>qw</state>
+      <tasks>2-8:1</tasks>
+    </job_list>
+    <job_list state="pending">
+      <JB_job_number>104</JB_job_number>
+      <JB_name>interactive-session</JB_name>
+      <state>qw</state>
+    </job_list>
+    <job_list state="pending">
+      <JB_job_number>105</JB_job_number>
+      <JB_name>repx-1a2b-broken-1.0</JB_name>
+      <state>Eqw</state>
+    </job_list>
+  </job_info>
+</job_info>
+"#;
+        let parsed = parse_qstat_xml(output);
+        assert_eq!(parsed.len(), 4);
+
+        let train = &parsed[&JobId::from("4f2a-train-1.0")];
+        assert_eq!(train.slurm_id, 101);
+        assert_eq!(train.state, SlurmState::Running);
+
+        let eval = &parsed[&JobId::from("7b3e-eval-1.0")];
+        assert_eq!(eval.state, SlurmState::Pending);
+        assert_eq!(eval.reason.as_deref(), Some("held"));
+
+        let array = &parsed[&JobId::from("9c1d-sweep-1.0-align")];
+        assert_eq!(array.slurm_id, 103);
+        assert_eq!(array.state, SlurmState::Running);
+
+        let broken = &parsed[&JobId::from("1a2b-broken-1.0")];
+        assert_eq!(broken.state, SlurmState::Other("Eqw".into()));
+        assert_eq!(broken.reason.as_deref(), Some("error"));
+
+        assert!(parse_qstat_xml("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_flux_jobs() {
+        let output = "1234567890 RUN 4f2a-train-1.0
+1234567891 DEPEND 7b3e-eval-1.0
+1234567892 SCHED 9c1d-sweep-1.0
+1234567893 INACTIVE 1a2b-done-1.0
+f3Cj7ePSP PRIORITY malformed-id
+garbage
+";
+        let parsed = parse_flux_jobs(output);
+        assert_eq!(parsed.len(), 4);
+
+        let train = &parsed[&JobId::from("4f2a-train-1.0")];
+        assert_eq!(train.slurm_id, 1234567890);
+        assert_eq!(train.state, SlurmState::Running);
+
+        let eval = &parsed[&JobId::from("7b3e-eval-1.0")];
+        assert_eq!(eval.state, SlurmState::Pending);
+        assert_eq!(eval.reason.as_deref(), Some("dependency"));
+
+        let sweep = &parsed[&JobId::from("9c1d-sweep-1.0")];
+        assert_eq!(sweep.state, SlurmState::Pending);
+        assert_eq!(sweep.reason, None);
+
+        let done = &parsed[&JobId::from("1a2b-done-1.0")];
+        assert_eq!(done.state, SlurmState::Other("INACTIVE".into()));
+    }
+
+    #[test]
+    fn test_parse_step_markers() {
+        let output = "/base/outputs/job-a/branch-0/step-prep/repx/FAIL
+/base/outputs/job-a/branch-12/step-train-model/repx/FAIL
+/base/outputs/job-a/scatter/step-x/repx/FAIL
+garbage
+";
+        let parsed = parse_step_markers(output);
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed.contains(&(0, "prep".to_string())));
+        assert!(parsed.contains(&(12, "train-model".to_string())));
+    }
 }
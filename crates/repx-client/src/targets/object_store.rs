@@ -0,0 +1,416 @@
+use super::{
+    ArtifactSync, CommandRunner, FileOps, FluxOps, GcOps, GcRootEntry, JobRunner, SgeOps,
+    SlurmJobInfo, SlurmOps, Target, TargetHealth, TargetInfo,
+};
+use crate::error::Result;
+use crate::object_store::ObjectStore;
+use crate::ClientEvent;
+use repx_core::{
+    config, constants::dirs, engine, model::JobId, store::slurm_failure::SlurmFailure,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{mpsc::Sender, Arc},
+};
+
+pub struct ObjectStoreTarget {
+    inner: Arc<dyn Target>,
+    store: ObjectStore,
+}
+
+impl ObjectStoreTarget {
+    pub fn new(inner: Arc<dyn Target>, store: ObjectStore) -> Self {
+        Self { inner, store }
+    }
+
+    fn output_key<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        path.strip_prefix(self.inner.base_path())
+            .ok()
+            .filter(|key| key.starts_with(dirs::OUTPUTS))
+    }
+
+    fn missing_on_target(&self, path: &Path) -> bool {
+        !matches!(self.inner.remote_file_size(path), Ok(Some(_)))
+    }
+
+    fn read_published(&self, path: &Path) -> Result<Option<String>> {
+        let Some(key) = self.output_key(path) else {
+            return Ok(None);
+        };
+        Ok(self
+            .store
+            .read(key)?
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+    }
+}
+
+impl TargetInfo for ObjectStoreTarget {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn base_path(&self) -> &Path {
+        self.inner.base_path()
+    }
+
+    fn config(&self) -> &config::Target {
+        self.inner.config()
+    }
+
+    fn get_remote_path_str(&self, job_id: &JobId) -> String {
+        self.inner.get_remote_path_str(job_id)
+    }
+
+    fn artifacts_base_path(&self) -> PathBuf {
+        self.inner.artifacts_base_path()
+    }
+
+    fn health(&self) -> TargetHealth {
+        self.inner.health()
+    }
+
+    fn runtime_capabilities(&self) -> Option<super::RuntimeCapabilities> {
+        self.inner.runtime_capabilities()
+    }
+}
+
+impl CommandRunner for ObjectStoreTarget {
+    fn run_command(&self, command: &str, args: &[&str]) -> Result<String> {
+        self.inner.run_command(command, args)
+    }
+
+    fn spawn_command(&self, command: &str, args: &[&str]) -> Result<std::process::Child> {
+        self.inner.spawn_command(command, args)
+    }
+}
+
+impl ArtifactSync for ObjectStoreTarget {
+    fn get_missing_artifacts(&self, artifacts: &HashSet<PathBuf>) -> Result<HashSet<PathBuf>> {
+        self.inner.get_missing_artifacts(artifacts)
+    }
+
+    fn sync_artifact(&self, local_path: &Path, relative_path: &Path) -> Result<()> {
+        self.inner.sync_artifact(local_path, relative_path)
+    }
+
+    fn sync_lab_root(
+        &self,
+        local_lab_path: &Path,
+        event_sender: Option<&Sender<ClientEvent>>,
+    ) -> Result<()> {
+        self.inner.sync_lab_root(local_lab_path, event_sender)
+    }
+
+    fn sync_lab_root_metadata_only(
+        &self,
+        local_lab_path: &Path,
+        event_sender: Option<&Sender<ClientEvent>>,
+    ) -> Result<()> {
+        self.inner
+            .sync_lab_root_metadata_only(local_lab_path, event_sender)
+    }
+
+    fn sync_lab_from_tar_via_rsync(
+        &self,
+        tar_path: &Path,
+        event_sender: Option<&Sender<ClientEvent>>,
+    ) -> Result<()> {
+        self.inner
+            .sync_lab_from_tar_via_rsync(tar_path, event_sender)
+    }
+
+    fn sync_directory(&self, local_path: &Path, remote_path: &Path) -> Result<()> {
+        self.inner.sync_directory(local_path, remote_path)
+    }
+
+    fn sync_file(
+        &self,
+        local_path: &Path,
+        remote_path: &Path,
+        event_sender: Option<&Sender<ClientEvent>>,
+    ) -> Result<()> {
+        self.inner.sync_file(local_path, remote_path, event_sender)
+    }
+
+    fn fetch_directory(&self, remote_path: &Path, local_path: &Path) -> Result<()> {
+        match self.inner.fetch_directory(remote_path, local_path) {
+            Ok(()) => Ok(()),
+            Err(e) => match self.output_key(remote_path) {
+                Some(key) => {
+                    tracing::debug!(
+                        "Fetching '{}' from {} instead: {}",
+                        remote_path.display(),
+                        self.store.uri(),
+                        e
+                    );
+                    self.store.download_dir(key, local_path)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    fn sync_image_incrementally(
+        &self,
+        image_path: &Path,
+        image_tag: &str,
+        local_cache_root: &Path,
+    ) -> Result<()> {
+        self.inner
+            .sync_image_incrementally(image_path, image_tag, local_cache_root)
+    }
+
+    fn sync_artifacts_batch(
+        &self,
+        local_lab_path: &Path,
+        artifacts: &HashSet<PathBuf>,
+        event_sender: Option<&Sender<ClientEvent>>,
+    ) -> Result<()> {
+        self.inner
+            .sync_artifacts_batch(local_lab_path, artifacts, event_sender)
+    }
+}
+
+impl FileOps for ObjectStoreTarget {
+    fn write_remote_file(&self, path: &Path, content: &str) -> Result<()> {
+        self.inner.write_remote_file(path, content)
+    }
+
+    fn read_remote_file(&self, path: &Path) -> Result<String> {
+        match self.inner.read_remote_file(path) {
+            Ok(content) => Ok(content),
+            Err(e) => self.read_published(path)?.ok_or(e),
+        }
+    }
+
+    fn read_remote_file_tail(&self, path: &Path, line_count: u32) -> Result<Vec<String>> {
+        let inner = self.inner.read_remote_file_tail(path, line_count);
+        if matches!(&inner, Ok(lines) if !lines.is_empty()) || !self.missing_on_target(path) {
+            return inner;
+        }
+        match self.read_published(path)? {
+            Some(content) => {
+                let lines: Vec<&str> = content.lines().collect();
+                let start = lines.len().saturating_sub(line_count as usize);
+                Ok(lines[start..].iter().map(|line| line.to_string()).collect())
+            }
+            None => inner,
+        }
+    }
+
+    fn remote_file_size(&self, path: &Path) -> Result<Option<u64>> {
+        let inner = self.inner.remote_file_size(path);
+        if matches!(inner, Ok(Some(_))) {
+            return inner;
+        }
+        match self.output_key(path) {
+            Some(key) => match self.store.size(key)? {
+                Some(size) => Ok(Some(size)),
+                None => inner,
+            },
+            None => inner,
+        }
+    }
+
+    fn read_remote_file_from(&self, path: &Path, offset: u64) -> Result<String> {
+        let inner = self.inner.read_remote_file_from(path, offset);
+        if matches!(&inner, Ok(chunk) if !chunk.is_empty()) || !self.missing_on_target(path) {
+            return inner;
+        }
+        match self.read_published(path)? {
+            Some(content) => {
+                let start = usize::try_from(offset)
+                    .unwrap_or(usize::MAX)
+                    .min(content.len());
+                Ok(String::from_utf8_lossy(&content.as_bytes()[start..]).into_owned())
+            }
+            None => inner,
+        }
+    }
+}
+
+impl SlurmOps for ObjectStoreTarget {
+    fn scancel(&self, slurm_id: u64) -> Result<()> {
+        self.inner.scancel(slurm_id)
+    }
+
+    fn scancel_batch(&self, slurm_ids: &[u64]) -> Result<()> {
+        self.inner.scancel_batch(slurm_ids)
+    }
+
+    fn squeue(&self) -> Result<HashMap<JobId, SlurmJobInfo>> {
+        self.inner.squeue()
+    }
+
+    fn sacct(&self, slurm_ids: &[u64]) -> Result<HashMap<u64, SlurmFailure>> {
+        self.inner.sacct(slurm_ids)
+    }
+
+    fn slurm_snapshot(
+        &self,
+        slurm_ids: &[u64],
+    ) -> Result<(HashMap<JobId, SlurmJobInfo>, HashMap<u64, SlurmFailure>)> {
+        self.inner.slurm_snapshot(slurm_ids)
+    }
+}
+
+impl SgeOps for ObjectStoreTarget {
+    fn qdel(&self, job_ids: &[u64]) -> Result<()> {
+        self.inner.qdel(job_ids)
+    }
+
+    fn qstat(&self) -> Result<HashMap<JobId, SlurmJobInfo>> {
+        self.inner.qstat()
+    }
+}
+
+impl FluxOps for ObjectStoreTarget {
+    fn flux_cancel(&self, job_ids: &[u64]) -> Result<()> {
+        self.inner.flux_cancel(job_ids)
+    }
+
+    fn flux_jobs(&self) -> Result<HashMap<JobId, SlurmJobInfo>> {
+        self.inner.flux_jobs()
+    }
+}
+
+impl JobRunner for ObjectStoreTarget {
+    fn deploy_repx_binary(&self) -> Result<PathBuf> {
+        self.inner.deploy_repx_binary()
+    }
+
+    fn spawn_repx_job(
+        &self,
+        repx_binary_path: &Path,
+        args: &[String],
+    ) -> Result<std::process::Child> {
+        self.inner.spawn_repx_job(repx_binary_path, args)
+    }
+
+    fn check_outcome_markers(&self) -> Result<HashMap<JobId, engine::JobStatus>> {
+        self.inner.check_outcome_markers()
+    }
+
+    fn check_heartbeats(&self) -> Result<HashMap<JobId, std::time::Duration>> {
+        self.inner.check_heartbeats()
+    }
+
+    fn find_step_markers(&self, job_id: &JobId, marker: &str) -> Result<HashSet<(usize, String)>> {
+        self.inner.find_step_markers(job_id, marker)
+    }
+}
+
+impl GcOps for ObjectStoreTarget {
+    fn register_gc_root(&self, project_id: &str, lab_hash: &str) -> Result<()> {
+        self.inner.register_gc_root(project_id, lab_hash)
+    }
+
+    fn garbage_collect(
+        &self,
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String> {
+        self.inner.garbage_collect(dry_run, verbose)
+    }
+
+    fn pin_gc_root(&self, lab_hash: &str, name: &str) -> Result<()> {
+        self.inner.pin_gc_root(lab_hash, name)
+    }
+
+    fn unpin_gc_root(&self, name: &str) -> Result<()> {
+        self.inner.unpin_gc_root(name)
+    }
+
+    fn list_gc_roots(&self, compute_sizes: bool) -> Result<Vec<GcRootEntry>> {
+        self.inner.list_gc_roots(compute_sizes)
+    }
+
+    fn remove_auto_roots(&self) -> Result<u64> {
+        self.inner.remove_auto_roots()
+    }
+
+    fn clean_outputs(
+        &self,
+        job_ids: &[JobId],
+        older_than: Option<std::time::Duration>,
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String> {
+        self.inner
+            .clean_outputs(job_ids, older_than, dry_run, verbose)
+    }
+
+    fn prune_step_outputs(
+        &self,
+        job_ids: &[JobId],
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String> {
+        self.inner.prune_step_outputs(job_ids, dry_run, verbose)
+    }
+
+    fn prune_images(
+        &self,
+        max_bytes: Option<u64>,
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String> {
+        self.inner.prune_images(max_bytes, dry_run, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::targets::LocalTarget;
+
+    fn wrapped(base: &Path) -> ObjectStoreTarget {
+        let config: config::Target = toml::from_str(&format!(
+            "base_path = {:?}\n[object_store]\nuri = \"s3://bucket/lab\"\n",
+            base
+        ))
+        .expect("target config");
+        let store =
+            ObjectStore::new(config.object_store.clone().expect("object store")).expect("store");
+        let inner = LocalTarget {
+            name: "local".to_string(),
+            config,
+            local_tools_path: PathBuf::new(),
+            capabilities: Default::default(),
+        };
+        ObjectStoreTarget::new(Arc::new(inner), store)
+    }
+
+    #[test]
+    fn test_only_outputs_map_to_bucket_keys() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let target = wrapped(dir.path());
+        let log = dir.path().join("outputs/job-a/repx/stdout.log");
+        assert_eq!(
+            target.output_key(&log),
+            Some(Path::new("outputs/job-a/repx/stdout.log"))
+        );
+        assert_eq!(target.output_key(&dir.path().join("artifacts/x")), None);
+        assert_eq!(target.output_key(Path::new("/elsewhere/outputs/x")), None);
+    }
+
+    #[test]
+    fn test_reads_from_target_while_the_file_is_there() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let target = wrapped(dir.path());
+        let log = dir.path().join("outputs/job-a/repx/stdout.log");
+        fs_err::create_dir_all(log.parent().expect("log dir")).expect("create log dir");
+        fs_err::write(&log, "one\ntwo\nthree\n").expect("write log");
+
+        assert_eq!(target.remote_file_size(&log).expect("size"), Some(14));
+        assert_eq!(
+            target.read_remote_file_tail(&log, 2).expect("tail"),
+            vec!["two", "three"]
+        );
+        assert_eq!(
+            target.read_remote_file_from(&log, 8).expect("read"),
+            "three\n"
+        );
+    }
+}
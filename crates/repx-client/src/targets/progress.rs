@@ -0,0 +1,169 @@
+use crate::ClientEvent;
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::sync::{mpsc::Sender, Mutex};
+
+pub struct SyncProgress<'a> {
+    sender: &'a Sender<ClientEvent>,
+    total: u64,
+    state: Mutex<ProgressState>,
+}
+
+#[derive(Default)]
+struct ProgressState {
+    finished: u64,
+    in_flight: HashMap<usize, u64>,
+    reported: Option<u64>,
+}
+
+impl<'a> SyncProgress<'a> {
+    pub fn new(sender: &'a Sender<ClientEvent>, total: u64) -> Self {
+        let progress = Self {
+            sender,
+            total,
+            state: Mutex::new(ProgressState::default()),
+        };
+        if let Ok(mut state) = progress.state.lock() {
+            progress.report(&mut state);
+        }
+        progress
+    }
+
+    pub fn update(&self, id: usize, bytes: u64) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.in_flight.insert(id, bytes);
+        self.report(&mut state);
+    }
+
+    pub fn finish(&self, id: usize, planned: u64) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.in_flight.remove(&id);
+        state.finished = state.finished.saturating_add(planned);
+        self.report(&mut state);
+    }
+
+    fn report(&self, state: &mut ProgressState) {
+        let in_flight: u64 = state.in_flight.values().sum();
+        let bytes_done = state.finished.saturating_add(in_flight).min(self.total);
+        if state.reported == Some(bytes_done) {
+            return;
+        }
+        state.reported = Some(bytes_done);
+        if let Err(e) = self.sender.send(ClientEvent::SyncProgress {
+            bytes_done,
+            bytes_total: self.total,
+        }) {
+            tracing::debug!("Failed to send sync progress event: {}", e);
+        }
+    }
+}
+
+pub(crate) fn output_reporting(
+    cmd: &mut Command,
+    progress: Option<(&SyncProgress<'_>, usize)>,
+) -> std::io::Result<Output> {
+    let Some((progress, id)) = progress else {
+        return cmd.output();
+    };
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stderr = child.stderr.take();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut stderr) = stderr {
+            if let Err(e) = stderr.read_to_end(&mut buf) {
+                tracing::debug!("Failed to read rsync stderr: {}", e);
+            }
+        }
+        buf
+    });
+
+    if let Some(mut stdout) = child.stdout.take() {
+        // progress2 redraws one line with carriage returns.
+        let mut line = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let read = stdout.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            for &byte in &chunk[..read] {
+                if byte == b'\r' || byte == b'\n' {
+                    if let Some(bytes) = parse_progress2_bytes(&String::from_utf8_lossy(&line)) {
+                        progress.update(id, bytes);
+                    }
+                    line.clear();
+                } else {
+                    line.push(byte);
+                }
+            }
+        }
+    }
+
+    let status = child.wait()?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+    Ok(Output {
+        status,
+        stdout: Vec::new(),
+        stderr,
+    })
+}
+
+pub fn parse_progress2_bytes(line: &str) -> Option<u64> {
+    let mut fields = line.split_whitespace();
+    let bytes = fields.next()?;
+    if !fields.next()?.ends_with('%') {
+        return None;
+    }
+    // The thousands separator follows the locale.
+    let digits: String = bytes.chars().filter(char::is_ascii_digit).collect();
+    if digits.is_empty()
+        || !bytes
+            .chars()
+            .all(|c| c.is_ascii_digit() || ",.'".contains(c))
+    {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_parse_progress2_bytes() {
+        assert_eq!(
+            parse_progress2_bytes(
+                "      1,238,099  42%  146.38MB/s    0:00:03 (xfr#1, to-chk=5/9)"
+            ),
+            Some(1_238_099)
+        );
+        assert_eq!(
+            parse_progress2_bytes("          32.768   0%    0,00kB/s    0:00:00"),
+            Some(32_768)
+        );
+        assert_eq!(parse_progress2_bytes("sending incremental file list"), None);
+        assert_eq!(parse_progress2_bytes(""), None);
+    }
+
+    #[test]
+    fn test_sums_concurrent_transfers() {
+        let (tx, rx) = mpsc::channel();
+        let progress = SyncProgress::new(&tx, 100);
+        progress.update(0, 10);
+        progress.update(1, 30);
+        progress.update(0, 20);
+        progress.finish(0, 50);
+        progress.finish(1, 50);
+        let reported: Vec<u64> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                ClientEvent::SyncProgress { bytes_done, .. } => Some(bytes_done),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(reported, [0, 10, 40, 50, 80, 100]);
+    }
+}
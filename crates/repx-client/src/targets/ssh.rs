@@ -1,6 +1,9 @@
 use super::common::shell_quote;
+use super::progress::SyncProgress;
+use super::sync_manifest::SyncManifest;
 use super::{
-    ArtifactSync, CommandRunner, FileOps, GcOps, JobRunner, RemoteCommand, SlurmOps, TargetInfo,
+    ArtifactSync, CommandRunner, FileOps, FluxOps, GcOps, JobRunner, RemoteCommand, SgeOps,
+    SlurmOps, TargetInfo,
 };
 use crate::error::{ClientError, Result};
 use repx_core::{
@@ -11,16 +14,20 @@ use repx_core::{
     fs_utils::path_to_string,
     logging,
     model::JobId,
+    store::cas::{self, TreeEntry},
 };
 use std::{
     collections::HashSet,
     io::Write,
     path::{Path, PathBuf},
-    process::Command,
-    sync::mpsc::Sender,
+    process::{Command, Output, Stdio},
+    sync::{mpsc::Sender, Mutex, OnceLock},
+    time::{Duration, Instant},
 };
 
 const GC_ROOTS_KEEP: usize = 5;
+const MIN_FILES_TO_SHARD: usize = 64;
+const CAS_HASH_CACHE_FILE: &str = "cas-hashes.json";
 
 pub struct SshTarget {
     pub(crate) name: String,
@@ -29,9 +36,38 @@ pub struct SshTarget {
     pub(crate) local_tools_path: PathBuf,
     pub(crate) local_temp_path: PathBuf,
     pub(crate) host_tools_dir_name: String,
+    control_path: Option<PathBuf>,
+    master_seen: Mutex<Option<Instant>>,
+    remote_rsync: OnceLock<Option<String>>,
+    capabilities: OnceLock<super::RuntimeCapabilities>,
 }
 
 impl SshTarget {
+    pub(crate) fn new(
+        name: String,
+        address: String,
+        config: config::Target,
+        local_tools_path: PathBuf,
+        local_temp_path: PathBuf,
+        host_tools_dir_name: String,
+    ) -> Self {
+        let control_path = config
+            .ssh_control_persist()
+            .map(|_| control_socket_dir().join(control_socket_name(&address)));
+        Self {
+            name,
+            address,
+            config,
+            local_tools_path,
+            local_temp_path,
+            host_tools_dir_name,
+            control_path,
+            master_seen: Mutex::new(None),
+            remote_rsync: OnceLock::new(),
+            capabilities: OnceLock::new(),
+        }
+    }
+
     fn local_tool(&self, name: &str) -> PathBuf {
         let tool_path = self.local_tools_path.join(name);
         if tool_path.exists() {
@@ -42,7 +78,11 @@ impl SshTarget {
     }
 
     fn remote_tool(&self, name: &str) -> String {
-        if ["sbatch", "scancel", "squeue", "sacct", "sh"].contains(&name) {
+        if [
+            "sbatch", "scancel", "squeue", "sacct", "qsub", "qstat", "qdel", "qrls", "flux", "sh",
+        ]
+        .contains(&name)
+        {
             return name.to_string();
         }
 
@@ -55,12 +95,175 @@ impl SshTarget {
             .to_string()
     }
 
-    fn ssh_upload_file(&self, local_path: &Path, remote_path: &Path) -> Result<()> {
-        let local_file = std::fs::File::open(local_path).map_err(ClientError::Io)?;
+    fn control_options(&self) -> Vec<String> {
+        match &self.control_path {
+            Some(path) => vec![
+                "-o".to_string(),
+                "ControlMaster=no".to_string(),
+                "-o".to_string(),
+                format!("ControlPath={}", path.display()),
+            ],
+            None => Vec::new(),
+        }
+    }
 
-        let remote_cmd = format!("cat > {}", shell_quote(&remote_path.to_string_lossy()));
+    fn ensure_master(&self) {
+        let (Some(path), Some(persist)) = (&self.control_path, self.config.ssh_control_persist())
+        else {
+            return;
+        };
+        let mut seen = self.master_seen.lock().unwrap_or_else(|e| e.into_inner());
+        if seen.is_some_and(|at| at.elapsed() < persist) {
+            *seen = Some(Instant::now());
+            return;
+        }
+        if path.exists() && self.master_alive(path) {
+            *seen = Some(Instant::now());
+            return;
+        }
+        *seen = None;
+        if let Some(dir) = path.parent() {
+            if let Err(e) = create_private_dir(dir) {
+                tracing::debug!(
+                    "Failed to create ssh control dir '{}': {}",
+                    dir.display(),
+                    e
+                );
+                return;
+            }
+        }
+        if let Err(e) = fs_err::remove_file(path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::debug!("Failed to remove stale control socket: {}", e);
+            }
+        }
 
         let mut cmd = Command::new(self.local_tool("ssh"));
+        cmd.args(["-M", "-N", "-f"])
+            .arg("-o")
+            .arg(format!("ControlPath={}", path.display()))
+            .arg("-o")
+            .arg(format!("ControlPersist={}", persist_arg(persist)))
+            .arg(&self.address)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        logging::log_and_print_command(&cmd);
+        match cmd.status() {
+            Ok(status) if status.success() => *seen = Some(Instant::now()),
+            Ok(status) => tracing::debug!(
+                "ssh master for '{}' exited with {}; connecting directly",
+                self.address,
+                status
+            ),
+            Err(e) => tracing::debug!(
+                "Failed to start ssh master for '{}': {}; connecting directly",
+                self.address,
+                e
+            ),
+        }
+    }
+
+    fn recheck_master(&self) {
+        *self.master_seen.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.ensure_master();
+    }
+
+    fn master_alive(&self, path: &Path) -> bool {
+        Command::new(self.local_tool("ssh"))
+            .arg("-O")
+            .arg("check")
+            .arg("-o")
+            .arg(format!("ControlPath={}", path.display()))
+            .arg(&self.address)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    fn ssh_command(&self) -> Command {
+        self.ensure_master();
+        let mut cmd = Command::new(self.local_tool("ssh"));
+        cmd.args(self.control_options());
+        cmd
+    }
+
+    fn output_with_retry(&self, cmd: &mut Command) -> Result<Output> {
+        self.output_with_progress(cmd, None)
+    }
+
+    fn output_with_progress(
+        &self,
+        cmd: &mut Command,
+        progress: Option<(&SyncProgress<'_>, usize)>,
+    ) -> Result<Output> {
+        let policy = self.config.retry_policy();
+        let mut attempt = 1;
+        loop {
+            logging::log_and_print_command(cmd);
+            let output =
+                super::progress::output_reporting(cmd, progress).map_err(ClientError::Io)?;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if output.status.success()
+                || attempt >= policy.attempts
+                || !super::common::is_transient_failure(output.status.code(), &stderr)
+            {
+                return Ok(output);
+            }
+            let backoff = policy.backoff(attempt);
+            tracing::warn!(
+                "'{}' failed on target '{}' (attempt {}/{}), retrying in {}s: {}",
+                cmd.get_program().to_string_lossy(),
+                self.name,
+                attempt,
+                policy.attempts,
+                backoff.as_secs(),
+                stderr.trim()
+            );
+            std::thread::sleep(backoff);
+            self.recheck_master();
+            attempt += 1;
+        }
+    }
+
+    fn rsync_command(&self) -> Command {
+        let mut cmd = Command::new(self.local_tool("rsync"));
+        cmd.args(self.config.rsync.clone().unwrap_or_default().args());
+        if self.control_path.is_some() {
+            self.ensure_master();
+            let mut rsh = vec![shell_quote(&self.local_tool("ssh").to_string_lossy())];
+            rsh.extend(self.control_options().iter().map(|opt| shell_quote(opt)));
+            cmd.arg("-e").arg(rsh.join(" "));
+        }
+        cmd
+    }
+
+    fn ssh_upload_file(&self, local_path: &Path, remote_path: &Path) -> Result<()> {
+        self.ssh_upload_file_from(local_path, remote_path, 0)
+    }
+
+    fn ssh_upload_file_from(
+        &self,
+        local_path: &Path,
+        remote_path: &Path,
+        offset: u64,
+    ) -> Result<()> {
+        use std::io::{Seek, SeekFrom};
+        let mut local_file = std::fs::File::open(local_path).map_err(ClientError::Io)?;
+        local_file
+            .seek(SeekFrom::Start(offset))
+            .map_err(ClientError::Io)?;
+
+        let redirect = if offset > 0 { ">>" } else { ">" };
+        let remote_cmd = format!(
+            "cat {} {}",
+            redirect,
+            shell_quote(&remote_path.to_string_lossy())
+        );
+
+        let mut cmd = self.ssh_command();
         cmd.arg(&self.address)
             .arg(&remote_cmd)
             .stdin(local_file)
@@ -129,9 +332,287 @@ impl SshTarget {
 
         Ok(remote_dest_str)
     }
+
+    fn remote_rsync(&self) -> Result<Option<&str>> {
+        if let Some(path) = self.remote_rsync.get() {
+            return Ok(path.as_deref());
+        }
+        let path = self.deploy_rsync_binary()?;
+        let probe = format!(
+            "if {} --version >/dev/null 2>&1; then echo ok; fi",
+            shell_quote(&path)
+        );
+        let runs = self.run_command("sh", &["-c", &probe])?.trim() == "ok";
+        if !runs {
+            tracing::warn!(
+                "rsync does not run on target '{}'; falling back to tar over ssh, which copies every file in full on each sync",
+                self.name
+            );
+        }
+        Ok(self
+            .remote_rsync
+            .get_or_init(|| runs.then_some(path))
+            .as_deref())
+    }
+
+    fn tar_push(
+        &self,
+        local_root: &Path,
+        entries: &[PathBuf],
+        remote_root: &Path,
+        follow_symlinks: bool,
+    ) -> Result<()> {
+        if let Err(e) = fs_err::create_dir_all(&self.local_temp_path) {
+            tracing::debug!(
+                "Failed to create local temp dir '{}': {}",
+                self.local_temp_path.display(),
+                e
+            );
+        }
+        let mut list = tempfile::Builder::new()
+            .prefix("repx-tar-list-")
+            .tempfile_in(&self.local_temp_path)
+            .map_err(ClientError::Io)?;
+        for entry in entries {
+            writeln!(list, "{}", entry.to_string_lossy()).map_err(ClientError::Io)?;
+        }
+        list.flush().map_err(ClientError::Io)?;
+
+        let mut tar_cmd = Command::new(self.local_tool("tar"));
+        tar_cmd.arg("-C").arg(local_root).arg("-cf").arg("-");
+        if follow_symlinks {
+            tar_cmd.arg("-h");
+        }
+        tar_cmd
+            .arg("-T")
+            .arg(list.path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        logging::log_and_print_command(&tar_cmd);
+        let mut tar = tar_cmd.spawn().map_err(ClientError::Io)?;
+        let archive = tar.stdout.take().ok_or_else(|| {
+            ClientError::Config(CoreError::InvalidConfig {
+                detail: "Failed to capture stdout pipe of local tar".to_string(),
+            })
+        })?;
+
+        let remote = shell_quote(&remote_root.to_string_lossy());
+        // Directories arrive with their source permissions, which are
+        // read-only for store paths; later syncs must be able to write.
+        let unpack = format!(
+            "mkdir -p {0} && tar -C {0} -xf - && find {0} -type d ! -perm -u+w -exec chmod u+w {{}} +",
+            remote
+        );
+        let mut ssh_cmd = self.ssh_command();
+        ssh_cmd
+            .arg(&self.address)
+            .arg(format!("sh -c {}", shell_quote(&unpack)))
+            .stdin(archive);
+        logging::log_and_print_command(&ssh_cmd);
+        let ssh_output = ssh_cmd.output().map_err(ClientError::Io)?;
+        let tar_output = tar.wait_with_output().map_err(ClientError::Io)?;
+
+        if !tar_output.status.success() {
+            return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                "local tar failed: {}",
+                String::from_utf8_lossy(&tar_output.stderr)
+            ))));
+        }
+        if !ssh_output.status.success() {
+            return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                "tar over ssh to '{}' failed: {}",
+                self.name,
+                String::from_utf8_lossy(&ssh_output.stderr)
+            ))));
+        }
+        Ok(())
+    }
+
+    fn tar_pull(&self, remote_root: &Path, local_root: &Path) -> Result<()> {
+        let pack = format!(
+            "tar -C {} -cf - .",
+            shell_quote(&remote_root.to_string_lossy())
+        );
+        let mut ssh_cmd = self.ssh_command();
+        ssh_cmd
+            .arg(&self.address)
+            .arg(format!("sh -c {}", shell_quote(&pack)))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        logging::log_and_print_command(&ssh_cmd);
+        let mut ssh = ssh_cmd.spawn().map_err(ClientError::Io)?;
+        let archive = ssh.stdout.take().ok_or_else(|| {
+            ClientError::Config(CoreError::InvalidConfig {
+                detail: "Failed to capture stdout pipe of ssh".to_string(),
+            })
+        })?;
+
+        let mut tar_cmd = Command::new(self.local_tool("tar"));
+        tar_cmd
+            .arg("-C")
+            .arg(local_root)
+            .arg("-xf")
+            .arg("-")
+            .stdin(archive);
+        logging::log_and_print_command(&tar_cmd);
+        let tar_output = tar_cmd.output().map_err(ClientError::Io)?;
+        let ssh_output = ssh.wait_with_output().map_err(ClientError::Io)?;
+
+        if !ssh_output.status.success() {
+            return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                "tar over ssh from '{}' failed: {}",
+                self.name,
+                String::from_utf8_lossy(&ssh_output.stderr)
+            ))));
+        }
+        if !tar_output.status.success() {
+            return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                "local tar failed: {}",
+                String::from_utf8_lossy(&tar_output.stderr)
+            ))));
+        }
+        Ok(())
+    }
+
+    /// `<remote_path>.part` is only renamed once complete, so a truncated layer
+    /// never sits under the name the store listing takes as present.
+    fn upload_layer(
+        &self,
+        local_path: &Path,
+        remote_path: &Path,
+        remote_rsync_path: Option<&str>,
+    ) -> Result<()> {
+        let mut partial = remote_path.as_os_str().to_owned();
+        partial.push(".part");
+        let partial = PathBuf::from(partial);
+
+        let mut finish = RemoteCommand::new("mv")
+            .arg("-f")
+            .arg(&partial.to_string_lossy())
+            .arg(&remote_path.to_string_lossy());
+        match remote_rsync_path {
+            Some(remote_rsync_path) => {
+                let mut rsync_cmd = self.rsync_command();
+                rsync_cmd
+                    .arg("-tp")
+                    .arg("--partial")
+                    .arg("--append-verify")
+                    .arg(format!("--rsync-path={}", remote_rsync_path))
+                    .arg(local_path)
+                    .arg(format!("{}:{}", self.address, partial.display()));
+
+                let output = self.output_with_retry(&mut rsync_cmd)?;
+
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                        "rsync failed for layer {}: {}",
+                        local_path.display(),
+                        stderr
+                    ))));
+                }
+            }
+            None => {
+                let metadata = fs_err::metadata(local_path).map_err(ClientError::Io)?;
+                let len = metadata.len();
+                let offset = self
+                    .remote_file_size(&partial)?
+                    .filter(|size| *size <= len)
+                    .unwrap_or(0);
+                if offset > 0 {
+                    tracing::info!(
+                        "Resuming upload of {} at {} of {} bytes",
+                        local_path.display(),
+                        offset,
+                        len
+                    );
+                }
+                if offset < len || len == 0 {
+                    self.ssh_upload_file_from(local_path, &partial, offset)?;
+                }
+                let mode = format!(
+                    "{:o}",
+                    std::os::unix::fs::PermissionsExt::mode(&metadata.permissions()) & 0o7777
+                );
+                finish = RemoteCommand::new("chmod")
+                    .arg(&mode)
+                    .arg(&partial.to_string_lossy())
+                    .and(finish);
+            }
+        }
+
+        self.run_command("sh", &["-c", &finish.to_shell_string()])?;
+        Ok(())
+    }
+
+    /// Uploads one file without rsync, keeping its permission bits.
+    fn upload_file_without_rsync(&self, local_path: &Path, remote_path: &Path) -> Result<()> {
+        let metadata = fs_err::metadata(local_path).map_err(ClientError::Io)?;
+        self.ssh_upload_file(local_path, remote_path)?;
+        let mode = format!(
+            "{:o}",
+            std::os::unix::fs::PermissionsExt::mode(&metadata.permissions()) & 0o7777
+        );
+        let chmod_cmd = RemoteCommand::new("chmod")
+            .arg(&mode)
+            .arg(&remote_path.to_string_lossy());
+        self.run_command("sh", &["-c", &chmod_cmd.to_shell_string()])?;
+        Ok(())
+    }
+}
+
+// Socket paths are limited to about 100 bytes, so this cannot live in the
+// client's state directory.
+fn control_socket_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("repx-ssh-{}", nix::unistd::getuid()))
+}
+
+fn control_socket_name(address: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = format!("{:x}", Sha256::digest(address.as_bytes()));
+    digest[..16].to_string()
+}
+
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+}
+
+fn persist_arg(persist: Duration) -> String {
+    format!("{}s", persist.as_secs().max(1))
 }
 
 impl TargetInfo for SshTarget {
+    fn health(&self) -> super::TargetHealth {
+        let binary_path = super::find_local_runner_binary()
+            .and_then(|exe| super::compute_file_hash(&exe))
+            .map(|binary_hash| {
+                FsCache::new(self.base_path().to_path_buf()).path(&CacheKey::RemoteBinary {
+                    binary_hash,
+                    target: self.name.clone(),
+                })
+            });
+        super::health::probe(self, binary_path)
+    }
+
+    fn runtime_capabilities(&self) -> Option<super::RuntimeCapabilities> {
+        let tools_dir = self
+            .artifacts_base_path()
+            .join("host-tools")
+            .join(&self.host_tools_dir_name)
+            .join("bin");
+        super::capabilities::cached(
+            self,
+            &self.capabilities,
+            &tools_dir,
+            &self.host_tools_dir_name,
+        )
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
@@ -169,11 +650,10 @@ impl CommandRunner for SshTarget {
             parts.join(" ")
         };
 
-        let mut cmd = Command::new(self.local_tool("ssh"));
+        let mut cmd = self.ssh_command();
         cmd.arg(&self.address).arg(&remote_command_string);
 
-        logging::log_and_print_command(&cmd);
-        let output = cmd.output().map_err(ClientError::Io)?;
+        let output = self.output_with_retry(&mut cmd)?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -200,7 +680,7 @@ impl CommandRunner for SshTarget {
             parts.join(" ")
         };
 
-        let mut cmd = Command::new(self.local_tool("ssh"));
+        let mut cmd = self.ssh_command();
         cmd.arg(&self.address)
             .arg(&remote_command_string)
             .stdin(std::process::Stdio::piped())
@@ -214,12 +694,15 @@ impl CommandRunner for SshTarget {
 }
 
 impl SlurmOps for SshTarget {
-    fn scancel(&self, slurm_id: u32) -> Result<()> {
-        self.run_command("scancel", &[&slurm_id.to_string()])?;
-        Ok(())
+    fn scancel(&self, slurm_id: u64) -> Result<()> {
+        self.scancel_batch(&[slurm_id])
     }
 }
 
+impl SgeOps for SshTarget {}
+
+impl FluxOps for SshTarget {}
+
 impl SshTarget {
     fn find_lab_manifest_remote(&self, lab_hash: &str) -> Result<String> {
         let lab_dir = self.artifacts_base_path().join("lab");
@@ -248,21 +731,57 @@ impl SshTarget {
         local_path: &Path,
         remote_path: &Path,
         follow_symlinks: bool,
+        event_sender: Option<&Sender<super::super::ClientEvent>>,
     ) -> Result<()> {
-        let remote_rsync_path = self.deploy_rsync_binary()?;
+        let Some(remote_rsync_path) = self.remote_rsync()? else {
+            return self.tar_push(
+                local_path,
+                &[PathBuf::from(".")],
+                remote_path,
+                follow_symlinks,
+            );
+        };
+        let flags = if follow_symlinks { "-rLtp" } else { "-rltp" };
+        let shard = self.config.sync_concurrency() > 1;
+        let mut files = Vec::new();
+        let mut total_bytes = 0;
+        if shard || event_sender.is_some() {
+            for entry in walkdir::WalkDir::new(local_path)
+                .follow_links(follow_symlinks)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| !entry.file_type().is_dir())
+            {
+                total_bytes += entry.metadata().map_or(0, |m| m.len());
+                if let Ok(path) = entry.path().strip_prefix(local_path) {
+                    files.push(path.to_path_buf());
+                }
+            }
+        }
+        let progress = event_sender.map(|sender| SyncProgress::new(sender, total_bytes));
 
-        let mut rsync_cmd = Command::new(self.local_tool("rsync"));
-        let flags = if follow_symlinks { "-rLtpz" } else { "-rltpz" };
+        let sharded = shard && files.len() >= MIN_FILES_TO_SHARD;
+        if sharded {
+            self.sync_files_sharded(local_path, remote_path, &files, flags, progress.as_ref())?;
+        }
+
+        let mut rsync_cmd = self.rsync_command();
         rsync_cmd
             .arg(flags)
             .arg("--chmod=Du+w")
             .arg("--mkpath")
-            .arg(format!("--rsync-path={}", remote_rsync_path))
+            .arg(format!("--rsync-path={}", remote_rsync_path));
+        if progress.is_some() {
+            rsync_cmd.arg("--info=progress2");
+        }
+        rsync_cmd
             .arg(format!("{}/", local_path.display()))
             .arg(format!("{}:{}", self.address, remote_path.display()));
 
-        logging::log_and_print_command(&rsync_cmd);
-        let output = rsync_cmd.output().map_err(ClientError::Io)?;
+        let output = self.output_with_progress(
+            &mut rsync_cmd,
+            progress.as_ref().map(|progress| (progress, usize::MAX)),
+        )?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -271,94 +790,360 @@ impl SshTarget {
                 stderr
             ))));
         }
+        if let Some(progress) = &progress {
+            progress.finish(usize::MAX, if sharded { 0 } else { total_bytes });
+        }
 
         Ok(())
     }
 }
 
-impl ArtifactSync for SshTarget {
-    fn get_missing_artifacts(&self, artifacts: &HashSet<PathBuf>) -> Result<HashSet<PathBuf>> {
-        if artifacts.is_empty() {
-            return Ok(HashSet::new());
-        }
+impl SshTarget {
+    fn sync_files_sharded(
+        &self,
+        local_root: &Path,
+        remote_root: &Path,
+        files: &[PathBuf],
+        flags: &str,
+        progress: Option<&SyncProgress<'_>>,
+    ) -> Result<()> {
+        let concurrency = if files.len() < MIN_FILES_TO_SHARD {
+            1
+        } else {
+            self.config.sync_concurrency()
+        };
+        let sized: Vec<(PathBuf, u64)> = files
+            .iter()
+            .map(|path| {
+                let size = std::fs::metadata(local_root.join(path)).map_or(0, |m| m.len());
+                (path.clone(), size)
+            })
+            .collect();
+        let sizes: std::collections::HashMap<PathBuf, u64> = sized.iter().cloned().collect();
+        let shards = super::common::plan_shards(sized, concurrency);
 
-        let artifacts_base = self.artifacts_base_path();
-        let find_bin = self.remote_tool("find");
-        let mkdir_bin = self.remote_tool("mkdir");
+        if let Err(e) = fs_err::create_dir_all(&self.local_temp_path) {
+            tracing::debug!(
+                "Failed to create local temp dir '{}': {}",
+                self.local_temp_path.display(),
+                e
+            );
+        }
+        let Some(remote_rsync_path) = self.remote_rsync()? else {
+            self.tar_push(local_root, files, remote_root, flags.contains('L'))?;
+            if let Some(progress) = progress {
+                progress.finish(0, sizes.values().sum());
+            }
+            return Ok(());
+        };
+        if shards.len() > 1 {
+            tracing::info!(
+                "Syncing {} files to '{}' in {} concurrent shards",
+                files.len(),
+                self.name,
+                shards.len()
+            );
+        }
 
-        let find_cmd = RemoteCommand::new(&mkdir_bin)
-            .arg("-p")
-            .arg(&artifacts_base.to_string_lossy())
-            .and(
-                RemoteCommand::new("cd")
-                    .arg(&artifacts_base.to_string_lossy())
-                    .and(RemoteCommand::new(&find_bin).arg(".").arg("-type").arg("f")),
-            )
-            .or(RemoteCommand::new("true"));
+        let results: Vec<Result<()>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = shards
+                .iter()
+                .enumerate()
+                .map(|(idx, shard)| {
+                    let sizes = &sizes;
+                    scope.spawn(move || {
+                        self.sync_shard_with_retries(
+                            idx,
+                            local_root,
+                            remote_root,
+                            shard,
+                            flags,
+                            remote_rsync_path,
+                            progress,
+                        )?;
+                        if let Some(progress) = progress {
+                            let bytes = shard.iter().filter_map(|path| sizes.get(path)).sum();
+                            progress.finish(idx, bytes);
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle.join().unwrap_or_else(|_| {
+                        Err(ClientError::Config(CoreError::CommandFailed(
+                            "rsync shard thread panicked".to_string(),
+                        )))
+                    })
+                })
+                .collect()
+        });
+        results.into_iter().collect()
+    }
 
-        let output = self.run_command("sh", &["-c", &find_cmd.to_shell_string()])?;
+    fn sync_tree_content_addressed(
+        &self,
+        local_root: &Path,
+        event_sender: Option<&Sender<super::super::ClientEvent>>,
+    ) -> Result<()> {
+        let root = local_root.canonicalize().map_err(ClientError::Io)?;
+        if let Err(e) = fs_err::create_dir_all(&self.local_temp_path) {
+            tracing::debug!(
+                "Failed to create local temp dir '{}': {}",
+                self.local_temp_path.display(),
+                e
+            );
+        }
+        let entries =
+            super::common::scan_tree(&root, &self.local_temp_path.join(CAS_HASH_CACHE_FILE))?;
+        let mut manifest = String::new();
+        for entry in &entries {
+            let line = serde_json::to_string(entry).map_err(|e| {
+                ClientError::Config(CoreError::InvalidConfig {
+                    detail: format!("Failed to serialise tree manifest: {}", e),
+                })
+            })?;
+            manifest.push_str(&line);
+            manifest.push('\n');
+        }
 
-        let existing: HashSet<PathBuf> = output
+        let repx_bin = self.deploy_repx_binary()?;
+        let link_cmd = RemoteCommand::new(&repx_bin.to_string_lossy())
+            .arg("internal-cas-link")
+            .arg("--base-path")
+            .arg(&self.base_path().to_string_lossy());
+        let missing_cmd = link_cmd.clone().arg("--missing");
+        let missing: Vec<PathBuf> = self
+            .run_with_input(&missing_cmd.to_shell_string(), &manifest)?
             .lines()
-            .filter_map(|s| s.strip_prefix("./"))
+            .filter(|line| !line.is_empty())
             .map(PathBuf::from)
             .collect();
 
-        let missing = artifacts
+        let file_count = entries
             .iter()
-            .filter(|p| !existing.contains(*p))
-            .cloned()
-            .collect();
+            .filter(|entry| matches!(entry, TreeEntry::File { .. }))
+            .count();
+        tracing::info!(
+            "Uploading {} of {} distinct lab files to '{}'",
+            missing.len(),
+            file_count,
+            self.name
+        );
+        if !missing.is_empty() {
+            let mut sources = std::collections::HashMap::new();
+            for entry in &entries {
+                if let TreeEntry::File {
+                    path,
+                    hash,
+                    executable,
+                } = entry
+                {
+                    sources
+                        .entry(cas::blob_rel_path(hash, *executable)?)
+                        .or_insert_with(|| root.join(path));
+                }
+            }
+            let staging = tempfile::Builder::new()
+                .prefix("repx-cas-")
+                .tempdir_in(&self.local_temp_path)
+                .map_err(ClientError::Io)?;
+            for blob in &missing {
+                let source = sources.get(blob).ok_or_else(|| ClientError::InvalidPath {
+                    path: blob.clone(),
+                    reason: "target asked for a blob the lab does not contain".to_string(),
+                })?;
+                let staged = staging.path().join(blob);
+                if let Some(parent) = staged.parent() {
+                    fs_err::create_dir_all(parent).map_err(ClientError::Io)?;
+                }
+                std::os::unix::fs::symlink(source, &staged).map_err(ClientError::Io)?;
+            }
+            let progress = event_sender.map(|sender| {
+                let total = missing
+                    .iter()
+                    .filter_map(|blob| sources.get(blob))
+                    .map(|source| std::fs::metadata(source).map_or(0, |m| m.len()))
+                    .sum();
+                SyncProgress::new(sender, total)
+            });
+            self.sync_files_sharded(
+                staging.path(),
+                &self.artifacts_base_path(),
+                &missing,
+                "-rLtp",
+                progress.as_ref(),
+            )?;
+        }
 
-        Ok(missing)
+        self.run_with_input(&link_cmd.to_shell_string(), &manifest)?;
+        Ok(())
     }
 
-    fn sync_artifacts_batch(
+    fn read_sync_manifest(&self) -> Result<SyncManifest> {
+        let path = FsCache::new(self.base_path().to_path_buf()).path(&CacheKey::SyncManifest);
+        let quoted_path = path.to_string_lossy();
+        let cmd = RemoteCommand::new("[")
+            .arg("-f")
+            .arg(&quoted_path)
+            .arg("]")
+            .and(RemoteCommand::new(&self.remote_tool("cat")).arg(&quoted_path))
+            .or(RemoteCommand::new("true"));
+        let content = self.run_command("sh", &["-c", &cmd.to_shell_string()])?;
+        Ok(SyncManifest::parse(&content))
+    }
+
+    fn sync_tree_by_manifest(
         &self,
-        local_lab_path: &Path,
-        artifacts: &HashSet<PathBuf>,
+        local_root: &Path,
+        hash_cache: &Path,
         event_sender: Option<&Sender<super::super::ClientEvent>>,
     ) -> Result<()> {
-        if artifacts.is_empty() {
-            return Ok(());
+        let root = local_root.canonicalize().map_err(ClientError::Io)?;
+        let entries = super::common::scan_tree(&root, hash_cache)?;
+        let mut manifest = self.read_sync_manifest()?;
+        let changed: Vec<PathBuf> = manifest
+            .changed(&entries)
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        tracing::info!(
+            "Syncing {} of {} lab paths to '{}'",
+            changed.len(),
+            entries.len(),
+            self.name
+        );
+
+        if !changed.is_empty() {
+            let progress = event_sender.map(|sender| {
+                let total = changed
+                    .iter()
+                    .map(|path| std::fs::symlink_metadata(root.join(path)).map_or(0, |m| m.len()))
+                    .sum();
+                SyncProgress::new(sender, total)
+            });
+            self.sync_files_sharded(
+                &root,
+                &self.artifacts_base_path(),
+                &changed,
+                "-rltpI",
+                progress.as_ref(),
+            )?;
         }
 
-        if let Err(e) = fs_err::create_dir_all(&self.local_temp_path) {
-            tracing::debug!(
-                "Failed to create local temp dir '{}': {}",
-                self.local_temp_path.display(),
-                e
-            );
+        manifest.merge(entries);
+        let path = FsCache::new(self.base_path().to_path_buf()).path(&CacheKey::SyncManifest);
+        self.write_remote_file(&path, &manifest.render())
+    }
+
+    fn run_with_input(&self, command: &str, input: &str) -> Result<String> {
+        let mut child = self.spawn_command("sh", &["-c", command])?;
+        let mut stdin = child.stdin.take().ok_or_else(|| {
+            ClientError::Config(CoreError::InvalidConfig {
+                detail: "Failed to capture stdin pipe for remote command".to_string(),
+            })
+        })?;
+        let input = input.as_bytes().to_vec();
+        std::thread::spawn(move || {
+            if let Err(e) = stdin.write_all(&input) {
+                tracing::debug!("Failed to write to remote stdin pipe: {}", e);
+            }
+        });
+
+        let output = child.wait_with_output().map_err(ClientError::Io)?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(ClientError::TargetCommandFailed {
+                target: self.name.clone(),
+                source: CoreError::CommandFailed(format!(
+                    "Command '{}' failed on target '{}': {}",
+                    command, self.name, stderr
+                )),
+            });
         }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn sync_shard_with_retries(
+        &self,
+        idx: usize,
+        local_root: &Path,
+        remote_root: &Path,
+        shard: &[PathBuf],
+        flags: &str,
+        remote_rsync_path: &str,
+        progress: Option<&SyncProgress<'_>>,
+    ) -> Result<()> {
+        let policy = self.config.retry_policy();
+        let mut attempt = 1;
+        loop {
+            match self.sync_shard(
+                local_root,
+                remote_root,
+                shard,
+                flags,
+                remote_rsync_path,
+                progress.map(|progress| (progress, idx)),
+            ) {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < policy.attempts => {
+                    tracing::warn!(
+                        "rsync shard {} ({} files) failed on attempt {}/{}: {}",
+                        idx,
+                        shard.len(),
+                        attempt,
+                        policy.attempts,
+                        e
+                    );
+                    std::thread::sleep(policy.backoff(attempt));
+                    self.recheck_master();
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn sync_shard(
+        &self,
+        local_root: &Path,
+        remote_root: &Path,
+        shard: &[PathBuf],
+        flags: &str,
+        remote_rsync_path: &str,
+        progress: Option<(&SyncProgress<'_>, usize)>,
+    ) -> Result<()> {
         let mut temp_file = tempfile::Builder::new()
             .prefix("repx-sync-list-")
             .tempfile_in(&self.local_temp_path)
             .map_err(ClientError::Io)?;
-
-        for path in artifacts {
+        for path in shard {
             writeln!(temp_file, "{}", path.to_string_lossy()).map_err(ClientError::Io)?;
         }
         temp_file.flush().map_err(ClientError::Io)?;
 
-        let remote_rsync_path = self.deploy_rsync_binary()?;
-
-        let mut rsync_cmd = Command::new(self.local_tool("rsync"));
+        let mut rsync_cmd = self.rsync_command();
         rsync_cmd
-            .arg("-rLtpz")
+            .arg(flags)
             .arg("--chmod=Du+w")
-            .arg(format!("--rsync-path={}", remote_rsync_path))
+            .arg("--mkpath")
+            .arg(format!("--rsync-path={}", remote_rsync_path));
+        if progress.is_some() {
+            rsync_cmd.arg("--info=progress2");
+        }
+        rsync_cmd
             .arg("--files-from")
             .arg(temp_file.path())
             .arg("./")
-            .arg(format!(
-                "{}:{}",
-                self.address,
-                self.artifacts_base_path().display()
-            ))
-            .current_dir(local_lab_path);
+            .arg(format!("{}:{}", self.address, remote_root.display()))
+            .current_dir(local_root);
 
         logging::log_and_print_command(&rsync_cmd);
-        let output = rsync_cmd.output().map_err(ClientError::Io)?;
+        let output =
+            super::progress::output_reporting(&mut rsync_cmd, progress).map_err(ClientError::Io)?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -367,18 +1152,47 @@ impl ArtifactSync for SshTarget {
                 stderr
             ))));
         }
+        Ok(())
+    }
+}
 
-        if let Some(sender) = event_sender {
-            for path in artifacts {
-                if let Err(e) = sender
-                    .send(super::super::ClientEvent::SyncingArtifactProgress { path: path.clone() })
-                {
-                    tracing::debug!("Failed to send artifact sync progress event: {}", e);
-                }
-            }
+impl ArtifactSync for SshTarget {
+    fn get_missing_artifacts(&self, artifacts: &HashSet<PathBuf>) -> Result<HashSet<PathBuf>> {
+        if artifacts.is_empty() {
+            return Ok(HashSet::new());
         }
+        let manifest = self.read_sync_manifest()?;
+        Ok(artifacts
+            .iter()
+            .filter(|path| !manifest.contains(path))
+            .cloned()
+            .collect())
+    }
 
-        Ok(())
+    fn sync_artifacts_batch(
+        &self,
+        local_lab_path: &Path,
+        artifacts: &HashSet<PathBuf>,
+        event_sender: Option<&Sender<super::super::ClientEvent>>,
+    ) -> Result<()> {
+        if artifacts.is_empty() {
+            return Ok(());
+        }
+        let files: Vec<PathBuf> = artifacts.iter().cloned().collect();
+        let progress = event_sender.map(|sender| {
+            let total = files
+                .iter()
+                .map(|path| std::fs::metadata(local_lab_path.join(path)).map_or(0, |m| m.len()))
+                .sum();
+            SyncProgress::new(sender, total)
+        });
+        self.sync_files_sharded(
+            local_lab_path,
+            &self.artifacts_base_path(),
+            &files,
+            "-rLtp",
+            progress.as_ref(),
+        )
     }
 
     fn sync_artifact(&self, local_path: &Path, relative_path: &Path) -> Result<()> {
@@ -392,7 +1206,7 @@ impl ArtifactSync for SshTarget {
         }
 
         if local_path.is_dir() {
-            self.sync_directory_impl(local_path, &dest, true)?;
+            self.sync_directory_impl(local_path, &dest, true, None)?;
         } else {
             self.ssh_upload_file(local_path, &dest)?;
 
@@ -413,9 +1227,28 @@ impl ArtifactSync for SshTarget {
         Ok(())
     }
 
-    fn sync_lab_root(&self, local_lab_path: &Path) -> Result<()> {
+    fn sync_lab_root(
+        &self,
+        local_lab_path: &Path,
+        event_sender: Option<&Sender<super::super::ClientEvent>>,
+    ) -> Result<()> {
         let remote_artifacts_base = self.artifacts_base_path();
-        self.sync_directory(local_lab_path, &remote_artifacts_base)?;
+        if self.config.content_addressed_store {
+            self.sync_tree_content_addressed(local_lab_path, event_sender)?;
+        } else {
+            if let Err(e) = fs_err::create_dir_all(&self.local_temp_path) {
+                tracing::debug!(
+                    "Failed to create local temp dir '{}': {}",
+                    self.local_temp_path.display(),
+                    e
+                );
+            }
+            self.sync_tree_by_manifest(
+                local_lab_path,
+                &self.local_temp_path.join(CAS_HASH_CACHE_FILE),
+                event_sender,
+            )?;
+        }
 
         let chmod_bin = self.remote_tool("chmod");
         let cmd = RemoteCommand::new(&chmod_bin)
@@ -426,15 +1259,28 @@ impl ArtifactSync for SshTarget {
         Ok(())
     }
 
-    fn sync_lab_root_metadata_only(&self, local_lab_path: &Path) -> Result<()> {
+    fn sync_lab_root_metadata_only(
+        &self,
+        local_lab_path: &Path,
+        _event_sender: Option<&Sender<super::super::ClientEvent>>,
+    ) -> Result<()> {
         let remote_artifacts_base = self.artifacts_base_path();
-        let remote_rsync_path = self.deploy_rsync_binary()?;
-
         let resolved = local_lab_path.canonicalize().map_err(ClientError::Io)?;
 
-        let mut rsync_cmd = Command::new(self.local_tool("rsync"));
+        let Some(remote_rsync_path) = self.remote_rsync()? else {
+            let mut entries = Vec::new();
+            for entry in fs_err::read_dir(&resolved).map_err(ClientError::Io)? {
+                let name = entry.map_err(ClientError::Io)?.file_name();
+                if name != "jobs" {
+                    entries.push(PathBuf::from(name));
+                }
+            }
+            return self.tar_push(&resolved, &entries, &remote_artifacts_base, false);
+        };
+
+        let mut rsync_cmd = self.rsync_command();
         rsync_cmd
-            .arg("-rltpz")
+            .arg("-rltp")
             .arg("--chmod=Du+w")
             .arg("--mkpath")
             .arg("--exclude=/jobs")
@@ -446,8 +1292,7 @@ impl ArtifactSync for SshTarget {
                 remote_artifacts_base.display()
             ));
 
-        logging::log_and_print_command(&rsync_cmd);
-        let output = rsync_cmd.output().map_err(ClientError::Io)?;
+        let output = self.output_with_retry(&mut rsync_cmd)?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -466,9 +1311,12 @@ impl ArtifactSync for SshTarget {
         Ok(())
     }
 
-    fn sync_lab_from_tar_via_rsync(&self, tar_path: &Path) -> Result<()> {
+    fn sync_lab_from_tar_via_rsync(
+        &self,
+        tar_path: &Path,
+        event_sender: Option<&Sender<super::super::ClientEvent>>,
+    ) -> Result<()> {
         let remote_artifacts_base = self.artifacts_base_path();
-        let remote_rsync_path = self.deploy_rsync_binary()?;
 
         let tmp_dir = tempfile::tempdir().map_err(ClientError::Io)?;
         let extract_root = tmp_dir.path();
@@ -482,28 +1330,15 @@ impl ArtifactSync for SshTarget {
         );
         crate::tar_extract::extract_tar_to_dir(tar_path, extract_root)?;
 
-        let mut rsync_cmd = Command::new(self.local_tool("rsync"));
-        rsync_cmd
-            .arg("-rltpz")
-            .arg("--chmod=Du+w")
-            .arg("--mkpath")
-            .arg(format!("--rsync-path={}", remote_rsync_path))
-            .arg(format!("{}/", extract_root.display()))
-            .arg(format!(
-                "{}:{}",
-                self.address,
-                remote_artifacts_base.display()
-            ));
-
-        logging::log_and_print_command(&rsync_cmd);
-        let output = rsync_cmd.output().map_err(ClientError::Io)?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(ClientError::Config(CoreError::CommandFailed(format!(
-                "rsync from tar extraction failed: {}",
-                stderr
-            ))));
+        if self.config.content_addressed_store {
+            self.sync_tree_content_addressed(extract_root, event_sender)?;
+        } else {
+            let hash_dir = tempfile::tempdir().map_err(ClientError::Io)?;
+            self.sync_tree_by_manifest(
+                extract_root,
+                &hash_dir.path().join(CAS_HASH_CACHE_FILE),
+                event_sender,
+            )?;
         }
 
         let chmod_bin = self.remote_tool("chmod");
@@ -516,11 +1351,16 @@ impl ArtifactSync for SshTarget {
     }
 
     fn sync_directory(&self, local_path: &Path, remote_path: &Path) -> Result<()> {
-        self.sync_directory_impl(local_path, remote_path, false)
+        self.sync_directory_impl(local_path, remote_path, false, None)
     }
 
-    fn sync_file(&self, local_path: &Path, remote_path: &Path) -> Result<()> {
-        let remote_rsync_path = self.deploy_rsync_binary()?;
+    fn sync_file(
+        &self,
+        local_path: &Path,
+        remote_path: &Path,
+        event_sender: Option<&Sender<super::super::ClientEvent>>,
+    ) -> Result<()> {
+        let remote_rsync_path = self.remote_rsync()?;
 
         if let Some(parent) = remote_path.parent() {
             let mkdir_cmd = RemoteCommand::new("mkdir")
@@ -528,16 +1368,27 @@ impl ArtifactSync for SshTarget {
                 .arg(&parent.to_string_lossy());
             self.run_command("sh", &["-c", &mkdir_cmd.to_shell_string()])?;
         }
+        let Some(remote_rsync_path) = remote_rsync_path else {
+            return self.upload_file_without_rsync(local_path, remote_path);
+        };
 
-        let mut rsync_cmd = Command::new(self.local_tool("rsync"));
+        let size = std::fs::metadata(local_path).map_or(0, |m| m.len());
+        let progress = event_sender.map(|sender| SyncProgress::new(sender, size));
+        let mut rsync_cmd = self.rsync_command();
+        rsync_cmd
+            .arg("-Ltp")
+            .arg(format!("--rsync-path={}", remote_rsync_path));
+        if progress.is_some() {
+            rsync_cmd.arg("--info=progress2");
+        }
         rsync_cmd
-            .arg("-Ltpz")
-            .arg(format!("--rsync-path={}", remote_rsync_path))
             .arg(local_path.to_string_lossy().as_ref())
             .arg(format!("{}:{}", self.address, remote_path.display()));
 
-        logging::log_and_print_command(&rsync_cmd);
-        let output = rsync_cmd.output().map_err(ClientError::Io)?;
+        let output = self.output_with_progress(
+            &mut rsync_cmd,
+            progress.as_ref().map(|progress| (progress, 0)),
+        )?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -546,6 +1397,35 @@ impl ArtifactSync for SshTarget {
                 stderr
             ))));
         }
+        if let Some(progress) = &progress {
+            progress.finish(0, size);
+        }
+
+        Ok(())
+    }
+
+    fn fetch_directory(&self, remote_path: &Path, local_path: &Path) -> Result<()> {
+        fs_err::create_dir_all(local_path).map_err(ClientError::Io)?;
+        let Some(remote_rsync_path) = self.remote_rsync()? else {
+            return self.tar_pull(remote_path, local_path);
+        };
+
+        let mut rsync_cmd = self.rsync_command();
+        rsync_cmd
+            .arg("-rltp")
+            .arg(format!("--rsync-path={}", remote_rsync_path))
+            .arg(format!("{}:{}/", self.address, remote_path.display()))
+            .arg(local_path);
+
+        let output = self.output_with_retry(&mut rsync_cmd)?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(ClientError::Config(CoreError::CommandFailed(format!(
+                "rsync directory fetch failed: {}",
+                stderr
+            ))));
+        }
 
         Ok(())
     }
@@ -615,7 +1495,7 @@ impl ArtifactSync for SshTarget {
 
             let remote_image_dir = remote_images.join(image_dir_name);
 
-            self.sync_directory_impl(image_path, &remote_image_dir, true)?;
+            self.sync_directory_impl(image_path, &remote_image_dir, true, None)?;
 
             let ln_cmd = RemoteCommand::new("cd")
                 .arg(&remote_images.to_string_lossy())
@@ -677,28 +1557,14 @@ impl ArtifactSync for SshTarget {
         }
 
         if !layers_to_sync.is_empty() {
-            let remote_rsync_path = self.deploy_rsync_binary()?;
+            let remote_rsync_path = self.remote_rsync()?;
 
             for (local_layer_path, layer_name) in &layers_to_sync {
-                let remote_dest = remote_store.join(layer_name);
-
-                let mut rsync_cmd = Command::new(self.local_tool("rsync"));
-                rsync_cmd
-                    .arg("-tpz")
-                    .arg(format!("--rsync-path={}", remote_rsync_path))
-                    .arg(local_layer_path)
-                    .arg(format!("{}:{}", self.address, remote_dest.display()));
-
-                logging::log_and_print_command(&rsync_cmd);
-                let output = rsync_cmd.output().map_err(ClientError::Io)?;
-
-                if !output.status.success() {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    return Err(ClientError::Config(CoreError::CommandFailed(format!(
-                        "rsync failed for layer {}: {}",
-                        layer_name, stderr
-                    ))));
-                }
+                self.upload_layer(
+                    local_layer_path,
+                    &remote_store.join(layer_name),
+                    remote_rsync_path,
+                )?;
             }
         }
 
@@ -789,6 +1655,43 @@ impl FileOps for SshTarget {
         Ok(output.lines().map(String::from).collect())
     }
 
+    fn remote_file_size(&self, path: &Path) -> Result<Option<u64>> {
+        let quoted_path = path.to_string_lossy();
+        let cmd = RemoteCommand::new("[")
+            .arg("-f")
+            .arg(&quoted_path)
+            .arg("]")
+            .and(
+                RemoteCommand::new(&self.remote_tool("wc"))
+                    .arg("-c")
+                    .arg(&quoted_path),
+            )
+            .or(RemoteCommand::new("true"));
+
+        let output = self.run_command("sh", &["-c", &cmd.to_shell_string()])?;
+        Ok(output
+            .split_whitespace()
+            .next()
+            .and_then(|size| size.parse().ok()))
+    }
+
+    fn read_remote_file_from(&self, path: &Path, offset: u64) -> Result<String> {
+        let quoted_path = path.to_string_lossy();
+        let cmd = RemoteCommand::new("[")
+            .arg("-f")
+            .arg(&quoted_path)
+            .arg("]")
+            .and(
+                RemoteCommand::new(&self.remote_tool("tail"))
+                    .arg("-c")
+                    .arg(&format!("+{}", offset + 1))
+                    .arg(&quoted_path),
+            )
+            .or(RemoteCommand::new("true"));
+
+        self.run_command("sh", &["-c", &cmd.to_shell_string()])
+    }
+
     fn write_remote_file(&self, path: &Path, content: &str) -> Result<()> {
         let parent = path.parent().ok_or_else(|| ClientError::InvalidPath {
             path: path.to_path_buf(),
@@ -803,7 +1706,7 @@ impl FileOps for SshTarget {
             .arg(&parent.to_string_lossy())
             .and(RemoteCommand::new(&cat_bin).redirect_out(&path.to_string_lossy()));
 
-        let mut cmd = Command::new(self.local_tool("ssh"));
+        let mut cmd = self.ssh_command();
         cmd.arg(&self.address)
             .arg(remote_command.to_shell_string())
             .stdin(std::process::Stdio::piped())
@@ -914,9 +1817,12 @@ impl JobRunner for SshTarget {
     ) -> Result<std::process::Child> {
         let remote_cmd = RemoteCommand::new(&repx_binary_path.to_string_lossy()).args(args);
 
-        let mut cmd = Command::new(self.local_tool("ssh"));
+        let mut cmd = self.ssh_command();
         cmd.arg(&self.address)
-            .arg(remote_cmd.to_shell_string())
+            .arg(
+                self.config
+                    .with_setup_commands(&remote_cmd.to_shell_string()),
+            )
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
 
@@ -1109,6 +2015,77 @@ impl GcOps for SshTarget {
         self.run_command("sh", &["-c", &cmd.to_shell_string()])
     }
 
+    fn clean_outputs(
+        &self,
+        job_ids: &[JobId],
+        older_than: Option<std::time::Duration>,
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String> {
+        let repx_bin = self.deploy_repx_binary()?;
+        let mut cmd = RemoteCommand::new(&repx_bin.to_string_lossy())
+            .args(verbose.as_args())
+            .arg("internal-clean")
+            .arg("--base-path")
+            .arg(&self.base_path().to_string_lossy());
+        if let Some(age) = older_than {
+            cmd = cmd.arg("--older-than-secs").arg(&age.as_secs().to_string());
+        }
+        if dry_run {
+            cmd = cmd.arg("--dry-run");
+        }
+        for job_id in job_ids {
+            cmd = cmd.arg(job_id.as_str());
+        }
+
+        self.run_command("sh", &["-c", &cmd.to_shell_string()])
+    }
+
+    fn prune_step_outputs(
+        &self,
+        job_ids: &[JobId],
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String> {
+        let repx_bin = self.deploy_repx_binary()?;
+        let mut cmd = RemoteCommand::new(&repx_bin.to_string_lossy())
+            .args(verbose.as_args())
+            .arg("internal-prune")
+            .arg("--base-path")
+            .arg(&self.base_path().to_string_lossy());
+        if dry_run {
+            cmd = cmd.arg("--dry-run");
+        }
+        for job_id in job_ids {
+            cmd = cmd.arg(job_id.as_str());
+        }
+
+        self.run_command("sh", &["-c", &cmd.to_shell_string()])
+    }
+
+    fn prune_images(
+        &self,
+        max_bytes: Option<u64>,
+        dry_run: bool,
+        verbose: repx_core::logging::Verbosity,
+    ) -> Result<String> {
+        let repx_bin = self.deploy_repx_binary()?;
+        let mut cmd = RemoteCommand::new(&repx_bin.to_string_lossy())
+            .args(verbose.as_args())
+            .arg("internal-images-prune");
+        for dir in super::common::image_cache_dirs(self.base_path(), self.config()) {
+            cmd = cmd.arg("--cache-dir").arg(&dir.to_string_lossy());
+        }
+        if let Some(max_bytes) = max_bytes {
+            cmd = cmd.arg("--max-bytes").arg(&max_bytes.to_string());
+        }
+        if dry_run {
+            cmd = cmd.arg("--dry-run");
+        }
+
+        self.run_command("sh", &["-c", &cmd.to_shell_string()])
+    }
+
     fn remove_auto_roots(&self) -> Result<u64> {
         let auto_dir = self.base_path().join(dirs::GCROOTS).join("auto");
 
@@ -0,0 +1,115 @@
+use repx_core::store::cas::TreeEntry;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+#[derive(Debug, Default)]
+pub(crate) struct SyncManifest {
+    entries: BTreeMap<String, TreeEntry>,
+}
+
+fn entry_path(entry: &TreeEntry) -> &str {
+    match entry {
+        TreeEntry::File { path, .. }
+        | TreeEntry::Symlink { path, .. }
+        | TreeEntry::Dir { path } => path,
+    }
+}
+
+impl SyncManifest {
+    pub(crate) fn parse(content: &str) -> Self {
+        let entries = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str::<TreeEntry>(line) {
+                Ok(entry) => Some((entry_path(&entry).to_string(), entry)),
+                Err(e) => {
+                    tracing::debug!("Ignoring sync manifest line '{}': {}", line, e);
+                    None
+                }
+            })
+            .collect();
+        Self { entries }
+    }
+
+    pub(crate) fn render(&self) -> String {
+        let mut content = String::new();
+        for entry in self.entries.values() {
+            if let Ok(line) = serde_json::to_string(entry) {
+                content.push_str(&line);
+                content.push('\n');
+            }
+        }
+        content
+    }
+
+    pub(crate) fn contains(&self, path: &Path) -> bool {
+        self.entries.contains_key(path.to_string_lossy().as_ref())
+    }
+
+    pub(crate) fn changed<'a>(&self, local: &'a [TreeEntry]) -> Vec<&'a str> {
+        let parents: HashSet<&str> = local
+            .iter()
+            .filter_map(|entry| Path::new(entry_path(entry)).parent())
+            .filter_map(Path::to_str)
+            .collect();
+        local
+            .iter()
+            .filter(|entry| self.entries.get(entry_path(entry)) != Some(*entry))
+            .filter(|entry| {
+                !matches!(entry, TreeEntry::Dir { path } if parents.contains(path.as_str()))
+            })
+            .map(entry_path)
+            .collect()
+    }
+
+    pub(crate) fn merge(&mut self, local: Vec<TreeEntry>) {
+        for entry in local {
+            self.entries.insert(entry_path(&entry).to_string(), entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, hash: &str) -> TreeEntry {
+        TreeEntry::File {
+            path: path.to_string(),
+            hash: hash.to_string(),
+            executable: false,
+        }
+    }
+
+    #[test]
+    fn test_changed_compares_content() {
+        let mut remote = SyncManifest::default();
+        remote.merge(vec![
+            TreeEntry::Dir {
+                path: "jobs".to_string(),
+            },
+            file("jobs/a.sh", "aaa"),
+            file("jobs/b.sh", "bbb"),
+            file("other-lab/c.sh", "ccc"),
+        ]);
+        let remote = SyncManifest::parse(&remote.render());
+
+        let local = vec![
+            TreeEntry::Dir {
+                path: "jobs".to_string(),
+            },
+            file("jobs/a.sh", "aaa"),
+            file("jobs/b.sh", "b2b"),
+            file("jobs/new.sh", "ddd"),
+            TreeEntry::Dir {
+                path: "empty".to_string(),
+            },
+        ];
+        assert_eq!(
+            remote.changed(&local),
+            ["jobs/b.sh", "jobs/new.sh", "empty"]
+        );
+        assert!(remote.contains(Path::new("other-lab/c.sh")));
+        assert!(!remote.contains(Path::new("jobs/new.sh")));
+    }
+}
@@ -27,6 +27,21 @@ fn test_submit_options_has_cancel_flag() {
         .load(Ordering::SeqCst));
 }
 
+#[test]
+fn test_cancel_report_describes_handles() {
+    use repx_client::CancelReport;
+    use repx_core::model::SchedulerType;
+
+    let report = CancelReport {
+        scheduler: Some(SchedulerType::Sge),
+        slurm_ids: vec![41, 42],
+        pids: vec![977],
+        cloud_ids: vec![],
+    };
+    assert_eq!(report.describe_handles(), "SGE 41, 42, PID 977");
+    assert_eq!(CancelReport::default().describe_handles(), "");
+}
+
 #[test]
 fn test_child_process_can_be_killed_while_waiting() {
     use nix::sys::signal::{kill, Signal};
@@ -76,11 +76,43 @@ fn test_local_target_smart_sync() {
             default_execution_type: None,
             mount_host_paths: false,
             mount_paths: vec![],
+            network: None,
+            seccomp: None,
+            pre_run: None,
+            post_run: None,
+            image_cache_max_size: None,
+            heartbeat_interval_secs: None,
+            ssh_control_persist_secs: None,
+            sync_concurrency: None,
+            rsync: None,
+            content_addressed_store: false,
+            object_store: None,
+            retry: None,
+            failure_rules: Vec::new(),
+            structured_logs: false,
+            auto_prune_steps: false,
+            env: Default::default(),
+            env_passthrough: Vec::new(),
             local: Some(repx_core::config::SchedulerConfig {
                 execution_types: vec![],
                 local_concurrency: None,
+                host_ledger: None,
+                enforce_limits: false,
+                kill_grace_secs: None,
+                sbatch_template: None,
+                sbatch_prologue: vec![],
+                parallel_environment: None,
+                rest: None,
             }),
             slurm: None,
+            sge: None,
+            flux: None,
+            aws_batch: None,
+            container: None,
+            setup_commands: Vec::new(),
+            max_in_flight: None,
+            agent: false,
+            submission_windows: None,
             artifact_store: None,
         },
     );
@@ -0,0 +1,743 @@
+use crate::{
+    errors::CoreError,
+    model::{
+        DataMount, DependencyType, Executable, ExecutionType, InputMapping, Job, JobId, Lab,
+        MappingType, ResourceHints, Run, RunId, StageType,
+    },
+};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_HOST_TOOLS_DIR: &str = "default";
+const HASH_PREFIX_LEN: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct LabBuilder {
+    repx_version: String,
+    lab_version: String,
+    git_hash: String,
+    host_tools_dir_name: String,
+    runs: Vec<RunBuilder>,
+    groups: BTreeMap<String, Vec<RunId>>,
+    extra_files: Vec<(PathBuf, Vec<u8>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunBuilder {
+    name: RunId,
+    image: Option<PathBuf>,
+    dependencies: HashMap<RunId, String>,
+    jobs: Vec<JobBuilder>,
+}
+
+#[derive(Debug, Clone)]
+pub struct JobBuilder {
+    id: JobId,
+    name: Option<String>,
+    params: serde_json::Map<String, serde_json::Value>,
+    stage_type: StageType,
+    executables: BTreeMap<String, ExecutableBuilder>,
+    resource_hints: Option<ResourceHints>,
+    data_mounts: Vec<DataMount>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutableBuilder {
+    source: ExecutableSource,
+    inputs: Vec<InputMapping>,
+    outputs: HashMap<String, serde_json::Value>,
+    resource_hints: Option<ResourceHints>,
+    deps: Vec<String>,
+    interpreter: Option<String>,
+    args: Vec<String>,
+    image: Option<PathBuf>,
+    runtime: Option<ExecutionType>,
+}
+
+#[derive(Debug, Clone)]
+enum ExecutableSource {
+    Path(PathBuf),
+    Script { file_name: String, content: Vec<u8> },
+}
+
+impl Default for LabBuilder {
+    fn default() -> Self {
+        Self::new("0.0.0")
+    }
+}
+
+impl LabBuilder {
+    pub fn new(lab_version: impl Into<String>) -> Self {
+        Self {
+            repx_version: env!("CARGO_PKG_VERSION").to_string(),
+            lab_version: lab_version.into(),
+            git_hash: "unknown".to_string(),
+            host_tools_dir_name: DEFAULT_HOST_TOOLS_DIR.to_string(),
+            runs: Vec::new(),
+            groups: BTreeMap::new(),
+            extra_files: Vec::new(),
+        }
+    }
+
+    pub fn repx_version(mut self, version: impl Into<String>) -> Self {
+        self.repx_version = version.into();
+        self
+    }
+
+    pub fn git_hash(mut self, git_hash: impl Into<String>) -> Self {
+        self.git_hash = git_hash.into();
+        self
+    }
+
+    pub fn host_tools_dir(mut self, name: impl Into<String>) -> Self {
+        self.host_tools_dir_name = name.into();
+        self
+    }
+
+    pub fn run(mut self, run: RunBuilder) -> Self {
+        self.runs.push(run);
+        self
+    }
+
+    pub fn group<I, R>(mut self, name: impl Into<String>, runs: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<RunId>,
+    {
+        self.groups
+            .insert(name.into(), runs.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn file(mut self, rel_path: impl Into<PathBuf>, content: impl Into<Vec<u8>>) -> Self {
+        self.extra_files.push((rel_path.into(), content.into()));
+        self
+    }
+
+    pub fn build(&self) -> Result<Lab, CoreError> {
+        self.validate()?;
+
+        let mut runs = HashMap::new();
+        let mut jobs = HashMap::new();
+        for run in &self.runs {
+            runs.insert(
+                run.name.clone(),
+                Run {
+                    image: run.image.clone(),
+                    jobs: run.jobs.iter().map(|j| j.id.clone()).collect(),
+                    dependencies: run.dependencies.clone(),
+                },
+            );
+            for job in &run.jobs {
+                jobs.insert(job.id.clone(), job.to_job());
+            }
+        }
+
+        Ok(Lab {
+            repx_version: self.repx_version.clone(),
+            lab_version: self.lab_version.clone(),
+            git_hash: self.git_hash.clone(),
+            content_hash: self.content_hash()?,
+            runs,
+            jobs,
+            groups: self
+                .groups
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            host_tools_path: PathBuf::from("host-tools")
+                .join(&self.host_tools_dir_name)
+                .join("bin"),
+            host_tools_dir_name: self.host_tools_dir_name.clone(),
+            referenced_files: Vec::new(),
+            tar_dir_name: None,
+        })
+    }
+
+    pub fn write_to(&self, lab_path: &Path) -> Result<Lab, CoreError> {
+        self.validate()?;
+
+        let mut files: Vec<(PathBuf, Vec<u8>)> = Vec::new();
+        let mut run_meta_paths = Vec::new();
+
+        for run in &self.runs {
+            for job in &run.jobs {
+                files.extend(job.script_files());
+            }
+
+            let run_meta_bytes = serde_json::to_vec_pretty(&run.to_metadata())?;
+            let run_meta_rel = PathBuf::from("revision").join(format!(
+                "{}-metadata-{}.json",
+                short_hash(&run_meta_bytes),
+                run.name
+            ));
+            run_meta_paths.push(run_meta_rel.to_string_lossy().into_owned());
+            files.push((run_meta_rel, run_meta_bytes));
+        }
+
+        let root_meta = serde_json::json!({
+            "repx_version": self.repx_version,
+            "gitHash": self.git_hash,
+            "runs": run_meta_paths,
+            "groups": self.groups,
+        });
+        let root_meta_bytes = serde_json::to_vec_pretty(&root_meta)?;
+        let root_meta_rel = PathBuf::from("revision")
+            .join(format!("{}-metadata.json", short_hash(&root_meta_bytes)));
+        files.push((root_meta_rel.clone(), root_meta_bytes));
+        files.extend(self.extra_files.iter().cloned());
+
+        let file_entries: Vec<serde_json::Value> = files
+            .iter()
+            .map(|(path, content)| {
+                serde_json::json!({
+                    "path": path.to_string_lossy(),
+                    "sha256": sha256_hex(content),
+                })
+            })
+            .collect();
+
+        let manifest = serde_json::json!({
+            "labId": self.content_hash()?,
+            "lab_version": self.lab_version,
+            "metadata": root_meta_rel.to_string_lossy(),
+            "files": file_entries,
+        });
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+        let manifest_rel =
+            PathBuf::from("lab").join(format!("{}-lab-metadata.json", short_hash(&manifest_bytes)));
+        files.push((manifest_rel, manifest_bytes));
+
+        let host_tools_bin = lab_path
+            .join("host-tools")
+            .join(&self.host_tools_dir_name)
+            .join("bin");
+        fs::create_dir_all(&host_tools_bin).map_err(|e| CoreError::path_io(&host_tools_bin, e))?;
+        let jobs_dir = lab_path.join("jobs");
+        fs::create_dir_all(&jobs_dir).map_err(|e| CoreError::path_io(&jobs_dir, e))?;
+        for run in &self.runs {
+            for job in &run.jobs {
+                let job_dir = jobs_dir.join(job.id.as_str());
+                fs::create_dir_all(&job_dir).map_err(|e| CoreError::path_io(&job_dir, e))?;
+            }
+        }
+
+        for (rel_path, content) in &files {
+            let full_path = crate::path_safety::safe_join(lab_path, &rel_path.to_string_lossy())?;
+            if let Some(parent) = full_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| CoreError::path_io(parent, e))?;
+            }
+            fs::write(&full_path, content).map_err(|e| CoreError::path_io(&full_path, e))?;
+        }
+
+        for run in &self.runs {
+            for job in &run.jobs {
+                for (rel_path, _) in job.script_files() {
+                    set_executable(&lab_path.join(rel_path))?;
+                }
+            }
+        }
+
+        crate::lab::load_from_path(lab_path)
+    }
+
+    fn validate(&self) -> Result<(), CoreError> {
+        let mut run_names = HashSet::new();
+        let mut job_ids = HashSet::new();
+        for run in &self.runs {
+            if !run_names.insert(&run.name) {
+                return Err(CoreError::InconsistentMetadata {
+                    detail: format!("run '{}' is defined more than once", run.name),
+                });
+            }
+            for job in &run.jobs {
+                if !job_ids.insert(&job.id) {
+                    return Err(CoreError::InconsistentMetadata {
+                        detail: format!("job '{}' is defined more than once", job.id),
+                    });
+                }
+            }
+        }
+
+        for run in &self.runs {
+            for dep in run.dependencies.keys() {
+                if !run_names.contains(dep) {
+                    return Err(CoreError::InconsistentMetadata {
+                        detail: format!("run '{}' depends on unknown run '{}'", run.name, dep),
+                    });
+                }
+            }
+            for job in &run.jobs {
+                for dep in job.to_job().all_dependencies() {
+                    if !job_ids.contains(dep) {
+                        return Err(CoreError::InconsistentMetadata {
+                            detail: format!("job '{}' depends on unknown job '{}'", job.id, dep),
+                        });
+                    }
+                }
+                job.validate_stage()?;
+            }
+        }
+
+        for (group, members) in &self.groups {
+            if let Some(missing) = members.iter().find(|r| !run_names.contains(r)) {
+                return Err(CoreError::InconsistentMetadata {
+                    detail: format!("group '{}' references unknown run '{}'", group, missing),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn content_hash(&self) -> Result<String, CoreError> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.lab_version.as_bytes());
+        hasher.update(self.git_hash.as_bytes());
+        for run in &self.runs {
+            hasher.update(serde_json::to_vec(&run.to_metadata())?);
+            for job in &run.jobs {
+                for (path, content) in job.script_files() {
+                    hasher.update(path.to_string_lossy().as_bytes());
+                    hasher.update(&content);
+                }
+            }
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+}
+
+impl RunBuilder {
+    pub fn new(name: impl Into<RunId>) -> Self {
+        Self {
+            name: name.into(),
+            image: None,
+            dependencies: HashMap::new(),
+            jobs: Vec::new(),
+        }
+    }
+
+    pub fn image(mut self, image: impl Into<PathBuf>) -> Self {
+        self.image = Some(image.into());
+        self
+    }
+
+    pub fn depends_on(mut self, run: impl Into<RunId>, dependency_type: DependencyType) -> Self {
+        self.dependencies
+            .insert(run.into(), dependency_type.to_string());
+        self
+    }
+
+    pub fn job(mut self, job: JobBuilder) -> Self {
+        self.jobs.push(job);
+        self
+    }
+
+    fn to_metadata(&self) -> serde_json::Value {
+        let jobs: BTreeMap<&str, Job> = self
+            .jobs
+            .iter()
+            .map(|j| (j.id.as_str(), j.to_job()))
+            .collect();
+        let mut meta = serde_json::json!({
+            "name": self.name,
+            "dependencies": self.dependencies.iter().collect::<BTreeMap<_, _>>(),
+            "jobs": jobs,
+        });
+        if let (Some(image), Some(obj)) = (&self.image, meta.as_object_mut()) {
+            obj.insert(
+                "image".to_string(),
+                serde_json::Value::String(image.to_string_lossy().into_owned()),
+            );
+        }
+        meta
+    }
+}
+
+impl JobBuilder {
+    pub fn new(id: impl Into<JobId>) -> Self {
+        Self {
+            id: id.into(),
+            name: None,
+            params: serde_json::Map::new(),
+            stage_type: StageType::Simple,
+            executables: BTreeMap::new(),
+            resource_hints: None,
+            data_mounts: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> &JobId {
+        &self.id
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.params.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn resources(mut self, hints: ResourceHints) -> Self {
+        self.resource_hints = Some(hints);
+        self
+    }
+
+    pub fn stage_type(mut self, stage_type: StageType) -> Self {
+        self.stage_type = stage_type;
+        self
+    }
+
+    pub fn data_mount(mut self, mount: DataMount) -> Self {
+        self.data_mounts.push(mount);
+        self
+    }
+
+    pub fn main(self, exe: ExecutableBuilder) -> Self {
+        self.executable("main", exe)
+    }
+
+    pub fn executable(mut self, name: impl Into<String>, exe: ExecutableBuilder) -> Self {
+        self.executables.insert(name.into(), exe);
+        self
+    }
+
+    pub fn scatter_gather(
+        mut self,
+        scatter: ExecutableBuilder,
+        steps: impl IntoIterator<Item = (String, ExecutableBuilder)>,
+        gather: ExecutableBuilder,
+    ) -> Self {
+        self.stage_type = StageType::ScatterGather;
+        self.executables.insert("scatter".to_string(), scatter);
+        for (step_name, exe) in steps {
+            self.executables.insert(format!("step-{}", step_name), exe);
+        }
+        self.executables.insert("gather".to_string(), gather);
+        self
+    }
+
+    fn validate_stage(&self) -> Result<(), CoreError> {
+        let required: &[&str] = match self.stage_type {
+            StageType::ScatterGather => &["scatter", "gather"],
+            _ => &["main"],
+        };
+        for name in required {
+            if !self.executables.contains_key(*name) {
+                return Err(CoreError::MissingExecutable {
+                    job_id: self.id.to_string(),
+                    executable: name.to_string(),
+                });
+            }
+        }
+
+        if self.stage_type == StageType::ScatterGather {
+            let step_names: HashSet<&str> = self
+                .executables
+                .keys()
+                .filter_map(|k| k.strip_prefix("step-"))
+                .collect();
+            if step_names.is_empty() {
+                return Err(CoreError::StepError {
+                    detail: format!("scatter-gather job '{}' has no steps", self.id),
+                });
+            }
+            for (name, exe) in &self.executables {
+                if let Some(dep) = exe.deps.iter().find(|d| !step_names.contains(d.as_str())) {
+                    return Err(CoreError::StepError {
+                        detail: format!(
+                            "executable '{}' of job '{}' depends on unknown step '{}'",
+                            name, self.id, dep
+                        ),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn job_dir(&self) -> PathBuf {
+        PathBuf::from("jobs").join(self.id.as_str())
+    }
+
+    fn script_files(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        self.executables
+            .values()
+            .filter_map(|exe| match &exe.source {
+                ExecutableSource::Script { file_name, content } => {
+                    Some((self.job_dir().join("bin").join(file_name), content.clone()))
+                }
+                ExecutableSource::Path(_) => None,
+            })
+            .collect()
+    }
+
+    fn to_job(&self) -> Job {
+        Job {
+            name: self.name.clone(),
+            params: serde_json::Value::Object(self.params.clone()),
+            path_in_lab: self.job_dir(),
+            stage_type: self.stage_type.clone(),
+            executables: self
+                .executables
+                .iter()
+                .map(|(name, exe)| (name.clone(), exe.to_executable(&self.job_dir())))
+                .collect(),
+            resource_hints: self.resource_hints.clone(),
+            data_mounts: self.data_mounts.clone(),
+        }
+    }
+}
+
+impl ExecutableBuilder {
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        Self::with_source(ExecutableSource::Path(path.into()))
+    }
+
+    pub fn script(file_name: impl Into<String>, content: impl Into<Vec<u8>>) -> Self {
+        Self::with_source(ExecutableSource::Script {
+            file_name: file_name.into(),
+            content: content.into(),
+        })
+    }
+
+    fn with_source(source: ExecutableSource) -> Self {
+        Self {
+            source,
+            inputs: Vec::new(),
+            outputs: HashMap::new(),
+            resource_hints: None,
+            deps: Vec::new(),
+            interpreter: None,
+            args: Vec::new(),
+            image: None,
+            runtime: None,
+        }
+    }
+
+    pub fn input(mut self, mapping: InputMapping) -> Self {
+        self.inputs.push(mapping);
+        self
+    }
+
+    pub fn input_from(
+        self,
+        job_id: impl Into<JobId>,
+        source_output: impl Into<String>,
+        target_input: impl Into<String>,
+    ) -> Self {
+        self.input(InputMapping {
+            job_id: Some(job_id.into()),
+            source_output: Some(source_output.into()),
+            target_input: target_input.into(),
+            source: None,
+            source_key: None,
+            mapping_type: Some(MappingType::IntraPipeline),
+            dependency_type: Some(DependencyType::Hard),
+            source_run: None,
+            source_stage_filter: None,
+        })
+    }
+
+    pub fn input_from_run(
+        self,
+        run: impl Into<RunId>,
+        job_id: impl Into<JobId>,
+        source_output: impl Into<String>,
+        target_input: impl Into<String>,
+        dependency_type: DependencyType,
+    ) -> Self {
+        self.input(InputMapping {
+            job_id: Some(job_id.into()),
+            source_output: Some(source_output.into()),
+            target_input: target_input.into(),
+            source: None,
+            source_key: None,
+            mapping_type: Some(MappingType::InterRun),
+            dependency_type: Some(dependency_type),
+            source_run: Some(run.into()),
+            source_stage_filter: None,
+        })
+    }
+
+    pub fn output(mut self, name: impl Into<String>, template: impl Into<String>) -> Self {
+        self.outputs
+            .insert(name.into(), serde_json::Value::String(template.into()));
+        self
+    }
+
+    pub fn resources(mut self, hints: ResourceHints) -> Self {
+        self.resource_hints = Some(hints);
+        self
+    }
+
+    pub fn after(mut self, step: impl Into<String>) -> Self {
+        self.deps.push(step.into());
+        self
+    }
+
+    pub fn interpreter(mut self, interpreter: impl Into<String>) -> Self {
+        self.interpreter = Some(interpreter.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn image(mut self, image: impl Into<PathBuf>) -> Self {
+        self.image = Some(image.into());
+        self
+    }
+
+    pub fn runtime(mut self, runtime: ExecutionType) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    fn to_executable(&self, job_dir: &Path) -> Executable {
+        let path = match &self.source {
+            ExecutableSource::Path(p) => p.clone(),
+            ExecutableSource::Script { file_name, .. } => job_dir.join("bin").join(file_name),
+        };
+        Executable {
+            path,
+            inputs: self.inputs.clone(),
+            outputs: self.outputs.clone(),
+            resource_hints: self.resource_hints.clone(),
+            deps: self.deps.clone(),
+            interpreter: self.interpreter.clone(),
+            args: self.args.clone(),
+            image: self.image.clone(),
+            runtime: self.runtime,
+        }
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn short_hash(data: &[u8]) -> String {
+    sha256_hex(data)[..HASH_PREFIX_LEN].to_string()
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), CoreError> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .map_err(|e| CoreError::path_io(path, e))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<(), CoreError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_stage_lab() -> LabBuilder {
+        LabBuilder::new("1.0").git_hash("deadbeef").run(
+            RunBuilder::new("pipeline")
+                .job(
+                    JobBuilder::new("aaaa-producer-1.0")
+                        .name("producer")
+                        .param("size", 10)
+                        .main(
+                            ExecutableBuilder::script("run.sh", "#!/bin/sh\necho data > $1/data\n")
+                                .output("data", "$out/data"),
+                        ),
+                )
+                .job(JobBuilder::new("bbbb-consumer-1.0").name("consumer").main(
+                    ExecutableBuilder::script("run.sh", "#!/bin/sh\ncat $2\n").input_from(
+                        "aaaa-producer-1.0",
+                        "data",
+                        "data",
+                    ),
+                )),
+        )
+    }
+
+    #[test]
+    fn test_build_in_memory() {
+        let lab = two_stage_lab().build().expect("lab must build");
+        assert_eq!(lab.runs.len(), 1);
+        assert_eq!(lab.jobs.len(), 2);
+        let consumer = &lab.jobs[&JobId::from("bbbb-consumer-1.0")];
+        let deps: Vec<_> = consumer.all_dependencies().collect();
+        assert_eq!(deps, vec![&JobId::from("aaaa-producer-1.0")]);
+    }
+
+    #[test]
+    fn test_write_roundtrips_through_loader() {
+        let dir = tempfile::tempdir().expect("tempdir creation must succeed");
+        let built = two_stage_lab()
+            .group("all", ["pipeline"])
+            .write_to(dir.path())
+            .expect("lab must be written");
+        let loaded = crate::lab::load_from_path(dir.path()).expect("lab must load");
+
+        assert_eq!(loaded.content_hash, built.content_hash);
+        assert_eq!(loaded.git_hash, "deadbeef");
+        assert_eq!(loaded.groups["all"], vec![RunId::from("pipeline")]);
+        let producer = &loaded.jobs[&JobId::from("aaaa-producer-1.0")];
+        assert_eq!(producer.params["size"], 10);
+        assert!(dir
+            .path()
+            .join("jobs/aaaa-producer-1.0/bin/run.sh")
+            .is_file());
+    }
+
+    #[test]
+    fn test_scatter_gather_job() {
+        let job = JobBuilder::new("cccc-sweep-1.0").scatter_gather(
+            ExecutableBuilder::script("scatter.sh", "#!/bin/sh\n"),
+            [
+                (
+                    "prep".to_string(),
+                    ExecutableBuilder::script("prep.sh", "#!/bin/sh\n"),
+                ),
+                (
+                    "train".to_string(),
+                    ExecutableBuilder::script("train.sh", "#!/bin/sh\n").after("prep"),
+                ),
+            ],
+            ExecutableBuilder::script("gather.sh", "#!/bin/sh\n"),
+        );
+        let lab = LabBuilder::new("1.0")
+            .run(RunBuilder::new("sweep").job(job))
+            .build()
+            .expect("lab must build");
+        let job = &lab.jobs[&JobId::from("cccc-sweep-1.0")];
+        assert_eq!(job.stage_type, StageType::ScatterGather);
+        assert_eq!(job.executables["step-train"].deps, vec!["prep".to_string()]);
+    }
+
+    #[test]
+    fn test_unknown_dependency_rejected() {
+        let result = LabBuilder::new("1.0")
+            .run(
+                RunBuilder::new("r").job(
+                    JobBuilder::new("dddd-a-1.0")
+                        .main(ExecutableBuilder::path("bin/a").input_from("missing", "x", "y")),
+                ),
+            )
+            .build();
+        assert!(matches!(
+            result,
+            Err(CoreError::InconsistentMetadata { .. })
+        ));
+    }
+}
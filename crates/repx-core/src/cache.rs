@@ -16,6 +16,10 @@ pub enum CacheKey {
         image_hash: String,
     },
     OverlayCapability,
+    RuntimeCapabilities {
+        host_tools: String,
+    },
+    SyncManifest,
 
     HostTools {
         content_hash: String,
@@ -85,6 +89,8 @@ impl CacheKey {
             Self::Rootfs { .. } => "rootfs",
             Self::ImageStaging { .. } => "image-staging",
             Self::OverlayCapability => "overlay-capability",
+            Self::RuntimeCapabilities { .. } => "runtime-capabilities",
+            Self::SyncManifest => "sync-manifest",
             Self::HostTools { .. } => "host-tools",
             Self::LabTar { .. } => "lab-tar",
             Self::RemoteLabTar { .. } => "remote-lab-tar",
@@ -110,6 +116,8 @@ impl CacheKey {
             Self::Rootfs { image_hash } => image_hash.clone(),
             Self::ImageStaging { image_hash } => image_hash.clone(),
             Self::OverlayCapability => "(singleton)".to_string(),
+            Self::RuntimeCapabilities { host_tools } => host_tools.clone(),
+            Self::SyncManifest => "(singleton)".to_string(),
             Self::HostTools { content_hash } => content_hash.clone(),
             Self::LabTar { content_hash } => content_hash.clone(),
             Self::RemoteLabTar {
@@ -246,6 +254,13 @@ impl FsCache {
                 self.root.join("capabilities").join("overlay_support.json")
             }
 
+            CacheKey::RuntimeCapabilities { host_tools } => self
+                .root
+                .join("capabilities")
+                .join(format!("runtimes-{host_tools}.json")),
+
+            CacheKey::SyncManifest => self.root.join("sync").join("artifacts-manifest.jsonl"),
+
             CacheKey::HostTools { content_hash } => self
                 .root
                 .join("temp")
@@ -522,6 +537,10 @@ fn cache_key_from_metadata(meta: &CacheMetadata) -> Option<CacheKey> {
             image_hash: id.clone(),
         }),
         "overlay-capability" => Some(CacheKey::OverlayCapability),
+        "runtime-capabilities" => Some(CacheKey::RuntimeCapabilities {
+            host_tools: id.clone(),
+        }),
+        "sync-manifest" => Some(CacheKey::SyncManifest),
         "host-tools" => Some(CacheKey::HostTools {
             content_hash: id.clone(),
         }),
@@ -1017,6 +1036,10 @@ mod tests {
                 image_hash: "abc".to_string(),
             },
             CacheKey::OverlayCapability,
+            CacheKey::RuntimeCapabilities {
+                host_tools: "abc-host-tools".to_string(),
+            },
+            CacheKey::SyncManifest,
             CacheKey::RemoteBinary {
                 binary_hash: "def".to_string(),
                 target: "safari".to_string(),
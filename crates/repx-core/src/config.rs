@@ -1,5 +1,5 @@
 use crate::errors::CoreError;
-use crate::model::{Memory, SlurmTime};
+use crate::model::{BranchQuota, Memory, SlurmTime};
 use crate::theme;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -11,6 +11,7 @@ use xdg::BaseDirectories;
 const CONFIG_FILE_NAME: &str = "config.toml";
 const THEME_FILE_NAME: &str = "theme.toml";
 const RESOURCES_FILE_NAME: &str = "resources.toml";
+const SECRETS_FILE_NAME: &str = "secrets.toml";
 
 const DEFAULT_CONFIG_CONTENT: &str = include_str!("defaults/config.toml");
 const DEFAULT_RESOURCES_CONTENT: &str = include_str!("defaults/resources.toml");
@@ -21,10 +22,161 @@ pub struct SchedulerConfig {
     #[serde(default)]
     pub execution_types: Vec<crate::model::ExecutionType>,
     pub local_concurrency: Option<usize>,
+    #[serde(default)]
+    pub host_ledger: Option<PathBuf>,
+    #[serde(default)]
+    pub enforce_limits: bool,
+    #[serde(default)]
+    pub kill_grace_secs: Option<u64>,
+    #[serde(default)]
+    pub sbatch_template: Option<PathBuf>,
+    #[serde(default)]
+    pub sbatch_prologue: Vec<String>,
+    #[serde(default)]
+    pub parallel_environment: Option<String>,
+    #[serde(default)]
+    pub rest: Option<SlurmRestConfig>,
+}
+
+pub const DEFAULT_SLURM_REST_API_VERSION: &str = "v0.0.40";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SlurmRestConfig {
+    pub url: String,
+    #[serde(default)]
+    pub api_version: Option<String>,
+    #[serde(default)]
+    pub token_env: Option<String>,
+}
+
+impl SlurmRestConfig {
+    pub fn api_version(&self) -> &str {
+        self.api_version
+            .as_deref()
+            .unwrap_or(DEFAULT_SLURM_REST_API_VERSION)
+    }
+
+    pub fn token_env(&self) -> &str {
+        self.token_env.as_deref().unwrap_or("SLURM_JWT")
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
+pub struct AwsBatchConfig {
+    pub job_queue: String,
+    pub image: String,
+    pub s3_prefix: String,
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub job_role_arn: Option<String>,
+    #[serde(default)]
+    pub log_group: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ContainerTargetConfig {
+    pub engine: crate::model::ExecutionType,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RsyncConfig {
+    #[serde(default)]
+    pub bwlimit: Option<String>,
+    #[serde(default)]
+    pub compress_level: Option<u32>,
+    #[serde(default)]
+    pub checksum: bool,
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+impl RsyncConfig {
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        match self.compress_level {
+            Some(0) => {}
+            Some(level) => {
+                args.push("--compress".to_string());
+                args.push(format!("--compress-level={}", level));
+            }
+            None => args.push("--compress".to_string()),
+        }
+        if let Some(limit) = &self.bwlimit {
+            args.push(format!("--bwlimit={}", limit));
+        }
+        if self.checksum {
+            args.push("--checksum".to_string());
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RetryConfig {
+    #[serde(default = "default_retry_attempts")]
+    pub attempts: u32,
+    #[serde(default = "default_retry_backoff_secs")]
+    pub backoff_secs: u64,
+    #[serde(default = "default_retry_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+}
+
+fn default_retry_attempts() -> u32 {
+    3
+}
+
+fn default_retry_backoff_secs() -> u64 {
+    2
+}
+
+fn default_retry_max_backoff_secs() -> u64 {
+    60
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            attempts: default_retry_attempts(),
+            backoff_secs: default_retry_backoff_secs(),
+            max_backoff_secs: default_retry_max_backoff_secs(),
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn backoff(&self, failed: u32) -> Duration {
+        let factor = 2u64.saturating_pow(failed.saturating_sub(1));
+        Duration::from_secs(
+            self.backoff_secs
+                .saturating_mul(factor)
+                .min(self.max_backoff_secs),
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectStoreConfig {
+    pub uri: String,
+    #[serde(default)]
+    pub endpoint_url: Option<String>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
 pub struct Target {
     pub address: Option<String>,
     pub base_path: PathBuf,
@@ -38,17 +190,116 @@ pub struct Target {
     #[serde(default)]
     pub mount_paths: Vec<String>,
     #[serde(default)]
+    pub network: Option<crate::model::NetworkMode>,
+    #[serde(default)]
+    pub seccomp: Option<crate::model::SeccompProfile>,
+    #[serde(default)]
+    pub pre_run: Option<PathBuf>,
+    #[serde(default)]
+    pub post_run: Option<PathBuf>,
+    #[serde(default)]
+    pub image_cache_max_size: Option<Memory>,
+    #[serde(default)]
+    pub heartbeat_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub ssh_control_persist_secs: Option<u64>,
+    #[serde(default)]
+    pub sync_concurrency: Option<usize>,
+    #[serde(default)]
+    pub rsync: Option<RsyncConfig>,
+    #[serde(default)]
+    pub retry: Option<RetryConfig>,
+    #[serde(default)]
+    pub content_addressed_store: bool,
+    #[serde(default)]
+    pub object_store: Option<ObjectStoreConfig>,
+    #[serde(default)]
+    pub failure_rules: Vec<crate::model::FailureRule>,
+    #[serde(default)]
+    pub structured_logs: bool,
+    #[serde(default)]
+    pub auto_prune_steps: bool,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub env_passthrough: Vec<String>,
+    #[serde(default)]
+    pub setup_commands: Vec<String>,
+    #[serde(default)]
     pub local: Option<SchedulerConfig>,
     #[serde(default)]
     pub slurm: Option<SchedulerConfig>,
+    #[serde(default)]
+    pub sge: Option<SchedulerConfig>,
+    #[serde(default)]
+    pub flux: Option<SchedulerConfig>,
+    #[serde(default)]
+    pub aws_batch: Option<AwsBatchConfig>,
+    #[serde(default)]
+    pub container: Option<ContainerTargetConfig>,
+    #[serde(default)]
+    pub submission_windows: Option<crate::submission_window::SubmissionWindows>,
+    #[serde(default)]
+    pub max_in_flight: Option<usize>,
+    #[serde(default)]
+    pub agent: bool,
 }
 
 impl Target {
     pub fn mount_policy(&self) -> crate::model::MountPolicy {
         crate::model::MountPolicy::from_flags(self.mount_host_paths, self.mount_paths.clone())
     }
+
+    pub fn heartbeat_interval(&self) -> Duration {
+        self.heartbeat_interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(crate::store::heartbeat::DEFAULT_INTERVAL)
+    }
+
+    pub fn ssh_control_persist(&self) -> Option<Duration> {
+        match self.ssh_control_persist_secs {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(DEFAULT_SSH_CONTROL_PERSIST),
+        }
+    }
+
+    pub fn sync_concurrency(&self) -> usize {
+        self.sync_concurrency
+            .unwrap_or(DEFAULT_SYNC_CONCURRENCY)
+            .max(1)
+    }
+
+    pub fn retry_policy(&self) -> RetryConfig {
+        self.retry.clone().unwrap_or_default()
+    }
+
+    pub fn image_cache_max_bytes(&self) -> Option<u64> {
+        self.image_cache_max_size
+            .as_ref()
+            .and_then(Memory::to_bytes)
+    }
+
+    pub fn max_in_flight(&self) -> Option<usize> {
+        self.max_in_flight.map(|limit| limit.max(1))
+    }
+
+    pub fn slurm_rest(&self) -> Option<&SlurmRestConfig> {
+        self.slurm.as_ref().and_then(|slurm| slurm.rest.as_ref())
+    }
+
+    pub fn with_setup_commands(&self, command: &str) -> String {
+        if self.setup_commands.is_empty() {
+            return command.to_string();
+        }
+        format!("{} && {}", self.setup_commands.join(" && "), command)
+    }
 }
 
+pub const DEFAULT_SYNC_CONCURRENCY: usize = 4;
+
+pub const DEFAULT_SSH_CONTROL_PERSIST: Duration = Duration::from_secs(600);
+
 pub const TUI_TICK_RATE: Duration = Duration::from_millis(1000);
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -99,8 +350,21 @@ pub struct ResourceRule {
     pub cpus_per_task: Option<u32>,
     pub mem: Option<Memory>,
     pub time: Option<SlurmTime>,
+    pub qos: Option<String>,
+    pub account: Option<String>,
+    pub constraint: Option<String>,
+    pub gres: Option<String>,
+    pub exclusive: Option<bool>,
     #[serde(default)]
     pub sbatch_opts: Vec<String>,
+    pub max_retries: Option<u32>,
+    pub retry_backoff: Option<u64>,
+    #[serde(default)]
+    pub retry_exit_codes: Vec<i32>,
+    pub checkpoint_interval: Option<u64>,
+    pub disk: Option<Memory>,
+    pub priority: Option<i32>,
+    pub min_branch_success: Option<BranchQuota>,
     #[serde(default)]
     pub worker_resources: Option<Box<ResourceRule>>,
 }
@@ -114,6 +378,71 @@ pub struct Resources {
     pub rules: Vec<ResourceRule>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Secrets {
+    #[serde(default)]
+    pub secrets: BTreeMap<String, SecretSource>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SecretSource {
+    pub env: Option<String>,
+    pub file: Option<PathBuf>,
+    pub command: Option<Vec<String>>,
+    #[serde(default)]
+    pub mount: bool,
+    pub job_id_glob: Option<String>,
+}
+
+impl SecretSource {
+    pub fn applies_to(&self, job_id: &str) -> bool {
+        self.job_id_glob
+            .as_deref()
+            .is_none_or(|glob| wildmatch::WildMatch::new(glob).matches(job_id))
+    }
+
+    pub fn resolve(&self, name: &str) -> Result<String, CoreError> {
+        let invalid = |detail: String| CoreError::InvalidConfig {
+            detail: format!("Secret '{}': {}", name, detail),
+        };
+        let value = match (&self.env, &self.file, &self.command) {
+            (Some(var), None, None) => std::env::var(var)
+                .map_err(|_| invalid(format!("environment variable '{}' is not set", var)))?,
+            (None, Some(path), None) => {
+                let path = PathBuf::from(shellexpand::tilde(&path.display().to_string()).as_ref());
+                fs::read_to_string(&path).map_err(|e| CoreError::path_io(&path, e))?
+            }
+            (None, None, Some(command)) => {
+                let (program, args) = command
+                    .split_first()
+                    .ok_or_else(|| invalid("`command` must not be empty".to_string()))?;
+                let output = std::process::Command::new(program)
+                    .args(args)
+                    .stdin(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::inherit())
+                    .output()
+                    .map_err(|e| invalid(format!("failed to run '{}': {}", program, e)))?;
+                if !output.status.success() {
+                    return Err(invalid(format!(
+                        "'{}' exited with {}",
+                        program, output.status
+                    )));
+                }
+                String::from_utf8(output.stdout)
+                    .map_err(|_| invalid(format!("'{}' printed non-UTF-8 output", program)))?
+            }
+            _ => {
+                return Err(invalid(
+                    "exactly one of `env`, `file` or `command` must be set".to_string(),
+                ))
+            }
+        };
+        Ok(value.trim_end_matches(['\n', '\r']).to_string())
+    }
+}
+
 impl Config {
     #[deprecated(note = "Use repx_core::config::TUI_TICK_RATE constant instead")]
     pub fn tui_tick_rate(&self) -> Duration {
@@ -205,6 +534,15 @@ pub fn load_resources(
     }
 }
 
+pub fn load_secrets() -> Result<Secrets, CoreError> {
+    let Some(path) = crate::xdg_dirs().find_config_file(SECRETS_FILE_NAME) else {
+        return Ok(Secrets::default());
+    };
+    tracing::debug!("Loading secrets from: {}", path.display());
+    let content = fs::read_to_string(&path).map_err(|e| CoreError::path_io(&path, e))?;
+    toml::from_str(&content).map_err(|e| CoreError::toml_path(&path, e))
+}
+
 pub fn load_config() -> Result<Config, CoreError> {
     let xdg_dirs = crate::xdg_dirs();
 
@@ -237,6 +575,15 @@ pub fn load_config() -> Result<Config, CoreError> {
                 ),
             });
         }
+
+        for rule in &target.failure_rules {
+            rule.validate().map_err(|e| CoreError::InvalidConfig {
+                detail: format!(
+                    "Target '{}': failure rule for class '{}' has an invalid stderr pattern: {}",
+                    name, rule.class, e
+                ),
+            })?;
+        }
     }
     Ok(config)
 }
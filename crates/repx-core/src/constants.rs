@@ -1,15 +1,20 @@
 pub mod markers {
     pub const SUCCESS: &str = "SUCCESS";
     pub const FAIL: &str = "FAIL";
+    pub const TIMEOUT: &str = "TIMEOUT";
+    pub const CANCELLED: &str = "CANCELLED";
 }
 
 pub mod logs {
     pub const STDOUT: &str = "stdout.log";
     pub const STDERR: &str = "stderr.log";
+    pub const JSONL: &str = "logs.jsonl";
 }
 
 pub mod manifests {
     pub const WORKER_SLURM_IDS: &str = "worker_slurm_ids.json";
+    pub const SKIPPED_BRANCHES: &str = "skipped_branches.json";
+    pub const STEP_INPUT_HASH: &str = "input_hash";
 }
 
 pub mod dirs {
@@ -22,6 +27,10 @@ pub mod dirs {
     pub const OUT: &str = "out";
 }
 
+pub mod sge {
+    pub const JOB_NAME_PREFIX: &str = "repx-";
+}
+
 pub mod targets {
     pub const LOCAL: &str = "local";
 }
@@ -1,16 +1,35 @@
-use crate::model::{JobId, Lab, RunId};
+use crate::model::{FailureClass, JobId, Lab, RunId};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, HashSet};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
 pub enum JobStatus {
-    Succeeded { location: String },
-    Failed { location: String },
+    Succeeded {
+        location: String,
+    },
+    Failed {
+        location: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        class: Option<FailureClass>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    TimedOut {
+        location: String,
+    },
+    Cancelled {
+        location: String,
+    },
+    Lost {
+        location: String,
+    },
     Pending,
     Queued,
     Running,
-    Blocked { missing_deps: HashSet<JobId> },
+    Blocked {
+        missing_deps: HashSet<JobId>,
+    },
 }
 
 pub fn determine_job_statuses(
@@ -113,7 +132,12 @@ pub fn determine_run_aggregate_statuses(
             for job_id in &run.jobs {
                 match all_job_statuses.get(job_id) {
                     Some(JobStatus::Succeeded { .. }) => succeeded_count += 1,
-                    Some(JobStatus::Failed { .. }) => has_failed = true,
+                    Some(
+                        JobStatus::Failed { .. }
+                        | JobStatus::TimedOut { .. }
+                        | JobStatus::Cancelled { .. }
+                        | JobStatus::Lost { .. },
+                    ) => has_failed = true,
                     Some(JobStatus::Running) => has_running = true,
                     Some(JobStatus::Queued) => has_queued = true,
                     Some(JobStatus::Pending) => has_pending = true,
@@ -127,6 +151,8 @@ pub fn determine_run_aggregate_statuses(
             } else if has_failed {
                 JobStatus::Failed {
                     location: "".to_string(),
+                    class: None,
+                    reason: None,
                 }
             } else if has_queued {
                 JobStatus::Queued
@@ -210,6 +236,7 @@ mod tests {
                 stage_type: crate::model::StageType::Simple,
                 executables: HashMap::new(),
                 resource_hints: None,
+                data_mounts: vec![],
             };
 
             let mut exe = crate::model::Executable {
@@ -218,6 +245,10 @@ mod tests {
                 outputs: HashMap::new(),
                 resource_hints: None,
                 deps: vec![],
+                interpreter: None,
+                args: vec![],
+                image: None,
+                runtime: None,
             };
 
             for inp in inputs {
@@ -206,6 +206,14 @@ pub enum CoreError {
 
     #[error("Cache error for key '{key}': {detail}")]
     CacheError { key: String, detail: String },
+
+    #[error(
+        "No lab named '{name}' in the workspace. Use `repx workspace list` to see registered labs."
+    )]
+    WorkspaceLabNotFound { name: String },
+
+    #[error("Submission to target '{target}' is held: {reason}. No upcoming submission window was found; check `submission_windows` in config.toml.")]
+    SubmissionWindowClosed { target: String, reason: String },
 }
 
 #[derive(Error, Debug)]
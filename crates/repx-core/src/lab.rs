@@ -385,6 +385,8 @@ fn load_from_path_inner(initial_path: &Path, verify_integrity: bool) -> Result<L
         for (job_id, mut job) in run_meta.jobs.drain() {
             job.path_in_lab = PathBuf::from("jobs").join(job_id.as_str());
             lab.referenced_files.push(job.path_in_lab.clone());
+            lab.referenced_files
+                .extend(job.executables.values().filter_map(|exe| exe.image.clone()));
             lab.jobs.insert(job_id, job);
         }
     }
@@ -960,6 +962,8 @@ pub fn load_from_tar(tar_path: &Path) -> Result<Lab, CoreError> {
         for (job_id, mut job) in run_meta.jobs.drain() {
             job.path_in_lab = PathBuf::from("jobs").join(job_id.as_str());
             lab.referenced_files.push(job.path_in_lab.clone());
+            lab.referenced_files
+                .extend(job.executables.values().filter_map(|exe| exe.image.clone()));
             lab.jobs.insert(job_id, job);
         }
     }
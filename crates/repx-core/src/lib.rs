@@ -1,3 +1,4 @@
+pub mod builder;
 pub mod cache;
 pub mod config;
 pub mod constants;
@@ -11,7 +12,10 @@ pub mod path_safety;
 pub mod protocol;
 pub mod resolver;
 pub mod store;
+pub mod submission_window;
 pub mod theme;
+pub mod tree_prefix;
+pub mod workspace;
 
 use std::sync::OnceLock;
 
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 macro_rules! impl_string_newtype {
@@ -94,6 +94,10 @@ pub enum SchedulerType {
     #[default]
     Local,
     Slurm,
+    Sge,
+    Flux,
+    #[serde(rename = "aws-batch")]
+    AwsBatch,
 }
 
 impl fmt::Display for SchedulerType {
@@ -101,6 +105,9 @@ impl fmt::Display for SchedulerType {
         match self {
             SchedulerType::Local => write!(f, "local"),
             SchedulerType::Slurm => write!(f, "slurm"),
+            SchedulerType::Sge => write!(f, "sge"),
+            SchedulerType::Flux => write!(f, "flux"),
+            SchedulerType::AwsBatch => write!(f, "aws-batch"),
         }
     }
 }
@@ -112,7 +119,7 @@ impl fmt::Display for ParseSchedulerTypeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "invalid scheduler type: '{}'. Valid values are: local, slurm",
+            "invalid scheduler type: '{}'. Valid values are: local, slurm, sge, flux, aws-batch",
             self.0
         )
     }
@@ -127,6 +134,9 @@ impl FromStr for SchedulerType {
         match s {
             "local" => Ok(SchedulerType::Local),
             "slurm" => Ok(SchedulerType::Slurm),
+            "sge" | "uge" => Ok(SchedulerType::Sge),
+            "flux" => Ok(SchedulerType::Flux),
+            "aws-batch" => Ok(SchedulerType::AwsBatch),
             _ => Err(ParseSchedulerTypeError(s.to_string())),
         }
     }
@@ -172,6 +182,7 @@ pub enum ExecutionType {
     Bwrap,
     Podman,
     Docker,
+    Proot,
 }
 
 impl fmt::Display for ExecutionType {
@@ -181,6 +192,7 @@ impl fmt::Display for ExecutionType {
             ExecutionType::Bwrap => write!(f, "bwrap"),
             ExecutionType::Podman => write!(f, "podman"),
             ExecutionType::Docker => write!(f, "docker"),
+            ExecutionType::Proot => write!(f, "proot"),
         }
     }
 }
@@ -192,7 +204,7 @@ impl fmt::Display for ParseExecutionTypeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "invalid execution type: '{}'. Valid values are: native, bwrap, podman, docker",
+            "invalid execution type: '{}'. Valid values are: native, bwrap, podman, docker, proot",
             self.0
         )
     }
@@ -209,11 +221,344 @@ impl FromStr for ExecutionType {
             "bwrap" => Ok(ExecutionType::Bwrap),
             "podman" => Ok(ExecutionType::Podman),
             "docker" => Ok(ExecutionType::Docker),
+            "proot" => Ok(ExecutionType::Proot),
             _ => Err(ParseExecutionTypeError(s.to_string())),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkMode {
+    None,
+    Host,
+}
+
+impl fmt::Display for NetworkMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkMode::None => write!(f, "none"),
+            NetworkMode::Host => write!(f, "host"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNetworkModeError(pub String);
+
+impl fmt::Display for ParseNetworkModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid network mode: '{}'. Valid values are: none, host",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseNetworkModeError {}
+
+impl FromStr for NetworkMode {
+    type Err = ParseNetworkModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(NetworkMode::None),
+            "host" => Ok(NetworkMode::Host),
+            _ => Err(ParseNetworkModeError(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum SeccompProfile {
+    Default,
+    File(PathBuf),
+}
+
+impl fmt::Display for SeccompProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeccompProfile::Default => write!(f, "default"),
+            SeccompProfile::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSeccompProfileError(pub String);
+
+impl fmt::Display for ParseSeccompProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid seccomp profile: '{}'. Use 'default' or an absolute path to a profile",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseSeccompProfileError {}
+
+impl FromStr for SeccompProfile {
+    type Err = ParseSeccompProfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(SeccompProfile::Default),
+            path if Path::new(path).is_absolute() => Ok(SeccompProfile::File(PathBuf::from(path))),
+            _ => Err(ParseSeccompProfileError(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for SeccompProfile {
+    type Error = ParseSeccompProfileError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<SeccompProfile> for String {
+    fn from(profile: SeccompProfile) -> Self {
+        profile.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct DataMount {
+    pub host_path: PathBuf,
+    pub mount_path: Option<PathBuf>,
+}
+
+impl DataMount {
+    pub fn target(&self) -> &Path {
+        self.mount_path.as_deref().unwrap_or(&self.host_path)
+    }
+}
+
+impl fmt::Display for DataMount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.mount_path {
+            Some(mount) => write!(f, "{}:{}", self.host_path.display(), mount.display()),
+            None => write!(f, "{}", self.host_path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDataMountError(pub String);
+
+impl fmt::Display for ParseDataMountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid data mount: '{}'. Use HOST_PATH or HOST_PATH:MOUNT_PATH with absolute paths",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseDataMountError {}
+
+impl FromStr for DataMount {
+    type Err = ParseDataMountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (host, mount) = match s.split_once(':') {
+            Some((host, mount)) => (host, Some(mount)),
+            None => (s, None),
+        };
+        let absolute = |p: &str| Path::new(p).is_absolute().then(|| PathBuf::from(p));
+        let host_path = absolute(host).ok_or_else(|| ParseDataMountError(s.to_string()))?;
+        let mount_path = match mount {
+            Some(mount) => Some(absolute(mount).ok_or_else(|| ParseDataMountError(s.to_string()))?),
+            None => None,
+        };
+        Ok(DataMount {
+            host_path,
+            mount_path,
+        })
+    }
+}
+
+impl TryFrom<String> for DataMount {
+    type Error = ParseDataMountError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<DataMount> for String {
+    fn from(mount: DataMount) -> Self {
+        mount.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FailureClass {
+    Retriable,
+    Infrastructure,
+    UserError,
+}
+
+impl FailureClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureClass::Retriable => "retriable",
+            FailureClass::Infrastructure => "infrastructure",
+            FailureClass::UserError => "user-error",
+        }
+    }
+
+    pub fn is_retriable(&self) -> bool {
+        !matches!(self, FailureClass::UserError)
+    }
+}
+
+impl fmt::Display for FailureClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFailureClassError(pub String);
+
+impl fmt::Display for ParseFailureClassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid failure class: '{}'. Valid values are: retriable, infrastructure, user-error",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseFailureClassError {}
+
+impl FromStr for FailureClass {
+    type Err = ParseFailureClassError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "retriable" => Ok(FailureClass::Retriable),
+            "infrastructure" => Ok(FailureClass::Infrastructure),
+            "user-error" => Ok(FailureClass::UserError),
+            _ => Err(ParseFailureClassError(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FailureRule {
+    pub class: FailureClass,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exit_codes: Vec<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+}
+
+impl FailureRule {
+    pub fn validate(&self) -> Result<(), regex::Error> {
+        match &self.stderr {
+            Some(pattern) => regex::Regex::new(pattern).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    pub fn matches(&self, exit_code: i32, stderr: &str) -> bool {
+        if !self.exit_codes.is_empty() && !self.exit_codes.contains(&exit_code) {
+            return false;
+        }
+        match &self.stderr {
+            Some(pattern) => match regex::Regex::new(pattern) {
+                Ok(re) => re.is_match(stderr),
+                Err(e) => {
+                    tracing::warn!("Ignoring failure rule with invalid pattern: {}", e);
+                    false
+                }
+            },
+            None => true,
+        }
+    }
+
+    pub fn classify(rules: &[FailureRule], exit_code: i32, stderr: &str) -> Option<FailureClass> {
+        rules
+            .iter()
+            .find(|rule| rule.matches(exit_code, stderr))
+            .map(|rule| rule.class)
+    }
+}
+
+impl fmt::Display for FailureRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.class)?;
+        if self.exit_codes.is_empty() && self.stderr.is_none() {
+            return Ok(());
+        }
+        let codes: Vec<String> = self.exit_codes.iter().map(i32::to_string).collect();
+        write!(f, ":{}", codes.join(","))?;
+        if let Some(pattern) = &self.stderr {
+            write!(f, ":{}", pattern)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFailureRuleError(pub String);
+
+impl fmt::Display for ParseFailureRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid failure rule: {}. Use CLASS[:EXIT_CODES[:STDERR_REGEX]]",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseFailureRuleError {}
+
+impl FromStr for FailureRule {
+    type Err = ParseFailureRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, ':');
+        let class = parts
+            .next()
+            .unwrap_or_default()
+            .parse::<FailureClass>()
+            .map_err(|e| ParseFailureRuleError(e.to_string()))?;
+        let exit_codes = parts
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|code| !code.is_empty())
+            .map(|code| {
+                code.trim()
+                    .parse::<i32>()
+                    .map_err(|_| ParseFailureRuleError(format!("bad exit code '{}'", code)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let rule = FailureRule {
+            class,
+            exit_codes,
+            stderr: parts.next().map(str::to_string),
+        };
+        rule.validate()
+            .map_err(|e| ParseFailureRuleError(e.to_string()))?;
+        Ok(rule)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum MappingType {
@@ -362,6 +707,121 @@ pub struct SlurmTime(String);
 
 impl_string_newtype!(SlurmTime);
 
+impl SlurmTime {
+    pub fn to_seconds(&self) -> Option<u64> {
+        let s = self.0.trim();
+        let (days, rest) = match s.split_once('-') {
+            Some((d, rest)) => (Some(d.parse::<u64>().ok()?), rest),
+            None => (None, s),
+        };
+        let parts = rest
+            .split(':')
+            .map(|p| p.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let (h, m, sec) = match (days, parts.as_slice()) {
+            (None, [m]) => (0, *m, 0),
+            (None, [m, sec]) => (0, *m, *sec),
+            (Some(_), [h]) => (*h, 0, 0),
+            (Some(_), [h, m]) => (*h, *m, 0),
+            (_, [h, m, sec]) => (*h, *m, *sec),
+            _ => return None,
+        };
+        Some(days.unwrap_or(0) * 86400 + h * 3600 + m * 60 + sec)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "BranchQuotaValue", into = "String")]
+pub enum BranchQuota {
+    Count(usize),
+    Fraction(f64),
+}
+
+impl BranchQuota {
+    fn fraction(fraction: f64) -> Option<Self> {
+        (0.0..=1.0)
+            .contains(&fraction)
+            .then_some(BranchQuota::Fraction(fraction))
+    }
+
+    pub fn required(&self, total: usize) -> usize {
+        match *self {
+            BranchQuota::Count(n) => n.min(total),
+            BranchQuota::Fraction(f) => {
+                ((f * total as f64 - 1e-9).ceil().max(0.0) as usize).min(total)
+            }
+        }
+    }
+}
+
+impl fmt::Display for BranchQuota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BranchQuota::Count(n) => write!(f, "{}", n),
+            BranchQuota::Fraction(frac) => write!(f, "{:?}", frac),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBranchQuotaError(pub String);
+
+impl fmt::Display for ParseBranchQuotaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid branch quota: '{}'. Use a branch count, a fraction between 0 and 1, or a percentage",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseBranchQuotaError {}
+
+impl FromStr for BranchQuota {
+    type Err = ParseBranchQuotaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let err = || ParseBranchQuotaError(s.to_string());
+        let fraction = match s.strip_suffix('%') {
+            Some(pct) => pct.trim().parse::<f64>().map_err(|_| err())? / 100.0,
+            None => match s.parse::<usize>() {
+                Ok(count) => return Ok(BranchQuota::Count(count)),
+                Err(_) => s.parse::<f64>().map_err(|_| err())?,
+            },
+        };
+        BranchQuota::fraction(fraction).ok_or_else(err)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BranchQuotaValue {
+    Count(usize),
+    Fraction(f64),
+    Text(String),
+}
+
+impl TryFrom<BranchQuotaValue> for BranchQuota {
+    type Error = ParseBranchQuotaError;
+
+    fn try_from(value: BranchQuotaValue) -> Result<Self, Self::Error> {
+        match value {
+            BranchQuotaValue::Count(count) => Ok(BranchQuota::Count(count)),
+            BranchQuotaValue::Fraction(fraction) => BranchQuota::fraction(fraction)
+                .ok_or_else(|| ParseBranchQuotaError(fraction.to_string())),
+            BranchQuotaValue::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl From<BranchQuota> for String {
+    fn from(quota: BranchQuota) -> Self {
+        quota.to_string()
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ResourceHints {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -376,6 +836,12 @@ pub struct ResourceHints {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub partition: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk: Option<Memory>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub sbatch_opts: Vec<String>,
 }
@@ -391,6 +857,87 @@ pub struct Executable {
     pub resource_hints: Option<ResourceHints>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub deps: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interpreter: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<ExecutionType>,
+}
+
+impl Executable {
+    pub fn resolve_args(
+        &self,
+        job_id: &JobId,
+        params: &serde_json::Value,
+    ) -> Result<Vec<String>, crate::errors::CoreError> {
+        let mut missing = None;
+        let args = expand_arg_template(&self.args, |name| {
+            let key = name.strip_prefix("params.")?;
+            match params.get(key) {
+                Some(serde_json::Value::String(value)) => Some(value.clone()),
+                Some(value) => Some(value.to_string()),
+                None => {
+                    missing.get_or_insert_with(|| key.to_string());
+                    None
+                }
+            }
+        });
+        match missing {
+            Some(key) => Err(crate::errors::CoreError::InconsistentMetadata {
+                detail: format!(
+                    "argument template of job '{}' references unknown parameter '{}'",
+                    job_id, key
+                ),
+            }),
+            None => Ok(args),
+        }
+    }
+}
+
+pub fn expand_arg_template(
+    template: &[String],
+    mut lookup: impl FnMut(&str) -> Option<String>,
+) -> Vec<String> {
+    template
+        .iter()
+        .map(|arg| expand_placeholders(arg, &mut lookup))
+        .collect()
+}
+
+fn expand_placeholders(arg: &str, lookup: &mut impl FnMut(&str) -> Option<String>) -> String {
+    let mut expanded = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(pos) = rest.find('$') {
+        expanded.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let (name, len) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+        match (!name.is_empty()).then(|| lookup(name)).flatten() {
+            Some(value) => {
+                expanded.push_str(&value);
+                rest = &after[len..];
+            }
+            None => {
+                expanded.push('$');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -405,6 +952,8 @@ pub struct Job {
     pub executables: HashMap<String, Executable>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resource_hints: Option<ResourceHints>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub data_mounts: Vec<DataMount>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -581,4 +1130,188 @@ mod tests {
         let meta: RootMetadata = serde_json::from_str(json).expect("valid JSON must deserialize");
         assert!(meta.groups.is_empty());
     }
+
+    #[test]
+    fn test_slurm_time_to_seconds() {
+        assert_eq!(SlurmTime::from("30").to_seconds(), Some(1800));
+        assert_eq!(SlurmTime::from("5:30").to_seconds(), Some(330));
+        assert_eq!(SlurmTime::from("02:00:00").to_seconds(), Some(7200));
+        assert_eq!(SlurmTime::from("1-12").to_seconds(), Some(129600));
+        assert_eq!(SlurmTime::from("1-00:30:00").to_seconds(), Some(88200));
+        assert_eq!(SlurmTime::from("soon").to_seconds(), None);
+    }
+
+    #[test]
+    fn test_branch_quota_parse_and_required() {
+        assert_eq!("45".parse(), Ok(BranchQuota::Count(45)));
+        assert_eq!("90%".parse(), Ok(BranchQuota::Fraction(0.9)));
+        assert_eq!("0.5".parse(), Ok(BranchQuota::Fraction(0.5)));
+        assert!("1.5".parse::<BranchQuota>().is_err());
+        assert!("most".parse::<BranchQuota>().is_err());
+
+        assert_eq!(BranchQuota::Count(45).required(10), 10);
+        assert_eq!(BranchQuota::Fraction(0.9).required(45), 41);
+        assert_eq!(BranchQuota::Fraction(0.1).required(30), 3);
+
+        #[derive(Deserialize)]
+        struct Wrapper {
+            quota: BranchQuota,
+        }
+        let parse = |src: &str| toml::from_str::<Wrapper>(src).map(|w| w.quota);
+        assert_eq!(parse("quota = 3").ok(), Some(BranchQuota::Count(3)));
+        assert_eq!(parse("quota = 1.0").ok(), Some(BranchQuota::Fraction(1.0)));
+        assert_eq!(
+            parse(r#"quota = "95%""#).ok(),
+            Some(BranchQuota::Fraction(0.95))
+        );
+        assert!(parse("quota = 2.5").is_err());
+
+        assert_eq!(
+            BranchQuota::Fraction(1.0).to_string().parse(),
+            Ok(BranchQuota::Fraction(1.0))
+        );
+    }
+
+    #[test]
+    fn test_seccomp_profile_parse_and_serde() {
+        assert_eq!(
+            "default".parse::<SeccompProfile>(),
+            Ok(SeccompProfile::Default)
+        );
+        assert_eq!(
+            "/etc/repx/strict.json".parse::<SeccompProfile>(),
+            Ok(SeccompProfile::File(PathBuf::from("/etc/repx/strict.json")))
+        );
+        assert!("strict.json".parse::<SeccompProfile>().is_err());
+
+        #[derive(Deserialize)]
+        struct Wrapper {
+            seccomp: SeccompProfile,
+        }
+        let parsed: Wrapper =
+            toml::from_str(r#"seccomp = "/opt/profile.json""#).expect("valid profile");
+        assert_eq!(
+            parsed.seccomp,
+            SeccompProfile::File(PathBuf::from("/opt/profile.json"))
+        );
+        assert!(toml::from_str::<Wrapper>(r#"seccomp = "relative""#).is_err());
+    }
+
+    #[test]
+    fn test_data_mount_parse_and_serde() {
+        let plain: DataMount = "/datasets/imagenet".parse().expect("valid mount");
+        assert_eq!(plain.target(), Path::new("/datasets/imagenet"));
+        assert_eq!(plain.to_string(), "/datasets/imagenet");
+
+        let remapped: DataMount = "/scratch/imagenet:/data".parse().expect("valid mount");
+        assert_eq!(remapped.host_path, PathBuf::from("/scratch/imagenet"));
+        assert_eq!(remapped.target(), Path::new("/data"));
+        assert_eq!(remapped.to_string(), "/scratch/imagenet:/data");
+
+        assert!("datasets".parse::<DataMount>().is_err());
+        assert!("/datasets:data".parse::<DataMount>().is_err());
+
+        let job: Job = serde_json::from_value(serde_json::json!({
+            "name": null,
+            "params": {},
+            "data_mounts": ["/datasets/imagenet", "/scratch/cifar:/data/cifar"]
+        }))
+        .expect("valid job");
+        assert_eq!(
+            job.data_mounts,
+            vec![
+                plain,
+                "/scratch/cifar:/data/cifar".parse().expect("valid mount")
+            ]
+        );
+        assert!(serde_json::from_value::<Job>(serde_json::json!({
+            "name": null,
+            "params": {},
+            "data_mounts": ["relative/path"]
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn test_failure_rule_parse_and_classify() {
+        let oom: FailureRule = "infrastructure::CUDA (error|out of memory)"
+            .parse()
+            .expect("valid rule");
+        assert_eq!(oom.class, FailureClass::Infrastructure);
+        assert!(oom.exit_codes.is_empty());
+        assert_eq!(
+            oom.to_string(),
+            "infrastructure::CUDA (error|out of memory)"
+        );
+
+        let preempted: FailureRule = "retriable:75,143".parse().expect("valid rule");
+        assert_eq!(preempted.exit_codes, vec![75, 143]);
+        assert_eq!(preempted.to_string(), "retriable:75,143");
+
+        let fallback: FailureRule = "user-error".parse().expect("valid rule");
+        assert_eq!(fallback.to_string(), "user-error");
+
+        assert!("flaky:1".parse::<FailureRule>().is_err());
+        assert!("retriable:abc".parse::<FailureRule>().is_err());
+        assert!("retriable::(".parse::<FailureRule>().is_err());
+
+        let rules = vec![oom, preempted, fallback];
+        assert_eq!(
+            FailureRule::classify(&rules, 1, "RuntimeError: CUDA out of memory"),
+            Some(FailureClass::Infrastructure)
+        );
+        assert_eq!(
+            FailureRule::classify(&rules, 75, ""),
+            Some(FailureClass::Retriable)
+        );
+        assert_eq!(
+            FailureRule::classify(&rules, 2, "KeyError: 'lr'"),
+            Some(FailureClass::UserError)
+        );
+        assert_eq!(FailureRule::classify(&rules[..2], 2, ""), None);
+
+        let from_toml: FailureRule =
+            toml::from_str("class = \"retriable\"\nexit_codes = [137]").expect("valid toml");
+        assert_eq!(from_toml.exit_codes, vec![137]);
+    }
+
+    #[test]
+    fn test_executable_args_expand_params_and_paths() {
+        let exe: Executable = serde_json::from_value(serde_json::json!({
+            "path": "bin/train.py",
+            "interpreter": "python3",
+            "args": ["--out=$out", "$inputs", "--lr", "${params.lr}", "${params.name}", "$HOME"]
+        }))
+        .expect("valid executable");
+        let params = serde_json::json!({"lr": 0.01, "name": "baseline"});
+        let job_id = JobId::from("abc-train");
+
+        let resolved = exe.resolve_args(&job_id, &params).expect("params resolve");
+        assert_eq!(
+            resolved,
+            vec!["--out=$out", "$inputs", "--lr", "0.01", "baseline", "$HOME"]
+        );
+
+        let expanded = expand_arg_template(&resolved, |name| match name {
+            "out" => Some("/store/out".to_string()),
+            "inputs" => Some("/store/inputs.json".to_string()),
+            _ => None,
+        });
+        assert_eq!(
+            expanded,
+            vec![
+                "--out=/store/out",
+                "/store/inputs.json",
+                "--lr",
+                "0.01",
+                "baseline",
+                "$HOME"
+            ]
+        );
+
+        let err = exe
+            .resolve_args(&job_id, &serde_json::json!({"lr": 1}))
+            .expect_err("missing parameter must be reported");
+        assert!(err.to_string().contains("'name'"));
+    }
 }
@@ -1,4 +1,7 @@
+use crate::engine::JobStatus;
+use crate::model::{JobId, SchedulerType};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 pub const WAVE_BOUNDARY: &str = "__WAVE_BOUNDARY__";
 
@@ -15,7 +18,7 @@ pub struct StreamJob {
 
     pub script: String,
 
-    pub deps: Vec<u32>,
+    pub deps: Vec<u64>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -29,5 +32,33 @@ pub enum StreamJobType {
 pub struct StreamJobResult {
     pub id: String,
 
-    pub slurm_id: u32,
+    pub slurm_id: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AgentEvent {
+    Outcomes {
+        changed: HashMap<JobId, JobStatus>,
+        cleared: Vec<JobId>,
+    },
+    Queue {
+        scheduler: SchedulerType,
+        jobs: Vec<AgentQueuedJob>,
+    },
+    Heartbeats {
+        ages: HashMap<JobId, u64>,
+    },
+    Synced {
+        complete: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentQueuedJob {
+    pub job_id: JobId,
+    pub scheduler_id: u64,
+    pub state: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
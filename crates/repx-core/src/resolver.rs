@@ -141,6 +141,10 @@ mod tests {
             outputs: HashMap::new(),
             resource_hints: None,
             deps: vec![],
+            interpreter: None,
+            args: vec![],
+            image: None,
+            runtime: None,
         };
 
         Job {
@@ -150,6 +154,7 @@ mod tests {
             stage_type: crate::model::StageType::Simple,
             executables: HashMap::from([("main".to_string(), main_executable)]),
             resource_hints: None,
+            data_mounts: vec![],
         }
     }
 
@@ -0,0 +1,253 @@
+use crate::errors::CoreError;
+use crate::path_safety::safe_join;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const CAS_DIR: &str = "cas";
+
+const EXECUTABLE_SUFFIX: &str = ".x";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TreeEntry {
+    File {
+        path: String,
+        hash: String,
+        executable: bool,
+    },
+    Symlink {
+        path: String,
+        target: String,
+    },
+    Dir {
+        path: String,
+    },
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LinkStats {
+    pub linked: usize,
+    pub unchanged: usize,
+}
+
+pub fn blob_rel_path(hash: &str, executable: bool) -> Result<PathBuf, CoreError> {
+    if hash.len() < 3 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(CoreError::InvalidConfig {
+            detail: format!("'{}' is not a content hash", hash),
+        });
+    }
+    let name = if executable {
+        format!("{}{}", hash, EXECUTABLE_SUFFIX)
+    } else {
+        hash.to_string()
+    };
+    Ok(Path::new(CAS_DIR).join(&hash[..2]).join(name))
+}
+
+pub fn missing_blobs(
+    artifacts_dir: &Path,
+    entries: &[TreeEntry],
+) -> Result<Vec<PathBuf>, CoreError> {
+    let mut missing = Vec::new();
+    for entry in entries {
+        if let TreeEntry::File {
+            hash, executable, ..
+        } = entry
+        {
+            let rel = blob_rel_path(hash, *executable)?;
+            if !artifacts_dir.join(&rel).is_file() && !missing.contains(&rel) {
+                missing.push(rel);
+            }
+        }
+    }
+    Ok(missing)
+}
+
+pub fn link_tree(artifacts_dir: &Path, entries: &[TreeEntry]) -> Result<LinkStats, CoreError> {
+    let mut stats = LinkStats::default();
+    for entry in entries {
+        match entry {
+            TreeEntry::Dir { path } => {
+                let dest = safe_join(artifacts_dir, path)?;
+                fs::create_dir_all(&dest).map_err(|e| CoreError::path_io(&dest, e))?;
+            }
+            TreeEntry::Symlink { path, target } => {
+                let dest = safe_join(artifacts_dir, path)?;
+                if fs::read_link(&dest).is_ok_and(|existing| existing == Path::new(target)) {
+                    stats.unchanged += 1;
+                    continue;
+                }
+                prepare_dest(&dest)?;
+                std::os::unix::fs::symlink(target, &dest)
+                    .map_err(|e| CoreError::path_io(&dest, e))?;
+                stats.linked += 1;
+            }
+            TreeEntry::File {
+                path,
+                hash,
+                executable,
+            } => {
+                let dest = safe_join(artifacts_dir, path)?;
+                let blob = artifacts_dir.join(blob_rel_path(hash, *executable)?);
+                let blob_meta = fs::metadata(&blob).map_err(|e| CoreError::path_io(&blob, e))?;
+                let mode = if *executable { 0o555 } else { 0o444 };
+                if blob_meta.mode() & 0o777 != mode {
+                    fs::set_permissions(&blob, fs::Permissions::from_mode(mode))
+                        .map_err(|e| CoreError::path_io(&blob, e))?;
+                }
+                let same_inode = fs::symlink_metadata(&dest).is_ok_and(|meta| {
+                    meta.dev() == blob_meta.dev() && meta.ino() == blob_meta.ino()
+                });
+                if same_inode {
+                    stats.unchanged += 1;
+                    continue;
+                }
+                prepare_dest(&dest)?;
+                fs::hard_link(&blob, &dest).map_err(|e| CoreError::path_io(&dest, e))?;
+                stats.linked += 1;
+            }
+        }
+    }
+    Ok(stats)
+}
+
+fn prepare_dest(dest: &Path) -> Result<(), CoreError> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| CoreError::path_io(parent, e))?;
+    }
+    match fs::symlink_metadata(dest) {
+        Ok(meta) if meta.is_dir() => {
+            crate::fs_utils::force_remove_dir(dest).map_err(|e| CoreError::path_io(dest, e))
+        }
+        Ok(_) => fs::remove_file(dest).map_err(|e| CoreError::path_io(dest, e)),
+        Err(_) => Ok(()),
+    }
+}
+
+pub fn unreferenced_blobs(artifacts_dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(artifacts_dir.join(CAS_DIR))
+        .min_depth(2)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| entry.metadata().is_ok_and(|meta| meta.nlink() <= 1))
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_blob(artifacts: &Path, hash: &str, executable: bool, content: &str) {
+        let blob = artifacts.join(blob_rel_path(hash, executable).expect("blob path"));
+        fs::create_dir_all(blob.parent().expect("blob parent")).expect("create fan-out dir");
+        fs::write(blob, content).expect("write blob");
+    }
+
+    fn file(path: &str, hash: &str, executable: bool) -> TreeEntry {
+        TreeEntry::File {
+            path: path.to_string(),
+            hash: hash.to_string(),
+            executable,
+        }
+    }
+
+    #[test]
+    fn test_blob_rel_path() {
+        assert_eq!(
+            blob_rel_path("abcdef", false).expect("blob path"),
+            PathBuf::from("cas/ab/abcdef")
+        );
+        assert_eq!(
+            blob_rel_path("abcdef", true).expect("blob path"),
+            PathBuf::from("cas/ab/abcdef.x")
+        );
+        assert!(blob_rel_path("../etc", false).is_err());
+    }
+
+    #[test]
+    fn test_link_tree_shares_blobs_between_labs() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let artifacts = dir.path();
+        add_blob(artifacts, "aaaa", true, "tool");
+        add_blob(artifacts, "bbbb", false, "data");
+
+        let entries = vec![
+            TreeEntry::Dir {
+                path: "jobs/empty".to_string(),
+            },
+            file("jobs/one/bin/tool", "aaaa", true),
+            file("jobs/two/bin/tool", "aaaa", true),
+            file("jobs/one/data", "bbbb", false),
+            TreeEntry::Symlink {
+                path: "jobs/one/latest".to_string(),
+                target: "data".to_string(),
+            },
+        ];
+        let stats = link_tree(artifacts, &entries).expect("link tree");
+        assert_eq!(stats.linked, 4);
+        assert!(artifacts.join("jobs/empty").is_dir());
+
+        let one = fs::metadata(artifacts.join("jobs/one/bin/tool")).expect("stat link");
+        let two = fs::metadata(artifacts.join("jobs/two/bin/tool")).expect("stat link");
+        assert_eq!(one.ino(), two.ino());
+        assert_eq!(one.mode() & 0o777, 0o555);
+        assert_eq!(
+            fs::read_to_string(artifacts.join("jobs/one/latest")).expect("read link"),
+            "data"
+        );
+
+        let again = link_tree(artifacts, &entries).expect("link tree");
+        assert_eq!(again.linked, 0);
+        assert_eq!(again.unchanged, 4);
+    }
+
+    #[test]
+    fn test_link_tree_replaces_changed_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let artifacts = dir.path();
+        fs::create_dir_all(artifacts.join("lab")).expect("write fixture");
+        fs::write(artifacts.join("lab/meta.json"), "old").expect("write fixture");
+        add_blob(artifacts, "cccc", false, "new");
+
+        let stats =
+            link_tree(artifacts, &[file("lab/meta.json", "cccc", false)]).expect("link tree");
+        assert_eq!(stats.linked, 1);
+        assert_eq!(
+            fs::read_to_string(artifacts.join("lab/meta.json")).expect("read link"),
+            "new"
+        );
+    }
+
+    #[test]
+    fn test_link_tree_rejects_escaping_paths() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        add_blob(dir.path(), "dddd", false, "x");
+        assert!(link_tree(dir.path(), &[file("../outside", "dddd", false)]).is_err());
+    }
+
+    #[test]
+    fn test_missing_and_unreferenced_blobs() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let artifacts = dir.path();
+        add_blob(artifacts, "aaaa", false, "kept");
+        add_blob(artifacts, "eeee", false, "orphan");
+
+        let entries = vec![file("a", "aaaa", false), file("b", "ffff", false)];
+        assert_eq!(
+            missing_blobs(artifacts, &entries).expect("missing blobs"),
+            vec![PathBuf::from("cas/ff/ffff")]
+        );
+
+        link_tree(artifacts, &entries[..1]).expect("link tree");
+        assert_eq!(
+            unreferenced_blobs(artifacts),
+            vec![artifacts.join("cas/ee/eeee")]
+        );
+    }
+}
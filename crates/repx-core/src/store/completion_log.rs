@@ -1,9 +1,14 @@
-use crate::{engine::JobStatus, errors::CoreError, model::JobId};
+use crate::{
+    engine::JobStatus,
+    errors::CoreError,
+    model::{FailureClass, JobId},
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::{self, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 const COMPLETIONS_FILE: &str = "completions.jsonl";
 
@@ -11,6 +16,10 @@ const COMPLETIONS_FILE: &str = "completions.jsonl";
 struct CompletionRecord {
     id: String,
     s: CompletionStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    d: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    c: Option<FailureClass>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
@@ -19,6 +28,12 @@ enum CompletionStatus {
     Ok,
     #[serde(rename = "fail")]
     Fail,
+    #[serde(rename = "timeout")]
+    Timeout,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+    #[serde(rename = "cleared")]
+    Cleared,
 }
 
 pub fn completions_path(base_path: &Path) -> PathBuf {
@@ -29,6 +44,47 @@ pub fn append_completion(
     base_path: &Path,
     job_id: &JobId,
     succeeded: bool,
+    duration: Option<Duration>,
+) -> Result<(), CoreError> {
+    let status = if succeeded {
+        CompletionStatus::Ok
+    } else {
+        CompletionStatus::Fail
+    };
+    append_record(base_path, job_id, status, duration, None)
+}
+
+pub fn append_failure(
+    base_path: &Path,
+    job_id: &JobId,
+    class: Option<FailureClass>,
+    duration: Option<Duration>,
+) -> Result<(), CoreError> {
+    append_record(base_path, job_id, CompletionStatus::Fail, duration, class)
+}
+
+pub fn append_timeout(
+    base_path: &Path,
+    job_id: &JobId,
+    duration: Option<Duration>,
+) -> Result<(), CoreError> {
+    append_record(base_path, job_id, CompletionStatus::Timeout, duration, None)
+}
+
+pub fn append_cancelled(base_path: &Path, job_id: &JobId) -> Result<(), CoreError> {
+    append_record(base_path, job_id, CompletionStatus::Cancelled, None, None)
+}
+
+pub fn append_cleared(base_path: &Path, job_id: &JobId) -> Result<(), CoreError> {
+    append_record(base_path, job_id, CompletionStatus::Cleared, None, None)
+}
+
+fn append_record(
+    base_path: &Path,
+    job_id: &JobId,
+    status: CompletionStatus,
+    duration: Option<Duration>,
+    class: Option<FailureClass>,
 ) -> Result<(), CoreError> {
     let path = completions_path(base_path);
     if let Some(parent) = path.parent() {
@@ -37,11 +93,9 @@ pub fn append_completion(
 
     let record = CompletionRecord {
         id: job_id.to_string(),
-        s: if succeeded {
-            CompletionStatus::Ok
-        } else {
-            CompletionStatus::Fail
-        },
+        s: status,
+        d: duration.map(|d| d.as_secs_f64()),
+        c: class,
     };
 
     let mut line = serde_json::to_string(&record)?;
@@ -52,10 +106,7 @@ pub fn append_completion(
     Ok(())
 }
 
-pub fn read_completions(
-    base_path: &Path,
-    location: &str,
-) -> Result<Option<HashMap<JobId, JobStatus>>, CoreError> {
+fn read_records(base_path: &Path) -> Result<Option<Vec<CompletionRecord>>, CoreError> {
     let path = completions_path(base_path);
     if !path.exists() {
         return Ok(None);
@@ -63,7 +114,7 @@ pub fn read_completions(
 
     let file = fs::File::open(&path)?;
     let reader = BufReader::with_capacity(1024 * 1024, file);
-    let mut outcomes = HashMap::new();
+    let mut records = Vec::new();
 
     for (line_num, line_result) in reader.lines().enumerate() {
         let line = match line_result {
@@ -83,8 +134,8 @@ pub fn read_completions(
             continue;
         }
 
-        let record: CompletionRecord = match serde_json::from_str(trimmed) {
-            Ok(r) => r,
+        match serde_json::from_str(trimmed) {
+            Ok(record) => records.push(record),
             Err(e) => {
                 tracing::warn!(
                     "Completion log line {} parse error ({}), skipping: {}",
@@ -92,25 +143,112 @@ pub fn read_completions(
                     e,
                     truncate_for_log(trimmed, 120)
                 );
-                continue;
             }
-        };
+        }
+    }
 
-        let job_id = JobId::from(record.id);
-        let status = match record.s {
-            CompletionStatus::Ok => JobStatus::Succeeded {
-                location: location.to_string(),
-            },
-            CompletionStatus::Fail => JobStatus::Failed {
-                location: location.to_string(),
-            },
-        };
-        outcomes.insert(job_id, status);
+    Ok(Some(records))
+}
+
+pub fn read_completions(
+    base_path: &Path,
+    location: &str,
+) -> Result<Option<HashMap<JobId, JobStatus>>, CoreError> {
+    let Some(records) = read_records(base_path)? else {
+        return Ok(None);
+    };
+
+    let mut outcomes = HashMap::new();
+    for record in records {
+        apply_record(record, location, &mut outcomes);
     }
 
     Ok(Some(outcomes))
 }
 
+pub fn apply_completions(
+    content: &str,
+    location: &str,
+    outcomes: &mut HashMap<JobId, JobStatus>,
+) -> usize {
+    let complete = content.rfind('\n').map_or(0, |end| end + 1);
+    for line in content[..complete].lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match serde_json::from_str(trimmed) {
+            Ok(record) => apply_record(record, location, outcomes),
+            Err(e) => tracing::warn!(
+                "Completion log parse error ({}), skipping: {}",
+                e,
+                truncate_for_log(trimmed, 120)
+            ),
+        }
+    }
+    complete
+}
+
+fn apply_record(
+    record: CompletionRecord,
+    location: &str,
+    outcomes: &mut HashMap<JobId, JobStatus>,
+) {
+    let status = match record.s {
+        CompletionStatus::Ok => JobStatus::Succeeded {
+            location: location.to_string(),
+        },
+        CompletionStatus::Fail => JobStatus::Failed {
+            location: location.to_string(),
+            class: record.c,
+            reason: None,
+        },
+        CompletionStatus::Timeout => JobStatus::TimedOut {
+            location: location.to_string(),
+        },
+        CompletionStatus::Cancelled => JobStatus::Cancelled {
+            location: location.to_string(),
+        },
+        CompletionStatus::Cleared => {
+            outcomes.remove(&JobId::from(record.id));
+            return;
+        }
+    };
+    outcomes.insert(JobId::from(record.id), status);
+}
+
+pub fn read_durations(base_path: &Path) -> Result<HashMap<JobId, Duration>, CoreError> {
+    let mut durations = HashMap::new();
+    for record in read_records(base_path)?.unwrap_or_default() {
+        let job_id = JobId::from(record.id);
+        match (record.s, record.d) {
+            (CompletionStatus::Ok, Some(secs)) if secs.is_finite() && secs >= 0.0 => {
+                durations.insert(job_id, Duration::from_secs_f64(secs));
+            }
+            _ => {
+                durations.remove(&job_id);
+            }
+        }
+    }
+    Ok(durations)
+}
+
+pub fn read_elapsed(base_path: &Path) -> Result<HashMap<JobId, Duration>, CoreError> {
+    let mut elapsed = HashMap::new();
+    for record in read_records(base_path)?.unwrap_or_default() {
+        let job_id = JobId::from(record.id);
+        match record.d {
+            Some(secs) if secs.is_finite() && secs >= 0.0 => {
+                elapsed.insert(job_id, Duration::from_secs_f64(secs));
+            }
+            _ => {
+                elapsed.remove(&job_id);
+            }
+        }
+    }
+    Ok(elapsed)
+}
+
 fn truncate_for_log(s: &str, max_len: usize) -> &str {
     crate::fs_utils::safe_truncate_ref(s, max_len)
 }
@@ -129,9 +267,9 @@ mod tests {
         let job2 = JobId::from("def456-stage-b-2.0");
         let job3 = JobId::from("ghi789-stage-c-3.0");
 
-        append_completion(base, &job1, true).expect("append job1");
-        append_completion(base, &job2, false).expect("append job2");
-        append_completion(base, &job3, true).expect("append job3");
+        append_completion(base, &job1, true, None).expect("append job1");
+        append_completion(base, &job2, false, None).expect("append job2");
+        append_completion(base, &job3, true, None).expect("append job3");
 
         let outcomes = read_completions(base, "test-target")
             .expect("read")
@@ -152,6 +290,28 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_failure_class_round_trip() {
+        let dir = tempdir().expect("tempdir");
+        let base = dir.path();
+        let job = JobId::from("abc123-oom-job-1.0");
+
+        append_failure(base, &job, Some(FailureClass::Infrastructure), None)
+            .expect("append failure");
+
+        let outcomes = read_completions(base, "test-target")
+            .expect("read")
+            .expect("some outcomes");
+        assert_eq!(
+            outcomes.get(&job),
+            Some(&JobStatus::Failed {
+                location: "test-target".to_string(),
+                class: Some(FailureClass::Infrastructure),
+                reason: None,
+            })
+        );
+    }
+
     #[test]
     fn test_last_write_wins() {
         let dir = tempdir().expect("tempdir");
@@ -159,8 +319,8 @@ mod tests {
 
         let job = JobId::from("abc123-retry-job-1.0");
 
-        append_completion(base, &job, false).expect("append fail");
-        append_completion(base, &job, true).expect("append success");
+        append_completion(base, &job, false, None).expect("append fail");
+        append_completion(base, &job, true, None).expect("append success");
 
         let outcomes = read_completions(base, "test")
             .expect("read")
@@ -171,6 +331,85 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_cleared_forgets_earlier_outcome() {
+        let dir = tempdir().expect("tempdir");
+        let base = dir.path();
+
+        let cleared = JobId::from("abc123-cleared-job-1.0");
+        let kept = JobId::from("def456-kept-job-1.0");
+        append_completion(base, &cleared, true, Some(Duration::from_secs(5)))
+            .expect("append success");
+        append_completion(base, &kept, true, None).expect("append success");
+        append_cleared(base, &cleared).expect("append cleared");
+
+        let outcomes = read_completions(base, "test")
+            .expect("read")
+            .expect("some outcomes");
+        assert!(!outcomes.contains_key(&cleared));
+        assert!(outcomes.contains_key(&kept));
+        assert!(!read_durations(base)
+            .expect("read durations")
+            .contains_key(&cleared));
+    }
+
+    #[test]
+    fn test_timeout_is_distinct_from_failure() {
+        let dir = tempdir().expect("tempdir");
+        let base = dir.path();
+
+        let job = JobId::from("abc123-slow-job-1.0");
+        append_timeout(base, &job, Some(Duration::from_secs(60))).expect("append timeout");
+
+        let outcomes = read_completions(base, "test")
+            .expect("read")
+            .expect("some outcomes");
+        assert!(matches!(
+            outcomes.get(&job),
+            Some(JobStatus::TimedOut { .. })
+        ));
+        assert!(read_durations(base).expect("read").is_empty());
+    }
+
+    #[test]
+    fn test_cancelled_overrides_earlier_failure() {
+        let dir = tempdir().expect("tempdir");
+        let base = dir.path();
+
+        let job = JobId::from("abc123-long-job-1.0");
+        append_failure(base, &job, None, Some(Duration::from_secs(5))).expect("append fail");
+        append_cancelled(base, &job).expect("append cancelled");
+
+        let outcomes = read_completions(base, "test")
+            .expect("read")
+            .expect("some outcomes");
+        assert!(matches!(
+            outcomes.get(&job),
+            Some(JobStatus::Cancelled { .. })
+        ));
+        assert!(read_elapsed(base).expect("read").is_empty());
+    }
+
+    #[test]
+    fn test_apply_completions_leaves_partial_line() {
+        let job = JobId::from("abc123-tail-job-1.0");
+        let other = JobId::from("def456-tail-job-1.0");
+        let mut outcomes = HashMap::new();
+
+        let chunk = "{\"id\":\"abc123-tail-job-1.0\",\"s\":\"ok\"}\n{\"id\":\"def4";
+        let consumed = apply_completions(chunk, "test", &mut outcomes);
+        assert_eq!(&chunk[consumed..], "{\"id\":\"def4");
+        assert_eq!(outcomes.len(), 1);
+
+        let rest = "{\"id\":\"def456-tail-job-1.0\",\"s\":\"fail\"}\n{\"id\":\"abc123-tail-job-1.0\",\"s\":\"cleared\"}\n";
+        assert_eq!(apply_completions(rest, "test", &mut outcomes), rest.len());
+        assert!(!outcomes.contains_key(&job));
+        assert!(matches!(
+            outcomes.get(&other),
+            Some(JobStatus::Failed { .. })
+        ));
+    }
+
     #[test]
     fn test_no_log_returns_none() {
         let dir = tempdir().expect("tempdir");
@@ -224,4 +463,33 @@ this is garbage
             .expect("some outcomes");
         assert!(outcomes.is_empty());
     }
+
+    #[test]
+    fn test_read_durations() {
+        let dir = tempdir().expect("tempdir");
+        let base = dir.path();
+
+        let timed = JobId::from("abc123-timed-1.0");
+        let untimed = JobId::from("def456-untimed-1.0");
+        let retried = JobId::from("ghi789-retried-1.0");
+
+        append_completion(base, &timed, true, Some(Duration::from_secs(90))).expect("append");
+        append_completion(base, &untimed, true, None).expect("append");
+        append_completion(base, &retried, true, Some(Duration::from_secs(5))).expect("append");
+        append_completion(base, &retried, false, Some(Duration::from_secs(1))).expect("append");
+
+        let durations = read_durations(base).expect("read");
+        assert_eq!(durations.len(), 1);
+        assert_eq!(durations.get(&timed), Some(&Duration::from_secs(90)));
+
+        let elapsed = read_elapsed(base).expect("read");
+        assert_eq!(elapsed.len(), 2);
+        assert_eq!(elapsed.get(&retried), Some(&Duration::from_secs(1)));
+        assert!(!elapsed.contains_key(&untimed));
+
+        let outcomes = read_completions(base, "test")
+            .expect("read")
+            .expect("some outcomes");
+        assert_eq!(outcomes.len(), 3);
+    }
 }
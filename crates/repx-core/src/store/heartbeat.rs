@@ -0,0 +1,116 @@
+use crate::{constants::dirs, errors::CoreError, model::JobId};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+pub const HEARTBEAT_FILE: &str = "heartbeat";
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(30);
+pub const MISSED_BEATS_BEFORE_LOST: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liveness {
+    Alive,
+    Lost,
+}
+
+pub fn heartbeat_path(repx_dir: &Path) -> PathBuf {
+    repx_dir.join(HEARTBEAT_FILE)
+}
+
+pub fn beat(repx_dir: &Path) -> Result<(), CoreError> {
+    let path = heartbeat_path(repx_dir);
+    let tmp = repx_dir.join(format!("{}.tmp", HEARTBEAT_FILE));
+    fs::write(&tmp, Utc::now().to_rfc3339())?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+pub fn liveness(age: Duration, interval: Duration) -> Liveness {
+    if age > interval.saturating_mul(MISSED_BEATS_BEFORE_LOST) {
+        Liveness::Lost
+    } else {
+        Liveness::Alive
+    }
+}
+
+pub fn scan_heartbeats(base_path: &Path) -> Result<HashMap<JobId, Duration>, CoreError> {
+    let outputs_path = base_path.join(dirs::OUTPUTS);
+    let mut ages = HashMap::new();
+    if !outputs_path.exists() {
+        return Ok(ages);
+    }
+    let now = SystemTime::now();
+    for entry in WalkDir::new(&outputs_path)
+        .min_depth(3)
+        .max_depth(3)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        let path = entry.path();
+        if path.file_name().and_then(|s| s.to_str()) != Some(HEARTBEAT_FILE) {
+            continue;
+        }
+        let Some(repx_dir) = path.parent() else {
+            continue;
+        };
+        if repx_dir.file_name().and_then(|s| s.to_str()) != Some(dirs::REPX) {
+            continue;
+        }
+        let Some(job_id) = repx_dir
+            .parent()
+            .and_then(|job_dir| job_dir.file_name())
+            .and_then(|s| s.to_str())
+        else {
+            continue;
+        };
+        let Ok(modified) = fs::metadata(path).and_then(|meta| meta.modified()) else {
+            continue;
+        };
+        let age = now.duration_since(modified).unwrap_or(Duration::ZERO);
+        ages.insert(JobId::from(job_id.to_string()), age);
+    }
+    Ok(ages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_liveness_allows_missed_beats_before_declaring_lost() {
+        let interval = Duration::from_secs(30);
+        assert_eq!(liveness(Duration::from_secs(5), interval), Liveness::Alive);
+        assert_eq!(
+            liveness(Duration::from_secs(300), interval),
+            Liveness::Alive
+        );
+        assert_eq!(liveness(Duration::from_secs(301), interval), Liveness::Lost);
+    }
+
+    #[test]
+    fn test_scan_heartbeats_finds_beating_jobs() {
+        let temp = tempdir().expect("tempdir");
+        let repx_dir = temp
+            .path()
+            .join(dirs::OUTPUTS)
+            .join("job-a")
+            .join(dirs::REPX);
+        fs::create_dir_all(&repx_dir).expect("create repx dir");
+        fs::create_dir_all(
+            temp.path()
+                .join(dirs::OUTPUTS)
+                .join("job-b")
+                .join(dirs::REPX),
+        )
+        .expect("create repx dir");
+        beat(&repx_dir).expect("beat");
+
+        let ages = scan_heartbeats(temp.path()).expect("scan");
+        assert_eq!(ages.len(), 1);
+        assert!(ages[&JobId::from("job-a")] < Duration::from_secs(60));
+    }
+}
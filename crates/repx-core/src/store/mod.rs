@@ -1,3 +1,8 @@
 pub mod artifacts;
+pub mod cas;
 pub mod completion_log;
+pub mod heartbeat;
 pub mod outcomes;
+pub mod progress;
+pub mod slurm_failure;
+pub mod work_items;
@@ -1,19 +1,60 @@
 use crate::{
     constants::{dirs, markers},
     errors::CoreError,
-    model::JobId,
+    model::{FailureClass, JobId},
     path_safety::safe_join,
+    store::completion_log,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use walkdir::WalkDir;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum JobOutcome {
     Succeeded,
     Failed,
+    TimedOut,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<FailureClass>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+impl FailureInfo {
+    pub fn write_marker(&self, repx_dir: &Path) -> Result<(), CoreError> {
+        let json = serde_json::to_vec(self)?;
+        crate::fs_utils::write_atomic(&repx_dir.join(markers::FAIL), &json)?;
+        Ok(())
+    }
+
+    pub fn read_marker(repx_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(repx_dir.join(markers::FAIL)).ok()?;
+        if content.trim().is_empty() {
+            return Some(Self::default());
+        }
+        match serde_json::from_str(&content) {
+            Ok(info) => Some(info),
+            Err(e) => {
+                tracing::debug!(
+                    "Unreadable failure metadata in {}: {}",
+                    repx_dir.display(),
+                    e
+                );
+                Some(Self::default())
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -41,28 +82,78 @@ pub fn get_job_outcomes(
         let repx_dir = job_path.join(dirs::REPX);
         let success_marker = repx_dir.join(markers::SUCCESS);
         let fail_marker = repx_dir.join(markers::FAIL);
-
-        if success_marker.exists() {
-            outcomes.insert(
-                job_id.clone(),
-                FoundJob {
-                    outcome: JobOutcome::Succeeded,
-                    path: job_path,
-                },
-            );
+        let timeout_marker = repx_dir.join(markers::TIMEOUT);
+        let cancelled_marker = repx_dir.join(markers::CANCELLED);
+
+        let outcome = if success_marker.exists() {
+            JobOutcome::Succeeded
+        } else if cancelled_marker.exists() {
+            JobOutcome::Cancelled
+        } else if timeout_marker.exists() {
+            JobOutcome::TimedOut
         } else if fail_marker.exists() {
-            outcomes.insert(
-                job_id.clone(),
-                FoundJob {
-                    outcome: JobOutcome::Failed,
-                    path: job_path,
-                },
-            );
-        }
+            JobOutcome::Failed
+        } else {
+            continue;
+        };
+        outcomes.insert(
+            job_id.clone(),
+            FoundJob {
+                outcome,
+                path: job_path,
+            },
+        );
     }
     Ok(outcomes)
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobTiming {
+    pub outcome: JobOutcome,
+    pub finished_at: DateTime<Utc>,
+    pub elapsed: Option<Duration>,
+}
+
+pub fn get_job_timings(
+    store_path: &Path,
+    job_ids_to_check: &[JobId],
+) -> Result<HashMap<JobId, JobTiming>, CoreError> {
+    let outcomes = get_job_outcomes(store_path, job_ids_to_check)?;
+    if outcomes.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let elapsed = completion_log::read_elapsed(store_path)?;
+
+    let mut timings = HashMap::new();
+    for (job_id, found) in outcomes {
+        let marker = match found.outcome {
+            JobOutcome::Succeeded => markers::SUCCESS,
+            JobOutcome::Failed => markers::FAIL,
+            JobOutcome::TimedOut => markers::TIMEOUT,
+            JobOutcome::Cancelled => markers::CANCELLED,
+        };
+        let modified = match fs::metadata(found.path.join(dirs::REPX).join(marker))
+            .and_then(|meta| meta.modified())
+        {
+            Ok(modified) => modified,
+            Err(e) => {
+                tracing::debug!("No completion time for job '{}': {}", job_id, e);
+                continue;
+            }
+        };
+        let elapsed = elapsed.get(&job_id).copied();
+        timings.insert(
+            job_id,
+            JobTiming {
+                outcome: found.outcome,
+                finished_at: modified.into(),
+                elapsed,
+            },
+        );
+    }
+    Ok(timings)
+}
+
 pub struct MergeProgress {
     pub total_entries: u64,
     pub processed_entries: u64,
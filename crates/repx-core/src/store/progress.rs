@@ -0,0 +1,228 @@
+use crate::{errors::CoreError, fs_utils};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const PROGRESS_FILE: &str = "progress.json";
+
+const PROGRESS_LOCK_FILE: &str = "progress.lock";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepProgress {
+    pub name: String,
+    pub pending: usize,
+    pub running: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScatterProgress {
+    pub branches_total: usize,
+    pub branches_succeeded: usize,
+    pub branches_failed: usize,
+    pub sink_step: String,
+    pub steps: Vec<StepProgress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gather: Option<StepState>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub slurm_ids: BTreeMap<String, Vec<u64>>,
+    pub branches: BTreeMap<usize, BTreeMap<String, StepState>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl ScatterProgress {
+    pub fn start(&mut self, steps: &[String], sink_step: &str) {
+        *self = Self {
+            sink_step: sink_step.to_string(),
+            steps: steps
+                .iter()
+                .map(|name| StepProgress {
+                    name: name.clone(),
+                    ..StepProgress::default()
+                })
+                .collect(),
+            ..Self::default()
+        };
+    }
+
+    pub fn set_step(&mut self, branch: usize, step: &str, state: StepState) {
+        self.branches
+            .entry(branch)
+            .or_default()
+            .insert(step.to_string(), state);
+    }
+
+    fn recount(&mut self) {
+        for step in &mut self.steps {
+            let (mut running, mut succeeded, mut failed) = (0, 0, 0);
+            for states in self.branches.values() {
+                match states.get(&step.name) {
+                    Some(StepState::Running) => running += 1,
+                    Some(StepState::Succeeded) => succeeded += 1,
+                    Some(StepState::Failed) => failed += 1,
+                    None => {}
+                }
+            }
+            step.running = running;
+            step.succeeded = succeeded;
+            step.failed = failed;
+            step.pending = self
+                .branches_total
+                .saturating_sub(running + succeeded + failed);
+        }
+        self.branches_succeeded = self
+            .branches
+            .values()
+            .filter(|states| states.get(&self.sink_step) == Some(&StepState::Succeeded))
+            .count();
+        self.branches_failed = self
+            .branches
+            .values()
+            .filter(|states| {
+                states.get(&self.sink_step) != Some(&StepState::Succeeded)
+                    && states.values().any(|state| *state == StepState::Failed)
+            })
+            .count();
+    }
+
+    pub fn summary(&self) -> String {
+        if self.branches_total == 0 {
+            return "scatter running".to_string();
+        }
+        let mut parts = vec![format!(
+            "{}/{} branches done",
+            self.branches_succeeded, self.branches_total
+        )];
+        if self.branches_failed > 0 {
+            parts.push(format!("{} failed", self.branches_failed));
+        }
+        let running: Vec<&StepProgress> = self.steps.iter().filter(|s| s.running > 0).collect();
+        match running.as_slice() {
+            [] => {}
+            [step] if step.running == 1 => parts.push(format!("step {} running", step.name)),
+            [step] => parts.push(format!(
+                "step {} running in {} branches",
+                step.name, step.running
+            )),
+            steps => parts.push(format!(
+                "steps {} running",
+                steps
+                    .iter()
+                    .map(|s| s.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+        match self.gather {
+            Some(StepState::Running) => parts.push("gather running".to_string()),
+            Some(StepState::Succeeded) => parts.push("gather done".to_string()),
+            Some(StepState::Failed) => parts.push("gather failed".to_string()),
+            None => {}
+        }
+        parts.join(", ")
+    }
+}
+
+pub fn path(repx_dir: &Path) -> PathBuf {
+    repx_dir.join(PROGRESS_FILE)
+}
+
+pub fn read(repx_dir: &Path) -> Result<Option<ScatterProgress>, CoreError> {
+    match fs::read_to_string(path(repx_dir)) {
+        Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn update(repx_dir: &Path, change: impl FnOnce(&mut ScatterProgress)) -> Result<(), CoreError> {
+    fs::create_dir_all(repx_dir)?;
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(repx_dir.join(PROGRESS_LOCK_FILE))?;
+    lock.lock()?;
+
+    let mut progress = match read(repx_dir) {
+        Ok(progress) => progress.unwrap_or_default(),
+        Err(e) => {
+            tracing::debug!(
+                "Discarding unreadable progress in {}: {}",
+                repx_dir.display(),
+                e
+            );
+            ScatterProgress::default()
+        }
+    };
+    change(&mut progress);
+    progress.recount();
+    progress.updated_at = Some(Utc::now());
+    fs_utils::write_atomic_nosync(&path(repx_dir), &serde_json::to_vec_pretty(&progress)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_counts_branches_and_steps() {
+        let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
+        let steps = ["gen".to_string(), "align".to_string()];
+        update(tmp.path(), |p| {
+            p.start(&steps, "align");
+            p.branches_total = 3;
+        })
+        .expect("update must succeed");
+        update(tmp.path(), |p| {
+            p.set_step(0, "gen", StepState::Succeeded);
+            p.set_step(0, "align", StepState::Succeeded);
+            p.set_step(1, "gen", StepState::Succeeded);
+            p.set_step(1, "align", StepState::Running);
+            p.set_step(2, "gen", StepState::Failed);
+        })
+        .expect("update must succeed");
+
+        let progress = read(tmp.path())
+            .expect("progress must read")
+            .expect("progress must exist");
+        assert_eq!(progress.branches_succeeded, 1);
+        assert_eq!(progress.branches_failed, 1);
+        assert_eq!(
+            progress.steps[1],
+            StepProgress {
+                name: "align".to_string(),
+                pending: 1,
+                running: 1,
+                succeeded: 1,
+                failed: 0,
+            }
+        );
+        assert_eq!(
+            progress.summary(),
+            "1/3 branches done, 1 failed, step align running"
+        );
+
+        update(tmp.path(), |p| p.start(&steps, "align")).expect("update must succeed");
+        let restarted = read(tmp.path())
+            .expect("progress must read")
+            .expect("progress must exist");
+        assert!(restarted.branches.is_empty());
+        assert_eq!(restarted.summary(), "scatter running");
+    }
+}
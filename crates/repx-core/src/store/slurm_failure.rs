@@ -0,0 +1,217 @@
+use crate::{
+    engine::JobStatus,
+    errors::CoreError,
+    model::{FailureClass, Memory},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const SLURM_FAILURE_FILE: &str = "slurm_failure.json";
+
+pub const SACCT_FIELDS: &str = "JobID,State,ExitCode,Reason,MaxRSS,ReqMem";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlurmFailure {
+    pub slurm_id: u64,
+    pub state: String,
+    pub exit_code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rss: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub req_mem: Option<String>,
+}
+
+impl SlurmFailure {
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self.state.as_str(),
+            "FAILED"
+                | "NODE_FAIL"
+                | "OUT_OF_MEMORY"
+                | "TIMEOUT"
+                | "CANCELLED"
+                | "BOOT_FAIL"
+                | "DEADLINE"
+                | "PREEMPTED"
+        )
+    }
+
+    pub fn summary(&self) -> String {
+        match self.state.as_str() {
+            "OUT_OF_MEMORY" => match (&self.req_mem, &self.max_rss) {
+                (Some(req_mem), _) => format!("OUT_OF_MEMORY at {}", req_mem),
+                (None, Some(max_rss)) => format!("OUT_OF_MEMORY (MaxRSS {})", max_rss),
+                (None, None) => self.state.clone(),
+            },
+            "FAILED" => match self.exit_code.split_once(':') {
+                Some((_, signal)) if signal != "0" => format!("FAILED signal {}", signal),
+                Some((code, _)) => format!("FAILED exit {}", code),
+                None => self.state.clone(),
+            },
+            _ => match &self.reason {
+                Some(reason) => format!("{} ({})", self.state, reason),
+                None => self.state.clone(),
+            },
+        }
+    }
+
+    pub fn status(&self, location: &str) -> JobStatus {
+        let location = location.to_string();
+        match self.state.as_str() {
+            "TIMEOUT" | "DEADLINE" => JobStatus::TimedOut { location },
+            "CANCELLED" => JobStatus::Cancelled { location },
+            "NODE_FAIL" | "BOOT_FAIL" | "PREEMPTED" => JobStatus::Failed {
+                location,
+                class: Some(FailureClass::Infrastructure),
+                reason: Some(self.summary()),
+            },
+            _ => JobStatus::Failed {
+                location,
+                class: None,
+                reason: Some(self.summary()),
+            },
+        }
+    }
+
+    pub fn path(repx_dir: &Path) -> PathBuf {
+        repx_dir.join(SLURM_FAILURE_FILE)
+    }
+
+    pub fn read(repx_dir: &Path) -> Result<Option<Self>, CoreError> {
+        match fs::read_to_string(Self::path(repx_dir)) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn non_empty(field: &str) -> Option<String> {
+    let field = field.trim();
+    (!field.is_empty() && field != "None").then(|| field.to_string())
+}
+
+fn rss_bytes(rss: &str) -> u64 {
+    Memory::from(rss).to_bytes().unwrap_or(0)
+}
+
+pub fn parse_sacct(output: &str) -> HashMap<u64, SlurmFailure> {
+    let mut records: HashMap<u64, SlurmFailure> = HashMap::new();
+    let mut step_rss: HashMap<u64, String> = HashMap::new();
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split('|').collect();
+        if fields.len() < 6 {
+            continue;
+        }
+        let (id, step) = match fields[0].split_once('.') {
+            Some((id, step)) => (id, Some(step)),
+            None => (fields[0], None),
+        };
+        let Ok(slurm_id) = id.trim().parse::<u64>() else {
+            continue;
+        };
+
+        if let Some(rss) = non_empty(fields[4]) {
+            let current = step_rss.entry(slurm_id).or_insert_with(|| rss.clone());
+            if rss_bytes(&rss) > rss_bytes(current) {
+                *current = rss;
+            }
+        }
+        if step.is_some() {
+            continue;
+        }
+
+        let state = fields[1].split_whitespace().next().unwrap_or_default();
+        let req_mem = non_empty(fields[5])
+            .map(|mem| mem.trim_end_matches(['n', 'c']).to_string())
+            .filter(|mem| !mem.is_empty());
+        records.insert(
+            slurm_id,
+            SlurmFailure {
+                slurm_id,
+                state: state.to_string(),
+                exit_code: fields[2].trim().to_string(),
+                reason: non_empty(fields[3]),
+                max_rss: None,
+                req_mem,
+            },
+        );
+    }
+
+    for (slurm_id, rss) in step_rss {
+        if let Some(record) = records.get_mut(&slurm_id) {
+            record.max_rss = Some(rss);
+        }
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sacct_merges_step_rss_into_allocation() {
+        let output = "\
+4242|OUT_OF_MEMORY|0:125|None||64G
+4242.batch|OUT_OF_MEMORY|0:125||67100000K|
+4242.extern|COMPLETED|0:0||1024K|
+4243|NODE_FAIL|1:0|NodeDown||4000Mn
+4244|CANCELLED by 1000|0:0|None||1G
+4245|COMPLETED|0:0|None||1G
+";
+        let records = parse_sacct(output);
+        assert_eq!(records.len(), 4);
+
+        let oom = &records[&4242];
+        assert_eq!(oom.max_rss.as_deref(), Some("67100000K"));
+        assert_eq!(oom.reason, None);
+        assert_eq!(oom.summary(), "OUT_OF_MEMORY at 64G");
+        assert!(matches!(
+            oom.status("cluster"),
+            JobStatus::Failed { reason: Some(ref r), .. } if r == "OUT_OF_MEMORY at 64G"
+        ));
+
+        let node_fail = &records[&4243];
+        assert_eq!(node_fail.req_mem.as_deref(), Some("4000M"));
+        assert_eq!(node_fail.summary(), "NODE_FAIL (NodeDown)");
+        assert!(matches!(
+            node_fail.status("cluster"),
+            JobStatus::Failed {
+                class: Some(FailureClass::Infrastructure),
+                ..
+            }
+        ));
+
+        assert_eq!(records[&4244].state, "CANCELLED");
+        assert!(matches!(
+            records[&4244].status("cluster"),
+            JobStatus::Cancelled { .. }
+        ));
+        assert!(!records[&4245].is_failure());
+    }
+
+    #[test]
+    fn test_summary_reports_exit_code_or_signal() {
+        let mut failure = SlurmFailure {
+            slurm_id: 1,
+            state: "FAILED".to_string(),
+            exit_code: "2:0".to_string(),
+            reason: None,
+            max_rss: None,
+            req_mem: None,
+        };
+        assert_eq!(failure.summary(), "FAILED exit 2");
+        failure.exit_code = "0:9".to_string();
+        assert_eq!(failure.summary(), "FAILED signal 9");
+        failure.state = "OUT_OF_MEMORY".to_string();
+        failure.max_rss = Some("8G".to_string());
+        assert_eq!(failure.summary(), "OUT_OF_MEMORY (MaxRSS 8G)");
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{errors::CoreError, fs_utils};
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const WORK_ITEMS_FILE: &str = "work_items.json";
+
+pub const WORK_ITEMS_STREAM_FILE: &str = "work_items.jsonl";
+
+pub fn parse_stream(content: &str) -> Result<(Vec<Value>, usize), CoreError> {
+    let Some(end) = content.rfind('\n').map(|i| i + 1) else {
+        return Ok((Vec::new(), 0));
+    };
+    let items = content[..end]
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<Value>, _>>()?;
+    Ok((items, end))
+}
+
+pub fn read(scatter_out: &Path) -> Result<Vec<Value>, CoreError> {
+    match fs::read_to_string(scatter_out.join(WORK_ITEMS_FILE)) {
+        Ok(content) => return Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+        Err(_) => {}
+    }
+    let content = fs::read_to_string(scatter_out.join(WORK_ITEMS_STREAM_FILE))?;
+    Ok(parse_stream(&content)?.0)
+}
+
+pub fn finalize(scatter_out: &Path) -> Result<(), CoreError> {
+    let list_path = scatter_out.join(WORK_ITEMS_FILE);
+    if list_path.exists() {
+        return Ok(());
+    }
+    let mut content = match fs::read_to_string(scatter_out.join(WORK_ITEMS_STREAM_FILE)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    let (items, _) = parse_stream(&content)?;
+    fs_utils::write_atomic(&list_path, serde_json::to_string(&items)?.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_keeps_partial_line_for_next_read() {
+        let (items, consumed) =
+            parse_stream("{\"id\": 0}\n\n{\"id\": 1}\n{\"id\"").expect("stream must parse");
+        assert_eq!(
+            items,
+            vec![serde_json::json!({"id": 0}), serde_json::json!({"id": 1})]
+        );
+        assert_eq!(consumed, "{\"id\": 0}\n\n{\"id\": 1}\n".len());
+        assert_eq!(parse_stream("{\"id\"").expect("stream must parse").1, 0);
+        assert!(parse_stream("not json\n").is_err());
+    }
+
+    #[test]
+    fn test_finalize_writes_list_from_stream() {
+        let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
+        fs::write(tmp.path().join(WORK_ITEMS_STREAM_FILE), "1\n2\n3")
+            .expect("file write must succeed");
+        assert_eq!(read(tmp.path()).expect("stream must read").len(), 2);
+
+        finalize(tmp.path()).expect("finalize must succeed");
+        let list =
+            fs::read_to_string(tmp.path().join(WORK_ITEMS_FILE)).expect("list must be written");
+        assert_eq!(list, "[1,2,3]");
+        assert_eq!(read(tmp.path()).expect("list must read").len(), 3);
+    }
+}
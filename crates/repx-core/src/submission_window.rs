@@ -0,0 +1,235 @@
+use chrono::{DateTime, Datelike, Days, FixedOffset, Local, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const LOOKAHEAD_DAYS: u64 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay(pub NaiveTime);
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        NaiveTime::parse_from_str(&s, "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(&s, "%H:%M:%S"))
+            .map(TimeOfDay)
+            .map_err(|_| format!("invalid time of day '{}', expected HH:MM", s))
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(t: TimeOfDay) -> Self {
+        t.0.format("%H:%M").to_string()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AllowWindow {
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    pub start: Option<TimeOfDay>,
+    pub end: Option<TimeOfDay>,
+}
+
+impl AllowWindow {
+    fn contains(&self, now: &DateTime<Local>) -> bool {
+        let t = now.time();
+        let start = self.start.map(|s| s.0).unwrap_or(NaiveTime::MIN);
+        let end = self.end.map(|e| e.0);
+        let (in_time, day) = match end {
+            None => (t >= start, now.weekday()),
+            Some(end) if start <= end => (t >= start && t < end, now.weekday()),
+            Some(_) if t >= start => (true, now.weekday()),
+            Some(end) => (t < end, now.weekday().pred()),
+        };
+        in_time && (self.days.is_empty() || self.days.contains(&day))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MaintenanceWindow {
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SubmissionWindows {
+    pub large_batch_jobs: Option<usize>,
+    #[serde(default)]
+    pub allow: Vec<AllowWindow>,
+    #[serde(default)]
+    pub maintenance: Vec<MaintenanceWindow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowDecision {
+    Open,
+    Closed {
+        reason: String,
+        opens_at: Option<DateTime<Local>>,
+    },
+}
+
+impl fmt::Display for WindowDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowDecision::Open => write!(f, "open"),
+            WindowDecision::Closed {
+                reason,
+                opens_at: Some(at),
+            } => write!(f, "{} (opens {})", reason, at.format("%a %Y-%m-%d %H:%M")),
+            WindowDecision::Closed {
+                reason,
+                opens_at: None,
+            } => write!(f, "{} (no opening found)", reason),
+        }
+    }
+}
+
+impl SubmissionWindows {
+    fn closed_reason(&self, now: &DateTime<Local>, batch_jobs: usize) -> Option<String> {
+        if let Some(m) = self
+            .maintenance
+            .iter()
+            .find(|m| *now >= m.start && *now < m.end)
+        {
+            return Some(match &m.reason {
+                Some(r) => format!("maintenance: {}", r),
+                None => "maintenance".to_string(),
+            });
+        }
+
+        let gated = self.large_batch_jobs.is_none_or(|min| batch_jobs >= min);
+        if gated && !self.allow.is_empty() && !self.allow.iter().any(|w| w.contains(now)) {
+            return Some(match self.large_batch_jobs {
+                Some(min) => format!(
+                    "batch of {} jobs (>= {}) is outside its submission window",
+                    batch_jobs, min
+                ),
+                None => "outside submission window".to_string(),
+            });
+        }
+        None
+    }
+
+    pub fn check(&self, now: DateTime<Local>, batch_jobs: usize) -> WindowDecision {
+        let Some(reason) = self.closed_reason(&now, batch_jobs) else {
+            return WindowDecision::Open;
+        };
+        WindowDecision::Closed {
+            reason,
+            opens_at: self.next_opening(now, batch_jobs),
+        }
+    }
+
+    fn next_opening(&self, now: DateTime<Local>, batch_jobs: usize) -> Option<DateTime<Local>> {
+        let mut candidates: Vec<DateTime<Local>> = self
+            .maintenance
+            .iter()
+            .map(|m| m.end.with_timezone(&Local))
+            .filter(|t| *t > now)
+            .collect();
+        for offset in 0..=LOOKAHEAD_DAYS {
+            let Some(date) = now.date_naive().checked_add_days(Days::new(offset)) else {
+                continue;
+            };
+            let starts = self
+                .allow
+                .iter()
+                .map(|w| w.start.map(|s| s.0).unwrap_or(NaiveTime::MIN))
+                .chain(std::iter::once(NaiveTime::MIN));
+            for start in starts {
+                if let Some(t) = Local.from_local_datetime(&date.and_time(start)).earliest() {
+                    if t > now {
+                        candidates.push(t);
+                    }
+                }
+            }
+        }
+        candidates.sort();
+        candidates
+            .into_iter()
+            .find(|t| self.closed_reason(t, batch_jobs).is_none())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Local> {
+        let naive = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&naive).earliest().unwrap()
+    }
+
+    fn nights_and_weekends() -> SubmissionWindows {
+        toml::from_str(
+            r#"
+            large_batch_jobs = 10
+            allow = [
+                { days = ["Sat", "Sun"] },
+                { start = "20:00", end = "06:00" },
+            ]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_allow_windows_gate_only_large_batches() {
+        let windows = nights_and_weekends();
+        let wednesday_noon = at("2026-10-14 12:00");
+
+        assert_eq!(windows.check(wednesday_noon, 3), WindowDecision::Open);
+        match windows.check(wednesday_noon, 50) {
+            WindowDecision::Closed { opens_at, .. } => {
+                assert_eq!(opens_at, Some(at("2026-10-14 20:00")));
+            }
+            WindowDecision::Open => panic!("large batch must be held during the day"),
+        }
+        assert_eq!(
+            windows.check(at("2026-10-15 03:30"), 50),
+            WindowDecision::Open
+        );
+        assert_eq!(
+            windows.check(at("2026-10-17 12:00"), 50),
+            WindowDecision::Open
+        );
+    }
+
+    #[test]
+    fn test_maintenance_blocks_every_submission() {
+        let mut windows = SubmissionWindows::default();
+        windows.maintenance.push(MaintenanceWindow {
+            start: at("2026-10-14 08:00").fixed_offset(),
+            end: at("2026-10-14 18:00").fixed_offset(),
+            reason: Some("OS upgrade".to_string()),
+        });
+
+        match windows.check(at("2026-10-14 09:00"), 1) {
+            WindowDecision::Closed { reason, opens_at } => {
+                assert!(reason.contains("OS upgrade"));
+                assert_eq!(opens_at, Some(at("2026-10-14 18:00")));
+            }
+            WindowDecision::Open => panic!("maintenance must close the window"),
+        }
+        assert_eq!(
+            windows.check(at("2026-10-14 18:00"), 1),
+            WindowDecision::Open
+        );
+    }
+
+    #[test]
+    fn test_invalid_time_of_day_is_rejected() {
+        let parsed: Result<SubmissionWindows, _> =
+            toml::from_str(r#"allow = [{ start = "25:00" }]"#);
+        assert!(parsed.is_err());
+    }
+}
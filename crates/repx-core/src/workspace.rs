@@ -0,0 +1,227 @@
+use crate::errors::CoreError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const WORKSPACE_FILE_NAME: &str = "workspace.toml";
+pub const LAB_REF_PREFIX: char = '@';
+pub const DEFAULT_LAB_PATH: &str = "./result";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workspace {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub labs: BTreeMap<String, WorkspaceLab>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceLab {
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedLab {
+    pub name: Option<String>,
+    pub path: PathBuf,
+    pub target: Option<String>,
+}
+
+impl Workspace {
+    pub fn load_from(path: &Path) -> Result<Self, CoreError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| CoreError::path_io(path, e))?;
+        toml::from_str(&content).map_err(|e| CoreError::toml_path(path, e))
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), CoreError> {
+        let toml_string = toml::to_string_pretty(self)?;
+        crate::fs_utils::write_atomic(path, toml_string.as_bytes())
+            .map_err(|e| CoreError::path_io(path, e))
+    }
+
+    pub fn add(
+        &mut self,
+        name: &str,
+        path: &Path,
+        target: Option<String>,
+    ) -> Result<(), CoreError> {
+        if name.is_empty() || name.starts_with(LAB_REF_PREFIX) || name.contains(char::is_whitespace)
+        {
+            return Err(CoreError::InvalidConfig {
+                detail: format!("Invalid workspace lab name '{}'", name),
+            });
+        }
+        let path = std::path::absolute(path).map_err(|e| CoreError::path_io(path, e))?;
+        self.labs
+            .insert(name.to_string(), WorkspaceLab { path, target });
+        if self.active.is_none() {
+            self.active = Some(name.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<WorkspaceLab, CoreError> {
+        let lab = self
+            .labs
+            .remove(name)
+            .ok_or_else(|| CoreError::WorkspaceLabNotFound {
+                name: name.to_string(),
+            })?;
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+        Ok(lab)
+    }
+
+    pub fn set_active(&mut self, name: &str) -> Result<(), CoreError> {
+        if !self.labs.contains_key(name) {
+            return Err(CoreError::WorkspaceLabNotFound {
+                name: name.to_string(),
+            });
+        }
+        self.active = Some(name.to_string());
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&WorkspaceLab> {
+        self.labs.get(name)
+    }
+
+    pub fn active_lab(&self) -> Option<(&str, &WorkspaceLab)> {
+        let name = self.active.as_deref()?;
+        self.labs.get_key_value(name).map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn name_for_path(&self, path: &Path) -> Option<&str> {
+        let path = std::path::absolute(path).ok()?;
+        self.labs
+            .iter()
+            .find(|(_, lab)| lab.path == path)
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn resolve(&self, lab: &Path, lab_is_default: bool) -> Result<ResolvedLab, CoreError> {
+        if let Some(name) = lab.to_str().and_then(|s| s.strip_prefix(LAB_REF_PREFIX)) {
+            let entry = self
+                .get(name)
+                .ok_or_else(|| CoreError::WorkspaceLabNotFound {
+                    name: name.to_string(),
+                })?;
+            return Ok(ResolvedLab {
+                name: Some(name.to_string()),
+                path: entry.path.clone(),
+                target: entry.target.clone(),
+            });
+        }
+
+        if lab_is_default && !lab.exists() {
+            if let Some((name, entry)) = self.active_lab() {
+                return Ok(ResolvedLab {
+                    name: Some(name.to_string()),
+                    path: entry.path.clone(),
+                    target: entry.target.clone(),
+                });
+            }
+        }
+
+        let name = self.name_for_path(lab).map(str::to_string);
+        let target = name
+            .as_deref()
+            .and_then(|n| self.get(n))
+            .and_then(|entry| entry.target.clone());
+        Ok(ResolvedLab {
+            name,
+            path: lab.to_path_buf(),
+            target,
+        })
+    }
+}
+
+pub fn workspace_path() -> Result<PathBuf, CoreError> {
+    Ok(crate::xdg_dirs().place_config_file(WORKSPACE_FILE_NAME)?)
+}
+
+pub fn load_workspace() -> Result<Workspace, CoreError> {
+    match crate::xdg_dirs().find_config_file(WORKSPACE_FILE_NAME) {
+        Some(path) => Workspace::load_from(&path),
+        None => Ok(Workspace::default()),
+    }
+}
+
+pub fn save_workspace(workspace: &Workspace) -> Result<(), CoreError> {
+    workspace.save_to(&workspace_path()?)
+}
+
+pub fn resolve_lab_arg(lab: &Path) -> Result<ResolvedLab, CoreError> {
+    load_workspace()?.resolve(lab, lab == Path::new(DEFAULT_LAB_PATH))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_roundtrip_and_active_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(WORKSPACE_FILE_NAME);
+
+        let mut ws = Workspace::default();
+        ws.add("alpha", &dir.path().join("a"), Some("cluster".into()))
+            .unwrap();
+        ws.add("beta", &dir.path().join("b"), None).unwrap();
+        assert_eq!(ws.active.as_deref(), Some("alpha"));
+        ws.save_to(&file).unwrap();
+
+        let loaded = Workspace::load_from(&file).unwrap();
+        assert_eq!(loaded, ws);
+        assert!(Workspace::load_from(&dir.path().join("missing.toml"))
+            .unwrap()
+            .labs
+            .is_empty());
+    }
+
+    #[test]
+    fn test_workspace_resolves_named_and_active_labs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ws = Workspace::default();
+        ws.add("alpha", &dir.path().join("a"), Some("cluster".into()))
+            .unwrap();
+        ws.add("beta", &dir.path().join("b"), None).unwrap();
+        ws.set_active("beta").unwrap();
+
+        let named = ws.resolve(Path::new("@alpha"), false).unwrap();
+        assert_eq!(named.path, dir.path().join("a"));
+        assert_eq!(named.target.as_deref(), Some("cluster"));
+
+        let fallback = ws
+            .resolve(&dir.path().join("no-such-result"), true)
+            .unwrap();
+        assert_eq!(fallback.name.as_deref(), Some("beta"));
+
+        let explicit = ws.resolve(&dir.path().join("a"), false).unwrap();
+        assert_eq!(explicit.name.as_deref(), Some("alpha"));
+
+        assert!(matches!(
+            ws.resolve(Path::new("@gamma"), false),
+            Err(CoreError::WorkspaceLabNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_workspace_remove_clears_active() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ws = Workspace::default();
+        ws.add("alpha", dir.path(), None).unwrap();
+        ws.remove("alpha").unwrap();
+        assert!(ws.active.is_none());
+        assert!(ws.remove("alpha").is_err());
+        assert!(ws.add("@bad", dir.path(), None).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::secrets::Redactor;
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::Child;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize)]
+struct LogRecord<'a> {
+    ts: String,
+    stream: &'a str,
+    line: &'a str,
+}
+
+pub(crate) struct OutputCapture {
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl OutputCapture {
+    pub(crate) fn start(
+        child: &mut Child,
+        stdout: File,
+        stderr: File,
+        records: Option<File>,
+        redactor: Redactor,
+    ) -> Self {
+        let records = records.map(|records| Arc::new(Mutex::new(records)));
+        let redactor = Arc::new(redactor);
+        let mut tasks = Vec::new();
+        if let Some(out) = child.stdout.take() {
+            tasks.push(tokio::spawn(copy_lines(
+                out,
+                "stdout",
+                stdout,
+                records.clone(),
+                redactor.clone(),
+            )));
+        }
+        if let Some(err) = child.stderr.take() {
+            tasks.push(tokio::spawn(copy_lines(
+                err, "stderr", stderr, records, redactor,
+            )));
+        }
+        Self { tasks }
+    }
+
+    pub(crate) async fn finish(self) {
+        for mut task in self.tasks {
+            if tokio::time::timeout(DRAIN_TIMEOUT, &mut task)
+                .await
+                .is_err()
+            {
+                tracing::warn!("Job output was still open after the job exited; closing capture.");
+                task.abort();
+            }
+        }
+    }
+}
+
+async fn copy_lines(
+    reader: impl AsyncRead + Unpin,
+    stream: &'static str,
+    mut raw: File,
+    records: Option<Arc<Mutex<File>>>,
+    redactor: Arc<Redactor>,
+) {
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf).await {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!("Failed to read job {}: {}", stream, e);
+                break;
+            }
+        }
+        let text = String::from_utf8_lossy(&buf);
+        let text = redactor.redact(&text);
+        let written = if redactor.is_empty() {
+            raw.write_all(&buf).await
+        } else {
+            raw.write_all(text.as_bytes()).await
+        };
+        if let Err(e) = written {
+            tracing::warn!("Failed to write job {} log: {}", stream, e);
+        }
+
+        let Some(records) = &records else {
+            continue;
+        };
+        let record = LogRecord {
+            ts: Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true),
+            stream,
+            line: text.trim_end_matches(['\n', '\r']),
+        };
+        let Ok(mut json) = serde_json::to_vec(&record) else {
+            continue;
+        };
+        json.push(b'\n');
+        if let Err(e) = records.lock().await.write_all(&json).await {
+            tracing::warn!("Failed to write structured log record: {}", e);
+        }
+    }
+    let _ = raw.flush().await;
+    if let Some(records) = &records {
+        let _ = records.lock().await.flush().await;
+    }
+}
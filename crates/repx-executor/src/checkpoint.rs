@@ -0,0 +1,257 @@
+use crate::context::RuntimeContext;
+use crate::error::{ExecutorError, IoContext, Result};
+use crate::runtime::ContainerRuntime;
+use crate::{ExecutionRequest, Runtime};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command as TokioCommand;
+
+pub const CHECKPOINTS_DIR: &str = "checkpoints";
+const LATEST_FILE: &str = "latest.json";
+const CONTAINER_ARCHIVE: &str = "container.tar.gz";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckpointEngine {
+    Criu,
+    Podman,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointMetadata {
+    pub job_id: String,
+    pub sequence: u64,
+    pub engine: CheckpointEngine,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CheckpointMetadata {
+    pub fn load_latest(repx_out_dir: &Path) -> Result<Option<Self>> {
+        let path = checkpoints_dir(repx_out_dir).join(LATEST_FILE);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).io_ctx("read", &path),
+        };
+        let metadata = serde_json::from_str(&content)
+            .map_err(std::io::Error::other)
+            .io_ctx("parse", &path)?;
+        Ok(Some(metadata))
+    }
+
+    pub fn images_dir(&self, repx_out_dir: &Path) -> PathBuf {
+        checkpoints_dir(repx_out_dir).join(self.sequence.to_string())
+    }
+}
+
+pub fn checkpoints_dir(repx_out_dir: &Path) -> PathBuf {
+    repx_out_dir.join(CHECKPOINTS_DIR)
+}
+
+pub(crate) struct Checkpointer {
+    engine: CheckpointEngine,
+    dir: PathBuf,
+    latest: Option<CheckpointMetadata>,
+}
+
+impl Checkpointer {
+    pub(crate) fn for_request(request: &ExecutionRequest) -> Result<Option<Self>> {
+        if request.checkpoint_interval.is_none() {
+            return Ok(None);
+        }
+        let unsupported = |reason: &str| ExecutorError::CheckpointUnsupported {
+            job_id: request.job_id.to_string(),
+            reason: reason.to_string(),
+        };
+        let engine = match &request.runtime {
+            Runtime::Native => CheckpointEngine::Criu,
+            Runtime::Podman { .. } => CheckpointEngine::Podman,
+            Runtime::Docker { .. } => return Err(unsupported("the docker runtime")),
+            Runtime::Bwrap { .. } => return Err(unsupported("the bwrap runtime")),
+            Runtime::Proot { .. } => return Err(unsupported("the proot runtime")),
+        };
+        if request.structured_logs || !request.redactor().is_empty() {
+            return Err(unsupported("captured output (structured logs or secrets)"));
+        }
+        Ok(Some(Self {
+            engine,
+            dir: checkpoints_dir(&request.repx_out_dir),
+            latest: CheckpointMetadata::load_latest(&request.repx_out_dir)?,
+        }))
+    }
+
+    pub(crate) fn latest(&self) -> Option<&CheckpointMetadata> {
+        self.latest.as_ref()
+    }
+
+    pub(crate) async fn restore_command(
+        &self,
+        ctx: &RuntimeContext<'_>,
+    ) -> Result<Option<TokioCommand>> {
+        let Some(latest) = &self.latest else {
+            return Ok(None);
+        };
+        let job_id = ctx.request.job_id.to_string();
+        if latest.engine != self.engine || latest.job_id != job_id {
+            return Err(ExecutorError::CheckpointFailed {
+                job_id,
+                detail: format!(
+                    "checkpoint {} was taken by {:?} for job '{}'",
+                    latest.sequence, latest.engine, latest.job_id
+                ),
+            });
+        }
+        let images_dir = self.dir.join(latest.sequence.to_string());
+        match self.engine {
+            CheckpointEngine::Criu => {
+                let mut cmd = TokioCommand::new(ctx.resolve_tool("criu").await?);
+                cmd.arg("restore")
+                    .arg("--images-dir")
+                    .arg(&images_dir)
+                    .arg("--shell-job")
+                    .arg("--log-file")
+                    .arg("restore.log");
+                Ok(Some(cmd))
+            }
+            CheckpointEngine::Podman => {
+                let name = ContainerRuntime::container_name(ctx.request);
+                let _ = podman_command(ctx)
+                    .arg("rm")
+                    .arg("--force")
+                    .arg(&name)
+                    .output()
+                    .await;
+                let mut restore = podman_command(ctx);
+                restore
+                    .arg("container")
+                    .arg("restore")
+                    .arg("--import")
+                    .arg(images_dir.join(CONTAINER_ARCHIVE));
+                run_checked(restore, "podman container restore", &job_id).await?;
+
+                let mut cmd = podman_command(ctx);
+                cmd.arg("attach").arg("--no-stdin").arg(&name);
+                Ok(Some(cmd))
+            }
+        }
+    }
+
+    pub(crate) async fn dump(&mut self, ctx: &RuntimeContext<'_>, pid: u32) -> Result<()> {
+        let job_id = ctx.request.job_id.to_string();
+        let sequence = self.latest.as_ref().map_or(1, |latest| latest.sequence + 1);
+        let partial = self.dir.join(format!("{}.partial", sequence));
+        if partial.exists() {
+            tokio::fs::remove_dir_all(&partial)
+                .await
+                .io_ctx("remove_dir_all", &partial)?;
+        }
+        tokio::fs::create_dir_all(&partial)
+            .await
+            .io_ctx("create_dir_all", &partial)?;
+
+        let cmd = match self.engine {
+            CheckpointEngine::Criu => {
+                let mut cmd = TokioCommand::new(ctx.resolve_tool("criu").await?);
+                cmd.arg("dump")
+                    .arg("--tree")
+                    .arg(pid.to_string())
+                    .arg("--images-dir")
+                    .arg(&partial)
+                    .arg("--leave-running")
+                    .arg("--shell-job")
+                    .arg("--log-file")
+                    .arg("dump.log");
+                cmd
+            }
+            CheckpointEngine::Podman => {
+                let mut cmd = podman_command(ctx);
+                cmd.arg("container")
+                    .arg("checkpoint")
+                    .arg("--leave-running")
+                    .arg("--export")
+                    .arg(partial.join(CONTAINER_ARCHIVE))
+                    .arg(ContainerRuntime::container_name(ctx.request));
+                cmd
+            }
+        };
+        if let Err(e) = run_checked(cmd, "checkpoint", &job_id).await {
+            let _ = tokio::fs::remove_dir_all(&partial).await;
+            return Err(e);
+        }
+
+        let images_dir = self.dir.join(sequence.to_string());
+        tokio::fs::rename(&partial, &images_dir)
+            .await
+            .io_ctx("rename", &images_dir)?;
+        let metadata = CheckpointMetadata {
+            job_id,
+            sequence,
+            engine: self.engine,
+            created_at: Utc::now(),
+        };
+        let json = serde_json::to_vec_pretty(&metadata)
+            .map_err(std::io::Error::other)
+            .io_ctx("serialize", &self.dir)?;
+        let latest_tmp = self.dir.join(format!("{}.tmp", LATEST_FILE));
+        let latest_path = self.dir.join(LATEST_FILE);
+        tokio::fs::write(&latest_tmp, json)
+            .await
+            .io_ctx("write", &latest_tmp)?;
+        tokio::fs::rename(&latest_tmp, &latest_path)
+            .await
+            .io_ctx("rename", &latest_path)?;
+
+        if let Some(previous) = self.latest.replace(metadata) {
+            let previous_dir = self.dir.join(previous.sequence.to_string());
+            let _ = tokio::fs::remove_dir_all(&previous_dir).await;
+        }
+        tracing::info!(
+            "Checkpointed job '{}' (snapshot {}).",
+            ctx.request.job_id,
+            sequence
+        );
+        Ok(())
+    }
+
+    pub(crate) async fn discard(&mut self) -> Result<()> {
+        self.latest = None;
+        match tokio::fs::remove_dir_all(&self.dir).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).io_ctx("remove_dir_all", &self.dir)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn podman_command(ctx: &RuntimeContext<'_>) -> TokioCommand {
+    let mut cmd = TokioCommand::new("podman");
+    cmd.env(
+        "XDG_RUNTIME_DIR",
+        ContainerRuntime::runtime_dir(ctx.request),
+    );
+    cmd
+}
+
+async fn run_checked(mut cmd: TokioCommand, what: &str, job_id: &str) -> Result<()> {
+    let output = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| ExecutorError::command_failed(format!("{:?}", cmd.as_std()), e))?;
+    if !output.status.success() {
+        return Err(ExecutorError::CheckpointFailed {
+            job_id: job_id.to_string(),
+            detail: format!(
+                "{} exited with {}: {}",
+                what,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    Ok(())
+}
@@ -2,16 +2,32 @@ use crate::error::{ExecutorError, Result};
 use crate::util::ALLOWED_SYSTEM_BINARIES;
 use crate::ExecutionRequest;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::process::Command as TokioCommand;
 
 pub struct RuntimeContext<'a> {
     pub request: &'a ExecutionRequest,
+    pub secrets_dir: Option<&'a Path>,
+    pub interactive: bool,
 }
 
 impl<'a> RuntimeContext<'a> {
     pub fn new(request: &'a ExecutionRequest) -> Self {
-        Self { request }
+        Self {
+            request,
+            secrets_dir: None,
+            interactive: false,
+        }
+    }
+
+    pub fn with_secrets_dir(mut self, secrets_dir: Option<&'a Path>) -> Self {
+        self.secrets_dir = secrets_dir;
+        self
+    }
+
+    pub fn with_interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
     }
 
     fn local_artifacts_path(&self) -> Option<&PathBuf> {
@@ -0,0 +1,34 @@
+use crate::error::{ExecutorError, Result};
+use nix::sys::statvfs::statvfs;
+use std::path::Path;
+
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let stat = statvfs(existing).ok()?;
+    Some((stat.blocks_available() as u64).saturating_mul(stat.fragment_size() as u64))
+}
+
+pub fn check_free_space<'a>(
+    job_id: &str,
+    paths: impl IntoIterator<Item = &'a Path>,
+    required: u64,
+) -> Result<()> {
+    for path in paths {
+        let Some(available) = available_space(path) else {
+            tracing::debug!(
+                "Could not determine free space on '{}', skipping disk check",
+                path.display()
+            );
+            continue;
+        };
+        if available < required {
+            return Err(ExecutorError::InsufficientDiskSpace {
+                job_id: job_id.to_string(),
+                path: path.to_path_buf(),
+                available,
+                required,
+            });
+        }
+    }
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use repx_core::fs_utils::format_bytes;
+use repx_core::model::{FailureClass, FailureRule};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -22,11 +24,15 @@ pub enum ExecutorError {
         source: std::io::Error,
     },
 
-    #[error("Execution of '{script}' failed with exit code {code}.\n--- STDERR ---\n{stderr}")]
+    #[error(
+        "Execution of '{script}' failed with exit code {code}{}.\n--- STDERR ---\n{stderr}",
+        oom.map(|oom| format!(": {}", oom)).unwrap_or_default()
+    )]
     ScriptFailed {
         script: String,
         code: i32,
         stderr: String,
+        oom: Option<crate::oom::OomKill>,
     },
 
     #[error("Image not found: {0}")]
@@ -40,6 +46,65 @@ pub enum ExecutorError {
 
     #[error("Execution cancelled for job '{job_id}'.")]
     Cancelled { job_id: String },
+
+    #[error("Job '{job_id}' exceeded its time limit of {limit_secs}s.")]
+    TimedOut { job_id: String, limit_secs: u64 },
+
+    #[error(
+        "Job '{job_id}' requested network isolation, which the {runtime} runtime cannot provide."
+    )]
+    NetworkIsolationUnsupported {
+        job_id: String,
+        runtime: &'static str,
+    },
+
+    #[error(
+        "Job '{job_id}' requested a seccomp profile, which the {runtime} runtime cannot apply."
+    )]
+    SeccompUnsupported {
+        job_id: String,
+        runtime: &'static str,
+    },
+
+    #[error("Data mount '{mount}' for job '{job_id}' is unavailable: {detail}")]
+    DataMountUnavailable {
+        job_id: String,
+        mount: String,
+        detail: &'static str,
+    },
+
+    #[error(
+        "The {hook} hook of job '{job_id}' failed with exit code {code}. See {}",
+        log_path.display()
+    )]
+    HookFailed {
+        job_id: String,
+        hook: crate::hooks::Hook,
+        code: i32,
+        log_path: PathBuf,
+    },
+
+    #[error("Invalid seccomp profile '{profile}': {detail}")]
+    InvalidSeccompProfile { profile: String, detail: String },
+
+    #[error("Job '{job_id}' cannot be checkpointed: {reason} is not supported.")]
+    CheckpointUnsupported { job_id: String, reason: String },
+
+    #[error("Checkpoint of job '{job_id}' failed: {detail}")]
+    CheckpointFailed { job_id: String, detail: String },
+
+    #[error(
+        "Job '{job_id}' needs {} of free disk space on '{}', but only {} is available.",
+        format_bytes(*required, true),
+        path.display(),
+        format_bytes(*available, true)
+    )]
+    InsufficientDiskSpace {
+        job_id: String,
+        path: PathBuf,
+        available: u64,
+        required: u64,
+    },
 }
 
 impl ExecutorError {
@@ -49,6 +114,34 @@ impl ExecutorError {
             source,
         }
     }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            Self::ScriptFailed { code, .. } | Self::HookFailed { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self {
+            Self::ScriptFailed { oom: Some(oom), .. } => oom.to_string(),
+            Self::ScriptFailed { code, .. } => format!("exit code {}", code),
+            other => other
+                .to_string()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+
+    pub fn failure_class(&self, rules: &[FailureRule]) -> Option<FailureClass> {
+        match self {
+            Self::ScriptFailed { code, stderr, .. } => FailureRule::classify(rules, *code, stderr),
+            Self::Cancelled { .. } => None,
+            _ => Some(FailureClass::Infrastructure),
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, ExecutorError>;
@@ -1,20 +1,43 @@
+mod capture;
+mod checkpoint;
 mod context;
+mod disk;
 mod error;
+mod hooks;
+mod oom;
 mod runtime;
+mod secrets;
 mod util;
 
+pub use checkpoint::{checkpoints_dir, CheckpointEngine, CheckpointMetadata, CHECKPOINTS_DIR};
 pub use context::RuntimeContext;
+pub use disk::{available_space, check_free_space};
 pub use error::{ExecutorError, IoContext, Result};
-pub use runtime::{BwrapRuntime, ContainerRuntime, NativeRuntime, Runtime};
+pub use hooks::Hook;
+pub use oom::OomKill;
+pub use runtime::{
+    compile_seccomp_profile, prune_rootfs_cache, BwrapRuntime, ContainerRuntime, LayerCompression,
+    NativeRuntime, ProotRuntime, ResourceLimits, RootfsPruneReport, Runtime,
+    DEFAULT_SECCOMP_PROFILE,
+};
+pub use secrets::{Redactor, Secret, SECRETS_DIR_ENV};
 pub use util::{extract_image_hash, is_binary_allowed, ImageTag, ALLOWED_SYSTEM_BINARIES};
 
+use capture::OutputCapture;
+use checkpoint::Checkpointer;
+use oom::detect_oom;
 use repx_core::{
     constants::logs,
-    model::{JobId, MountPolicy},
+    model::{DataMount, FailureRule, JobId, MountPolicy, NetworkMode, SeccompProfile},
+    store::heartbeat,
 };
+use std::collections::{BTreeMap, HashMap};
+use std::os::unix::process::ExitStatusExt;
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
 use tokio::fs::{File, OpenOptions};
-use tokio::process::Command as TokioCommand;
+use tokio::process::{Child, Command as TokioCommand};
 pub use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone)]
@@ -30,13 +53,93 @@ pub struct ExecutionRequest {
     pub repx_out_dir: PathBuf,
     pub host_tools_bin_dir: Option<PathBuf>,
     pub mount_policy: MountPolicy,
+    pub data_mounts: Vec<DataMount>,
+    pub network: Option<NetworkMode>,
+    pub seccomp: Option<SeccompProfile>,
+    pub pre_run: Option<PathBuf>,
+    pub post_run: Option<PathBuf>,
+    pub image_cache_limit: Option<u64>,
+    pub min_disk: Option<u64>,
     pub inputs_data: Option<Vec<u8>>,
     pub parameters_data: Option<Vec<u8>>,
+    pub interpreter: Option<String>,
+    pub arg_template: Vec<String>,
+    pub resource_limits: Option<ResourceLimits>,
+    pub time_limit: Option<TimeLimit>,
+    pub max_retries: u32,
+    pub retry_backoff: Duration,
+    pub retry_exit_codes: Vec<i32>,
+    pub failure_rules: Vec<FailureRule>,
+    pub checkpoint_interval: Option<Duration>,
+    pub heartbeat_interval: Option<Duration>,
+    pub structured_logs: bool,
+    pub env: HashMap<String, String>,
+    pub env_passthrough: Vec<String>,
+    pub secrets: Vec<Secret>,
+}
+
+impl ExecutionRequest {
+    pub fn network_isolated(&self) -> bool {
+        self.network == Some(NetworkMode::None)
+    }
+
+    pub fn job_env(&self) -> Vec<(String, String)> {
+        let mut vars: BTreeMap<String, String> = self
+            .env_passthrough
+            .iter()
+            .filter_map(|name| std::env::var(name).ok().map(|value| (name.clone(), value)))
+            .collect();
+        vars.extend(self.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        vars.into_iter().collect()
+    }
+
+    // Never put these on a command line, where any user can read them
+    // from /proc/<pid>/cmdline.
+    pub fn env_secrets(&self) -> Vec<(String, String)> {
+        self.secrets
+            .iter()
+            .filter(|secret| !secret.mount)
+            .map(|secret| (secret.name.clone(), secret.value.clone()))
+            .collect()
+    }
+
+    pub fn redactor(&self) -> Redactor {
+        Redactor::new(&self.secrets)
+    }
+
+    pub fn hook(&self, hook: Hook) -> Option<&Path> {
+        match hook {
+            Hook::PreRun => self.pre_run.as_deref(),
+            Hook::PostRun => self.post_run.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeLimit {
+    pub limit: Duration,
+    pub grace: Duration,
+}
+
+async fn terminate_child(child: &mut Child, grace: Duration) {
+    if let Some(pid) = child.id().and_then(|pid| i32::try_from(pid).ok()) {
+        use nix::sys::signal::{kill, Signal};
+        use nix::unistd::Pid;
+        if kill(Pid::from_raw(pid), Signal::SIGTERM).is_ok()
+            && tokio::time::timeout(grace, child.wait()).await.is_ok()
+        {
+            return;
+        }
+    }
+    let _ = child.kill().await;
 }
 
 pub struct Executor {
     pub request: ExecutionRequest,
     local_log_dir: Option<PathBuf>,
+    secrets_dir: Option<tempfile::TempDir>,
+    rootfs_lock: Option<nix::fcntl::Flock<std::fs::File>>,
+    interactive: bool,
     _temp_files: Vec<tempfile::TempPath>,
 }
 
@@ -45,12 +148,77 @@ impl Executor {
         Self {
             request,
             local_log_dir: None,
+            secrets_dir: None,
+            rootfs_lock: None,
+            interactive: false,
             _temp_files: Vec::new(),
         }
     }
 
     fn context(&self) -> RuntimeContext<'_> {
         RuntimeContext::new(&self.request)
+            .with_secrets_dir(self.secrets_dir.as_ref().map(|dir| dir.path()))
+            .with_interactive(self.interactive)
+    }
+
+    async fn resolve_runtime(&mut self) {
+        let (engine, image_tag) = match &self.request.runtime {
+            Runtime::Podman { image_tag } => ("podman", image_tag.clone()),
+            Runtime::Docker { image_tag } => ("docker", image_tag.clone()),
+            _ => return,
+        };
+        if let Err(reason) = ContainerRuntime::probe(&self.context(), &self.request.runtime).await {
+            tracing::warn!(
+                "{} is not usable on this host ({}). Running job '{}' with bwrap and the same image '{}' instead.",
+                engine,
+                reason,
+                self.request.job_id,
+                image_tag
+            );
+            self.request.runtime = Runtime::Bwrap { image_tag };
+        }
+    }
+
+    async fn lock_rootfs(&mut self) -> Result<()> {
+        if self.rootfs_lock.is_some() {
+            return Ok(());
+        }
+        if let Runtime::Bwrap { image_tag } | Runtime::Proot { image_tag } = &self.request.runtime {
+            let lock =
+                BwrapRuntime::lock_rootfs_in_use(&self.context(), image_tag.as_str()).await?;
+            self.rootfs_lock = Some(lock);
+        }
+        Ok(())
+    }
+
+    fn prepare_secrets_dir(&mut self) -> Result<()> {
+        if self.secrets_dir.is_some() || !self.request.secrets.iter().any(|s| s.mount) {
+            return Ok(());
+        }
+        let shm = Path::new("/dev/shm");
+        let parent = if shm.is_dir() {
+            shm.to_path_buf()
+        } else {
+            std::env::temp_dir()
+        };
+        let dir = tempfile::Builder::new()
+            .prefix("repx-secrets-")
+            .tempdir_in(&parent)
+            .io_ctx("create secrets dir", &parent)?;
+        for secret in self.request.secrets.iter().filter(|s| s.mount) {
+            use std::io::Write;
+            use std::os::unix::fs::OpenOptionsExt;
+            let path = dir.path().join(&secret.name);
+            std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .mode(0o600)
+                .open(&path)
+                .and_then(|mut file| file.write_all(secret.value.as_bytes()))
+                .io_ctx("write secret", &path)?;
+        }
+        self.secrets_dir = Some(dir);
+        Ok(())
     }
 
     pub async fn execute_script(
@@ -58,6 +226,164 @@ impl Executor {
         script_path: &Path,
         args: &[String],
         cancel: &CancellationToken,
+    ) -> Result<()> {
+        self.check_disk_space()?;
+        self.run_hook(Hook::PreRun, args, cancel).await?;
+        let mut attempt = 0;
+        let result = loop {
+            match self.execute_attempt(script_path, args, cancel).await {
+                Err(ExecutorError::ScriptFailed {
+                    code, ref stderr, ..
+                }) if attempt < self.request.max_retries && self.is_retriable(code, stderr) => {
+                    attempt += 1;
+                    self.archive_attempt_logs(attempt).await?;
+                    let delay = self
+                        .request
+                        .retry_backoff
+                        .saturating_mul(2u32.saturating_pow(attempt - 1));
+                    tracing::warn!(
+                        "Job '{}' exited with code {}, retrying in {:?} (attempt {} of {})...",
+                        self.request.job_id,
+                        code,
+                        delay,
+                        attempt,
+                        self.request.max_retries,
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = cancel.cancelled() => {
+                            return Err(ExecutorError::Cancelled {
+                                job_id: self.request.job_id.to_string(),
+                            });
+                        }
+                    }
+                }
+                result => break result,
+            }
+        };
+        result?;
+        self.run_hook(Hook::PostRun, args, cancel).await
+    }
+
+    async fn run_hook(
+        &mut self,
+        hook: Hook,
+        args: &[String],
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let Some(hook_path) = self.request.hook(hook).map(Path::to_path_buf) else {
+            return Ok(());
+        };
+        tokio::fs::metadata(&hook_path)
+            .await
+            .io_ctx("stat hook", &hook_path)?;
+        self.resolve_runtime().await;
+        self.prepare_secrets_dir()?;
+        self.lock_rootfs().await?;
+
+        let mount = DataMount {
+            host_path: hook_path.clone(),
+            mount_path: None,
+        };
+        let added = !self.request.data_mounts.contains(&mount);
+        if added {
+            self.request.data_mounts.push(mount);
+        }
+        let built = self
+            .build_command_for_script_with_temps(&hook_path, args)
+            .await;
+        if added {
+            self.request.data_mounts.pop();
+        }
+        let (mut cmd, _temps) = built?;
+
+        let redactor = self.request.redactor();
+        tracing::info!(
+            "Running {} hook for job '{}': {}",
+            hook,
+            self.request.job_id,
+            redactor.redact(&format!("{:?}", cmd))
+        );
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let child = cmd.spawn().map_err(|e| {
+            ExecutorError::command_failed(format!("{:?}", cmd.as_std().get_program()), e)
+        })?;
+        let output = tokio::select! {
+            output = child.wait_with_output() => output.map_err(|e| {
+                ExecutorError::command_failed(format!("{:?}", cmd.as_std().get_program()), e)
+            })?,
+            _ = cancel.cancelled() => {
+                NativeRuntime::release_limits(&self.request);
+                return Err(ExecutorError::Cancelled {
+                    job_id: self.request.job_id.to_string(),
+                });
+            }
+        };
+        NativeRuntime::release_limits(&self.request);
+
+        let log_path = self.request.repx_out_dir.join(hook.log_file());
+        let mut log = redactor
+            .redact(&String::from_utf8_lossy(&output.stdout))
+            .into_owned();
+        log.push_str(&redactor.redact(&String::from_utf8_lossy(&output.stderr)));
+        tokio::fs::write(&log_path, log)
+            .await
+            .io_ctx("write", &log_path)?;
+
+        if !output.status.success() {
+            return Err(ExecutorError::HookFailed {
+                job_id: self.request.job_id.to_string(),
+                hook,
+                code: output.status.code().unwrap_or(1),
+                log_path,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_disk_space(&self) -> Result<()> {
+        let Some(required) = self.request.min_disk.filter(|&bytes| bytes > 0) else {
+            return Ok(());
+        };
+        let paths = std::iter::once(self.request.base_path.as_path())
+            .chain(self.request.node_local_path.as_deref());
+        check_free_space(self.request.job_id.as_str(), paths, required)
+    }
+
+    fn is_retriable(&self, code: i32, stderr: &str) -> bool {
+        match FailureRule::classify(&self.request.failure_rules, code, stderr) {
+            Some(class) => class.is_retriable(),
+            None => {
+                self.request.retry_exit_codes.is_empty()
+                    || self.request.retry_exit_codes.contains(&code)
+            }
+        }
+    }
+
+    async fn archive_attempt_logs(&self, attempt: u32) -> Result<()> {
+        let mut dirs = vec![self.request.repx_out_dir.clone()];
+        dirs.extend(self.local_log_dir.clone());
+        for dir in dirs {
+            for log in [logs::STDOUT, logs::STDERR, logs::JSONL] {
+                let current = dir.join(log);
+                if current.exists() {
+                    let archived = dir.join(format!("{}.{}", log, attempt));
+                    tokio::fs::rename(&current, &archived)
+                        .await
+                        .io_ctx("rename", &current)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn execute_attempt(
+        &mut self,
+        script_path: &Path,
+        args: &[String],
+        cancel: &CancellationToken,
     ) -> Result<()> {
         if cancel.is_cancelled() {
             return Err(ExecutorError::Cancelled {
@@ -65,7 +391,12 @@ impl Executor {
             });
         }
 
-        let (stdout_log, stderr_log) = self.create_log_files().await?;
+        self.resolve_runtime().await;
+        let mut checkpointer = Checkpointer::for_request(&self.request)?;
+        let (stdout_log, stderr_log, records_log) = self.create_log_files().await?;
+        self.prepare_secrets_dir()?;
+        self.lock_rootfs().await?;
+        let redactor = self.request.redactor();
 
         let stderr_path = if let Some(ref local_dir) = self.local_log_dir {
             local_dir.join(logs::STDERR)
@@ -73,43 +404,135 @@ impl Executor {
             self.request.repx_out_dir.join(logs::STDERR)
         };
 
-        let (mut cmd, temp_files) = self
-            .build_command_for_script_with_temps(script_path, args)
-            .await?;
+        let (mut cmd, temp_files) = match self.restore_command(checkpointer.as_mut()).await? {
+            Some(cmd) => (cmd, Vec::new()),
+            None => {
+                self.build_command_for_script_with_temps(script_path, args)
+                    .await?
+            }
+        };
         self._temp_files = temp_files;
 
         tracing::info!(
-            "Executing command for job '{}': {:?}",
+            "Executing command for job '{}': {}",
             self.request.job_id,
-            cmd
+            redactor.redact(&format!("{:?}", cmd))
         );
 
-        let mut child = cmd
-            .stdout(stdout_log.into_std().await)
-            .stderr(stderr_log.into_std().await)
-            .spawn()
-            .map_err(|e| {
-                ExecutorError::command_failed(format!("{:?}", cmd.as_std().get_program()), e)
-            })?;
+        let captured_files = if records_log.is_some() || !redactor.is_empty() {
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+            Some((stdout_log, stderr_log, records_log))
+        } else {
+            cmd.stdout(stdout_log.into_std().await)
+                .stderr(stderr_log.into_std().await);
+            None
+        };
+        let mut child = cmd.spawn().map_err(|e| {
+            ExecutorError::command_failed(format!("{:?}", cmd.as_std().get_program()), e)
+        })?;
+        let child_pid = child.id();
+        let mut capture = captured_files.map(|(stdout, stderr, records)| {
+            OutputCapture::start(&mut child, stdout, stderr, records, redactor)
+        });
 
-        let status = tokio::select! {
-            result = child.wait() => {
-                result.map_err(|e| ExecutorError::command_failed(format!("{:?}", cmd.as_std().get_program()), e))?
+        let time_limit = self.request.time_limit;
+        let deadline = async move {
+            match time_limit {
+                Some(time_limit) => {
+                    tokio::time::sleep(time_limit.limit).await;
+                    time_limit
+                }
+                None => std::future::pending().await,
             }
-            _ = cancel.cancelled() => {
-                tracing::warn!(
-                    "Cancellation requested for job '{}', killing child process...",
-                    self.request.job_id,
-                );
-                let _ = child.kill().await;
-                let _ = self.sync_logs_to_nfs().await;
-                return Err(ExecutorError::Cancelled {
-                    job_id: self.request.job_id.to_string(),
-                });
+        };
+
+        let mut checkpoint_ticker = self.request.checkpoint_interval.map(|interval| {
+            tokio::time::interval_at(tokio::time::Instant::now() + interval, interval)
+        });
+        let mut heartbeat_ticker = self.request.heartbeat_interval.map(tokio::time::interval);
+        tokio::pin!(deadline);
+
+        let status = loop {
+            let next_checkpoint = async {
+                match checkpoint_ticker.as_mut() {
+                    Some(ticker) => {
+                        ticker.tick().await;
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+            let next_heartbeat = async {
+                match heartbeat_ticker.as_mut() {
+                    Some(ticker) => {
+                        ticker.tick().await;
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                result = child.wait() => {
+                    break result.map_err(|e| ExecutorError::command_failed(format!("{:?}", cmd.as_std().get_program()), e))?;
+                }
+                _ = next_heartbeat => {
+                    if let Err(e) = heartbeat::beat(&self.request.repx_out_dir) {
+                        tracing::warn!(
+                            "Failed to write heartbeat for job '{}': {}",
+                            self.request.job_id,
+                            e
+                        );
+                    }
+                }
+                _ = next_checkpoint => {
+                    if let (Some(checkpointer), Some(pid)) = (checkpointer.as_mut(), child.id()) {
+                        if let Err(e) = checkpointer.dump(&self.context(), pid).await {
+                            tracing::warn!("{}", e);
+                        }
+                    }
+                }
+                _ = cancel.cancelled() => {
+                    tracing::warn!(
+                        "Cancellation requested for job '{}', killing child process...",
+                        self.request.job_id,
+                    );
+                    let _ = child.kill().await;
+                    NativeRuntime::release_limits(&self.request);
+                    if let Some(capture) = capture.take() {
+                        capture.finish().await;
+                    }
+                    let _ = self.sync_logs_to_nfs().await;
+                    return Err(ExecutorError::Cancelled {
+                        job_id: self.request.job_id.to_string(),
+                    });
+                }
+                time_limit = &mut deadline => {
+                    tracing::warn!(
+                        "Job '{}' exceeded its time limit, sending SIGTERM ({}s grace before SIGKILL)...",
+                        self.request.job_id,
+                        time_limit.grace.as_secs(),
+                    );
+                    terminate_child(&mut child, time_limit.grace).await;
+                    NativeRuntime::release_limits(&self.request);
+                    if let Some(capture) = capture.take() {
+                        capture.finish().await;
+                    }
+                    let _ = self.sync_logs_to_nfs().await;
+                    return Err(ExecutorError::TimedOut {
+                        job_id: self.request.job_id.to_string(),
+                        limit_secs: time_limit.limit.as_secs(),
+                    });
+                }
             }
         };
 
+        let oom = detect_oom(&self.request, status, child_pid).await;
+        NativeRuntime::release_limits(&self.request);
+        if let Some(capture) = capture {
+            capture.finish().await;
+        }
         self.sync_logs_to_nfs().await?;
+        if let Some(checkpointer) = checkpointer.as_mut() {
+            checkpointer.discard().await?;
+        }
 
         if !status.success() {
             let nfs_stderr = self.request.repx_out_dir.join(logs::STDERR);
@@ -119,13 +542,84 @@ impl Executor {
                 .unwrap_or_else(|e| format!("<failed to read stderr.log: {}>", e));
             return Err(ExecutorError::ScriptFailed {
                 script: script_path.display().to_string(),
-                code: status.code().unwrap_or(1),
+                code: status
+                    .code()
+                    .or_else(|| status.signal().map(|signal| 128 + signal))
+                    .unwrap_or(1),
                 stderr: stderr_content,
+                oom,
             });
         }
         Ok(())
     }
 
+    pub async fn execute_interactive(&mut self, program: &Path, args: &[String]) -> Result<i32> {
+        self.interactive = true;
+        self.request.interpreter = None;
+        self.request.arg_template.clear();
+        self.resolve_runtime().await;
+        self.prepare_secrets_dir()?;
+        self.lock_rootfs().await?;
+
+        let (mut cmd, temp_files) = self
+            .build_command_for_script_with_temps(program, args)
+            .await?;
+        self._temp_files = temp_files;
+        cmd.stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit());
+
+        tracing::info!(
+            "Starting interactive session for job '{}': {}",
+            self.request.job_id,
+            self.request.redactor().redact(&format!("{:?}", cmd))
+        );
+        let mut child = cmd.spawn().map_err(|e| {
+            ExecutorError::command_failed(format!("{:?}", cmd.as_std().get_program()), e)
+        })?;
+        let status = loop {
+            tokio::select! {
+                result = child.wait() => {
+                    break result.map_err(|e| ExecutorError::command_failed(format!("{:?}", cmd.as_std().get_program()), e))?;
+                }
+                _ = tokio::signal::ctrl_c() => {}
+            }
+        };
+        NativeRuntime::release_limits(&self.request);
+
+        Ok(status
+            .code()
+            .or_else(|| status.signal().map(|signal| 128 + signal))
+            .unwrap_or(1))
+    }
+
+    async fn restore_command(
+        &self,
+        checkpointer: Option<&mut Checkpointer>,
+    ) -> Result<Option<TokioCommand>> {
+        let Some(checkpointer) = checkpointer else {
+            return Ok(None);
+        };
+        let Some(sequence) = checkpointer.latest().map(|latest| latest.sequence) else {
+            return Ok(None);
+        };
+        match checkpointer.restore_command(&self.context()).await {
+            Ok(cmd) => {
+                tracing::info!(
+                    "Resuming job '{}' from checkpoint {}.",
+                    self.request.job_id,
+                    sequence
+                );
+                Ok(cmd)
+            }
+            Err(e) => {
+                tracing::warn!("{}; starting the job from scratch.", e);
+                checkpointer.discard().await?;
+                Ok(None)
+            }
+        }
+    }
+
     pub async fn build_command_for_script(
         &self,
         script_path: &Path,
@@ -137,6 +631,39 @@ impl Executor {
         Ok(cmd)
     }
 
+    pub async fn explain(&self, script_path: &Path, args: &[String]) -> Result<String> {
+        let (cmd, _temps) = self
+            .build_command_for_script_with_temps(script_path, args)
+            .await?;
+        let cmd = cmd.as_std();
+        let mut parts = Vec::new();
+        if let Some(dir) = cmd.get_current_dir() {
+            parts.push(format!(
+                "cd {} &&",
+                util::shell_quote(&dir.to_string_lossy())
+            ));
+        }
+        for (key, value) in cmd.get_envs() {
+            if let Some(value) = value {
+                parts.push(format!(
+                    "{}={}",
+                    key.to_string_lossy(),
+                    util::shell_quote(&value.to_string_lossy())
+                ));
+            }
+        }
+        parts.push(util::shell_quote(&cmd.get_program().to_string_lossy()));
+        parts.extend(
+            cmd.get_args()
+                .map(|arg| util::shell_quote(&arg.to_string_lossy())),
+        );
+        Ok(self
+            .request
+            .redactor()
+            .redact(&parts.join(" \\\n    "))
+            .into_owned())
+    }
+
     async fn build_command_for_script_with_temps(
         &self,
         script_path: &Path,
@@ -147,20 +674,40 @@ impl Executor {
         let script_path = resolved_script.as_path();
 
         let (cmd, temps) = match &self.request.runtime {
-            Runtime::Native => (
-                NativeRuntime::build_command(&self.request, script_path, args)?,
-                Vec::new(),
-            ),
+            Runtime::Native => {
+                let mut cmd = NativeRuntime::build_command(&self.request, script_path, args)?;
+                if let Some(dir) = ctx.secrets_dir {
+                    cmd.env(SECRETS_DIR_ENV, dir);
+                }
+                (cmd, Vec::new())
+            }
             Runtime::Podman { .. } | Runtime::Docker { .. } => {
                 ContainerRuntime::ensure_image_loaded(&ctx, &self.request.runtime).await?;
                 ContainerRuntime::build_command(&ctx, &self.request.runtime, script_path, args)
                     .await?
             }
             Runtime::Bwrap { image_tag } => {
+                let rootfs_path =
+                    BwrapRuntime::ensure_rootfs_extracted(&ctx, image_tag.as_str()).await?;
+                let cmd = if !BwrapRuntime::check_userns_support(&ctx).await
+                    && ProotRuntime::is_available(&ctx).await
+                {
+                    tracing::warn!(
+                        "bwrap cannot create a sandbox on this host (user namespaces may be \
+                         disabled). Falling back to proot for job '{}'.",
+                        self.request.job_id
+                    );
+                    ProotRuntime::build_command(&ctx, &rootfs_path, script_path, args).await?
+                } else {
+                    BwrapRuntime::build_command(&ctx, &rootfs_path, script_path, args).await?
+                };
+                (cmd, Vec::new())
+            }
+            Runtime::Proot { image_tag } => {
                 let rootfs_path =
                     BwrapRuntime::ensure_rootfs_extracted(&ctx, image_tag.as_str()).await?;
                 (
-                    BwrapRuntime::build_command(&ctx, &rootfs_path, script_path, args).await?,
+                    ProotRuntime::build_command(&ctx, &rootfs_path, script_path, args).await?,
                     Vec::new(),
                 )
             }
@@ -168,8 +715,10 @@ impl Executor {
         Ok((cmd, temps))
     }
 
-    async fn create_log_files(&mut self) -> Result<(File, File)> {
-        let log_dir = if let Ok(tmpdir) = std::env::var("TMPDIR") {
+    async fn create_log_files(&mut self) -> Result<(File, File, Option<File>)> {
+        let log_dir = if self.request.checkpoint_interval.is_some() {
+            self.request.repx_out_dir.clone()
+        } else if let Ok(tmpdir) = std::env::var("TMPDIR") {
             let local_dir = PathBuf::from(tmpdir).join("repx-logs");
             if tokio::fs::create_dir_all(&local_dir).await.is_ok() {
                 self.local_log_dir = Some(local_dir.clone());
@@ -196,7 +745,20 @@ impl Executor {
             .open(&stderr_path)
             .await
             .io_ctx("open (create/append)", &stderr_path)?;
-        Ok((stdout_file, stderr_file))
+        let records_file = if self.request.structured_logs {
+            let records_path = log_dir.join(logs::JSONL);
+            Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&records_path)
+                    .await
+                    .io_ctx("open (create/append)", &records_path)?,
+            )
+        } else {
+            None
+        };
+        Ok((stdout_file, stderr_file, records_file))
     }
 
     pub async fn sync_logs_to_nfs(&self) -> Result<()> {
@@ -220,6 +782,13 @@ impl Executor {
                     .await
                     .io_ctx("copy stderr.log to NFS", &nfs_stderr)?;
             }
+            let local_records = local_dir.join(logs::JSONL);
+            if local_records.exists() {
+                let nfs_records = self.request.repx_out_dir.join(logs::JSONL);
+                tokio::fs::copy(&local_records, &nfs_records)
+                    .await
+                    .io_ctx("copy logs.jsonl to NFS", &nfs_records)?;
+            }
         }
         Ok(())
     }
@@ -0,0 +1,116 @@
+use crate::runtime::job_memory_usage;
+use crate::{ExecutionRequest, Runtime};
+use repx_core::fs_utils::format_bytes;
+use std::fmt;
+use std::os::unix::process::ExitStatusExt;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::process::Command as TokioCommand;
+
+const SIGKILL_EXIT_CODE: i32 = 128 + 9;
+const DMESG_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OomKill {
+    pub peak_bytes: Option<u64>,
+    pub limit_bytes: Option<u64>,
+}
+
+impl OomKill {
+    pub fn from_kernel_log(log: &str, pid: u32) -> Option<Self> {
+        let needle = format!("Killed process {} ", pid);
+        let line = log.lines().rev().find(|line| line.contains(&needle))?;
+        let peak_bytes = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .find_map(|field| field.strip_prefix("anon-rss:"))
+            .and_then(|rss| rss.strip_suffix("kB"))
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map(|kb| kb * 1024);
+        Some(Self {
+            peak_bytes,
+            limit_bytes: None,
+        })
+    }
+
+    fn is_sigkill(status: ExitStatus) -> bool {
+        status.signal() == Some(9) || status.code() == Some(SIGKILL_EXIT_CODE)
+    }
+
+    pub fn attribute(
+        status: ExitStatus,
+        limit_bytes: Option<u64>,
+        cgroup_kill: Option<OomKill>,
+        kernel_log: Option<&str>,
+        pid: Option<u32>,
+    ) -> Option<Self> {
+        if !Self::is_sigkill(status) {
+            return None;
+        }
+        let oom = match cgroup_kill {
+            Some(oom) => oom,
+            None => Self::from_kernel_log(kernel_log?, pid?)?,
+        };
+        Some(Self {
+            limit_bytes: oom.limit_bytes.or(limit_bytes),
+            ..oom
+        })
+    }
+}
+
+impl fmt::Display for OomKill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OOMKilled")?;
+        match (self.peak_bytes, self.limit_bytes) {
+            (Some(peak), Some(limit)) => write!(
+                f,
+                " (peak {} of {})",
+                format_bytes(peak, true),
+                format_bytes(limit, true)
+            ),
+            (Some(peak), None) => write!(f, " (peak {})", format_bytes(peak, true)),
+            (None, Some(limit)) => write!(f, " (limit {})", format_bytes(limit, true)),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+pub(crate) async fn detect_oom(
+    request: &ExecutionRequest,
+    status: ExitStatus,
+    pid: Option<u32>,
+) -> Option<OomKill> {
+    if !OomKill::is_sigkill(status) {
+        return None;
+    }
+    let limit_bytes = request.resource_limits.and_then(|limits| limits.mem_bytes);
+    let cgroup_kill = if matches!(request.runtime, Runtime::Native) && limit_bytes.is_some() {
+        job_memory_usage(&request.job_id)
+            .filter(|usage| usage.oom_kills > 0)
+            .map(|usage| OomKill {
+                peak_bytes: usage.peak_bytes,
+                limit_bytes: usage.limit_bytes,
+            })
+    } else {
+        None
+    };
+    let kernel_log = match cgroup_kill {
+        Some(_) => None,
+        None => read_kernel_log().await,
+    };
+    OomKill::attribute(status, limit_bytes, cgroup_kill, kernel_log.as_deref(), pid)
+}
+
+async fn read_kernel_log() -> Option<String> {
+    let output = TokioCommand::new("dmesg")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    match tokio::time::timeout(DMESG_TIMEOUT, output).await {
+        Ok(Ok(output)) if output.status.success() => {
+            Some(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        _ => None,
+    }
+}
@@ -1,3 +1,5 @@
+use super::layers;
+use super::rootfs_cache::{prune_rootfs_cache, touch_last_used, IN_USE_LOCK, SUCCESS_MARKER};
 use crate::context::RuntimeContext;
 use crate::error::{ExecutorError, IoContext, Result};
 use crate::ExecutionRequest;
@@ -8,11 +10,12 @@ use std::process::Stdio;
 use tokio::process::Command as TokioCommand;
 
 const EXCLUDED_ROOTFS_DIRS: &[&str] = &["dev", "proc", "tmp"];
-const SUCCESS_MARKER: &str = "SUCCESS";
 
 fn ephemeral_base(request: &ExecutionRequest) -> PathBuf {
     if let Ok(tmpdir) = std::env::var("TMPDIR") {
-        PathBuf::from(tmpdir).join("repx-bwrap")
+        PathBuf::from(tmpdir)
+            .join("repx-bwrap")
+            .join(request.job_id.as_str().replace('/', "_"))
     } else {
         request.repx_out_dir.clone()
     }
@@ -83,12 +86,14 @@ impl BwrapRuntime {
             .io_ctx("create_dir_all", &images_cache_dir)?;
 
         if success_marker.exists() && extract_dir.exists() {
+            touch_last_used(&image_dir);
             return Ok(extract_dir);
         }
 
         let _lock = super::acquire_flock(&lock_path, "extraction").await?;
 
         if success_marker.exists() && extract_dir.exists() {
+            touch_last_used(&image_dir);
             return Ok(extract_dir);
         }
 
@@ -187,56 +192,13 @@ impl BwrapRuntime {
             .await
             .io_ctx("create_dir_all", &staging_dir)?;
 
-        let tar_path = ctx.resolve_tool("tar").await?;
-
-        for layer in layers {
-            let layer_path = image_source.join(layer);
-            if !layer_path.exists() {
-                let _ = tokio::fs::remove_dir_all(&staging_dir).await;
-                return Err(ExecutorError::Io {
-                    source: std::io::Error::other(format!(
-                        "Layer '{}' listed in manifest but not found at '{}'",
-                        layer,
-                        layer_path.display()
-                    )),
-                    operation: "metadata",
-                    path: layer_path.clone(),
-                });
-            }
-
-            tracing::debug!("Extracting layer: {:?}", layer_path);
-
-            let mut cmd_layer = TokioCommand::new(&tar_path);
-            cmd_layer
-                .arg("-xf")
-                .arg(&layer_path)
-                .arg("-C")
-                .arg(&staging_dir)
-                .arg("--no-same-owner")
-                .arg("--no-same-permissions")
-                .arg("--mode=0755")
-                .arg("--delay-directory-restore")
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped());
-
-            ctx.restrict_command_environment(&mut cmd_layer, &[]).await;
-
-            let output = cmd_layer
-                .output()
-                .await
-                .map_err(|e| ExecutorError::command_failed("tar", e))?;
-            if !output.status.success() {
-                let _ = tokio::fs::remove_dir_all(&staging_dir).await;
-                return Err(ExecutorError::Io {
-                    source: std::io::Error::other(format!(
-                        "Failed to extract layer '{}'. Stderr: {}",
-                        layer,
-                        String::from_utf8_lossy(&output.stderr)
-                    )),
-                    operation: "extract",
-                    path: layer_path.clone(),
-                });
-            }
+        let layers_dir = image_dir.join(".layers_staging");
+        let extracted =
+            layers::extract_layers(ctx, &image_source, layers, &staging_dir, &layers_dir).await;
+        let _ = tokio::fs::remove_dir_all(&layers_dir).await;
+        if let Err(e) = extracted {
+            let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+            return Err(e);
         }
 
         for dir in &["dev", "proc", "tmp"] {
@@ -277,9 +239,35 @@ impl BwrapRuntime {
         let _ = tokio::fs::remove_file(&lock_path).await;
 
         tracing::info!("Successfully extracted rootfs for '{}'", image_tag);
+
+        if let Some(max_bytes) = ctx.request.image_cache_limit {
+            match prune_rootfs_cache(&images_cache_dir, max_bytes, Some(&image_hash), false) {
+                Ok(report) if report.remaining_bytes > max_bytes => tracing::warn!(
+                    "Rootfs cache at {:?} holds {} bytes, above its {} byte cap; {} image(s) are in use.",
+                    images_cache_dir,
+                    report.remaining_bytes,
+                    max_bytes,
+                    report.in_use.len()
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to prune rootfs cache: {}", e),
+            }
+        }
         Ok(extract_dir)
     }
 
+    pub(crate) async fn lock_rootfs_in_use(
+        ctx: &RuntimeContext<'_>,
+        image_tag: &str,
+    ) -> Result<nix::fcntl::Flock<std::fs::File>> {
+        let image_hash = crate::util::extract_image_hash(image_tag)?;
+        let image_dir = ctx.get_images_cache_dir().join(image_hash);
+        tokio::fs::create_dir_all(&image_dir)
+            .await
+            .io_ctx("create_dir_all", &image_dir)?;
+        super::acquire_shared_flock(&image_dir.join(IN_USE_LOCK), "rootfs in-use").await
+    }
+
     async fn stage_image_locally(
         shared_path: &std::path::Path,
         local_path: &std::path::Path,
@@ -576,6 +564,27 @@ impl BwrapRuntime {
         }
     }
 
+    pub async fn check_userns_support(ctx: &RuntimeContext<'_>) -> bool {
+        let bwrap_path = match ctx.get_host_tool_path("bwrap").await {
+            Ok(p) => p,
+            Err(_) => return false,
+        };
+
+        let mut cmd = TokioCommand::new(&bwrap_path);
+        cmd.arg("--ro-bind").arg("/").arg("/").arg("true");
+
+        ctx.restrict_command_environment(&mut cmd, &[]).await;
+        cmd.stdout(Stdio::null()).stderr(Stdio::null());
+
+        match cmd.status().await {
+            Ok(status) => status.success(),
+            Err(e) => {
+                tracing::debug!("bwrap capability check failed to start: {}", e);
+                false
+            }
+        }
+    }
+
     pub async fn build_command(
         ctx: &RuntimeContext<'_>,
         rootfs_path: &Path,
@@ -586,22 +595,29 @@ impl BwrapRuntime {
         let mut cmd = TokioCommand::new(bwrap_path);
         let request = ctx.request;
 
-        use repx_core::model::MountPolicy;
+        use repx_core::model::{MountPolicy, NetworkMode};
 
         if request.mount_policy.is_all_host() {
             Self::configure_host_path_mounts(&mut cmd, ctx, rootfs_path).await?;
 
             cmd.arg("--unshare-pid")
                 .arg("--unshare-ipc")
-                .arg("--unshare-uts")
-                .arg("--dev-bind")
+                .arg("--unshare-uts");
+            if request.network_isolated() {
+                cmd.arg("--unshare-net");
+            }
+            cmd.arg("--dev-bind")
                 .arg("/dev")
                 .arg("/dev")
                 .arg("--proc")
                 .arg("/proc");
         } else {
+            let share_net = request.network == Some(NetworkMode::Host);
             if matches!(request.mount_policy, MountPolicy::Isolated) {
                 cmd.arg("--unshare-all");
+                if share_net {
+                    cmd.arg("--share-net");
+                }
             } else {
                 tracing::info!(
                     "Skipping user namespace isolation to preserve supplementary groups for mounted paths"
@@ -609,8 +625,10 @@ impl BwrapRuntime {
                 cmd.arg("--unshare-ipc")
                     .arg("--unshare-pid")
                     .arg("--unshare-uts")
-                    .arg("--unshare-net")
                     .arg("--unshare-cgroup");
+                if !share_net {
+                    cmd.arg("--unshare-net");
+                }
             }
             cmd.arg("--hostname").arg(super::CONTAINER_HOSTNAME);
 
@@ -698,8 +716,25 @@ impl BwrapRuntime {
             }
         }
 
+        for mount in super::checked_data_mounts(request)? {
+            cmd.arg("--ro-bind")
+                .arg(&mount.host_path)
+                .arg(mount.target());
+        }
+
         cmd.arg("--setenv").arg("PATH").arg(inner_path);
         cmd.arg("--setenv").arg("TERM").arg("xterm");
+        for (key, value) in request.job_env() {
+            cmd.arg("--setenv").arg(key).arg(value);
+        }
+        if let Some(dir) = ctx.secrets_dir {
+            cmd.arg("--ro-bind")
+                .arg(dir)
+                .arg(dir)
+                .arg("--setenv")
+                .arg(crate::SECRETS_DIR_ENV)
+                .arg(dir);
+        }
 
         cmd.arg("--chdir").arg(&request.user_out_dir);
 
@@ -729,22 +764,47 @@ impl BwrapRuntime {
             memfd_guards.push(owned);
         }
 
+        let secrets = request.env_secrets();
+        if !secrets.is_empty() {
+            // bwrap clears the environment, so the values travel through
+            // --args instead of the visible command line.
+            let mut data = Vec::new();
+            for (name, value) in &secrets {
+                for arg in ["--setenv", name, value] {
+                    data.extend_from_slice(arg.as_bytes());
+                    data.push(0);
+                }
+            }
+            let (fd, owned) = create_memfd_with_data(&data, "repx-secrets")?;
+            cmd.arg("--args").arg(fd.to_string());
+            memfd_guards.push(owned);
+        }
+
+        if let Some(profile) = &request.seccomp {
+            let program = super::seccomp::compile_seccomp_profile(profile)?;
+            let (fd, owned) =
+                create_memfd_with_data(&super::seccomp::program_bytes(&program), "repx-seccomp")?;
+            cmd.arg("--seccomp").arg(fd.to_string());
+            memfd_guards.push(owned);
+        }
+
+        let (program, program_args) = super::job_argv(request, script_path, &rewritten_args);
         if !request.mount_policy.specific_paths().is_empty() {
             cmd.arg("--");
             cmd.arg("/bin/sh");
             cmd.arg("-c");
 
             let mut args_escaped = Vec::new();
-            for a in &rewritten_args {
-                args_escaped.push(format!("'{}'", a.replace('\'', "'\\''")));
+            for a in std::iter::once(&program).chain(&program_args) {
+                args_escaped.push(format!("'{}'", a.to_string_lossy().replace('\'', "'\\''")));
             }
             let args_str = args_escaped.join(" ");
 
-            cmd.arg(format!("exec '{}' {}", script_path.display(), args_str));
+            cmd.arg(format!("exec {}", args_str));
         } else {
             cmd.arg("--");
-            cmd.arg(script_path);
-            cmd.args(&rewritten_args);
+            cmd.arg(program);
+            cmd.args(program_args);
         }
 
         for guard in memfd_guards {
@@ -761,7 +821,10 @@ impl BwrapRuntime {
             rootfs_path = %rootfs_path.display(),
             "Building bwrap command"
         );
-        tracing::info!(command = ?cmd.as_std(), "Full bwrap command");
+        tracing::info!(
+            command = %request.redactor().redact(&format!("{:?}", cmd.as_std())),
+            "Full bwrap command"
+        );
 
         Ok(cmd)
     }
@@ -830,7 +893,17 @@ impl BwrapRuntime {
                 false
             };
 
-            if has_image_store_entries && can_overlay {
+            if has_image_store_entries
+                && can_overlay
+                && Self::check_tmp_overlay_support(ctx, rootfs_path).await
+            {
+                cmd.arg("--overlay-src")
+                    .arg(&image_store)
+                    .arg("--overlay-src")
+                    .arg("/nix/store")
+                    .arg("--tmp-overlay")
+                    .arg("/nix/store");
+            } else if has_image_store_entries && can_overlay {
                 let eph_base = ephemeral_base(request);
                 let overlay_upper = eph_base.join("nix_overlay_upper");
                 let overlay_work = eph_base.join("nix_overlay_work");
@@ -902,7 +975,14 @@ impl BwrapRuntime {
             let image_nix = rootfs_path.join("nix");
             if image_nix.exists() {
                 cmd.arg("--dir").arg("/nix");
-                cmd.arg("--bind").arg(image_nix).arg("/nix");
+                if Self::check_tmp_overlay_support(ctx, rootfs_path).await {
+                    cmd.arg("--overlay-src")
+                        .arg(image_nix)
+                        .arg("--tmp-overlay")
+                        .arg("/nix");
+                } else {
+                    cmd.arg("--ro-bind").arg(image_nix).arg("/nix");
+                }
             }
         }
 
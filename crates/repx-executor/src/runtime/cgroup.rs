@@ -0,0 +1,215 @@
+use nix::errno::Errno;
+use repx_core::model::JobId;
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::process::Command as TokioCommand;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+const CPU_PERIOD_US: u64 = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceLimits {
+    pub mem_bytes: Option<u64>,
+    pub cpus: Option<u32>,
+}
+
+impl ResourceLimits {
+    pub fn new(mem_bytes: Option<u64>, cpus: Option<u32>) -> Option<Self> {
+        (mem_bytes.is_some() || cpus.is_some()).then_some(Self { mem_bytes, cpus })
+    }
+
+    fn controllers(&self) -> Vec<&'static str> {
+        let mut controllers = Vec::new();
+        if self.mem_bytes.is_some() {
+            controllers.push("memory");
+        }
+        if self.cpus.is_some() {
+            controllers.push("cpu");
+        }
+        controllers
+    }
+}
+
+fn own_cgroup() -> io::Result<PathBuf> {
+    let content = std::fs::read_to_string("/proc/self/cgroup")?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(|path| Path::new(CGROUP_ROOT).join(path.trim_start_matches('/')))
+        .ok_or_else(|| io::Error::other("no cgroup v2 hierarchy for this process"))
+}
+
+// Processes may not sit in a cgroup that delegates controllers to its
+// children, so repx moves itself and its siblings into this leaf first.
+const LEAF_CGROUP: &str = "repx-leaf";
+
+fn delegated_cgroup() -> io::Result<PathBuf> {
+    let own = own_cgroup()?;
+    match own.parent() {
+        Some(parent) if own.file_name() == Some(OsStr::new(LEAF_CGROUP)) => {
+            Ok(parent.to_path_buf())
+        }
+        _ => Ok(own),
+    }
+}
+
+fn job_cgroup_dir(job_id: &JobId) -> io::Result<PathBuf> {
+    let name = format!("repx-{}", job_id.as_str().replace('/', "_"));
+    Ok(delegated_cgroup()?.join(name))
+}
+
+fn move_processes_to_leaf(parent: &Path) -> io::Result<()> {
+    let leaf = parent.join(LEAF_CGROUP);
+    std::fs::create_dir_all(&leaf)?;
+    let procs = std::fs::read_to_string(parent.join("cgroup.procs"))?;
+    for pid in procs.split_whitespace() {
+        match std::fs::write(leaf.join("cgroup.procs"), pid) {
+            Ok(()) => {}
+            Err(e) if e.raw_os_error() == Some(Errno::ESRCH as i32) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+fn enable_controllers(parent: &Path, controllers: &[&str]) -> io::Result<()> {
+    let enabled = std::fs::read_to_string(parent.join("cgroup.subtree_control"))?;
+    let missing: Vec<String> = controllers
+        .iter()
+        .filter(|c| !enabled.split_whitespace().any(|e| e == **c))
+        .map(|c| format!("+{}", c))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let mut attempts = 0;
+    loop {
+        move_processes_to_leaf(parent)?;
+        match std::fs::write(parent.join("cgroup.subtree_control"), missing.join(" ")) {
+            Err(e) if e.raw_os_error() == Some(Errno::EBUSY as i32) && attempts < 3 => {
+                attempts += 1
+            }
+            result => return result,
+        }
+    }
+}
+
+fn create_job_cgroup(job_id: &JobId, limits: &ResourceLimits) -> io::Result<PathBuf> {
+    let dir = job_cgroup_dir(job_id)?;
+    let parent = dir
+        .parent()
+        .ok_or_else(|| io::Error::other("cgroup has no parent"))?;
+    enable_controllers(parent, &limits.controllers())?;
+
+    std::fs::create_dir_all(&dir)?;
+    if let Some(mem) = limits.mem_bytes {
+        std::fs::write(dir.join("memory.max"), mem.to_string())?;
+    }
+    if let Some(cpus) = limits.cpus {
+        std::fs::write(
+            dir.join("cpu.max"),
+            format!("{} {}", u64::from(cpus) * CPU_PERIOD_US, CPU_PERIOD_US),
+        )?;
+    }
+    Ok(dir)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MemoryUsage {
+    pub oom_kills: u64,
+    pub peak_bytes: Option<u64>,
+    pub limit_bytes: Option<u64>,
+}
+
+fn read_memory_usage(dir: &Path) -> Option<MemoryUsage> {
+    let events = std::fs::read_to_string(dir.join("memory.events")).ok()?;
+    let oom_kills = events
+        .lines()
+        .find_map(|line| line.strip_prefix("oom_kill "))
+        .and_then(|count| count.trim().parse().ok())
+        .unwrap_or(0);
+    let read_bytes = |file: &str| {
+        std::fs::read_to_string(dir.join(file))
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+    };
+    Some(MemoryUsage {
+        oom_kills,
+        peak_bytes: read_bytes("memory.peak"),
+        limit_bytes: read_bytes("memory.max"),
+    })
+}
+
+pub(crate) fn job_memory_usage(job_id: &JobId) -> Option<MemoryUsage> {
+    read_memory_usage(&job_cgroup_dir(job_id).ok()?)
+}
+
+pub(crate) fn remove_job_cgroup(job_id: &JobId) {
+    if let Ok(dir) = job_cgroup_dir(job_id) {
+        if dir.exists() {
+            if let Err(e) = std::fs::remove_dir(&dir) {
+                tracing::debug!("Failed to remove cgroup {:?}: {}", dir, e);
+            }
+        }
+    }
+}
+
+fn find_on_path(binary: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| candidate.is_file())
+}
+
+pub(crate) fn limited_command(
+    job_id: &JobId,
+    limits: &ResourceLimits,
+    program: &OsStr,
+    args: &[OsString],
+) -> TokioCommand {
+    let cgroup_error = match create_job_cgroup(job_id, limits) {
+        Ok(dir) => {
+            tracing::info!("Running job '{}' in cgroup {:?}", job_id, dir);
+            let mut cmd = TokioCommand::new("/bin/sh");
+            cmd.arg("-c")
+                .arg("echo $$ > \"$0/cgroup.procs\" && exec \"$@\"")
+                .arg(&dir)
+                .arg(program)
+                .args(args);
+            return cmd;
+        }
+        Err(e) => e,
+    };
+
+    if let Some(systemd_run) = find_on_path("systemd-run") {
+        tracing::info!(
+            "Cannot create a cgroup directly ({}). Using systemd-run for job '{}'.",
+            cgroup_error,
+            job_id
+        );
+        let mut cmd = TokioCommand::new(systemd_run);
+        cmd.arg("--user")
+            .arg("--scope")
+            .arg("--quiet")
+            .arg("--collect");
+        if let Some(mem) = limits.mem_bytes {
+            cmd.arg("-p").arg(format!("MemoryMax={}", mem));
+        }
+        if let Some(cpus) = limits.cpus {
+            cmd.arg("-p")
+                .arg(format!("CPUQuota={}%", u64::from(cpus) * 100));
+        }
+        cmd.arg("--").arg(program).args(args);
+        return cmd;
+    }
+
+    tracing::warn!(
+        "Cannot enforce resource limits for job '{}': {}. Running it without limits.",
+        job_id,
+        cgroup_error
+    );
+    let mut cmd = TokioCommand::new(program);
+    cmd.args(args);
+    cmd
+}
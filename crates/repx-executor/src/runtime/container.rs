@@ -1,16 +1,21 @@
 use crate::context::RuntimeContext;
 use crate::error::{ExecutorError, IoContext, Result};
-use std::path::Path;
+use crate::ExecutionRequest;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::time::Duration;
 use tokio::fs::File;
 use tokio::process::Command as TokioCommand;
 
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
 pub struct ContainerRuntime;
 
 use super::{Runtime, CONTAINER_HOSTNAME};
 
 impl ContainerRuntime {
-    fn get_runtime_details(runtime: &Runtime) -> Result<(&str, &str)> {
+    fn get_runtime_details(runtime: &Runtime) -> Result<(&'static str, &str)> {
         match runtime {
             Runtime::Docker { image_tag } => Ok(("docker", image_tag.as_str())),
             Runtime::Podman { image_tag } => Ok(("podman", image_tag.as_str())),
@@ -23,6 +28,64 @@ impl ContainerRuntime {
         }
     }
 
+    pub fn has_subid_range(content: &str, user: &str, uid: u32) -> bool {
+        let uid = uid.to_string();
+        content.lines().any(|line| {
+            line.split(':')
+                .next()
+                .is_some_and(|owner| owner == user || owner == uid)
+        })
+    }
+
+    pub async fn probe(
+        ctx: &RuntimeContext<'_>,
+        runtime: &Runtime,
+    ) -> std::result::Result<(), String> {
+        let (binary, _) = Self::get_runtime_details(runtime).map_err(|e| e.to_string())?;
+        let uid = nix::unistd::getuid();
+        if binary == "podman" && !uid.is_root() {
+            let user = nix::unistd::User::from_uid(uid)
+                .ok()
+                .flatten()
+                .map(|user| user.name)
+                .unwrap_or_default();
+            for file in ["/etc/subuid", "/etc/subgid"] {
+                let content = std::fs::read_to_string(file).unwrap_or_default();
+                if !Self::has_subid_range(&content, &user, uid.as_raw()) {
+                    return Err(format!(
+                        "user '{}' has no range in {} for rootless podman",
+                        user, file
+                    ));
+                }
+            }
+        }
+
+        let mut cmd = TokioCommand::new(binary);
+        cmd.arg("info")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        ctx.restrict_command_environment(&mut cmd, &[binary]).await;
+        match tokio::time::timeout(PROBE_TIMEOUT, cmd.output()).await {
+            Ok(Ok(output)) if output.status.success() => Ok(()),
+            Ok(Ok(output)) => Err(format!(
+                "`{} info` failed: {}",
+                binary,
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .find(|line| !line.trim().is_empty())
+                    .unwrap_or("no error output")
+            )),
+            Ok(Err(e)) => Err(format!("cannot run {}: {}", binary, e)),
+            Err(_) => Err(format!(
+                "`{} info` did not answer within {}s",
+                binary,
+                PROBE_TIMEOUT.as_secs()
+            )),
+        }
+    }
+
     pub async fn ensure_image_loaded(ctx: &RuntimeContext<'_>, runtime: &Runtime) -> Result<()> {
         let (binary, image_tag) = Self::get_runtime_details(runtime)?;
         let image_hash = crate::util::extract_image_hash(image_tag)?;
@@ -224,28 +287,45 @@ impl ContainerRuntime {
         Ok(())
     }
 
-    pub async fn build_command(
-        ctx: &RuntimeContext<'_>,
-        runtime: &Runtime,
-        script_path: &Path,
-        args: &[String],
-    ) -> Result<(TokioCommand, Vec<tempfile::TempPath>)> {
-        let (binary, image_tag) = Self::get_runtime_details(runtime)?;
-        let request = ctx.request;
-        let mut cmd = TokioCommand::new(binary);
-
+    fn unique_id(request: &ExecutionRequest) -> u64 {
         let path_bytes = request.repx_out_dir.as_os_str().as_encoded_bytes();
         let mut unique_id: u64 = 0xcbf29ce484222325;
         for &byte in path_bytes {
             unique_id ^= byte as u64;
             unique_id = unique_id.wrapping_mul(0x100000001b3);
         }
+        unique_id
+    }
 
-        let xdg_runtime_dir = request
+    pub(crate) fn runtime_dir(request: &ExecutionRequest) -> PathBuf {
+        request
             .base_path
             .join("repx")
             .join("runtime")
-            .join(format!("podman-{:x}", unique_id));
+            .join(format!("podman-{:x}", Self::unique_id(request)))
+    }
+
+    pub(crate) fn container_name(request: &ExecutionRequest) -> String {
+        format!("repx-{:x}", Self::unique_id(request))
+    }
+
+    pub async fn build_command(
+        ctx: &RuntimeContext<'_>,
+        runtime: &Runtime,
+        script_path: &Path,
+        args: &[String],
+    ) -> Result<(TokioCommand, Vec<tempfile::TempPath>)> {
+        let (binary, image_tag) = Self::get_runtime_details(runtime)?;
+        let request = ctx.request;
+        if request.seccomp.is_some() {
+            return Err(ExecutorError::SeccompUnsupported {
+                job_id: request.job_id.to_string(),
+                runtime: binary,
+            });
+        }
+        let mut cmd = TokioCommand::new(binary);
+
+        let xdg_runtime_dir = Self::runtime_dir(request);
 
         if !xdg_runtime_dir.exists() {
             tokio::fs::create_dir_all(&xdg_runtime_dir)
@@ -262,12 +342,46 @@ impl ContainerRuntime {
             }
         }
 
-        cmd.arg("run")
-            .arg("--rm")
-            .arg("--hostname")
+        cmd.arg("run").arg("--rm");
+        if ctx.interactive {
+            cmd.arg("--interactive");
+            if std::io::stdin().is_terminal() {
+                cmd.arg("--tty");
+            }
+        }
+        cmd.arg("--hostname")
             .arg(CONTAINER_HOSTNAME)
             .arg("--env")
             .arg("TERM=xterm");
+        if let Some(network) = request.network {
+            cmd.arg(format!("--network={}", network));
+        }
+        if let Some(limits) = request.resource_limits {
+            if let Some(mem_bytes) = limits.mem_bytes {
+                cmd.arg(format!("--memory={}", mem_bytes));
+            }
+            if let Some(cpus) = limits.cpus {
+                cmd.arg(format!("--cpus={}", cpus));
+            }
+        }
+        if request.checkpoint_interval.is_some() {
+            cmd.arg("--name")
+                .arg(Self::container_name(request))
+                .arg("--replace");
+        }
+        for (key, value) in request.job_env() {
+            cmd.arg("--env").arg(format!("{}={}", key, value));
+        }
+        for (name, value) in request.env_secrets() {
+            cmd.arg("--env").arg(&name);
+            cmd.env(name, value);
+        }
+        if let Some(dir) = ctx.secrets_dir {
+            cmd.arg("--volume")
+                .arg(format!("{}:{}:ro", dir.display(), dir.display()))
+                .arg("--env")
+                .arg(format!("{}={}", crate::SECRETS_DIR_ENV, dir.display()));
+        }
 
         if matches!(runtime, Runtime::Podman { .. }) {
             cmd.arg("--unsetenv").arg("container");
@@ -303,6 +417,13 @@ impl ContainerRuntime {
             }
             repx_core::model::MountPolicy::Isolated => {}
         }
+        for mount in super::checked_data_mounts(request)? {
+            cmd.arg("--volume").arg(format!(
+                "{}:{}:ro",
+                mount.host_path.display(),
+                mount.target().display()
+            ));
+        }
 
         let mut rewritten_args: Vec<String> = args.to_vec();
         let mut temp_files: Vec<tempfile::TempPath> = Vec::new();
@@ -349,8 +470,8 @@ impl ContainerRuntime {
             temp_files.push(tmp.into_temp_path());
         }
 
-        cmd.arg(image_tag).arg(script_path);
-        cmd.args(&rewritten_args);
+        let (program, program_args) = super::job_argv(request, script_path, &rewritten_args);
+        cmd.arg(image_tag).arg(program).args(program_args);
 
         ctx.restrict_command_environment(&mut cmd, &[binary]).await;
         Ok((cmd, temp_files))
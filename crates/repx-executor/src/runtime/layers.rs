@@ -0,0 +1,224 @@
+use crate::context::RuntimeContext;
+use crate::error::{ExecutorError, IoContext, Result};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::process::Command as TokioCommand;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl LayerCompression {
+    pub fn detect(layer_path: &Path) -> Result<Self> {
+        let mut magic = [0u8; 4];
+        let mut file = std::fs::File::open(layer_path).io_ctx("open", layer_path)?;
+        let mut read = 0;
+        while read < magic.len() {
+            match file.read(&mut magic[read..]).io_ctx("read", layer_path)? {
+                0 => break,
+                n => read += n,
+            }
+        }
+        let magic = &magic[..read];
+        Ok(if magic.starts_with(GZIP_MAGIC) {
+            LayerCompression::Gzip
+        } else if magic.starts_with(ZSTD_MAGIC) {
+            LayerCompression::Zstd
+        } else {
+            LayerCompression::None
+        })
+    }
+
+    fn tool(&self) -> Option<&'static str> {
+        match self {
+            LayerCompression::None => None,
+            LayerCompression::Gzip => Some("gzip"),
+            LayerCompression::Zstd => Some("zstd"),
+        }
+    }
+}
+
+enum PendingLayer {
+    Plain(PathBuf),
+    Decompressing(JoinHandle<Result<PathBuf>>),
+}
+
+pub(crate) async fn extract_layers(
+    ctx: &RuntimeContext<'_>,
+    image_source: &Path,
+    layers: &[String],
+    staging_dir: &Path,
+    work_dir: &Path,
+) -> Result<()> {
+    let mut layer_paths = Vec::with_capacity(layers.len());
+    for layer in layers {
+        let layer_path = image_source.join(layer);
+        if !layer_path.exists() {
+            return Err(ExecutorError::Io {
+                source: std::io::Error::other(format!(
+                    "Layer '{}' listed in manifest but not found at '{}'",
+                    layer,
+                    layer_path.display()
+                )),
+                operation: "metadata",
+                path: layer_path,
+            });
+        }
+        layer_paths.push(layer_path);
+    }
+
+    tokio::fs::create_dir_all(work_dir)
+        .await
+        .io_ctx("create_dir_all", work_dir)?;
+
+    let tar_path = ctx.resolve_tool("tar").await?;
+    let mut decompressors = Vec::with_capacity(layer_paths.len());
+    for layer_path in &layer_paths {
+        let decompressor = match LayerCompression::detect(layer_path)?.tool() {
+            Some(tool) => Some((tool, ctx.resolve_tool(tool).await?)),
+            None => None,
+        };
+        decompressors.push(decompressor);
+    }
+
+    let parallelism = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let permits = Arc::new(Semaphore::new(parallelism));
+    let mut pending = Vec::with_capacity(layer_paths.len());
+    for (index, (layer_path, decompressor)) in
+        layer_paths.into_iter().zip(decompressors).enumerate()
+    {
+        let Some((tool, tool_path)) = decompressor else {
+            pending.push(PendingLayer::Plain(layer_path));
+            continue;
+        };
+        let output_path = work_dir.join(format!("{}.tar", index));
+        let mut cmd = TokioCommand::new(&tool_path);
+        cmd.arg("-dc").arg(&layer_path).stderr(Stdio::piped());
+        ctx.restrict_command_environment(&mut cmd, &[]).await;
+        pending.push(PendingLayer::Decompressing(tokio::spawn(decompress(
+            cmd,
+            tool,
+            layer_path,
+            output_path,
+            permits.clone(),
+        ))));
+    }
+
+    let mut pending = pending.into_iter();
+    while let Some(layer) = pending.next() {
+        let result = extract_one(ctx, &tar_path, layer, staging_dir).await;
+        if result.is_err() {
+            abort_pending(pending.by_ref());
+        }
+        result?;
+    }
+    Ok(())
+}
+
+fn abort_pending(pending: impl Iterator<Item = PendingLayer>) {
+    for layer in pending {
+        if let PendingLayer::Decompressing(handle) = layer {
+            handle.abort();
+        }
+    }
+}
+
+async fn decompress(
+    mut cmd: TokioCommand,
+    tool: &'static str,
+    layer_path: PathBuf,
+    output_path: PathBuf,
+    permits: Arc<Semaphore>,
+) -> Result<PathBuf> {
+    let _permit = permits
+        .acquire_owned()
+        .await
+        .map_err(|e| ExecutorError::command_failed(tool, std::io::Error::other(e)))?;
+    tracing::debug!("Decompressing layer {:?} with {}", layer_path, tool);
+    let output = std::fs::File::create(&output_path).io_ctx("create", &output_path)?;
+    let result = cmd
+        .stdout(output)
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| ExecutorError::command_failed(tool, e))?
+        .wait_with_output()
+        .await
+        .map_err(|e| ExecutorError::command_failed(tool, e))?;
+    if !result.status.success() {
+        let _ = tokio::fs::remove_file(&output_path).await;
+        return Err(ExecutorError::Io {
+            source: std::io::Error::other(format!(
+                "Failed to decompress layer with {}. Stderr: {}",
+                tool,
+                String::from_utf8_lossy(&result.stderr)
+            )),
+            operation: "decompress",
+            path: layer_path,
+        });
+    }
+    Ok(output_path)
+}
+
+async fn extract_one(
+    ctx: &RuntimeContext<'_>,
+    tar_path: &Path,
+    layer: PendingLayer,
+    staging_dir: &Path,
+) -> Result<()> {
+    let (archive, temporary) = match layer {
+        PendingLayer::Plain(path) => (path, false),
+        PendingLayer::Decompressing(handle) => {
+            let path = handle.await.map_err(|e| {
+                ExecutorError::command_failed("layer decompression", std::io::Error::other(e))
+            })??;
+            (path, true)
+        }
+    };
+
+    tracing::debug!("Extracting layer: {:?}", archive);
+
+    let mut cmd_layer = TokioCommand::new(tar_path);
+    cmd_layer
+        .arg("-xf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(staging_dir)
+        .arg("--no-same-owner")
+        .arg("--no-same-permissions")
+        .arg("--mode=0755")
+        .arg("--delay-directory-restore")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    ctx.restrict_command_environment(&mut cmd_layer, &[]).await;
+
+    let output = cmd_layer
+        .output()
+        .await
+        .map_err(|e| ExecutorError::command_failed("tar", e))?;
+    if temporary {
+        let _ = tokio::fs::remove_file(&archive).await;
+    }
+    if !output.status.success() {
+        return Err(ExecutorError::Io {
+            source: std::io::Error::other(format!(
+                "Failed to extract layer '{}'. Stderr: {}",
+                archive.display(),
+                String::from_utf8_lossy(&output.stderr)
+            )),
+            operation: "extract",
+            path: archive,
+        });
+    }
+    Ok(())
+}
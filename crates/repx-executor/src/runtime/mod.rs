@@ -1,15 +1,28 @@
 mod bwrap;
+mod cgroup;
 mod container;
+mod layers;
 mod native;
+mod proot;
+mod rootfs_cache;
+mod seccomp;
 
 pub use bwrap::BwrapRuntime;
+pub(crate) use cgroup::job_memory_usage;
+pub use cgroup::ResourceLimits;
 pub use container::ContainerRuntime;
+pub use layers::LayerCompression;
 pub use native::NativeRuntime;
+pub use proot::ProotRuntime;
+pub use rootfs_cache::{prune_rootfs_cache, RootfsPruneReport};
+pub use seccomp::{compile_seccomp_profile, DEFAULT_SECCOMP_PROFILE};
 
 use crate::error::ExecutorError;
 use crate::error::IoContext;
 use crate::util::ImageTag;
 use nix::fcntl::{Flock, FlockArg};
+use repx_core::model::expand_arg_template;
+use std::ffi::OsString;
 
 pub(crate) const CONTAINER_HOSTNAME: &str = "repx-container";
 
@@ -19,6 +32,7 @@ pub enum Runtime {
     Podman { image_tag: ImageTag },
     Docker { image_tag: ImageTag },
     Bwrap { image_tag: ImageTag },
+    Proot { image_tag: ImageTag },
 }
 
 impl Runtime {
@@ -27,7 +41,8 @@ impl Runtime {
             Runtime::Native => None,
             Runtime::Podman { image_tag }
             | Runtime::Docker { image_tag }
-            | Runtime::Bwrap { image_tag } => Some(image_tag),
+            | Runtime::Bwrap { image_tag }
+            | Runtime::Proot { image_tag } => Some(image_tag),
         }
     }
 }
@@ -39,10 +54,57 @@ impl std::fmt::Display for Runtime {
             Runtime::Podman { image_tag } => write!(f, "podman ({})", image_tag),
             Runtime::Docker { image_tag } => write!(f, "docker ({})", image_tag),
             Runtime::Bwrap { image_tag } => write!(f, "bwrap ({})", image_tag),
+            Runtime::Proot { image_tag } => write!(f, "proot ({})", image_tag),
         }
     }
 }
 
+pub(crate) fn checked_data_mounts(
+    request: &crate::ExecutionRequest,
+) -> Result<&[repx_core::model::DataMount], ExecutorError> {
+    for mount in &request.data_mounts {
+        if !mount.host_path.exists() {
+            return Err(ExecutorError::DataMountUnavailable {
+                job_id: request.job_id.to_string(),
+                mount: mount.to_string(),
+                detail: "host path does not exist",
+            });
+        }
+    }
+    if !request.data_mounts.is_empty() {
+        tracing::info!("Read-only data mounts: {:?}", request.data_mounts);
+    }
+    Ok(&request.data_mounts)
+}
+
+pub(crate) fn job_argv(
+    request: &crate::ExecutionRequest,
+    script_path: &std::path::Path,
+    args: &[String],
+) -> (OsString, Vec<OsString>) {
+    let mut argv: Vec<OsString> = Vec::new();
+    if let Some(interpreter) = &request.interpreter {
+        argv.push(interpreter.into());
+    }
+    argv.push(script_path.into());
+    if request.arg_template.is_empty() {
+        argv.extend(args.iter().map(OsString::from));
+    } else {
+        let expanded = expand_arg_template(&request.arg_template, |name| {
+            let index = match name {
+                "out" => 0,
+                "inputs" => 1,
+                "params" => 2,
+                _ => return None,
+            };
+            args.get(index).cloned()
+        });
+        argv.extend(expanded.into_iter().map(OsString::from));
+    }
+    let program = argv.remove(0);
+    (program, argv)
+}
+
 const LOCK_POLL_INTERVAL_MS: u64 = 100;
 const LOCK_TIMEOUT_SECS_DEFAULT: u64 = 300;
 
@@ -57,12 +119,27 @@ fn lock_timeout() -> std::time::Duration {
 pub(crate) async fn acquire_flock(
     lock_path: &std::path::Path,
     context_name: &str,
+) -> Result<Flock<std::fs::File>, ExecutorError> {
+    acquire_flock_with(lock_path, context_name, FlockArg::LockExclusiveNonblock).await
+}
+
+pub(crate) async fn acquire_shared_flock(
+    lock_path: &std::path::Path,
+    context_name: &str,
+) -> Result<Flock<std::fs::File>, ExecutorError> {
+    acquire_flock_with(lock_path, context_name, FlockArg::LockSharedNonblock).await
+}
+
+async fn acquire_flock_with(
+    lock_path: &std::path::Path,
+    context_name: &str,
+    mode: FlockArg,
 ) -> Result<Flock<std::fs::File>, ExecutorError> {
     let mut lock_file = std::fs::File::create(lock_path).io_ctx("create", lock_path)?;
     let timeout = lock_timeout();
     let lock_start = std::time::Instant::now();
     loop {
-        match Flock::lock(lock_file, FlockArg::LockExclusiveNonblock) {
+        match Flock::lock(lock_file, mode) {
             Ok(lock) => return Ok(lock),
             Err((f, errno))
                 if errno == nix::errno::Errno::EWOULDBLOCK
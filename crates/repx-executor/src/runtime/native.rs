@@ -1,4 +1,4 @@
-use crate::error::Result;
+use crate::error::{ExecutorError, IoContext, Result};
 use crate::ExecutionRequest;
 use std::path::Path;
 use tokio::process::Command as TokioCommand;
@@ -11,6 +11,18 @@ impl NativeRuntime {
         script_path: &Path,
         args: &[String],
     ) -> Result<TokioCommand> {
+        if request.network_isolated() {
+            return Err(ExecutorError::NetworkIsolationUnsupported {
+                job_id: request.job_id.to_string(),
+                runtime: "native",
+            });
+        }
+        if request.seccomp.is_some() {
+            return Err(ExecutorError::SeccompUnsupported {
+                job_id: request.job_id.to_string(),
+                runtime: "native",
+            });
+        }
         tracing::warn!(
             job_id = %request.job_id,
             script = %script_path.display(),
@@ -19,6 +31,8 @@ impl NativeRuntime {
              sandboxed execution."
         );
 
+        Self::link_data_mounts(request)?;
+
         let mut rewritten_args: Vec<String> = args.to_vec();
         let mut _memfd_guards: Vec<std::os::fd::OwnedFd> = Vec::new();
 
@@ -37,8 +51,17 @@ impl NativeRuntime {
             _memfd_guards.push(owned);
         }
 
-        let mut cmd = TokioCommand::new(script_path);
-        cmd.args(&rewritten_args);
+        let (program, program_args) = super::job_argv(request, script_path, &rewritten_args);
+        let mut cmd = match &request.resource_limits {
+            Some(limits) => {
+                super::cgroup::limited_command(&request.job_id, limits, &program, &program_args)
+            }
+            None => {
+                let mut cmd = TokioCommand::new(&program);
+                cmd.args(&program_args);
+                cmd
+            }
+        };
 
         if let Some(host_tools) = &request.host_tools_bin_dir {
             if let Some(system_path) = std::env::var_os("PATH") {
@@ -52,10 +75,44 @@ impl NativeRuntime {
             }
         }
 
+        cmd.envs(request.job_env());
+        cmd.envs(request.env_secrets());
+
         for guard in _memfd_guards {
             std::mem::forget(guard);
         }
 
         Ok(cmd)
     }
+
+    fn link_data_mounts(request: &ExecutionRequest) -> Result<()> {
+        for mount in super::checked_data_mounts(request)? {
+            let target = mount.target();
+            if target == mount.host_path {
+                continue;
+            }
+            match std::fs::read_link(target) {
+                Ok(existing) if existing == mount.host_path => continue,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                _ => {
+                    return Err(ExecutorError::DataMountUnavailable {
+                        job_id: request.job_id.to_string(),
+                        mount: mount.to_string(),
+                        detail: "mount path is already occupied on the host",
+                    })
+                }
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).io_ctx("create_dir_all", parent)?;
+            }
+            std::os::unix::fs::symlink(&mount.host_path, target).io_ctx("symlink", target)?;
+        }
+        Ok(())
+    }
+
+    pub fn release_limits(request: &ExecutionRequest) {
+        if matches!(request.runtime, crate::Runtime::Native) && request.resource_limits.is_some() {
+            super::cgroup::remove_job_cgroup(&request.job_id);
+        }
+    }
 }
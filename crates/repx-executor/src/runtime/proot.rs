@@ -0,0 +1,195 @@
+use crate::context::RuntimeContext;
+use crate::error::{ExecutorError, IoContext, Result};
+use std::ffi::OsString;
+use std::path::Path;
+use tokio::process::Command as TokioCommand;
+
+const EXCLUDED_HOST_DIRS: &[&str] = &["dev", "proc", "sys", "nix"];
+const DEFAULT_CONTAINER_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+fn bind(host: &Path, guest: &Path) -> OsString {
+    let mut spec = host.as_os_str().to_os_string();
+    spec.push(":");
+    spec.push(guest.as_os_str());
+    spec
+}
+
+pub struct ProotRuntime;
+
+impl ProotRuntime {
+    pub async fn is_available(ctx: &RuntimeContext<'_>) -> bool {
+        ctx.get_host_tool_path("proot").await.is_ok()
+    }
+
+    pub async fn build_command(
+        ctx: &RuntimeContext<'_>,
+        rootfs_path: &Path,
+        script_path: &Path,
+        args: &[String],
+    ) -> Result<TokioCommand> {
+        let request = ctx.request;
+        if request.network_isolated() {
+            return Err(ExecutorError::NetworkIsolationUnsupported {
+                job_id: request.job_id.to_string(),
+                runtime: "proot",
+            });
+        }
+        if request.seccomp.is_some() {
+            return Err(ExecutorError::SeccompUnsupported {
+                job_id: request.job_id.to_string(),
+                runtime: "proot",
+            });
+        }
+        let proot_path = ctx.get_host_tool_path("proot").await?;
+        let mut cmd = TokioCommand::new(proot_path);
+
+        cmd.env_clear();
+        cmd.arg("--kill-on-exit").arg("-r").arg(rootfs_path);
+        cmd.arg("-b")
+            .arg("/dev")
+            .arg("-b")
+            .arg("/proc")
+            .arg("-b")
+            .arg("/sys");
+
+        let mut inner_path = String::from(DEFAULT_CONTAINER_PATH);
+        if let Some(host_tools) = &request.host_tools_bin_dir {
+            inner_path = format!("{}:{}", host_tools.display(), inner_path);
+        }
+
+        if request.mount_policy.is_all_host() {
+            Self::configure_host_path_mounts(&mut cmd, rootfs_path).await?;
+
+            let host_path = std::env::var("PATH").unwrap_or_default();
+            if !host_path.is_empty() {
+                inner_path = format!("{}:{}", inner_path, host_path);
+            }
+            cmd.env("HOME", std::env::var("HOME").unwrap_or_else(|_| "/".into()));
+        } else {
+            let canonical_job_path = request
+                .job_package_path
+                .canonicalize()
+                .unwrap_or_else(|_| request.job_package_path.clone());
+
+            for path in [
+                &request.base_path,
+                &request.user_out_dir,
+                &request.repx_out_dir,
+            ] {
+                cmd.arg("-b").arg(path);
+            }
+            cmd.arg("-b")
+                .arg(bind(&canonical_job_path, &request.job_package_path));
+
+            let specific = request.mount_policy.specific_paths();
+            if !specific.is_empty() {
+                tracing::info!("[IMPURE] Specific host paths mounted: {:?}", specific);
+                for path in specific {
+                    cmd.arg("-b").arg(path);
+                }
+            }
+            cmd.env("HOME", "/");
+        }
+
+        for mount in super::checked_data_mounts(request)? {
+            cmd.arg("-b").arg(bind(&mount.host_path, mount.target()));
+        }
+        if let Some(dir) = ctx.secrets_dir {
+            cmd.arg("-b").arg(dir);
+        }
+        cmd.arg("-w").arg(&request.user_out_dir);
+
+        let mut rewritten_args: Vec<String> = args.to_vec();
+        let mut memfd_guards: Vec<std::os::fd::OwnedFd> = Vec::new();
+
+        if let Some(ref data) = request.inputs_data {
+            let (fd, owned) = super::bwrap::create_memfd_with_data(data, "repx-inputs")?;
+            if rewritten_args.len() > 1 {
+                rewritten_args[1] = format!("/proc/self/fd/{}", fd);
+            }
+            memfd_guards.push(owned);
+        }
+        if let Some(ref data) = request.parameters_data {
+            let (fd, owned) = super::bwrap::create_memfd_with_data(data, "repx-params")?;
+            if rewritten_args.len() > 2 {
+                rewritten_args[2] = format!("/proc/self/fd/{}", fd);
+            }
+            memfd_guards.push(owned);
+        }
+
+        let (program, program_args) = super::job_argv(request, script_path, &rewritten_args);
+        cmd.arg(program).args(program_args);
+
+        for guard in memfd_guards {
+            std::mem::forget(guard);
+        }
+
+        let proot_tmp = ctx.get_temp_path().await;
+        cmd.env("PROOT_TMP_DIR", &proot_tmp)
+            .env("PATH", inner_path)
+            .env("TERM", "xterm")
+            .envs(request.job_env())
+            .envs(request.env_secrets());
+        if let Some(dir) = ctx.secrets_dir {
+            cmd.env(crate::SECRETS_DIR_ENV, dir);
+        }
+
+        tracing::info!(
+            job_id = %request.job_id,
+            mount_policy = ?request.mount_policy,
+            job_package_path = %request.job_package_path.display(),
+            script_path = %script_path.display(),
+            rootfs_path = %rootfs_path.display(),
+            "Building proot command"
+        );
+        tracing::info!(
+            command = %request.redactor().redact(&format!("{:?}", cmd.as_std())),
+            "Full proot command"
+        );
+
+        Ok(cmd)
+    }
+
+    async fn configure_host_path_mounts(cmd: &mut TokioCommand, rootfs_path: &Path) -> Result<()> {
+        let root = Path::new("/");
+        let host_dirs: Vec<std::path::PathBuf> = tokio::task::spawn_blocking(move || {
+            let mut dirs = Vec::new();
+            if let Ok(entries) = std::fs::read_dir(root) {
+                for entry in entries.flatten() {
+                    let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+                    let name = entry.file_name();
+                    if is_dir && !EXCLUDED_HOST_DIRS.contains(&name.to_string_lossy().as_ref()) {
+                        dirs.push(entry.path());
+                    }
+                }
+            }
+            dirs
+        })
+        .await
+        .unwrap_or_default();
+
+        for dir_path in &host_dirs {
+            cmd.arg("-b").arg(dir_path);
+        }
+
+        if Path::new("/nix/store").exists() {
+            cmd.arg("-b").arg("/nix/store");
+            let image_store = rootfs_path.join("nix/store");
+            if image_store.exists() {
+                let mut entries = tokio::fs::read_dir(&image_store)
+                    .await
+                    .io_ctx("read_dir", &image_store)?;
+                while let Some(entry) = entries
+                    .next_entry()
+                    .await
+                    .io_ctx("read_dir", &image_store)?
+                {
+                    let target = Path::new("/nix/store").join(entry.file_name());
+                    cmd.arg("-b").arg(bind(&entry.path(), &target));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
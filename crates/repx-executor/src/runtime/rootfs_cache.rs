@@ -0,0 +1,143 @@
+use crate::error::{IoContext, Result};
+use nix::fcntl::{Flock, FlockArg};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub(crate) const IN_USE_LOCK: &str = ".in-use.lock";
+pub(crate) const SUCCESS_MARKER: &str = "SUCCESS";
+const EVICTABLE_ENTRIES: &[&str] = &[
+    SUCCESS_MARKER,
+    "rootfs",
+    "image",
+    ".rootfs_staging",
+    ".image_staging",
+    ".layers_staging",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RootfsPruneReport {
+    pub removed: Vec<String>,
+    pub in_use: Vec<String>,
+    pub freed_bytes: u64,
+    pub remaining_bytes: u64,
+}
+
+struct CachedRootfs {
+    image_hash: String,
+    dir: PathBuf,
+    size: u64,
+    last_used: SystemTime,
+}
+
+pub(crate) fn touch_last_used(image_dir: &Path) {
+    let marker = image_dir.join(SUCCESS_MARKER);
+    let touched = fs::File::options()
+        .write(true)
+        .open(&marker)
+        .and_then(|f| f.set_modified(SystemTime::now()));
+    if let Err(e) = touched {
+        tracing::debug!("Failed to update last-used time of {:?}: {}", marker, e);
+    }
+}
+
+pub fn prune_rootfs_cache(
+    images_cache_dir: &Path,
+    max_bytes: u64,
+    keep: Option<&str>,
+    dry_run: bool,
+) -> Result<RootfsPruneReport> {
+    let mut entries = list_cached_rootfs(images_cache_dir)?;
+    entries.sort_by_key(|e| e.last_used);
+
+    let mut report = RootfsPruneReport {
+        remaining_bytes: entries.iter().map(|e| e.size).sum(),
+        ..Default::default()
+    };
+
+    for entry in entries {
+        if report.remaining_bytes <= max_bytes {
+            break;
+        }
+        if keep == Some(entry.image_hash.as_str()) {
+            continue;
+        }
+        let lock_path = entry.dir.join(IN_USE_LOCK);
+        let lock_file = fs::File::create(&lock_path).io_ctx("create", &lock_path)?;
+        let Ok(_lock) = Flock::lock(lock_file, FlockArg::LockExclusiveNonblock) else {
+            report.in_use.push(entry.image_hash);
+            continue;
+        };
+        if !dry_run {
+            evict(&entry.dir)?;
+        }
+        tracing::info!(
+            "Evicted rootfs {} ({} bytes) from {:?}",
+            entry.image_hash,
+            entry.size,
+            images_cache_dir
+        );
+        report.freed_bytes += entry.size;
+        report.remaining_bytes -= entry.size;
+        report.removed.push(entry.image_hash);
+    }
+    Ok(report)
+}
+
+fn list_cached_rootfs(images_cache_dir: &Path) -> Result<Vec<CachedRootfs>> {
+    let read_dir = match fs::read_dir(images_cache_dir) {
+        Ok(rd) => rd,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).io_ctx("read_dir", images_cache_dir),
+    };
+    let mut entries = Vec::new();
+    for dir_entry in read_dir.flatten() {
+        let dir = dir_entry.path();
+        let Ok(marker) = fs::metadata(dir.join(SUCCESS_MARKER)) else {
+            continue;
+        };
+        entries.push(CachedRootfs {
+            image_hash: dir_entry.file_name().to_string_lossy().into_owned(),
+            size: dir_size(&dir),
+            last_used: marker.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            dir,
+        });
+    }
+    Ok(entries)
+}
+
+fn evict(image_dir: &Path) -> Result<()> {
+    for name in EVICTABLE_ENTRIES {
+        let path = image_dir.join(name);
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            repx_core::fs_utils::force_remove_dir(&path).io_ctx("remove_dir_all", &path)?;
+        } else {
+            fs::remove_file(&path).io_ctx("remove_file", &path)?;
+        }
+    }
+    for sidecar in fs::read_dir(image_dir).into_iter().flatten().flatten() {
+        let name = sidecar.file_name();
+        if name.to_string_lossy().ends_with(".repx-cache.json") {
+            let _ = fs::remove_file(sidecar.path());
+        }
+    }
+    Ok(())
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| dir_size(&entry.path()))
+        .sum()
+}
@@ -0,0 +1,43 @@
+use crate::error::{ExecutorError, Result};
+use repx_core::model::SeccompProfile;
+use seccompiler::{BpfProgram, TargetArch};
+
+pub const DEFAULT_SECCOMP_PROFILE: &str = include_str!("seccomp_default.json");
+
+pub fn compile_seccomp_profile(profile: &SeccompProfile) -> Result<BpfProgram> {
+    let invalid = |detail: String| ExecutorError::InvalidSeccompProfile {
+        profile: profile.to_string(),
+        detail,
+    };
+    let json = match profile {
+        SeccompProfile::Default => DEFAULT_SECCOMP_PROFILE.to_string(),
+        SeccompProfile::File(path) => std::fs::read_to_string(path)
+            .map_err(|e| invalid(format!("cannot read profile: {}", e)))?,
+    };
+    let arch = TargetArch::try_from(std::env::consts::ARCH)
+        .map_err(|e| invalid(format!("unsupported architecture: {}", e)))?;
+    let mut filters = seccompiler::compile_from_json(json.as_bytes(), arch)
+        .map_err(|e| invalid(e.to_string()))?;
+    if filters.len() != 1 {
+        return Err(invalid(format!(
+            "expected exactly one filter, found {}",
+            filters.len()
+        )));
+    }
+    let (_, program) = filters
+        .drain()
+        .next()
+        .ok_or_else(|| invalid("profile has no filter".to_string()))?;
+    Ok(program)
+}
+
+pub(crate) fn program_bytes(program: &BpfProgram) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(program.len() * 8);
+    for insn in program {
+        bytes.extend_from_slice(&insn.code.to_ne_bytes());
+        bytes.push(insn.jt);
+        bytes.push(insn.jf);
+        bytes.extend_from_slice(&insn.k.to_ne_bytes());
+    }
+    bytes
+}
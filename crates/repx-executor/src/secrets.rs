@@ -0,0 +1,57 @@
+use std::borrow::Cow;
+use std::fmt;
+
+pub const SECRETS_DIR_ENV: &str = "REPX_SECRETS_DIR";
+const REDACTED: &str = "***";
+
+#[derive(Clone)]
+pub struct Secret {
+    pub name: String,
+    pub value: String,
+    pub mount: bool,
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Secret")
+            .field("name", &self.name)
+            .field("value", &REDACTED)
+            .field("mount", &self.mount)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    needles: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new(secrets: &[Secret]) -> Self {
+        let mut needles: Vec<String> = secrets
+            .iter()
+            .flat_map(|secret| {
+                std::iter::once(secret.value.as_str()).chain(secret.value.lines().map(str::trim))
+            })
+            .filter(|needle| !needle.trim().is_empty())
+            .map(str::to_string)
+            .collect();
+        needles.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        needles.dedup();
+        Self { needles }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.needles.is_empty()
+    }
+
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for needle in &self.needles {
+            if text.contains(needle.as_str()) {
+                text = Cow::Owned(text.replace(needle.as_str(), REDACTED));
+            }
+        }
+        text
+    }
+}
@@ -1,9 +1,20 @@
 use std::fmt;
 
 pub const ALLOWED_SYSTEM_BINARIES: &[&str] = &[
-    "docker", "podman", "sbatch", "squeue", "sinfo", "sacct", "scancel",
+    "docker", "podman", "sbatch", "squeue", "sinfo", "sacct", "scancel", "criu",
 ];
 
+pub(crate) fn shell_quote(s: &str) -> String {
+    let safe = !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if safe {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
 pub fn is_binary_allowed(binary_name: &str) -> bool {
     ALLOWED_SYSTEM_BINARIES.contains(&binary_name)
 }
@@ -1,11 +1,14 @@
 #![allow(clippy::expect_used)]
 
-use repx_core::model::{JobId, MountPolicy};
+use repx_core::model::{DataMount, FailureClass, JobId, MountPolicy, NetworkMode, SeccompProfile};
 use repx_executor::{
-    CancellationToken, ExecutionRequest, Executor, ExecutorError, ImageTag, Runtime,
+    checkpoints_dir, compile_seccomp_profile, prune_rootfs_cache, CancellationToken,
+    CheckpointEngine, CheckpointMetadata, ContainerRuntime, ExecutionRequest, Executor,
+    ExecutorError, Hook, ImageTag, LayerCompression, Redactor, ResourceLimits, Runtime,
+    RuntimeContext, Secret, TimeLimit,
 };
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tempfile::tempdir;
 
 fn create_test_request(base_path: PathBuf) -> ExecutionRequest {
@@ -21,8 +24,29 @@ fn create_test_request(base_path: PathBuf) -> ExecutionRequest {
         repx_out_dir: base_path.join("outputs/repx"),
         host_tools_bin_dir: None,
         mount_policy: MountPolicy::Isolated,
+        data_mounts: vec![],
+        network: None,
+        seccomp: None,
+        pre_run: None,
+        post_run: None,
+        image_cache_limit: None,
+        min_disk: None,
         inputs_data: None,
         parameters_data: None,
+        interpreter: None,
+        arg_template: Vec::new(),
+        resource_limits: None,
+        time_limit: None,
+        max_retries: 0,
+        retry_backoff: std::time::Duration::ZERO,
+        retry_exit_codes: Vec::new(),
+        failure_rules: Vec::new(),
+        checkpoint_interval: None,
+        heartbeat_interval: None,
+        structured_logs: false,
+        env: Default::default(),
+        env_passthrough: Vec::new(),
+        secrets: Vec::new(),
     }
 }
 
@@ -42,8 +66,29 @@ fn create_test_request_with_host_tools(
         repx_out_dir: base_path.join("outputs/repx"),
         host_tools_bin_dir: Some(host_tools),
         mount_policy: MountPolicy::Isolated,
+        data_mounts: vec![],
+        network: None,
+        seccomp: None,
+        pre_run: None,
+        post_run: None,
+        image_cache_limit: None,
+        min_disk: None,
         inputs_data: None,
         parameters_data: None,
+        interpreter: None,
+        arg_template: Vec::new(),
+        resource_limits: None,
+        time_limit: None,
+        max_retries: 0,
+        retry_backoff: std::time::Duration::ZERO,
+        retry_exit_codes: Vec::new(),
+        failure_rules: Vec::new(),
+        checkpoint_interval: None,
+        heartbeat_interval: None,
+        structured_logs: false,
+        env: Default::default(),
+        env_passthrough: Vec::new(),
+        secrets: Vec::new(),
     }
 }
 
@@ -249,6 +294,45 @@ fn test_build_native_command_basic() {
     assert_eq!(collected_args.len(), 2);
 }
 
+#[tokio::test]
+async fn test_explain_renders_command_with_quoted_env_and_redacted_secrets() {
+    let temp = tempdir().expect("tempdir creation must succeed");
+    let mut request = create_test_request(temp.path().to_path_buf());
+    request
+        .env
+        .insert("GREETING".to_string(), "hello world".to_string());
+    request.secrets = vec![Secret {
+        name: "API_TOKEN".to_string(),
+        value: "tok-123".to_string(),
+        mount: false,
+    }];
+    assert!(
+        request
+            .job_env()
+            .iter()
+            .all(|(name, _)| name != "API_TOKEN"),
+        "secrets must not reach command-line env flags"
+    );
+    let executor = Executor::new(request);
+
+    let explained = executor
+        .explain(
+            &PathBuf::from("/path/to/script.sh"),
+            &["out dir".to_string(), "inputs.json".to_string()],
+        )
+        .await
+        .expect("explain must succeed");
+
+    assert!(
+        explained.contains("GREETING='hello world'"),
+        "{}",
+        explained
+    );
+    assert!(explained.contains("API_TOKEN=***"), "{}", explained);
+    assert!(!explained.contains("tok-123"));
+    assert!(explained.contains("/path/to/script.sh \\\n    'out dir' \\\n    inputs.json"));
+}
+
 #[test]
 fn test_build_native_command_with_host_tools() {
     let temp = tempdir().expect("tempdir creation must succeed");
@@ -319,6 +403,7 @@ fn test_executor_error_script_failed_display() {
         script: "/path/to/script.sh".to_string(),
         code: 127,
         stderr: "command not found".to_string(),
+        oom: None,
     };
     let display = format!("{}", err);
     assert!(display.contains("script.sh"));
@@ -383,6 +468,318 @@ async fn test_ensure_bwrap_rootfs_extracted_from_directory() {
         .exists());
 }
 
+#[tokio::test]
+async fn test_build_command_for_script_proot_runtime() {
+    let temp = tempdir().expect("tempdir creation must succeed");
+    let base_path = temp.path().to_path_buf();
+    let host_tools = base_path.join("host-tools/bin");
+    fs::create_dir_all(&host_tools).expect("dir creation must succeed");
+    fs::write(host_tools.join("proot"), "#!/bin/sh\nexit 0").expect("file write must succeed");
+
+    let image_dir = base_path.join("cache/images/v1");
+    let rootfs = image_dir.join("rootfs");
+    fs::create_dir_all(&rootfs).expect("dir creation must succeed");
+    fs::write(image_dir.join("SUCCESS"), "").expect("file write must succeed");
+
+    let mut request = create_test_request_with_host_tools(base_path.clone(), host_tools.clone());
+    request.runtime = Runtime::Proot {
+        image_tag: ImageTag::parse("my-image:v1").expect("valid image tag"),
+    };
+    request.env = [("SEED".to_string(), "7".to_string())].into();
+    let executor = Executor::new(request);
+
+    let script_path = PathBuf::from("/test/script.sh");
+    let cmd = executor
+        .build_command_for_script(&script_path, &["--flag".to_string()])
+        .await
+        .expect("proot command build must succeed");
+    let std_cmd = cmd.as_std();
+    assert_eq!(std_cmd.get_program(), host_tools.join("proot").as_os_str());
+    assert!(std_cmd
+        .get_envs()
+        .any(|(k, v)| k == "SEED" && v == Some(std::ffi::OsStr::new("7"))));
+
+    let args: Vec<_> = std_cmd.get_args().collect();
+    let root_flag = args
+        .iter()
+        .position(|a| *a == "-r")
+        .expect("rootfs flag must be present");
+    assert_eq!(args[root_flag + 1], rootfs.as_os_str());
+    assert_eq!(args[args.len() - 2], "/test/script.sh");
+    assert_eq!(args[args.len() - 1], "--flag");
+}
+
+#[tokio::test]
+async fn test_build_command_passes_resource_limits_to_container_engine() {
+    let temp = tempdir().expect("tempdir creation must succeed");
+    let mut request = create_test_request(temp.path().to_path_buf());
+    request.runtime = Runtime::Docker {
+        image_tag: ImageTag::parse("my-image:v1").expect("valid image tag"),
+    };
+    request.resource_limits = ResourceLimits::new(Some(2 * 1024 * 1024 * 1024), Some(2));
+
+    let ctx = RuntimeContext::new(&request);
+    let (cmd, _temps) = ContainerRuntime::build_command(
+        &ctx,
+        &request.runtime,
+        &PathBuf::from("/test/script.sh"),
+        &[],
+    )
+    .await
+    .expect("docker command build must succeed");
+    let args: Vec<_> = cmd.as_std().get_args().collect();
+    assert!(args.iter().any(|a| *a == "--memory=2147483648"));
+    assert!(args.iter().any(|a| *a == "--cpus=2"));
+}
+
+#[cfg(unix)]
+async fn bwrap_args_for(configure: impl FnOnce(&mut ExecutionRequest)) -> Vec<std::ffi::OsString> {
+    let temp = tempdir().expect("tempdir creation must succeed");
+    let base_path = temp.path().to_path_buf();
+    let host_tools = base_path.join("host-tools/bin");
+    fs::create_dir_all(&host_tools).expect("dir creation must succeed");
+    write_script(&host_tools, "bwrap", "exit 0");
+
+    let image_dir = base_path.join("cache/images/v1");
+    fs::create_dir_all(image_dir.join("rootfs")).expect("dir creation must succeed");
+    fs::write(image_dir.join("SUCCESS"), "").expect("file write must succeed");
+
+    let mut request = create_test_request_with_host_tools(base_path, host_tools);
+    request.runtime = Runtime::Bwrap {
+        image_tag: ImageTag::parse("my-image:v1").expect("valid image tag"),
+    };
+    configure(&mut request);
+    let executor = Executor::new(request);
+
+    let cmd = executor
+        .build_command_for_script(&PathBuf::from("/test/script.sh"), &[])
+        .await
+        .expect("bwrap command build must succeed");
+    cmd.as_std().get_args().map(|a| a.to_os_string()).collect()
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_build_bwrap_command_network_modes() {
+    let offline = bwrap_args_for(|r| {
+        r.mount_policy = MountPolicy::AllHostPaths;
+        r.network = Some(NetworkMode::None);
+    })
+    .await;
+    assert!(offline.iter().any(|a| a == "--unshare-net"));
+
+    let default = bwrap_args_for(|r| r.mount_policy = MountPolicy::AllHostPaths).await;
+    assert!(!default.iter().any(|a| a == "--unshare-net"));
+
+    let shared = bwrap_args_for(|r| {
+        r.mount_policy = MountPolicy::Isolated;
+        r.network = Some(NetworkMode::Host);
+    })
+    .await;
+    assert!(shared.iter().any(|a| a == "--unshare-all"));
+    assert!(shared.iter().any(|a| a == "--share-net"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_build_bwrap_command_overlays_image_nix_per_job() {
+    let args = bwrap_args_for(|r| {
+        r.mount_policy = MountPolicy::AllHostPaths;
+        let image_store = r.base_path.join("cache/images/v1/rootfs/nix/store");
+        fs::create_dir_all(image_store.join("abc-pkg")).expect("dir creation must succeed");
+    })
+    .await;
+
+    let target = if Path::new("/nix/store").exists() {
+        "/nix/store"
+    } else {
+        "/nix"
+    };
+    let pos = args
+        .iter()
+        .position(|a| a == "--tmp-overlay")
+        .expect("--tmp-overlay must be passed");
+    assert_eq!(args[pos + 1], target);
+    assert!(!args.windows(3).any(|w| w[0] == "--bind" && w[2] == target));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_build_bwrap_command_with_seccomp_profile() {
+    let without = bwrap_args_for(|_| {}).await;
+    assert!(!without.iter().any(|a| a == "--seccomp"));
+
+    let with = bwrap_args_for(|r| r.seccomp = Some(SeccompProfile::Default)).await;
+    let pos = with
+        .iter()
+        .position(|a| a == "--seccomp")
+        .expect("--seccomp must be passed");
+    let fd = with[pos + 1]
+        .to_str()
+        .and_then(|fd| fd.parse::<i32>().ok())
+        .expect("--seccomp takes a file descriptor");
+    let program = fs::read(format!("/proc/self/fd/{}", fd)).expect("read seccomp program");
+    assert!(!program.is_empty());
+    assert_eq!(program.len() % 8, 0);
+    assert!(pos < with.iter().position(|a| a == "--").expect("separator"));
+}
+
+#[test]
+fn test_compile_seccomp_profile_default_and_custom() {
+    let default = compile_seccomp_profile(&SeccompProfile::Default).expect("default compiles");
+    assert!(!default.is_empty());
+
+    let temp = tempdir().expect("tempdir");
+    let custom = temp.path().join("custom.json");
+    fs::write(
+        &custom,
+        r#"{"strict": {"mismatch_action": "allow", "match_action": "kill_process", "filter": [{"syscall": "ptrace"}]}}"#,
+    )
+    .expect("write profile");
+    let program =
+        compile_seccomp_profile(&SeccompProfile::File(custom.clone())).expect("custom compiles");
+    assert!(!program.is_empty());
+
+    fs::write(
+        &custom,
+        r#"{"bad": {"mismatch_action": "allow", "match_action": "trap", "filter": [{"syscall": "no_such_syscall"}]}}"#,
+    )
+    .expect("write profile");
+    assert!(matches!(
+        compile_seccomp_profile(&SeccompProfile::File(custom)),
+        Err(ExecutorError::InvalidSeccompProfile { .. })
+    ));
+    assert!(matches!(
+        compile_seccomp_profile(&SeccompProfile::File(temp.path().join("missing.json"))),
+        Err(ExecutorError::InvalidSeccompProfile { .. })
+    ));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_interactive_returns_exit_code_with_job_env() {
+    let temp = tempdir().expect("tempdir creation must succeed");
+    let mut request = create_test_request(temp.path().to_path_buf());
+    request.env = [("REPX_DEBUG_VALUE".to_string(), "from-job".to_string())]
+        .into_iter()
+        .collect();
+    let marker = temp.path().join("marker");
+    let mut executor = Executor::new(request);
+
+    let code = executor
+        .execute_interactive(
+            Path::new("/bin/sh"),
+            &[
+                "-c".to_string(),
+                format!(
+                    "echo \"$REPX_DEBUG_VALUE\" > '{}'; exit 3",
+                    marker.display()
+                ),
+            ],
+        )
+        .await
+        .expect("interactive session must start");
+    assert_eq!(code, 3);
+    assert_eq!(
+        fs::read_to_string(&marker).expect("marker must be written"),
+        "from-job\n"
+    );
+}
+
+#[tokio::test]
+async fn test_native_runtime_rejects_seccomp_profile() {
+    let temp = tempdir().expect("tempdir creation must succeed");
+    let mut request = create_test_request(temp.path().to_path_buf());
+    request.seccomp = Some(SeccompProfile::Default);
+    let executor = Executor::new(request);
+
+    let result = executor
+        .build_command_for_script(&PathBuf::from("/test/script.sh"), &[])
+        .await;
+    assert!(matches!(
+        result,
+        Err(ExecutorError::SeccompUnsupported {
+            runtime: "native",
+            ..
+        })
+    ));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_build_bwrap_command_binds_data_mounts_read_only() {
+    let data = tempdir().expect("tempdir creation must succeed");
+    let host_path = data.path().to_path_buf();
+    let mount: DataMount = format!("{}:/data/imagenet", host_path.display())
+        .parse()
+        .expect("valid data mount");
+
+    let args = bwrap_args_for(|r| r.data_mounts = vec![mount]).await;
+    let pos = args
+        .windows(3)
+        .position(|w| w[0] == "--ro-bind" && w[1] == host_path.as_os_str())
+        .expect("data mount must be bound read-only");
+    assert_eq!(args[pos + 2], "/data/imagenet");
+    assert!(pos < args.iter().position(|a| a == "--").expect("separator"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_native_runtime_links_data_mounts() {
+    let temp = tempdir().expect("tempdir creation must succeed");
+    let host_path = temp.path().join("datasets/cifar");
+    fs::create_dir_all(&host_path).expect("dir creation must succeed");
+    let link = temp.path().join("mnt/cifar");
+
+    let mut request = create_test_request(temp.path().to_path_buf());
+    request.data_mounts = vec![DataMount {
+        host_path: host_path.clone(),
+        mount_path: Some(link.clone()),
+    }];
+    let executor = Executor::new(request.clone());
+    executor
+        .build_command_for_script(&PathBuf::from("/test/script.sh"), &[])
+        .await
+        .expect("native command build must succeed");
+    assert_eq!(fs::read_link(&link).expect("symlink created"), host_path);
+
+    Executor::new(request.clone())
+        .build_command_for_script(&PathBuf::from("/test/script.sh"), &[])
+        .await
+        .expect("existing link to the same path is reused");
+
+    request.data_mounts = vec![DataMount {
+        host_path: temp.path().join("missing"),
+        mount_path: None,
+    }];
+    let result = Executor::new(request)
+        .build_command_for_script(&PathBuf::from("/test/script.sh"), &[])
+        .await;
+    assert!(matches!(
+        result,
+        Err(ExecutorError::DataMountUnavailable { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_native_runtime_rejects_network_isolation() {
+    let temp = tempdir().expect("tempdir creation must succeed");
+    let mut request = create_test_request(temp.path().to_path_buf());
+    request.network = Some(NetworkMode::None);
+    let executor = Executor::new(request);
+
+    let result = executor
+        .build_command_for_script(&PathBuf::from("/test/script.sh"), &[])
+        .await;
+    assert!(matches!(
+        result,
+        Err(ExecutorError::NetworkIsolationUnsupported {
+            runtime: "native",
+            ..
+        })
+    ));
+}
+
 #[tokio::test]
 async fn test_build_command_for_script_native_runtime() {
     let temp = tempdir().expect("tempdir creation must succeed");
@@ -498,8 +895,29 @@ fn create_runnable_request(temp: &tempfile::TempDir) -> (ExecutionRequest, PathB
         repx_out_dir: repx_out,
         host_tools_bin_dir: None,
         mount_policy: MountPolicy::Isolated,
+        data_mounts: vec![],
+        network: None,
+        seccomp: None,
+        pre_run: None,
+        post_run: None,
+        image_cache_limit: None,
+        min_disk: None,
         inputs_data: None,
         parameters_data: None,
+        interpreter: None,
+        arg_template: Vec::new(),
+        resource_limits: None,
+        time_limit: None,
+        max_retries: 0,
+        retry_backoff: std::time::Duration::ZERO,
+        retry_exit_codes: Vec::new(),
+        failure_rules: Vec::new(),
+        checkpoint_interval: None,
+        heartbeat_interval: None,
+        structured_logs: false,
+        env: Default::default(),
+        env_passthrough: Vec::new(),
+        secrets: Vec::new(),
     };
     (request, base)
 }
@@ -541,6 +959,106 @@ async fn test_execute_script_cancelled_kills_child() {
     );
 }
 
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_runs_hooks_around_main_script() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    let trace = base.join("trace.txt");
+    let append = |step: &str| format!("echo {} >> '{}'", step, trace.display());
+    request.pre_run = Some(write_script(&base, "pre.sh", &append("pre")));
+    request.post_run = Some(write_script(&base, "post.sh", &append("post")));
+    let script = write_script(&base, "main.sh", &append("main"));
+    let repx_out = request.repx_out_dir.clone();
+
+    let mut executor = Executor::new(request.clone());
+    executor
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await
+        .expect("job with hooks must succeed");
+    assert_eq!(
+        fs::read_to_string(&trace).expect("read trace"),
+        "pre\nmain\npost\n"
+    );
+    assert!(repx_out.join(Hook::PreRun.log_file()).exists());
+
+    fs::remove_file(&trace).expect("remove trace");
+    request.pre_run = Some(write_script(
+        &base,
+        "pre.sh",
+        "echo staging failed >&2; exit 7",
+    ));
+    let result = Executor::new(request)
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await;
+    match result {
+        Err(ExecutorError::HookFailed {
+            hook: Hook::PreRun,
+            code: 7,
+            log_path,
+            ..
+        }) => assert_eq!(
+            fs::read_to_string(log_path).expect("read hook log"),
+            "staging failed\n"
+        ),
+        other => panic!("expected pre_run HookFailed, got {:?}", other),
+    }
+    assert!(
+        !trace.exists(),
+        "main script must not run after a failed hook"
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_writes_heartbeat_while_running() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    request.heartbeat_interval = Some(std::time::Duration::from_millis(50));
+    let heartbeat = request
+        .repx_out_dir
+        .join(repx_core::store::heartbeat::HEARTBEAT_FILE);
+    let script = write_script(
+        &base,
+        "beat.sh",
+        &format!(
+            "for i in $(seq 50); do [ -f '{}' ] && exit 0; sleep 0.1; done; exit 1",
+            heartbeat.display()
+        ),
+    );
+
+    Executor::new(request)
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await
+        .expect("heartbeat must be written while the script runs");
+    let stamp = fs::read_to_string(&heartbeat).expect("read heartbeat");
+    chrono::DateTime::parse_from_rfc3339(stamp.trim()).expect("heartbeat is an RFC3339 timestamp");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_fails_fast_without_enough_disk_space() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    let marker = base.join("ran");
+    request.min_disk = Some(u64::MAX / 2);
+    let script = write_script(&base, "touch.sh", &format!("touch '{}'", marker.display()));
+
+    let err = Executor::new(request)
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await
+        .expect_err("an impossible disk requirement must fail");
+    match err {
+        ExecutorError::InsufficientDiskSpace {
+            available,
+            required,
+            ..
+        } => assert!(available < required),
+        other => panic!("expected InsufficientDiskSpace, got {:?}", other),
+    }
+    assert!(!marker.exists(), "script must not run without enough disk");
+}
+
 #[cfg(unix)]
 #[tokio::test]
 async fn test_execute_script_pre_cancelled_token_returns_immediately() {
@@ -612,6 +1130,505 @@ async fn test_execute_script_failure_not_masked_by_token() {
     }
 }
 
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_time_limit_terminates_child() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    request.time_limit = Some(TimeLimit {
+        limit: std::time::Duration::from_millis(200),
+        grace: std::time::Duration::from_secs(30),
+    });
+    let script = write_script(&base, "slow.sh", "sleep 300");
+    let mut executor = Executor::new(request);
+
+    let start = std::time::Instant::now();
+    let result = executor
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await;
+
+    assert!(
+        matches!(result, Err(ExecutorError::TimedOut { .. })),
+        "expected TimedOut, got: {:?}",
+        result,
+    );
+    assert!(
+        start.elapsed() < std::time::Duration::from_secs(10),
+        "SIGTERM should stop the job without waiting for the grace period",
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_time_limit_kills_after_grace() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    request.time_limit = Some(TimeLimit {
+        limit: std::time::Duration::from_millis(200),
+        grace: std::time::Duration::from_millis(500),
+    });
+    let script = write_script(&base, "stubborn.sh", "trap '' TERM\nsleep 300");
+    let mut executor = Executor::new(request);
+
+    let start = std::time::Instant::now();
+    let result = executor
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await;
+    let elapsed = start.elapsed();
+
+    assert!(
+        matches!(result, Err(ExecutorError::TimedOut { .. })),
+        "expected TimedOut, got: {:?}",
+        result,
+    );
+    assert!(elapsed >= std::time::Duration::from_millis(700));
+    assert!(elapsed < std::time::Duration::from_secs(10));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_retries_and_keeps_attempt_logs() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    request.max_retries = 2;
+    request.retry_exit_codes = vec![75];
+    let repx_out = request.repx_out_dir.clone();
+    let script = write_script(
+        &base,
+        "flaky.sh",
+        "if [ -f \"$0.ran\" ]; then echo second; exit 0; fi\ntouch \"$0.ran\"\necho first\nexit 75",
+    );
+    let mut executor = Executor::new(request);
+
+    let result = executor
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await;
+
+    assert!(result.is_ok(), "retry should succeed: {:?}", result.err());
+    let first = fs::read_to_string(repx_out.join("stdout.log.1")).expect("first attempt log");
+    assert_eq!(first.trim(), "first");
+    let second = fs::read_to_string(repx_out.join("stdout.log")).expect("final attempt log");
+    assert_eq!(second.trim(), "second");
+    assert!(!repx_out.join("stdout.log.2").exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_does_not_retry_other_exit_codes() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    request.max_retries = 3;
+    request.retry_exit_codes = vec![75];
+    let repx_out = request.repx_out_dir.clone();
+    let script = write_script(&base, "broken.sh", "exit 1");
+    let mut executor = Executor::new(request);
+
+    let result = executor
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ExecutorError::ScriptFailed { code: 1, .. })
+    ));
+    assert!(!repx_out.join("stdout.log.1").exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_retries_by_failure_class() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    request.max_retries = 3;
+    request.failure_rules = vec![
+        "infrastructure::Stale file handle"
+            .parse()
+            .expect("valid rule"),
+        "user-error".parse().expect("valid rule"),
+    ];
+    let repx_out = request.repx_out_dir.clone();
+    let script = write_script(
+        &base,
+        "classified.sh",
+        "if [ -f \"$0.ran\" ]; then echo 'KeyError: lr' >&2; exit 1; fi\ntouch \"$0.ran\"\necho 'Stale file handle' >&2\nexit 1",
+    );
+    let mut executor = Executor::new(request.clone());
+
+    let result = executor
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await;
+
+    let err = result.expect_err("user error must not be retried");
+    assert_eq!(
+        err.failure_class(&request.failure_rules),
+        Some(FailureClass::UserError)
+    );
+    assert_eq!(err.exit_code(), Some(1));
+    assert!(repx_out.join("stderr.log.1").exists());
+    assert!(!repx_out.join("stderr.log.2").exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_structured_logs() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    request.structured_logs = true;
+    let repx_out = request.repx_out_dir.clone();
+    let script = write_script(&base, "chatty.sh", "echo out-1\necho err-1 >&2\necho out-2");
+    let mut executor = Executor::new(request);
+
+    executor
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await
+        .expect("script must succeed");
+
+    let stdout = fs::read_to_string(repx_out.join("stdout.log")).expect("stdout.log");
+    assert_eq!(stdout, "out-1\nout-2\n");
+    let stderr = fs::read_to_string(repx_out.join("stderr.log")).expect("stderr.log");
+    assert_eq!(stderr, "err-1\n");
+
+    let records: Vec<serde_json::Value> = fs::read_to_string(repx_out.join("logs.jsonl"))
+        .expect("logs.jsonl")
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("each record is JSON"))
+        .collect();
+    assert_eq!(records.len(), 3);
+    assert!(records.iter().all(|r| r["ts"].is_string()));
+    let stdout_lines: Vec<&str> = records
+        .iter()
+        .filter(|r| r["stream"] == "stdout")
+        .filter_map(|r| r["line"].as_str())
+        .collect();
+    assert_eq!(stdout_lines, vec!["out-1", "out-2"]);
+    assert!(records
+        .iter()
+        .any(|r| r["stream"] == "stderr" && r["line"] == "err-1"));
+}
+
+#[test]
+fn test_job_env_merges_passthrough_and_declared() {
+    let temp = tempdir().expect("tempdir");
+    let mut request = create_test_request(temp.path().to_path_buf());
+    request.env_passthrough = vec!["HOME".to_string(), "REPX_TEST_UNSET_VAR".to_string()];
+    request.env = [
+        ("HOME".to_string(), "/job-home".to_string()),
+        ("SEED".to_string(), "42".to_string()),
+    ]
+    .into();
+
+    assert_eq!(
+        request.job_env(),
+        vec![
+            ("HOME".to_string(), "/job-home".to_string()),
+            ("SEED".to_string(), "42".to_string()),
+        ]
+    );
+
+    request.env.clear();
+    let env = request.job_env();
+    assert_eq!(env.len(), usize::from(std::env::var("HOME").is_ok()));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_receives_declared_env() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    request.env = [("REPX_GREETING".to_string(), "hello".to_string())].into();
+    let repx_out = request.repx_out_dir.clone();
+    let script = write_script(&base, "env.sh", "echo \"$REPX_GREETING\"");
+    let mut executor = Executor::new(request);
+
+    executor
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await
+        .expect("script must succeed");
+
+    let stdout = fs::read_to_string(repx_out.join("stdout.log")).expect("stdout.log");
+    assert_eq!(stdout.trim(), "hello");
+}
+
+#[test]
+fn test_redactor_masks_secret_values_and_lines() {
+    let secrets = vec![
+        Secret {
+            name: "API_TOKEN".to_string(),
+            value: "tok-123".to_string(),
+            mount: false,
+        },
+        Secret {
+            name: "KEY".to_string(),
+            value: "line-one\nline-two\n".to_string(),
+            mount: true,
+        },
+    ];
+    let redactor = Redactor::new(&secrets);
+
+    assert_eq!(redactor.redact("auth tok-123 ok"), "auth *** ok");
+    assert_eq!(redactor.redact("got line-two"), "got ***");
+    assert_eq!(redactor.redact("nothing here"), "nothing here");
+    assert!(!format!("{:?}", secrets).contains("tok-123"));
+    assert!(Redactor::new(&[]).is_empty());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_exposes_and_redacts_secrets() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    request.secrets = vec![
+        Secret {
+            name: "API_TOKEN".to_string(),
+            value: "s3cr3t-env".to_string(),
+            mount: false,
+        },
+        Secret {
+            name: "DB_PASSWORD".to_string(),
+            value: "s3cr3t-file".to_string(),
+            mount: true,
+        },
+    ];
+    let repx_out = request.repx_out_dir.clone();
+    let script = write_script(
+        &base,
+        "secrets.sh",
+        "echo \"env=$API_TOKEN\"\n\
+         echo \"file=$(cat \"$REPX_SECRETS_DIR/DB_PASSWORD\")\"\n\
+         echo \"$API_TOKEN\" >&2\n\
+         [ -z \"$DB_PASSWORD\" ]",
+    );
+    let mut executor = Executor::new(request);
+
+    executor
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await
+        .expect("script must succeed");
+
+    let stdout = fs::read_to_string(repx_out.join("stdout.log")).expect("stdout.log");
+    let stderr = fs::read_to_string(repx_out.join("stderr.log")).expect("stderr.log");
+    assert_eq!(stdout, "env=***\nfile=***\n");
+    assert_eq!(stderr, "***\n");
+}
+
+#[cfg(unix)]
+fn make_cached_rootfs(cache: &std::path::Path, hash: &str, size: usize, age_secs: u64) {
+    let dir = cache.join(hash);
+    fs::create_dir_all(dir.join("rootfs")).expect("create rootfs");
+    fs::write(dir.join("rootfs/blob"), vec![0u8; size]).expect("write blob");
+    let marker = fs::File::create(dir.join("SUCCESS")).expect("create marker");
+    marker
+        .set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(age_secs))
+        .expect("set mtime");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_prune_rootfs_cache_evicts_least_recently_used() {
+    let temp = tempdir().expect("tempdir");
+    let cache = temp.path();
+    make_cached_rootfs(cache, "oldest", 1000, 300);
+    make_cached_rootfs(cache, "middle", 1000, 200);
+    make_cached_rootfs(cache, "newest", 1000, 100);
+
+    let report = prune_rootfs_cache(cache, 1500, None, false).expect("prune");
+
+    assert_eq!(report.removed, vec!["oldest", "middle"]);
+    assert_eq!(report.freed_bytes, 2000);
+    assert_eq!(report.remaining_bytes, 1000);
+    assert!(!cache.join("oldest/rootfs").exists());
+    assert!(!cache.join("oldest/SUCCESS").exists());
+    assert!(cache.join("newest/rootfs/blob").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_prune_rootfs_cache_skips_images_in_use() {
+    use nix::fcntl::{Flock, FlockArg};
+    let temp = tempdir().expect("tempdir");
+    let cache = temp.path();
+    make_cached_rootfs(cache, "busy", 1000, 300);
+    make_cached_rootfs(cache, "idle", 1000, 100);
+    let lock_file = fs::File::create(cache.join("busy/.in-use.lock")).expect("lock file");
+    let _lock = Flock::lock(lock_file, FlockArg::LockSharedNonblock).expect("shared lock");
+
+    let dry = prune_rootfs_cache(cache, 0, None, true).expect("dry run");
+    assert_eq!(dry.removed, vec!["idle"]);
+    assert!(cache.join("idle/rootfs/blob").exists());
+
+    let report = prune_rootfs_cache(cache, 0, None, false).expect("prune");
+    assert_eq!(report.removed, vec!["idle"]);
+    assert_eq!(report.in_use, vec!["busy"]);
+    assert!(cache.join("busy/rootfs/blob").exists());
+    assert!(!cache.join("idle/rootfs").exists());
+}
+
+#[test]
+fn test_layer_compression_detected_from_magic_bytes() {
+    let temp = tempdir().expect("tempdir");
+    let cases: [(&[u8], LayerCompression); 4] = [
+        (&[0x1f, 0x8b, 0x08, 0x00], LayerCompression::Gzip),
+        (&[0x28, 0xb5, 0x2f, 0xfd, 0x00], LayerCompression::Zstd),
+        (b"usr/bin/", LayerCompression::None),
+        (&[0x1f], LayerCompression::None),
+    ];
+    for (i, (bytes, expected)) in cases.iter().enumerate() {
+        let path = temp.path().join(format!("layer{}", i));
+        fs::write(&path, bytes).expect("write layer");
+        assert_eq!(
+            LayerCompression::detect(&path).expect("detect"),
+            *expected,
+            "layer {:?}",
+            bytes
+        );
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_ensure_bwrap_rootfs_decompresses_layers_and_extracts_in_order() {
+    let temp = tempdir().expect("tempdir");
+    let base_path = temp.path().to_path_buf();
+    let host_tools = base_path.join("host-tools/bin");
+    fs::create_dir_all(&host_tools).expect("create host tools");
+    let extract_log = base_path.join("extract.log");
+    write_script(
+        &host_tools,
+        "tar",
+        &format!(
+            "PATH=/usr/bin:/bin\ncat \"$2\" >> '{}'\necho >> '{}'\n",
+            extract_log.display(),
+            extract_log.display()
+        ),
+    );
+    write_script(
+        &host_tools,
+        "gzip",
+        "PATH=/usr/bin:/bin\nsleep 0.2\nprintf 'gzip:'\ntail -c +3 \"$2\"\n",
+    );
+    write_script(
+        &host_tools,
+        "zstd",
+        "PATH=/usr/bin:/bin\nprintf 'zstd:'\ntail -c +5 \"$2\"\n",
+    );
+
+    let image_tag_str = "layered:v2";
+    let image_dir = base_path.join("artifacts/images").join(image_tag_str);
+    fs::create_dir_all(&image_dir).expect("create image dir");
+    fs::write(
+        image_dir.join("manifest.json"),
+        r#"[{"Layers": ["a.tar", "b.tar.gz", "c.tar.zst"]}]"#,
+    )
+    .expect("write manifest");
+    fs::write(image_dir.join("a.tar"), "plain").expect("write layer");
+    fs::write(image_dir.join("b.tar.gz"), b"\x1f\x8bgz").expect("write layer");
+    fs::write(image_dir.join("c.tar.zst"), b"\x28\xb5\x2f\xfdzst").expect("write layer");
+
+    let mut request = create_test_request_with_host_tools(base_path.clone(), host_tools);
+    request.runtime = Runtime::Bwrap {
+        image_tag: ImageTag::parse(image_tag_str).expect("valid image tag"),
+    };
+    let executor = Executor::new(request);
+
+    executor
+        .ensure_bwrap_rootfs_extracted(image_tag_str)
+        .await
+        .expect("extraction must succeed");
+
+    let log = fs::read_to_string(&extract_log).expect("read extract log");
+    assert_eq!(
+        log.lines().collect::<Vec<_>>(),
+        vec!["plain", "gzip:gz", "zstd:zst"]
+    );
+    let cache_dir = base_path.join("cache/images/v2");
+    assert!(cache_dir.join("SUCCESS").exists());
+    assert!(!cache_dir.join(".layers_staging").exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_checkpointed_job_resumes_from_latest_snapshot() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    let host_tools = base.join("host-tools/bin");
+    fs::create_dir_all(&host_tools).expect("create host tools");
+    let criu_log = base.join("criu.log");
+    write_script(
+        &host_tools,
+        "criu",
+        &format!(
+            "echo \"$1\" >> '{}'\nif [ \"$1\" = dump ]; then touch \"$5/pages.img\"; else echo restored; fi\n",
+            criu_log.display()
+        ),
+    );
+    request.host_tools_bin_dir = Some(host_tools);
+    request.checkpoint_interval = Some(std::time::Duration::from_millis(100));
+    let repx_out = request.repx_out_dir.clone();
+    let ran_marker = base.join("ran");
+    let script = write_script(
+        &base,
+        "long.sh",
+        &format!("touch '{}'\nsleep 5\n", ran_marker.display()),
+    );
+
+    let cancel = CancellationToken::new();
+    let canceller = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(450)).await;
+        canceller.cancel();
+    });
+    let mut executor = Executor::new(request.clone());
+    let result = executor.execute_script(&script, &[], &cancel).await;
+    assert!(matches!(result, Err(ExecutorError::Cancelled { .. })));
+
+    let latest = CheckpointMetadata::load_latest(&repx_out)
+        .expect("read checkpoint metadata")
+        .expect("a checkpoint must have been taken");
+    assert_eq!(latest.job_id, "cancel-test-job");
+    assert_eq!(latest.engine, CheckpointEngine::Criu);
+    assert!(latest.images_dir(&repx_out).join("pages.img").exists());
+    let snapshots = fs::read_dir(checkpoints_dir(&repx_out))
+        .expect("read checkpoints dir")
+        .filter(|entry| entry.as_ref().is_ok_and(|e| e.path().is_dir()))
+        .count();
+    assert_eq!(snapshots, 1, "older snapshots must be pruned");
+
+    fs::remove_file(&ran_marker).expect("remove marker");
+    let mut executor = Executor::new(request);
+    executor
+        .execute_script(&script, &[], &CancellationToken::new())
+        .await
+        .expect("restored job must succeed");
+
+    assert!(!ran_marker.exists(), "job must not restart from scratch");
+    let stdout = fs::read_to_string(repx_out.join("stdout.log")).expect("stdout log");
+    assert!(stdout.contains("restored"));
+    let calls = fs::read_to_string(&criu_log).expect("criu log");
+    assert_eq!(calls.lines().last(), Some("restore"));
+    assert!(!checkpoints_dir(&repx_out).exists());
+}
+
+#[tokio::test]
+async fn test_checkpoint_rejects_unsupported_runtime() {
+    let temp = tempdir().expect("tempdir");
+    let mut request = create_test_request(temp.path().to_path_buf());
+    request.runtime = Runtime::Bwrap {
+        image_tag: ImageTag::parse("img:v1").expect("valid image tag"),
+    };
+    request.checkpoint_interval = Some(std::time::Duration::from_secs(60));
+    let mut executor = Executor::new(request);
+
+    let result = executor
+        .execute_script(&temp.path().join("run.sh"), &[], &CancellationToken::new())
+        .await;
+
+    assert!(
+        matches!(result, Err(ExecutorError::CheckpointUnsupported { .. })),
+        "unexpected result: {:?}",
+        result
+    );
+}
+
 #[test]
 fn test_executor_error_cancelled_display() {
     let err = ExecutorError::Cancelled {
@@ -621,3 +1638,32 @@ fn test_executor_error_cancelled_display() {
     assert!(display.contains("cancelled"));
     assert!(display.contains("my-job-123"));
 }
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_script_with_interpreter_and_arg_template() {
+    let temp = tempdir().expect("tempdir");
+    let (mut request, base) = create_runnable_request(&temp);
+    request.interpreter = Some("sh".to_string());
+    request.arg_template = vec![
+        "--out=$out".to_string(),
+        "${params}".to_string(),
+        "0.01".to_string(),
+    ];
+    let script = base.join("plain.sh");
+    fs::write(&script, "printf '%s\\n' \"$@\" > \"$0.args\"\n").expect("write script");
+    let exec_args = vec![
+        "/data/out".to_string(),
+        "/data/inputs.json".to_string(),
+        "/data/parameters.json".to_string(),
+    ];
+    let mut executor = Executor::new(request);
+
+    executor
+        .execute_script(&script, &exec_args, &CancellationToken::new())
+        .await
+        .expect("non-executable script must run through its interpreter");
+
+    let recorded = fs::read_to_string(base.join("plain.sh.args")).expect("args recorded");
+    assert_eq!(recorded, "--out=/data/out\n/data/parameters.json\n0.01\n");
+}
@@ -1,4 +1,7 @@
-use repx_executor::{extract_image_hash, is_binary_allowed, ImageTag};
+use repx_executor::{
+    extract_image_hash, is_binary_allowed, ContainerRuntime, ExecutorError, ImageTag, OomKill,
+    ResourceLimits,
+};
 
 #[test]
 fn test_is_binary_allowed_rejects_invalid() {
@@ -86,3 +89,89 @@ fn test_image_tag_parse_rejects_special_chars() {
     assert!(ImageTag::parse("image\0null").is_err());
     assert!(ImageTag::parse("image tag").is_err());
 }
+
+#[test]
+fn test_resource_limits_need_a_value() {
+    assert_eq!(ResourceLimits::new(None, None), None);
+    assert_eq!(
+        ResourceLimits::new(Some(1 << 30), None),
+        Some(ResourceLimits {
+            mem_bytes: Some(1 << 30),
+            cpus: None,
+        })
+    );
+}
+
+#[test]
+fn test_oom_kill_from_kernel_log() {
+    let log = "[ 812.113] Memory cgroup out of memory: Killed process 4242 (python) total-vm:35000000kB, anon-rss:33344716kB, file-rss:0kB\n\
+               [ 812.200] oom_reaper: reaped process 4242 (python)";
+    let oom = OomKill::from_kernel_log(log, 4242).expect("OOM kill of pid 4242");
+    assert_eq!(oom.peak_bytes, Some(33_344_716 * 1024));
+    assert!(OomKill::from_kernel_log(log, 424).is_none());
+
+    let oom = OomKill {
+        limit_bytes: Some(32 * 1024 * 1024 * 1024),
+        ..oom
+    };
+    assert_eq!(oom.to_string(), "OOMKilled (peak 31.8G of 32.0G)");
+
+    let err = ExecutorError::ScriptFailed {
+        script: "/path/to/train.sh".to_string(),
+        code: 137,
+        stderr: String::new(),
+        oom: Some(oom),
+    };
+    assert!(err
+        .to_string()
+        .contains("exit code 137: OOMKilled (peak 31.8G of 32.0G)"));
+    assert_eq!(err.summary(), "OOMKilled (peak 31.8G of 32.0G)");
+}
+
+#[test]
+fn test_has_subid_range_matches_user_name_or_uid() {
+    let subuid = "alice:100000:65536\n1001:165536:65536\n";
+    assert!(ContainerRuntime::has_subid_range(subuid, "alice", 1000));
+    assert!(ContainerRuntime::has_subid_range(subuid, "bob", 1001));
+    assert!(!ContainerRuntime::has_subid_range(subuid, "carol", 1002));
+    assert!(!ContainerRuntime::has_subid_range("", "alice", 1000));
+}
+
+#[test]
+fn test_sigkill_without_job_oom_event_is_not_oom_killed() {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    let sigkill = ExitStatus::from_raw(9);
+    let sibling_log = "[ 812.113] Memory cgroup out of memory: Killed process 5151 (python) total-vm:35000000kB, anon-rss:33344716kB, file-rss:0kB";
+    assert_eq!(
+        OomKill::attribute(sigkill, Some(1 << 30), None, Some(sibling_log), Some(4242)),
+        None
+    );
+    assert_eq!(
+        OomKill::attribute(sigkill, Some(1 << 30), None, None, Some(4242)),
+        None
+    );
+
+    let own_kill = OomKill {
+        peak_bytes: Some(1 << 30),
+        limit_bytes: None,
+    };
+    assert_eq!(
+        OomKill::attribute(sigkill, Some(1 << 30), Some(own_kill), None, Some(4242)),
+        Some(OomKill {
+            peak_bytes: Some(1 << 30),
+            limit_bytes: Some(1 << 30),
+        })
+    );
+    assert_eq!(
+        OomKill::attribute(
+            ExitStatus::from_raw(1 << 8),
+            None,
+            Some(own_kill),
+            None,
+            None
+        ),
+        None
+    );
+}
@@ -1,11 +1,82 @@
 use clap::{Args, Parser, Subcommand, ValueEnum};
-use repx_core::model::{ExecutionType, SchedulerType};
+use repx_core::config::ResourceRule;
+use repx_core::model::{
+    BranchQuota, DataMount, ExecutionType, FailureRule, Memory, NetworkMode, SchedulerType,
+    SeccompProfile, SlurmTime,
+};
 use std::path::PathBuf;
+use std::time::Duration;
+
+fn parse_env_var(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{}'", s)),
+    }
+}
+
+fn parse_size(s: &str) -> Result<u64, String> {
+    Memory::from(s.to_string())
+        .to_bytes()
+        .ok_or_else(|| format!("expected a size like 500M or 20G, got '{}'", s))
+}
+
+fn parse_age(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("expected an age like 30m, 12h or 7d, got '{}'", s);
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (amount, unit) = s.split_at(split);
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" | "" => 86400,
+        "w" => 7 * 86400,
+        _ => return Err(invalid()),
+    };
+    Ok(Duration::from_secs(amount * unit_secs))
+}
+
+fn parse_memory(s: &str) -> Result<Memory, String> {
+    let mem = Memory::from(s);
+    match mem.to_bytes() {
+        Some(_) => Ok(mem),
+        None => Err(format!("expected a size like 512M or 64G, got '{}'", s)),
+    }
+}
+
+fn parse_slurm_time(s: &str) -> Result<SlurmTime, String> {
+    let time = SlurmTime::from(s);
+    match time.to_seconds() {
+        Some(_) => Ok(time),
+        None => Err(format!(
+            "expected a time like 12:00:00 or 1-00:00:00, got '{}'",
+            s
+        )),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn is_structured(&self) -> bool {
+        !matches!(self, OutputFormat::Table)
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum StatusFilter {
     Succeeded,
     Failed,
+    TimedOut,
+    Cancelled,
+    Lost,
     Pending,
     Running,
     Queued,
@@ -17,6 +88,9 @@ impl StatusFilter {
         match self {
             StatusFilter::Succeeded => "succeeded",
             StatusFilter::Failed => "failed",
+            StatusFilter::TimedOut => "timed-out",
+            StatusFilter::Cancelled => "cancelled",
+            StatusFilter::Lost => "lost",
             StatusFilter::Pending => "pending",
             StatusFilter::Running => "running",
             StatusFilter::Queued => "queued",
@@ -59,7 +133,7 @@ pub struct Cli {
     #[arg(
         long,
         global = true,
-        help = "The scheduler to use: 'slurm' or 'local'. Overrides the target's configuration."
+        help = "The scheduler to use: 'slurm', 'sge', 'flux', 'aws-batch' or 'local'. Overrides the target's configuration."
     )]
     pub scheduler: Option<SchedulerType>,
 }
@@ -70,6 +144,8 @@ pub enum Commands {
     Run(RunArgs),
     #[command(about = "Garbage collect old runs/jobs")]
     Gc(GcArgs),
+    #[command(about = "Manage cached container images")]
+    Images(ImagesArgs),
 
     #[command(hide = true)]
     InternalOrchestrate(InternalOrchestrateArgs),
@@ -83,6 +159,18 @@ pub enum Commands {
     #[command(hide = true)]
     InternalGc(InternalGcArgs),
 
+    #[command(hide = true)]
+    InternalImagesPrune(InternalImagesPruneArgs),
+
+    #[command(hide = true)]
+    InternalClean(InternalCleanArgs),
+
+    #[command(hide = true)]
+    InternalPrune(InternalPruneArgs),
+
+    #[command(hide = true)]
+    InternalCasLink(InternalCasLinkArgs),
+
     #[command(about = "List runs, jobs, or dependencies")]
     List(ListArgs),
 
@@ -96,6 +184,76 @@ pub enum Commands {
 
     #[command(about = "View logs for a job")]
     Log(LogArgs),
+
+    #[command(about = "Open a shell inside the sandbox a job would run in")]
+    DebugRun(DebugRunArgs),
+
+    #[command(about = "Cancel running or queued jobs")]
+    Cancel(CancelArgs),
+
+    #[command(
+        about = "Re-run only the failed jobs and scatter-gather branches of runs, keeping succeeded outputs"
+    )]
+    Retry(RunArgs),
+
+    #[command(
+        about = "Re-run failed jobs with overridden resources, without editing resources.toml"
+    )]
+    Resubmit(ResubmitArgs),
+
+    #[command(about = "Delete the outputs of selected jobs so they run again on the next submit")]
+    Clean(CleanArgs),
+
+    #[command(about = "Copy the outputs of selected jobs from the target into a local folder")]
+    Fetch(FetchArgs),
+
+    #[command(
+        about = "Delete the intermediate step outputs of scatter-gather jobs whose gather succeeded"
+    )]
+    Prune(PruneArgs),
+
+    #[command(about = "Package job outputs, logs and provenance into a tarball or directory")]
+    Archive(ArchiveArgs),
+
+    #[command(
+        about = "Export the declared outputs and params of succeeded jobs as one CSV or JSON results table"
+    )]
+    Export(ExportArgs),
+
+    #[command(about = "Compare two lab builds: added, removed and changed jobs")]
+    Diff(DiffArgs),
+
+    #[command(about = "Summarize wall times, queue waits and success rates per stage")]
+    Stats(StatsArgs),
+
+    #[command(about = "Show job statuses per target, or federated across targets and labs")]
+    Status(StatusArgs),
+
+    #[command(about = "Continuously print job status counts, finished jobs, and failures")]
+    Watch(WatchArgs),
+
+    #[command(
+        about = "Block until jobs finish; exits non-zero if any failed or the wait timed out"
+    )]
+    Wait(WaitArgs),
+
+    #[command(
+        about = "Explain why a job is in its current state: upstream jobs, queue reasons and markers"
+    )]
+    Why(WhyArgs),
+
+    #[command(
+        about = "Check connectivity, schedulers, disk space and binary deployment of targets"
+    )]
+    Targets(TargetsArgs),
+
+    #[command(
+        about = "Watch a target's markers and scheduler queues and stream status changes to stdout"
+    )]
+    Agent(AgentArgs),
+
+    #[command(about = "Manage named labs in the workspace")]
+    Workspace(WorkspaceArgs),
 }
 
 #[derive(Args)]
@@ -122,6 +280,19 @@ pub enum ShowEntity {
 pub struct ShowJobArgs {
     #[arg(help = "Job ID (or prefix) to inspect")]
     pub job_id: String,
+    #[arg(
+        long,
+        help = "Print the exact sandbox command the job would run on the target, without running it"
+    )]
+    pub command: bool,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Table,
+        conflicts_with = "command",
+        help = "Output format for the job details"
+    )]
+    pub format: OutputFormat,
 }
 
 #[derive(Args)]
@@ -181,6 +352,14 @@ pub struct ListJobsArgs {
 
     #[arg(long, short = 'g', help = "Group jobs by stage name")]
     pub group_by_stage: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Table,
+        help = "Output format. json, yaml and csv include run, status, timestamps, params and output path for every job"
+    )]
+    pub format: OutputFormat,
 }
 
 #[derive(Args)]
@@ -198,7 +377,7 @@ pub struct GcArgs {
     #[arg(
         long,
         global = true,
-        help = "Preview what would be deleted without actually deleting anything"
+        help = "Report what would be deleted and how much space it frees, grouped by lab and age, without deleting anything"
     )]
     pub dry_run: bool,
 
@@ -281,7 +460,9 @@ pub struct GcUnpinArgs {
 
 #[derive(Args)]
 pub struct LogArgs {
-    #[arg(help = "Job ID (or prefix) to get logs for")]
+    #[arg(
+        help = "Job ID (or prefix) to get logs for, or JOB::b<BRANCH>::<STEP> for a scatter-gather step"
+    )]
     pub job_id: String,
 
     #[arg(
@@ -299,6 +480,255 @@ pub struct LogArgs {
     pub follow: bool,
 }
 
+#[derive(Args)]
+pub struct DebugRunArgs {
+    #[arg(help = "Job ID (or prefix) to debug")]
+    pub job_id: String,
+    #[arg(
+        short,
+        long,
+        help = "Command to run (defaults to an interactive shell)"
+    )]
+    pub command: Option<String>,
+}
+
+#[derive(Args)]
+pub struct CancelArgs {
+    #[arg(
+        required = true,
+        value_name = "RUN_OR_JOB",
+        help = "Runs, @groups, or job IDs (or prefixes) to cancel"
+    )]
+    pub specs: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct CleanArgs {
+    #[arg(
+        value_name = "RUN_OR_JOB",
+        help = "Runs, @groups, or job IDs (or prefixes) whose outputs to delete"
+    )]
+    pub specs: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "RUN",
+        help = "Only clean jobs in this run or @group (repeatable)"
+    )]
+    pub run: Vec<String>,
+
+    #[arg(long, help = "Only clean failed, timed-out, cancelled and lost jobs")]
+    pub failed: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Only clean jobs with this status (repeatable)"
+    )]
+    pub status: Vec<StatusFilter>,
+
+    #[arg(
+        long,
+        value_name = "AGE",
+        value_parser = parse_age,
+        help = "Only clean jobs that finished longer ago than this (e.g. 12h, 7d)"
+    )]
+    pub older_than: Option<Duration>,
+
+    #[arg(
+        long,
+        help = "List the outputs that would be deleted without deleting them"
+    )]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct FetchArgs {
+    #[arg(
+        value_name = "RUN_OR_JOB",
+        help = "Runs, @groups, or job IDs (or prefixes) whose outputs to fetch (default: all jobs)"
+    )]
+    pub specs: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "RUN",
+        help = "Only fetch jobs in this run or @group (repeatable)"
+    )]
+    pub run: Vec<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Only fetch jobs with this status (repeatable, default: succeeded)"
+    )]
+    pub status: Vec<StatusFilter>,
+
+    #[arg(
+        long = "match",
+        value_name = "GLOB",
+        help = "Only fetch jobs whose ID matches this glob, e.g. '*-train-*' (repeatable)"
+    )]
+    pub globs: Vec<String>,
+
+    #[arg(
+        short,
+        long,
+        value_name = "DIR",
+        default_value = "results",
+        help = "Folder to copy into; each job gets a subfolder named after its ID"
+    )]
+    pub output: PathBuf,
+
+    #[arg(
+        long,
+        help = "List the jobs that would be fetched without copying anything"
+    )]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct ArchiveArgs {
+    #[arg(
+        value_name = "RUN_OR_JOB",
+        help = "Runs, @groups, or job IDs (or prefixes) to archive (default: all jobs)"
+    )]
+    pub specs: Vec<String>,
+
+    #[arg(
+        short,
+        long,
+        value_name = "PATH",
+        help = "Tarball (or directory with --dir) to write"
+    )]
+    pub output: PathBuf,
+
+    #[arg(long, help = "Write a directory instead of a tarball")]
+    pub dir: bool,
+
+    #[arg(long, help = "Also archive failed, timed-out and cancelled jobs")]
+    pub include_failed: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    #[arg(
+        value_name = "RUN_OR_JOB",
+        help = "Runs, @groups, or job IDs (or prefixes) to export (default: all jobs)"
+    )]
+    pub specs: Vec<String>,
+
+    #[arg(
+        long,
+        short = 's',
+        help = "Only export jobs of this stage (exact stage name)"
+    )]
+    pub stage: Option<String>,
+
+    #[arg(
+        long = "metrics",
+        help = "Extract scalar values from JSON and single-row CSV outputs into columns"
+    )]
+    pub metrics: bool,
+
+    #[arg(
+        short,
+        long,
+        value_name = "PATH",
+        help = "File to write (default: stdout)"
+    )]
+    pub output: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv, help = "Table format")]
+    pub format: ExportFormat,
+}
+
+#[derive(Args)]
+pub struct DiffArgs {
+    #[arg(
+        value_name = "OLD_LAB",
+        help = "Lab path or workspace name to compare against"
+    )]
+    pub old: PathBuf,
+
+    #[arg(
+        value_name = "NEW_LAB",
+        help = "Lab path or workspace name to compare (default: the current lab)"
+    )]
+    pub new: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, help = "Output format (table, json or yaml)")]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct StatsArgs {
+    #[arg(
+        value_name = "RUN_OR_JOB",
+        help = "Runs, @groups, or job IDs (or prefixes) to summarize (default: all jobs)"
+    )]
+    pub specs: Vec<String>,
+
+    #[arg(long, default_value_t = 10, help = "Number of slowest jobs to list")]
+    pub top: usize,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, help = "Output format (table, json or yaml)")]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct InternalCleanArgs {
+    #[arg(long)]
+    pub base_path: PathBuf,
+
+    #[arg(long)]
+    pub older_than_secs: Option<u64>,
+
+    #[arg(long)]
+    pub dry_run: bool,
+
+    pub job_ids: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct PruneArgs {
+    #[arg(
+        value_name = "RUN_OR_JOB",
+        help = "Runs, @groups, or job IDs (or prefixes) to prune (default: all scatter-gather jobs)"
+    )]
+    pub specs: Vec<String>,
+
+    #[arg(long, help = "Show what would be deleted without deleting it")]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct InternalPruneArgs {
+    #[arg(long)]
+    pub base_path: PathBuf,
+
+    #[arg(long)]
+    pub dry_run: bool,
+
+    pub job_ids: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct InternalCasLinkArgs {
+    #[arg(long)]
+    pub base_path: PathBuf,
+
+    #[arg(long)]
+    pub missing: bool,
+}
+
 #[derive(Args)]
 pub struct TraceParamsArgs {
     #[arg(help = "Job ID to trace (optional, shows all jobs if omitted)")]
@@ -314,6 +744,69 @@ pub struct InternalGcArgs {
     pub dry_run: bool,
 }
 
+#[derive(Args)]
+pub struct AgentArgs {
+    #[arg(long, help = "Base path of the target this agent runs on")]
+    pub base_path: PathBuf,
+
+    #[arg(
+        long,
+        default_value = "local",
+        help = "Name of the target in the client's configuration, reported as the job location"
+    )]
+    pub target_name: String,
+
+    #[arg(
+        long = "queue",
+        help = "Scheduler whose queue to watch: 'slurm', 'sge' or 'flux' (repeatable)"
+    )]
+    pub queues: Vec<SchedulerType>,
+
+    #[arg(long, default_value_t = 5, help = "Seconds between scans")]
+    pub interval_secs: u64,
+}
+
+#[derive(Args)]
+pub struct ImagesArgs {
+    #[command(subcommand)]
+    pub command: ImagesCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ImagesCommand {
+    #[command(about = "Remove extracted rootfs images that no running job is using")]
+    Prune(ImagesPruneArgs),
+}
+
+#[derive(Args)]
+pub struct ImagesPruneArgs {
+    #[arg(long, help = "The target (must be defined in config.toml)")]
+    pub target: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "SIZE",
+        value_parser = parse_size,
+        help = "Only evict least recently used images until the cache fits this size (e.g. 20G)"
+    )]
+    pub max_size: Option<u64>,
+
+    #[arg(long, help = "Preview what would be removed without deleting anything")]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct InternalImagesPruneArgs {
+    #[arg(long)]
+    pub cache_dir: Vec<PathBuf>,
+
+    #[arg(long)]
+    pub max_bytes: Option<u64>,
+
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
 #[derive(Args)]
 pub struct RunArgs {
     #[arg(value_name = "RUN_OR_JOB_ID")]
@@ -349,6 +842,77 @@ pub struct RunArgs {
                 'node-local': tar + extract to each node's local disk (requires node_local_path)."
     )]
     pub artifact_store: Option<ArtifactStoreArg>,
+
+    #[arg(
+        long,
+        help = "Submit immediately even if the target's submission windows are closed."
+    )]
+    pub ignore_windows: bool,
+}
+
+#[derive(Args)]
+pub struct ResubmitArgs {
+    #[arg(value_name = "RUN_OR_JOB_ID", required = true)]
+    pub run_specs: Vec<String>,
+
+    #[arg(long, value_name = "SIZE", value_parser = parse_memory, help = "Memory for the job, e.g. 64G")]
+    pub mem: Option<Memory>,
+
+    #[arg(
+        long,
+        value_name = "TIME",
+        value_parser = parse_slurm_time,
+        help = "Wall time limit for the job, e.g. 12:00:00"
+    )]
+    pub time: Option<SlurmTime>,
+
+    #[arg(long, help = "SLURM partition to submit to")]
+    pub partition: Option<String>,
+
+    #[arg(long, help = "CPUs per task")]
+    pub cpus_per_task: Option<u32>,
+
+    #[arg(
+        short = 'j',
+        long,
+        help = "Set the maximum number of parallel jobs for the local scheduler."
+    )]
+    pub jobs: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Continue running independent jobs even when some jobs fail. Report all failures at the end."
+    )]
+    pub continue_on_failure: bool,
+}
+
+impl ResubmitArgs {
+    pub fn has_overrides(&self) -> bool {
+        self.mem.is_some()
+            || self.time.is_some()
+            || self.partition.is_some()
+            || self.cpus_per_task.is_some()
+    }
+
+    pub fn into_parts(self) -> (RunArgs, ResourceRule) {
+        let overrides = ResourceRule {
+            partition: self.partition,
+            cpus_per_task: self.cpus_per_task,
+            mem: self.mem,
+            time: self.time,
+            ..Default::default()
+        };
+        let run_args = RunArgs {
+            run_specs: self.run_specs,
+            jobs: self.jobs,
+            mem: None,
+            continue_on_failure: self.continue_on_failure,
+            no_timing: false,
+            artifact_store: None,
+            ignore_windows: false,
+        };
+        (run_args, overrides)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
@@ -373,6 +937,18 @@ pub struct InternalOrchestrateArgs {
 
     #[arg(long)]
     pub stream: bool,
+
+    #[arg(long, default_value = "slurm")]
+    pub scheduler: SchedulerType,
+
+    #[arg(long)]
+    pub rest_url: Option<String>,
+
+    #[arg(long, requires = "rest_url")]
+    pub rest_api_version: Option<String>,
+
+    #[arg(long, requires = "rest_url")]
+    pub rest_token_env: Option<String>,
 }
 
 #[derive(Args)]
@@ -387,6 +963,12 @@ pub struct InternalExecuteArgs {
     pub base_path: PathBuf,
     #[arg(long)]
     pub node_local_path: Option<PathBuf>,
+    #[arg(
+        long,
+        requires = "node_local_path",
+        help = "Run against node-local copies of the inputs and outputs, copying outputs back on success."
+    )]
+    pub node_local_scratch: bool,
     #[arg(
         long,
         help = "Node-local path to extracted lab artifacts (lab-tar mode)."
@@ -398,8 +980,77 @@ pub struct InternalExecuteArgs {
     pub mount_host_paths: bool,
     #[arg(long)]
     pub mount_paths: Vec<String>,
+    #[arg(
+        long = "data-mount",
+        value_name = "HOST[:MOUNT]",
+        help = "Host path to bind read-only into the job, optionally at a different mount path."
+    )]
+    pub data_mounts: Vec<DataMount>,
+    #[arg(
+        long,
+        value_name = "MODE",
+        help = "Network access for the job: 'none' to run offline, 'host' to share the host network."
+    )]
+    pub network: Option<NetworkMode>,
+    #[arg(
+        long,
+        value_name = "PROFILE",
+        help = "Seccomp profile for bwrap jobs: 'default' or an absolute path to a JSON profile."
+    )]
+    pub seccomp: Option<SeccompProfile>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Hook executable run in the job's sandbox before the main script."
+    )]
+    pub pre_run: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Hook executable run in the job's sandbox after the main script succeeds."
+    )]
+    pub post_run: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Evict least recently used rootfs images once the image cache exceeds this size."
+    )]
+    pub image_cache_max_bytes: Option<u64>,
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Fail before running unless the base and node-local paths have this much free space."
+    )]
+    pub min_disk: Option<u64>,
+    #[arg(
+        long = "env",
+        value_name = "KEY=VALUE",
+        value_parser = parse_env_var,
+        help = "Set an environment variable for the job (repeatable)."
+    )]
+    pub env: Vec<(String, String)>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Pass a host environment variable through to the job (repeatable)."
+    )]
+    pub env_passthrough: Vec<String>,
     #[arg(long)]
     pub executable_path: PathBuf,
+    #[arg(long, help = "Run the executable through this interpreter.")]
+    pub interpreter: Option<String>,
+    #[arg(
+        long = "arg-template",
+        allow_hyphen_values = true,
+        help = "Argument template entry for the executable; $out, $inputs and $params expand to the job paths."
+    )]
+    pub arg_template: Vec<String>,
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Also record job output as timestamped JSON lines in logs.jsonl."
+    )]
+    pub structured_logs: bool,
     #[arg(long, default_value_t = false, help = "Enable debug logging to stderr")]
     pub debug: bool,
 
@@ -428,6 +1079,90 @@ pub struct InternalExecuteArgs {
         help = "Override the job package path (for scatter-gather steps)."
     )]
     pub job_package_path: Option<PathBuf>,
+    #[arg(
+        long,
+        requires = "step_name",
+        help = "Scatter-gather branch this step runs in, recorded in the job's progress."
+    )]
+    pub branch_idx: Option<usize>,
+    #[arg(long, requires = "branch_idx")]
+    pub step_name: Option<String>,
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Cap the job's memory: a cgroup for the native runtime, the engine's --memory for podman and docker."
+    )]
+    pub limit_mem: Option<u64>,
+    #[arg(
+        long,
+        value_name = "CPUS",
+        help = "Cap the job's CPUs: a cgroup for the native runtime, the engine's --cpus for podman and docker."
+    )]
+    pub limit_cpus: Option<u32>,
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Stop the job with SIGTERM once it has run this long."
+    )]
+    pub time_limit: Option<u64>,
+    #[arg(
+        long,
+        value_name = "SECS",
+        default_value_t = 30,
+        help = "Seconds to wait after SIGTERM before sending SIGKILL."
+    )]
+    pub kill_grace: u64,
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Retry the job in place this many times after a retriable failure."
+    )]
+    pub max_retries: u32,
+    #[arg(
+        long,
+        value_name = "SECS",
+        default_value_t = 5,
+        help = "Delay before the first retry; doubled for each further attempt."
+    )]
+    pub retry_backoff: u64,
+    #[arg(
+        long = "retry-exit-code",
+        value_name = "CODE",
+        help = "Exit code that triggers a retry (repeatable). Any non-zero code if omitted."
+    )]
+    pub retry_exit_codes: Vec<i32>,
+    #[arg(
+        long = "failure-rule",
+        value_name = "CLASS[:EXIT_CODES[:STDERR_REGEX]]",
+        help = "Classify a failure as retriable, infrastructure or user-error (repeatable, first match wins)."
+    )]
+    pub failure_rules: Vec<FailureRule>,
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Snapshot the running job with CRIU this often and resume from the latest snapshot."
+    )]
+    pub checkpoint_interval: Option<u64>,
+    #[arg(
+        long,
+        value_name = "SECS",
+        default_value_t = 30,
+        help = "Touch repx/heartbeat this often while the job runs; 0 disables the heartbeat."
+    )]
+    pub heartbeat_interval: u64,
+    #[arg(
+        long,
+        value_name = "URI",
+        help = "Publish the finished job's outputs to this s3:// or gs:// prefix."
+    )]
+    pub object_store: Option<String>,
+    #[arg(
+        long,
+        value_name = "URL",
+        requires = "object_store",
+        help = "Endpoint of the object store, for MinIO and other S3-compatible services."
+    )]
+    pub object_store_endpoint: Option<String>,
 }
 
 #[derive(Args)]
@@ -458,6 +1193,19 @@ pub struct InternalScatterGatherArgs {
     pub scheduler: SchedulerType,
     #[arg(long, allow_hyphen_values = true)]
     pub step_sbatch_opts: String,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Template for the step and gather batch scripts, with {{directives}}, {{prologue}} and {{body}} placeholders."
+    )]
+    pub sbatch_template: Option<PathBuf>,
+    #[arg(
+        long = "sbatch-prologue",
+        value_name = "LINE",
+        allow_hyphen_values = true,
+        help = "Shell line run before repx in every step and gather batch script (repeatable)."
+    )]
+    pub sbatch_prologue: Vec<String>,
     #[arg(long)]
     pub job_package_path: PathBuf,
     #[arg(long)]
@@ -470,7 +1218,7 @@ pub struct InternalScatterGatherArgs {
     pub last_step_outputs_json: String,
 
     #[arg(long)]
-    pub anchor_id: Option<u32>,
+    pub anchor_id: Option<u64>,
 
     #[arg(long, value_enum, default_value = "all")]
     pub phase: ScatterGatherPhase,
@@ -485,4 +1233,224 @@ pub struct InternalScatterGatherArgs {
     pub mount_host_paths: bool,
     #[arg(long)]
     pub mount_paths: Vec<String>,
+    #[arg(
+        long = "data-mount",
+        value_name = "HOST[:MOUNT]",
+        help = "Host path to bind read-only into the job, optionally at a different mount path."
+    )]
+    pub data_mounts: Vec<DataMount>,
+    #[arg(
+        long,
+        value_name = "MODE",
+        help = "Network access for the job: 'none' to run offline, 'host' to share the host network."
+    )]
+    pub network: Option<NetworkMode>,
+    #[arg(
+        long,
+        value_name = "PROFILE",
+        help = "Seccomp profile for bwrap jobs: 'default' or an absolute path to a JSON profile."
+    )]
+    pub seccomp: Option<SeccompProfile>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Hook executable run in the job's sandbox before the main script."
+    )]
+    pub pre_run: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Hook executable run in the job's sandbox after the main script succeeds."
+    )]
+    pub post_run: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Evict least recently used rootfs images once the image cache exceeds this size."
+    )]
+    pub image_cache_max_bytes: Option<u64>,
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Fail before running unless the base and node-local paths have this much free space."
+    )]
+    pub min_disk: Option<u64>,
+    #[arg(
+        long = "env",
+        value_name = "KEY=VALUE",
+        value_parser = parse_env_var,
+        help = "Set an environment variable for the job (repeatable)."
+    )]
+    pub env: Vec<(String, String)>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Pass a host environment variable through to the job (repeatable)."
+    )]
+    pub env_passthrough: Vec<String>,
+    #[arg(
+        long,
+        help = "Keep branch steps that succeeded in a previous attempt and only re-run the rest."
+    )]
+    pub retry_failed: bool,
+    #[arg(
+        long,
+        help = "Delete branch step outputs once the gather succeeded, keeping logs and markers."
+    )]
+    pub auto_prune_steps: bool,
+    #[arg(
+        long,
+        value_name = "COUNT|FRACTION",
+        help = "Run the gather over the branches that succeeded once at least this many did (e.g. 45, 0.9 or 90%)."
+    )]
+    pub min_branch_success: Option<BranchQuota>,
+    #[arg(
+        long,
+        value_name = "URI",
+        help = "Publish the finished job's outputs to this s3:// or gs:// prefix."
+    )]
+    pub object_store: Option<String>,
+    #[arg(
+        long,
+        value_name = "URL",
+        requires = "object_store",
+        help = "Endpoint of the object store, for MinIO and other S3-compatible services."
+    )]
+    pub object_store_endpoint: Option<String>,
+}
+
+#[derive(Args)]
+pub struct StatusArgs {
+    #[arg(long, help = "Query every configured target and show a combined view")]
+    pub all_targets: bool,
+
+    #[arg(
+        long,
+        help = "Include every lab registered in the workspace (implies --all-targets)"
+    )]
+    pub workspace: bool,
+
+    #[arg(
+        long,
+        value_name = "JOB_ID",
+        help = "Show where a single job stands on each target"
+    )]
+    pub job: Option<String>,
+}
+
+#[derive(Args)]
+pub struct WatchArgs {
+    #[arg(
+        value_name = "RUN_OR_JOB",
+        help = "Runs, @groups, or job IDs (or prefixes) to watch. Defaults to every job in the lab."
+    )]
+    pub specs: Vec<String>,
+
+    #[arg(
+        short = 'n',
+        long,
+        default_value_t = 5,
+        value_name = "SECS",
+        help = "Seconds between status polls"
+    )]
+    pub interval: u64,
+
+    #[arg(
+        long,
+        default_value_t = 10,
+        value_name = "N",
+        help = "Number of recently finished jobs to show"
+    )]
+    pub recent: usize,
+
+    #[arg(
+        long,
+        help = "Exit once every watched job has finished or is blocked; exits non-zero if any failed"
+    )]
+    pub exit_when_done: bool,
+}
+
+#[derive(Args)]
+pub struct WaitArgs {
+    #[arg(
+        value_name = "RUN_OR_JOB",
+        help = "Runs, @groups, or job IDs (or prefixes) to wait for. Defaults to every job in the lab."
+    )]
+    pub specs: Vec<String>,
+
+    #[arg(
+        short = 'n',
+        long,
+        default_value_t = 10,
+        value_name = "SECS",
+        help = "Seconds between status polls"
+    )]
+    pub interval: u64,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Give up after this many seconds and exit with code 2"
+    )]
+    pub timeout: Option<u64>,
+
+    #[arg(short, long, help = "Only print the final result")]
+    pub quiet: bool,
+}
+
+#[derive(Args)]
+pub struct WhyArgs {
+    #[arg(value_name = "JOB_ID", help = "Job ID (or unique prefix) to explain")]
+    pub job_id: String,
+}
+
+#[derive(Args)]
+pub struct TargetsArgs {
+    #[arg(
+        value_name = "TARGET",
+        help = "Targets to check (default: all configured targets)"
+    )]
+    pub names: Vec<String>,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, help = "Output format (table, json or yaml)")]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct WorkspaceArgs {
+    #[command(subcommand)]
+    pub command: WorkspaceCommand,
+}
+
+#[derive(Subcommand)]
+pub enum WorkspaceCommand {
+    #[command(about = "List labs registered in the workspace")]
+    List,
+
+    #[command(about = "Register a lab under a name (--target sets its default target)")]
+    Add(WorkspaceAddArgs),
+
+    #[command(about = "Remove a lab from the workspace")]
+    Remove(WorkspaceNameArgs),
+
+    #[command(about = "Make a lab the active one")]
+    Use(WorkspaceNameArgs),
+
+    #[command(about = "Summarize job statuses across all workspace labs")]
+    Status,
+}
+
+#[derive(Args)]
+pub struct WorkspaceAddArgs {
+    #[arg(help = "Name used to refer to the lab (e.g. --lab @NAME)")]
+    pub name: String,
+
+    #[arg(default_value = "./result", help = "Path to the lab directory or tar")]
+    pub path: PathBuf,
+}
+
+#[derive(Args)]
+pub struct WorkspaceNameArgs {
+    #[arg()]
+    pub name: String,
 }
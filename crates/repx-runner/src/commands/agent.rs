@@ -0,0 +1,31 @@
+use crate::cli::AgentArgs;
+use crate::error::CliError;
+use repx_client::targets::local::LocalTarget;
+use repx_core::config;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub fn handle_agent(args: AgentArgs) -> Result<(), CliError> {
+    let interval = Duration::from_secs(args.interval_secs.max(1));
+    tracing::info!(
+        "Agent watching '{}' at '{}' every {}s",
+        args.target_name,
+        args.base_path.display(),
+        interval.as_secs()
+    );
+    let target = LocalTarget::new(
+        args.target_name,
+        config::Target {
+            base_path: args.base_path,
+            ..Default::default()
+        },
+        PathBuf::new(),
+    );
+    repx_client::agent::run(
+        &target,
+        &args.queues,
+        interval,
+        &mut std::io::stdout().lock(),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use crate::cli::ArchiveArgs;
+use crate::commands::records::{self, JobRecord};
+use crate::commands::show::get_store_path;
+use crate::commands::trace::compute_all_effective_params;
+use crate::error::CliError;
+use repx_core::{
+    config,
+    constants::{dirs, logs, markers},
+    errors::CoreError,
+    model::{JobId, Lab},
+    store::outcomes::{get_job_timings, JobOutcome},
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const MANIFEST_FILE: &str = "archive.json";
+const PARAMS_FILE: &str = "params.json";
+const PROVENANCE_FILES: [&str; 5] = [
+    "inputs.json",
+    logs::STDOUT,
+    logs::STDERR,
+    logs::JSONL,
+    markers::FAIL,
+];
+
+#[derive(Serialize)]
+struct ArchivedJob<'a> {
+    #[serde(flatten)]
+    record: JobRecord,
+    name: Option<&'a str>,
+    image: Option<&'a Path>,
+    image_tag: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct ArchiveManifest<'a> {
+    repx_version: &'a str,
+    lab_version: &'a str,
+    git_hash: &'a str,
+    lab_content_hash: &'a str,
+    created_at: String,
+    jobs: Vec<ArchivedJob<'a>>,
+}
+
+enum ArchiveSink {
+    Dir(PathBuf),
+    Tar {
+        root: PathBuf,
+        builder: tar::Builder<File>,
+    },
+}
+
+impl ArchiveSink {
+    fn create(output: &Path, as_dir: bool) -> Result<Self, CliError> {
+        if as_dir {
+            if output.exists() && fs::read_dir(output)?.next().is_some() {
+                return Err(CliError::Config(CoreError::InvalidConfig {
+                    detail: format!(
+                        "Archive directory '{}' already exists and is not empty",
+                        output.display()
+                    ),
+                }));
+            }
+            fs::create_dir_all(output)?;
+            return Ok(Self::Dir(output.to_path_buf()));
+        }
+        let root = output
+            .file_stem()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("repx-archive"));
+        let mut builder = tar::Builder::new(File::create(output)?);
+        builder.mode(tar::HeaderMode::Deterministic);
+        Ok(Self::Tar { root, builder })
+    }
+
+    fn add_bytes(&mut self, rel: &Path, content: &[u8]) -> Result<(), CliError> {
+        match self {
+            Self::Dir(root) => {
+                let dest = root.join(rel);
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(dest, content)?;
+            }
+            Self::Tar { root, builder } => {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(0);
+                builder.append_data(&mut header, root.join(rel), content)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_file(&mut self, rel: &Path, src: &Path) -> Result<(), CliError> {
+        match self {
+            Self::Dir(root) => {
+                let dest = root.join(rel);
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(src, dest)?;
+            }
+            Self::Tar { root, builder } => {
+                builder.append_path_with_name(src, root.join(rel))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_dir_all(&mut self, rel: &Path, src: &Path) -> Result<(), CliError> {
+        match self {
+            Self::Dir(root) => {
+                let dest_root = root.join(rel);
+                for entry in WalkDir::new(src).follow_links(true) {
+                    let entry = entry.map_err(std::io::Error::from)?;
+                    let suffix = entry.path().strip_prefix(src).unwrap_or(entry.path());
+                    let dest = dest_root.join(suffix);
+                    if entry.file_type().is_dir() {
+                        fs::create_dir_all(&dest)?;
+                    } else {
+                        fs::copy(entry.path(), &dest)?;
+                    }
+                }
+            }
+            Self::Tar { root, builder } => {
+                builder.append_dir_all(root.join(rel), src)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), CliError> {
+        if let Self::Tar { mut builder, .. } = self {
+            builder.finish()?;
+        }
+        Ok(())
+    }
+}
+
+fn run_image<'a>(lab: &'a Lab, job_id: &JobId) -> Option<&'a Path> {
+    lab.runs
+        .values()
+        .find(|run| run.jobs.contains(job_id))
+        .and_then(|run| run.image.as_deref())
+}
+
+pub fn handle_archive(args: ArchiveArgs, lab: &Lab, target: Option<&str>) -> Result<(), CliError> {
+    let job_ids: BTreeSet<JobId> = if args.specs.is_empty() {
+        lab.jobs.keys().cloned().collect()
+    } else {
+        super::resolve_spec_job_ids(lab, &args.specs)?
+    };
+
+    let config = config::load_config()?;
+    let store_path = get_store_path(&config, target)?;
+    let owned_ids: Vec<JobId> = job_ids.into_iter().collect();
+    let timings = get_job_timings(&store_path, &owned_ids)?;
+
+    let selected: Vec<&JobId> = owned_ids
+        .iter()
+        .filter(|job_id| match timings.get(*job_id) {
+            Some(timing) => args.include_failed || timing.outcome == JobOutcome::Succeeded,
+            None => false,
+        })
+        .collect();
+    if selected.is_empty() {
+        println!("No finished jobs to archive.");
+        return Ok(());
+    }
+
+    let mut all_params = compute_all_effective_params(lab);
+    let mut sink = ArchiveSink::create(&args.output, args.dir)?;
+    let mut archived = Vec::with_capacity(selected.len());
+
+    for job_id in selected {
+        let timing = &timings[job_id];
+        let job_dir = store_path.join(dirs::OUTPUTS).join(job_id.as_str());
+        let rel_dir = Path::new(dirs::JOBS).join(job_id.as_str());
+        let params = all_params.remove(job_id).unwrap_or(serde_json::Value::Null);
+
+        let out_dir = job_dir.join(dirs::OUT);
+        if out_dir.is_dir() {
+            sink.add_dir_all(&rel_dir.join(dirs::OUT), &out_dir)?;
+        }
+        let repx_dir = job_dir.join(dirs::REPX);
+        for file in PROVENANCE_FILES {
+            let src = repx_dir.join(file);
+            if src.is_file() {
+                sink.add_file(&rel_dir.join(dirs::REPX).join(file), &src)?;
+            }
+        }
+        sink.add_bytes(
+            &rel_dir.join(PARAMS_FILE),
+            &serde_json::to_vec_pretty(&params)?,
+        )?;
+
+        let mut record = JobRecord::new(
+            lab,
+            job_id,
+            records::outcome_label(&timing.outcome),
+            Some(timing),
+            None,
+            params,
+        );
+        record.output_path = out_dir.is_dir().then(|| rel_dir.join(dirs::OUT));
+        let image = run_image(lab, job_id);
+        archived.push(ArchivedJob {
+            record,
+            name: lab.jobs.get(job_id).and_then(|job| job.name.as_deref()),
+            image,
+            image_tag: image
+                .and_then(|path| path.file_stem())
+                .and_then(|stem| stem.to_str()),
+        });
+    }
+
+    let job_count = archived.len();
+    let manifest = ArchiveManifest {
+        repx_version: &lab.repx_version,
+        lab_version: &lab.lab_version,
+        git_hash: &lab.git_hash,
+        lab_content_hash: &lab.content_hash,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        jobs: archived,
+    };
+    sink.add_bytes(
+        Path::new(MANIFEST_FILE),
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    sink.finish()?;
+
+    println!("Archived {} job(s) to {}", job_count, args.output.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_tar_sink_nests_entries_under_archive_name() {
+        let dir = tempdir().expect("tempdir");
+        let src = dir.path().join("src");
+        fs::create_dir_all(src.join("nested")).expect("create src");
+        fs::write(src.join("nested").join("metric.json"), "{}").expect("write src");
+        let output = dir.path().join("paper-results.tar");
+
+        let mut sink = ArchiveSink::create(&output, false).expect("create sink");
+        sink.add_dir_all(Path::new("jobs/abc-train-1.0/out"), &src)
+            .expect("add dir");
+        sink.add_bytes(Path::new(MANIFEST_FILE), b"{}")
+            .expect("add manifest");
+        sink.finish().expect("finish");
+
+        let mut archive = tar::Archive::new(File::open(&output).expect("open tar"));
+        let paths: Vec<PathBuf> = archive
+            .entries()
+            .expect("entries")
+            .map(|entry| entry.expect("entry").path().expect("path").into_owned())
+            .collect();
+        assert!(paths.contains(&PathBuf::from(
+            "paper-results/jobs/abc-train-1.0/out/nested/metric.json"
+        )));
+        assert!(paths.contains(&PathBuf::from("paper-results/archive.json")));
+    }
+
+    #[test]
+    fn test_dir_sink_refuses_non_empty_directory() {
+        let dir = tempdir().expect("tempdir");
+        fs::write(dir.path().join("existing"), "x").expect("write existing");
+        assert!(ArchiveSink::create(dir.path(), true).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+use crate::cli::CancelArgs;
+use crate::commands::AppContext;
+use crate::error::CliError;
+
+pub fn handle_cancel(args: CancelArgs, context: &AppContext) -> Result<(), CliError> {
+    let job_ids = super::resolve_spec_job_ids(context.client.lab(), &args.specs)?;
+
+    let (_, statuses) = context.client.get_statuses()?;
+    let mut cancelled = 0;
+    for job_id in job_ids {
+        if statuses.get(&job_id).is_some_and(super::watch::is_finished) {
+            tracing::debug!("Skipping finished job '{}'", job_id);
+            continue;
+        }
+        let report = context.client.cancel_job(job_id.clone())?;
+        if report.is_empty() {
+            continue;
+        }
+        println!("Cancelled {} ({})", job_id, report.describe_handles());
+        cancelled += 1;
+    }
+
+    if cancelled == 0 {
+        println!("No running or queued jobs matched.");
+    }
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::cli::InternalCasLinkArgs;
+use crate::error::CliError;
+use repx_core::{
+    constants::dirs,
+    errors::CoreError,
+    store::cas::{self, TreeEntry},
+};
+use std::io::BufRead;
+
+pub fn handle_internal_cas_link(args: InternalCasLinkArgs) -> Result<(), CliError> {
+    let artifacts_dir = args.base_path.join(dirs::ARTIFACTS);
+    let mut entries = Vec::new();
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: TreeEntry = serde_json::from_str(&line).map_err(|e| {
+            CliError::Config(CoreError::InvalidConfig {
+                detail: format!("Invalid tree manifest entry '{}': {}", line, e),
+            })
+        })?;
+        entries.push(entry);
+    }
+
+    if args.missing {
+        for blob in cas::missing_blobs(&artifacts_dir, &entries)? {
+            println!("{}", blob.display());
+        }
+        return Ok(());
+    }
+
+    let stats = cas::link_tree(&artifacts_dir, &entries)?;
+    tracing::info!(
+        "Linked {} path(s) into '{}', {} already up to date",
+        stats.linked,
+        artifacts_dir.display(),
+        stats.unchanged
+    );
+    Ok(())
+}
@@ -0,0 +1,266 @@
+use crate::cli::{CleanArgs, InternalCleanArgs, StatusFilter};
+use crate::commands::gc::{force_remove_no_parent, path_size, with_writable_dir};
+use crate::commands::list::status_matches_filter;
+use crate::commands::AppContext;
+use crate::error::CliError;
+use repx_core::{
+    constants::{dirs, markers},
+    engine::{self, JobStatus},
+    errors::{CoreError, DomainError},
+    fs_utils,
+    model::JobId,
+    path_safety::safe_join,
+    store::completion_log,
+};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+const OUTCOME_MARKERS: [&str; 4] = [
+    markers::SUCCESS,
+    markers::FAIL,
+    markers::TIMEOUT,
+    markers::CANCELLED,
+];
+
+fn is_in_flight(status: Option<&JobStatus>) -> bool {
+    matches!(status, Some(JobStatus::Running | JobStatus::Queued))
+}
+
+pub fn handle_clean(
+    args: CleanArgs,
+    context: &AppContext,
+    verbose: repx_core::logging::Verbosity,
+) -> Result<(), CliError> {
+    let mut status_filters = args.status;
+    if args.failed && !status_filters.contains(&StatusFilter::Failed) {
+        status_filters.push(StatusFilter::Failed);
+    }
+
+    if args.specs.is_empty()
+        && args.run.is_empty()
+        && status_filters.is_empty()
+        && args.older_than.is_none()
+    {
+        return Err(CliError::Config(CoreError::MissingArgument {
+            argument: "specs".to_string(),
+            context:
+                "Select jobs to clean with a run, job ID, --run, --failed, --status or --older-than"
+                    .to_string(),
+        }));
+    }
+
+    let lab = context.client.lab();
+    let mut job_ids: BTreeSet<JobId> = if args.specs.is_empty() {
+        lab.jobs.keys().cloned().collect()
+    } else {
+        super::resolve_spec_job_ids(lab, &args.specs)?
+    };
+    if !args.run.is_empty() {
+        let in_runs = super::resolve_spec_job_ids(lab, &args.run)?;
+        job_ids.retain(|job_id| in_runs.contains(job_id));
+    }
+
+    let target_name = context.submission_target;
+    let target = context
+        .client
+        .get_target(target_name)
+        .ok_or_else(|| CliError::Domain(DomainError::TargetNotFound(target_name.to_string())))?;
+
+    let raw_statuses = context
+        .client
+        .get_statuses_for_active_target(target_name, None)?;
+    let statuses = engine::determine_job_statuses(lab, raw_statuses);
+    job_ids.retain(|job_id| {
+        let status = statuses.get(job_id);
+        if is_in_flight(status) {
+            tracing::debug!("Skipping in-flight job '{}'", job_id);
+            return false;
+        }
+        status_filters.is_empty()
+            || status.is_some_and(|status| {
+                status_filters
+                    .iter()
+                    .any(|filter| status_matches_filter(status, filter))
+            })
+    });
+
+    if job_ids.is_empty() {
+        println!("No job outputs matched.");
+        return Ok(());
+    }
+
+    let job_ids: Vec<JobId> = job_ids.into_iter().collect();
+    let msg = target
+        .clean_outputs(&job_ids, args.older_than, args.dry_run, verbose)
+        .map_err(|e| {
+            CliError::execution_failed(
+                format!("Failed to clean outputs on target '{}'", target_name),
+                e.to_string(),
+            )
+        })?;
+    let msg = msg.trim();
+    if !msg.is_empty() {
+        println!("{}", msg);
+    }
+    Ok(())
+}
+
+fn finished_at(job_dir: &Path) -> Option<SystemTime> {
+    let repx_dir = job_dir.join(dirs::REPX);
+    OUTCOME_MARKERS
+        .iter()
+        .find_map(|marker| fs::metadata(repx_dir.join(marker)).ok())
+        .or_else(|| fs::metadata(job_dir).ok())
+        .and_then(|meta| meta.modified().ok())
+}
+
+fn is_older_than(job_dir: &Path, age: Duration) -> bool {
+    finished_at(job_dir)
+        .and_then(|at| SystemTime::now().duration_since(at).ok())
+        .is_some_and(|elapsed| elapsed >= age)
+}
+
+fn remove_markers(job_dir: &Path) {
+    let repx_dir = job_dir.join(dirs::REPX);
+    with_writable_dir(&repx_dir, || {
+        for marker in OUTCOME_MARKERS {
+            let path = repx_dir.join(marker);
+            if path.exists() {
+                force_remove_no_parent(&path);
+            }
+        }
+    });
+}
+
+pub fn handle_internal_clean(args: InternalCleanArgs) -> Result<(), CliError> {
+    let outputs_dir = args.base_path.join(dirs::OUTPUTS);
+    let older_than = args.older_than_secs.map(Duration::from_secs);
+
+    let mut cleaned: u64 = 0;
+    let mut freed_bytes: u64 = 0;
+
+    for raw_id in &args.job_ids {
+        let job_id = JobId::from(raw_id.as_str());
+        let job_dir = safe_join(&outputs_dir, job_id.as_str())?;
+        if !job_dir.is_dir() {
+            tracing::debug!("No outputs for job '{}'", job_id);
+            continue;
+        }
+        if let Some(age) = older_than {
+            if !is_older_than(&job_dir, age) {
+                tracing::debug!("Skipping recent job '{}'", job_id);
+                continue;
+            }
+        }
+
+        let size = path_size(&job_dir);
+        if args.dry_run {
+            println!(
+                "Would delete {} ({})",
+                job_id,
+                fs_utils::format_bytes(size, false)
+            );
+            cleaned += 1;
+            freed_bytes += size;
+            continue;
+        }
+
+        remove_markers(&job_dir);
+        let mut removed = false;
+        with_writable_dir(&outputs_dir, || removed = force_remove_no_parent(&job_dir));
+        if let Err(e) = completion_log::append_cleared(&args.base_path, &job_id) {
+            tracing::warn!("Failed to record cleanup of '{}': {}", job_id, e);
+        }
+        if removed {
+            tracing::info!("Deleted outputs of '{}'", job_id);
+            cleaned += 1;
+            freed_bytes += size;
+        }
+    }
+
+    if cleaned == 0 {
+        println!("Nothing to clean.");
+    } else if args.dry_run {
+        println!(
+            "Would delete the outputs of {} job(s). Would free {}.",
+            cleaned,
+            fs_utils::format_bytes(freed_bytes, false)
+        );
+    } else {
+        println!(
+            "Deleted the outputs of {} job(s). Freed {}.",
+            cleaned,
+            fs_utils::format_bytes(freed_bytes, false)
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn finished_job(base: &Path, id: &str) -> PathBuf {
+        let job_dir = base.join(dirs::OUTPUTS).join(id);
+        let repx_dir = job_dir.join(dirs::REPX);
+        fs::create_dir_all(job_dir.join(dirs::OUT)).expect("create job dirs");
+        fs::create_dir_all(&repx_dir).expect("create repx dir");
+        fs::write(job_dir.join(dirs::OUT).join("result.csv"), "a,b\n").expect("write output");
+        fs::File::create(repx_dir.join(markers::SUCCESS)).expect("write marker");
+        job_dir
+    }
+
+    #[test]
+    fn test_internal_clean_removes_outputs_and_records_cleared() {
+        let dir = tempdir().expect("tempdir");
+        let base = dir.path();
+        let cleaned = finished_job(base, "abc123-train-1.0");
+        let kept = finished_job(base, "def456-eval-1.0");
+        completion_log::append_completion(base, &JobId::from("abc123-train-1.0"), true, None)
+            .expect("append completion");
+
+        handle_internal_clean(InternalCleanArgs {
+            base_path: base.to_path_buf(),
+            older_than_secs: None,
+            dry_run: false,
+            job_ids: vec!["abc123-train-1.0".to_string()],
+        })
+        .expect("clean");
+
+        assert!(!cleaned.exists());
+        assert!(kept.join(dirs::REPX).join(markers::SUCCESS).exists());
+        let outcomes = completion_log::read_completions(base, "test")
+            .expect("read")
+            .unwrap_or_default();
+        assert!(!outcomes.contains_key(&JobId::from("abc123-train-1.0")));
+    }
+
+    #[test]
+    fn test_internal_clean_dry_run_and_age_filter_keep_outputs() {
+        let dir = tempdir().expect("tempdir");
+        let base = dir.path();
+        let job_dir = finished_job(base, "abc123-train-1.0");
+
+        handle_internal_clean(InternalCleanArgs {
+            base_path: base.to_path_buf(),
+            older_than_secs: None,
+            dry_run: true,
+            job_ids: vec!["abc123-train-1.0".to_string()],
+        })
+        .expect("dry run");
+        assert!(job_dir.join(dirs::REPX).join(markers::SUCCESS).exists());
+
+        handle_internal_clean(InternalCleanArgs {
+            base_path: base.to_path_buf(),
+            older_than_secs: Some(7 * 86400),
+            dry_run: false,
+            job_ids: vec!["abc123-train-1.0".to_string()],
+        })
+        .expect("age-filtered clean");
+        assert!(job_dir.join(dirs::REPX).join(markers::SUCCESS).exists());
+    }
+}
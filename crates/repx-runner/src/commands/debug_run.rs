@@ -0,0 +1,49 @@
+use super::show::LocalJob;
+use crate::cli::DebugRunArgs;
+use crate::error::CliError;
+use repx_core::model::Lab;
+use repx_executor::Executor;
+use std::fs;
+use std::path::Path;
+
+const SHELL: &str = "/bin/sh";
+
+pub fn handle_debug_run(
+    args: DebugRunArgs,
+    lab: &Lab,
+    target: Option<&str>,
+    resources: Option<&Path>,
+) -> Result<(), CliError> {
+    let job = LocalJob::resolve(lab, &args.job_id, target, resources)?;
+    fs::create_dir_all(&job.request.user_out_dir)?;
+    fs::create_dir_all(&job.request.repx_out_dir)?;
+
+    let shell_args = match &args.command {
+        Some(command) => vec!["-c".to_string(), command.clone()],
+        None => {
+            eprintln!(
+                "Entering the sandbox of job '{}' ({}). The job runs as:\n  {} {}",
+                job.request.job_id,
+                job.request.runtime,
+                job.script_path.display(),
+                job.args.join(" ")
+            );
+            Vec::new()
+        }
+    };
+
+    let mut executor = Executor::new(job.request);
+    let rt = super::create_tokio_runtime()?;
+    let code = rt
+        .block_on(executor.execute_interactive(Path::new(SHELL), &shell_args))
+        .map_err(|e| {
+            CliError::execution_failed("Failed to start the job sandbox", e.to_string())
+        })?;
+    if code != 0 {
+        return Err(CliError::execution_failed(
+            "Debug session failed",
+            format!("{} exited with code {}", SHELL, code),
+        ));
+    }
+    Ok(())
+}
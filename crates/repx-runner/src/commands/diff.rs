@@ -0,0 +1,377 @@
+use crate::cli::{DiffArgs, OutputFormat};
+use crate::commands::records::{stage_name, write_structured};
+use crate::error::CliError;
+use colored::Colorize;
+use repx_core::{
+    lab::{self, LabSource},
+    model::{Job, JobId, Lab, RunId},
+    workspace,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize)]
+struct ParamChange {
+    key: String,
+    old: Option<Value>,
+    new: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExecutableChange {
+    name: String,
+    old: Option<PathBuf>,
+    new: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct JobDiff {
+    stage: String,
+    old_id: JobId,
+    new_id: JobId,
+    params: Vec<ParamChange>,
+    executables: Vec<ExecutableChange>,
+    dependencies_added: Vec<JobId>,
+    dependencies_removed: Vec<JobId>,
+    upstream_changed: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct LabDiff {
+    old_lab: PathBuf,
+    new_lab: PathBuf,
+    runs_added: Vec<RunId>,
+    runs_removed: Vec<RunId>,
+    jobs_added: Vec<JobId>,
+    jobs_removed: Vec<JobId>,
+    jobs_changed: Vec<JobDiff>,
+    jobs_unchanged: usize,
+}
+
+fn param_changes(old: &Value, new: &Value) -> Vec<ParamChange> {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            let keys: BTreeSet<&String> = old_map.keys().chain(new_map.keys()).collect();
+            keys.into_iter()
+                .filter(|key| old_map.get(*key) != new_map.get(*key))
+                .map(|key| ParamChange {
+                    key: key.clone(),
+                    old: old_map.get(key).cloned(),
+                    new: new_map.get(key).cloned(),
+                })
+                .collect()
+        }
+        _ if old == new => Vec::new(),
+        _ => vec![ParamChange {
+            key: String::new(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }],
+    }
+}
+
+fn executable_changes(old: &Job, new: &Job) -> Vec<ExecutableChange> {
+    let names: BTreeSet<&String> = old
+        .executables
+        .keys()
+        .chain(new.executables.keys())
+        .collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let old_path = old.executables.get(name).map(|exe| exe.path.clone());
+            let new_path = new.executables.get(name).map(|exe| exe.path.clone());
+            (old_path != new_path).then(|| ExecutableChange {
+                name: name.clone(),
+                old: old_path,
+                new: new_path,
+            })
+        })
+        .collect()
+}
+
+fn pair_jobs(old: &Lab, new: &Lab) -> Vec<(JobId, JobId)> {
+    let mut old_by_stage: BTreeMap<String, Vec<&JobId>> = BTreeMap::new();
+    for job_id in old.jobs.keys().filter(|id| !new.jobs.contains_key(*id)) {
+        old_by_stage
+            .entry(stage_name(job_id))
+            .or_default()
+            .push(job_id);
+    }
+    let mut new_ids: Vec<&JobId> = new
+        .jobs
+        .keys()
+        .filter(|id| !old.jobs.contains_key(*id))
+        .collect();
+    new_ids.sort();
+
+    let mut pairs = Vec::new();
+    for new_id in new_ids {
+        let Some(candidates) = old_by_stage.get_mut(&stage_name(new_id)) else {
+            continue;
+        };
+        let new_params = &new.jobs[new_id].params;
+        let best = candidates
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, old_id)| {
+                (
+                    param_changes(&old.jobs[**old_id].params, new_params).len(),
+                    **old_id,
+                )
+            })
+            .map(|(idx, _)| idx);
+        if let Some(idx) = best {
+            pairs.push((candidates.remove(idx).clone(), new_id.clone()));
+        }
+    }
+    pairs
+}
+
+fn diff_labs(old: &Lab, new: &Lab, old_path: &Path, new_path: &Path) -> LabDiff {
+    let pairs = pair_jobs(old, new);
+    let renamed: HashMap<&JobId, &JobId> = pairs.iter().map(|(o, n)| (o, n)).collect();
+    let paired_new: BTreeSet<&JobId> = pairs.iter().map(|(_, n)| n).collect();
+
+    let jobs_changed = pairs
+        .iter()
+        .map(|(old_id, new_id)| {
+            let old_job = &old.jobs[old_id];
+            let new_job = &new.jobs[new_id];
+            let old_deps: BTreeSet<&JobId> = old_job
+                .all_dependencies()
+                .map(|dep| renamed.get(dep).copied().unwrap_or(dep))
+                .collect();
+            let new_deps: BTreeSet<&JobId> = new_job.all_dependencies().collect();
+            let upstream_changed = new_deps.iter().any(|dep| !old.jobs.contains_key(*dep));
+            JobDiff {
+                stage: stage_name(new_id),
+                old_id: old_id.clone(),
+                new_id: new_id.clone(),
+                params: param_changes(&old_job.params, &new_job.params),
+                executables: executable_changes(old_job, new_job),
+                dependencies_added: new_deps
+                    .difference(&old_deps)
+                    .map(|dep| (*dep).clone())
+                    .collect(),
+                dependencies_removed: old_deps
+                    .difference(&new_deps)
+                    .map(|dep| (*dep).clone())
+                    .collect(),
+                upstream_changed,
+            }
+        })
+        .collect();
+
+    let mut jobs_added: Vec<JobId> = new
+        .jobs
+        .keys()
+        .filter(|id| !old.jobs.contains_key(*id) && !paired_new.contains(id))
+        .cloned()
+        .collect();
+    jobs_added.sort();
+    let mut jobs_removed: Vec<JobId> = old
+        .jobs
+        .keys()
+        .filter(|id| !new.jobs.contains_key(*id) && !renamed.contains_key(id))
+        .cloned()
+        .collect();
+    jobs_removed.sort();
+    let mut runs_added: Vec<RunId> = new
+        .runs
+        .keys()
+        .filter(|id| !old.runs.contains_key(*id))
+        .cloned()
+        .collect();
+    runs_added.sort();
+    let mut runs_removed: Vec<RunId> = old
+        .runs
+        .keys()
+        .filter(|id| !new.runs.contains_key(*id))
+        .cloned()
+        .collect();
+    runs_removed.sort();
+
+    LabDiff {
+        old_lab: old_path.to_path_buf(),
+        new_lab: new_path.to_path_buf(),
+        runs_added,
+        runs_removed,
+        jobs_added,
+        jobs_removed,
+        jobs_changed,
+        jobs_unchanged: new
+            .jobs
+            .keys()
+            .filter(|id| old.jobs.contains_key(*id))
+            .count(),
+    }
+}
+
+fn format_param(value: Option<&Value>) -> String {
+    value.map_or_else(|| "(unset)".to_string(), |v| v.to_string())
+}
+
+fn format_exe(path: Option<&PathBuf>) -> String {
+    path.map_or_else(|| "(none)".to_string(), |p| p.display().to_string())
+}
+
+fn print_diff(diff: &LabDiff) {
+    println!(
+        "Comparing {} -> {}",
+        diff.old_lab.display(),
+        diff.new_lab.display()
+    );
+    for run in &diff.runs_added {
+        println!("{}", format!("+ run {}", run).green());
+    }
+    for run in &diff.runs_removed {
+        println!("{}", format!("- run {}", run).red());
+    }
+    println!(
+        "Jobs: {} added, {} removed, {} changed, {} unchanged",
+        diff.jobs_added.len(),
+        diff.jobs_removed.len(),
+        diff.jobs_changed.len(),
+        diff.jobs_unchanged
+    );
+
+    for job_id in &diff.jobs_added {
+        println!("{}", format!("+ {}", job_id.short_id()).green());
+    }
+    for job_id in &diff.jobs_removed {
+        println!("{}", format!("- {}", job_id.short_id()).red());
+    }
+    for job in &diff.jobs_changed {
+        println!(
+            "{} {} -> {}",
+            "~".yellow(),
+            job.old_id.short_id(),
+            job.new_id.short_id()
+        );
+        for param in &job.params {
+            println!(
+                "    params.{}: {} -> {}",
+                param.key,
+                format_param(param.old.as_ref()),
+                format_param(param.new.as_ref())
+            );
+        }
+        for exe in &job.executables {
+            println!(
+                "    executable {}: {} -> {}",
+                exe.name,
+                format_exe(exe.old.as_ref()),
+                format_exe(exe.new.as_ref())
+            );
+        }
+        for dep in &job.dependencies_added {
+            println!("    + dependency {}", dep.short_id());
+        }
+        for dep in &job.dependencies_removed {
+            println!("    - dependency {}", dep.short_id());
+        }
+        if job.upstream_changed {
+            println!("    {}", "upstream job changed".dimmed());
+        }
+    }
+}
+
+pub fn handle_diff(args: DiffArgs, current_lab: &Path) -> Result<(), CliError> {
+    let old_path = workspace::resolve_lab_arg(&args.old)?.path;
+    let new_path = match &args.new {
+        Some(new) => workspace::resolve_lab_arg(new)?.path,
+        None => current_lab.to_path_buf(),
+    };
+    let old = lab::load(&LabSource::from_path(&old_path))?;
+    let new = lab::load(&LabSource::from_path(&new_path))?;
+    let diff = diff_labs(&old, &new, &old_path, &new_path);
+
+    match args.format {
+        OutputFormat::Table => print_diff(&diff),
+        format => write_structured(&diff, format, std::io::stdout().lock())?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lab_with(jobs: &[(&str, Value, &str, &[&str])]) -> Lab {
+        let mut lab: Lab = serde_json::from_value(json!({
+            "repx_version": "0",
+            "lab_version": "1",
+            "gitHash": "abc",
+            "runs": {},
+            "jobs": {}
+        }))
+        .expect("empty lab");
+        for (id, params, exe, deps) in jobs {
+            let inputs: Vec<Value> = deps
+                .iter()
+                .map(|dep| json!({"job_id": dep, "source_output": "data", "target_input": "data"}))
+                .collect();
+            let job: Job = serde_json::from_value(json!({
+                "name": null,
+                "params": params,
+                "executables": {"main": {"path": exe, "inputs": inputs}}
+            }))
+            .expect("job");
+            lab.jobs.insert(JobId::from(*id), job);
+        }
+        lab
+    }
+
+    #[test]
+    fn test_diff_pairs_changed_jobs_by_stage_and_params() {
+        let old = lab_with(&[
+            ("aaa-prepare-1.0", json!({}), "store/p1", &[]),
+            (
+                "bbb-train-1.0",
+                json!({"lr": 0.1}),
+                "store/t1",
+                &["aaa-prepare-1.0"],
+            ),
+            (
+                "ccc-train-1.0",
+                json!({"lr": 0.2}),
+                "store/t1",
+                &["aaa-prepare-1.0"],
+            ),
+        ]);
+        let new = lab_with(&[
+            ("aaa-prepare-1.0", json!({}), "store/p1", &[]),
+            (
+                "bbb-train-1.0",
+                json!({"lr": 0.1}),
+                "store/t1",
+                &["aaa-prepare-1.0"],
+            ),
+            (
+                "ddd-train-1.0",
+                json!({"lr": 0.3}),
+                "store/t2",
+                &["aaa-prepare-1.0"],
+            ),
+            ("eee-eval-1.0", json!({}), "store/e1", &["ddd-train-1.0"]),
+        ]);
+
+        let diff = diff_labs(&old, &new, Path::new("old"), Path::new("new"));
+        assert_eq!(diff.jobs_unchanged, 2);
+        assert_eq!(diff.jobs_added, vec![JobId::from("eee-eval-1.0")]);
+        assert!(diff.jobs_removed.is_empty());
+        assert_eq!(diff.jobs_changed.len(), 1);
+        let changed = &diff.jobs_changed[0];
+        assert_eq!(changed.old_id, JobId::from("ccc-train-1.0"));
+        assert_eq!(changed.new_id, JobId::from("ddd-train-1.0"));
+        assert_eq!(changed.params.len(), 1);
+        assert_eq!(changed.params[0].key, "lr");
+        assert_eq!(changed.executables.len(), 1);
+        assert!(changed.dependencies_added.is_empty());
+        assert!(!changed.upstream_changed);
+    }
+}
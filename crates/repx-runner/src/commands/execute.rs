@@ -4,17 +4,37 @@ use repx_core::{
     constants::{dirs, logs, markers},
     errors::CoreError,
     fs_utils::path_to_string,
-    model::{JobId, MountPolicy},
-    store::completion_log,
+    model::{DataMount, JobId, MountPolicy},
+    store::{completion_log, outcomes::FailureInfo, progress::StepState},
 };
-use repx_executor::{CancellationToken, ExecutionRequest, Executor};
+use repx_executor::{ExecutionRequest, Executor, ExecutorError, ResourceLimits, TimeLimit};
 use std::fs;
+use std::time::{Duration, Instant};
 
-use super::write_marker;
+use super::{
+    scatter_gather::{record_progress, scratch::StepScratch},
+    write_marker,
+};
 
 pub fn handle_execute(args: InternalExecuteArgs) -> Result<(), CliError> {
     let rt = super::create_tokio_runtime()?;
-    rt.block_on(async_handle_execute(args))
+    let publish = args
+        .object_store
+        .clone()
+        .filter(|_| args.user_out_dir.is_none())
+        .map(|uri| {
+            (
+                uri,
+                args.object_store_endpoint.clone(),
+                args.base_path.clone(),
+                JobId::from(args.job_id.clone()),
+            )
+        });
+    let result = rt.block_on(async_handle_execute(args));
+    if let Some((uri, endpoint, base_path, job_id)) = publish {
+        super::publish_outputs(Some(&uri), endpoint.as_deref(), &base_path, &job_id);
+    }
+    result
 }
 
 async fn async_handle_execute(args: InternalExecuteArgs) -> Result<(), CliError> {
@@ -35,6 +55,8 @@ async fn async_handle_execute(args: InternalExecuteArgs) -> Result<(), CliError>
 
     let _ = fs::remove_file(repx_dir.join(markers::SUCCESS));
     let _ = fs::remove_file(repx_dir.join(markers::FAIL));
+    let _ = fs::remove_file(repx_dir.join(markers::TIMEOUT));
+    let _ = fs::remove_file(repx_dir.join(markers::CANCELLED));
 
     let script_path = super::resolve_to_local_artifacts(
         &args.executable_path,
@@ -61,10 +83,51 @@ async fn async_handle_execute(args: InternalExecuteArgs) -> Result<(), CliError>
         .parameters_json_path
         .unwrap_or_else(|| repx_dir.join("parameters.json"));
 
-    let (inputs_json_path, inputs_data) = read_fd_path_to_memory(&inputs_json_path_raw)?;
+    let (inputs_json_path, mut inputs_data) = read_fd_path_to_memory(&inputs_json_path_raw)?;
     let (parameters_json_path, parameters_data) =
         read_fd_path_to_memory(&parameters_json_path_raw)?;
 
+    let scratch = match (&args.node_local_path, args.node_local_scratch) {
+        (Some(node_local), true) => {
+            let key = user_out_dir
+                .parent()
+                .and_then(|step_root| {
+                    step_root
+                        .strip_prefix(args.base_path.join(dirs::OUTPUTS))
+                        .ok()
+                })
+                .ok_or_else(|| {
+                    CliError::Config(CoreError::InvalidConfig {
+                        detail: format!(
+                            "Output directory '{}' is not inside the job store",
+                            user_out_dir.display()
+                        ),
+                    })
+                })?;
+            let scratch = StepScratch::create(node_local, key)?;
+            let raw = match &inputs_data {
+                Some(data) => data.clone(),
+                None => fs::read(&inputs_json_path)?,
+            };
+            let mut inputs: serde_json::Map<String, serde_json::Value> =
+                serde_json::from_slice(&raw)?;
+            scratch.stage_inputs(&mut inputs)?;
+            inputs_data = Some(serde_json::to_vec_pretty(&inputs)?);
+            Some(scratch)
+        }
+        _ => None,
+    };
+    let run_out_dir = scratch
+        .as_ref()
+        .map_or_else(|| user_out_dir.clone(), StepScratch::out_dir);
+    let mut data_mounts = args.data_mounts;
+    if let Some(scratch) = &scratch {
+        data_mounts.push(DataMount {
+            host_path: scratch.inputs_dir(),
+            mount_path: None,
+        });
+    }
+
     let runtime = super::parse_runtime(args.runtime, args.image_tag)?;
     let host_tools_bin_dir = super::resolve_host_tools_dir(
         &args.base_path,
@@ -73,7 +136,7 @@ async fn async_handle_execute(args: InternalExecuteArgs) -> Result<(), CliError>
     );
 
     let exec_args = vec![
-        path_to_string(&user_out_dir),
+        path_to_string(&run_out_dir),
         path_to_string(&inputs_json_path),
         path_to_string(&parameters_json_path),
     ];
@@ -87,20 +150,74 @@ async fn async_handle_execute(args: InternalExecuteArgs) -> Result<(), CliError>
         local_artifacts_path: args.local_artifacts_path,
         job_package_path,
         inputs_json_path,
-        user_out_dir,
+        user_out_dir: run_out_dir,
         repx_out_dir: repx_dir.clone(),
         host_tools_bin_dir,
         mount_policy: MountPolicy::from_flags(args.mount_host_paths, args.mount_paths),
+        data_mounts,
+        network: args.network,
+        seccomp: args.seccomp.clone(),
+        pre_run: args.pre_run,
+        post_run: args.post_run,
+        image_cache_limit: args.image_cache_max_bytes,
+        min_disk: args.min_disk,
         inputs_data,
         parameters_data,
+        interpreter: args.interpreter,
+        arg_template: args.arg_template,
+        resource_limits: ResourceLimits::new(args.limit_mem, args.limit_cpus),
+        time_limit: args.time_limit.map(|secs| TimeLimit {
+            limit: Duration::from_secs(secs),
+            grace: Duration::from_secs(args.kill_grace),
+        }),
+        max_retries: args.max_retries,
+        retry_backoff: Duration::from_secs(args.retry_backoff),
+        retry_exit_codes: args.retry_exit_codes,
+        failure_rules: args.failure_rules.clone(),
+        checkpoint_interval: args.checkpoint_interval.map(Duration::from_secs),
+        heartbeat_interval: (args.heartbeat_interval > 0)
+            .then(|| Duration::from_secs(args.heartbeat_interval)),
+        structured_logs: args.structured_logs,
+        env: args.env.into_iter().collect(),
+        env_passthrough: args.env_passthrough,
+        secrets: super::load_job_secrets(job_id.as_str())?,
     };
 
     let mut executor = Executor::new(request);
 
-    let cancel = CancellationToken::new();
+    let progress_step = args.branch_idx.zip(args.step_name);
+    let report_step = |state: StepState| {
+        if let Some((branch, step)) = &progress_step {
+            record_progress(&job_root.join(dirs::REPX), |p| {
+                p.set_step(*branch, step, state)
+            });
+        }
+    };
+    report_step(StepState::Running);
+
+    let cancel = super::cancel_on_sigterm();
+    let started = Instant::now();
     let result = executor
         .execute_script(&script_path, &exec_args, &cancel)
         .await;
+    let elapsed = started.elapsed();
+
+    if let (Ok(_), Some(scratch)) = (&result, &scratch) {
+        if let Err(e) = scratch.copy_back(&user_out_dir) {
+            write_marker(&repx_dir.join(markers::FAIL))?;
+            report_step(StepState::Failed);
+            return Err(CliError::execution_failed(
+                "Failed to copy outputs back from node-local scratch",
+                e.to_string(),
+            ));
+        }
+    }
+    drop(scratch);
+    report_step(if result.is_ok() {
+        StepState::Succeeded
+    } else {
+        StepState::Failed
+    });
 
     let outcome_cache = FsCache::new(base_path.clone());
     let outcome_key = CacheKey::JobOutcome {
@@ -114,27 +231,72 @@ async fn async_handle_execute(args: InternalExecuteArgs) -> Result<(), CliError>
             if let Err(e) = outcome_cache.mark_ready(&outcome_key, meta) {
                 tracing::debug!("Failed to write cache metadata for job outcome: {}", e);
             }
-            if let Err(e) = completion_log::append_completion(&base_path, &job_id, true) {
+            if let Err(e) =
+                completion_log::append_completion(&base_path, &job_id, true, Some(elapsed))
+            {
                 tracing::debug!("Failed to append to completion log: {}", e);
             }
             tracing::info!("Job '{}' completed successfully.", job_id);
         }
+        Err(e @ ExecutorError::TimedOut { .. }) => {
+            write_marker(&repx_dir.join(markers::TIMEOUT))?;
+            let meta = CacheMetadata::new(&outcome_key, format!("job '{}' timed out", job_id));
+            if let Err(err) = outcome_cache.mark_ready(&outcome_key, meta) {
+                tracing::debug!("Failed to write cache metadata for job outcome: {}", err);
+            }
+            if let Err(err) = completion_log::append_timeout(&base_path, &job_id, Some(elapsed)) {
+                tracing::debug!("Failed to append to completion log: {}", err);
+            }
+            tracing::error!("{}", e);
+
+            eprintln!("{}", e);
+            return Err(CliError::ExecutionFailed {
+                message: "Execution timed out".to_string(),
+                log_path: Some(repx_dir.join(logs::STDERR)),
+                log_summary: e.to_string(),
+            });
+        }
+        Err(e @ ExecutorError::Cancelled { .. }) if repx_dir.join(markers::CANCELLED).exists() => {
+            tracing::warn!("{}", e);
+            return Err(CliError::ExecutionFailed {
+                message: "Execution cancelled".to_string(),
+                log_path: Some(repx_dir.join(logs::STDERR)),
+                log_summary: e.to_string(),
+            });
+        }
         Err(e) => {
-            write_marker(&repx_dir.join(markers::FAIL))?;
+            let failure = FailureInfo {
+                exit_code: e.exit_code(),
+                class: e.failure_class(&args.failure_rules),
+                summary: Some(e.summary()),
+            };
+            failure.write_marker(&repx_dir)?;
             let meta = CacheMetadata::new(&outcome_key, format!("job '{}' failed", job_id));
             if let Err(err) = outcome_cache.mark_ready(&outcome_key, meta) {
                 tracing::debug!("Failed to write cache metadata for job outcome: {}", err);
             }
-            if let Err(err) = completion_log::append_completion(&base_path, &job_id, false) {
+            if let Err(err) =
+                completion_log::append_failure(&base_path, &job_id, failure.class, Some(elapsed))
+            {
                 tracing::debug!("Failed to append to completion log: {}", err);
             }
             let err_msg = format!("Job '{}' failed: {}", job_id, e);
             tracing::error!("{}", err_msg);
 
             eprintln!("{}", err_msg);
+            let (message, log_path) = match &e {
+                ExecutorError::HookFailed { hook, log_path, .. } => {
+                    (format!("The {} hook failed", hook), log_path.clone())
+                }
+                ExecutorError::ScriptFailed { oom: Some(oom), .. } => (
+                    format!("Execution failed: {}", oom),
+                    repx_dir.join(logs::STDERR),
+                ),
+                _ => ("Execution failed".to_string(), repx_dir.join(logs::STDERR)),
+            };
             return Err(CliError::ExecutionFailed {
-                message: "Execution failed".to_string(),
-                log_path: Some(repx_dir.join(logs::STDERR)),
+                message,
+                log_path: Some(log_path),
                 log_summary: e.to_string(),
             });
         }
@@ -0,0 +1,264 @@
+use crate::cli::{ExportArgs, ExportFormat};
+use crate::commands::records::{serialization_error, stage_name};
+use crate::commands::show::get_store_path;
+use crate::commands::trace::compute_all_effective_params;
+use crate::error::CliError;
+use repx_core::{
+    config,
+    constants::dirs,
+    model::{JobId, Lab},
+    store::outcomes::{get_job_outcomes, JobOutcome},
+};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+const FIXED_COLUMNS: [&str; 3] = ["job_id", "stage", "runs"];
+
+type Row = BTreeMap<String, Value>;
+
+fn flatten_into(prefix: &str, value: &Value, row: &mut Row) {
+    match value {
+        Value::Object(map) => {
+            for (key, nested) in map {
+                flatten_into(&format!("{}.{}", prefix, key), nested, row);
+            }
+        }
+        other => {
+            row.insert(prefix.to_string(), other.clone());
+        }
+    }
+}
+
+fn parse_cell(cell: &str) -> Value {
+    cell.parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(cell.to_string()))
+}
+
+fn extract_metrics(path: &Path) -> Option<Value> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => {
+            let content = fs::read_to_string(path).ok()?;
+            match serde_json::from_str::<Value>(&content) {
+                Ok(value @ Value::Object(_)) => Some(value),
+                Ok(_) => None,
+                Err(e) => {
+                    tracing::debug!("Skipping unparsable metrics in {}: {}", path.display(), e);
+                    None
+                }
+            }
+        }
+        Some("csv") => {
+            let mut reader = csv::Reader::from_path(path).ok()?;
+            let headers = reader.headers().ok()?.clone();
+            let mut records = reader.records();
+            let record = records.next()?.ok()?;
+            if records.next().is_some() {
+                tracing::debug!(
+                    "Skipping multi-row CSV output {} for metric extraction",
+                    path.display()
+                );
+                return None;
+            }
+            let map: Map<String, Value> = headers
+                .iter()
+                .zip(record.iter())
+                .map(|(header, cell)| (header.to_string(), parse_cell(cell)))
+                .collect();
+            Some(Value::Object(map))
+        }
+        _ => None,
+    }
+}
+
+fn build_row(lab: &Lab, job_id: &JobId, out_dir: &Path, params: &Value, with_metrics: bool) -> Row {
+    let mut row = Row::new();
+    row.insert("job_id".to_string(), Value::String(job_id.to_string()));
+    row.insert("stage".to_string(), Value::String(stage_name(job_id)));
+    let mut runs: Vec<String> = lab
+        .runs
+        .iter()
+        .filter(|(_, run)| run.jobs.contains(job_id))
+        .map(|(run_id, _)| run_id.to_string())
+        .collect();
+    runs.sort();
+    row.insert("runs".to_string(), Value::String(runs.join(";")));
+    flatten_into("params", params, &mut row);
+
+    let Some(job) = lab.jobs.get(job_id) else {
+        return row;
+    };
+    let out_str = out_dir.to_string_lossy();
+    for exe in job.executables.values() {
+        for (name, template) in &exe.outputs {
+            let Some(template) = template.as_str() else {
+                continue;
+            };
+            let path = template.replace("$out", &out_str);
+            if !Path::new(&path).exists() {
+                continue;
+            }
+            let column = format!("outputs.{}", name);
+            if with_metrics {
+                if let Some(metrics) = extract_metrics(Path::new(&path)) {
+                    flatten_into(&column, &metrics, &mut row);
+                }
+            }
+            row.insert(column, Value::String(path));
+        }
+    }
+    row
+}
+
+fn columns(rows: &[Row]) -> Vec<String> {
+    let dynamic: BTreeSet<&String> = rows
+        .iter()
+        .flat_map(|row| row.keys())
+        .filter(|key| !FIXED_COLUMNS.contains(&key.as_str()))
+        .collect();
+    FIXED_COLUMNS
+        .iter()
+        .map(|column| column.to_string())
+        .chain(dynamic.into_iter().cloned())
+        .collect()
+}
+
+fn cell_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn write_csv(rows: &[Row], writer: impl Write) -> Result<(), CliError> {
+    let columns = columns(rows);
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    csv_writer
+        .write_record(&columns)
+        .map_err(|e| serialization_error("CSV", e))?;
+    for row in rows {
+        csv_writer
+            .write_record(columns.iter().map(|column| cell_text(row.get(column))))
+            .map_err(|e| serialization_error("CSV", e))?;
+    }
+    csv_writer
+        .flush()
+        .map_err(|e| serialization_error("CSV", e))?;
+    Ok(())
+}
+
+pub fn handle_export(args: ExportArgs, lab: &Lab, target: Option<&str>) -> Result<(), CliError> {
+    let job_ids: BTreeSet<JobId> = if args.specs.is_empty() {
+        lab.jobs.keys().cloned().collect()
+    } else {
+        super::resolve_spec_job_ids(lab, &args.specs)?
+    };
+    let job_ids: Vec<JobId> = job_ids
+        .into_iter()
+        .filter(|job_id| {
+            args.stage
+                .as_deref()
+                .is_none_or(|stage| stage_name(job_id) == stage)
+        })
+        .collect();
+
+    let config = config::load_config()?;
+    let store_path = get_store_path(&config, target)?;
+    let outcomes = get_job_outcomes(&store_path, &job_ids)?;
+    let all_params = compute_all_effective_params(lab);
+
+    let rows: Vec<Row> = job_ids
+        .iter()
+        .filter(|job_id| {
+            outcomes
+                .get(*job_id)
+                .is_some_and(|found| found.outcome == JobOutcome::Succeeded)
+        })
+        .map(|job_id| {
+            let out_dir = outcomes[job_id].path.join(dirs::OUT);
+            let params = all_params.get(job_id).unwrap_or(&Value::Null);
+            build_row(lab, job_id, &out_dir, params, args.metrics)
+        })
+        .collect();
+
+    if rows.is_empty() {
+        eprintln!("No succeeded jobs to export.");
+    }
+
+    let mut writer: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    match args.format {
+        ExportFormat::Csv => write_csv(&rows, &mut writer)?,
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, &rows)?;
+            writeln!(writer)?;
+        }
+    }
+    if let Some(path) = &args.output {
+        eprintln!("Exported {} job(s) to {}", rows.len(), path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_extract_metrics_from_json_and_single_row_csv() {
+        let dir = tempdir().expect("tempdir");
+        let json_path = dir.path().join("metrics.json");
+        fs::write(&json_path, r#"{"accuracy": 0.91, "loss": {"final": 0.2}}"#).expect("write json");
+        let csv_path = dir.path().join("summary.csv");
+        fs::write(&csv_path, "runtime,solver\n12.5,cg\n").expect("write csv");
+        let table_path = dir.path().join("table.csv");
+        fs::write(&table_path, "x,y\n1,2\n3,4\n").expect("write table");
+
+        let mut row = Row::new();
+        flatten_into(
+            "outputs.metrics",
+            &extract_metrics(&json_path).expect("json metrics"),
+            &mut row,
+        );
+        flatten_into(
+            "outputs.summary",
+            &extract_metrics(&csv_path).expect("csv metrics"),
+            &mut row,
+        );
+        assert_eq!(row["outputs.metrics.accuracy"], serde_json::json!(0.91));
+        assert_eq!(row["outputs.metrics.loss.final"], serde_json::json!(0.2));
+        assert_eq!(row["outputs.summary.runtime"], serde_json::json!(12.5));
+        assert_eq!(row["outputs.summary.solver"], serde_json::json!("cg"));
+        assert!(extract_metrics(&table_path).is_none());
+    }
+
+    #[test]
+    fn test_csv_columns_union_with_fixed_columns_first() {
+        let mut first = Row::new();
+        first.insert("job_id".to_string(), Value::String("a".to_string()));
+        first.insert("params.lr".to_string(), serde_json::json!(0.1));
+        let mut second = Row::new();
+        second.insert("job_id".to_string(), Value::String("b".to_string()));
+        second.insert("params.seed".to_string(), serde_json::json!(7));
+
+        let mut buf = Vec::new();
+        write_csv(&[first, second], &mut buf).expect("write csv");
+        let text = String::from_utf8(buf).expect("utf8");
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next(),
+            Some("job_id,stage,runs,params.lr,params.seed")
+        );
+        assert_eq!(lines.next(), Some("a,,,0.1,"));
+        assert_eq!(lines.next(), Some("b,,,,7"));
+    }
+}
@@ -0,0 +1,147 @@
+use crate::cli::{FetchArgs, StatusFilter};
+use crate::commands::list::status_matches_filter;
+use crate::commands::AppContext;
+use crate::error::CliError;
+use repx_core::{
+    engine::{self, JobStatus},
+    errors::DomainError,
+    model::JobId,
+};
+use std::collections::{BTreeSet, HashMap};
+use wildmatch::WildMatch;
+
+fn is_selected(
+    job_id: &JobId,
+    status: Option<&JobStatus>,
+    status_filters: &[StatusFilter],
+    globs: &[WildMatch],
+) -> bool {
+    let status_ok = status.is_some_and(|status| {
+        status_filters
+            .iter()
+            .any(|filter| status_matches_filter(status, filter))
+    });
+    let glob_ok = globs.is_empty() || globs.iter().any(|glob| glob.matches(job_id.as_str()));
+    status_ok && glob_ok
+}
+
+fn select_jobs(
+    candidates: BTreeSet<JobId>,
+    statuses: &HashMap<JobId, JobStatus>,
+    status_filters: &[StatusFilter],
+    globs: &[String],
+) -> Vec<JobId> {
+    let globs: Vec<WildMatch> = globs.iter().map(|glob| WildMatch::new(glob)).collect();
+    candidates
+        .into_iter()
+        .filter(|job_id| is_selected(job_id, statuses.get(job_id), status_filters, &globs))
+        .collect()
+}
+
+pub fn handle_fetch(args: FetchArgs, context: &AppContext) -> Result<(), CliError> {
+    let status_filters = if args.status.is_empty() {
+        vec![StatusFilter::Succeeded]
+    } else {
+        args.status
+    };
+
+    let lab = context.client.lab();
+    let mut job_ids: BTreeSet<JobId> = if args.specs.is_empty() {
+        lab.jobs.keys().cloned().collect()
+    } else {
+        super::resolve_spec_job_ids(lab, &args.specs)?
+    };
+    if !args.run.is_empty() {
+        let in_runs = super::resolve_spec_job_ids(lab, &args.run)?;
+        job_ids.retain(|job_id| in_runs.contains(job_id));
+    }
+
+    let target_name = context.submission_target;
+    if context.client.get_target(target_name).is_none() {
+        return Err(CliError::Domain(DomainError::TargetNotFound(
+            target_name.to_string(),
+        )));
+    }
+    let raw_statuses = context
+        .client
+        .get_statuses_for_active_target(target_name, None)?;
+    let statuses = engine::determine_job_statuses(lab, raw_statuses);
+    let job_ids = select_jobs(job_ids, &statuses, &status_filters, &args.globs);
+
+    if job_ids.is_empty() {
+        println!("No job outputs matched.");
+        return Ok(());
+    }
+
+    if args.dry_run {
+        for job_id in &job_ids {
+            println!(
+                "Would fetch {} -> {}",
+                job_id,
+                args.output.join(job_id.as_str()).display()
+            );
+        }
+        println!(
+            "Would fetch the outputs of {} job(s) from '{}'.",
+            job_ids.len(),
+            target_name
+        );
+        return Ok(());
+    }
+
+    let report = context
+        .client
+        .fetch_outputs(&job_ids, target_name, &args.output)?;
+    for (job_id, e) in &report.failed {
+        eprintln!("Failed to fetch {}: {}", job_id, e);
+    }
+    println!(
+        "Fetched the outputs of {} job(s) from '{}' into {}.",
+        report.fetched.len(),
+        target_name,
+        args.output.display()
+    );
+    if !report.failed.is_empty() {
+        return Err(CliError::execution_failed(
+            format!("Failed to fetch {} job(s)", report.failed.len()),
+            format!("from target '{}'", target_name),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_jobs_by_status_and_glob() {
+        let succeeded = JobStatus::Succeeded {
+            location: "local".to_string(),
+        };
+        let statuses: HashMap<JobId, JobStatus> = [
+            (JobId::from("aaa-train-1.0"), succeeded.clone()),
+            (JobId::from("bbb-eval-1.0"), succeeded),
+            (JobId::from("ccc-train-1.0"), JobStatus::Running),
+        ]
+        .into_iter()
+        .collect();
+        let candidates: BTreeSet<JobId> = statuses.keys().cloned().collect();
+
+        let selected = select_jobs(
+            candidates.clone(),
+            &statuses,
+            &[StatusFilter::Succeeded],
+            &["*-train-*".to_string()],
+        );
+        assert_eq!(selected, vec![JobId::from("aaa-train-1.0")]);
+
+        let selected = select_jobs(
+            candidates,
+            &statuses,
+            &[StatusFilter::Succeeded, StatusFilter::Running],
+            &[],
+        );
+        assert_eq!(selected.len(), 3);
+    }
+}
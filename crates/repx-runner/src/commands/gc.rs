@@ -3,11 +3,29 @@ use crate::cli::{
 };
 use crate::commands::AppContext;
 use crate::error::CliError;
-use repx_core::{config::Config, constants::dirs, errors::DomainError, fs_utils, lab, resolver};
-use std::collections::HashSet;
+use repx_core::{
+    cache::{CacheKey, CacheStore, FsCache},
+    config::Config,
+    constants::dirs,
+    errors::DomainError,
+    fs_utils, lab, resolver,
+    store::cas,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const LAB_METADATA_SUFFIX: &str = "-lab-metadata.json";
+const UNATTRIBUTED: &str = "(unattributed)";
+const SHARED: &str = "(shared)";
+const AGE_BUCKETS: [(&str, u64); 4] = [
+    ("<1d", 86_400),
+    ("1-7d", 7 * 86_400),
+    ("7-30d", 30 * 86_400),
+    (">30d", u64::MAX),
+];
 
 pub fn handle_gc_dispatch(
     args: GcArgs,
@@ -393,6 +411,116 @@ fn handle_gc_unpin(
     Ok(())
 }
 
+#[derive(Default)]
+struct DeadLabOwners {
+    jobs: HashMap<String, String>,
+    artifacts: HashMap<PathBuf, String>,
+}
+
+impl DeadLabOwners {
+    fn scan(artifacts_dir: &Path, live_artifacts: &HashSet<PathBuf>) -> Self {
+        let mut owners = Self::default();
+        let Ok(entries) = fs::read_dir(artifacts_dir.join("lab")) else {
+            return owners;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let file_name = entry.file_name();
+            let name = file_name.to_string_lossy();
+            let Some(hash) = name.strip_suffix(LAB_METADATA_SUFFIX) else {
+                continue;
+            };
+            let rel = PathBuf::from("lab").join(&file_name);
+            if live_artifacts.contains(&rel) {
+                continue;
+            }
+            owners.claim_artifact(rel, hash);
+            let Ok(lab) = lab::load_from_path_unchecked(&entry.path()) else {
+                continue;
+            };
+            for job_id in lab.jobs.keys() {
+                Self::claim(&mut owners.jobs, job_id.as_str().to_owned(), hash);
+            }
+            for ref_file in &lab.referenced_files {
+                let mut prefix = PathBuf::new();
+                for component in ref_file.components().take(2) {
+                    prefix.push(component);
+                    owners.claim_artifact(prefix.clone(), hash);
+                }
+                owners.claim_artifact(ref_file.clone(), hash);
+            }
+        }
+        owners
+    }
+
+    fn claim<K: std::hash::Hash + Eq>(map: &mut HashMap<K, String>, key: K, hash: &str) {
+        map.entry(key)
+            .and_modify(|owner| {
+                if owner != hash {
+                    *owner = SHARED.to_string();
+                }
+            })
+            .or_insert_with(|| hash.to_string());
+    }
+
+    fn claim_artifact(&mut self, rel: PathBuf, hash: &str) {
+        Self::claim(&mut self.artifacts, rel, hash);
+    }
+
+    fn job(&self, job_id: &str) -> &str {
+        self.jobs.get(job_id).map_or(UNATTRIBUTED, String::as_str)
+    }
+
+    fn artifact(&self, rel: &Path) -> &str {
+        self.artifacts.get(rel).map_or(UNATTRIBUTED, String::as_str)
+    }
+}
+
+fn age_bucket(age: Duration) -> usize {
+    let secs = age.as_secs();
+    AGE_BUCKETS
+        .iter()
+        .position(|(_, limit)| secs < *limit)
+        .unwrap_or(AGE_BUCKETS.len() - 1)
+}
+
+#[derive(Default)]
+struct ReclaimReport {
+    groups: BTreeMap<(String, usize), (u64, u64)>,
+}
+
+impl ReclaimReport {
+    fn add(&mut self, lab: &str, path: &Path, size: u64, now: SystemTime) {
+        let age = fs::symlink_metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        let group = self
+            .groups
+            .entry((lab.to_string(), age_bucket(age)))
+            .or_default();
+        group.0 += 1;
+        group.1 += size;
+    }
+
+    fn print(&self) {
+        if self.groups.is_empty() {
+            return;
+        }
+        println!();
+        println!("{:<20} {:>6} {:>8} {:>12}", "LAB", "AGE", "ITEMS", "SIZE");
+        for ((lab, bucket), (items, bytes)) in &self.groups {
+            println!(
+                "{:<20} {:>6} {:>8} {:>12}",
+                truncate(lab, 20),
+                AGE_BUCKETS[*bucket].0,
+                items,
+                fs_utils::format_bytes(*bytes, false)
+            );
+        }
+    }
+}
+
 pub async fn async_handle_internal_gc(args: InternalGcArgs) -> Result<(), CliError> {
     let base_path = args.base_path;
     let dry_run = args.dry_run;
@@ -490,6 +618,14 @@ pub async fn async_handle_internal_gc(args: InternalGcArgs) -> Result<(), CliErr
     let mut deleted_outputs: u64 = 0;
     let mut freed_bytes: u64 = 0;
 
+    let owners = if dry_run {
+        DeadLabOwners::scan(&artifacts_dir, &live_artifacts)
+    } else {
+        DeadLabOwners::default()
+    };
+    let mut report = ReclaimReport::default();
+    let now = SystemTime::now();
+
     if artifacts_dir.exists() {
         let collection_dirs = [
             "host-tools",
@@ -513,7 +649,7 @@ pub async fn async_handle_internal_gc(args: InternalGcArgs) -> Result<(), CliErr
             let name_str = name.to_string_lossy();
             let name_path = PathBuf::from(&name);
 
-            if name_str == "bin" {
+            if name_str == "bin" || name_str == cas::CAS_DIR {
                 continue;
             }
 
@@ -540,6 +676,7 @@ pub async fn async_handle_internal_gc(args: InternalGcArgs) -> Result<(), CliErr
                                     );
                                     deleted_artifacts += 1;
                                     freed_bytes += size;
+                                    report.add(owners.artifact(&sub_rel), &sub.path(), size, now);
                                 } else {
                                     tracing::info!("Deleting unused artifact: {:?}", sub_rel);
                                     if force_remove_no_parent(&sub.path()) {
@@ -569,6 +706,7 @@ pub async fn async_handle_internal_gc(args: InternalGcArgs) -> Result<(), CliErr
                         );
                         deleted_artifacts += 1;
                         freed_bytes += size;
+                        report.add(owners.artifact(Path::new(&name)), &entry.path(), size, now);
                     } else {
                         tracing::info!("Deleting unused artifact: {:?}", name);
                         if force_remove_no_parent(&entry.path()) {
@@ -579,6 +717,35 @@ pub async fn async_handle_internal_gc(args: InternalGcArgs) -> Result<(), CliErr
                 }
             });
         }
+
+        // A dry run cannot tell which blobs the sweep above would orphan, so it
+        // lists only those that are already unreferenced.
+        for blob in cas::unreferenced_blobs(&artifacts_dir) {
+            if dry_run {
+                let size = path_size(&blob);
+                tracing::info!(
+                    "[dry-run] Would delete unreferenced blob: {:?} ({})",
+                    blob,
+                    fs_utils::format_bytes(size, false)
+                );
+                deleted_artifacts += 1;
+                freed_bytes += size;
+            } else if force_remove_no_parent(&blob) {
+                tracing::info!("Deleted unreferenced blob: {:?}", blob);
+                deleted_artifacts += 1;
+            }
+        }
+    }
+
+    // The sync manifest would still list what was just deleted, and the
+    // next sync would skip sending it again.
+    if !dry_run && deleted_artifacts > 0 {
+        let manifest = FsCache::new(base_path.clone()).path(&CacheKey::SyncManifest);
+        if let Err(e) = fs::remove_file(&manifest) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove sync manifest {:?}: {}", manifest, e);
+            }
+        }
     }
 
     if outputs_dir.exists() {
@@ -603,6 +770,12 @@ pub async fn async_handle_internal_gc(args: InternalGcArgs) -> Result<(), CliErr
                         );
                         deleted_outputs += 1;
                         freed_bytes += size;
+                        report.add(
+                            owners.job(&name.to_string_lossy()),
+                            &entry.path(),
+                            size,
+                            now,
+                        );
                     } else {
                         tracing::info!("Deleting unused output: {:?}", name);
                         if force_remove_no_parent(&entry.path()) {
@@ -625,6 +798,7 @@ pub async fn async_handle_internal_gc(args: InternalGcArgs) -> Result<(), CliErr
                 deleted_outputs,
                 fs_utils::format_bytes(freed_bytes, false)
             );
+            report.print();
         }
     } else if deleted_artifacts == 0 && deleted_outputs == 0 {
         println!("Nothing to collect.");
@@ -640,7 +814,7 @@ pub async fn async_handle_internal_gc(args: InternalGcArgs) -> Result<(), CliErr
     Ok(())
 }
 
-fn force_remove_no_parent(path: &std::path::Path) -> bool {
+pub(crate) fn force_remove_no_parent(path: &std::path::Path) -> bool {
     let result = if path.is_dir() {
         for entry in walkdir::WalkDir::new(path)
             .into_iter()
@@ -665,7 +839,7 @@ fn force_remove_no_parent(path: &std::path::Path) -> bool {
     }
 }
 
-fn with_writable_dir<F>(dir: &std::path::Path, f: F)
+pub(crate) fn with_writable_dir<F>(dir: &std::path::Path, f: F)
 where
     F: FnOnce(),
 {
@@ -677,7 +851,7 @@ where
     }
 }
 
-fn path_size(path: &std::path::Path) -> u64 {
+pub(crate) fn path_size(path: &std::path::Path) -> u64 {
     if !path.exists() {
         return 0;
     }
@@ -0,0 +1,73 @@
+use crate::cli::{ImagesArgs, ImagesCommand, ImagesPruneArgs, InternalImagesPruneArgs};
+use crate::commands::AppContext;
+use crate::error::CliError;
+use repx_core::{errors::DomainError, fs_utils, logging::Verbosity};
+
+pub fn handle_images(
+    args: ImagesArgs,
+    context: &AppContext,
+    verbose: Verbosity,
+) -> Result<(), CliError> {
+    match args.command {
+        ImagesCommand::Prune(prune_args) => handle_images_prune(prune_args, context, verbose),
+    }
+}
+
+fn handle_images_prune(
+    args: ImagesPruneArgs,
+    context: &AppContext,
+    verbose: Verbosity,
+) -> Result<(), CliError> {
+    let target_name = args.target.as_deref().unwrap_or(context.submission_target);
+
+    let target = context
+        .client
+        .get_target(target_name)
+        .ok_or_else(|| CliError::Domain(DomainError::TargetNotFound(target_name.to_string())))?;
+
+    let output = target
+        .prune_images(args.max_size, args.dry_run, verbose)
+        .map_err(|e| {
+            CliError::execution_failed("Failed to prune images on target", e.to_string())
+        })?;
+    let output = output.trim();
+    if !output.is_empty() {
+        println!("{}", output);
+    }
+    Ok(())
+}
+
+pub fn handle_internal_images_prune(args: InternalImagesPruneArgs) -> Result<(), CliError> {
+    let max_bytes = args.max_bytes.unwrap_or(0);
+    let verb = if args.dry_run {
+        "Would remove"
+    } else {
+        "Removed"
+    };
+
+    for cache_dir in &args.cache_dir {
+        if !cache_dir.exists() {
+            continue;
+        }
+        let report = repx_executor::prune_rootfs_cache(cache_dir, max_bytes, None, args.dry_run)
+            .map_err(|e| {
+                CliError::execution_failed("Failed to prune image cache", e.to_string())
+            })?;
+
+        for image in &report.removed {
+            println!("{} rootfs {}", verb, image);
+        }
+        for image in &report.in_use {
+            println!("Kept rootfs {} (in use)", image);
+        }
+        println!(
+            "{}: {} {} image(s), freed {}, {} remaining.",
+            cache_dir.display(),
+            verb.to_lowercase(),
+            report.removed.len(),
+            fs_utils::format_bytes(report.freed_bytes, false),
+            fs_utils::format_bytes(report.remaining_bytes, false),
+        );
+    }
+    Ok(())
+}
@@ -1,7 +1,10 @@
 use crate::{cli::InternalOrchestrateArgs, error::CliError};
+use repx_client::slurm_rest;
 use repx_core::{
+    config::SlurmRestConfig,
+    constants::sge,
     errors::CoreError,
-    model::{JobId, StageType},
+    model::{JobId, SchedulerType, StageType},
     protocol::{self, StreamJob, StreamJobResult, StreamJobType},
 };
 use std::collections::{HashMap, HashSet};
@@ -10,9 +13,9 @@ use std::process::{Command, Stdio};
 
 fn submit_via_sbatch_stdin(
     script: &str,
-    deps: &[u32],
-    anchor_id: Option<u32>,
-) -> Result<u32, CliError> {
+    deps: &[u64],
+    anchor_id: Option<u64>,
+) -> Result<u64, CliError> {
     let mut sbatch_cmd = Command::new("sbatch");
     sbatch_cmd.arg("--parsable");
 
@@ -54,7 +57,7 @@ fn submit_via_sbatch_stdin(
     }
 
     let id_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    id_str.parse::<u32>().map_err(|_| {
+    id_str.parse::<u64>().map_err(|_| {
         CliError::execution_failed(
             "Failed to parse SLURM ID from sbatch output",
             format!("sbatch output was: '{}'", id_str),
@@ -62,7 +65,7 @@ fn submit_via_sbatch_stdin(
     })
 }
 
-fn submit_anchor(job_id: &str) -> Result<u32, CliError> {
+fn submit_anchor(job_id: &str) -> Result<u64, CliError> {
     let mut cmd = Command::new("sbatch");
     cmd.arg("--parsable")
         .arg("--hold")
@@ -85,7 +88,7 @@ fn submit_anchor(job_id: &str) -> Result<u32, CliError> {
     }
 
     let id_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    id_str.parse::<u32>().map_err(|_| {
+    id_str.parse::<u64>().map_err(|_| {
         CliError::execution_failed(
             format!("Failed to parse Anchor ID for job '{}'", job_id),
             id_str.clone(),
@@ -93,7 +96,269 @@ fn submit_anchor(job_id: &str) -> Result<u32, CliError> {
     })
 }
 
-fn handle_stream_orchestrate() -> Result<(), CliError> {
+const REST_TOKEN_LIFESPAN_SECS: u64 = 24 * 60 * 60;
+
+struct RestSubmitter {
+    rest: SlurmRestConfig,
+    token: String,
+    environment: Vec<String>,
+}
+
+impl RestSubmitter {
+    fn new(rest: SlurmRestConfig) -> Result<Self, CliError> {
+        let token = match std::env::var(rest.token_env()) {
+            Ok(token) if !token.is_empty() => token,
+            _ => {
+                let output = Command::new("scontrol")
+                    .arg("token")
+                    .arg(format!("lifespan={}", REST_TOKEN_LIFESPAN_SECS))
+                    .output()
+                    .map_err(|e| {
+                        CliError::execution_failed("Failed to run scontrol token", e.to_string())
+                    })?;
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .find_map(|line| line.trim().strip_prefix("SLURM_JWT="))
+                    .map(str::to_string)
+                    .ok_or_else(|| {
+                        CliError::execution_failed(
+                            format!(
+                                "No slurmrestd token: set {} or allow `scontrol token`",
+                                rest.token_env()
+                            ),
+                            String::from_utf8_lossy(&output.stderr).to_string(),
+                        )
+                    })?
+            }
+        };
+        let environment = std::env::vars()
+            .filter(|(name, _)| name != rest.token_env())
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        Ok(Self {
+            rest,
+            token,
+            environment,
+        })
+    }
+
+    fn submit(&self, request: &serde_json::Value) -> Result<u64, CliError> {
+        let command = slurm_rest::request_command(&self.rest, "POST", "job/submit", true);
+        let mut child = Command::new("sh")
+            .args(["-c", &command])
+            .env(self.rest.token_env(), &self.token)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| CliError::execution_failed("Failed to spawn curl", e.to_string()))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(slurm_rest::body_config(request).as_bytes())
+                .map_err(|e| {
+                    CliError::execution_failed("Failed to write request to curl", e.to_string())
+                })?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| CliError::execution_failed("curl process failed", e.to_string()))?;
+        if !output.status.success() {
+            return Err(CliError::execution_failed(
+                "slurmrestd request failed",
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            ));
+        }
+        Ok(slurm_rest::parse_submit_response(
+            &String::from_utf8_lossy(&output.stdout),
+        )?)
+    }
+
+    fn submit_script(
+        &self,
+        script: &str,
+        deps: &[u64],
+        anchor_id: Option<u64>,
+    ) -> Result<u64, CliError> {
+        self.submit(&slurm_rest::submit_request(
+            script,
+            deps,
+            anchor_id,
+            &self.environment,
+        )?)
+    }
+
+    fn submit_anchor(&self, job_id: &str) -> Result<u64, CliError> {
+        self.submit(&slurm_rest::anchor_request(job_id, &self.environment))
+    }
+}
+
+pub(crate) fn parse_qsub_job_id(output: &str) -> Option<u64> {
+    let trimmed = output.trim();
+    let digits = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(trimmed, |end| &trimmed[..end]);
+    digits.parse().ok()
+}
+
+fn submit_via_qsub_stdin(
+    script: &str,
+    deps: &[u64],
+    anchor_id: Option<u64>,
+) -> Result<u64, CliError> {
+    let mut qsub_cmd = Command::new("qsub");
+    qsub_cmd.arg("-terse");
+
+    if !deps.is_empty() {
+        let dep_str: Vec<String> = deps.iter().map(|d| d.to_string()).collect();
+        qsub_cmd.arg("-hold_jid").arg(dep_str.join(","));
+    }
+
+    if let Some(aid) = anchor_id {
+        qsub_cmd.arg("-v").arg(format!("REPX_ANCHOR_ID={}", aid));
+    }
+
+    qsub_cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = qsub_cmd
+        .spawn()
+        .map_err(|e| CliError::execution_failed("Failed to spawn qsub", e.to_string()))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(script.as_bytes()).map_err(|e| {
+            CliError::execution_failed("Failed to write script to qsub stdin", e.to_string())
+        })?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| CliError::execution_failed("qsub process failed", e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CliError::execution_failed(
+            "qsub command failed",
+            stderr.to_string(),
+        ));
+    }
+
+    let id_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    parse_qsub_job_id(&id_str).ok_or_else(|| {
+        CliError::execution_failed(
+            "Failed to parse job number from qsub output",
+            format!("qsub output was: '{}'", id_str),
+        )
+    })
+}
+
+fn submit_qsub_anchor(job_id: &str) -> Result<u64, CliError> {
+    let output = Command::new("qsub")
+        .args(["-terse", "-h", "-b", "y", "-N"])
+        .arg(format!("{}anchor-{}", sge::JOB_NAME_PREFIX, job_id))
+        .args([
+            "-l",
+            "h_rt=60",
+            "-o",
+            "/dev/null",
+            "-e",
+            "/dev/null",
+            "true",
+        ])
+        .output()
+        .map_err(|e| CliError::execution_failed("Failed to submit anchor job", e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CliError::execution_failed(
+            format!("Failed to submit anchor for job '{}'", job_id),
+            stderr.to_string(),
+        ));
+    }
+
+    let id_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    parse_qsub_job_id(&id_str).ok_or_else(|| {
+        CliError::execution_failed(
+            format!("Failed to parse Anchor ID for job '{}'", job_id),
+            id_str.clone(),
+        )
+    })
+}
+
+const F58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+pub(crate) fn parse_flux_job_id(output: &str) -> Option<u64> {
+    let trimmed = output.trim();
+    if let Some(hex) = trimmed.strip_prefix("0x") {
+        return u64::from_str_radix(hex, 16).ok();
+    }
+    let Some(f58) = trimmed
+        .strip_prefix('ƒ')
+        .or_else(|| trimmed.strip_prefix('f'))
+    else {
+        return trimmed.parse().ok();
+    };
+    if f58.is_empty() {
+        return None;
+    }
+    f58.chars().try_fold(0u64, |id, c| {
+        let digit = F58_ALPHABET.find(c)? as u64;
+        id.checked_mul(58)?.checked_add(digit)
+    })
+}
+
+fn submit_via_flux_stdin(script: &str, deps: &[u64]) -> Result<u64, CliError> {
+    let mut flux_cmd = Command::new("flux");
+    flux_cmd.arg("batch");
+    for dep in deps {
+        flux_cmd.arg(format!("--dependency=afterok:{}", dep));
+    }
+
+    flux_cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = flux_cmd
+        .spawn()
+        .map_err(|e| CliError::execution_failed("Failed to spawn flux batch", e.to_string()))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(script.as_bytes()).map_err(|e| {
+            CliError::execution_failed("Failed to write script to flux batch stdin", e.to_string())
+        })?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| CliError::execution_failed("flux batch process failed", e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CliError::execution_failed(
+            "flux batch command failed",
+            stderr.to_string(),
+        ));
+    }
+
+    let id_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    parse_flux_job_id(&id_str).ok_or_else(|| {
+        CliError::execution_failed(
+            "Failed to parse job ID from flux batch output",
+            format!("flux batch output was: '{}'", id_str),
+        )
+    })
+}
+
+fn handle_stream_orchestrate(
+    scheduler: SchedulerType,
+    rest: Option<SlurmRestConfig>,
+) -> Result<(), CliError> {
+    let rest = match rest {
+        Some(rest) if scheduler == SchedulerType::Slurm => Some(RestSubmitter::new(rest)?),
+        _ => None,
+    };
     let stdin = io::stdin();
     let reader = BufReader::new(stdin.lock());
     let stdout = io::stdout();
@@ -140,13 +405,38 @@ fn handle_stream_orchestrate() -> Result<(), CliError> {
             )))
         })?;
 
-        let anchor_id = if job.job_type == StreamJobType::ScatterGather {
-            Some(submit_anchor(&job.id)?)
-        } else {
-            None
+        if scheduler == SchedulerType::Flux {
+            if job.job_type == StreamJobType::ScatterGather {
+                return Err(CliError::Config(CoreError::CommandFailed(format!(
+                    "Job '{}' is a scatter-gather job, which the Flux backend cannot run",
+                    job.id
+                ))));
+            }
+            let result = StreamJobResult {
+                slurm_id: submit_via_flux_stdin(&job.script, &job.deps)?,
+                id: job.id,
+            };
+            let result_line = serde_json::to_string(&result)
+                .map_err(|e| CliError::Config(CoreError::CommandFailed(e.to_string())))?;
+            writeln!(writer, "{}", result_line)
+                .map_err(|e| CliError::Config(CoreError::CommandFailed(e.to_string())))?;
+            wave_count += 1;
+            continue;
+        }
+
+        let sge = scheduler == SchedulerType::Sge;
+        let anchor_id = match (job.job_type, &rest) {
+            (StreamJobType::ScatterGather, _) if sge => Some(submit_qsub_anchor(&job.id)?),
+            (StreamJobType::ScatterGather, Some(rest)) => Some(rest.submit_anchor(&job.id)?),
+            (StreamJobType::ScatterGather, None) => Some(submit_anchor(&job.id)?),
+            (StreamJobType::Simple, _) => None,
         };
 
-        let slurm_id = submit_via_sbatch_stdin(&job.script, &job.deps, anchor_id)?;
+        let slurm_id = match &rest {
+            _ if sge => submit_via_qsub_stdin(&job.script, &job.deps, anchor_id)?,
+            Some(rest) => rest.submit_script(&job.script, &job.deps, anchor_id)?,
+            None => submit_via_sbatch_stdin(&job.script, &job.deps, anchor_id)?,
+        };
         let track_id = anchor_id.unwrap_or(slurm_id);
 
         let result = StreamJobResult {
@@ -170,7 +460,7 @@ fn handle_plan_file_orchestrate(plan_file: &std::path::Path) -> Result<(), CliEr
         .map_err(|e| CliError::Config(CoreError::path_io(plan_file, e)))?;
     let plan: repx_client::orchestration::OrchestrationPlan = serde_json::from_str(&plan_content)?;
 
-    let mut submitted_slurm_ids: HashMap<JobId, u32> = HashMap::new();
+    let mut submitted_slurm_ids: HashMap<JobId, u64> = HashMap::new();
     let mut jobs_left: HashSet<JobId> = plan.jobs.keys().cloned().collect();
     let mut wave_num = 0;
 
@@ -256,7 +546,7 @@ fn handle_plan_file_orchestrate(plan_file: &std::path::Path) -> Result<(), CliEr
 
             let slurm_id_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
             let slurm_id = slurm_id_str
-                .parse::<u32>()
+                .parse::<u64>()
                 .map_err(|_| CliError::ExecutionFailed {
                     message: format!(
                         "Failed to parse SLURM ID from sbatch output for job '{}'",
@@ -280,7 +570,17 @@ fn handle_plan_file_orchestrate(plan_file: &std::path::Path) -> Result<(), CliEr
 
 pub fn handle_internal_orchestrate(args: InternalOrchestrateArgs) -> Result<(), CliError> {
     if args.stream {
-        handle_stream_orchestrate()
+        let rest = args.rest_url.map(|url| SlurmRestConfig {
+            url,
+            api_version: args.rest_api_version,
+            token_env: args.rest_token_env,
+        });
+        handle_stream_orchestrate(args.scheduler, rest)
+    } else if matches!(args.scheduler, SchedulerType::Sge | SchedulerType::Flux) {
+        Err(CliError::Config(CoreError::CommandFailed(format!(
+            "Plan files are only supported for SLURM; use --stream with {}",
+            args.scheduler
+        ))))
     } else if let Some(ref plan_file) = args.plan_file {
         handle_plan_file_orchestrate(plan_file)
     } else {
@@ -289,3 +589,20 @@ pub fn handle_internal_orchestrate(args: InternalOrchestrateArgs) -> Result<(),
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flux_job_id() {
+        assert_eq!(parse_flux_job_id("ƒ1\n"), Some(0));
+        assert_eq!(parse_flux_job_id("f21"), Some(58));
+        assert_eq!(parse_flux_job_id("ƒz"), Some(57));
+        assert_eq!(parse_flux_job_id("1234567890"), Some(1234567890));
+        assert_eq!(parse_flux_job_id("0x4d2"), Some(1234));
+        assert_eq!(parse_flux_job_id("ƒ0"), None);
+        assert_eq!(parse_flux_job_id("f"), None);
+        assert_eq!(parse_flux_job_id(""), None);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::cli::{ListArgs, ListEntity, ListJobsArgs, StatusFilter};
+use crate::cli::{ListArgs, ListEntity, ListJobsArgs, OutputFormat, StatusFilter};
+use crate::commands::records::{stage_name, write_records, JobRecord};
 use crate::commands::trace::compute_all_effective_params;
 use crate::error::CliError;
 use repx_client::{client::status as client_status, Client};
@@ -10,9 +11,10 @@ use repx_core::{
     lab::LabSource,
     model::{JobId, Lab, RunId},
     resolver,
+    store::outcomes::get_job_timings,
 };
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
 pub fn handle_list(
@@ -32,6 +34,7 @@ pub fn handle_list(
                     output_paths: false,
                     param: vec![],
                     group_by_stage: false,
+                    format: OutputFormat::Table,
                 },
                 source,
                 target,
@@ -65,92 +68,31 @@ struct ListJobsContext {
     status_filters: Vec<StatusFilter>,
 }
 
-fn list_jobs(
-    lab: &Lab,
-    args: &ListJobsArgs,
-    source: &LabSource,
-    target: Option<&str>,
-) -> Result<(), CliError> {
-    let needs_config = args.output_paths || !args.status.is_empty();
-
-    let (store_path, job_statuses, resolved_target_name) = if needs_config {
-        let config = config::load_config()?;
-
-        let target_name = target.unwrap_or(targets::LOCAL).to_string();
-
-        let target_config = config.targets.get(&target_name).ok_or_else(|| {
-            CliError::Config(CoreError::TargetNotConfigured {
-                name: target_name.clone(),
-            })
-        })?;
-
-        let store = if args.output_paths {
-            Some(target_config.base_path.clone())
-        } else {
-            None
-        };
-
-        let statuses = if !args.status.is_empty() {
-            let client = Client::new(config.clone(), source.clone()).map_err(|e| {
-                CliError::Config(CoreError::InvalidConfig {
-                    detail: format!("Failed to initialize client: {}", e),
-                })
-            })?;
-            let job_statuses =
-                client_status::get_statuses_for_active_target(&client, &target_name, None)
-                    .map_err(|e| {
-                        CliError::Config(CoreError::CommandFailed(format!(
-                            "Failed to get job statuses: {}",
-                            e
-                        )))
-                    })?;
-            Some(job_statuses)
-        } else {
-            None
-        };
-
-        (store, statuses, Some(target_name))
-    } else {
-        (None, None, None)
-    };
-
-    let effective_params = if !args.param.is_empty() {
-        Some(compute_all_effective_params(lab))
-    } else {
-        None
-    };
-
-    let ctx = ListJobsContext {
-        store_path,
-        effective_params,
-        param_keys: args.param.clone(),
-        group_by_stage: args.group_by_stage,
-        job_statuses,
-        status_filters: args.status.clone(),
-    };
-
-    if let Some(ref target) = resolved_target_name {
-        if !args.status.is_empty() {
-            println!("(status from target '{}')", target);
-            println!();
-        }
-    }
+enum JobSelection<'a> {
+    Sections(Vec<(String, Vec<&'a JobId>)>),
+    FoundInRuns {
+        query: String,
+        runs: Vec<&'a RunId>,
+        jobs: Vec<&'a JobId>,
+    },
+}
 
-    let run_id_str = match &args.name {
-        Some(s) => s.as_str(),
+fn select_jobs<'a>(lab: &'a Lab, name: Option<&str>) -> Result<JobSelection<'a>, CliError> {
+    let run_id_str = match name {
+        Some(s) => s,
         None => {
             let mut run_ids: Vec<_> = lab.runs.keys().collect();
             run_ids.sort();
-            for (i, run_id) in run_ids.iter().enumerate() {
-                if i > 0 {
-                    println!();
-                }
-                println!("Jobs in run '{}':", run_id);
-                let run = &lab.runs[*run_id];
-                let jobs: Vec<_> = run.jobs.iter().collect();
-                print_jobs_list(lab, jobs, args.stage.as_deref(), &ctx);
-            }
-            return Ok(());
+            let sections = run_ids
+                .into_iter()
+                .map(|run_id| {
+                    (
+                        format!("Jobs in run '{}':", run_id),
+                        lab.runs[run_id].jobs.iter().collect(),
+                    )
+                })
+                .collect();
+            return Ok(JobSelection::Sections(sections));
         }
     };
 
@@ -164,17 +106,18 @@ fn list_jobs(
             Some(run_ids) => {
                 let mut sorted_ids: Vec<_> = run_ids.iter().collect();
                 sorted_ids.sort();
-                for (i, run_id) in sorted_ids.iter().enumerate() {
-                    if i > 0 {
-                        println!();
-                    }
-                    if let Some(run) = lab.runs.get(*run_id) {
-                        println!("Jobs in run '{}' (group @{}):", run_id, group_name);
-                        let jobs: Vec<_> = run.jobs.iter().collect();
-                        print_jobs_list(lab, jobs, args.stage.as_deref(), &ctx);
-                    }
-                }
-                return Ok(());
+                let sections = sorted_ids
+                    .into_iter()
+                    .filter_map(|run_id| {
+                        lab.runs.get(run_id).map(|run| {
+                            (
+                                format!("Jobs in run '{}' (group @{}):", run_id, group_name),
+                                run.jobs.iter().collect(),
+                            )
+                        })
+                    })
+                    .collect();
+                return Ok(JobSelection::Sections(sections));
             }
             None => {
                 let available: Vec<_> = {
@@ -198,8 +141,8 @@ fn list_jobs(
         })
     })?;
 
-    let matched_run = if let Some(run) = lab.runs.get(&run_id) {
-        Some((&run_id, run))
+    let matched_run = if let Some((id, run)) = lab.runs.get_key_value(&run_id) {
+        Some((id, run))
     } else {
         let matches: Vec<_> = lab
             .runs
@@ -221,80 +164,221 @@ fn list_jobs(
     };
 
     if let Some((id, run)) = matched_run {
-        println!("Jobs in run '{}':", id);
-        let jobs: Vec<_> = run.jobs.iter().collect();
-        print_jobs_list(lab, jobs, args.stage.as_deref(), &ctx);
-        Ok(())
-    } else {
-        let job_id_query = run_id_str;
-        let matching_jobs: Vec<_> = lab
-            .jobs
-            .keys()
-            .filter(|jid| jid.as_str().starts_with(job_id_query))
-            .collect();
+        return Ok(JobSelection::Sections(vec![(
+            format!("Jobs in run '{}':", id),
+            run.jobs.iter().collect(),
+        )]));
+    }
 
-        if !matching_jobs.is_empty() {
-            let mut found_runs = Vec::new();
-            for (rid, r) in &lab.runs {
-                for match_job in &matching_jobs {
-                    if r.jobs.contains(match_job) {
-                        found_runs.push(rid);
-                    }
+    let job_id_query = run_id_str;
+    let matching_jobs: Vec<_> = lab
+        .jobs
+        .keys()
+        .filter(|jid| jid.as_str().starts_with(job_id_query))
+        .collect();
+
+    if !matching_jobs.is_empty() {
+        let mut found_runs = Vec::new();
+        for (rid, r) in &lab.runs {
+            for match_job in &matching_jobs {
+                if r.jobs.contains(match_job) {
+                    found_runs.push(rid);
                 }
             }
-            found_runs.sort();
-            found_runs.dedup();
+        }
+        found_runs.sort();
+        found_runs.dedup();
+
+        if !found_runs.is_empty() {
+            return Ok(JobSelection::FoundInRuns {
+                query: job_id_query.to_string(),
+                runs: found_runs,
+                jobs: matching_jobs,
+            });
+        }
+    }
 
-            if !found_runs.is_empty() {
-                println!("Job '{}' found in the following runs:", job_id_query);
-                for rid in &found_runs {
-                    println!("  {}", rid);
-                }
-                if found_runs.len() == 1 {
-                    println!();
-                    let new_args = ListJobsArgs {
-                        name: Some(found_runs[0].to_string()),
-                        stage: args.stage.clone(),
-                        status: args.status.clone(),
-                        output_paths: args.output_paths,
-                        param: args.param.clone(),
-                        group_by_stage: args.group_by_stage,
-                    };
-                    return list_jobs(lab, &new_args, source, target);
-                }
-                return Ok(());
+    Err(CliError::Domain(DomainError::TargetNotFound(
+        run_id.into_inner(),
+    )))
+}
+
+fn list_jobs(
+    lab: &Lab,
+    args: &ListJobsArgs,
+    source: &LabSource,
+    target: Option<&str>,
+) -> Result<(), CliError> {
+    let structured = args.format.is_structured();
+    let wants_statuses = structured || !args.status.is_empty();
+    let wants_store = structured || args.output_paths;
+    let needs_config = wants_store || wants_statuses;
+
+    let (store_path, job_statuses, resolved_target_name) = if needs_config {
+        let config = config::load_config()?;
+
+        let target_name = target.unwrap_or(targets::LOCAL).to_string();
+
+        let target_config = config.targets.get(&target_name).ok_or_else(|| {
+            CliError::Config(CoreError::TargetNotConfigured {
+                name: target_name.clone(),
+            })
+        })?;
+
+        let store = if wants_store {
+            Some(target_config.base_path.clone())
+        } else {
+            None
+        };
+
+        let statuses = if wants_statuses {
+            let client = Client::new(config.clone(), source.clone()).map_err(|e| {
+                CliError::Config(CoreError::InvalidConfig {
+                    detail: format!("Failed to initialize client: {}", e),
+                })
+            })?;
+            let job_statuses =
+                client_status::get_statuses_for_active_target(&client, &target_name, None)
+                    .map_err(|e| {
+                        CliError::Config(CoreError::CommandFailed(format!(
+                            "Failed to get job statuses: {}",
+                            e
+                        )))
+                    })?;
+            Some(job_statuses)
+        } else {
+            None
+        };
+
+        (store, statuses, Some(target_name))
+    } else {
+        (None, None, None)
+    };
+
+    let effective_params = if structured || !args.param.is_empty() {
+        Some(compute_all_effective_params(lab))
+    } else {
+        None
+    };
+
+    let ctx = ListJobsContext {
+        store_path,
+        effective_params,
+        param_keys: args.param.clone(),
+        group_by_stage: args.group_by_stage,
+        job_statuses,
+        status_filters: args.status.clone(),
+    };
+
+    let sections = match select_jobs(lab, args.name.as_deref())? {
+        JobSelection::Sections(sections) => sections,
+        JobSelection::FoundInRuns { jobs, .. } if structured => vec![(String::new(), jobs)],
+        JobSelection::FoundInRuns { query, runs, .. } => {
+            println!("Job '{}' found in the following runs:", query);
+            if runs.len() == 1 {
+                println!("  {}", runs[0]);
+                println!();
+                let new_args = ListJobsArgs {
+                    name: Some(runs[0].to_string()),
+                    stage: args.stage.clone(),
+                    status: args.status.clone(),
+                    output_paths: args.output_paths,
+                    param: args.param.clone(),
+                    group_by_stage: args.group_by_stage,
+                    format: args.format,
+                };
+                return list_jobs(lab, &new_args, source, target);
+            }
+            for rid in &runs {
+                println!("  {}", rid);
             }
+            return Ok(());
         }
+    };
 
-        Err(CliError::Domain(DomainError::TargetNotFound(
-            run_id.into_inner(),
-        )))
+    if structured {
+        let mut seen = HashSet::new();
+        let jobs: Vec<&JobId> = sections
+            .into_iter()
+            .flat_map(|(_, jobs)| filter_jobs(jobs, args.stage.as_deref(), &ctx))
+            .filter(|job_id| seen.insert(*job_id))
+            .collect();
+        return write_job_records(lab, &jobs, &ctx, args.format);
     }
-}
 
-fn extract_stage_name(job_id: &JobId) -> String {
-    let s = job_id.as_str();
-    if let Some(first_dash) = s.find('-') {
-        let after_hash = &s[first_dash + 1..];
-        if let Some(last_dash) = after_hash.rfind('-') {
-            let potential_version = &after_hash[last_dash + 1..];
-            if potential_version.contains('.')
-                || potential_version.chars().all(|c| c.is_ascii_digit())
-            {
-                return after_hash[..last_dash].to_string();
-            }
+    if let Some(ref target) = resolved_target_name {
+        if !args.status.is_empty() {
+            println!("(status from target '{}')", target);
+            println!();
+        }
+    }
+
+    for (i, (heading, jobs)) in sections.into_iter().enumerate() {
+        if i > 0 {
+            println!();
         }
-        return after_hash.to_string();
+        println!("{}", heading);
+        print_jobs_list(lab, jobs, args.stage.as_deref(), &ctx);
     }
-    s.to_string()
+    Ok(())
 }
 
-fn print_jobs_list(
+fn write_job_records(
     lab: &Lab,
-    jobs: Vec<&JobId>,
+    jobs: &[&JobId],
+    ctx: &ListJobsContext,
+    format: OutputFormat,
+) -> Result<(), CliError> {
+    let owned_ids: Vec<JobId> = jobs.iter().map(|job_id| (*job_id).clone()).collect();
+    let timings = match &ctx.store_path {
+        Some(store) => get_job_timings(store, &owned_ids).unwrap_or_else(|e| {
+            tracing::warn!("Failed to read job timings: {}", e);
+            HashMap::new()
+        }),
+        None => HashMap::new(),
+    };
+    let records: Vec<JobRecord> = jobs
+        .iter()
+        .map(|job_id| {
+            let status = ctx
+                .job_statuses
+                .as_ref()
+                .and_then(|statuses| statuses.get(*job_id))
+                .map_or("pending", |status| format_job_status(Some(status)));
+            let all_params = ctx
+                .effective_params
+                .as_ref()
+                .and_then(|params| params.get(*job_id))
+                .cloned()
+                .unwrap_or(Value::Null);
+            let params = if ctx.param_keys.is_empty() {
+                all_params
+            } else {
+                Value::Object(
+                    ctx.param_keys
+                        .iter()
+                        .map(|key| (key.clone(), get_nested_value(&all_params, key)))
+                        .collect(),
+                )
+            };
+            JobRecord::new(
+                lab,
+                job_id,
+                status,
+                timings.get(*job_id),
+                ctx.store_path.as_deref(),
+                params,
+            )
+        })
+        .collect();
+    write_records(&records, format)
+}
+
+fn filter_jobs<'a>(
+    jobs: Vec<&'a JobId>,
     stage_filter: Option<&str>,
     ctx: &ListJobsContext,
-) {
+) -> Vec<&'a JobId> {
     let mut jobs: Vec<_> = jobs;
     jobs.sort();
 
@@ -306,7 +390,7 @@ fn print_jobs_list(
         jobs
     };
 
-    let jobs: Vec<_> = if !ctx.status_filters.is_empty() {
+    if !ctx.status_filters.is_empty() {
         jobs.into_iter()
             .filter(|job_id| {
                 if let Some(ref statuses) = ctx.job_statuses {
@@ -324,7 +408,16 @@ fn print_jobs_list(
             .collect()
     } else {
         jobs
-    };
+    }
+}
+
+fn print_jobs_list(
+    lab: &Lab,
+    jobs: Vec<&JobId>,
+    stage_filter: Option<&str>,
+    ctx: &ListJobsContext,
+) {
+    let jobs = filter_jobs(jobs, stage_filter, ctx);
 
     let has_filters = stage_filter.is_some() || !ctx.status_filters.is_empty();
     if jobs.is_empty() && has_filters {
@@ -336,7 +429,7 @@ fn print_jobs_list(
     if ctx.group_by_stage {
         let mut groups: HashMap<String, Vec<&JobId>> = HashMap::new();
         for job_id in &jobs {
-            let stage = extract_stage_name(job_id);
+            let stage = stage_name(job_id);
             groups.entry(stage).or_default().push(job_id);
         }
 
@@ -357,11 +450,20 @@ fn print_jobs_list(
     }
 }
 
-fn status_matches_filter(status: &JobStatus, filter: &StatusFilter) -> bool {
+pub(crate) fn status_matches_filter(status: &JobStatus, filter: &StatusFilter) -> bool {
     matches!(
         (status, filter),
         (JobStatus::Succeeded { .. }, StatusFilter::Succeeded)
-            | (JobStatus::Failed { .. }, StatusFilter::Failed)
+            | (
+                JobStatus::Failed { .. }
+                    | JobStatus::TimedOut { .. }
+                    | JobStatus::Cancelled { .. }
+                    | JobStatus::Lost { .. },
+                StatusFilter::Failed
+            )
+            | (JobStatus::TimedOut { .. }, StatusFilter::TimedOut)
+            | (JobStatus::Cancelled { .. }, StatusFilter::Cancelled)
+            | (JobStatus::Lost { .. }, StatusFilter::Lost)
             | (JobStatus::Pending, StatusFilter::Pending)
             | (JobStatus::Running, StatusFilter::Running)
             | (JobStatus::Queued, StatusFilter::Queued)
@@ -369,10 +471,13 @@ fn status_matches_filter(status: &JobStatus, filter: &StatusFilter) -> bool {
     )
 }
 
-fn format_job_status(status: Option<&JobStatus>) -> &'static str {
+pub fn format_job_status(status: Option<&JobStatus>) -> &'static str {
     match status {
         Some(JobStatus::Succeeded { .. }) => "succeeded",
         Some(JobStatus::Failed { .. }) => "failed",
+        Some(JobStatus::TimedOut { .. }) => "timed-out",
+        Some(JobStatus::Cancelled { .. }) => "cancelled",
+        Some(JobStatus::Lost { .. }) => "lost",
         Some(JobStatus::Pending) => "pending",
         Some(JobStatus::Running) => "running",
         Some(JobStatus::Queued) => "queued",
@@ -1,13 +1,118 @@
 use crate::cli::LogArgs;
 use crate::commands::AppContext;
 use crate::error::CliError;
-use repx_client::client::LogType;
-use repx_core::{model::RunId, resolver};
+use repx_client::{client::LogType, error::ClientError, targets::Target};
+use repx_core::{errors::CoreError, model::RunId, resolver};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::{thread, time::Duration};
+
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MIN_TAIL_WINDOW_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, PartialEq, Eq)]
+struct LogAddress<'a> {
+    job: &'a str,
+    step: Option<(usize, &'a str)>,
+}
+
+fn parse_log_address(address: &str) -> Result<LogAddress<'_>, CliError> {
+    let invalid = || {
+        CliError::Config(CoreError::UnsupportedValue {
+            kind: "log address (expected JOB or JOB::b<BRANCH>::<STEP>)".to_string(),
+            value: address.to_string(),
+        })
+    };
+    let mut parts = address.split("::");
+    let job = parts
+        .next()
+        .filter(|job| !job.is_empty())
+        .ok_or_else(invalid)?;
+    match (parts.next(), parts.next(), parts.next()) {
+        (None, _, _) => Ok(LogAddress { job, step: None }),
+        (Some(branch), Some(step), None) if !step.is_empty() => {
+            let branch = branch
+                .strip_prefix('b')
+                .and_then(|idx| idx.parse().ok())
+                .ok_or_else(invalid)?;
+            Ok(LogAddress {
+                job,
+                step: Some((branch, step)),
+            })
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn complete_lines(chunk: &str) -> (Vec<&str>, u64) {
+    match chunk.rfind('\n') {
+        Some(end) => (
+            chunk[..end]
+                .split('\n')
+                .map(|line| line.strip_suffix('\r').unwrap_or(line))
+                .collect(),
+            end as u64 + 1,
+        ),
+        None => (Vec::new(), 0),
+    }
+}
+
+fn print_initial_tail(target: &dyn Target, path: &Path, line_count: u32) -> Result<u64, CliError> {
+    let size = target.remote_file_size(path)?.unwrap_or(0);
+    let window = MIN_TAIL_WINDOW_BYTES.max(u64::from(line_count) * 1024);
+    let start = size.saturating_sub(window);
+    let chunk = target.read_remote_file_from(path, start)?;
+
+    let skipped = if start > 0 {
+        chunk.find('\n').map_or(chunk.len(), |idx| idx + 1)
+    } else {
+        0
+    };
+    let (lines, consumed) = complete_lines(&chunk[skipped..]);
+    for line in &lines[lines.len().saturating_sub(line_count as usize)..] {
+        println!("{}", line);
+    }
+    Ok(start + skipped as u64 + consumed)
+}
+
+fn follow(target: &dyn Target, path: &Path, line_count: u32) -> Result<(), CliError> {
+    let running = Arc::new(AtomicBool::new(true));
+    let r = running.clone();
+    if let Err(e) = ctrlc::set_handler(move || {
+        r.store(false, Ordering::SeqCst);
+    }) {
+        tracing::warn!("Failed to set Ctrl+C handler: {e}");
+    }
+
+    let mut offset = print_initial_tail(target, path, line_count)?;
+    while running.load(Ordering::SeqCst) {
+        thread::sleep(FOLLOW_POLL_INTERVAL);
+        let Some(size) = target.remote_file_size(path)? else {
+            continue;
+        };
+        if size < offset {
+            tracing::debug!("Log {} was truncated, rereading", path.display());
+            offset = 0;
+        }
+        if size == offset {
+            continue;
+        }
+
+        let chunk = target.read_remote_file_from(path, offset)?;
+        let (lines, consumed) = complete_lines(&chunk);
+        for line in lines {
+            println!("{}", line);
+        }
+        offset += consumed;
+    }
+    Ok(())
+}
 
 pub fn handle_log(args: LogArgs, context: &AppContext) -> Result<(), CliError> {
     let lab = context.client.lab();
-    let target_input = RunId::from(args.job_id.clone());
-    let job_id = resolver::resolve_target_job_id(lab, &target_input)?;
+    let address = parse_log_address(&args.job_id)?;
+    let job_id = resolver::resolve_target_job_id(lab, &RunId::from(address.job.to_string()))?;
 
     let log_type = if args.stderr {
         LogType::Stderr
@@ -15,75 +120,55 @@ pub fn handle_log(args: LogArgs, context: &AppContext) -> Result<(), CliError> {
         LogType::Auto
     };
 
-    let lines = context.client.get_log_tail(
-        job_id.clone(),
-        context.submission_target,
-        args.lines,
-        log_type,
-    )?;
-
-    for line in &lines {
-        println!("{}", line);
-    }
+    let target = context
+        .client
+        .get_target(context.submission_target)
+        .ok_or_else(|| ClientError::TargetNotFound(context.submission_target.to_string()))?;
+    let log_path =
+        context
+            .client
+            .log_path(job_id, context.submission_target, log_type, address.step)?;
 
     if args.follow {
-        use std::sync::atomic::{AtomicBool, Ordering};
-        use std::sync::Arc;
-        use std::{thread, time::Duration};
-
-        let running = Arc::new(AtomicBool::new(true));
-        let r = running.clone();
-        if let Err(e) = ctrlc::set_handler(move || {
-            r.store(false, Ordering::SeqCst);
-        }) {
-            tracing::warn!("Failed to set Ctrl+C handler: {e}");
-        }
-
-        let mut last_seen_lines: Vec<String> = lines.iter().rev().take(10).cloned().collect();
-        last_seen_lines.reverse();
+        return follow(target.as_ref(), &log_path, args.lines);
+    }
 
-        while running.load(Ordering::SeqCst) {
-            thread::sleep(Duration::from_secs(1));
-            let new_lines = context.client.get_log_tail(
-                job_id.clone(),
-                context.submission_target,
-                args.lines.max(200),
-                log_type,
-            )?;
+    for line in target.read_remote_file_tail(&log_path, args.lines)? {
+        println!("{}", line);
+    }
+    Ok(())
+}
 
-            if new_lines.is_empty() {
-                continue;
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            let start_idx = if last_seen_lines.is_empty() {
-                0
-            } else {
-                let mut found_idx = None;
-                for i in 0..new_lines.len() {
-                    let remaining = new_lines.len() - i;
-                    let check_len = last_seen_lines.len().min(remaining);
-                    if check_len > 0
-                        && new_lines[i..i + check_len] == last_seen_lines[..check_len]
-                        && last_seen_lines.len() <= remaining
-                        && new_lines[i..i + last_seen_lines.len()] == last_seen_lines[..]
-                    {
-                        found_idx = Some(i + last_seen_lines.len());
-                        break;
-                    }
-                }
-                found_idx.unwrap_or(0)
-            };
-
-            for line in &new_lines[start_idx..] {
-                println!("{}", line);
+    #[test]
+    fn test_parse_log_address_with_branch_and_step() {
+        assert_eq!(
+            parse_log_address("abc123").expect("plain job"),
+            LogAddress {
+                job: "abc123",
+                step: None
             }
-
-            if !new_lines.is_empty() {
-                last_seen_lines = new_lines.iter().rev().take(10).cloned().collect();
-                last_seen_lines.reverse();
+        );
+        assert_eq!(
+            parse_log_address("abc123::b3::train-model").expect("step address"),
+            LogAddress {
+                job: "abc123",
+                step: Some((3, "train-model"))
             }
-        }
+        );
+        assert!(parse_log_address("abc123::3::train").is_err());
+        assert!(parse_log_address("abc123::b3").is_err());
+        assert!(parse_log_address("::b3::train").is_err());
     }
 
-    Ok(())
+    #[test]
+    fn test_complete_lines_keeps_partial_line_for_next_read() {
+        let (lines, consumed) = complete_lines("epoch 1\r\nepoch 2\nepo");
+        assert_eq!(lines, vec!["epoch 1", "epoch 2"]);
+        assert_eq!(consumed, 17);
+        assert_eq!(complete_lines("no newline yet"), (Vec::new(), 0));
+    }
 }
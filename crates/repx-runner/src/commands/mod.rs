@@ -1,29 +1,103 @@
 use crate::error::CliError;
 use repx_client::Client;
-use repx_core::{errors::CoreError, lab::LabSource, model::ExecutionType};
-use repx_executor::{ImageTag, Runtime};
+use repx_core::{
+    errors::CoreError,
+    lab::LabSource,
+    model::{ExecutionType, JobId, Lab},
+    resolver,
+};
+use repx_executor::{CancellationToken, ImageTag, Runtime, Secret};
+use std::collections::BTreeSet;
 use std::path::Path;
+use tokio::signal::unix::SignalKind;
 
+pub mod agent;
+pub mod archive;
+pub mod cancel;
+pub mod cas;
+pub mod clean;
+pub mod debug_run;
+pub mod diff;
 pub mod execute;
+pub mod export;
+pub mod fetch;
 pub mod gc;
+pub mod images;
 pub mod internal;
 pub mod list;
 pub mod log;
+pub mod prune;
+pub(crate) mod records;
+pub mod resubmit;
+pub mod retry;
 pub mod run;
 pub mod scatter_gather;
 pub mod show;
+pub mod stats;
+pub mod status;
+pub mod targets;
 pub mod trace;
+pub mod wait;
+pub mod watch;
+pub mod why;
+pub mod workspace;
 
 pub(crate) fn create_tokio_runtime() -> Result<tokio::runtime::Runtime, CliError> {
     tokio::runtime::Runtime::new()
         .map_err(|e| CliError::execution_failed("Failed to create async runtime", e.to_string()))
 }
 
+pub(crate) fn cancel_on_sigterm() -> CancellationToken {
+    let cancel = CancellationToken::new();
+    match tokio::signal::unix::signal(SignalKind::terminate()) {
+        Ok(mut term) => {
+            let on_term = cancel.clone();
+            tokio::spawn(async move {
+                if term.recv().await.is_some() {
+                    tracing::warn!("Received SIGTERM, cancelling job...");
+                    on_term.cancel();
+                }
+            });
+        }
+        Err(e) => tracing::debug!("Failed to install SIGTERM handler: {}", e),
+    }
+    cancel
+}
+
 pub(crate) fn write_marker(path: &Path) -> std::io::Result<()> {
     let _f = std::fs::File::create(path)?;
     Ok(())
 }
 
+pub(crate) fn publish_outputs(
+    uri: Option<&str>,
+    endpoint_url: Option<&str>,
+    base_path: &Path,
+    job_id: &JobId,
+) {
+    let Some(uri) = uri else {
+        return;
+    };
+    let config = repx_core::config::ObjectStoreConfig {
+        uri: uri.to_string(),
+        endpoint_url: endpoint_url.map(str::to_string),
+        profile: None,
+        region: None,
+    };
+    let key = Path::new(repx_core::constants::dirs::OUTPUTS).join(job_id.as_str());
+    let published = repx_client::object_store::ObjectStore::new(config)
+        .and_then(|store| store.upload_dir(&base_path.join(&key), &key));
+    match published {
+        Ok(()) => tracing::info!("Published outputs of '{}' to {}", job_id, uri),
+        Err(e) => tracing::warn!(
+            "Failed to publish outputs of '{}' to {}: {}",
+            job_id,
+            uri,
+            e
+        ),
+    }
+}
+
 pub(crate) fn parse_runtime(
     execution_type: ExecutionType,
     image_tag: Option<String>,
@@ -52,7 +126,25 @@ pub(crate) fn parse_runtime(
         ExecutionType::Bwrap => Ok(Runtime::Bwrap {
             image_tag: parse_tag("bwrap", image_tag)?,
         }),
+        ExecutionType::Proot => Ok(Runtime::Proot {
+            image_tag: parse_tag("proot", image_tag)?,
+        }),
+    }
+}
+
+pub(crate) fn load_job_secrets(job_id: &str) -> Result<Vec<Secret>, CliError> {
+    let secrets = repx_core::config::load_secrets()?;
+    let mut resolved = Vec::new();
+    for (name, source) in &secrets.secrets {
+        if source.applies_to(job_id) {
+            resolved.push(Secret {
+                name: name.clone(),
+                value: source.resolve(name)?,
+                mount: source.mount,
+            });
+        }
     }
+    Ok(resolved)
 }
 
 pub(crate) fn resolve_host_tools_dir(
@@ -76,6 +168,24 @@ pub struct AppContext<'a> {
     pub submission_target: &'a str,
 }
 
+pub(crate) fn resolve_spec_job_ids(
+    lab: &Lab,
+    specs: &[String],
+) -> Result<BTreeSet<JobId>, CliError> {
+    let mut job_ids = BTreeSet::new();
+    for spec in specs {
+        for run_id in resolver::resolve_run_spec(lab, spec)? {
+            match lab.runs.get(&run_id) {
+                Some(run) => job_ids.extend(run.jobs.iter().cloned()),
+                None => {
+                    job_ids.insert(resolver::resolve_target_job_id(lab, &run_id)?.clone());
+                }
+            }
+        }
+    }
+    Ok(job_ids)
+}
+
 pub(crate) fn resolve_to_local_artifacts(
     path: &Path,
     base_path: &Path,
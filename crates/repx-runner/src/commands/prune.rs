@@ -0,0 +1,216 @@
+use crate::cli::{InternalPruneArgs, PruneArgs};
+use crate::commands::gc::{force_remove_no_parent, path_size, with_writable_dir};
+use crate::commands::AppContext;
+use crate::error::CliError;
+use repx_core::{
+    constants::{dirs, markers},
+    errors::DomainError,
+    fs_utils,
+    model::{JobId, StageType},
+    path_safety::safe_join,
+};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PruneSummary {
+    pub dirs: usize,
+    pub bytes: u64,
+}
+
+fn subdirs_with_prefix(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|ft| ft.is_dir()))
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+        .map(|entry| entry.path())
+        .collect();
+    found.sort();
+    found
+}
+
+pub(crate) fn prune_step_outputs(job_root: &Path, dry_run: bool) -> PruneSummary {
+    let mut summary = PruneSummary::default();
+    for branch in subdirs_with_prefix(job_root, "branch-") {
+        for step in subdirs_with_prefix(&branch, "step-") {
+            let out_dir = step.join(dirs::OUT);
+            if !out_dir.is_dir() {
+                continue;
+            }
+            let size = path_size(&out_dir);
+            let mut removed = dry_run;
+            if !dry_run {
+                with_writable_dir(&step, || removed = force_remove_no_parent(&out_dir));
+            }
+            if removed {
+                summary.dirs += 1;
+                summary.bytes += size;
+            } else {
+                tracing::warn!("Failed to prune '{}'", out_dir.display());
+            }
+        }
+    }
+    summary
+}
+
+pub fn handle_prune(
+    args: PruneArgs,
+    context: &AppContext,
+    verbose: repx_core::logging::Verbosity,
+) -> Result<(), CliError> {
+    let lab = context.client.lab();
+    let mut job_ids: BTreeSet<JobId> = if args.specs.is_empty() {
+        lab.jobs.keys().cloned().collect()
+    } else {
+        super::resolve_spec_job_ids(lab, &args.specs)?
+    };
+    job_ids.retain(|job_id| {
+        lab.jobs
+            .get(job_id)
+            .is_some_and(|job| job.stage_type == StageType::ScatterGather)
+    });
+
+    if job_ids.is_empty() {
+        println!("No scatter-gather jobs matched.");
+        return Ok(());
+    }
+
+    let target_name = context.submission_target;
+    let target = context
+        .client
+        .get_target(target_name)
+        .ok_or_else(|| CliError::Domain(DomainError::TargetNotFound(target_name.to_string())))?;
+
+    let job_ids: Vec<JobId> = job_ids.into_iter().collect();
+    let msg = target
+        .prune_step_outputs(&job_ids, args.dry_run, verbose)
+        .map_err(|e| {
+            CliError::execution_failed(
+                format!("Failed to prune step outputs on target '{}'", target_name),
+                e.to_string(),
+            )
+        })?;
+    let msg = msg.trim();
+    if !msg.is_empty() {
+        println!("{}", msg);
+    }
+    Ok(())
+}
+
+pub fn handle_internal_prune(args: InternalPruneArgs) -> Result<(), CliError> {
+    let outputs_dir = args.base_path.join(dirs::OUTPUTS);
+
+    let mut jobs: u64 = 0;
+    let mut total = PruneSummary::default();
+
+    for raw_id in &args.job_ids {
+        let job_id = JobId::from(raw_id.as_str());
+        let job_dir = safe_join(&outputs_dir, job_id.as_str())?;
+        if !job_dir.join(dirs::REPX).join(markers::SUCCESS).exists() {
+            tracing::debug!("Skipping '{}': gather has not succeeded", job_id);
+            continue;
+        }
+
+        let summary = prune_step_outputs(&job_dir, args.dry_run);
+        if summary.dirs == 0 {
+            continue;
+        }
+        if args.dry_run {
+            println!(
+                "Would prune {} step output(s) of {} ({})",
+                summary.dirs,
+                job_id,
+                fs_utils::format_bytes(summary.bytes, false)
+            );
+        } else {
+            tracing::info!("Pruned {} step output(s) of '{}'", summary.dirs, job_id);
+        }
+        jobs += 1;
+        total.dirs += summary.dirs;
+        total.bytes += summary.bytes;
+    }
+
+    if jobs == 0 {
+        println!("Nothing to prune.");
+    } else if args.dry_run {
+        println!(
+            "Would prune {} step output(s) across {} job(s). Would free {}.",
+            total.dirs,
+            jobs,
+            fs_utils::format_bytes(total.bytes, false)
+        );
+    } else {
+        println!(
+            "Pruned {} step output(s) across {} job(s). Freed {}.",
+            total.dirs,
+            jobs,
+            fs_utils::format_bytes(total.bytes, false)
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn step(job_dir: &Path, branch: usize, name: &str) -> PathBuf {
+        let step_dir = job_dir
+            .join(format!("branch-{}", branch))
+            .join(format!("step-{}", name));
+        fs::create_dir_all(step_dir.join(dirs::OUT)).expect("create step out");
+        fs::create_dir_all(step_dir.join(dirs::REPX)).expect("create step repx");
+        fs::write(step_dir.join(dirs::OUT).join("data.bin"), vec![0u8; 128]).expect("write out");
+        fs::write(step_dir.join(dirs::REPX).join("stdout.log"), "ok\n").expect("write log");
+        fs::File::create(step_dir.join(dirs::REPX).join(markers::SUCCESS)).expect("marker");
+        step_dir
+    }
+
+    #[test]
+    fn test_internal_prune_keeps_logs_and_skips_unfinished_gather() {
+        let dir = tempdir().expect("tempdir");
+        let base = dir.path();
+        let done = base.join(dirs::OUTPUTS).join("abc123-sweep-1.0");
+        let pending = base.join(dirs::OUTPUTS).join("def456-sweep-1.0");
+        let done_steps = [step(&done, 0, "simulate"), step(&done, 1, "simulate")];
+        let pending_step = step(&pending, 0, "simulate");
+        fs::create_dir_all(done.join(dirs::OUT)).expect("create final out");
+        fs::create_dir_all(done.join(dirs::REPX)).expect("create job repx");
+        fs::File::create(done.join(dirs::REPX).join(markers::SUCCESS)).expect("job marker");
+
+        handle_internal_prune(InternalPruneArgs {
+            base_path: base.to_path_buf(),
+            dry_run: false,
+            job_ids: vec![
+                "abc123-sweep-1.0".to_string(),
+                "def456-sweep-1.0".to_string(),
+            ],
+        })
+        .expect("prune");
+
+        for step_dir in &done_steps {
+            assert!(!step_dir.join(dirs::OUT).exists());
+            assert!(step_dir.join(dirs::REPX).join("stdout.log").exists());
+            assert!(step_dir.join(dirs::REPX).join(markers::SUCCESS).exists());
+        }
+        assert!(done.join(dirs::OUT).exists());
+        assert!(pending_step.join(dirs::OUT).join("data.bin").exists());
+    }
+
+    #[test]
+    fn test_prune_dry_run_reports_without_deleting() {
+        let dir = tempdir().expect("tempdir");
+        let job_dir = dir.path().join("job");
+        let step_dir = step(&job_dir, 0, "simulate");
+
+        let summary = prune_step_outputs(&job_dir, true);
+        assert_eq!(summary.dirs, 1);
+        assert!(summary.bytes >= 128);
+        assert!(step_dir.join(dirs::OUT).join("data.bin").exists());
+    }
+}
@@ -0,0 +1,269 @@
+use crate::cli::OutputFormat;
+use crate::error::CliError;
+use repx_core::{
+    constants::dirs,
+    errors::CoreError,
+    model::{JobId, Lab, RunId},
+    store::outcomes::{JobOutcome, JobTiming},
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const CSV_COLUMNS: [&str; 8] = [
+    "job_id",
+    "runs",
+    "stage",
+    "status",
+    "started_at",
+    "finished_at",
+    "elapsed_secs",
+    "output_path",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct JobRecord {
+    pub job_id: JobId,
+    pub runs: Vec<RunId>,
+    pub stage: String,
+    pub status: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub elapsed_secs: Option<f64>,
+    pub output_path: Option<PathBuf>,
+    pub params: Value,
+}
+
+impl JobRecord {
+    pub(crate) fn new(
+        lab: &Lab,
+        job_id: &JobId,
+        status: &str,
+        timing: Option<&JobTiming>,
+        store_path: Option<&Path>,
+        params: Value,
+    ) -> Self {
+        let mut runs: Vec<RunId> = lab
+            .runs
+            .iter()
+            .filter(|(_, run)| run.jobs.contains(job_id))
+            .map(|(run_id, _)| run_id.clone())
+            .collect();
+        runs.sort();
+        let output_path = store_path
+            .map(|store| {
+                store
+                    .join(dirs::OUTPUTS)
+                    .join(job_id.as_str())
+                    .join(dirs::OUT)
+            })
+            .filter(|path| path.exists());
+        let started_at = timing.and_then(|t| {
+            let elapsed = chrono::TimeDelta::from_std(t.elapsed?).ok()?;
+            Some((t.finished_at - elapsed).to_rfc3339())
+        });
+        Self {
+            job_id: job_id.clone(),
+            runs,
+            stage: stage_name(job_id),
+            status: status.to_string(),
+            started_at,
+            finished_at: timing.map(|t| t.finished_at.to_rfc3339()),
+            elapsed_secs: timing.and_then(|t| t.elapsed).map(|d| d.as_secs_f64()),
+            output_path,
+            params,
+        }
+    }
+
+    fn csv_fields(&self) -> [String; 8] {
+        [
+            self.job_id.to_string(),
+            self.runs
+                .iter()
+                .map(|run| run.to_string())
+                .collect::<Vec<_>>()
+                .join(";"),
+            self.stage.clone(),
+            self.status.clone(),
+            self.started_at.clone().unwrap_or_default(),
+            self.finished_at.clone().unwrap_or_default(),
+            self.elapsed_secs
+                .map(|secs| format!("{:.3}", secs))
+                .unwrap_or_default(),
+            self.output_path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+pub(crate) fn outcome_label(outcome: &JobOutcome) -> &'static str {
+    match outcome {
+        JobOutcome::Succeeded => "succeeded",
+        JobOutcome::Failed => "failed",
+        JobOutcome::TimedOut => "timed-out",
+        JobOutcome::Cancelled => "cancelled",
+    }
+}
+
+pub(crate) fn stage_name(job_id: &JobId) -> String {
+    let s = job_id.as_str();
+    if let Some(first_dash) = s.find('-') {
+        let after_hash = &s[first_dash + 1..];
+        if let Some(last_dash) = after_hash.rfind('-') {
+            let potential_version = &after_hash[last_dash + 1..];
+            if potential_version.contains('.')
+                || potential_version.chars().all(|c| c.is_ascii_digit())
+            {
+                return after_hash[..last_dash].to_string();
+            }
+        }
+        return after_hash.to_string();
+    }
+    s.to_string()
+}
+
+fn flatten_params(prefix: &str, value: &Value, out: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, nested) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_params(&path, nested, out);
+            }
+        }
+        Value::Null if prefix.is_empty() => {}
+        Value::Null => {
+            out.insert(prefix.to_string(), String::new());
+        }
+        Value::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        other if !prefix.is_empty() => {
+            out.insert(prefix.to_string(), other.to_string());
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn serialization_error(format: &str, e: impl std::fmt::Display) -> CliError {
+    CliError::Config(CoreError::SerializationError(format!(
+        "Failed to write {}: {}",
+        format, e
+    )))
+}
+
+pub(crate) fn write_csv(records: &[JobRecord], out: impl Write) -> Result<(), CliError> {
+    let flattened: Vec<BTreeMap<String, String>> = records
+        .iter()
+        .map(|record| {
+            let mut params = BTreeMap::new();
+            flatten_params("", &record.params, &mut params);
+            params
+        })
+        .collect();
+    let param_columns: BTreeSet<&String> = flattened.iter().flat_map(|p| p.keys()).collect();
+
+    let mut writer = csv::Writer::from_writer(out);
+    let header = CSV_COLUMNS
+        .iter()
+        .map(|column| column.to_string())
+        .chain(param_columns.iter().map(|key| format!("params.{}", key)));
+    writer
+        .write_record(header)
+        .map_err(|e| serialization_error("CSV", e))?;
+    for (record, params) in records.iter().zip(&flattened) {
+        let fields = record.csv_fields().into_iter().chain(
+            param_columns
+                .iter()
+                .map(|key| params.get(*key).cloned().unwrap_or_default()),
+        );
+        writer
+            .write_record(fields)
+            .map_err(|e| serialization_error("CSV", e))?;
+    }
+    writer.flush().map_err(|e| serialization_error("CSV", e))?;
+    Ok(())
+}
+
+pub(crate) fn write_structured<T: Serialize>(
+    value: &T,
+    format: OutputFormat,
+    mut out: impl Write,
+) -> Result<(), CliError> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, value)
+                .map_err(|e| serialization_error("JSON", e))?;
+            writeln!(out)?;
+        }
+        OutputFormat::Yaml => {
+            serde_yaml::to_writer(&mut out, value).map_err(|e| serialization_error("YAML", e))?;
+        }
+        OutputFormat::Table | OutputFormat::Csv => {
+            return Err(CliError::Config(CoreError::UnsupportedValue {
+                kind: "structured output format".to_string(),
+                value: format!("{:?}", format).to_lowercase(),
+            }));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn write_records(records: &[JobRecord], format: OutputFormat) -> Result<(), CliError> {
+    let stdout = std::io::stdout().lock();
+    match format {
+        OutputFormat::Csv => write_csv(records, stdout),
+        _ => write_structured(&records, format, stdout),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(job_id: &str, params: Value) -> JobRecord {
+        JobRecord {
+            job_id: JobId::from(job_id),
+            runs: vec![RunId::from("run-a".to_string())],
+            stage: "stage".to_string(),
+            status: "succeeded".to_string(),
+            started_at: None,
+            finished_at: None,
+            elapsed_secs: Some(1.5),
+            output_path: None,
+            params,
+        }
+    }
+
+    #[test]
+    fn test_csv_flattens_params_into_columns() {
+        let records = vec![
+            record(
+                "abc-stage",
+                serde_json::json!({"lr": 0.1, "model": {"depth": 4, "name": "resnet"}}),
+            ),
+            record("def-stage", serde_json::json!({"lr": 0.2, "seed": 7})),
+        ];
+        let mut buf = Vec::new();
+        write_csv(&records, &mut buf).expect("CSV write must succeed");
+        let text = String::from_utf8(buf).expect("CSV must be UTF-8");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "job_id,runs,stage,status,started_at,finished_at,elapsed_secs,output_path,\
+             params.lr,params.model.depth,params.model.name,params.seed"
+        );
+        assert_eq!(
+            lines[1],
+            "abc-stage,run-a,stage,succeeded,,,1.500,,0.1,4,resnet,"
+        );
+        assert_eq!(lines[2], "def-stage,run-a,stage,succeeded,,,1.500,,0.2,,,7");
+    }
+}
@@ -0,0 +1,49 @@
+use crate::cli::RunArgs;
+use crate::commands::{retry, run, AppContext};
+use crate::error::CliError;
+use repx_core::config::{ResourceRule, Resources};
+use repx_core::model::JobId;
+
+fn describe_overrides(overrides: &ResourceRule) -> String {
+    let mut parts = Vec::new();
+    if let Some(partition) = &overrides.partition {
+        parts.push(format!("partition={}", partition));
+    }
+    if let Some(cpus) = overrides.cpus_per_task {
+        parts.push(format!("cpus-per-task={}", cpus));
+    }
+    if let Some(mem) = &overrides.mem {
+        parts.push(format!("mem={}", mem));
+    }
+    if let Some(time) = &overrides.time {
+        parts.push(format!("time={}", time));
+    }
+    parts.join(", ")
+}
+
+pub fn handle_resubmit(
+    args: RunArgs,
+    overrides: &ResourceRule,
+    context: &AppContext<'_>,
+    resources: Option<Resources>,
+    run_config: run::RunConfig,
+) -> Result<(), CliError> {
+    let lab = context.client.lab();
+    let job_ids: Vec<JobId> = super::resolve_spec_job_ids(lab, &args.run_specs)?
+        .into_iter()
+        .collect();
+
+    let resources = repx_client::resources::with_job_overrides(
+        resources,
+        &job_ids,
+        &run_config.target_name,
+        overrides,
+    );
+    println!(
+        "- Overriding resources for {} job(s): {}",
+        job_ids.len(),
+        describe_overrides(overrides)
+    );
+
+    retry::handle_retry(args, context, Some(resources), run_config)
+}
@@ -0,0 +1,122 @@
+use crate::cli::RunArgs;
+use crate::commands::{run, AppContext};
+use crate::error::CliError;
+use colored::Colorize;
+use repx_core::{
+    config::Resources,
+    engine::{self, JobStatus},
+    errors::CoreError,
+    model::{JobId, StageType},
+};
+
+const MAX_LISTED_BRANCH_STEPS: usize = 5;
+
+fn is_failed(status: Option<&JobStatus>) -> bool {
+    matches!(
+        status,
+        Some(
+            JobStatus::Failed { .. }
+                | JobStatus::TimedOut { .. }
+                | JobStatus::Cancelled { .. }
+                | JobStatus::Lost { .. }
+        )
+    )
+}
+
+fn describe_branch_steps(failed: &[(usize, String)]) -> String {
+    let mut listed: Vec<String> = failed
+        .iter()
+        .take(MAX_LISTED_BRANCH_STEPS)
+        .map(|(branch, step)| format!("branch {} step '{}'", branch, step))
+        .collect();
+    if failed.len() > MAX_LISTED_BRANCH_STEPS {
+        listed.push(format!(
+            "and {} more",
+            failed.len() - MAX_LISTED_BRANCH_STEPS
+        ));
+    }
+    listed.join(", ")
+}
+
+pub fn handle_retry(
+    mut args: RunArgs,
+    context: &AppContext<'_>,
+    resources: Option<Resources>,
+    run_config: run::RunConfig,
+) -> Result<(), CliError> {
+    if args.run_specs.is_empty() {
+        return Err(CliError::Config(CoreError::MissingArgument {
+            argument: "run_specs".to_string(),
+            context: "No run or job specified to retry".to_string(),
+        }));
+    }
+
+    let client = context.client;
+    let lab = client.lab();
+    let target_name = &run_config.target_name;
+    let job_ids = super::resolve_spec_job_ids(lab, &args.run_specs)?;
+
+    println!(
+        "- Checking job statuses on target '{}'...",
+        target_name.cyan()
+    );
+    let raw_statuses =
+        client.get_statuses_for_active_target(target_name, Some(run_config.scheduler))?;
+    let statuses = engine::determine_job_statuses(lab, raw_statuses);
+    let failed: Vec<JobId> = job_ids
+        .into_iter()
+        .filter(|job_id| is_failed(statuses.get(job_id)))
+        .collect();
+
+    if failed.is_empty() {
+        println!("No failed jobs to retry.");
+        return Ok(());
+    }
+
+    println!(
+        "- Retrying {} failed job{}:",
+        failed.len().to_string().bold(),
+        if failed.len() == 1 { "" } else { "s" }
+    );
+    for job_id in &failed {
+        let is_scatter_gather = lab
+            .jobs
+            .get(job_id)
+            .is_some_and(|job| job.stage_type == StageType::ScatterGather);
+        let branch_steps = if is_scatter_gather {
+            client
+                .failed_branch_steps(job_id, target_name)
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to read branch markers of '{}': {}", job_id, e);
+                    Vec::new()
+                })
+        } else {
+            Vec::new()
+        };
+        if branch_steps.is_empty() {
+            println!("  {}", job_id);
+        } else {
+            println!(
+                "  {} {}",
+                job_id,
+                format!("({})", describe_branch_steps(&branch_steps)).dimmed()
+            );
+        }
+    }
+
+    args.run_specs = failed.iter().map(|job_id| job_id.to_string()).collect();
+    run::handle_run(args, context, resources, run_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_branch_steps_truncates_long_lists() {
+        let failed: Vec<(usize, String)> = (0..7).map(|b| (b, "train".to_string())).collect();
+        let described = describe_branch_steps(&failed);
+        assert!(described.starts_with("branch 0 step 'train', branch 1 step 'train'"));
+        assert!(described.ends_with("branch 4 step 'train', and 2 more"));
+    }
+}
@@ -18,6 +18,7 @@ pub struct RunConfig {
     pub num_jobs: Option<usize>,
     pub verbose: repx_core::logging::Verbosity,
     pub artifact_store: repx_core::model::ArtifactStore,
+    pub retry_failed: bool,
 }
 
 fn format_phase_suffix(phase: &Option<WorkUnitPhase>) -> String {
@@ -27,7 +28,7 @@ fn format_phase_suffix(phase: &Option<WorkUnitPhase>) -> String {
     }
 }
 
-fn format_wall_time(d: &Duration) -> String {
+pub(crate) fn format_wall_time(d: &Duration) -> String {
     let total_secs = d.as_secs();
     let days = total_secs / 86400;
     let hours = (total_secs % 86400) / 3600;
@@ -72,6 +73,7 @@ pub fn handle_run(
     let num_jobs = run_config.num_jobs;
     let verbose = run_config.verbose;
     let artifact_store = run_config.artifact_store;
+    let retry_failed = run_config.retry_failed;
     let mem_override = if let Some(ref mem_str) = args.mem {
         let m = Memory::from(mem_str.as_str());
         Some(m.to_bytes().ok_or_else(|| {
@@ -115,6 +117,7 @@ pub fn handle_run(
 
     let target_name_clone = target_name.to_string();
     let continue_on_failure = args.continue_on_failure;
+    let ignore_submission_windows = args.ignore_windows;
 
     let submission_thread = thread::spawn(move || {
         let options = SubmitOptions {
@@ -127,11 +130,14 @@ pub fn handle_run(
             verbose,
             cancel_flag: Some(cancelled_for_submit),
             artifact_store,
+            ignore_submission_windows,
+            retry_failed,
         };
         client.submit_batch_run(run_specs, &target_name_clone, scheduler, options)
     });
     let show_timing = !args.no_timing;
     let mut pb: Option<ProgressBar> = None;
+    let mut counting_bytes = false;
     let mut user_cancelled = false;
 
     loop {
@@ -175,7 +181,13 @@ pub fn handle_run(
                 );
             }
             ClientEvent::GeneratingSlurmScripts { num_jobs } => {
-                println!("- Generating {} SLURM scripts...", num_jobs);
+                let kind = match scheduler {
+                    SchedulerType::Sge => "Grid Engine",
+                    SchedulerType::Flux => "Flux",
+                    SchedulerType::AwsBatch => "AWS Batch",
+                    _ => "SLURM",
+                };
+                println!("- Generating {} {} scripts...", num_jobs, kind);
             }
             ClientEvent::ExecutingOrchestrator => {
                 println!("- Executing orchestrator on target...");
@@ -191,24 +203,48 @@ pub fn handle_run(
                     );
                 new_pb.set_message("Syncing artifacts...");
                 pb = Some(new_pb);
+                counting_bytes = false;
             }
             ClientEvent::SyncingArtifactProgress { path } => {
                 if let Some(pb) = pb.as_ref() {
-                    pb.inc(1);
+                    if !counting_bytes {
+                        pb.inc(1);
+                    }
                     pb.set_message(format!("{}", path.display()));
                 }
             }
+            ClientEvent::SyncProgress {
+                bytes_done,
+                bytes_total,
+            } => {
+                let pb = pb.get_or_insert_with(|| ProgressBar::new(bytes_total));
+                if !counting_bytes {
+                    pb.set_style(
+                        ProgressStyle::default_bar()
+                        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta}) {msg}")
+                        .expect("static progress bar template must be valid")
+                        .progress_chars("#>-"),
+                    );
+                    pb.set_message("Uploading...");
+                    counting_bytes = true;
+                }
+                pb.set_length(bytes_total);
+                pb.set_position(bytes_done);
+            }
             ClientEvent::SyncingFinished => {
                 if let Some(pb) = pb.as_ref() {
                     pb.finish_with_message("Sync complete");
                 }
                 pb = None;
+                counting_bytes = false;
             }
             ClientEvent::SubmittingJobs { total, concurrency } => {
-                let executor = if scheduler == SchedulerType::Slurm {
-                    "SLURM"
-                } else {
-                    "local executor"
+                let executor = match scheduler {
+                    SchedulerType::Slurm => "SLURM",
+                    SchedulerType::Sge => "Grid Engine",
+                    SchedulerType::Flux => "Flux",
+                    SchedulerType::AwsBatch => "AWS Batch",
+                    SchedulerType::Local => "local executor",
                 };
                 match concurrency {
                     Some(c) => println!(
@@ -235,6 +271,17 @@ pub fn handle_run(
                     current, total, job_id, slurm_id
                 );
             }
+            ClientEvent::CloudJobSubmitted {
+                job_id,
+                cloud_id,
+                total,
+                current,
+            } => {
+                println!(
+                    "  [{}/{}] Submitted job {} as AWS Batch job {}",
+                    current, total, job_id, cloud_id
+                );
+            }
             ClientEvent::JobStarted {
                 job_id,
                 pid,
@@ -347,6 +394,32 @@ pub fn handle_run(
             ClientEvent::WaveCompleted { wave, num_jobs } => {
                 println!("- Wave {} completed ({} jobs finished).", wave, num_jobs);
             }
+            ClientEvent::SubmissionHeld {
+                target,
+                reason,
+                opens_at,
+            } => {
+                println!(
+                    "- {} Submission to '{}' held ({}). Releasing at {}...",
+                    "[HELD]".yellow(),
+                    target,
+                    reason,
+                    opens_at.format("%a %Y-%m-%d %H:%M")
+                );
+            }
+            ClientEvent::SubmissionPaced {
+                target,
+                in_flight,
+                limit,
+            } => {
+                println!(
+                    "- {} {} of at most {} jobs in flight on '{}'. Waiting for the queue to drain...",
+                    "[HELD]".yellow(),
+                    in_flight,
+                    limit,
+                    target
+                );
+            }
         }
     }
 
@@ -0,0 +1,87 @@
+use crate::error::CliError;
+use repx_core::errors::CoreError;
+use std::fs;
+use std::path::Path;
+
+pub(crate) const SCRIPT_FILE: &str = "sbatch.sh";
+
+const DIRECTIVES: &str = "{{directives}}";
+const PROLOGUE: &str = "{{prologue}}";
+const BODY: &str = "{{body}}";
+
+pub(crate) const DEFAULT_TEMPLATE: &str = "#!/usr/bin/env bash
+{{directives}}
+set -e
+{{prologue}}
+{{body}}";
+
+#[derive(Debug, Clone)]
+pub(crate) struct BatchScriptTemplate {
+    template: String,
+    prologue: Vec<String>,
+}
+
+impl Default for BatchScriptTemplate {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_TEMPLATE.to_string(),
+            prologue: Vec::new(),
+        }
+    }
+}
+
+impl BatchScriptTemplate {
+    pub(crate) fn new(template: String, prologue: Vec<String>) -> Result<Self, CliError> {
+        if !template.contains(BODY) {
+            return Err(CliError::Config(CoreError::InvalidConfig {
+                detail: format!("sbatch template is missing the {} placeholder", BODY),
+            }));
+        }
+        Ok(Self { template, prologue })
+    }
+
+    pub(crate) fn load(path: Option<&Path>, prologue: Vec<String>) -> Result<Self, CliError> {
+        let template = match path {
+            Some(path) => fs::read_to_string(path).map_err(|e| {
+                CliError::execution_failed(
+                    format!("Failed to read sbatch template '{}'", path.display()),
+                    e.to_string(),
+                )
+            })?,
+            None => DEFAULT_TEMPLATE.to_string(),
+        };
+        Self::new(template, prologue)
+    }
+
+    pub(crate) fn render(&self, directives: &[String], body: &str) -> String {
+        self.render_with_prefix("#SBATCH", directives, body)
+    }
+
+    pub(crate) fn render_with_prefix(
+        &self,
+        prefix: &str,
+        directives: &[String],
+        body: &str,
+    ) -> String {
+        let directives = directives
+            .iter()
+            .map(|directive| format!("{} {}", prefix, directive))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut script = self
+            .template
+            .replace(DIRECTIVES, &directives)
+            .replace(PROLOGUE, &self.prologue.join("\n"))
+            .replace(BODY, body);
+        if !script.ends_with('\n') {
+            script.push('\n');
+        }
+        script
+    }
+}
+
+pub(crate) fn write_versioned(repx_dir: &Path, script: &str) -> Result<(), CliError> {
+    fs::create_dir_all(repx_dir)?;
+    fs::write(repx_dir.join(SCRIPT_FILE), script)?;
+    Ok(())
+}
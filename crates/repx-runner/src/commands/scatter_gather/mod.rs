@@ -6,22 +6,34 @@ use repx_core::{
     constants::{dirs, manifests, markers},
     errors::CoreError,
     fs_utils::path_to_string,
-    model::{JobId, Memory, MountPolicy, SlurmTime},
-    store::completion_log,
+    model::{
+        DataMount, ExecutionType, JobId, Memory, MountPolicy, NetworkMode, SeccompProfile,
+        SlurmTime,
+    },
+    store::{
+        completion_log,
+        progress::{self, ScatterProgress, StepState},
+        work_items,
+    },
 };
-use repx_executor::{CancellationToken, ExecutionRequest, Executor, Runtime};
+use repx_executor::{CancellationToken, ExecutionRequest, Executor, Runtime, Secret};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
+    io::{Read, Seek, SeekFrom},
     path::{Path, PathBuf},
+    time::Duration,
 };
-use tokio::process::Command as TokioCommand;
 
 use super::write_marker;
 
+pub(crate) mod batch_script;
 pub(crate) mod inputs;
+pub(crate) mod scratch;
+pub(crate) mod sge;
 pub(crate) mod slurm;
 pub(crate) mod toposort;
 
@@ -39,6 +51,10 @@ pub struct StepMeta {
     pub inputs: Vec<StepInputMapping>,
     #[serde(default)]
     pub resource_hints: Option<StepResourceHints>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<ExecutionType>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +107,7 @@ pub(crate) struct ScatterGatherOrchestrator {
     pub(crate) inputs_json_path: PathBuf,
     pub(crate) parameters_json_path: PathBuf,
     pub(crate) runtime: Runtime,
+    pub(crate) execution_type: ExecutionType,
     pub(crate) job_package_path: PathBuf,
     pub(crate) static_inputs: Value,
     pub(crate) host_tools_bin_dir: Option<PathBuf>,
@@ -98,6 +115,17 @@ pub(crate) struct ScatterGatherOrchestrator {
     pub(crate) local_artifacts_path: Option<PathBuf>,
     pub(crate) lab_tar_path: Option<PathBuf>,
     pub(crate) mount_policy: MountPolicy,
+    pub(crate) data_mounts: Vec<DataMount>,
+    pub(crate) network: Option<NetworkMode>,
+    pub(crate) seccomp: Option<SeccompProfile>,
+    pub(crate) pre_run: Option<PathBuf>,
+    pub(crate) post_run: Option<PathBuf>,
+    pub(crate) image_cache_limit: Option<u64>,
+    pub(crate) min_disk: Option<u64>,
+    pub(crate) env: HashMap<String, String>,
+    pub(crate) env_passthrough: Vec<String>,
+    pub(crate) secrets: Vec<Secret>,
+    pub(crate) batch_template: batch_script::BatchScriptTemplate,
 }
 
 impl ScatterGatherOrchestrator {
@@ -139,6 +167,7 @@ impl ScatterGatherOrchestrator {
             inputs_json_path,
             parameters_json_path,
             runtime,
+            execution_type: args.runtime,
             job_package_path: resolve(&args.job_package_path),
             static_inputs: Value::Object(Default::default()),
             host_tools_bin_dir,
@@ -146,9 +175,33 @@ impl ScatterGatherOrchestrator {
             local_artifacts_path: args.local_artifacts_path.clone(),
             lab_tar_path: args.lab_tar_path.clone(),
             mount_policy: MountPolicy::from_flags(args.mount_host_paths, args.mount_paths.clone()),
+            data_mounts: args.data_mounts.clone(),
+            network: args.network,
+            seccomp: args.seccomp.clone(),
+            pre_run: args.pre_run.clone(),
+            post_run: args.post_run.clone(),
+            image_cache_limit: args.image_cache_max_bytes,
+            min_disk: args.min_disk,
+            env: args.env.iter().cloned().collect(),
+            env_passthrough: args.env_passthrough.clone(),
+            secrets: super::load_job_secrets(&args.job_id)?,
+            batch_template: batch_script::BatchScriptTemplate::load(
+                args.sbatch_template.as_deref(),
+                args.sbatch_prologue.clone(),
+            )?,
         })
     }
 
+    pub(crate) fn check_disk_space(&self) -> Result<(), CliError> {
+        let Some(required) = self.min_disk.filter(|&bytes| bytes > 0) else {
+            return Ok(());
+        };
+        let paths =
+            std::iter::once(self.base_path.as_path()).chain(self.node_local_path.as_deref());
+        repx_executor::check_free_space(self.job_id.as_str(), paths, required)
+            .map_err(|e| CliError::execution_failed("Disk space preflight failed", e.to_string()))
+    }
+
     pub(crate) fn init_dirs(&mut self) -> Result<(), CliError> {
         for dir in [
             &self.user_out_dir,
@@ -160,6 +213,7 @@ impl ScatterGatherOrchestrator {
         }
         let _ = fs::remove_file(self.repx_dir.join(markers::SUCCESS));
         let _ = fs::remove_file(self.repx_dir.join(markers::FAIL));
+        let _ = fs::remove_file(self.repx_dir.join(markers::CANCELLED));
 
         self.load_static_inputs()?;
         Ok(())
@@ -173,10 +227,26 @@ impl ScatterGatherOrchestrator {
         Ok(())
     }
 
-    pub(crate) fn create_executor(&self, user_out: PathBuf, repx_out: PathBuf) -> Executor {
+    pub(crate) fn step_runtime(&self, step: &StepMeta) -> Result<Runtime, CliError> {
+        if step.runtime.is_none() && step.image_tag.is_none() {
+            return Ok(self.runtime.clone());
+        }
+        let image_tag = step
+            .image_tag
+            .clone()
+            .or_else(|| self.runtime.image_tag().map(|tag| tag.to_string()));
+        super::parse_runtime(step.runtime.unwrap_or(self.execution_type), image_tag)
+    }
+
+    pub(crate) fn create_executor(
+        &self,
+        runtime: Runtime,
+        user_out: PathBuf,
+        repx_out: PathBuf,
+    ) -> Executor {
         Executor::new(ExecutionRequest {
             job_id: self.job_id.clone(),
-            runtime: self.runtime.clone(),
+            runtime,
             base_path: self.base_path.clone(),
             node_local_path: self.node_local_path.clone(),
             local_artifacts_path: self.local_artifacts_path.clone(),
@@ -186,15 +256,40 @@ impl ScatterGatherOrchestrator {
             repx_out_dir: repx_out,
             host_tools_bin_dir: self.host_tools_bin_dir.clone(),
             mount_policy: self.mount_policy.clone(),
+            data_mounts: self.data_mounts.clone(),
+            network: self.network,
+            seccomp: self.seccomp.clone(),
+            pre_run: None,
+            post_run: None,
+            image_cache_limit: self.image_cache_limit,
+            min_disk: self.min_disk,
             inputs_data: None,
             parameters_data: None,
+            interpreter: None,
+            arg_template: Vec::new(),
+            resource_limits: None,
+            time_limit: None,
+            max_retries: 0,
+            retry_backoff: Duration::ZERO,
+            retry_exit_codes: Vec::new(),
+            failure_rules: Vec::new(),
+            checkpoint_interval: None,
+            heartbeat_interval: None,
+            structured_logs: false,
+            env: self.env.clone(),
+            env_passthrough: self.env_passthrough.clone(),
+            secrets: self.secrets.clone(),
         })
     }
 
     async fn run_scatter(&self, exe_path: &Path) -> Result<(), CliError> {
         tracing::info!("[1/4] Starting scatter phase for job '{}'...", self.job_id);
-        let mut executor =
-            self.create_executor(self.scatter_out_dir.clone(), self.scatter_repx_dir.clone());
+        let mut executor = self.create_executor(
+            self.runtime.clone(),
+            self.scatter_out_dir.clone(),
+            self.scatter_repx_dir.clone(),
+        );
+        executor.request.pre_run = self.pre_run.clone();
         let args = vec![
             path_to_string(&self.scatter_out_dir),
             path_to_string(&self.inputs_json_path),
@@ -259,7 +354,12 @@ impl ScatterGatherOrchestrator {
             serde_json::to_string_pretty(&gather_inputs)?,
         )?;
 
-        let mut executor = self.create_executor(self.user_out_dir.clone(), self.repx_dir.clone());
+        let mut executor = self.create_executor(
+            self.runtime.clone(),
+            self.user_out_dir.clone(),
+            self.repx_dir.clone(),
+        );
+        executor.request.post_run = self.post_run.clone();
         let args = vec![
             path_to_string(&self.user_out_dir),
             path_to_string(&gather_inputs_json_path),
@@ -284,7 +384,10 @@ async fn run_scatter_if_needed(
     scatter_exe_path: &Path,
 ) -> Result<bool, CliError> {
     let already_succeeded = orch.scatter_repx_dir.join(markers::SUCCESS).exists()
-        && orch.scatter_out_dir.join("work_items.json").exists();
+        && orch
+            .scatter_out_dir
+            .join(work_items::WORK_ITEMS_FILE)
+            .exists();
 
     if already_succeeded {
         return Ok(true);
@@ -295,7 +398,11 @@ async fn run_scatter_if_needed(
         fs::create_dir_all(&orch.scatter_out_dir)?;
     }
 
-    if let Err(e) = orch.run_scatter(scatter_exe_path).await {
+    let scatter = match orch.run_scatter(scatter_exe_path).await {
+        Ok(()) => work_items::finalize(&orch.scatter_out_dir).map_err(CliError::from),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = scatter {
         write_marker(&orch.scatter_repx_dir.join(markers::FAIL))?;
         write_marker(&orch.repx_dir.join(markers::FAIL))?;
         tracing::error!("Scatter failed: {}", e);
@@ -308,54 +415,98 @@ async fn run_scatter_if_needed(
 async fn handle_phase_scatter_only(
     orch: &mut ScatterGatherOrchestrator,
     args: &InternalScatterGatherArgs,
+    steps_meta: &StepsMetadata,
+    topo_order: &[String],
 ) -> Result<(), CliError> {
     orch.init_dirs()?;
+    record_progress(&orch.repx_dir, |p| {
+        p.start(topo_order, &steps_meta.sink_step)
+    });
 
     let skipped = run_scatter_if_needed(orch, &args.scatter_exe_path).await?;
     if skipped {
         tracing::info!("Scatter already succeeded (SUCCESS marker exists), skipping re-execution.");
     }
+
+    let branches_total = work_items::read(&orch.scatter_out_dir)?.len();
+    let retained: Vec<HashSet<String>> = (0..branches_total)
+        .map(|i| {
+            retained_steps(
+                &orch.job_root.join(format!("branch-{}", i)),
+                &steps_meta.steps,
+                topo_order,
+            )
+        })
+        .collect();
+    record_progress(&orch.repx_dir, |p| {
+        p.branches_total = branches_total;
+        for (branch, steps) in retained.iter().enumerate() {
+            for step in steps {
+                p.set_step(branch, step, StepState::Succeeded);
+            }
+        }
+    });
     Ok(())
 }
 
-fn invalidate_stale_step_markers(
+pub(crate) fn retained_steps(
     branch_root: &Path,
-    work_item_path: &Path,
-    new_work_item_json: &str,
     steps: &HashMap<String, StepMeta>,
-    branch_idx: usize,
-) -> Result<(), CliError> {
-    if work_item_path.exists() {
-        let old = match fs::read_to_string(work_item_path) {
-            Ok(content) => content,
-            Err(e) => {
-                tracing::warn!(
-                    "Failed to read previous work item at '{}': {}. Treating as changed.",
-                    work_item_path.display(),
-                    e
-                );
-                String::new()
-            }
-        };
-        if old != new_work_item_json {
-            tracing::info!(
-                "Branch {} work item changed, invalidating step markers",
-                branch_idx
-            );
-            let topo_order = toposort::toposort_steps(steps)?;
-            for s in &topo_order {
-                let sr = branch_root.join(format!("step-{}", s)).join(dirs::REPX);
-                let _ = fs::remove_file(sr.join(markers::SUCCESS));
-                let _ = fs::remove_file(sr.join(markers::FAIL));
-            }
+    topo_order: &[String],
+) -> HashSet<String> {
+    let mut retained = HashSet::new();
+    for step_name in topo_order {
+        let deps_retained = steps
+            .get(step_name)
+            .is_none_or(|meta| meta.deps.iter().all(|dep| retained.contains(dep)));
+        let succeeded = branch_root
+            .join(format!("step-{}", step_name))
+            .join(dirs::REPX)
+            .join(markers::SUCCESS)
+            .exists();
+        if deps_retained && succeeded {
+            retained.insert(step_name.clone());
         }
     }
-    Ok(())
+    retained
+}
+
+pub(crate) fn step_input_hash(
+    step_meta: &StepMeta,
+    inputs_json: &str,
+    work_item_json: &str,
+    branch_root: &Path,
+) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        path_to_string(&step_meta.exe_path).as_str(),
+        inputs_json,
+        work_item_json,
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    let mut deps: Vec<&String> = step_meta.deps.iter().collect();
+    deps.sort();
+    for dep in deps {
+        let dep_hash = fs::read_to_string(
+            branch_root
+                .join(format!("step-{}", dep))
+                .join(dirs::REPX)
+                .join(manifests::STEP_INPUT_HASH),
+        )
+        .unwrap_or_default();
+        hasher.update(dep.as_bytes());
+        hasher.update(dep_hash.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
 }
 
 fn clear_step_markers(step_repx: &Path) {
     let _ = fs::remove_file(step_repx.join(markers::SUCCESS));
     let _ = fs::remove_file(step_repx.join(markers::FAIL));
+    let _ = fs::remove_file(step_repx.join(manifests::STEP_INPUT_HASH));
 
     if let Some(step_root) = step_repx.parent() {
         let step_out = step_root.join(dirs::OUT);
@@ -365,6 +516,12 @@ fn clear_step_markers(step_repx: &Path) {
     }
 }
 
+pub(crate) fn record_progress(repx_dir: &Path, change: impl FnOnce(&mut ScatterProgress)) {
+    if let Err(e) = progress::update(repx_dir, change) {
+        tracing::warn!("Failed to update progress in {}: {}", repx_dir.display(), e);
+    }
+}
+
 async fn handle_phase_step(
     orch: &mut ScatterGatherOrchestrator,
     args: &InternalScatterGatherArgs,
@@ -393,8 +550,7 @@ async fn handle_phase_step(
     let branch_repx = branch_root.join(dirs::REPX);
     fs::create_dir_all(&branch_repx)?;
 
-    let work_items_str = fs::read_to_string(orch.scatter_out_dir.join("work_items.json"))?;
-    let work_items: Vec<Value> = serde_json::from_str(&work_items_str)?;
+    let work_items = work_items::read(&orch.scatter_out_dir)?;
     let item = work_items.get(branch_idx).ok_or_else(|| {
         CliError::Config(CoreError::InvalidConfig {
             detail: format!(
@@ -404,17 +560,10 @@ async fn handle_phase_step(
             ),
         })
     })?;
-    let new_work_item_json = serde_json::to_string(item)?;
+    let work_item_json = serde_json::to_string(item)?;
 
     let work_item_path = branch_repx.join("work_item.json");
-    invalidate_stale_step_markers(
-        &branch_root,
-        &work_item_path,
-        &new_work_item_json,
-        &steps_meta.steps,
-        branch_idx,
-    )?;
-    fs::write(&work_item_path, &new_work_item_json)?;
+    fs::write(&work_item_path, &work_item_json)?;
 
     let step_out = branch_root
         .join(format!("step-{}", step_name))
@@ -423,32 +572,126 @@ async fn handle_phase_step(
         .join(format!("step-{}", step_name))
         .join(dirs::REPX);
     fs::create_dir_all(&step_repx)?;
-    clear_step_markers(&step_repx);
-    fs::create_dir_all(&step_out)?;
 
-    let inputs = inputs::resolve_step_inputs(
+    // Grid Engine starts a step once its upstream tasks have ended, whether
+    // or not they succeeded, so the step must not run in that case either.
+    let tolerate_failure = args.min_branch_success.is_some();
+    let failed_dep = step_meta.deps.iter().find(|dep| {
+        !branch_root
+            .join(format!("step-{}", dep))
+            .join(dirs::REPX)
+            .join(markers::SUCCESS)
+            .exists()
+    });
+    if let Some(dep) = failed_dep {
+        tracing::warn!(
+            "Skipping branch #{} step '{}': step '{}' did not succeed.",
+            branch_idx,
+            step_name,
+            dep
+        );
+        clear_step_markers(&step_repx);
+        write_marker(&step_repx.join(markers::FAIL))?;
+        record_progress(&orch.repx_dir, |p| {
+            p.set_step(branch_idx, step_name, StepState::Failed)
+        });
+        if tolerate_failure {
+            return Ok(());
+        }
+        return Err(CliError::ExecutionFailed {
+            message: format!("Branch #{} step '{}' was not run", branch_idx, step_name),
+            log_path: Some(step_repx),
+            log_summary: format!("Step '{}' did not succeed", dep),
+        });
+    }
+
+    let mut inputs = inputs::resolve_step_inputs(
         step_meta,
         &branch_root,
         &work_item_path,
         &orch.static_inputs,
         &steps_meta.steps,
     )?;
+    let inputs_json = serde_json::to_string_pretty(&inputs)?;
+    let input_hash = step_input_hash(step_meta, &inputs_json, &work_item_json, &branch_root);
+    let previous_hash = fs::read_to_string(step_repx.join(manifests::STEP_INPUT_HASH)).ok();
+    if step_repx.join(markers::SUCCESS).exists() && previous_hash.as_deref() == Some(&input_hash) {
+        tracing::info!(
+            "Branch #{} step '{}' inputs unchanged, reusing its previous outputs.",
+            branch_idx,
+            step_name
+        );
+        record_progress(&orch.repx_dir, |p| {
+            p.set_step(branch_idx, step_name, StepState::Succeeded)
+        });
+        return Ok(());
+    }
+
+    clear_step_markers(&step_repx);
+    record_progress(&orch.repx_dir, |p| {
+        p.set_step(branch_idx, step_name, StepState::Running)
+    });
+    fs::create_dir_all(&step_out)?;
+
+    let scratch = match &orch.node_local_path {
+        Some(node_local) => {
+            let key = Path::new(orch.job_id.as_str())
+                .join(format!("branch-{}", branch_idx))
+                .join(format!("step-{}", step_name));
+            let scratch = scratch::StepScratch::create(node_local, &key)?;
+            scratch.stage_inputs(&mut inputs)?;
+            Some(scratch)
+        }
+        None => None,
+    };
+    let run_out = scratch
+        .as_ref()
+        .map_or_else(|| step_out.clone(), |scratch| scratch.out_dir());
+
     let step_inputs_path = step_repx.join("inputs.json");
     fs::write(&step_inputs_path, serde_json::to_string_pretty(&inputs)?)?;
 
-    let mut executor = orch.create_executor(step_out.clone(), step_repx.clone());
+    let runtime = orch.step_runtime(step_meta)?;
+    let mut executor = orch.create_executor(runtime, run_out.clone(), step_repx.clone());
+    if let Some(scratch) = &scratch {
+        executor.request.data_mounts.push(DataMount {
+            host_path: scratch.inputs_dir(),
+            mount_path: None,
+        });
+    }
     let exec_args = vec![
-        path_to_string(&step_out),
+        path_to_string(&run_out),
         path_to_string(&step_inputs_path),
         path_to_string(&orch.parameters_json_path),
     ];
 
-    let cancel = CancellationToken::new();
-    match executor
+    let cancel = super::cancel_on_sigterm();
+    let mut result = executor
         .execute_script(&step_meta.exe_path, &exec_args, &cancel)
         .await
-    {
-        Ok(_) => {
+        .map(|_| ())
+        .map_err(|e| e.to_string());
+    if let (Ok(()), Some(scratch)) = (&result, &scratch) {
+        result = scratch.copy_back(&step_out).map_err(|e| {
+            format!(
+                "Failed to copy outputs back from node-local scratch '{}': {}",
+                run_out.display(),
+                e
+            )
+        });
+    }
+    drop(scratch);
+
+    let state = if result.is_ok() {
+        StepState::Succeeded
+    } else {
+        StepState::Failed
+    };
+    record_progress(&orch.repx_dir, |p| p.set_step(branch_idx, step_name, state));
+
+    match result {
+        Ok(()) => {
+            fs::write(step_repx.join(manifests::STEP_INPUT_HASH), &input_hash)?;
             write_marker(&step_repx.join(markers::SUCCESS))?;
             tracing::info!(
                 "Branch #{} step '{}' completed successfully.",
@@ -456,58 +699,114 @@ async fn handle_phase_step(
                 step_name
             );
         }
+        Err(e) if tolerate_failure => {
+            write_marker(&step_repx.join(markers::FAIL))?;
+            tracing::warn!(
+                "Branch #{} step '{}' failed, leaving the branch out of the gather: {}",
+                branch_idx,
+                step_name,
+                e
+            );
+        }
         Err(e) => {
             let _ = write_marker(&step_repx.join(markers::FAIL));
             return Err(CliError::ExecutionFailed {
                 message: format!("Branch #{} step '{}' failed", branch_idx, step_name),
                 log_path: Some(step_repx),
-                log_summary: e.to_string(),
+                log_summary: e,
             });
         }
     }
     Ok(())
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SkippedBranch {
+    pub branch: usize,
+    pub work_item: Value,
+}
+
 async fn handle_phase_gather(
     orch: &mut ScatterGatherOrchestrator,
     args: &InternalScatterGatherArgs,
     sink_step: &str,
 ) -> Result<(), CliError> {
     orch.init_dirs()?;
-    let work_items_str = fs::read_to_string(orch.scatter_out_dir.join("work_items.json"))?;
-    let work_items: Vec<Value> = serde_json::from_str(&work_items_str)?;
+    let work_items = work_items::read(&orch.scatter_out_dir)?;
+    record_progress(&orch.repx_dir, |p| p.gather = Some(StepState::Running));
 
     let mut branch_sink_out_dirs = Vec::new();
-    for i in 0..work_items.len() {
-        let branch_root = orch.job_root.join(format!("branch-{}", i));
-        let sink_step_repx = branch_root
-            .join(format!("step-{}", sink_step))
-            .join(dirs::REPX);
-        if !sink_step_repx.join(markers::SUCCESS).exists() {
-            let msg = format!(
-                "Branch #{} sink step '{}' SUCCESS marker not found.",
-                i, sink_step
-            );
-            tracing::error!("{}", msg);
-            write_marker(&orch.repx_dir.join(markers::FAIL))?;
-            slurm::cancel_workers_from_manifest(&orch.repx_dir).await;
-            if let Some(anchor) = args.anchor_id {
-                let _ = TokioCommand::new("scancel")
-                    .arg(anchor.to_string())
-                    .output()
-                    .await;
-            }
-            return Err(CliError::ExecutionFailed {
-                message: msg,
-                log_path: Some(sink_step_repx),
-                log_summary: "Branch did not complete all steps successfully".into(),
+    let mut skipped = Vec::new();
+    for (i, item) in work_items.iter().enumerate() {
+        let sink_step_root = orch
+            .job_root
+            .join(format!("branch-{}", i))
+            .join(format!("step-{}", sink_step));
+        if sink_step_root
+            .join(dirs::REPX)
+            .join(markers::SUCCESS)
+            .exists()
+        {
+            branch_sink_out_dirs.push(sink_step_root.join(dirs::OUT));
+        } else {
+            skipped.push(SkippedBranch {
+                branch: i,
+                work_item: item.clone(),
             });
         }
-        branch_sink_out_dirs.push(
-            branch_root
+    }
+
+    let required = args
+        .min_branch_success
+        .map_or(work_items.len(), |quota| quota.required(work_items.len()));
+    if branch_sink_out_dirs.len() < required {
+        let msg = match args.min_branch_success {
+            Some(quota) => format!(
+                "Only {} of {} branches succeeded, below min_branch_success {} ({} required).",
+                branch_sink_out_dirs.len(),
+                work_items.len(),
+                quota,
+                required
+            ),
+            None => format!(
+                "Branch #{} sink step '{}' SUCCESS marker not found.",
+                skipped.first().map_or(0, |s| s.branch),
+                sink_step
+            ),
+        };
+        tracing::error!("{}", msg);
+        write_marker(&orch.repx_dir.join(markers::FAIL))?;
+        record_progress(&orch.repx_dir, |p| p.gather = Some(StepState::Failed));
+        slurm::cancel_workers_from_manifest(&orch.repx_dir, args.scheduler).await;
+        slurm::cancel_anchor(args).await;
+        let log_path = skipped.first().map(|s| {
+            orch.job_root
+                .join(format!("branch-{}", s.branch))
                 .join(format!("step-{}", sink_step))
-                .join(dirs::OUT),
+                .join(dirs::REPX)
+        });
+        return Err(CliError::ExecutionFailed {
+            message: msg,
+            log_path,
+            log_summary: if args.min_branch_success.is_some() {
+                "Too few branches completed all steps successfully".into()
+            } else {
+                "Branch did not complete all steps successfully".into()
+            },
+        });
+    }
+
+    let skipped_path = orch.repx_dir.join(manifests::SKIPPED_BRANCHES);
+    if skipped.is_empty() {
+        let _ = fs::remove_file(&skipped_path);
+    } else {
+        tracing::warn!(
+            "Gathering {} of {} branches; skipped branches: {:?}",
+            branch_sink_out_dirs.len(),
+            work_items.len(),
+            skipped.iter().map(|s| s.branch).collect::<Vec<_>>()
         );
+        fs::write(&skipped_path, serde_json::to_string_pretty(&skipped)?)?;
     }
 
     match orch
@@ -520,38 +819,117 @@ async fn handle_phase_gather(
     {
         Ok(_) => {
             write_marker(&orch.repx_dir.join(markers::SUCCESS))?;
-            if let Err(e) = completion_log::append_completion(&orch.base_path, &orch.job_id, true) {
+            record_progress(&orch.repx_dir, |p| p.gather = Some(StepState::Succeeded));
+            if let Err(e) =
+                completion_log::append_completion(&orch.base_path, &orch.job_id, true, None)
+            {
                 tracing::debug!("Failed to append to completion log: {}", e);
             }
-            if let Some(anchor) = args.anchor_id {
-                tracing::info!("Releasing anchor job {}", anchor);
-                let _ = TokioCommand::new("scontrol")
-                    .arg("release")
-                    .arg(anchor.to_string())
-                    .output()
-                    .await;
+            if args.auto_prune_steps {
+                let pruned = crate::commands::prune::prune_step_outputs(&orch.job_root, false);
+                tracing::info!(
+                    "Pruned {} step output(s), freeing {} bytes",
+                    pruned.dirs,
+                    pruned.bytes
+                );
             }
+            slurm::release_anchor(args).await;
         }
         Err(e) => {
             write_marker(&orch.repx_dir.join(markers::FAIL))?;
+            record_progress(&orch.repx_dir, |p| p.gather = Some(StepState::Failed));
             if let Err(err) =
-                completion_log::append_completion(&orch.base_path, &orch.job_id, false)
+                completion_log::append_completion(&orch.base_path, &orch.job_id, false, None)
             {
                 tracing::debug!("Failed to append to completion log: {}", err);
             }
-            slurm::cancel_workers_from_manifest(&orch.repx_dir).await;
-            if let Some(anchor) = args.anchor_id {
-                let _ = TokioCommand::new("scancel")
-                    .arg(anchor.to_string())
-                    .output()
-                    .await;
-            }
+            slurm::cancel_workers_from_manifest(&orch.repx_dir, args.scheduler).await;
+            slurm::cancel_anchor(args).await;
             return Err(e);
         }
     }
     Ok(())
 }
 
+const WORK_ITEM_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+struct StreamedBranches {
+    offset: u64,
+    items: Vec<Value>,
+    last_step_slurm_ids: Vec<String>,
+    worker_slurm_ids: Vec<u64>,
+}
+
+impl StreamedBranches {
+    async fn submit_new(
+        &mut self,
+        orch: &ScatterGatherOrchestrator,
+        args: &InternalScatterGatherArgs,
+        steps_meta: &StepsMetadata,
+        topo_order: &[String],
+    ) -> Result<(), CliError> {
+        let first_branch = self.items.len();
+        let new_items = self.read_new(
+            &orch
+                .scatter_out_dir
+                .join(work_items::WORK_ITEMS_STREAM_FILE),
+        )?;
+        if new_items.is_empty() {
+            return Ok(());
+        }
+        self.items.extend(new_items);
+        tracing::info!(
+            "Scatter streamed {} work item(s) so far, submitting branches {}..{}",
+            self.items.len(),
+            first_branch,
+            self.items.len()
+        );
+        let (last_step_slurm_ids, worker_slurm_ids) = slurm::submit_slurm_branches(
+            orch,
+            args,
+            &self.items,
+            steps_meta,
+            topo_order,
+            &args.step_sbatch_opts,
+            false,
+            first_branch,
+        )
+        .await?;
+        self.last_step_slurm_ids.extend(last_step_slurm_ids);
+        self.worker_slurm_ids.extend(worker_slurm_ids);
+        self.write_manifest(&orch.repx_dir)
+    }
+
+    fn read_new(&mut self, stream_path: &Path) -> Result<Vec<Value>, CliError> {
+        let mut file = match fs::File::open(stream_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        let (items, consumed) = work_items::parse_stream(&String::from_utf8_lossy(&buf))?;
+        self.offset += consumed as u64;
+        Ok(items)
+    }
+
+    fn write_manifest(&self, repx_dir: &Path) -> Result<(), CliError> {
+        let manifest_path = repx_dir.join(manifests::WORKER_SLURM_IDS);
+        fs::write(
+            &manifest_path,
+            serde_json::to_string(&self.worker_slurm_ids)?,
+        )?;
+        tracing::info!(
+            "Wrote {} worker SLURM IDs to {}",
+            self.worker_slurm_ids.len(),
+            manifest_path.display()
+        );
+        Ok(())
+    }
+}
+
 async fn async_handle_scatter_gather(
     mut args: InternalScatterGatherArgs,
     verbose: repx_core::logging::Verbosity,
@@ -592,21 +970,30 @@ async fn async_handle_scatter_gather(
     }
 
     let mut orch = ScatterGatherOrchestrator::new(&args)?;
+    orch.check_disk_space()?;
 
     match args.phase {
         ScatterGatherPhase::ScatterOnly => {
-            return handle_phase_scatter_only(&mut orch, &args).await;
+            return handle_phase_scatter_only(&mut orch, &args, &steps_meta, &topo_order).await;
         }
         ScatterGatherPhase::Step => {
             return handle_phase_step(&mut orch, &args, &steps_meta).await;
         }
         ScatterGatherPhase::Gather => {
-            return handle_phase_gather(&mut orch, &args, sink_step).await;
+            let result = handle_phase_gather(&mut orch, &args, sink_step).await;
+            crate::commands::publish_outputs(
+                args.object_store.as_deref(),
+                args.object_store_endpoint.as_deref(),
+                &args.base_path,
+                &orch.job_id,
+            );
+            return result;
         }
         ScatterGatherPhase::All => {}
     }
 
     orch.init_dirs()?;
+    record_progress(&orch.repx_dir, |p| p.start(&topo_order, sink_step));
     tracing::info!(
         "Orchestrating scatter-gather stage '{}' with {} step(s) in DAG order: {:?}",
         orch.job_id,
@@ -614,54 +1001,90 @@ async fn async_handle_scatter_gather(
         topo_order
     );
 
-    match run_scatter_if_needed(&orch, &args.scatter_exe_path).await {
+    let streaming = args.scheduler == repx_core::model::SchedulerType::Slurm;
+    let mut stream = StreamedBranches::default();
+    let scatter = run_scatter_if_needed(&orch, &args.scatter_exe_path);
+    tokio::pin!(scatter);
+    let mut poll = tokio::time::interval(WORK_ITEM_POLL_INTERVAL);
+    let scatter_result = loop {
+        tokio::select! {
+            biased;
+            res = &mut scatter => break res,
+            _ = poll.tick(), if streaming => {
+                if let Err(e) = stream.submit_new(&orch, &args, &steps_meta, &topo_order).await {
+                    break Err(e);
+                }
+            }
+        }
+    };
+    let scatter_reused = match scatter_result {
         Ok(true) => {
             tracing::info!(
                 "[1/4] Scatter already succeeded (SUCCESS marker exists), skipping re-execution."
             );
+            true
         }
-        Ok(false) => {}
+        Ok(false) => false,
         Err(e) => {
-            slurm::cancel_workers_from_manifest(&orch.repx_dir).await;
-            if let Some(anchor) = args.anchor_id {
-                let _ = TokioCommand::new("scancel")
-                    .arg(anchor.to_string())
-                    .output()
-                    .await;
-            }
+            slurm::cancel_workers_from_manifest(&orch.repx_dir, args.scheduler).await;
+            slurm::cancel_anchor(&args).await;
             return Err(e);
         }
-    }
+    };
 
     tracing::info!("[2/4] Scatter finished. Reading work items...");
-    let work_items_str = fs::read_to_string(orch.scatter_out_dir.join("work_items.json"))?;
-    let work_items: Vec<Value> = serde_json::from_str(&work_items_str)?;
+    let work_items = work_items::read(&orch.scatter_out_dir)?;
+    if work_items.len() < stream.items.len() {
+        return Err(CliError::Config(CoreError::InconsistentMetadata {
+            detail: format!(
+                "Scatter streamed {} work items but its final list has only {}",
+                stream.items.len(),
+                work_items.len()
+            ),
+        }));
+    }
 
     match args.scheduler {
         repx_core::model::SchedulerType::Slurm => {
-            let (last_step_slurm_ids, all_worker_slurm_ids) = slurm::submit_slurm_branches(
+            let (last_step_slurm_ids, worker_slurm_ids) = slurm::submit_slurm_branches(
                 &orch,
                 &args,
                 &work_items,
                 &steps_meta,
                 &topo_order,
                 &args.step_sbatch_opts,
+                args.retry_failed && scatter_reused,
+                stream.items.len(),
             )
             .await?;
+            stream.last_step_slurm_ids.extend(last_step_slurm_ids);
+            stream.worker_slurm_ids.extend(worker_slurm_ids);
+            stream.write_manifest(&orch.repx_dir)?;
+
+            slurm::submit_slurm_gather_job(&orch, &args, &stream.last_step_slurm_ids, verbose)
+                .await?;
 
-            let manifest_path = orch.repx_dir.join(manifests::WORKER_SLURM_IDS);
-            let manifest_json = serde_json::to_string(&all_worker_slurm_ids)?;
-            fs::write(&manifest_path, manifest_json)?;
             tracing::info!(
-                "Wrote {} worker SLURM IDs to {}",
-                all_worker_slurm_ids.len(),
-                manifest_path.display()
+                "Orchestrator finished submitting branches and gather job. Exiting to free slot."
             );
+        }
+        repx_core::model::SchedulerType::Sge => {
+            let (sink_array_id, worker_ids) = sge::submit_sge_branches(
+                &orch,
+                &args,
+                &work_items,
+                &steps_meta,
+                &topo_order,
+                verbose,
+            )
+            .await?;
+            stream.worker_slurm_ids.extend(worker_ids);
+            stream.write_manifest(&orch.repx_dir)?;
 
-            slurm::submit_slurm_gather_job(&orch, &args, &last_step_slurm_ids, verbose).await?;
+            sge::submit_sge_gather_job(&orch, &args, sink_array_id, verbose).await?;
 
             tracing::info!(
-                "Orchestrator finished submitting branches and gather job. Exiting to free slot."
+                "Orchestrator finished submitting step arrays and gather job. Exiting to free slot."
             );
         }
         other => {
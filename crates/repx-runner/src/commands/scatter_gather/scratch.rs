@@ -0,0 +1,100 @@
+use repx_core::{constants::dirs, fs_utils};
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const SCRATCH_DIR: &str = "scratch";
+
+pub(crate) struct StepScratch {
+    root: PathBuf,
+}
+
+impl StepScratch {
+    pub(crate) fn create(node_local: &Path, key: &Path) -> io::Result<Self> {
+        let root = node_local.join("repx").join(SCRATCH_DIR).join(key);
+        fs_utils::force_remove_dir(&root)?;
+        let scratch = Self { root };
+        fs::create_dir_all(scratch.inputs_dir())?;
+        fs::create_dir_all(scratch.out_dir())?;
+        Ok(scratch)
+    }
+
+    pub(crate) fn inputs_dir(&self) -> PathBuf {
+        self.root.join("in")
+    }
+
+    pub(crate) fn out_dir(&self) -> PathBuf {
+        self.root.join(dirs::OUT)
+    }
+
+    pub(crate) fn stage_inputs(
+        &self,
+        inputs: &mut serde_json::Map<String, Value>,
+    ) -> io::Result<()> {
+        for (name, value) in inputs.iter_mut() {
+            let Some(source) = value.as_str().map(PathBuf::from) else {
+                continue;
+            };
+            let Some(file_name) = source.file_name() else {
+                continue;
+            };
+            if !source.exists() {
+                continue;
+            }
+            let staged = self.inputs_dir().join(name).join(file_name);
+            copy_recursive(&source, &staged)?;
+            *value = Value::String(fs_utils::path_to_string(&staged));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn copy_back(&self, step_out: &Path) -> io::Result<()> {
+        fs_utils::force_remove_dir(step_out)?;
+        copy_recursive(&self.out_dir(), step_out)
+    }
+}
+
+impl Drop for StepScratch {
+    fn drop(&mut self) {
+        if let Err(e) = fs_utils::force_remove_dir(&self.root) {
+            tracing::warn!(
+                "Failed to remove node-local scratch '{}': {}",
+                self.root.display(),
+                e
+            );
+            return;
+        }
+        for dir in self.root.ancestors().skip(1).take(2) {
+            if fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+fn copy_recursive(source: &Path, dest: &Path) -> io::Result<()> {
+    for entry in WalkDir::new(source).follow_links(false) {
+        let entry = entry.map_err(io::Error::other)?;
+        let target = match entry.path().strip_prefix(source) {
+            Ok(rel) if rel.as_os_str().is_empty() => dest.to_path_buf(),
+            Ok(rel) => dest.join(rel),
+            Err(e) => return Err(io::Error::other(e)),
+        };
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if file_type.is_symlink() {
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
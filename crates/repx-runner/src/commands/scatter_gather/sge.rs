@@ -0,0 +1,201 @@
+use crate::cli::InternalScatterGatherArgs;
+use crate::commands::internal::parse_qsub_job_id;
+use crate::error::CliError;
+use repx_core::{
+    constants::{dirs, sge},
+    errors::CoreError,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command as TokioCommand;
+
+use super::{batch_script, record_progress, slurm, ScatterGatherOrchestrator, StepsMetadata};
+
+fn common_directives(name: String) -> Vec<String> {
+    vec![
+        format!("-N {}", name),
+        "-S /bin/bash".to_string(),
+        "-V".to_string(),
+        "-wd /tmp".to_string(),
+    ]
+}
+
+fn worker_command(
+    orch: &ScatterGatherOrchestrator,
+    args: &InternalScatterGatherArgs,
+    phase: &str,
+    verbose: repx_core::logging::Verbosity,
+) -> Result<Vec<String>, CliError> {
+    let mut command = slurm::phase_command(orch, args, phase, verbose)?;
+    if let Some(quota) = args.min_branch_success {
+        command.push("--min-branch-success".to_string());
+        command.push(quota.to_string());
+    }
+    Ok(command)
+}
+
+pub(crate) async fn submit_sge_branches(
+    orch: &ScatterGatherOrchestrator,
+    args: &InternalScatterGatherArgs,
+    work_items: &[Value],
+    steps_meta: &StepsMetadata,
+    topo_order: &[String],
+    verbose: repx_core::logging::Verbosity,
+) -> Result<(Option<u64>, Vec<u64>), CliError> {
+    if work_items.is_empty() {
+        return Ok((None, Vec::new()));
+    }
+    let qsub_opts = slurm::sbatch_opt_directives(&args.step_sbatch_opts);
+    let bootstrap = slurm::lab_bootstrap(orch);
+    let mut array_ids: HashMap<String, u64> = HashMap::new();
+
+    for step_name in topo_order {
+        let step_meta = steps_meta.steps.get(step_name).ok_or_else(|| {
+            CliError::Config(CoreError::StepError {
+                detail: format!("Step '{}' not found in steps metadata", step_name),
+            })
+        })?;
+
+        let mut command = worker_command(orch, args, "step", verbose)?;
+        command.extend([
+            "--branch-idx".to_string(),
+            "$((SGE_TASK_ID - 1))".to_string(),
+            "--step-name".to_string(),
+            format!("'{}'", step_name),
+        ]);
+        let body = format!("{}{}", bootstrap, command.join(" "));
+
+        let mut directives = common_directives(format!(
+            "{}{}-{}",
+            sge::JOB_NAME_PREFIX,
+            orch.job_id.as_str(),
+            step_name
+        ));
+        directives.extend([
+            "-o /dev/null".to_string(),
+            "-e /dev/null".to_string(),
+            format!("-t 1-{}", work_items.len()),
+        ]);
+        if !qsub_opts.is_empty() {
+            directives.push(qsub_opts.join(" "));
+        }
+        let upstream: Vec<String> = step_meta
+            .deps
+            .iter()
+            .filter_map(|dep| array_ids.get(dep))
+            .map(u64::to_string)
+            .collect();
+        if !upstream.is_empty() {
+            directives.push(format!("-hold_jid_ad {}", upstream.join(",")));
+        }
+        let script = orch
+            .batch_template
+            .render_with_prefix("#$", &directives, &body);
+        for branch_idx in 0..work_items.len() {
+            let step_repx = orch
+                .job_root
+                .join(format!("branch-{}", branch_idx))
+                .join(format!("step-{}", step_name))
+                .join(dirs::REPX);
+            batch_script::write_versioned(&step_repx, &script)?;
+        }
+
+        let what = format!("step '{}'", step_name);
+        let array_id = submit_script(&script, &what).await?;
+        array_ids.insert(step_name.clone(), array_id);
+    }
+
+    record_progress(&orch.repx_dir, |p| {
+        p.branches_total = p.branches_total.max(work_items.len());
+        for (step_name, id) in &array_ids {
+            p.slurm_ids.insert(step_name.clone(), vec![*id]);
+        }
+    });
+
+    tracing::info!(
+        "Submitted {} array jobs of {} tasks to Grid Engine.",
+        array_ids.len(),
+        work_items.len()
+    );
+    let sink_array_id = array_ids.get(&steps_meta.sink_step).copied();
+    let worker_ids = topo_order
+        .iter()
+        .filter_map(|step_name| array_ids.get(step_name))
+        .copied()
+        .collect();
+    Ok((sink_array_id, worker_ids))
+}
+
+pub(crate) async fn submit_sge_gather_job(
+    orch: &ScatterGatherOrchestrator,
+    args: &InternalScatterGatherArgs,
+    sink_array_id: Option<u64>,
+    verbose: repx_core::logging::Verbosity,
+) -> Result<(), CliError> {
+    let command = worker_command(orch, args, "gather", verbose)?;
+    let body = format!("{}{}", slurm::lab_bootstrap(orch), command.join(" "));
+
+    let gather_repx_dir = orch.job_root.join("gather").join(dirs::REPX);
+    let mut directives = common_directives(format!(
+        "{}{}-gather",
+        sge::JOB_NAME_PREFIX,
+        orch.job_id.as_str()
+    ));
+    directives.extend([
+        format!("-o {}/sge-$JOB_ID.out", gather_repx_dir.display()),
+        "-j y".to_string(),
+    ]);
+    if let Some(id) = sink_array_id {
+        directives.push(format!("-hold_jid {}", id));
+    }
+    let script = orch
+        .batch_template
+        .render_with_prefix("#$", &directives, &body);
+    batch_script::write_versioned(&gather_repx_dir, &script)?;
+
+    let gather_id = submit_script(&script, "Gather job").await?;
+    record_progress(&orch.repx_dir, |p| {
+        p.slurm_ids.insert("gather".to_string(), vec![gather_id]);
+    });
+    Ok(())
+}
+
+async fn submit_script(script: &str, what: &str) -> Result<u64, CliError> {
+    let mut qsub = TokioCommand::new("qsub");
+    qsub.arg("-terse")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+
+    let mut child = qsub.spawn().map_err(|e| {
+        CliError::Config(CoreError::CommandFailed(format!(
+            "Failed to spawn qsub for {}: {}",
+            what, e
+        )))
+    })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(script.as_bytes()).await.map_err(|e| {
+            CliError::Config(CoreError::CommandFailed(format!(
+                "Failed to write script to qsub stdin for {}: {}",
+                what, e
+            )))
+        })?;
+        drop(stdin);
+    }
+
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        return Err(CliError::execution_failed(
+            format!("qsub submission for {} failed", what),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_qsub_job_id(&stdout).ok_or_else(|| {
+        CliError::execution_failed(
+            format!("Failed to parse job number from qsub output for {}", what),
+            format!("qsub output was: '{}'", stdout.trim()),
+        )
+    })
+}
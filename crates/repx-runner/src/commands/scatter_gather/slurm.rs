@@ -1,38 +1,96 @@
 use crate::error::CliError;
-use repx_core::{constants::dirs, errors::CoreError, fs_utils::path_to_string};
-use std::collections::HashMap;
+use repx_core::{
+    constants::{dirs, markers},
+    errors::CoreError,
+    fs_utils::path_to_string,
+    model::SchedulerType,
+    store::progress::StepState,
+};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use tokio::io::AsyncWriteExt;
 use tokio::process::Command as TokioCommand;
 
-use super::{inputs::resolve_step_inputs, ScatterGatherOrchestrator, StepsMetadata};
+use super::{
+    batch_script, inputs::resolve_step_inputs, record_progress, retained_steps, step_input_hash,
+    ScatterGatherOrchestrator, StepsMetadata,
+};
 use crate::cli::InternalScatterGatherArgs;
 use repx_core::constants::manifests;
 use serde_json::Value;
 
-pub(crate) async fn cancel_workers_from_manifest(repx_dir: &std::path::Path) {
+pub(super) fn sandbox_flags(orch: &ScatterGatherOrchestrator) -> Vec<String> {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "'\\''"));
+    let mut env: Vec<_> = orch.env.iter().collect();
+    env.sort();
+    let mut flags = Vec::new();
+    for mount in &orch.data_mounts {
+        flags.push("--data-mount".to_string());
+        flags.push(quote(&mount.to_string()));
+    }
+    if let Some(network) = orch.network {
+        flags.push("--network".to_string());
+        flags.push(network.to_string());
+    }
+    if let Some(seccomp) = &orch.seccomp {
+        flags.push("--seccomp".to_string());
+        flags.push(quote(&seccomp.to_string()));
+    }
+    for (flag, hook) in [("--pre-run", &orch.pre_run), ("--post-run", &orch.post_run)] {
+        if let Some(path) = hook {
+            flags.push(flag.to_string());
+            flags.push(quote(&path.to_string_lossy()));
+        }
+    }
+    if let Some(max_bytes) = orch.image_cache_limit {
+        flags.push("--image-cache-max-bytes".to_string());
+        flags.push(max_bytes.to_string());
+    }
+    if let Some(bytes) = orch.min_disk {
+        flags.push("--min-disk".to_string());
+        flags.push(bytes.to_string());
+    }
+    for (key, value) in env {
+        flags.push("--env".to_string());
+        flags.push(quote(&format!("{}={}", key, value)));
+    }
+    for name in &orch.env_passthrough {
+        flags.push("--env-passthrough".to_string());
+        flags.push(quote(name));
+    }
+    flags
+}
+
+pub(crate) async fn cancel_workers_from_manifest(
+    repx_dir: &std::path::Path,
+    scheduler: SchedulerType,
+) {
     let manifest_path = repx_dir.join(manifests::WORKER_SLURM_IDS);
     if let Ok(content) = fs::read_to_string(&manifest_path) {
-        if let Ok(worker_ids) = serde_json::from_str::<Vec<u32>>(&content) {
+        if let Ok(worker_ids) = serde_json::from_str::<Vec<u64>>(&content) {
             if !worker_ids.is_empty() {
                 let id_strs: Vec<String> = worker_ids.iter().map(|id| id.to_string()).collect();
                 tracing::info!(
-                    "Cancelling {} worker SLURM jobs: {:?}",
+                    "Cancelling {} worker {} jobs: {:?}",
                     worker_ids.len(),
+                    scheduler,
                     &id_strs
                 );
-                let _ = TokioCommand::new("scancel").args(&id_strs).output().await;
+                let _ = TokioCommand::new(cancel_command(scheduler))
+                    .args(&id_strs)
+                    .output()
+                    .await;
             }
         }
     }
 }
 
-pub(crate) async fn submit_slurm_gather_job(
+pub(super) fn phase_command(
     orch: &ScatterGatherOrchestrator,
     args: &InternalScatterGatherArgs,
-    last_step_slurm_ids: &[String],
+    phase: &str,
     verbose: repx_core::logging::Verbosity,
-) -> Result<(), CliError> {
+) -> Result<Vec<String>, CliError> {
     let current_exe = std::env::current_exe()?;
     let current_exe_str = current_exe.to_string_lossy();
 
@@ -43,7 +101,7 @@ pub(crate) async fn submit_slurm_gather_job(
     gather_cmd_parts.extend_from_slice(&[
         "internal-scatter-gather".to_string(),
         "--phase".to_string(),
-        "gather".to_string(),
+        phase.to_string(),
         "--job-id".to_string(),
         args.job_id.clone(),
         "--runtime".to_string(),
@@ -53,7 +111,7 @@ pub(crate) async fn submit_slurm_gather_job(
         "--host-tools-dir".to_string(),
         args.host_tools_dir.clone(),
         "--scheduler".to_string(),
-        "slurm".to_string(),
+        args.scheduler.to_string(),
         "--step-sbatch-opts".to_string(),
         "''".to_string(),
         "--job-package-path".to_string(),
@@ -107,62 +165,113 @@ pub(crate) async fn submit_slurm_gather_job(
         gather_cmd_parts.push("--anchor-id".to_string());
         gather_cmd_parts.push(anchor.to_string());
     }
-
-    let gather_bootstrap = match (&orch.local_artifacts_path, &orch.lab_tar_path) {
-        (Some(local_artifacts), Some(tar_path)) => {
-            let local_base = local_artifacts.parent().unwrap_or(local_artifacts);
-            let content_hash = local_base
-                .file_name()
-                .unwrap_or(std::ffi::OsStr::new("unknown"))
-                .to_string_lossy();
-            format!(
-                "export LOCAL_BASE='{local_base}' && \
-                 export MARKER=\"$LOCAL_BASE/.extracted-{hash}\" && \
-                 export LAB_TAR='{tar}' && \
-                 mkdir -p \"$LOCAL_BASE\" && \
-                 flock -x \"$LOCAL_BASE/.lock\" sh -c \
-                   'if [ ! -f \"$MARKER\" ]; then tar xf \"$LAB_TAR\" -C \"$LOCAL_BASE/\" && touch \"$MARKER\"; fi' && ",
-                local_base = local_base.display(),
-                hash = content_hash,
-                tar = tar_path.display(),
-            )
+    for (flag, value) in [
+        ("--object-store", &args.object_store),
+        ("--object-store-endpoint", &args.object_store_endpoint),
+    ] {
+        if let Some(value) = value {
+            gather_cmd_parts.push(flag.to_string());
+            gather_cmd_parts.push(format!("'{}'", value.replace('\'', "'\\''")));
         }
-        _ => String::new(),
-    };
-    let cmd_str = format!("{}{}", gather_bootstrap, gather_cmd_parts.join(" "));
+    }
+    gather_cmd_parts.extend(sandbox_flags(orch));
+    Ok(gather_cmd_parts)
+}
+
+pub(crate) async fn submit_slurm_gather_job(
+    orch: &ScatterGatherOrchestrator,
+    args: &InternalScatterGatherArgs,
+    last_step_slurm_ids: &[String],
+    verbose: repx_core::logging::Verbosity,
+) -> Result<(), CliError> {
+    let gather_cmd_parts = phase_command(orch, args, "gather", verbose)?;
+    let body = format!("{}{}", lab_bootstrap(orch), gather_cmd_parts.join(" "));
 
     let gather_repx_dir = orch.job_root.join("gather").join(dirs::REPX);
-    fs::create_dir_all(&gather_repx_dir)?;
+    let directives = vec![
+        format!("--job-name={}-gather", orch.job_id.as_str()),
+        format!("--output={}/slurm-%j.out", gather_repx_dir.display()),
+    ];
+    let script = orch.batch_template.render(&directives, &body);
+    batch_script::write_versioned(&gather_repx_dir, &script)?;
+
+    let dependency = (!last_step_slurm_ids.is_empty())
+        .then(|| format!("--dependency=afterany:{}", last_step_slurm_ids.join(":")));
+    let gather_slurm_id = submit_script(&script, dependency, "Gather job").await?;
+    if let Ok(id) = gather_slurm_id.parse::<u64>() {
+        record_progress(&orch.repx_dir, |p| {
+            p.slurm_ids.insert("gather".to_string(), vec![id]);
+        });
+    }
+    Ok(())
+}
+
+pub(super) fn lab_bootstrap(orch: &ScatterGatherOrchestrator) -> String {
+    let (Some(local_artifacts), Some(tar_path)) = (&orch.local_artifacts_path, &orch.lab_tar_path)
+    else {
+        return String::new();
+    };
+    let local_base = local_artifacts.parent().unwrap_or(local_artifacts);
+    let content_hash = local_base
+        .file_name()
+        .unwrap_or(std::ffi::OsStr::new("unknown"))
+        .to_string_lossy();
+    format!(
+        r#"export LOCAL_BASE='{local_base}'
+export MARKER="$LOCAL_BASE/.extracted-{hash}"
+export LAB_TAR='{tar}'
+mkdir -p "$LOCAL_BASE"
+flock -x "$LOCAL_BASE/.lock" sh -c \
+  'if [ ! -f "$MARKER" ]; then tar xf "$LAB_TAR" -C "$LOCAL_BASE/" && touch "$MARKER"; fi'
+"#,
+        local_base = local_base.display(),
+        hash = content_hash,
+        tar = tar_path.display(),
+    )
+}
 
+async fn submit_script(
+    script: &str,
+    dependency: Option<String>,
+    what: &str,
+) -> Result<String, CliError> {
     let mut sbatch = TokioCommand::new("sbatch");
-    sbatch.arg("--parsable");
-    if !last_step_slurm_ids.is_empty() {
-        sbatch.arg(format!(
-            "--dependency=afterany:{}",
-            last_step_slurm_ids.join(":")
-        ));
-    }
     sbatch
-        .arg(format!("--job-name={}-gather", orch.job_id.as_str()))
-        .arg(format!(
-            "--output={}/gather/repx/slurm-%j.out",
-            orch.job_root.display()
-        ))
-        .arg("--wrap")
-        .arg(cmd_str);
-
-    let output = sbatch.output().await?;
+        .arg("--parsable")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    if let Some(dependency) = dependency {
+        sbatch.arg(dependency);
+    }
+
+    let mut child = sbatch.spawn().map_err(|e| {
+        CliError::Config(CoreError::CommandFailed(format!(
+            "Failed to spawn sbatch for {}: {}",
+            what, e
+        )))
+    })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(script.as_bytes()).await.map_err(|e| {
+            CliError::Config(CoreError::CommandFailed(format!(
+                "Failed to write script to sbatch stdin for {}: {}",
+                what, e
+            )))
+        })?;
+        drop(stdin);
+    }
+
+    let output = child.wait_with_output().await?;
     if !output.status.success() {
         return Err(CliError::execution_failed(
-            "Failed to submit Gather job",
+            format!("sbatch submission for {} failed", what),
             String::from_utf8_lossy(&output.stderr).to_string(),
         ));
     }
-
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
-#[allow(clippy::expect_used)]
+#[allow(clippy::expect_used, clippy::too_many_arguments)]
 pub(crate) async fn submit_slurm_branches(
     orch: &ScatterGatherOrchestrator,
     args: &InternalScatterGatherArgs,
@@ -170,23 +279,26 @@ pub(crate) async fn submit_slurm_branches(
     steps_meta: &StepsMetadata,
     topo_order: &[String],
     sbatch_opts: &str,
-) -> Result<(Vec<String>, Vec<u32>), CliError> {
+    retain_succeeded: bool,
+    first_branch: usize,
+) -> Result<(Vec<String>, Vec<u64>), CliError> {
     let mut last_step_slurm_ids = Vec::new();
-    let mut all_worker_slurm_ids: Vec<u32> = Vec::new();
+    let mut all_worker_slurm_ids: Vec<u64> = Vec::new();
+    let mut slurm_ids_by_step: HashMap<String, Vec<u64>> = HashMap::new();
+    let mut retained_by_branch: Vec<(usize, HashSet<String>)> = Vec::new();
 
     let repx_binary = std::env::current_exe()?;
     let repx_binary_str = repx_binary.to_string_lossy();
-    let runtime_str = args.runtime.to_string();
     let base_path_str = orch.base_path.to_string_lossy();
-    let image_tag_flag = args
-        .image_tag
-        .as_ref()
-        .map(|t| format!("--image-tag '{}'", t))
-        .unwrap_or_default();
     let node_local_flag = orch
         .node_local_path
         .as_ref()
-        .map(|p| format!("--node-local-path '{}'", p.to_string_lossy()))
+        .map(|p| {
+            format!(
+                "--node-local-path '{}' --node-local-scratch",
+                p.to_string_lossy()
+            )
+        })
         .unwrap_or_default();
     let local_artifacts_flag = orch
         .local_artifacts_path
@@ -194,28 +306,8 @@ pub(crate) async fn submit_slurm_branches(
         .map(|p| format!("--local-artifacts-path '{}'", p.to_string_lossy()))
         .unwrap_or_default();
 
-    let worker_bootstrap = match (&orch.local_artifacts_path, &orch.lab_tar_path) {
-        (Some(local_artifacts), Some(tar_path)) => {
-            let local_base = local_artifacts.parent().unwrap_or(local_artifacts);
-            let content_hash = local_base
-                .file_name()
-                .unwrap_or(std::ffi::OsStr::new("unknown"))
-                .to_string_lossy();
-            format!(
-                r#"export LOCAL_BASE='{local_base}'
-export MARKER="$LOCAL_BASE/.extracted-{hash}"
-export LAB_TAR='{tar}'
-mkdir -p "$LOCAL_BASE"
-flock -x "$LOCAL_BASE/.lock" sh -c \
-  'if [ ! -f "$MARKER" ]; then tar xf "$LAB_TAR" -C "$LOCAL_BASE/" && touch "$MARKER"; fi'
-"#,
-                local_base = local_base.display(),
-                hash = content_hash,
-                tar = tar_path.display(),
-            )
-        }
-        _ => String::new(),
-    };
+    let worker_bootstrap = lab_bootstrap(orch);
+    let step_opts = sbatch_opt_directives(sbatch_opts);
     let mount_flags = match &orch.mount_policy {
         repx_core::model::MountPolicy::AllHostPaths => "--mount-host-paths".to_string(),
         repx_core::model::MountPolicy::SpecificPaths(paths) => paths
@@ -226,14 +318,24 @@ flock -x "$LOCAL_BASE/.lock" sh -c \
         repx_core::model::MountPolicy::Isolated => String::new(),
     };
 
-    for (branch_idx, item) in work_items.iter().enumerate() {
+    let sandbox_args = sandbox_flags(orch).join(" ");
+
+    for (branch_idx, item) in work_items.iter().enumerate().skip(first_branch) {
         let branch_root = orch.job_root.join(format!("branch-{}", branch_idx));
 
         let work_item_json = serde_json::to_string(item)?;
 
         let mut step_slurm_ids: HashMap<String, String> = HashMap::new();
+        let mut retained = if retain_succeeded {
+            retained_steps(&branch_root, &steps_meta.steps, topo_order)
+        } else {
+            HashSet::new()
+        };
 
         for step_name in topo_order {
+            if retained.contains(step_name) {
+                continue;
+            }
             let step_meta = steps_meta
                 .steps
                 .get(step_name)
@@ -252,16 +354,34 @@ flock -x "$LOCAL_BASE/.lock" sh -c \
             )?;
             let inputs_json = serde_json::to_string_pretty(&inputs)?;
 
-            #[allow(clippy::format_in_format_args)]
-            let script = format!(
-                r#"#!/usr/bin/env bash
-#SBATCH --job-name={job_name}
-#SBATCH --output=/dev/null
-#SBATCH --error=/dev/null
-{sbatch_directives}
-set -e
+            let runtime_str = step_meta.runtime.unwrap_or(args.runtime).to_string();
+            let image_tag_flag = orch
+                .step_runtime(step_meta)?
+                .image_tag()
+                .map(|t| format!("--image-tag '{}'", t))
+                .unwrap_or_default();
+
+            let input_hash =
+                step_input_hash(step_meta, &inputs_json, &work_item_json, &branch_root);
+            let hash_path = step_repx.join(manifests::STEP_INPUT_HASH);
+            if step_repx.join(markers::SUCCESS).exists()
+                && fs::read_to_string(&hash_path).is_ok_and(|previous| previous == input_hash)
+            {
+                tracing::info!(
+                    "Branch #{} step '{}' inputs unchanged, not resubmitting it.",
+                    branch_idx,
+                    step_name
+                );
+                retained.insert(step_name.clone());
+                continue;
+            }
+            // Recorded up front: the step's SUCCESS marker only appears if
+            // the run submitted with these inputs succeeds.
+            fs::create_dir_all(&step_repx)?;
+            fs::write(&hash_path, &input_hash)?;
 
-# Inputs, parameters, and work_item are passed via file descriptors.
+            let body = format!(
+                r#"# Inputs, parameters, and work_item are passed via file descriptors.
 # Zero filesystem writes — data flows: heredoc -> kernel buffer -> fd -> child.
 exec 3<<'__REPX_INPUTS_EOF__'
 {inputs_json}
@@ -281,15 +401,16 @@ exec {repx} internal-execute \
   {node_local} \
   {local_artifacts} \
   {mount} \
+  {sandbox} \
   --executable-path '{exe_path}' \
   --user-out-dir '{user_out}' \
   --repx-out-dir '{repx_out}' \
   --inputs-json-path /dev/fd/3 \
   --parameters-json-path '{params_json}' \
-  --job-package-path '{job_pkg}'
+  --job-package-path '{job_pkg}' \
+  --branch-idx {branch_idx} \
+  --step-name '{step_name}'
 "#,
-                job_name = format!("{}-b{}-{}", orch.job_id.as_str(), branch_idx, step_name),
-                sbatch_directives = format_sbatch_opts(sbatch_opts),
                 work_item_json = work_item_json,
                 inputs_json = inputs_json,
                 worker_bootstrap = worker_bootstrap,
@@ -302,68 +423,60 @@ exec {repx} internal-execute \
                 node_local = node_local_flag,
                 local_artifacts = local_artifacts_flag,
                 mount = mount_flags,
+                sandbox = sandbox_args,
                 exe_path = step_meta.exe_path.display(),
                 user_out = step_out.display(),
                 repx_out = step_repx.display(),
                 params_json = orch.parameters_json_path.display(),
                 job_pkg = orch.job_package_path.display(),
+                branch_idx = branch_idx,
+                step_name = step_name,
             );
 
-            let mut sbatch = TokioCommand::new("sbatch");
-            sbatch
-                .arg("--parsable")
-                .stdin(std::process::Stdio::piped())
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::piped());
+            let mut directives = vec![
+                format!(
+                    "--job-name={}-b{}-{}",
+                    orch.job_id.as_str(),
+                    branch_idx,
+                    step_name
+                ),
+                "--output=/dev/null".to_string(),
+                "--error=/dev/null".to_string(),
+            ];
+            directives.extend(step_opts.iter().cloned());
 
-            let dep_slurm_ids: Vec<&String> = step_meta
+            let dep_slurm_ids: Vec<&str> = step_meta
                 .deps
                 .iter()
                 .filter_map(|dep| step_slurm_ids.get(dep))
+                .map(String::as_str)
                 .collect();
-
-            if !dep_slurm_ids.is_empty() {
-                let dep_ids_str: Vec<&str> = dep_slurm_ids.iter().map(|s| s.as_str()).collect();
-                sbatch.arg(format!("--dependency=afterok:{}", dep_ids_str.join(":")));
+            if args.min_branch_success.is_some() && !dep_slurm_ids.is_empty() {
+                // Steps behind a failed one are cancelled rather than left
+                // pending, so the gather's afterany dependency is released.
+                directives.push("--kill-on-invalid-dep=yes".to_string());
             }
+            let script = orch.batch_template.render(&directives, &body);
+            batch_script::write_versioned(&step_repx, &script)?;
 
-            let mut child = sbatch.spawn().map_err(|e| {
-                CliError::Config(CoreError::CommandFailed(format!(
-                    "Failed to spawn sbatch for branch #{} step '{}': {}",
-                    branch_idx, step_name, e
-                )))
-            })?;
-
-            if let Some(mut stdin) = child.stdin.take() {
-                stdin.write_all(script.as_bytes()).await.map_err(|e| {
-                    CliError::Config(CoreError::CommandFailed(format!(
-                        "Failed to write script to sbatch stdin for branch #{} step '{}': {}",
-                        branch_idx, step_name, e
-                    )))
-                })?;
-                drop(stdin);
-            }
+            let dependency = (!dep_slurm_ids.is_empty())
+                .then(|| format!("--dependency=afterok:{}", dep_slurm_ids.join(":")));
 
-            let output = child.wait_with_output().await?;
-            if !output.status.success() {
-                return Err(CliError::execution_failed(
-                    format!(
-                        "sbatch submission for branch #{} step '{}' failed",
-                        branch_idx, step_name
-                    ),
-                    String::from_utf8_lossy(&output.stderr).to_string(),
-                ));
-            }
-            let slurm_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if let Ok(id) = slurm_id.parse::<u32>() {
+            let what = format!("branch #{} step '{}'", branch_idx, step_name);
+            let slurm_id = submit_script(&script, dependency, &what).await?;
+            if let Ok(id) = slurm_id.parse::<u64>() {
                 all_worker_slurm_ids.push(id);
+                slurm_ids_by_step
+                    .entry(step_name.clone())
+                    .or_default()
+                    .push(id);
             }
             step_slurm_ids.insert(step_name.clone(), slurm_id);
         }
 
         if let Some(sink_slurm_id) = step_slurm_ids.get(&steps_meta.sink_step) {
             last_step_slurm_ids.push(sink_slurm_id.clone());
-        } else {
+        } else if !retained.contains(&steps_meta.sink_step) {
             return Err(CliError::Config(CoreError::InconsistentMetadata {
                 detail: format!(
                     "Sink step '{}' was not submitted for branch #{}",
@@ -371,26 +484,65 @@ exec {repx} internal-execute \
                 ),
             }));
         }
+        retained_by_branch.push((branch_idx, retained));
     }
 
+    record_progress(&orch.repx_dir, |p| {
+        p.branches_total = p.branches_total.max(work_items.len());
+        for (branch_idx, retained) in &retained_by_branch {
+            for step_name in retained {
+                p.set_step(*branch_idx, step_name, StepState::Succeeded);
+            }
+        }
+        for (step_name, ids) in slurm_ids_by_step {
+            p.slurm_ids.entry(step_name).or_default().extend(ids);
+        }
+    });
+
     tracing::info!(
-        "Submitted {} branches ({} steps each, {} total worker jobs) to Slurm.",
-        work_items.len(),
-        topo_order.len(),
-        all_worker_slurm_ids.len()
+        "Submitted {} worker jobs for {} branches ({} steps each) to Slurm.",
+        all_worker_slurm_ids.len(),
+        work_items.len().saturating_sub(first_branch),
+        topo_order.len()
     );
     Ok((last_step_slurm_ids, all_worker_slurm_ids))
 }
 
-fn format_sbatch_opts(opts: &str) -> String {
+fn cancel_command(scheduler: SchedulerType) -> &'static str {
+    match scheduler {
+        SchedulerType::Sge => "qdel",
+        _ => "scancel",
+    }
+}
+
+pub(crate) async fn cancel_anchor(args: &InternalScatterGatherArgs) {
+    if let Some(anchor) = args.anchor_id {
+        let _ = TokioCommand::new(cancel_command(args.scheduler))
+            .arg(anchor.to_string())
+            .output()
+            .await;
+    }
+}
+
+pub(crate) async fn release_anchor(args: &InternalScatterGatherArgs) {
+    if let Some(anchor) = args.anchor_id {
+        tracing::info!("Releasing anchor job {}", anchor);
+        let mut release = match args.scheduler {
+            SchedulerType::Sge => TokioCommand::new("qrls"),
+            _ => {
+                let mut scontrol = TokioCommand::new("scontrol");
+                scontrol.arg("release");
+                scontrol
+            }
+        };
+        let _ = release.arg(anchor.to_string()).output().await;
+    }
+}
+
+pub(super) fn sbatch_opt_directives(opts: &str) -> Vec<String> {
     let trimmed = opts.trim();
-    if trimmed.is_empty() || trimmed == "''" {
-        return String::new();
+    if trimmed == "''" {
+        return Vec::new();
     }
-    trimmed
-        .split_whitespace()
-        .filter(|s| !s.is_empty())
-        .map(|opt| format!("#SBATCH {}", opt))
-        .collect::<Vec<_>>()
-        .join("\n")
+    trimmed.split_whitespace().map(str::to_string).collect()
 }
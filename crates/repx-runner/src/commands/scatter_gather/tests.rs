@@ -2,6 +2,7 @@ use super::inputs::resolve_step_inputs;
 use super::slurm::cancel_workers_from_manifest;
 use super::toposort::toposort_steps;
 use super::*;
+use repx_core::model::{BranchQuota, SchedulerType};
 use std::collections::HashSet;
 
 #[test]
@@ -15,6 +16,8 @@ fn test_toposort_single_step() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     let order = toposort_steps(&steps).expect("toposort must succeed");
@@ -32,6 +35,8 @@ fn test_toposort_linear_chain() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     steps.insert(
@@ -42,6 +47,8 @@ fn test_toposort_linear_chain() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     steps.insert(
@@ -52,6 +59,8 @@ fn test_toposort_linear_chain() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     let order = toposort_steps(&steps).expect("toposort must succeed");
@@ -69,6 +78,8 @@ fn test_toposort_diamond() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     steps.insert(
@@ -79,6 +90,8 @@ fn test_toposort_diamond() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     steps.insert(
@@ -89,6 +102,8 @@ fn test_toposort_diamond() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     steps.insert(
@@ -99,6 +114,8 @@ fn test_toposort_diamond() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     let order = toposort_steps(&steps).expect("toposort must succeed");
@@ -120,6 +137,8 @@ fn test_toposort_cycle_detection() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     steps.insert(
@@ -130,6 +149,8 @@ fn test_toposort_cycle_detection() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     let result = toposort_steps(&steps);
@@ -149,6 +170,8 @@ fn test_toposort_unknown_dep() {
             outputs: HashMap::new(),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
     let result = toposort_steps(&steps);
@@ -246,6 +269,8 @@ fn test_resolve_step_inputs_scatter_source() {
             mapping_type: None,
         }],
         resource_hints: None,
+        image_tag: None,
+        runtime: None,
     };
     steps.insert("compute".to_string(), step.clone());
 
@@ -272,6 +297,8 @@ fn test_resolve_step_inputs_step_dep() {
             outputs: HashMap::from([("trace".to_string(), "$out/trace.bin".to_string())]),
             inputs: vec![],
             resource_hints: None,
+            image_tag: None,
+            runtime: None,
         },
     );
 
@@ -287,6 +314,8 @@ fn test_resolve_step_inputs_step_dep() {
             mapping_type: None,
         }],
         resource_hints: None,
+        image_tag: None,
+        runtime: None,
     };
     steps.insert("analyze".to_string(), consumer.clone());
 
@@ -323,6 +352,8 @@ fn test_resolve_step_inputs_external() {
             mapping_type: Some("intra-pipeline".to_string()),
         }],
         resource_hints: None,
+        image_tag: None,
+        runtime: None,
     };
 
     let branch_root = PathBuf::from("/tmp/job/branch-0");
@@ -341,9 +372,9 @@ fn test_resolve_step_inputs_external() {
 
 #[test]
 fn test_worker_manifest_serialization() {
-    let worker_ids: Vec<u32> = vec![100, 101, 102, 103, 200, 201];
+    let worker_ids: Vec<u64> = vec![100, 101, 102, 103, 200, 201];
     let json = serde_json::to_string(&worker_ids).expect("JSON serialization must succeed");
-    let deserialized: Vec<u32> = serde_json::from_str(&json).expect("JSON must deserialize");
+    let deserialized: Vec<u64> = serde_json::from_str(&json).expect("JSON must deserialize");
     assert_eq!(deserialized, worker_ids);
 }
 
@@ -353,21 +384,21 @@ fn test_worker_manifest_written_to_correct_path() {
     let repx_dir = tmp.path().join("repx");
     fs::create_dir_all(&repx_dir).expect("dir creation must succeed");
 
-    let worker_ids: Vec<u32> = vec![42, 43, 44];
+    let worker_ids: Vec<u64> = vec![42, 43, 44];
     let manifest_path = repx_dir.join(manifests::WORKER_SLURM_IDS);
     let json = serde_json::to_string(&worker_ids).expect("JSON serialization must succeed");
     fs::write(&manifest_path, &json).expect("file write must succeed");
 
     let content = fs::read_to_string(&manifest_path).expect("file read must succeed");
-    let read_ids: Vec<u32> = serde_json::from_str(&content).expect("JSON must deserialize");
+    let read_ids: Vec<u64> = serde_json::from_str(&content).expect("JSON must deserialize");
     assert_eq!(read_ids, vec![42, 43, 44]);
 }
 
 #[test]
 fn test_worker_manifest_empty_is_valid() {
-    let worker_ids: Vec<u32> = vec![];
+    let worker_ids: Vec<u64> = vec![];
     let json = serde_json::to_string(&worker_ids).expect("JSON serialization must succeed");
-    let deserialized: Vec<u32> = serde_json::from_str(&json).expect("JSON must deserialize");
+    let deserialized: Vec<u64> = serde_json::from_str(&json).expect("JSON must deserialize");
     assert!(deserialized.is_empty());
 }
 
@@ -376,7 +407,7 @@ async fn test_cancel_workers_from_manifest_with_valid_file() {
     let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
     let repx_dir = tmp.path();
 
-    let worker_ids: Vec<u32> = vec![999, 998, 997];
+    let worker_ids: Vec<u64> = vec![999, 998, 997];
     let manifest_path = repx_dir.join(manifests::WORKER_SLURM_IDS);
     fs::write(
         &manifest_path,
@@ -384,7 +415,7 @@ async fn test_cancel_workers_from_manifest_with_valid_file() {
     )
     .expect("file write must succeed");
 
-    cancel_workers_from_manifest(repx_dir).await;
+    cancel_workers_from_manifest(repx_dir, SchedulerType::Slurm).await;
 
     assert!(manifest_path.exists());
 }
@@ -392,7 +423,7 @@ async fn test_cancel_workers_from_manifest_with_valid_file() {
 #[tokio::test]
 async fn test_cancel_workers_from_manifest_no_file() {
     let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
-    cancel_workers_from_manifest(tmp.path()).await;
+    cancel_workers_from_manifest(tmp.path(), SchedulerType::Slurm).await;
 }
 
 fn make_script(path: &Path, body: &str) {
@@ -430,6 +461,8 @@ fn step(exe: PathBuf, deps: &[&str], out_name: &str, input_src: &str) -> StepMet
         outputs: HashMap::from([(out_name.to_string(), format!("$out/{out_name}.txt"))]),
         inputs,
         resource_hints: None,
+        image_tag: None,
+        runtime: None,
     }
 }
 
@@ -474,6 +507,53 @@ fn diamond_step_metadata(exe: PathBuf) -> StepsMetadata {
     }
 }
 
+fn sg_args(
+    tmp: &Path,
+    scripts: &Path,
+    steps_json: &str,
+    phase: crate::cli::ScatterGatherPhase,
+) -> InternalScatterGatherArgs {
+    InternalScatterGatherArgs {
+        job_id: "test-job".into(),
+        runtime: repx_core::model::ExecutionType::Native,
+        image_tag: None,
+        base_path: tmp.to_path_buf(),
+        node_local_path: None,
+        local_artifacts_path: None,
+        lab_tar_path: None,
+        host_tools_dir: String::new(),
+        scheduler: repx_core::model::SchedulerType::Local,
+        step_sbatch_opts: String::new(),
+        sbatch_template: None,
+        sbatch_prologue: vec![],
+        job_package_path: scripts.to_path_buf(),
+        scatter_exe_path: scripts.join("scatter.sh"),
+        gather_exe_path: scripts.join("gather.sh"),
+        steps_json: steps_json.to_string(),
+        last_step_outputs_json: "{}".into(),
+        anchor_id: None,
+        phase,
+        branch_idx: None,
+        step_name: None,
+        mount_host_paths: false,
+        mount_paths: vec![],
+        data_mounts: vec![],
+        network: None,
+        seccomp: None,
+        pre_run: None,
+        post_run: None,
+        image_cache_max_bytes: None,
+        min_disk: None,
+        env: vec![],
+        env_passthrough: vec![],
+        retry_failed: false,
+        auto_prune_steps: false,
+        min_branch_success: None,
+        object_store: None,
+        object_store_endpoint: None,
+    }
+}
+
 async fn run_branch(
     tmp: &Path,
     job_root: &Path,
@@ -502,27 +582,14 @@ async fn run_branch(
 
     for step_name in topo_order {
         let args = InternalScatterGatherArgs {
-            job_id: "test-job".into(),
-            runtime: repx_core::model::ExecutionType::Native,
-            image_tag: None,
-            base_path: tmp.to_path_buf(),
-            node_local_path: None,
-            local_artifacts_path: None,
-            lab_tar_path: None,
-            host_tools_dir: String::new(),
-            scheduler: repx_core::model::SchedulerType::Local,
-            step_sbatch_opts: String::new(),
-            job_package_path: scripts.clone(),
-            scatter_exe_path: scripts.join("scatter.sh"),
-            gather_exe_path: scripts.join("gather.sh"),
-            steps_json: steps_json.clone(),
-            last_step_outputs_json: "{}".into(),
-            anchor_id: None,
-            phase: crate::cli::ScatterGatherPhase::Step,
             branch_idx: Some(branch_idx),
             step_name: Some(step_name.clone()),
-            mount_host_paths: false,
-            mount_paths: vec![],
+            ..sg_args(
+                tmp,
+                &scripts,
+                &steps_json,
+                crate::cli::ScatterGatherPhase::Step,
+            )
         };
         let mut orch = ScatterGatherOrchestrator::new(&args)?;
         orch.load_static_inputs()?;
@@ -621,6 +688,7 @@ async fn test_scatter_skipped_on_rerun_if_already_succeeded() {
         inputs_json_path: job_root.join(dirs::REPX).join("inputs.json"),
         parameters_json_path: job_root.join(dirs::REPX).join("parameters.json"),
         runtime: Runtime::Native,
+        execution_type: repx_core::model::ExecutionType::Native,
         job_package_path: scripts.clone(),
         static_inputs: Value::Object(Default::default()),
         host_tools_bin_dir: None,
@@ -628,6 +696,17 @@ async fn test_scatter_skipped_on_rerun_if_already_succeeded() {
         local_artifacts_path: None,
         lab_tar_path: None,
         mount_policy: repx_core::model::MountPolicy::Isolated,
+        data_mounts: vec![],
+        network: None,
+        seccomp: None,
+        pre_run: None,
+        post_run: None,
+        image_cache_limit: None,
+        min_disk: None,
+        env: HashMap::new(),
+        env_passthrough: vec![],
+        secrets: vec![],
+        batch_template: Default::default(),
     };
 
     orch.init_dirs().expect("init_dirs must succeed");
@@ -787,3 +866,462 @@ async fn test_rerun_preserves_scatter_output_and_skips_succeeded_steps() {
     assert!(s1_repx.join(markers::SUCCESS).exists());
     assert!(!s1_repx.join(markers::FAIL).exists());
 }
+
+#[test]
+fn test_retained_steps_rerun_failed_step_and_its_dependents() {
+    let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
+    let branch_root = tmp.path().join("branch-0");
+    let meta = diamond_step_metadata(PathBuf::from("/bin/step"));
+    let order = toposort_steps(&meta.steps).expect("toposort must succeed");
+
+    for succeeded in ["root", "right", "sink"] {
+        let step_repx = branch_root
+            .join(format!("step-{}", succeeded))
+            .join(dirs::REPX);
+        fs::create_dir_all(&step_repx).expect("dir creation must succeed");
+        fs::File::create(step_repx.join(markers::SUCCESS)).expect("file creation must succeed");
+    }
+
+    let retained = retained_steps(&branch_root, &meta.steps, &order);
+    let expected: HashSet<String> = ["root", "right"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(retained, expected);
+}
+
+#[test]
+fn test_batch_template_renders_directives_prologue_and_body() {
+    let template = batch_script::BatchScriptTemplate::new(
+        "#!/bin/bash\n{{directives}}\n#SBATCH --account=proj42\n{{prologue}}\n{{body}}".to_string(),
+        vec![
+            "module load apptainer".to_string(),
+            "export TMPDIR=/scratch/$USER".to_string(),
+        ],
+    )
+    .expect("template with body placeholder");
+    let script = template.render(
+        &["--job-name=j-b0-train".to_string(), "--mem=4G".to_string()],
+        "exec repx internal-execute",
+    );
+    assert_eq!(
+        script,
+        "#!/bin/bash\n#SBATCH --job-name=j-b0-train\n#SBATCH --mem=4G\n#SBATCH --account=proj42\n\
+         module load apptainer\nexport TMPDIR=/scratch/$USER\nexec repx internal-execute\n"
+    );
+
+    let default = batch_script::BatchScriptTemplate::default().render(&[], "true");
+    assert!(default.starts_with("#!/usr/bin/env bash\n"));
+    assert!(default.ends_with("set -e\n\ntrue\n"));
+
+    assert!(batch_script::BatchScriptTemplate::new("#!/bin/bash\n".to_string(), vec![]).is_err());
+}
+
+#[tokio::test]
+async fn test_gather_skips_failed_branches_within_quota() {
+    let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
+    let job_root = tmp.path().join("outputs/test-job");
+    let scripts = tmp.path().join("scripts");
+    fs::create_dir_all(&scripts).expect("dir creation must succeed");
+    make_script(
+        &scripts.join("gather.sh"),
+        "mkdir -p \"$1\"\necho done > \"$1/summary.txt\"",
+    );
+
+    let scatter_out = job_root.join("scatter").join(dirs::OUT);
+    fs::create_dir_all(&scatter_out).expect("dir creation must succeed");
+    fs::write(
+        scatter_out.join("work_items.json"),
+        r#"[{"id": 0}, {"id": 1}, {"id": 2}]"#,
+    )
+    .expect("file write must succeed");
+    for (branch, marker) in [
+        (0, markers::SUCCESS),
+        (1, markers::FAIL),
+        (2, markers::SUCCESS),
+    ] {
+        let step_repx = job_root
+            .join(format!("branch-{}/step-only", branch))
+            .join(dirs::REPX);
+        fs::create_dir_all(&step_repx).expect("dir creation must succeed");
+        write_marker(&step_repx.join(marker)).expect("marker write must succeed");
+    }
+
+    let meta = single_step_metadata(scripts.join("gather.sh"));
+    let steps_json = serde_json::to_string(&meta).expect("JSON serialization must succeed");
+    let gather_args = |quota| InternalScatterGatherArgs {
+        last_step_outputs_json: r#"{"result": "$out/result.txt"}"#.into(),
+        min_branch_success: Some(quota),
+        ..sg_args(
+            tmp.path(),
+            &scripts,
+            &steps_json,
+            crate::cli::ScatterGatherPhase::Gather,
+        )
+    };
+
+    let strict = gather_args(BranchQuota::Count(3));
+    let mut orch = ScatterGatherOrchestrator::new(&strict).expect("orchestrator must build");
+    let err = handle_phase_gather(&mut orch, &strict, "only").await;
+    assert!(err.is_err(), "gather must fail below the branch quota");
+    assert!(job_root.join(dirs::REPX).join(markers::FAIL).exists());
+
+    let tolerant = gather_args(BranchQuota::Fraction(0.6));
+    let mut orch = ScatterGatherOrchestrator::new(&tolerant).expect("orchestrator must build");
+    let res = handle_phase_gather(&mut orch, &tolerant, "only").await;
+    assert!(
+        res.is_ok(),
+        "gather should tolerate one failed branch: {:?}",
+        res.err()
+    );
+
+    let repx_dir = job_root.join(dirs::REPX);
+    let manifest: Vec<HashMap<String, String>> = serde_json::from_str(
+        &fs::read_to_string(repx_dir.join("worker_outs_manifest.json"))
+            .expect("manifest must exist"),
+    )
+    .expect("manifest must parse");
+    assert_eq!(manifest.len(), 2);
+    assert!(manifest[0]["result"].contains("branch-0"));
+    assert!(manifest[1]["result"].contains("branch-2"));
+
+    let skipped: Vec<SkippedBranch> = serde_json::from_str(
+        &fs::read_to_string(repx_dir.join(manifests::SKIPPED_BRANCHES))
+            .expect("skipped branches must be recorded"),
+    )
+    .expect("skipped branches must parse");
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].branch, 1);
+    assert_eq!(skipped[0].work_item, serde_json::json!({"id": 1}));
+}
+
+#[tokio::test]
+async fn test_step_not_run_behind_failed_dependency() {
+    let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
+    let job_root = tmp.path().join("outputs/test-job");
+    let scripts = tmp.path().join("scripts");
+    fs::create_dir_all(&scripts).expect("dir creation must succeed");
+    make_script(
+        &scripts.join("step.sh"),
+        "mkdir -p \"$1\"\necho done > \"$1/left.txt\"",
+    );
+
+    let scatter_out = job_root.join("scatter").join(dirs::OUT);
+    fs::create_dir_all(&scatter_out).expect("dir creation must succeed");
+    fs::write(scatter_out.join("work_items.json"), r#"[{"id": 0}]"#)
+        .expect("file write must succeed");
+    let root_repx = job_root.join("branch-0/step-root").join(dirs::REPX);
+    fs::create_dir_all(&root_repx).expect("dir creation must succeed");
+    write_marker(&root_repx.join(markers::FAIL)).expect("marker write must succeed");
+
+    let meta = diamond_step_metadata(scripts.join("step.sh"));
+    let steps_json = serde_json::to_string(&meta).expect("JSON serialization must succeed");
+    let args = InternalScatterGatherArgs {
+        branch_idx: Some(0),
+        step_name: Some("left".into()),
+        ..sg_args(
+            tmp.path(),
+            &scripts,
+            &steps_json,
+            crate::cli::ScatterGatherPhase::Step,
+        )
+    };
+    let mut orch = ScatterGatherOrchestrator::new(&args).expect("orchestrator must build");
+    orch.load_static_inputs().expect("static inputs must load");
+    let res = handle_phase_step(&mut orch, &args, &meta).await;
+    assert!(res.is_err(), "step behind a failed step must fail");
+
+    let left = job_root.join("branch-0/step-left");
+    assert!(left.join(dirs::REPX).join(markers::FAIL).exists());
+    assert!(!left.join(dirs::OUT).join("left.txt").exists());
+
+    let tolerant = InternalScatterGatherArgs {
+        min_branch_success: Some(BranchQuota::Count(0)),
+        ..args
+    };
+    let res = handle_phase_step(&mut orch, &tolerant, &meta).await;
+    assert!(
+        res.is_ok(),
+        "a branch quota keeps the failure to its branch"
+    );
+}
+
+#[tokio::test]
+async fn test_step_runs_from_streamed_work_items() {
+    let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
+    let job_root = tmp.path().join("outputs/test-job");
+    let scripts = tmp.path().join("scripts");
+    fs::create_dir_all(&scripts).expect("dir creation must succeed");
+    make_script(
+        &scripts.join("step.sh"),
+        "mkdir -p \"$1\"\necho done > \"$1/result.txt\"",
+    );
+
+    let scatter_out = job_root.join("scatter").join(dirs::OUT);
+    fs::create_dir_all(&scatter_out).expect("dir creation must succeed");
+    fs::write(
+        scatter_out.join(work_items::WORK_ITEMS_STREAM_FILE),
+        "{\"id\": 0}\n{\"id\": 1}\n{\"id\"",
+    )
+    .expect("file write must succeed");
+
+    let meta = single_step_metadata(scripts.join("step.sh"));
+    let steps_json = serde_json::to_string(&meta).expect("JSON serialization must succeed");
+    let args = InternalScatterGatherArgs {
+        branch_idx: Some(1),
+        step_name: Some("only".into()),
+        ..sg_args(
+            tmp.path(),
+            &scripts,
+            &steps_json,
+            crate::cli::ScatterGatherPhase::Step,
+        )
+    };
+    let mut orch = ScatterGatherOrchestrator::new(&args).expect("orchestrator must build");
+    orch.load_static_inputs().expect("static inputs must load");
+    let res = handle_phase_step(&mut orch, &args, &meta).await;
+    assert!(res.is_ok(), "streamed branch should run: {:?}", res.err());
+
+    let work_item = fs::read_to_string(job_root.join("branch-1/repx/work_item.json"))
+        .expect("work item must be written");
+    assert_eq!(work_item, r#"{"id":1}"#);
+
+    let partial = InternalScatterGatherArgs {
+        branch_idx: Some(2),
+        ..args
+    };
+    assert!(handle_phase_step(&mut orch, &partial, &meta).await.is_err());
+}
+
+#[test]
+fn test_step_runtime_overrides_stage_image() {
+    let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
+    let scripts = tmp.path().join("scripts");
+    let meta = single_step_metadata(scripts.join("step.sh"));
+    let steps_json = serde_json::to_string(&meta).expect("JSON serialization must succeed");
+    let args = InternalScatterGatherArgs {
+        runtime: repx_core::model::ExecutionType::Podman,
+        image_tag: Some("stage-image".into()),
+        ..sg_args(
+            tmp.path(),
+            &scripts,
+            &steps_json,
+            crate::cli::ScatterGatherPhase::Step,
+        )
+    };
+    let orch = ScatterGatherOrchestrator::new(&args).expect("orchestrator must build");
+    let tag_of = |step: &StepMeta| {
+        orch.step_runtime(step)
+            .expect("step runtime must resolve")
+            .image_tag()
+            .map(|tag| tag.to_string())
+    };
+
+    let mut step = meta.steps["only"].clone();
+    assert_eq!(tag_of(&step).as_deref(), Some("stage-image"));
+
+    step.image_tag = Some("cuda-image".into());
+    assert_eq!(tag_of(&step).as_deref(), Some("cuda-image"));
+
+    step.runtime = Some(repx_core::model::ExecutionType::Native);
+    assert_eq!(tag_of(&step), None);
+
+    let native = InternalScatterGatherArgs {
+        runtime: repx_core::model::ExecutionType::Native,
+        image_tag: None,
+        ..args
+    };
+    let orch = ScatterGatherOrchestrator::new(&native).expect("orchestrator must build");
+    step.runtime = Some(repx_core::model::ExecutionType::Bwrap);
+    step.image_tag = None;
+    assert!(
+        orch.step_runtime(&step).is_err(),
+        "a container runtime without any image must be rejected"
+    );
+}
+
+#[tokio::test]
+async fn test_step_skipped_when_input_hash_unchanged() {
+    let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
+    let job_root = tmp.path().join("outputs/test-job");
+    let scripts = tmp.path().join("scripts");
+    fs::create_dir_all(&scripts).expect("dir creation must succeed");
+    let runs_log = tmp.path().join("runs.log");
+    let body = format!(
+        "mkdir -p \"$1\"\nfor f in data left right final; do echo done > \"$1/$f.txt\"; done\necho \"$1\" >> '{}'",
+        runs_log.display()
+    );
+    make_script(&scripts.join("step.sh"), &body);
+    make_script(&scripts.join("step-v2.sh"), &body);
+    let runs = || {
+        fs::read_to_string(&runs_log)
+            .unwrap_or_default()
+            .lines()
+            .count()
+    };
+
+    let meta = diamond_step_metadata(scripts.join("step.sh"));
+    let order = toposort_steps(&meta.steps).expect("toposort must succeed");
+    let item = serde_json::json!({"id": 0});
+
+    let r = run_branch(tmp.path(), &job_root, 0, &item, &meta, &order).await;
+    assert!(r.is_ok(), "first run should succeed: {:?}", r.err());
+    assert_eq!(runs(), 4);
+
+    let r = run_branch(tmp.path(), &job_root, 0, &item, &meta, &order).await;
+    assert!(r.is_ok(), "cached run should succeed: {:?}", r.err());
+    assert_eq!(runs(), 4, "unchanged steps must not execute again");
+
+    let mut changed = meta.clone();
+    changed
+        .steps
+        .get_mut("root")
+        .expect("root step must exist")
+        .exe_path = scripts.join("step-v2.sh");
+    let r = run_branch(tmp.path(), &job_root, 0, &item, &changed, &order).await;
+    assert!(r.is_ok(), "changed run should succeed: {:?}", r.err());
+    assert_eq!(
+        runs(),
+        8,
+        "a changed root step must rerun every step downstream of it"
+    );
+}
+
+#[tokio::test]
+async fn test_step_runs_in_node_local_scratch() {
+    let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
+    let job_root = tmp.path().join("outputs/test-job");
+    let scripts = tmp.path().join("scripts");
+    let node_local = tmp.path().join("node");
+    fs::create_dir_all(&scripts).expect("dir creation must succeed");
+    let scatter_out = job_root.join("scatter").join(dirs::OUT);
+    fs::create_dir_all(&scatter_out).expect("dir creation must succeed");
+    fs::write(scatter_out.join("work_items.json"), r#"[{"id": 0}]"#)
+        .expect("file write must succeed");
+    make_script(
+        &scripts.join("step.sh"),
+        &format!(
+            "case \"$1\" in '{node}'/*) ;; *) exit 3 ;; esac\n\
+             grep -q '{node}' \"$2\" || exit 4\n\
+             echo done > \"$1/result.txt\"",
+            node = node_local.display()
+        ),
+    );
+    make_script(
+        &scripts.join("fail.sh"),
+        "echo partial > \"$1/result.txt\"\nexit 1",
+    );
+
+    let run = |exe: &str| {
+        let meta = single_step_metadata(scripts.join(exe));
+        let steps_json = serde_json::to_string(&meta).expect("JSON serialization must succeed");
+        let args = InternalScatterGatherArgs {
+            branch_idx: Some(0),
+            step_name: Some("only".into()),
+            node_local_path: Some(node_local.clone()),
+            ..sg_args(
+                tmp.path(),
+                &scripts,
+                &steps_json,
+                crate::cli::ScatterGatherPhase::Step,
+            )
+        };
+        async move {
+            let mut orch = ScatterGatherOrchestrator::new(&args)?;
+            handle_phase_step(&mut orch, &args, &meta).await
+        }
+    };
+    let step_out = job_root.join("branch-0/step-only").join(dirs::OUT);
+    let scratch_root = node_local.join("repx/scratch");
+
+    let res = run("step.sh").await;
+    assert!(res.is_ok(), "step should run in scratch: {:?}", res.err());
+    assert_eq!(
+        fs::read_to_string(step_out.join("result.txt")).expect("result must be copied back"),
+        "done\n"
+    );
+    assert!(
+        !scratch_root.join("test-job").exists(),
+        "scratch must be removed after success"
+    );
+
+    let res = run("fail.sh").await;
+    assert!(res.is_err());
+    assert!(
+        !step_out.join("result.txt").exists(),
+        "failed outputs must not reach the shared directory"
+    );
+    assert!(
+        !scratch_root.join("test-job").exists(),
+        "scratch must be removed after failure"
+    );
+}
+
+#[tokio::test]
+async fn test_progress_counts_steps_and_keeps_retained_branches() {
+    let tmp = tempfile::tempdir().expect("tempdir creation must succeed");
+    let job_root = tmp.path().join("outputs/test-job");
+    let scripts = tmp.path().join("scripts");
+    fs::create_dir_all(&scripts).expect("dir creation must succeed");
+    make_script(
+        &scripts.join("step.sh"),
+        "mkdir -p \"$1\"\nfor f in data left right final; do echo done > \"$1/$f.txt\"; done",
+    );
+    let scatter_out = job_root.join("scatter").join(dirs::OUT);
+    let scatter_repx = job_root.join("scatter").join(dirs::REPX);
+    fs::create_dir_all(&scatter_out).expect("dir creation must succeed");
+    fs::create_dir_all(&scatter_repx).expect("dir creation must succeed");
+    write_marker(&scatter_repx.join(markers::SUCCESS)).expect("marker write must succeed");
+    fs::write(
+        scatter_out.join("work_items.json"),
+        r#"[{"id": 0}, {"id": 0}]"#,
+    )
+    .expect("file write must succeed");
+
+    let meta = diamond_step_metadata(scripts.join("step.sh"));
+    let order = toposort_steps(&meta.steps).expect("toposort must succeed");
+    let steps_json = serde_json::to_string(&meta).expect("JSON serialization must succeed");
+    let scatter_args = sg_args(
+        tmp.path(),
+        &scripts,
+        &steps_json,
+        crate::cli::ScatterGatherPhase::ScatterOnly,
+    );
+    let scatter_only = || async {
+        let mut orch =
+            ScatterGatherOrchestrator::new(&scatter_args).expect("orchestrator must build");
+        handle_phase_scatter_only(&mut orch, &scatter_args, &meta, &order)
+            .await
+            .expect("scatter phase must succeed");
+        progress::read(&job_root.join(dirs::REPX))
+            .expect("progress must read")
+            .expect("progress must exist")
+    };
+
+    let progress = scatter_only().await;
+    assert_eq!(progress.branches_total, 2);
+    assert_eq!(progress.summary(), "0/2 branches done");
+
+    let r = run_branch(
+        tmp.path(),
+        &job_root,
+        1,
+        &serde_json::json!({"id": 0}),
+        &meta,
+        &order,
+    )
+    .await;
+    assert!(r.is_ok(), "branch should succeed: {:?}", r.err());
+    let progress = progress::read(&job_root.join(dirs::REPX))
+        .expect("progress must read")
+        .expect("progress must exist");
+    assert_eq!(progress.branches_succeeded, 1);
+    assert!(progress
+        .steps
+        .iter()
+        .all(|s| s.succeeded == 1 && s.pending == 1 && s.running == 0));
+
+    let progress = scatter_only().await;
+    assert_eq!(
+        progress.summary(),
+        "1/2 branches done",
+        "steps kept from the previous run must still count as done"
+    );
+}
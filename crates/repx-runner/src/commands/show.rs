@@ -1,17 +1,28 @@
-use crate::cli::{ShowArgs, ShowEntity, ShowJobArgs, ShowOutputArgs};
+use crate::cli::{OutputFormat, ShowArgs, ShowEntity, ShowJobArgs, ShowOutputArgs};
+use crate::commands::records::{self, JobRecord};
+use crate::commands::trace::compute_all_effective_params;
 use crate::error::CliError;
+use repx_client::{client::resolve_execution_type, resources};
 use repx_core::{
     config::{self, Config},
     constants::{dirs, logs},
     errors::CoreError,
+    fs_utils::path_to_string,
     lab::LabSource,
-    model::{JobId, Lab, RunId},
+    model::{ExecutionType, JobId, Lab, RunId, SchedulerType},
     resolver,
-    store::outcomes::{get_job_outcomes, JobOutcome},
+    store::{
+        outcomes::{get_job_outcomes, get_job_timings, JobOutcome},
+        progress::{self, ScatterProgress},
+        slurm_failure::SlurmFailure,
+    },
 };
+use repx_executor::{ExecutionRequest, Executor, ResourceLimits};
+use serde::Serialize;
 use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[allow(clippy::expect_used)]
 pub fn handle_show(
@@ -19,13 +30,102 @@ pub fn handle_show(
     lab: &Lab,
     _source: &LabSource,
     target: Option<&str>,
+    resources: Option<&Path>,
 ) -> Result<(), CliError> {
     match args.entity {
+        ShowEntity::Job(job_args) if job_args.command => {
+            handle_show_command(job_args, lab, target, resources)
+        }
         ShowEntity::Job(job_args) => handle_show_job(job_args, lab, target),
         ShowEntity::Output(output_args) => handle_show_output(output_args, lab, target),
     }
 }
 
+#[derive(Serialize)]
+struct JobDetail<'a> {
+    #[serde(flatten)]
+    record: JobRecord,
+    name: Option<&'a str>,
+    stage_type: String,
+    inputs: Vec<&'a JobId>,
+    logs_path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slurm_failure: Option<SlurmFailure>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    progress: Option<ScatterProgress>,
+}
+
+fn read_slurm_failure(store_path: &Path, job_id: &JobId, succeeded: bool) -> Option<SlurmFailure> {
+    if succeeded {
+        return None;
+    }
+    let repx_dir = store_path
+        .join(dirs::OUTPUTS)
+        .join(job_id.as_str())
+        .join(dirs::REPX);
+    SlurmFailure::read(&repx_dir).unwrap_or_else(|e| {
+        tracing::warn!("Failed to read SLURM failure record of '{}': {}", job_id, e);
+        None
+    })
+}
+
+fn read_progress(store_path: &Path, job_id: &JobId) -> Option<ScatterProgress> {
+    let repx_dir = store_path
+        .join(dirs::OUTPUTS)
+        .join(job_id.as_str())
+        .join(dirs::REPX);
+    progress::read(&repx_dir).unwrap_or_else(|e| {
+        tracing::warn!("Failed to read progress of '{}': {}", job_id, e);
+        None
+    })
+}
+
+fn write_job_detail(
+    lab: &Lab,
+    job_id: &JobId,
+    store_path: &Path,
+    format: OutputFormat,
+) -> Result<(), CliError> {
+    let timings = get_job_timings(store_path, std::slice::from_ref(job_id))?;
+    let timing = timings.get(job_id);
+    let status = timing.map_or("pending", |t| records::outcome_label(&t.outcome));
+    let params = compute_all_effective_params(lab)
+        .remove(job_id)
+        .unwrap_or(serde_json::Value::Null);
+    let record = JobRecord::new(lab, job_id, status, timing, Some(store_path), params);
+
+    if format == OutputFormat::Csv {
+        return records::write_csv(&[record], std::io::stdout().lock());
+    }
+
+    let job = &lab.jobs[job_id];
+    let mut inputs: Vec<&JobId> = job
+        .executables
+        .values()
+        .flat_map(|exe| exe.inputs.iter().filter_map(|input| input.job_id.as_ref()))
+        .collect();
+    inputs.sort();
+    inputs.dedup();
+    let logs_path = Some(
+        store_path
+            .join(dirs::OUTPUTS)
+            .join(job_id.as_str())
+            .join(dirs::REPX),
+    )
+    .filter(|path| path.exists());
+    let succeeded = timing.is_some_and(|t| t.outcome == JobOutcome::Succeeded);
+    let detail = JobDetail {
+        record,
+        name: job.name.as_deref(),
+        stage_type: job.stage_type.to_string(),
+        inputs,
+        logs_path,
+        slurm_failure: read_slurm_failure(store_path, job_id, succeeded),
+        progress: read_progress(store_path, job_id),
+    };
+    records::write_structured(&detail, format, std::io::stdout().lock())
+}
+
 #[allow(clippy::expect_used)]
 fn handle_show_job(args: ShowJobArgs, lab: &Lab, target: Option<&str>) -> Result<(), CliError> {
     let config = config::load_config()?;
@@ -46,11 +146,19 @@ fn handle_show_job(args: ShowJobArgs, lab: &Lab, target: Option<&str>) -> Result
         .map(|(run_id, _)| run_id.to_string());
 
     let store_path = get_store_path(&config, target)?;
+    if args.format.is_structured() {
+        return write_job_detail(lab, job_id, &store_path, args.format);
+    }
     let outcomes = get_job_outcomes(&store_path, std::slice::from_ref(job_id))?;
-    let status = outcomes.get(job_id).map(|found| match found.outcome {
+    let outcome = outcomes.get(job_id).map(|found| &found.outcome);
+    let status = outcome.map(|outcome| match outcome {
         JobOutcome::Succeeded => "SUCCESS",
         JobOutcome::Failed => "FAILED",
+        JobOutcome::TimedOut => "TIMEOUT",
+        JobOutcome::Cancelled => "CANCELLED",
     });
+    let slurm_failure =
+        read_slurm_failure(&store_path, job_id, outcome == Some(&JobOutcome::Succeeded));
 
     println!("Job: {}", job_id.as_str());
     if let Some(name) = &job.name {
@@ -59,10 +167,17 @@ fn handle_show_job(args: ShowJobArgs, lab: &Lab, target: Option<&str>) -> Result
     if let Some(run) = &run_name {
         println!("Run: {}", run);
     }
-    println!(
-        "Status: {}",
-        status.unwrap_or("PENDING (not executed or not found)")
-    );
+    match (status, &slurm_failure) {
+        (Some(status), Some(failure)) => println!("Status: {} ({})", status, failure.summary()),
+        (None, Some(failure)) => println!("Status: {}", failure.summary()),
+        (status, None) => println!(
+            "Status: {}",
+            status.unwrap_or("PENDING (not executed or not found)")
+        ),
+    }
+    if let Some(progress) = read_progress(&store_path, job_id) {
+        println!("Progress: {}", progress.summary());
+    }
     println!("Stage Type: {}", job.stage_type);
 
     println!();
@@ -198,7 +313,181 @@ fn handle_show_job(args: ShowJobArgs, lab: &Lab, target: Option<&str>) -> Result
     Ok(())
 }
 
-fn get_store_path(
+fn handle_show_command(
+    args: ShowJobArgs,
+    lab: &Lab,
+    target: Option<&str>,
+    resources: Option<&Path>,
+) -> Result<(), CliError> {
+    let job = LocalJob::resolve(lab, &args.job_id, target, resources)?;
+    let executor = Executor::new(job.request);
+    let rt = super::create_tokio_runtime()?;
+    let command = rt
+        .block_on(executor.explain(&job.script_path, &job.args))
+        .map_err(|e| {
+            CliError::execution_failed("Failed to resolve the job command", e.to_string())
+        })?;
+    println!("{}", command);
+    Ok(())
+}
+
+pub(crate) struct LocalJob {
+    pub request: ExecutionRequest,
+    pub script_path: PathBuf,
+    pub args: Vec<String>,
+}
+
+impl LocalJob {
+    pub(crate) fn resolve(
+        lab: &Lab,
+        job_id: &str,
+        target: Option<&str>,
+        resources: Option<&Path>,
+    ) -> Result<Self, CliError> {
+        let config = config::load_config()?;
+        let job_id = resolver::resolve_target_job_id(lab, &RunId::from(job_id.to_string()))?;
+        let job = lab.jobs.get(job_id).ok_or_else(|| {
+            CliError::Config(CoreError::InvalidConfig {
+                detail: format!("Job '{}' not found in lab", job_id),
+            })
+        })?;
+        let main_exe = job.executables.get("main").ok_or_else(|| {
+            CliError::Config(CoreError::MissingExecutable {
+                job_id: job_id.to_string(),
+                executable: "main".to_string(),
+            })
+        })?;
+
+        let target_name = target
+            .or(config.submission_target.as_deref())
+            .ok_or(CliError::Config(CoreError::NoSubmissionTarget))?;
+        let target_config = config.targets.get(target_name).ok_or_else(|| {
+            CliError::Config(CoreError::TargetNotConfigured {
+                name: target_name.to_string(),
+            })
+        })?;
+        if target_config.address.is_some() {
+            return Err(CliError::execution_failed(
+                "Cannot resolve the job command for a remote target",
+                format!(
+                    "Target '{}' is remote; its tools and images can only be resolved on that host.",
+                    target_name
+                ),
+            ));
+        }
+
+        let image_tag = lab
+            .runs
+            .values()
+            .find(|run| run.jobs.contains(job_id))
+            .and_then(|run| run.image.as_deref())
+            .and_then(|path| path.file_stem())
+            .and_then(|stem| stem.to_str());
+        let scheduler_config = match target_config.default_scheduler {
+            Some(SchedulerType::Slurm) => target_config.slurm.as_ref(),
+            Some(SchedulerType::Sge) => target_config.sge.as_ref(),
+            Some(SchedulerType::Flux) => target_config.flux.as_ref(),
+            Some(SchedulerType::AwsBatch) => None,
+            _ => target_config.local.as_ref(),
+        };
+        let execution_type: ExecutionType =
+            resolve_execution_type(image_tag, None, target_config, scheduler_config, None)
+                .parse()
+                .map_err(|e| {
+                    CliError::Config(CoreError::InvalidConfig {
+                        detail: format!("Invalid execution type: {}", e),
+                    })
+                })?;
+        let runtime = super::parse_runtime(execution_type, image_tag.map(str::to_string))?;
+
+        let resources = config::load_resources(resources)?;
+        let directives = resources::resolve_for_job(
+            job_id,
+            target_name,
+            &resources,
+            job.resource_hints.as_ref(),
+        );
+        let enforce_limits = execution_type == ExecutionType::Native
+            && target_config
+                .local
+                .as_ref()
+                .is_some_and(|local| local.enforce_limits);
+        let resource_limits = if enforce_limits {
+            ResourceLimits::new(
+                directives.mem.as_ref().and_then(|mem| mem.to_bytes()),
+                directives.cpus_per_task,
+            )
+        } else {
+            None
+        };
+
+        let base_path = target_config.base_path.clone();
+        let job_root = base_path.join(dirs::OUTPUTS).join(job_id.as_str());
+        let user_out_dir = job_root.join(dirs::OUT);
+        let repx_out_dir = job_root.join(dirs::REPX);
+        let script_path = base_path.join("artifacts").join(&main_exe.path);
+        let job_package_path = script_path
+            .parent()
+            .and_then(|p| p.parent())
+            .unwrap_or(&base_path)
+            .to_path_buf();
+        let inputs_json_path = repx_out_dir.join("inputs.json");
+        let exec_args = vec![
+            path_to_string(&user_out_dir),
+            path_to_string(&inputs_json_path),
+            path_to_string(repx_out_dir.join("parameters.json")),
+        ];
+
+        let request = ExecutionRequest {
+            job_id: job_id.clone(),
+            runtime,
+            base_path: base_path.clone(),
+            node_local_path: target_config.node_local_path.clone(),
+            local_artifacts_path: None,
+            job_package_path,
+            inputs_json_path,
+            user_out_dir,
+            repx_out_dir,
+            host_tools_bin_dir: super::resolve_host_tools_dir(
+                &base_path,
+                &lab.host_tools_dir_name,
+                None,
+            ),
+            mount_policy: target_config.mount_policy(),
+            data_mounts: job.data_mounts.clone(),
+            network: target_config.network,
+            seccomp: target_config.seccomp.clone(),
+            pre_run: target_config.pre_run.clone(),
+            post_run: target_config.post_run.clone(),
+            image_cache_limit: target_config.image_cache_max_bytes(),
+            min_disk: directives.disk.as_ref().and_then(|d| d.to_bytes()),
+            inputs_data: None,
+            parameters_data: None,
+            interpreter: main_exe.interpreter.clone(),
+            arg_template: main_exe.resolve_args(job_id, &job.params)?,
+            resource_limits,
+            time_limit: None,
+            max_retries: 0,
+            retry_backoff: Duration::ZERO,
+            retry_exit_codes: Vec::new(),
+            failure_rules: Vec::new(),
+            checkpoint_interval: directives.checkpoint_interval.map(Duration::from_secs),
+            heartbeat_interval: None,
+            structured_logs: target_config.structured_logs,
+            env: target_config.env.clone().into_iter().collect(),
+            env_passthrough: target_config.env_passthrough.clone(),
+            secrets: super::load_job_secrets(job_id.as_str())?,
+        };
+
+        Ok(Self {
+            request,
+            script_path,
+            args: exec_args,
+        })
+    }
+}
+
+pub(crate) fn get_store_path(
     config: &Config,
     target_override: Option<&str>,
 ) -> Result<std::path::PathBuf, CliError> {
@@ -0,0 +1,274 @@
+use crate::cli::{OutputFormat, StatsArgs};
+use crate::commands::records::{outcome_label, stage_name, write_structured};
+use crate::commands::show::get_store_path;
+use crate::error::CliError;
+use repx_core::{
+    config,
+    constants::dirs,
+    model::{JobId, Lab},
+    store::outcomes::{get_job_timings, JobTiming},
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+const INPUTS_FILE: &str = "inputs.json";
+
+#[derive(Debug, Clone, Serialize)]
+struct JobStat {
+    job_id: JobId,
+    stage: String,
+    status: &'static str,
+    elapsed_secs: Option<f64>,
+    queue_wait_secs: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct StageStats {
+    stage: String,
+    finished: usize,
+    succeeded: usize,
+    failed: usize,
+    success_rate: f64,
+    total_secs: f64,
+    mean_secs: Option<f64>,
+    max_secs: Option<f64>,
+    mean_queue_wait_secs: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct CampaignStats {
+    selected: usize,
+    finished: usize,
+    success_rate: f64,
+    total_secs: f64,
+    stages: Vec<StageStats>,
+    slowest: Vec<JobStat>,
+}
+
+fn queue_wait(job_dir: &Path, timing: &JobTiming) -> Option<Duration> {
+    let staged = fs::metadata(job_dir.join(dirs::REPX).join(INPUTS_FILE))
+        .and_then(|meta| meta.modified())
+        .ok()?;
+    let started = chrono::TimeDelta::from_std(timing.elapsed?)
+        .ok()
+        .map(|elapsed| timing.finished_at - elapsed)?;
+    let staged: chrono::DateTime<chrono::Utc> = staged.into();
+    (started - staged).to_std().ok()
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn rate(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+fn summarize(selected: usize, jobs: &[JobStat], top: usize) -> CampaignStats {
+    let mut by_stage: BTreeMap<&str, Vec<&JobStat>> = BTreeMap::new();
+    for job in jobs {
+        by_stage.entry(&job.stage).or_default().push(job);
+    }
+
+    let stages = by_stage
+        .into_iter()
+        .map(|(stage, jobs)| {
+            let succeeded = jobs.iter().filter(|j| j.status == "succeeded").count();
+            let elapsed: Vec<f64> = jobs.iter().filter_map(|j| j.elapsed_secs).collect();
+            let waits: Vec<f64> = jobs.iter().filter_map(|j| j.queue_wait_secs).collect();
+            StageStats {
+                stage: stage.to_string(),
+                finished: jobs.len(),
+                succeeded,
+                failed: jobs.len() - succeeded,
+                success_rate: rate(succeeded, jobs.len()),
+                total_secs: elapsed.iter().sum(),
+                mean_secs: mean(&elapsed),
+                max_secs: elapsed.iter().copied().reduce(f64::max),
+                mean_queue_wait_secs: mean(&waits),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut slowest: Vec<JobStat> = jobs
+        .iter()
+        .filter(|j| j.elapsed_secs.is_some())
+        .cloned()
+        .collect();
+    slowest.sort_by(|a, b| {
+        b.elapsed_secs
+            .partial_cmp(&a.elapsed_secs)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.job_id.cmp(&b.job_id))
+    });
+    slowest.truncate(top);
+
+    let succeeded = stages.iter().map(|s| s.succeeded).sum();
+    CampaignStats {
+        selected,
+        finished: jobs.len(),
+        success_rate: rate(succeeded, jobs.len()),
+        total_secs: stages.iter().map(|s| s.total_secs).sum(),
+        stages,
+        slowest,
+    }
+}
+
+fn format_secs(secs: Option<f64>) -> String {
+    let Some(secs) = secs else {
+        return "-".to_string();
+    };
+    let secs = secs.round() as u64;
+    match (secs / 3600, (secs % 3600) / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, _) => format!("{}h {:02}m", h, m),
+    }
+}
+
+fn print_stats(stats: &CampaignStats) {
+    println!(
+        "{} of {} selected job(s) finished, {:.0}% succeeded, {} total wall time",
+        stats.finished,
+        stats.selected,
+        stats.success_rate * 100.0,
+        format_secs(Some(stats.total_secs))
+    );
+    if stats.stages.is_empty() {
+        return;
+    }
+
+    println!();
+    println!(
+        "{:<32} {:>6} {:>8} {:>10} {:>10} {:>10} {:>10}",
+        "STAGE", "JOBS", "SUCCESS", "TOTAL", "MEAN", "MAX", "QUEUE"
+    );
+    for stage in &stats.stages {
+        println!(
+            "{:<32} {:>6} {:>7.0}% {:>10} {:>10} {:>10} {:>10}",
+            stage.stage,
+            stage.finished,
+            stage.success_rate * 100.0,
+            format_secs(Some(stage.total_secs)),
+            format_secs(stage.mean_secs),
+            format_secs(stage.max_secs),
+            format_secs(stage.mean_queue_wait_secs)
+        );
+    }
+
+    if stats.slowest.is_empty() {
+        return;
+    }
+    println!();
+    println!("{:<10} {:<10} {:>10} JOB", "ELAPSED", "STATUS", "QUEUE");
+    for job in &stats.slowest {
+        println!(
+            "{:<10} {:<10} {:>10} {}",
+            format_secs(job.elapsed_secs),
+            job.status,
+            format_secs(job.queue_wait_secs),
+            job.job_id
+        );
+    }
+}
+
+pub fn handle_stats(args: StatsArgs, lab: &Lab, target: Option<&str>) -> Result<(), CliError> {
+    let job_ids: BTreeSet<JobId> = if args.specs.is_empty() {
+        lab.jobs.keys().cloned().collect()
+    } else {
+        super::resolve_spec_job_ids(lab, &args.specs)?
+    };
+    let job_ids: Vec<JobId> = job_ids.into_iter().collect();
+
+    let config = config::load_config()?;
+    let store_path = get_store_path(&config, target)?;
+    let timings = get_job_timings(&store_path, &job_ids)?;
+    let outputs_dir = store_path.join(dirs::OUTPUTS);
+
+    let jobs: Vec<JobStat> = job_ids
+        .iter()
+        .filter_map(|job_id| {
+            let timing = timings.get(job_id)?;
+            Some(JobStat {
+                job_id: job_id.clone(),
+                stage: stage_name(job_id),
+                status: outcome_label(&timing.outcome),
+                elapsed_secs: timing.elapsed.map(|d| d.as_secs_f64()),
+                queue_wait_secs: queue_wait(&outputs_dir.join(job_id.as_str()), timing)
+                    .map(|d| d.as_secs_f64()),
+            })
+        })
+        .collect();
+
+    let stats = summarize(job_ids.len(), &jobs, args.top);
+    match args.format {
+        OutputFormat::Table => print_stats(&stats),
+        format => write_structured(&stats, format, std::io::stdout().lock())?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(id: &str, status: &'static str, elapsed: f64, wait: Option<f64>) -> JobStat {
+        JobStat {
+            job_id: JobId::from(id),
+            stage: stage_name(&JobId::from(id)),
+            status,
+            elapsed_secs: Some(elapsed),
+            queue_wait_secs: wait,
+        }
+    }
+
+    #[test]
+    fn test_summarize_groups_by_stage_and_ranks_slowest() {
+        let jobs = vec![
+            stat("aaa-train-1.0", "succeeded", 100.0, Some(10.0)),
+            stat("bbb-train-1.0", "failed", 300.0, Some(30.0)),
+            stat("ccc-eval-1.0", "succeeded", 5.0, None),
+        ];
+        let stats = summarize(4, &jobs, 2);
+
+        assert_eq!(stats.selected, 4);
+        assert_eq!(stats.finished, 3);
+        assert_eq!(stats.total_secs, 405.0);
+        assert_eq!(stats.stages.len(), 2);
+        let train = stats
+            .stages
+            .iter()
+            .find(|s| s.stage == "train")
+            .expect("train stage");
+        assert_eq!(train.succeeded, 1);
+        assert_eq!(train.failed, 1);
+        assert_eq!(train.success_rate, 0.5);
+        assert_eq!(train.mean_secs, Some(200.0));
+        assert_eq!(train.max_secs, Some(300.0));
+        assert_eq!(train.mean_queue_wait_secs, Some(20.0));
+        let eval = stats
+            .stages
+            .iter()
+            .find(|s| s.stage == "eval")
+            .expect("eval stage");
+        assert_eq!(eval.mean_queue_wait_secs, None);
+
+        let slowest: Vec<&str> = stats.slowest.iter().map(|j| j.job_id.as_str()).collect();
+        assert_eq!(slowest, vec!["bbb-train-1.0", "aaa-train-1.0"]);
+    }
+
+    #[test]
+    fn test_format_secs_scales_units() {
+        assert_eq!(format_secs(None), "-");
+        assert_eq!(format_secs(Some(42.4)), "42s");
+        assert_eq!(format_secs(Some(125.0)), "2m 05s");
+        assert_eq!(format_secs(Some(7260.0)), "2h 01m");
+    }
+}
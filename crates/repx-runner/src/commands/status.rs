@@ -0,0 +1,168 @@
+use crate::cli::StatusArgs;
+use crate::error::CliError;
+use repx_client::{
+    federation::{FederatedStatus, LabStatus, StatusCounts},
+    Client,
+};
+use repx_core::{
+    config::Config,
+    constants::targets,
+    lab::LabSource,
+    model::RunId,
+    resolver,
+    workspace::{self, Workspace},
+};
+use std::path::Path;
+
+pub fn handle_status(
+    args: StatusArgs,
+    lab_path: &Path,
+    target: Option<&str>,
+    config: &Config,
+) -> Result<(), CliError> {
+    let ws = workspace::load_workspace()?;
+    let mut clients = Vec::new();
+    let mut unavailable = Vec::new();
+    if args.workspace {
+        if ws.labs.is_empty() {
+            println!("No labs registered. Add one with `repx workspace add <NAME> [PATH]`.");
+            return Ok(());
+        }
+        for (name, lab) in &ws.labs {
+            match Client::new(config.clone(), LabSource::from_path(&lab.path)) {
+                Ok(client) => clients.push((name.clone(), client)),
+                Err(e) => unavailable.push((name.clone(), e.to_string())),
+            }
+        }
+    } else {
+        let client = Client::new(config.clone(), LabSource::from_path(lab_path))?;
+        clients.push((lab_label(&ws, lab_path), client));
+    }
+
+    let federated = if args.all_targets || args.workspace {
+        FederatedStatus::collect(clients.iter().map(|(label, c)| (label.clone(), c)))
+    } else {
+        let target_name = target
+            .or(config.submission_target.as_deref())
+            .unwrap_or(targets::LOCAL);
+        let mut labs = Vec::new();
+        for (label, client) in &clients {
+            labs.push(LabStatus::collect_target(
+                label.clone(),
+                client,
+                target_name,
+            )?);
+        }
+        FederatedStatus { labs }
+    };
+
+    match &args.job {
+        Some(job) => print_job(&federated, &clients, job),
+        None => {
+            print_table(&federated, &unavailable, args.workspace);
+            Ok(())
+        }
+    }
+}
+
+fn lab_label(ws: &Workspace, lab_path: &Path) -> String {
+    ws.name_for_path(lab_path)
+        .map(str::to_string)
+        .unwrap_or_else(|| lab_path.display().to_string())
+}
+
+fn print_header() {
+    println!(
+        "{:<32} {:>9} {:>7} {:>8} {:>7} {:>8} {:>8} {:>6}",
+        "TARGET", "SUCCEEDED", "FAILED", "RUNNING", "QUEUED", "PENDING", "BLOCKED", "TOTAL"
+    );
+}
+
+fn print_row(label: &str, counts: &StatusCounts) {
+    println!(
+        "{:<32} {:>9} {:>7} {:>8} {:>7} {:>8} {:>8} {:>6}",
+        label,
+        counts.succeeded,
+        counts.failed,
+        counts.running,
+        counts.queued,
+        counts.pending,
+        counts.blocked,
+        counts.total()
+    );
+}
+
+fn print_error_row(label: &str, message: &str) {
+    println!(
+        "{:<32} error: {}",
+        label,
+        message.lines().next().unwrap_or_default()
+    );
+}
+
+fn print_table(federated: &FederatedStatus, unavailable: &[(String, String)], per_lab: bool) {
+    print_header();
+    for (label, error) in unavailable {
+        print_error_row(label, error);
+    }
+    for lab in &federated.labs {
+        for (name, snapshot) in &lab.targets {
+            let label = if per_lab {
+                format!("{}/{}", lab.label, name)
+            } else {
+                name.clone()
+            };
+            match &snapshot.error {
+                Some(error) => print_error_row(&label, error),
+                None => print_row(&label, &snapshot.jobs.values().collect()),
+            }
+        }
+        if lab.targets.len() > 1 {
+            let label = if per_lab {
+                format!("{}/combined", lab.label)
+            } else {
+                "combined".to_string()
+            };
+            print_row(&label, &lab.counts());
+        }
+    }
+    if per_lab && federated.labs.len() > 1 {
+        print_row("ALL", &federated.counts());
+    }
+}
+
+fn print_job(
+    federated: &FederatedStatus,
+    clients: &[(String, Client)],
+    job: &str,
+) -> Result<(), CliError> {
+    let input = RunId::from(job.to_string());
+    let mut first_error = None;
+    let mut found = false;
+    for (label, client) in clients {
+        let job_id = match resolver::resolve_target_job_id(client.lab(), &input) {
+            Ok(job_id) => job_id,
+            Err(e) => {
+                first_error.get_or_insert(e);
+                continue;
+            }
+        };
+        found = true;
+        for (lab, target, status) in federated.locate(job_id) {
+            if lab != label.as_str() {
+                continue;
+            }
+            let status = super::list::format_job_status(Some(status));
+            let location = if clients.len() > 1 {
+                format!("{}/{}", lab, target)
+            } else {
+                target.to_string()
+            };
+            println!("{:<32} {:<10} {}", location, status, job_id);
+        }
+    }
+    match first_error {
+        Some(e) if !found => Err(e.into()),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,70 @@
+use crate::cli::{OutputFormat, TargetsArgs};
+use crate::commands::records::write_structured;
+use crate::error::CliError;
+use repx_client::{
+    targets::{HealthStatus, TargetHealth},
+    Client,
+};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct TargetReport {
+    target: String,
+    status: HealthStatus,
+    #[serde(flatten)]
+    health: TargetHealth,
+}
+
+pub fn handle_targets(args: TargetsArgs, client: &Client) -> Result<(), CliError> {
+    let names: Vec<String> = if args.names.is_empty() {
+        client.config().targets.keys().cloned().collect()
+    } else {
+        args.names
+    };
+    let targets = names
+        .iter()
+        .map(|name| {
+            client.get_target(name).ok_or_else(|| {
+                CliError::execution_failed(
+                    "Failed to check targets",
+                    format!("Target '{}' is not configured", name),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let reports: Vec<TargetReport> = std::thread::scope(|scope| {
+        let handles: Vec<_> = names
+            .iter()
+            .zip(&targets)
+            .map(|(name, target)| scope.spawn(move || (name, target.health())))
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .map(|(name, health)| TargetReport {
+                target: name.clone(),
+                status: health.status(),
+                health,
+            })
+            .collect()
+    });
+
+    match args.format {
+        OutputFormat::Table => print_reports(&reports),
+        format => write_structured(&reports, format, std::io::stdout().lock())?,
+    }
+    Ok(())
+}
+
+fn print_reports(reports: &[TargetReport]) {
+    for (i, report) in reports.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{} {}", report.target, report.status);
+        for check in &report.health.checks {
+            println!("  {:<5} {:<18} {}", check.level, check.name, check.detail);
+        }
+    }
+}
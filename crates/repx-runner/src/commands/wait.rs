@@ -0,0 +1,90 @@
+use crate::cli::WaitArgs;
+use crate::commands::{list::format_job_status, watch::WatchState, AppContext};
+use crate::error::CliError;
+use repx_core::{
+    engine::{self, JobStatus},
+    model::JobId,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub fn handle_wait(args: WaitArgs, context: &AppContext<'_>) -> Result<(), CliError> {
+    let client = context.client;
+    let lab = client.lab();
+    let target_name = context.submission_target;
+    let target = client.get_target(target_name).ok_or_else(|| {
+        CliError::execution_failed(
+            "Failed to wait for jobs",
+            format!("Target '{}' is not configured", target_name),
+        )
+    })?;
+
+    let waited: BTreeSet<JobId> = if args.specs.is_empty() {
+        lab.jobs.keys().cloned().collect()
+    } else {
+        super::resolve_spec_job_ids(lab, &args.specs)?
+    };
+    let interval = Duration::from_secs(args.interval.max(1));
+    let deadline = args
+        .timeout
+        .map(|secs| Instant::now() + Duration::from_secs(secs));
+    let mut state = WatchState::new(target_name, target.base_path().to_path_buf(), 0);
+
+    loop {
+        let raw = client.get_statuses_for_active_target(target_name, None)?;
+        let statuses: HashMap<JobId, JobStatus> = engine::determine_job_statuses(lab, raw)
+            .into_iter()
+            .filter(|(job_id, _)| waited.contains(job_id))
+            .collect();
+        let newly_finished = state.update(statuses);
+        if !args.quiet {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(state.render_changes(&newly_finished).as_bytes())?;
+            stdout.flush()?;
+        }
+
+        let counts = state.counts();
+        let unfinished = counts.running + counts.queued + counts.pending;
+        if unfinished == 0 {
+            break;
+        }
+        let remaining = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => interval,
+        };
+        if remaining.is_zero() {
+            return Err(CliError::TimedOut(format!(
+                "{} of {} jobs still unfinished after {}s",
+                unfinished,
+                counts.total(),
+                args.timeout.unwrap_or_default()
+            )));
+        }
+        thread::sleep(interval.min(remaining));
+    }
+
+    let counts = state.counts();
+    if counts.failed + counts.blocked == 0 {
+        println!("All {} jobs succeeded.", counts.total());
+        return Ok(());
+    }
+    for (job_id, status) in state.failures() {
+        eprintln!(
+            "{} {:<10} {}",
+            job_id,
+            format_job_status(Some(status)),
+            state.log_path(job_id).display()
+        );
+    }
+    Err(CliError::execution_failed(
+        "Waited-for jobs did not all succeed",
+        format!(
+            "{} failed and {} blocked of {} jobs",
+            counts.failed,
+            counts.blocked,
+            counts.total()
+        ),
+    ))
+}
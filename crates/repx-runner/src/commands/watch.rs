@@ -0,0 +1,256 @@
+use crate::cli::WatchArgs;
+use crate::commands::{list::format_job_status, run::format_wall_time, AppContext};
+use crate::error::CliError;
+use colored::Colorize;
+use repx_client::federation::StatusCounts;
+use repx_core::{
+    constants::{dirs, logs},
+    engine::{self, JobStatus},
+    model::JobId,
+};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const MAX_LISTED_FAILURES: usize = 20;
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+pub(super) fn is_finished(status: &JobStatus) -> bool {
+    matches!(
+        status,
+        JobStatus::Succeeded { .. }
+            | JobStatus::Failed { .. }
+            | JobStatus::TimedOut { .. }
+            | JobStatus::Cancelled { .. }
+            | JobStatus::Lost { .. }
+    )
+}
+
+pub(super) fn is_failure(status: &JobStatus) -> bool {
+    is_finished(status) && !matches!(status, JobStatus::Succeeded { .. })
+}
+
+struct Finished {
+    elapsed: Duration,
+    job_id: JobId,
+    status: JobStatus,
+}
+
+pub(super) struct WatchState {
+    target_name: String,
+    base_path: PathBuf,
+    started: Instant,
+    statuses: HashMap<JobId, JobStatus>,
+    recent: VecDeque<Finished>,
+    recent_limit: usize,
+    last_counts: Option<StatusCounts>,
+}
+
+impl WatchState {
+    pub(super) fn new(target_name: &str, base_path: PathBuf, recent_limit: usize) -> Self {
+        Self {
+            target_name: target_name.to_string(),
+            base_path,
+            started: Instant::now(),
+            statuses: HashMap::new(),
+            recent: VecDeque::new(),
+            recent_limit,
+            last_counts: None,
+        }
+    }
+
+    pub(super) fn log_path(&self, job_id: &JobId) -> PathBuf {
+        self.base_path
+            .join(dirs::OUTPUTS)
+            .join(job_id.as_str())
+            .join(dirs::REPX)
+            .join(logs::STDERR)
+    }
+
+    pub(super) fn update(&mut self, statuses: HashMap<JobId, JobStatus>) -> Vec<JobId> {
+        let first_poll = self.statuses.is_empty();
+        let mut newly_finished: Vec<JobId> = statuses
+            .iter()
+            .filter(|(job_id, status)| {
+                is_finished(status) && !first_poll && self.statuses.get(*job_id) != Some(*status)
+            })
+            .map(|(job_id, _)| job_id.clone())
+            .collect();
+        newly_finished.sort();
+
+        let elapsed = self.started.elapsed();
+        for job_id in &newly_finished {
+            self.recent.push_front(Finished {
+                elapsed,
+                job_id: job_id.clone(),
+                status: statuses[job_id].clone(),
+            });
+        }
+        self.recent.truncate(self.recent_limit);
+        self.statuses = statuses;
+        newly_finished
+    }
+
+    pub(super) fn counts(&self) -> StatusCounts {
+        self.statuses.values().collect()
+    }
+
+    pub(super) fn failures(&self) -> Vec<(&JobId, &JobStatus)> {
+        let mut failures: Vec<_> = self
+            .statuses
+            .iter()
+            .filter(|(_, status)| is_failure(status))
+            .collect();
+        failures.sort_by(|a, b| a.0.cmp(b.0));
+        failures
+    }
+
+    fn render_counts(&self, counts: &StatusCounts) -> String {
+        format!(
+            "{} {} | {} {} | {} {} | {} {} | {} {} | {} {} | {} total",
+            counts.succeeded.to_string().green().bold(),
+            "succeeded".green(),
+            counts.failed.to_string().red().bold(),
+            "failed".red(),
+            counts.running.to_string().yellow().bold(),
+            "running".yellow(),
+            counts.queued,
+            "queued",
+            counts.pending,
+            "pending",
+            counts.blocked.to_string().dimmed(),
+            "blocked".dimmed(),
+            counts.total()
+        )
+    }
+
+    fn render_finished(&self, job_id: &JobId, status: &JobStatus) -> String {
+        if is_failure(status) {
+            format!(
+                "{} {} ({}) log: {}",
+                "FAIL".red().bold(),
+                job_id,
+                format_job_status(Some(status)),
+                self.log_path(job_id).display()
+            )
+        } else {
+            format!("{} {}", "OK".green().bold(), job_id)
+        }
+    }
+
+    fn render_dashboard(&self, interval: Duration) -> String {
+        let counts = self.counts();
+        let mut out = format!(
+            "repx watch: target '{}', every {}s, elapsed {}\n\n{}\n",
+            self.target_name.cyan(),
+            interval.as_secs(),
+            format_wall_time(&self.started.elapsed()),
+            self.render_counts(&counts)
+        );
+
+        if !self.recent.is_empty() {
+            out.push_str("\nRecently finished:\n");
+            for finished in &self.recent {
+                out.push_str(&format!(
+                    "  {} {}\n",
+                    format!("[+{}]", format_wall_time(&finished.elapsed)).dimmed(),
+                    self.render_finished(&finished.job_id, &finished.status)
+                ));
+            }
+        }
+
+        let failures = self.failures();
+        if !failures.is_empty() {
+            out.push_str("\nFailures:\n");
+            for (job_id, status) in failures.iter().take(MAX_LISTED_FAILURES) {
+                out.push_str(&format!(
+                    "  {} {:<10} {}\n",
+                    job_id,
+                    format_job_status(Some(status)),
+                    self.log_path(job_id).display().to_string().dimmed()
+                ));
+            }
+            if failures.len() > MAX_LISTED_FAILURES {
+                out.push_str(&format!(
+                    "  ... and {} more\n",
+                    failures.len() - MAX_LISTED_FAILURES
+                ));
+            }
+        }
+        out
+    }
+
+    pub(super) fn render_changes(&mut self, newly_finished: &[JobId]) -> String {
+        let stamp = format!("[+{}]", format_wall_time(&self.started.elapsed()));
+        let mut out = String::new();
+        for job_id in newly_finished {
+            out.push_str(&format!(
+                "{} {}\n",
+                stamp,
+                self.render_finished(job_id, &self.statuses[job_id])
+            ));
+        }
+        let counts = self.counts();
+        if self.last_counts != Some(counts) {
+            out.push_str(&format!("{} {}\n", stamp, self.render_counts(&counts)));
+            self.last_counts = Some(counts);
+        }
+        out
+    }
+}
+
+pub fn handle_watch(args: WatchArgs, context: &AppContext<'_>) -> Result<(), CliError> {
+    let client = context.client;
+    client.enable_status_agents();
+    let lab = client.lab();
+    let target_name = context.submission_target;
+    let target = client.get_target(target_name).ok_or_else(|| {
+        CliError::execution_failed(
+            "Failed to watch jobs",
+            format!("Target '{}' is not configured", target_name),
+        )
+    })?;
+
+    let watched: BTreeSet<JobId> = if args.specs.is_empty() {
+        lab.jobs.keys().cloned().collect()
+    } else {
+        super::resolve_spec_job_ids(lab, &args.specs)?
+    };
+    let interval = Duration::from_secs(args.interval.max(1));
+    let dashboard = std::io::stdout().is_terminal();
+
+    let mut state = WatchState::new(target_name, target.base_path().to_path_buf(), args.recent);
+
+    loop {
+        let raw = client.get_statuses_for_active_target(target_name, None)?;
+        let statuses: HashMap<JobId, JobStatus> = engine::determine_job_statuses(lab, raw)
+            .into_iter()
+            .filter(|(job_id, _)| watched.contains(job_id))
+            .collect();
+        let newly_finished = state.update(statuses);
+
+        let output = if dashboard {
+            format!("{}{}", CLEAR_SCREEN, state.render_dashboard(interval))
+        } else {
+            state.render_changes(&newly_finished)
+        };
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(output.as_bytes())?;
+        stdout.flush()?;
+        drop(stdout);
+
+        let counts = state.counts();
+        if args.exit_when_done && counts.running + counts.queued + counts.pending == 0 {
+            if counts.failed > 0 {
+                return Err(CliError::execution_failed(
+                    "Watched jobs finished with failures",
+                    format!("{} of {} jobs failed", counts.failed, counts.total()),
+                ));
+            }
+            return Ok(());
+        }
+        thread::sleep(interval);
+    }
+}
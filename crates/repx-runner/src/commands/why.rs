@@ -0,0 +1,221 @@
+use crate::cli::WhyArgs;
+use crate::commands::{list::format_job_status, watch::is_failure, AppContext};
+use crate::error::CliError;
+use repx_client::{client::LocalQueueReport, targets::SlurmState};
+use repx_core::{
+    constants::{dirs, markers},
+    engine::{self, JobStatus},
+    fs_utils::format_bytes,
+    model::{JobId, Lab, RunId, SchedulerType},
+    resolver,
+    store::{completion_log, heartbeat, slurm_failure::SlurmFailure},
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+
+pub fn handle_why(args: WhyArgs, context: &AppContext<'_>) -> Result<(), CliError> {
+    let client = context.client;
+    let lab = client.lab();
+    let target_name = context.submission_target;
+    let target = client.get_target(target_name).ok_or_else(|| {
+        CliError::execution_failed(
+            "Failed to explain job",
+            format!("Target '{}' is not configured", target_name),
+        )
+    })?;
+    let job_id = resolver::resolve_target_job_id(lab, &RunId::from(args.job_id))?.clone();
+
+    let raw = client.get_statuses_for_active_target(target_name, None)?;
+    let statuses = engine::determine_job_statuses(lab, raw);
+    let status = statuses.get(&job_id);
+
+    println!("Job: {}", job_id);
+    println!("Target: {}", target_name);
+    println!("Status: {}", format_job_status(status));
+    if let Some(JobStatus::Failed {
+        reason: Some(reason),
+        ..
+    }) = status
+    {
+        println!("Reason: {}", reason);
+    }
+
+    let deps: Vec<&JobId> = lab
+        .jobs
+        .get(&job_id)
+        .map(|job| job.all_dependencies().collect())
+        .unwrap_or_default();
+    println!();
+    if deps.is_empty() {
+        println!("Dependencies: none");
+    } else {
+        println!("Dependencies:");
+        for dep in &deps {
+            println!("  {:<10} {}", format_job_status(statuses.get(*dep)), dep);
+        }
+    }
+    if matches!(status, Some(JobStatus::Blocked { .. })) {
+        let causes = blocking_causes(lab, &statuses, &job_id);
+        if !causes.is_empty() {
+            println!("Blocked by:");
+            for (cause, status) in &causes {
+                println!("  {:<10} {}", format_job_status(Some(status)), cause);
+            }
+        }
+    }
+
+    let tracked = client
+        .tracked_batch_job(&job_id)
+        .filter(|(name, _, _)| name == target_name)
+        .map(|(_, scheduler, id)| (scheduler, id));
+    let scheduler = tracked
+        .map(|(scheduler, _)| scheduler)
+        .or(target.config().default_scheduler)
+        .filter(|scheduler| {
+            matches!(
+                scheduler,
+                SchedulerType::Slurm | SchedulerType::Sge | SchedulerType::Flux
+            )
+        });
+    if let Some(scheduler) = scheduler {
+        let (label, queue) = match scheduler {
+            SchedulerType::Sge => ("SGE", target.qstat()),
+            SchedulerType::Flux => ("Flux", target.flux_jobs()),
+            _ => ("SLURM", target.squeue()),
+        };
+        println!();
+        match queue {
+            Ok(queue) => match queue.get(&job_id) {
+                Some(info) => {
+                    let state = match &info.state {
+                        SlurmState::Pending => "pending",
+                        SlurmState::Running => "running",
+                        SlurmState::Other(code) => code.as_str(),
+                    };
+                    match &info.reason {
+                        Some(reason) => {
+                            println!("{}: job {} is {} ({})", label, info.slurm_id, state, reason)
+                        }
+                        None => println!("{}: job {} is {}", label, info.slurm_id, state),
+                    }
+                }
+                None => match tracked {
+                    Some((_, id)) => println!("{}: job {} is no longer in the queue", label, id),
+                    None => println!("{}: not in the queue", label),
+                },
+            },
+            Err(e) => println!("{}: failed to query the queue: {}", label, e),
+        }
+    }
+
+    if let Some(report) = client.local_queue_report(&job_id, target_name) {
+        println!();
+        print_local_queue(&report);
+    }
+
+    let base_path = target.base_path();
+    let repx_dir = base_path
+        .join(dirs::OUTPUTS)
+        .join(job_id.as_str())
+        .join(dirs::REPX);
+    let mut consulted: Vec<PathBuf> = vec![completion_log::completions_path(base_path)];
+    consulted.extend(
+        [
+            markers::SUCCESS,
+            markers::FAIL,
+            markers::TIMEOUT,
+            markers::CANCELLED,
+        ]
+        .iter()
+        .map(|marker| repx_dir.join(marker)),
+    );
+    consulted.push(heartbeat::heartbeat_path(&repx_dir));
+    consulted.push(SlurmFailure::path(&repx_dir));
+    println!();
+    println!("Files consulted:");
+    for path in consulted {
+        let presence = match target.remote_file_size(&path) {
+            Ok(Some(_)) => "present",
+            Ok(None) => "missing",
+            Err(e) => {
+                tracing::debug!("Failed to stat '{}': {}", path.display(), e);
+                "unreadable"
+            }
+        };
+        println!("  {:<10} {}", presence, path.display());
+    }
+    Ok(())
+}
+
+fn blocking_causes(
+    lab: &Lab,
+    statuses: &HashMap<JobId, JobStatus>,
+    job_id: &JobId,
+) -> BTreeMap<JobId, JobStatus> {
+    let mut causes = BTreeMap::new();
+    let mut visited = BTreeSet::new();
+    let mut stack = vec![job_id.clone()];
+    while let Some(current) = stack.pop() {
+        if !visited.insert(current.clone()) {
+            continue;
+        }
+        let Some(job) = lab.jobs.get(&current) else {
+            continue;
+        };
+        for dep in job.all_dependencies() {
+            match statuses.get(dep) {
+                Some(JobStatus::Succeeded { .. }) => {}
+                Some(JobStatus::Blocked { .. }) => stack.push(dep.clone()),
+                Some(status) => {
+                    causes.insert(dep.clone(), status.clone());
+                }
+                None => {
+                    causes.insert(dep.clone(), JobStatus::Pending);
+                }
+            }
+        }
+    }
+    if causes.values().any(is_failure) {
+        causes.retain(|_, status| is_failure(status));
+    }
+    causes
+}
+
+fn print_local_queue(report: &LocalQueueReport) {
+    if report.waiting.is_empty() {
+        println!("Local queue: no unit of this job is waiting");
+    } else {
+        println!("Local queue:");
+        for unit in &report.waiting {
+            if unit.unmet_deps.is_empty() {
+                println!(
+                    "  {} waits for {} RAM and {} CPUs",
+                    unit.unit,
+                    format_bytes(unit.mem_bytes, false),
+                    unit.cpus
+                );
+            } else {
+                println!("  {} waits for {}", unit.unit, unit.unmet_deps.join(", "));
+            }
+        }
+    }
+    let used_mem: u64 = report.running.iter().map(|unit| unit.mem_bytes).sum();
+    let used_cpus: u64 = report.running.iter().map(|unit| u64::from(unit.cpus)).sum();
+    println!(
+        "  Host: {} of {} RAM and {} of {} CPUs reserved by {} running units",
+        format_bytes(used_mem, false),
+        format_bytes(report.host_mem_bytes, false),
+        used_cpus,
+        report.host_cpus,
+        report.running.len()
+    );
+    for unit in &report.running {
+        println!(
+            "    {} (pid {}): {} RAM, {} CPUs",
+            unit.unit,
+            unit.pid,
+            format_bytes(unit.mem_bytes, false),
+            unit.cpus
+        );
+    }
+}
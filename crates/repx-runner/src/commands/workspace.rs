@@ -0,0 +1,121 @@
+use crate::cli::{WorkspaceArgs, WorkspaceCommand};
+use crate::error::CliError;
+use repx_client::{federation::StatusCounts, Client};
+use repx_core::{
+    config,
+    lab::LabSource,
+    workspace::{self, Workspace},
+};
+
+pub fn handle_workspace(args: WorkspaceArgs, target: Option<String>) -> Result<(), CliError> {
+    let mut ws = workspace::load_workspace()?;
+    match args.command {
+        WorkspaceCommand::List => {
+            list_labs(&ws);
+            Ok(())
+        }
+        WorkspaceCommand::Add(add_args) => {
+            ws.add(&add_args.name, &add_args.path, target)?;
+            workspace::save_workspace(&ws)?;
+            println!("Added lab '{}' to the workspace.", add_args.name);
+            Ok(())
+        }
+        WorkspaceCommand::Remove(name_args) => {
+            ws.remove(&name_args.name)?;
+            workspace::save_workspace(&ws)?;
+            println!("Removed lab '{}' from the workspace.", name_args.name);
+            Ok(())
+        }
+        WorkspaceCommand::Use(name_args) => {
+            ws.set_active(&name_args.name)?;
+            workspace::save_workspace(&ws)?;
+            println!("Active lab is now '{}'.", name_args.name);
+            Ok(())
+        }
+        WorkspaceCommand::Status => workspace_status(&ws),
+    }
+}
+
+fn list_labs(ws: &Workspace) {
+    if ws.labs.is_empty() {
+        println!("No labs registered. Add one with `repx workspace add <NAME> [PATH]`.");
+        return;
+    }
+    println!("  {:<20} {:<12} PATH", "NAME", "TARGET");
+    for (name, lab) in &ws.labs {
+        let marker = if ws.active.as_deref() == Some(name.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        println!(
+            "{} {:<20} {:<12} {}",
+            marker,
+            name,
+            lab.target.as_deref().unwrap_or("-"),
+            lab.path.display()
+        );
+    }
+}
+
+fn workspace_status(ws: &Workspace) -> Result<(), CliError> {
+    if ws.labs.is_empty() {
+        println!("No labs registered. Add one with `repx workspace add <NAME> [PATH]`.");
+        return Ok(());
+    }
+    let config = config::load_config()?;
+
+    println!(
+        "  {:<20} {:>9} {:>7} {:>8} {:>7} {:>8} {:>8} {:>6}",
+        "NAME", "SUCCEEDED", "FAILED", "RUNNING", "QUEUED", "PENDING", "BLOCKED", "TOTAL"
+    );
+    let mut overall = StatusCounts::default();
+    for (name, lab) in &ws.labs {
+        let marker = if ws.active.as_deref() == Some(name.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        let statuses = Client::new(config.clone(), LabSource::from_path(&lab.path))
+            .and_then(|client| client.get_statuses());
+        match statuses {
+            Ok((_, jobs)) => {
+                let summary: StatusCounts = jobs.values().collect();
+                overall.merge(&summary);
+                println!(
+                    "{} {:<20} {:>9} {:>7} {:>8} {:>7} {:>8} {:>8} {:>6}",
+                    marker,
+                    name,
+                    summary.succeeded,
+                    summary.failed,
+                    summary.running,
+                    summary.queued,
+                    summary.pending,
+                    summary.blocked,
+                    summary.total()
+                );
+            }
+            Err(e) => {
+                let message = e.to_string();
+                println!(
+                    "{} {:<20} error: {}",
+                    marker,
+                    name,
+                    message.lines().next().unwrap_or_default()
+                );
+            }
+        }
+    }
+    println!(
+        "  {:<20} {:>9} {:>7} {:>8} {:>7} {:>8} {:>8} {:>6}",
+        "ALL",
+        overall.succeeded,
+        overall.failed,
+        overall.running,
+        overall.queued,
+        overall.pending,
+        overall.blocked,
+        overall.total()
+    );
+    Ok(())
+}
@@ -23,11 +23,21 @@ pub enum CliError {
         log_path: Option<std::path::PathBuf>,
         log_summary: String,
     },
+
+    #[error("Timed out: {0}")]
+    TimedOut(String),
 }
 
 pub type Result<T> = std::result::Result<T, CliError>;
 
 impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::TimedOut(_) => 2,
+            _ => 1,
+        }
+    }
+
     pub fn execution_failed(message: impl Into<String>, summary: impl Into<String>) -> Self {
         Self::ExecutionFailed {
             message: message.into(),
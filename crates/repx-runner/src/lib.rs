@@ -1,10 +1,17 @@
-use crate::cli::{Cli, Commands};
+use crate::cli::{Cli, Commands, RunArgs};
 use crate::commands::AppContext;
 use repx_client::Client;
 use repx_core::{
-    config, constants::targets, errors::CoreError, lab, lab::LabSource, logging::Verbosity,
+    config::{self, ResourceRule},
+    constants::targets,
+    errors::CoreError,
+    lab,
+    lab::LabSource,
+    logging::Verbosity,
     model::SchedulerType,
+    workspace,
 };
+use std::path::Path;
 
 pub mod cli;
 pub mod commands;
@@ -17,12 +24,127 @@ fn create_client(config: &config::Config, source: &LabSource) -> Result<Client,
         .map_err(|e| CliError::execution_failed("Failed to initialize client", e.to_string()))
 }
 
-pub fn run(cli: Cli) -> Result<(), CliError> {
+enum SubmitMode {
+    Run,
+    Retry,
+    Resubmit(Box<ResourceRule>),
+}
+
+fn handle_submission(
+    lab: &Path,
+    resources_path: Option<&Path>,
+    target: Option<&str>,
+    scheduler: Option<SchedulerType>,
+    verbose: Verbosity,
+    args: RunArgs,
+    mode: SubmitMode,
+) -> Result<(), CliError> {
+    let source = LabSource::from_path(lab);
+    let config = config::load_config()?;
+    let resources = config::load_resources(resources_path)?;
+
+    let client = create_client(&config, &source)?;
+
+    let target_name = match target.or(config.submission_target.as_deref()) {
+        Some(name) => name.to_string(),
+        None => return Err(CliError::Config(CoreError::NoSubmissionTarget)),
+    };
+
+    let target_config = config.targets.get(&target_name).ok_or_else(|| {
+        CliError::Config(CoreError::TargetNotConfigured {
+            name: target_name.clone(),
+        })
+    })?;
+
+    let scheduler: SchedulerType = if let Some(s) = scheduler {
+        s
+    } else {
+        target_config
+            .default_scheduler
+            .or(target_config
+                .container
+                .as_ref()
+                .map(|_| SchedulerType::Local))
+            .or(config.default_scheduler)
+            .unwrap_or(SchedulerType::Slurm)
+    };
+
+    let num_jobs = if scheduler == SchedulerType::Local {
+        Some(
+            args.jobs
+                .or_else(|| {
+                    target_config
+                        .local
+                        .as_ref()
+                        .and_then(|c| c.local_concurrency)
+                })
+                .unwrap_or_else(num_cpus::get),
+        )
+    } else {
+        None
+    };
+
+    let artifact_store = args
+        .artifact_store
+        .map(repx_core::model::ArtifactStore::from)
+        .or(target_config.artifact_store)
+        .unwrap_or_default();
+
+    let context = AppContext {
+        source: &source,
+        client: &client,
+        submission_target: &target_name,
+    };
+
+    let run_config = commands::run::RunConfig {
+        target_name: target_name.clone(),
+        scheduler,
+        num_jobs,
+        verbose,
+        artifact_store,
+        retry_failed: !matches!(mode, SubmitMode::Run),
+    };
+    match mode {
+        SubmitMode::Run => commands::run::handle_run(args, &context, resources, run_config),
+        SubmitMode::Retry => commands::retry::handle_retry(args, &context, resources, run_config),
+        SubmitMode::Resubmit(overrides) => {
+            commands::resubmit::handle_resubmit(args, &overrides, &context, resources, run_config)
+        }
+    }
+}
+
+pub fn run(mut cli: Cli) -> Result<(), CliError> {
     tracing::trace!(
         "repx invoked with: {:?}",
         std::env::args().collect::<Vec<_>>()
     );
 
+    let uses_lab = !matches!(
+        cli.command,
+        Commands::InternalOrchestrate(_)
+            | Commands::InternalExecute(_)
+            | Commands::InternalScatterGather(_)
+            | Commands::InternalGc(_)
+            | Commands::InternalImagesPrune(_)
+            | Commands::InternalClean(_)
+            | Commands::InternalPrune(_)
+            | Commands::InternalCasLink(_)
+            | Commands::Agent(_)
+            | Commands::Workspace(_)
+    );
+    if uses_lab {
+        let resolved = workspace::resolve_lab_arg(&cli.lab)?;
+        if let Some(name) = &resolved.name {
+            tracing::debug!(
+                "Using workspace lab '{}' at {}",
+                name,
+                resolved.path.display()
+            );
+        }
+        cli.lab = resolved.path;
+        cli.target = cli.target.or(resolved.target);
+    }
+
     match cli.command {
         Commands::InternalOrchestrate(args) => {
             commands::internal::handle_internal_orchestrate(args)
@@ -35,6 +157,11 @@ pub fn run(cli: Cli) -> Result<(), CliError> {
             let rt = commands::create_tokio_runtime()?;
             rt.block_on(commands::gc::async_handle_internal_gc(args))
         }
+        Commands::InternalImagesPrune(args) => commands::images::handle_internal_images_prune(args),
+        Commands::InternalClean(args) => commands::clean::handle_internal_clean(args),
+        Commands::InternalPrune(args) => commands::prune::handle_internal_prune(args),
+        Commands::InternalCasLink(args) => commands::cas::handle_internal_cas_link(args),
+        Commands::Agent(args) => commands::agent::handle_agent(args),
         Commands::List(args) => {
             let source = LabSource::from_path(&cli.lab);
             let loaded_lab = lab::load(&source)?;
@@ -43,13 +170,45 @@ pub fn run(cli: Cli) -> Result<(), CliError> {
         Commands::Show(args) => {
             let source = LabSource::from_path(&cli.lab);
             let loaded_lab = lab::load(&source)?;
-            commands::show::handle_show(args, &loaded_lab, &source, cli.target.as_deref())
+            commands::show::handle_show(
+                args,
+                &loaded_lab,
+                &source,
+                cli.target.as_deref(),
+                cli.resources.as_deref(),
+            )
+        }
+        Commands::Archive(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let loaded_lab = lab::load(&source)?;
+            commands::archive::handle_archive(args, &loaded_lab, cli.target.as_deref())
+        }
+        Commands::Export(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let loaded_lab = lab::load(&source)?;
+            commands::export::handle_export(args, &loaded_lab, cli.target.as_deref())
+        }
+        Commands::Diff(args) => commands::diff::handle_diff(args, &cli.lab),
+        Commands::Stats(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let loaded_lab = lab::load(&source)?;
+            commands::stats::handle_stats(args, &loaded_lab, cli.target.as_deref())
         }
         Commands::TraceParams(args) => {
             let source = LabSource::from_path(&cli.lab);
             let loaded_lab = lab::load(&source)?;
             commands::trace::handle_trace_params(args, &loaded_lab)
         }
+        Commands::DebugRun(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let loaded_lab = lab::load(&source)?;
+            commands::debug_run::handle_debug_run(
+                args,
+                &loaded_lab,
+                cli.target.as_deref(),
+                cli.resources.as_deref(),
+            )
+        }
         Commands::Log(args) => {
             let source = LabSource::from_path(&cli.lab);
             let config = config::load_config()?;
@@ -66,6 +225,124 @@ pub fn run(cli: Cli) -> Result<(), CliError> {
             };
             commands::log::handle_log(args, &context)
         }
+        Commands::Cancel(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let config = config::load_config()?;
+            let client = create_client(&config, &source)?;
+            let target_name = cli
+                .target
+                .as_deref()
+                .or(config.submission_target.as_deref())
+                .unwrap_or(targets::LOCAL);
+            let context = AppContext {
+                source: &source,
+                client: &client,
+                submission_target: target_name,
+            };
+            commands::cancel::handle_cancel(args, &context)
+        }
+        Commands::Clean(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let config = config::load_config()?;
+            let client = create_client(&config, &source)?;
+            let target_name = cli
+                .target
+                .as_deref()
+                .or(config.submission_target.as_deref())
+                .unwrap_or(targets::LOCAL);
+            let context = AppContext {
+                source: &source,
+                client: &client,
+                submission_target: target_name,
+            };
+            commands::clean::handle_clean(args, &context, Verbosity::from(cli.verbose))
+        }
+        Commands::Fetch(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let config = config::load_config()?;
+            let client = create_client(&config, &source)?;
+            let target_name = cli
+                .target
+                .as_deref()
+                .or(config.submission_target.as_deref())
+                .unwrap_or(targets::LOCAL);
+            let context = AppContext {
+                source: &source,
+                client: &client,
+                submission_target: target_name,
+            };
+            commands::fetch::handle_fetch(args, &context)
+        }
+        Commands::Prune(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let config = config::load_config()?;
+            let client = create_client(&config, &source)?;
+            let target_name = cli
+                .target
+                .as_deref()
+                .or(config.submission_target.as_deref())
+                .unwrap_or(targets::LOCAL);
+            let context = AppContext {
+                source: &source,
+                client: &client,
+                submission_target: target_name,
+            };
+            commands::prune::handle_prune(args, &context, Verbosity::from(cli.verbose))
+        }
+        Commands::Watch(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let config = config::load_config()?;
+            let client = create_client(&config, &source)?;
+            let target_name = cli
+                .target
+                .as_deref()
+                .or(config.submission_target.as_deref())
+                .unwrap_or(targets::LOCAL);
+            let context = AppContext {
+                source: &source,
+                client: &client,
+                submission_target: target_name,
+            };
+            commands::watch::handle_watch(args, &context)
+        }
+        Commands::Wait(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let config = config::load_config()?;
+            let client = create_client(&config, &source)?;
+            let target_name = cli
+                .target
+                .as_deref()
+                .or(config.submission_target.as_deref())
+                .unwrap_or(targets::LOCAL);
+            let context = AppContext {
+                source: &source,
+                client: &client,
+                submission_target: target_name,
+            };
+            commands::wait::handle_wait(args, &context)
+        }
+        Commands::Why(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let config = config::load_config()?;
+            let client = create_client(&config, &source)?;
+            let target_name = cli
+                .target
+                .as_deref()
+                .or(config.submission_target.as_deref())
+                .unwrap_or(targets::LOCAL);
+            let context = AppContext {
+                source: &source,
+                client: &client,
+                submission_target: target_name,
+            };
+            commands::why::handle_why(args, &context)
+        }
+        Commands::Targets(args) => {
+            let source = LabSource::from_path(&cli.lab);
+            let config = config::load_config()?;
+            let client = create_client(&config, &source)?;
+            commands::targets::handle_targets(args, &client)
+        }
         Commands::Gc(args) => {
             let source = LabSource::from_path(&cli.lab);
             let config = config::load_config()?;
@@ -73,6 +350,7 @@ pub fn run(cli: Cli) -> Result<(), CliError> {
             let submission_target = args
                 .target
                 .clone()
+                .or(cli.target.clone())
                 .or(config.submission_target.clone())
                 .unwrap_or_else(|| targets::LOCAL.to_string());
             let context = AppContext {
@@ -82,72 +360,63 @@ pub fn run(cli: Cli) -> Result<(), CliError> {
             };
             commands::gc::handle_gc_dispatch(args, &context, &config, Verbosity::from(cli.verbose))
         }
-        Commands::Run(args) => {
+        Commands::Images(args) => {
             let source = LabSource::from_path(&cli.lab);
             let config = config::load_config()?;
-            let resources = config::load_resources(cli.resources.as_deref())?;
-
             let client = create_client(&config, &source)?;
-
-            let target_name = match cli.target.as_ref().or(config.submission_target.as_ref()) {
-                Some(name) => name.clone(),
-                None => return Err(CliError::Config(CoreError::NoSubmissionTarget)),
-            };
-
-            let target_config = config.targets.get(&target_name).ok_or_else(|| {
-                CliError::Config(CoreError::TargetNotConfigured {
-                    name: target_name.clone(),
-                })
-            })?;
-
-            let scheduler: SchedulerType = if let Some(s) = cli.scheduler {
-                s
-            } else {
-                target_config
-                    .default_scheduler
-                    .or(config.default_scheduler)
-                    .unwrap_or(SchedulerType::Slurm)
-            };
-
-            let num_jobs = if scheduler == SchedulerType::Local {
-                Some(
-                    args.jobs
-                        .or_else(|| {
-                            target_config
-                                .local
-                                .as_ref()
-                                .and_then(|c| c.local_concurrency)
-                        })
-                        .unwrap_or_else(num_cpus::get),
-                )
-            } else {
-                None
-            };
-
-            let artifact_store = args
-                .artifact_store
-                .map(repx_core::model::ArtifactStore::from)
-                .or(target_config.artifact_store)
-                .unwrap_or_default();
-
+            let submission_target = cli
+                .target
+                .clone()
+                .or(config.submission_target.clone())
+                .unwrap_or_else(|| targets::LOCAL.to_string());
             let context = AppContext {
                 source: &source,
                 client: &client,
-                submission_target: &target_name,
+                submission_target: &submission_target,
             };
-
-            commands::run::handle_run(
+            commands::images::handle_images(args, &context, Verbosity::from(cli.verbose))
+        }
+        Commands::Run(args) => handle_submission(
+            &cli.lab,
+            cli.resources.as_deref(),
+            cli.target.as_deref(),
+            cli.scheduler,
+            Verbosity::from(cli.verbose),
+            args,
+            SubmitMode::Run,
+        ),
+        Commands::Retry(args) => handle_submission(
+            &cli.lab,
+            cli.resources.as_deref(),
+            cli.target.as_deref(),
+            cli.scheduler,
+            Verbosity::from(cli.verbose),
+            args,
+            SubmitMode::Retry,
+        ),
+        Commands::Resubmit(args) => {
+            if !args.has_overrides() {
+                return Err(CliError::Config(CoreError::MissingArgument {
+                    argument: "--mem, --time, --partition or --cpus-per-task".to_string(),
+                    context: "Nothing to override; use 'repx retry' to re-run unchanged"
+                        .to_string(),
+                }));
+            }
+            let (args, overrides) = args.into_parts();
+            handle_submission(
+                &cli.lab,
+                cli.resources.as_deref(),
+                cli.target.as_deref(),
+                cli.scheduler,
+                Verbosity::from(cli.verbose),
                 args,
-                &context,
-                resources,
-                commands::run::RunConfig {
-                    target_name: target_name.clone(),
-                    scheduler,
-                    num_jobs,
-                    verbose: Verbosity::from(cli.verbose),
-                    artifact_store,
-                },
+                SubmitMode::Resubmit(Box::new(overrides)),
             )
         }
+        Commands::Status(args) => {
+            let config = config::load_config()?;
+            commands::status::handle_status(args, &cli.lab, cli.target.as_deref(), &config)
+        }
+        Commands::Workspace(args) => commands::workspace::handle_workspace(args, cli.target),
     }
 }
@@ -258,6 +258,60 @@ fn test_show_commands() {
         .stdout(predicates::str::contains("/out"));
 }
 
+#[test]
+fn test_structured_list_and_show_output() {
+    let harness = TestHarness::new();
+
+    harness
+        .cmd()
+        .arg("run")
+        .arg("simulation-run")
+        .assert()
+        .success();
+
+    let job_id = harness.job_id_by_name("stage-A-producer");
+
+    let output = harness
+        .cmd()
+        .args(["list", "jobs", "simulation-run", "--stage", "stage-A"])
+        .args(["--format", "json"])
+        .output()
+        .expect("list jobs must run");
+    assert!(output.status.success());
+    let records: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("list output must be JSON");
+    let records = records.as_array().expect("list output must be an array");
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["job_id"], job_id.as_str());
+    assert_eq!(records[0]["status"], "succeeded");
+    assert_eq!(records[0]["runs"][0], "simulation-run");
+    assert!(records[0]["finished_at"].is_string());
+    assert!(records[0]["output_path"]
+        .as_str()
+        .is_some_and(|path| path.ends_with("/out")));
+
+    harness
+        .cmd()
+        .args(["show", "job", &job_id, "--format", "csv"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "job_id,runs,stage,status,started_at,finished_at,elapsed_secs,output_path",
+        ))
+        .stdout(predicates::str::contains(format!(
+            "{},simulation-run,stage-A-producer,succeeded",
+            job_id
+        )));
+
+    harness
+        .cmd()
+        .args(["show", "job", &job_id, "--format", "yaml"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(format!("job_id: {}", job_id)))
+        .stdout(predicates::str::contains("stage_type: simple"));
+}
+
 #[test]
 fn test_log_command_shows_stdout_by_default() {
     let harness = TestHarness::new();
@@ -224,7 +224,32 @@ fn test_gc_root_rotation_keeps_last_5() {
         mount_host_paths: false,
         local: None,
         slurm: None,
+        sge: None,
+        flux: None,
+        aws_batch: None,
+        container: None,
+        setup_commands: Vec::new(),
+        max_in_flight: None,
+        agent: false,
+        submission_windows: None,
         mount_paths: vec![],
+        network: None,
+        seccomp: None,
+        pre_run: None,
+        post_run: None,
+        image_cache_max_size: None,
+        heartbeat_interval_secs: None,
+        ssh_control_persist_secs: None,
+        sync_concurrency: None,
+        rsync: None,
+        content_addressed_store: false,
+        object_store: None,
+        retry: None,
+        failure_rules: Vec::new(),
+        structured_logs: false,
+        auto_prune_steps: false,
+        env: Default::default(),
+        env_passthrough: Vec::new(),
         artifact_store: None,
     };
     let config = Config {
@@ -434,7 +459,32 @@ fn make_client_and_target(
         mount_host_paths: false,
         local: None,
         slurm: None,
+        sge: None,
+        flux: None,
+        aws_batch: None,
+        container: None,
+        setup_commands: Vec::new(),
+        max_in_flight: None,
+        agent: false,
+        submission_windows: None,
         mount_paths: vec![],
+        network: None,
+        seccomp: None,
+        pre_run: None,
+        post_run: None,
+        image_cache_max_size: None,
+        heartbeat_interval_secs: None,
+        ssh_control_persist_secs: None,
+        sync_concurrency: None,
+        rsync: None,
+        content_addressed_store: false,
+        object_store: None,
+        retry: None,
+        failure_rules: Vec::new(),
+        structured_logs: false,
+        auto_prune_steps: false,
+        env: Default::default(),
+        env_passthrough: Vec::new(),
         artifact_store: None,
     };
     let config = Config {
@@ -790,6 +840,59 @@ fn test_gc_dry_run_prints_summary() {
     );
 }
 
+#[test]
+fn test_gc_dry_run_groups_reclaimable_space_by_lab() {
+    let harness = TestHarness::new();
+    let base_path = &harness.cache_dir;
+    let artifacts_dir = base_path.join("artifacts");
+    let outputs_dir = base_path.join("outputs");
+    let gcroots_dir = base_path.join("gcroots");
+
+    fs::create_dir_all(&outputs_dir).expect("creating outputs dir must succeed");
+    fs::create_dir_all(&gcroots_dir).expect("creating gcroots dir must succeed");
+    create_synthetic_lab(&artifacts_dir, "deadlab0000000001", &["job-dead-a"]);
+
+    let dead_output = outputs_dir.join("job-dead-a");
+    fs::create_dir_all(&dead_output).expect("creating dead output dir must succeed");
+    fs::write(dead_output.join("result.bin"), vec![0u8; 4096]).expect("writing file must succeed");
+    let orphan_output = outputs_dir.join("job-orphan-b");
+    fs::create_dir_all(&orphan_output).expect("creating orphan output dir must succeed");
+
+    let output = harness
+        .cmd()
+        .arg("internal-gc")
+        .arg("--base-path")
+        .arg(base_path)
+        .arg("--dry-run")
+        .output()
+        .expect("executing internal-gc must succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(
+        stdout.contains("LAB") && stdout.contains("AGE"),
+        "Dry-run stdout should contain the grouped table. Got: {}",
+        stdout
+    );
+    let lab_row = stdout
+        .lines()
+        .find(|line| line.starts_with("deadlab0000000001"))
+        .unwrap_or_else(|| panic!("Missing row for the dead lab. Got: {}", stdout));
+    assert!(
+        lab_row.contains("<1d"),
+        "Unexpected age bucket: {}",
+        lab_row
+    );
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.starts_with("(unattributed)")),
+        "Orphan output should be unattributed. Got: {}",
+        stdout
+    );
+    assert!(dead_output.exists(), "Dry-run must NOT delete dead outputs");
+}
+
 #[test]
 fn test_gc_prints_freed_summary() {
     let harness = TestHarness::new();
@@ -870,7 +973,32 @@ fn test_gc_remove_auto_roots() {
         mount_host_paths: false,
         local: None,
         slurm: None,
+        sge: None,
+        flux: None,
+        aws_batch: None,
+        container: None,
+        setup_commands: Vec::new(),
+        max_in_flight: None,
+        agent: false,
+        submission_windows: None,
         mount_paths: vec![],
+        network: None,
+        seccomp: None,
+        pre_run: None,
+        post_run: None,
+        image_cache_max_size: None,
+        heartbeat_interval_secs: None,
+        ssh_control_persist_secs: None,
+        sync_concurrency: None,
+        rsync: None,
+        content_addressed_store: false,
+        object_store: None,
+        retry: None,
+        failure_rules: Vec::new(),
+        structured_logs: false,
+        auto_prune_steps: false,
+        env: Default::default(),
+        env_passthrough: Vec::new(),
         artifact_store: None,
     };
     let config = Config {
@@ -1404,3 +1532,51 @@ fn test_gc_preserves_store_entries_for_live_labs() {
         dead_store_entry
     );
 }
+
+#[test]
+fn test_gc_collects_blobs_only_after_their_last_link() {
+    let harness = TestHarness::new();
+    let base_path = &harness.cache_dir;
+    let artifacts_dir = base_path.join("artifacts");
+    let gcroots_pinned = base_path.join("gcroots/pinned");
+
+    let manifest = create_synthetic_lab(&artifacts_dir, "labhash-cas", &["job-alpha"]);
+    fs::create_dir_all(&gcroots_pinned).expect("create pinned dir");
+    #[cfg(unix)]
+    symlink(&manifest, gcroots_pinned.join("pin-cas")).expect("symlink pinned root");
+
+    let live_blob = artifacts_dir.join("cas/aa/aaaa");
+    let dead_blob = artifacts_dir.join("cas/bb/bbbb");
+    for blob in [&live_blob, &dead_blob] {
+        fs::create_dir_all(blob.parent().expect("blob parent")).expect("create fan-out dir");
+        fs::write(blob, "content").expect("write blob");
+    }
+    fs::create_dir_all(artifacts_dir.join("jobs/job-dead")).expect("create dead job dir");
+    fs::hard_link(&live_blob, artifacts_dir.join("jobs/job-alpha/tool")).expect("link live");
+    fs::hard_link(&dead_blob, artifacts_dir.join("jobs/job-dead/tool")).expect("link dead");
+
+    harness
+        .cmd()
+        .arg("internal-gc")
+        .arg("--base-path")
+        .arg(base_path)
+        .assert()
+        .success();
+
+    assert!(
+        artifacts_dir.join("jobs/job-alpha/tool").exists(),
+        "Live job file must be preserved"
+    );
+    assert!(
+        live_blob.exists(),
+        "Blob linked from a live lab must survive"
+    );
+    assert!(
+        !artifacts_dir.join("jobs/job-dead").exists(),
+        "Dead job dir must be collected"
+    );
+    assert!(
+        !dead_blob.exists(),
+        "Blob whose last link was collected must be deleted"
+    );
+}
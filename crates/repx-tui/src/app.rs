@@ -1,9 +1,16 @@
 pub mod jobs;
+pub mod resubmit;
 pub mod targets;
 
-use crate::app::{jobs::JobsState, targets::TargetsState};
+use crate::app::{
+    jobs::JobsState,
+    resubmit::{ResubmitField, ResubmitForm},
+    targets::{TargetsState, ALL_TARGETS},
+};
 use crate::model::{JobStatus, StatusCounts, TuiExecutor, TuiRowItem, TuiScheduler, TuiTarget};
-use repx_client::{error::ClientError, Client, SubmitOptions};
+use repx_client::{
+    error::ClientError, targets::TargetHealth, CancelReport, Client, ClientEvent, SubmitOptions,
+};
 use repx_core::{
     config::Resources,
     constants::{dirs, logs},
@@ -14,7 +21,8 @@ use repx_core::{
 };
 use std::collections::{HashSet, VecDeque};
 use std::path::PathBuf;
-use std::sync::mpsc::{Receiver, Sender};
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
@@ -34,6 +42,8 @@ pub enum InputMode {
     SpaceMenu,
     GMenu,
     ZMenu,
+    Resubmit,
+    ConfirmCancel,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -77,7 +87,7 @@ pub enum LogPollerCommand {
     Watch(JobId),
     Stop,
 }
-type LogUpdate = (JobId, Result<Vec<String>, ClientError>);
+type LogUpdate = (JobId, Result<Vec<String>, ClientError>, Option<String>);
 pub enum SubmissionResult {
     Success {
         submitted_job_ids: HashSet<JobId>,
@@ -87,6 +97,10 @@ pub enum SubmissionResult {
         affected_job_ids: HashSet<JobId>,
         error: String,
     },
+    SyncProgress {
+        bytes_done: u64,
+        bytes_total: u64,
+    },
 }
 
 pub enum ExternalAction {
@@ -124,8 +138,16 @@ pub struct App {
     pub pending_action: Option<ExternalAction>,
     pub system_logs: VecDeque<String>,
     system_log_rx: Receiver<String>,
+    pub health_rx: Option<Receiver<(String, TargetHealth)>>,
     pending_context_job_id: Option<JobId>,
     pub is_pinned: bool,
+    pub sync_progress: Option<(u64, u64)>,
+    pub workspace_labs: Vec<(String, PathBuf)>,
+    pub workspace_name: Option<String>,
+    pub switch_lab: Option<PathBuf>,
+    pub show_dag: bool,
+    pub resubmit: Option<ResubmitForm>,
+    pub cancel_plan: Option<Vec<(JobId, CancelReport)>>,
 }
 
 impl App {
@@ -143,6 +165,7 @@ impl App {
         initial_active_target: String,
         active_target_ref: Arc<Mutex<String>>,
         active_scheduler_ref: Arc<Mutex<String>>,
+        aggregate_ref: Arc<AtomicBool>,
     ) -> Result<Self, ClientError> {
         tracing::info!("Initializing new App instance.");
         let lab = client.lab().clone();
@@ -166,15 +189,26 @@ impl App {
                         .collect();
                     available_executors.insert(TuiScheduler::Local, executors);
                 }
-                if let Some(conf) = &target_config.slurm {
-                    available_schedulers.push(TuiScheduler::Slurm);
-                    let executors: Vec<TuiExecutor> = conf
-                        .execution_types
-                        .iter()
-                        .map(|et| TuiExecutor::from(*et))
-                        .filter(|e| !is_native_lab || *e == TuiExecutor::Native)
-                        .collect();
-                    available_executors.insert(TuiScheduler::Slurm, executors);
+                for (scheduler, config) in [
+                    (TuiScheduler::Slurm, &target_config.slurm),
+                    (TuiScheduler::Sge, &target_config.sge),
+                    (TuiScheduler::Flux, &target_config.flux),
+                ] {
+                    if let Some(conf) = config {
+                        available_schedulers.push(scheduler);
+                        let executors: Vec<TuiExecutor> = conf
+                            .execution_types
+                            .iter()
+                            .map(|et| TuiExecutor::from(*et))
+                            .filter(|e| !is_native_lab || *e == TuiExecutor::Native)
+                            .collect();
+                        available_executors.insert(scheduler, executors);
+                    }
+                }
+
+                if target_config.aws_batch.is_some() {
+                    available_schedulers.push(TuiScheduler::AwsBatch);
+                    available_executors.insert(TuiScheduler::AwsBatch, vec![TuiExecutor::Native]);
                 }
 
                 available_schedulers.retain(|s| {
@@ -229,6 +263,7 @@ impl App {
                     available_executors,
                     selected_scheduler_idx,
                     selected_executor_idx,
+                    health: None,
                 }
             })
             .collect();
@@ -239,7 +274,12 @@ impl App {
             theme,
             lab,
             jobs_state: JobsState::new(),
-            targets_state: TargetsState::new(targets, active_target_ref, active_scheduler_ref),
+            targets_state: TargetsState::new(
+                targets,
+                active_target_ref,
+                active_scheduler_ref,
+                aggregate_ref,
+            ),
             status_history: VecDeque::new(),
             completion_rate_history: VecDeque::new(),
             last_completed_count: 0,
@@ -253,12 +293,20 @@ impl App {
             submission_rx,
             system_log_rx,
             system_logs: VecDeque::new(),
+            health_rx: None,
             is_loading: true,
             resources,
             focused_panel: PanelFocus::Jobs,
             pending_action: None,
             pending_context_job_id: None,
             is_pinned: false,
+            sync_progress: None,
+            workspace_labs: Vec::new(),
+            workspace_name: None,
+            switch_lab: None,
+            show_dag: false,
+            resubmit: None,
+            cancel_plan: None,
         };
 
         app.is_pinned = app.check_if_pinned();
@@ -284,7 +332,11 @@ impl App {
         while let Ok(update_result) = self.status_rx.try_recv() {
             match update_result {
                 Ok((target_name, job_statuses)) => {
-                    let active_target = self.targets_state.get_active_target_name();
+                    let active_target = if self.targets_state.is_aggregated() {
+                        ALL_TARGETS.to_string()
+                    } else {
+                        self.targets_state.get_active_target_name()
+                    };
                     if target_name != active_target {
                         tracing::info!(
                             "Ignoring status update from '{}' (active: '{}')",
@@ -313,7 +365,7 @@ impl App {
         }
     }
     pub fn check_for_log_updates(&mut self) {
-        while let Ok((job_id, log_result)) = self.log_result_rx.try_recv() {
+        while let Ok((job_id, log_result, progress)) = self.log_result_rx.try_recv() {
             if let Some(job) = self
                 .jobs_state
                 .jobs
@@ -321,6 +373,7 @@ impl App {
                 .find(|j| j.full_id == job_id)
             {
                 tracing::info!("Received log update for job '{}'", job_id);
+                job.progress = progress;
                 match log_result {
                     Ok(lines) => job.logs = lines,
                     Err(e) => job.logs = vec![format!("[Error fetching log: {}]", e)],
@@ -338,10 +391,34 @@ impl App {
         }
     }
 
+    pub fn check_for_health_updates(&mut self) {
+        let Some(health_rx) = &self.health_rx else {
+            return;
+        };
+        while let Ok((name, health)) = health_rx.try_recv() {
+            if let Some(target) = self
+                .targets_state
+                .items
+                .iter_mut()
+                .find(|target| target.name == name)
+            {
+                target.health = Some(health);
+            }
+        }
+    }
+
     pub fn check_for_submission_updates(&mut self) {
         while let Ok(result) = self.submission_rx.try_recv() {
             match result {
+                SubmissionResult::SyncProgress {
+                    bytes_done,
+                    bytes_total,
+                } => {
+                    self.sync_progress = Some((bytes_done, bytes_total));
+                    continue;
+                }
                 SubmissionResult::Success { submitted_job_ids } => {
+                    self.sync_progress = None;
                     tracing::info!(
                         "Received submission success for {} jobs.",
                         submitted_job_ids.len()
@@ -359,6 +436,7 @@ impl App {
                     affected_job_ids,
                     error,
                 } => {
+                    self.sync_progress = None;
                     tracing::info!(
                         "Received submission failure for '{}': {} (affected {} jobs)",
                         failed_run_or_job_id,
@@ -399,7 +477,11 @@ impl App {
                     counts.succeeded += 1;
                     current_completed_count += 1;
                 }
-                JobStatus::Failed | JobStatus::SubmitFailed => {
+                JobStatus::Failed
+                | JobStatus::TimedOut
+                | JobStatus::Cancelled
+                | JobStatus::Lost
+                | JobStatus::SubmitFailed => {
                     counts.failed += 1;
                     current_completed_count += 1;
                 }
@@ -524,6 +606,20 @@ impl App {
         self.jobs_state.reset_statuses();
         self.jobs_state.rebuild_display_list(&self.lab);
     }
+    pub fn toggle_aggregated_targets(&mut self) {
+        self.targets_state.toggle_aggregated();
+        tracing::info!(
+            "Aggregated target view {}",
+            if self.targets_state.is_aggregated() {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+        self.is_loading = true;
+        self.jobs_state.reset_statuses();
+        self.jobs_state.rebuild_display_list(&self.lab);
+    }
     pub fn set_focused_panel(&mut self, panel: PanelFocus) {
         self.focused_panel = panel;
     }
@@ -543,6 +639,27 @@ impl App {
         self.should_quit = true;
     }
 
+    pub fn switch_to_next_workspace_lab(&mut self) {
+        let current = self.workspace_name.as_ref().and_then(|current| {
+            self.workspace_labs
+                .iter()
+                .position(|(name, _)| name == current)
+        });
+        let next = match current {
+            Some(idx) => (idx + 1) % self.workspace_labs.len(),
+            None => 0,
+        };
+        let Some((name, path)) = self.workspace_labs.get(next) else {
+            return;
+        };
+        if current == Some(next) {
+            return;
+        }
+        tracing::info!("Switching to workspace lab '{}'", name);
+        self.switch_lab = Some(path.clone());
+        self.should_quit = true;
+    }
+
     pub fn toggle_reverse(&mut self) {
         self.jobs_state.is_reversed = !self.jobs_state.is_reversed;
         self.jobs_state.rebuild_display_list(&self.lab);
@@ -974,56 +1091,91 @@ impl App {
         };
 
         let scheduler_type: SchedulerType = scheduler.parse().unwrap_or_default();
+        let options = SubmitOptions {
+            execution_type: Some(execution_type),
+            resources: self.resources.clone(),
+            num_jobs,
+            mem_override: None,
+            event_sender: None,
+            continue_on_failure: false,
+            verbose: repx_core::logging::Verbosity::default(),
+            cancel_flag: None,
+            artifact_store: repx_core::model::ArtifactStore::default(),
+            ignore_submission_windows: false,
+            retry_failed: false,
+        };
+        self.spawn_submission(
+            ids_to_run,
+            all_jobs_to_submit,
+            target_name,
+            scheduler_type,
+            options,
+        );
+
+        self.clear_selection();
+        self.rebuild_display_list();
+    }
 
+    fn spawn_submission(
+        &self,
+        run_specs: Vec<String>,
+        jobs: HashSet<JobId>,
+        target_name: String,
+        scheduler_type: SchedulerType,
+        mut options: SubmitOptions,
+    ) {
         let client_clone = self.client.clone();
         let submission_tx_clone = self.submission_tx.clone();
-        let resources_clone = self.resources.clone();
-        let run_specs_to_submit = ids_to_run;
         thread::spawn(move || {
             tracing::info!(
                 "Submitting batch run for final jobs {:?} to target '{}'",
-                &run_specs_to_submit,
+                &run_specs,
                 &target_name
             );
 
-            let options = SubmitOptions {
-                execution_type: Some(execution_type),
-                resources: resources_clone,
-                num_jobs,
-                mem_override: None,
-                event_sender: None,
-                continue_on_failure: false,
-                verbose: repx_core::logging::Verbosity::default(),
-                cancel_flag: None,
-                artifact_store: repx_core::model::ArtifactStore::default(),
-            };
+            let (event_tx, event_rx) = mpsc::channel();
+            let progress_tx = submission_tx_clone.clone();
+            let forwarder = thread::spawn(move || {
+                for event in event_rx {
+                    if let ClientEvent::SyncProgress {
+                        bytes_done,
+                        bytes_total,
+                    } = event
+                    {
+                        let _ = progress_tx.send(SubmissionResult::SyncProgress {
+                            bytes_done,
+                            bytes_total,
+                        });
+                    }
+                }
+            });
+            options.event_sender = Some(event_tx);
 
-            match client_clone.submit_batch_run(
-                run_specs_to_submit.clone(),
+            let result = client_clone.submit_batch_run(
+                run_specs.clone(),
                 &target_name,
                 scheduler_type,
                 options,
-            ) {
+            );
+            let _ = forwarder.join();
+            match result {
                 Ok(msg) => {
                     tracing::info!("Batch submission successful: {}", msg);
                     let _ = submission_tx_clone.send(SubmissionResult::Success {
-                        submitted_job_ids: all_jobs_to_submit,
+                        submitted_job_ids: jobs,
                     });
                 }
                 Err(e) => {
                     let err_string = e.to_string();
                     tracing::warn!("Batch submission failed: {}", err_string);
                     let _ = submission_tx_clone.send(SubmissionResult::Failure {
-                        failed_run_or_job_id: run_specs_to_submit.join(", "),
-                        affected_job_ids: all_jobs_to_submit,
+                        failed_run_or_job_id: run_specs.join(", "),
+                        affected_job_ids: jobs,
                         error: err_string,
                     });
                 }
             }
         });
-
-        self.clear_selection();
-        self.rebuild_display_list();
     }
 
     pub fn toggle_tree_view(&mut self) {
@@ -1031,19 +1183,20 @@ impl App {
         self.jobs_state.rebuild_display_list(&self.lab);
     }
 
-    pub fn cancel_selected(&mut self) {
-        let raw_ids = self.get_target_ids_for_action();
-        tracing::info!("'Cancel' action triggered for: {:?}", raw_ids);
+    pub fn toggle_dag(&mut self) {
+        self.show_dag = !self.show_dag;
+    }
 
-        let mut job_ids_to_cancel: Vec<JobId> = Vec::new();
-        for id_str in &raw_ids {
+    fn resolve_selected_job_ids(&self, raw_ids: &[String]) -> Vec<JobId> {
+        let mut job_ids: Vec<JobId> = Vec::new();
+        for id_str in raw_ids {
             match repx_core::resolver::resolve_run_spec(&self.lab, id_str) {
                 Ok(run_ids) => {
                     for run_id in &run_ids {
                         if let Some(run) = self.lab.runs.get(run_id) {
-                            job_ids_to_cancel.extend(run.jobs.iter().cloned());
+                            job_ids.extend(run.jobs.iter().cloned());
                         } else {
-                            job_ids_to_cancel.push(JobId::from(run_id.to_string()));
+                            job_ids.push(JobId::from(run_id.to_string()));
                         }
                     }
                 }
@@ -1052,14 +1205,238 @@ impl App {
                 }
             }
         }
+        job_ids
+    }
+
+    pub fn cancel_selected(&mut self) {
+        let raw_ids = self.get_target_ids_for_action();
+        tracing::info!("'Cancel' action triggered for: {:?}", raw_ids);
 
-        for job_id in job_ids_to_cancel {
-            tracing::info!("Sending cancel request for job '{}'", job_id);
-            let _ = self.client.cancel_job(job_id);
+        let mut seen = HashSet::new();
+        let mut jobs = Vec::new();
+        for job_id in self.resolve_selected_job_ids(&raw_ids) {
+            if !seen.insert(job_id.clone()) {
+                continue;
+            }
+            let finished = self
+                .jobs_state
+                .job_index_map
+                .get(&job_id)
+                .and_then(|i| self.jobs_state.jobs.get(*i))
+                .is_some_and(|job| {
+                    matches!(
+                        job.status,
+                        JobStatus::Succeeded
+                            | JobStatus::Failed
+                            | JobStatus::TimedOut
+                            | JobStatus::Cancelled
+                            | JobStatus::Lost
+                    )
+                });
+            if finished {
+                continue;
+            }
+            match self.client.cancel_preview(&job_id) {
+                Ok(report) if !report.is_empty() => jobs.push((job_id, report)),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to look up '{}' for cancelling: {}", job_id, e),
+            }
+        }
+
+        if jobs.is_empty() {
+            self.system_logs
+                .push_back("Cancel: nothing is running or queued for the selection".to_string());
+            return;
         }
+        self.cancel_plan = Some(jobs);
+        self.input_mode = InputMode::ConfirmCancel;
+    }
+
+    pub fn close_cancel(&mut self) {
+        self.cancel_plan = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn confirm_cancel(&mut self) {
+        let Some(plan) = self.cancel_plan.take() else {
+            return;
+        };
+        self.close_cancel();
+        self.system_logs
+            .push_back(format!("Cancelling {} job(s)...", plan.len()));
+
+        let client = self.client.clone();
+        thread::spawn(move || {
+            let total = plan.len();
+            let mut cancelled = 0;
+            for (job_id, _) in plan {
+                match client.cancel_job(job_id.clone()) {
+                    Ok(report) if report.is_empty() => {
+                        tracing::info!("'{}' had nothing left to cancel", job_id);
+                    }
+                    Ok(report) => {
+                        cancelled += 1;
+                        tracing::info!("Cancelled '{}' ({})", job_id, report.describe_handles());
+                    }
+                    Err(e) => tracing::warn!("Failed to cancel '{}': {}", job_id, e),
+                }
+            }
+            tracing::info!("Cancelled {} of {} job(s)", cancelled, total);
+        });
         self.clear_selection();
     }
 
+    pub fn open_resubmit_selected(&mut self) {
+        let raw_ids = self.get_target_ids_for_action();
+        let mut seen = HashSet::new();
+        let job_ids: Vec<JobId> = self
+            .resolve_selected_job_ids(&raw_ids)
+            .into_iter()
+            .filter(|job_id| seen.insert(job_id.clone()))
+            .filter(|job_id| {
+                self.jobs_state
+                    .job_index_map
+                    .get(job_id)
+                    .and_then(|i| self.jobs_state.jobs.get(*i))
+                    .is_some_and(|job| {
+                        matches!(
+                            job.status,
+                            JobStatus::Failed
+                                | JobStatus::TimedOut
+                                | JobStatus::Cancelled
+                                | JobStatus::Lost
+                                | JobStatus::SubmitFailed
+                        )
+                    })
+            })
+            .collect();
+        let Some(first) = job_ids.first() else {
+            self.system_logs
+                .push_back("Resubmit: no failed jobs in the selection".to_string());
+            return;
+        };
+
+        let target_name = self.targets_state.get_active_target_name();
+        let Some(tui_target) = self
+            .targets_state
+            .items
+            .iter()
+            .find(|t| t.name == target_name)
+        else {
+            self.system_logs
+                .push_back(format!("Error: target '{}' not found", target_name));
+            return;
+        };
+        let executors = tui_target
+            .available_executors
+            .get(&tui_target.get_selected_scheduler())
+            .cloned()
+            .unwrap_or_default();
+        let executor_idx = executors
+            .iter()
+            .position(|e| *e == tui_target.get_selected_executor())
+            .unwrap_or(0);
+
+        let hints = self
+            .lab
+            .jobs
+            .get(first)
+            .and_then(|j| j.resource_hints.as_ref());
+        let current =
+            repx_client::resources::resolve_for_job(first, &target_name, &self.resources, hints);
+        self.resubmit = Some(ResubmitForm {
+            job_ids,
+            mem: current.mem.map(|m| m.to_string()).unwrap_or_default(),
+            time: current.time.map(|t| t.to_string()).unwrap_or_default(),
+            partition: current.partition.unwrap_or_default(),
+            executors,
+            executor_idx,
+            focused: ResubmitField::Memory,
+            error: None,
+        });
+        self.input_mode = InputMode::Resubmit;
+    }
+
+    pub fn close_resubmit(&mut self) {
+        self.resubmit = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn confirm_resubmit(&mut self) {
+        let Some(form) = self.resubmit.as_mut() else {
+            return;
+        };
+        let overrides = match form.overrides() {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                form.error = Some(e);
+                return;
+            }
+        };
+        let Some(executor) = form.executor() else {
+            form.error = Some("the active scheduler has no runtimes".to_string());
+            return;
+        };
+        let job_ids = form.job_ids.clone();
+        self.close_resubmit();
+
+        let target_name = self.targets_state.get_active_target_name();
+        let scheduler = self
+            .targets_state
+            .items
+            .iter()
+            .find(|t| t.name == target_name)
+            .map_or(TuiScheduler::Local, |t| t.get_selected_scheduler());
+        let num_jobs = (scheduler == TuiScheduler::Local).then(|| {
+            self.client
+                .config()
+                .targets
+                .get(&target_name)
+                .and_then(|tc| tc.local.as_ref())
+                .and_then(|c| c.local_concurrency)
+                .unwrap_or_else(num_cpus::get)
+        });
+        let resources = repx_client::resources::with_job_overrides(
+            self.resources.clone(),
+            &job_ids,
+            &target_name,
+            &overrides,
+        );
+
+        let jobs: HashSet<JobId> = job_ids.iter().cloned().collect();
+        for job in self.jobs_state.jobs.iter_mut() {
+            if jobs.contains(&job.full_id) {
+                job.status = JobStatus::Submitting;
+            }
+        }
+        self.system_logs.push_back(format!(
+            "Resubmitting {} job(s) to '{}' with {}",
+            job_ids.len(),
+            target_name,
+            executor.as_str()
+        ));
+
+        let options = SubmitOptions {
+            execution_type: Some(executor.as_str().to_string()),
+            resources: Some(resources),
+            num_jobs,
+            mem_override: None,
+            event_sender: None,
+            continue_on_failure: false,
+            verbose: repx_core::logging::Verbosity::default(),
+            cancel_flag: None,
+            artifact_store: repx_core::model::ArtifactStore::default(),
+            ignore_submission_windows: false,
+            retry_failed: true,
+        };
+        let run_specs = job_ids.iter().map(|id| id.to_string()).collect();
+        let scheduler_type: SchedulerType = scheduler.as_str().parse().unwrap_or_default();
+        self.spawn_submission(run_specs, jobs, target_name, scheduler_type, options);
+
+        self.clear_selection();
+        self.rebuild_display_list();
+    }
+
     pub fn pin_toggle(&mut self) {
         let target_name = self.targets_state.get_active_target_name();
         let target = match self.client.get_target(&target_name) {
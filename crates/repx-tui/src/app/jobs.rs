@@ -13,6 +13,7 @@ enum FilterType {
     Run,
     Params,
     Status,
+    Failure,
 }
 
 struct ParsedFilter {
@@ -91,9 +92,12 @@ impl JobsState {
                     params: job_def.params.clone(),
                     params_str: crate::tree_view::format_params_single_line(&job_def.params),
                     status: JobStatus::Unknown,
+                    failure_class: None,
+                    failure_reason: None,
                     context_depends_on: "-".to_string(),
                     context_dependents: "-".to_string(),
                     logs: vec!["Awaiting update...".to_string()],
+                    progress: None,
                     id_lower: id_str.to_lowercase(),
                     name_lower: name_str.to_lowercase(),
                     run_lower: run_str.to_lowercase(),
@@ -125,6 +129,8 @@ impl JobsState {
     pub fn reset_statuses(&mut self) {
         for job in self.jobs.iter_mut() {
             job.status = JobStatus::Unknown;
+            job.failure_class = None;
+            job.failure_reason = None;
         }
     }
 
@@ -150,6 +156,9 @@ impl JobsState {
             let new_status = match full_job_statuses.get(&job.full_id) {
                 Some(engine::JobStatus::Succeeded { .. }) => JobStatus::Succeeded,
                 Some(engine::JobStatus::Failed { .. }) => JobStatus::Failed,
+                Some(engine::JobStatus::TimedOut { .. }) => JobStatus::TimedOut,
+                Some(engine::JobStatus::Cancelled { .. }) => JobStatus::Cancelled,
+                Some(engine::JobStatus::Lost { .. }) => JobStatus::Lost,
                 Some(engine::JobStatus::Pending) => JobStatus::Pending,
                 Some(engine::JobStatus::Queued) => JobStatus::Queued,
                 Some(engine::JobStatus::Running) => JobStatus::Running,
@@ -157,6 +166,10 @@ impl JobsState {
                 None => JobStatus::Unknown,
             };
             job.status = new_status;
+            (job.failure_class, job.failure_reason) = match full_job_statuses.get(&job.full_id) {
+                Some(engine::JobStatus::Failed { class, reason, .. }) => (*class, reason.clone()),
+                _ => (None, None),
+            };
         }
     }
     pub fn next(&mut self) {
@@ -779,6 +792,8 @@ impl JobsState {
                 Some(FilterType::Params)
             } else if self.matches_column(col_prefix, &["status"]) {
                 Some(FilterType::Status)
+            } else if self.matches_column(col_prefix, &["failure"]) {
+                Some(FilterType::Failure)
             } else {
                 None
             };
@@ -800,7 +815,15 @@ impl JobsState {
     fn job_matches(&self, job: &TuiJob, filters: &[ParsedFilter]) -> bool {
         let status_match = match self.status_filter {
             StatusFilter::All => true,
-            StatusFilter::Failed => job.status == JobStatus::Failed,
+            StatusFilter::Failed => {
+                matches!(
+                    job.status,
+                    JobStatus::Failed
+                        | JobStatus::TimedOut
+                        | JobStatus::Cancelled
+                        | JobStatus::Lost
+                )
+            }
             StatusFilter::Running => job.status == JobStatus::Running,
             StatusFilter::Pending => job.status == JobStatus::Pending,
             StatusFilter::Succeeded => job.status == JobStatus::Succeeded,
@@ -823,6 +846,14 @@ impl JobsState {
                 FilterType::Run => job.run_lower.contains(&filter.term),
                 FilterType::Params => self.params_match(&job.params, &filter.term),
                 FilterType::Status => job.status.as_str().to_lowercase().contains(&filter.term),
+                FilterType::Failure => {
+                    job.failure_class
+                        .is_some_and(|class| class.as_str().contains(&filter.term))
+                        || job
+                            .failure_reason
+                            .as_ref()
+                            .is_some_and(|reason| reason.to_lowercase().contains(&filter.term))
+                }
             };
             if !matches {
                 return false;
@@ -0,0 +1,186 @@
+use crate::model::TuiExecutor;
+use repx_core::config::ResourceRule;
+use repx_core::model::{JobId, Memory, SlurmTime};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResubmitField {
+    Memory,
+    Time,
+    Partition,
+    Runtime,
+}
+
+impl ResubmitField {
+    pub const ALL: [ResubmitField; 4] = [
+        ResubmitField::Memory,
+        ResubmitField::Time,
+        ResubmitField::Partition,
+        ResubmitField::Runtime,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResubmitField::Memory => "Memory",
+            ResubmitField::Time => "Time",
+            ResubmitField::Partition => "Partition",
+            ResubmitField::Runtime => "Runtime",
+        }
+    }
+}
+
+pub struct ResubmitForm {
+    pub job_ids: Vec<JobId>,
+    pub mem: String,
+    pub time: String,
+    pub partition: String,
+    pub executors: Vec<TuiExecutor>,
+    pub executor_idx: usize,
+    pub focused: ResubmitField,
+    pub error: Option<String>,
+}
+
+impl ResubmitForm {
+    pub fn value(&self, field: ResubmitField) -> &str {
+        match field {
+            ResubmitField::Memory => &self.mem,
+            ResubmitField::Time => &self.time,
+            ResubmitField::Partition => &self.partition,
+            ResubmitField::Runtime => self.executor().map_or("-", |e| e.as_str()),
+        }
+    }
+
+    fn focused_text(&mut self) -> Option<&mut String> {
+        match self.focused {
+            ResubmitField::Memory => Some(&mut self.mem),
+            ResubmitField::Time => Some(&mut self.time),
+            ResubmitField::Partition => Some(&mut self.partition),
+            ResubmitField::Runtime => None,
+        }
+    }
+
+    pub fn executor(&self) -> Option<TuiExecutor> {
+        self.executors.get(self.executor_idx).copied()
+    }
+
+    pub fn next_field(&mut self) {
+        let i = ResubmitField::ALL
+            .iter()
+            .position(|f| *f == self.focused)
+            .unwrap_or(0);
+        self.focused = ResubmitField::ALL[(i + 1) % ResubmitField::ALL.len()];
+    }
+
+    pub fn previous_field(&mut self) {
+        let i = ResubmitField::ALL
+            .iter()
+            .position(|f| *f == self.focused)
+            .unwrap_or(0);
+        let len = ResubmitField::ALL.len();
+        self.focused = ResubmitField::ALL[(i + len - 1) % len];
+    }
+
+    pub fn insert(&mut self, c: char) {
+        if let Some(text) = self.focused_text() {
+            text.push(c);
+        }
+        self.error = None;
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(text) = self.focused_text() {
+            text.pop();
+        }
+        self.error = None;
+    }
+
+    pub fn cycle_executor(&mut self, forward: bool) {
+        let len = self.executors.len();
+        if self.focused != ResubmitField::Runtime || len == 0 {
+            return;
+        }
+        self.executor_idx = if forward {
+            (self.executor_idx + 1) % len
+        } else {
+            (self.executor_idx + len - 1) % len
+        };
+    }
+
+    pub fn overrides(&self) -> Result<ResourceRule, String> {
+        let mem = match self.mem.trim() {
+            "" => None,
+            mem => {
+                let mem = Memory::from(mem);
+                if mem.to_bytes().is_none() {
+                    return Err(format!("expected a size like 512M or 64G, got '{}'", mem));
+                }
+                Some(mem)
+            }
+        };
+        let time = match self.time.trim() {
+            "" => None,
+            time => {
+                let time = SlurmTime::from(time);
+                if time.to_seconds().is_none() {
+                    return Err(format!(
+                        "expected a time like 12:00:00 or 1-00:00:00, got '{}'",
+                        time
+                    ));
+                }
+                Some(time)
+            }
+        };
+        let partition = Some(self.partition.trim())
+            .filter(|p| !p.is_empty())
+            .map(str::to_string);
+        Ok(ResourceRule {
+            mem,
+            time,
+            partition,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form() -> ResubmitForm {
+        ResubmitForm {
+            job_ids: vec![JobId::from("aaa-train-1.0")],
+            mem: "8G".to_string(),
+            time: String::new(),
+            partition: "gpu".to_string(),
+            executors: vec![TuiExecutor::Native, TuiExecutor::Bwrap],
+            executor_idx: 0,
+            focused: ResubmitField::Memory,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_resubmit_form_overrides() {
+        let mut form = form();
+        form.backspace();
+        form.backspace();
+        for c in "64G".chars() {
+            form.insert(c);
+        }
+        let overrides = form.overrides().expect("valid overrides");
+        assert_eq!(overrides.mem, Some(Memory::from("64G")));
+        assert_eq!(overrides.time, None, "an empty field is not overridden");
+        assert_eq!(overrides.partition.as_deref(), Some("gpu"));
+
+        form.next_field();
+        form.insert('x');
+        assert!(form.overrides().is_err());
+
+        form.previous_field();
+        form.previous_field();
+        assert_eq!(form.focused, ResubmitField::Runtime);
+        form.insert('x');
+        form.cycle_executor(true);
+        assert_eq!(form.executor(), Some(TuiExecutor::Bwrap));
+        assert_eq!(form.value(ResubmitField::Runtime), "bwrap");
+    }
+}
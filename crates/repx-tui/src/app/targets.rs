@@ -1,7 +1,10 @@
 use crate::model::{TargetState as StateEnum, TuiTarget};
 use ratatui::widgets::TableState;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
+pub const ALL_TARGETS: &str = "all targets";
+
 pub struct TargetsState {
     pub items: Vec<TuiTarget>,
     pub table_state: TableState,
@@ -9,6 +12,7 @@ pub struct TargetsState {
     pub is_editing_cell: bool,
     pub active_target_ref: Arc<Mutex<String>>,
     pub active_scheduler_ref: Arc<Mutex<String>>,
+    pub aggregate_ref: Arc<AtomicBool>,
 }
 
 impl TargetsState {
@@ -16,6 +20,7 @@ impl TargetsState {
         items: Vec<TuiTarget>,
         active_target_ref: Arc<Mutex<String>>,
         active_scheduler_ref: Arc<Mutex<String>>,
+        aggregate_ref: Arc<AtomicBool>,
     ) -> Self {
         let mut state = Self {
             items,
@@ -24,6 +29,7 @@ impl TargetsState {
             is_editing_cell: false,
             active_target_ref,
             active_scheduler_ref,
+            aggregate_ref,
         };
         if !state.items.is_empty() {
             state.table_state.select(Some(0));
@@ -167,6 +173,14 @@ impl TargetsState {
         }
     }
 
+    pub fn is_aggregated(&self) -> bool {
+        self.aggregate_ref.load(Ordering::Relaxed)
+    }
+
+    pub fn toggle_aggregated(&mut self) {
+        self.aggregate_ref.fetch_xor(true, Ordering::Relaxed);
+    }
+
     pub fn get_active_target_name(&self) -> String {
         self.active_target_ref
             .lock()
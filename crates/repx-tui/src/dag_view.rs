@@ -0,0 +1,281 @@
+use crate::app::jobs::JobsState;
+use crate::model::JobStatus;
+use repx_core::model::{JobId, Lab};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+#[derive(Debug, Clone)]
+pub struct DagNode {
+    pub job_id: JobId,
+    pub label: String,
+    pub status: JobStatus,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DagLayer {
+    pub nodes: Vec<DagNode>,
+    pub hidden: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Neighborhood {
+    pub layers: Vec<DagLayer>,
+    pub focus: usize,
+    pub edges: Vec<(usize, usize, usize)>,
+}
+
+fn dependencies(lab: &Lab, job_id: &JobId) -> BTreeSet<JobId> {
+    lab.jobs
+        .get(job_id)
+        .map(|job| {
+            job.executables
+                .values()
+                .flat_map(|e| e.inputs.iter())
+                .filter_map(|m| m.job_id.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn dependents(jobs_state: &JobsState, job_id: &JobId) -> BTreeSet<JobId> {
+    jobs_state
+        .dependents_cache
+        .get(job_id)
+        .map(|deps| deps.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn node(jobs_state: &JobsState, job_id: &JobId) -> DagNode {
+    let job = jobs_state
+        .job_index_map
+        .get(job_id)
+        .and_then(|i| jobs_state.jobs.get(*i));
+    let label = match job {
+        Some(job) if !job.name.is_empty() => job.name.clone(),
+        Some(job) => job.id.clone(),
+        None => job_id.short_id(),
+    };
+    DagNode {
+        job_id: job_id.clone(),
+        label,
+        status: job.map_or(JobStatus::Unknown, |job| job.status),
+    }
+}
+
+fn walk(
+    jobs_state: &JobsState,
+    start: &JobId,
+    depth: usize,
+    max_per_layer: usize,
+    next: impl Fn(&JobId) -> BTreeSet<JobId>,
+) -> Vec<DagLayer> {
+    let mut seen: HashSet<JobId> = HashSet::from([start.clone()]);
+    let mut previous = vec![start.clone()];
+    let mut layers = Vec::new();
+    for _ in 0..depth {
+        let mut positions: HashMap<JobId, Vec<usize>> = HashMap::new();
+        for (i, job_id) in previous.iter().enumerate() {
+            for neighbour in next(job_id) {
+                if !seen.contains(&neighbour) {
+                    positions.entry(neighbour).or_default().push(i);
+                }
+            }
+        }
+        if positions.is_empty() {
+            break;
+        }
+        let mut ordered: Vec<(f64, DagNode)> = positions
+            .into_iter()
+            .map(|(job_id, at)| {
+                let centre = at.iter().sum::<usize>() as f64 / at.len() as f64;
+                (centre, node(jobs_state, &job_id))
+            })
+            .collect();
+        ordered.sort_by(|(a, x), (b, y)| a.total_cmp(b).then_with(|| x.label.cmp(&y.label)));
+
+        let hidden = ordered.len().saturating_sub(max_per_layer);
+        let nodes: Vec<DagNode> = ordered
+            .into_iter()
+            .map(|(_, node)| node)
+            .take(max_per_layer)
+            .collect();
+        seen.extend(nodes.iter().map(|node| node.job_id.clone()));
+        previous = nodes.iter().map(|node| node.job_id.clone()).collect();
+        layers.push(DagLayer { nodes, hidden });
+    }
+    layers
+}
+
+pub fn neighborhood(
+    lab: &Lab,
+    jobs_state: &JobsState,
+    job_id: &JobId,
+    depth: usize,
+    max_per_layer: usize,
+) -> Neighborhood {
+    let max_per_layer = max_per_layer.max(1);
+    let mut upstream = walk(jobs_state, job_id, depth, max_per_layer, |id| {
+        dependencies(lab, id)
+    });
+    let downstream = walk(jobs_state, job_id, depth, max_per_layer, |id| {
+        dependents(jobs_state, id)
+    });
+
+    upstream.reverse();
+    let focus = upstream.len();
+    let mut layers = upstream;
+    layers.push(DagLayer {
+        nodes: vec![node(jobs_state, job_id)],
+        hidden: 0,
+    });
+    layers.extend(downstream);
+
+    let mut edges = Vec::new();
+    for layer in 0..layers.len().saturating_sub(1) {
+        let upper: HashMap<&JobId, usize> = layers[layer]
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (&node.job_id, i))
+            .collect();
+        for (lower, node) in layers[layer + 1].nodes.iter().enumerate() {
+            for dep in dependencies(lab, &node.job_id) {
+                if let Some(upper) = upper.get(&dep) {
+                    edges.push((layer, *upper, lower));
+                }
+            }
+        }
+    }
+
+    Neighborhood {
+        layers,
+        focus,
+        edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repx_core::model::{Executable, InputMapping, Job, Run, RunId, StageType};
+    use std::path::PathBuf;
+
+    fn job(name: &str, inputs: &[&str]) -> Job {
+        let inputs = inputs
+            .iter()
+            .map(|src| InputMapping {
+                job_id: Some(JobId::from(*src)),
+                source_output: None,
+                target_input: "x".to_string(),
+                source: None,
+                source_key: None,
+                mapping_type: None,
+                dependency_type: None,
+                source_run: None,
+                source_stage_filter: None,
+            })
+            .collect();
+        Job {
+            name: Some(name.to_string()),
+            params: serde_json::Value::Null,
+            path_in_lab: PathBuf::new(),
+            stage_type: StageType::Simple,
+            executables: HashMap::from([(
+                "main".to_string(),
+                Executable {
+                    path: PathBuf::from("echo"),
+                    inputs,
+                    outputs: HashMap::new(),
+                    resource_hints: None,
+                    deps: vec![],
+                    interpreter: None,
+                    args: vec![],
+                    image: None,
+                    runtime: None,
+                },
+            )]),
+            resource_hints: None,
+            data_mounts: vec![],
+        }
+    }
+
+    #[test]
+    fn test_neighborhood_layers_and_edges() {
+        let jobs = [
+            ("aaa-fetch", job("fetch", &[])),
+            ("bbb-prep", job("prep", &["aaa-fetch"])),
+            ("ccc-index", job("index", &[])),
+            ("ddd-train", job("train", &["bbb-prep", "ccc-index"])),
+            ("eee-eval", job("eval", &["ddd-train", "bbb-prep"])),
+            ("fff-plot", job("plot", &["ddd-train"])),
+            ("ggg-report", job("report", &["eee-eval"])),
+        ];
+        let lab = Lab {
+            repx_version: "0.5.0".to_string(),
+            lab_version: "1.0.0".to_string(),
+            git_hash: "abc".to_string(),
+            content_hash: "abc".to_string(),
+            runs: HashMap::from([(
+                RunId::from("sim"),
+                Run {
+                    image: None,
+                    jobs: jobs.iter().map(|(id, _)| JobId::from(*id)).collect(),
+                    dependencies: HashMap::new(),
+                },
+            )]),
+            jobs: jobs
+                .into_iter()
+                .map(|(id, job)| (JobId::from(id), job))
+                .collect(),
+            groups: HashMap::new(),
+            host_tools_path: PathBuf::new(),
+            host_tools_dir_name: "tools".to_string(),
+            referenced_files: vec![],
+            tar_dir_name: None,
+        };
+        let mut jobs_state = JobsState::new();
+        jobs_state.init_from_lab(&lab);
+
+        let labels = |graph: &Neighborhood| -> Vec<Vec<String>> {
+            graph
+                .layers
+                .iter()
+                .map(|layer| layer.nodes.iter().map(|n| n.label.clone()).collect())
+                .collect()
+        };
+
+        let graph = neighborhood(&lab, &jobs_state, &JobId::from("ddd-train"), 2, 4);
+        assert_eq!(graph.focus, 2);
+        assert_eq!(
+            labels(&graph),
+            vec![
+                vec!["fetch"],
+                vec!["index", "prep"],
+                vec!["train"],
+                vec!["eval", "plot"],
+                vec!["report"],
+            ]
+        );
+        let mut edges = graph.edges.clone();
+        edges.sort();
+        assert_eq!(
+            edges,
+            vec![
+                (0, 0, 1),
+                (1, 0, 0),
+                (1, 1, 0),
+                (2, 0, 0),
+                (2, 0, 1),
+                (3, 0, 0)
+            ],
+            "eval's edge to prep skips a layer and is left out"
+        );
+
+        let narrow = neighborhood(&lab, &jobs_state, &JobId::from("ddd-train"), 1, 1);
+        assert_eq!(
+            labels(&narrow),
+            vec![vec!["index"], vec!["train"], vec!["eval"]]
+        );
+        assert_eq!(narrow.layers[0].hidden, 1);
+        assert_eq!(narrow.layers[2].hidden, 1);
+    }
+}
@@ -31,6 +31,20 @@ pub fn handle_key_event(key: KeyEvent, app: &mut App) {
         return;
     }
 
+    if app.input_mode == InputMode::ConfirmCancel {
+        match key.code {
+            KeyCode::Enter | KeyCode::Char('y') => app.confirm_cancel(),
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => app.close_cancel(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.input_mode == InputMode::Resubmit {
+        handle_resubmit_key_event(key, app);
+        return;
+    }
+
     match key.code {
         KeyCode::Char('q') => app.quit(),
         KeyCode::Char(' ') => {
@@ -83,6 +97,7 @@ fn handle_jobs_panel_key_event(key: KeyEvent, app: &mut App) {
         KeyCode::Char('+') | KeyCode::Char('=') => app.increase_tick_rate(),
         KeyCode::Char('-') => app.decrease_tick_rate(),
         KeyCode::Char('t') => app.toggle_tree_view(),
+        KeyCode::Char('v') => app.toggle_dag(),
         KeyCode::Char('.') => app.toggle_collapse_selected(),
         KeyCode::Char('x') => app.toggle_selection_and_move_down(),
         KeyCode::Esc => {
@@ -119,6 +134,7 @@ fn handle_targets_panel_key_event(key: KeyEvent, app: &mut App) {
             3 => app.set_active_target(),
             _ => {}
         },
+        KeyCode::Char('a') => app.toggle_aggregated_targets(),
         _ => {}
     }
 }
@@ -129,8 +145,12 @@ fn handle_space_menu_key_event(key: KeyEvent, app: &mut App) {
             app.input_mode = InputMode::Normal;
         }
         KeyCode::Char('c') => {
+            app.input_mode = InputMode::Normal;
             app.cancel_selected();
+        }
+        KeyCode::Char('s') => {
             app.input_mode = InputMode::Normal;
+            app.open_resubmit_selected();
         }
         KeyCode::Char('p') => {
             app.pin_toggle();
@@ -173,6 +193,10 @@ fn handle_g_menu_key_event(key: KeyEvent, app: &mut App) {
             app.open_job_logs_selected();
             app.input_mode = InputMode::Normal;
         }
+        KeyCode::Char('w') => {
+            app.switch_to_next_workspace_lab();
+            app.input_mode = InputMode::Normal;
+        }
         KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char(' ') => {
             app.input_mode = InputMode::Normal;
         }
@@ -265,6 +289,23 @@ fn handle_editing_mode_key_event(key: KeyEvent, app: &mut App) {
         _ => {}
     }
 }
+fn handle_resubmit_key_event(key: KeyEvent, app: &mut App) {
+    let Some(form) = app.resubmit.as_mut() else {
+        app.input_mode = InputMode::Normal;
+        return;
+    };
+    match key.code {
+        KeyCode::Esc => app.close_resubmit(),
+        KeyCode::Enter => app.confirm_resubmit(),
+        KeyCode::Tab | KeyCode::Down => form.next_field(),
+        KeyCode::BackTab | KeyCode::Up => form.previous_field(),
+        KeyCode::Left => form.cycle_executor(false),
+        KeyCode::Right => form.cycle_executor(true),
+        KeyCode::Backspace => form.backspace(),
+        KeyCode::Char(c) => form.insert(c),
+        _ => {}
+    }
+}
 pub fn poll_event(timeout: Duration) -> io::Result<Option<CrosstermEvent>> {
     if event::poll(timeout)? {
         Ok(Some(crossterm::event::read()?))
@@ -1,4 +1,5 @@
 pub mod app;
+pub mod dag_view;
 pub mod error;
 pub mod event;
 pub mod model;
@@ -8,7 +9,7 @@ pub mod tree_view;
 pub mod ui;
 pub mod widgets;
 
-use crate::app::{ExternalAction, LogPollerCommand};
+use crate::app::{targets::ALL_TARGETS, ExternalAction, LogPollerCommand};
 
 fn shell_quote_single(s: &str) -> String {
     format!("'{}'", s.replace('\'', "'\\''"))
@@ -25,13 +26,14 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use repx_client::Client;
+use repx_client::{targets::TargetHealth, Client};
 use repx_core::constants::targets;
+use repx_core::workspace::{self, Workspace};
 use repx_core::{config, model::JobId, model::SchedulerType, theme};
 use std::{
     fs,
     io::{self, Stdout},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
         mpsc, Arc, Mutex,
@@ -40,6 +42,8 @@ use std::{
     time::{Duration, Instant},
 };
 
+const HEALTH_CHECK_INTERVAL_SECS: u64 = 60;
+
 #[derive(Parser)]
 pub struct TuiArgs {
     #[arg(short, long, global = true, default_value = "./result")]
@@ -74,7 +78,77 @@ pub fn run(args: TuiArgs) -> Result<(), TuiError> {
     repx_core::logging::init_tui_logger(&logging_config)?;
     tracing::info!("--- Repx TUI Started ---");
 
-    let lab_source = repx_core::lab::LabSource::from_path(&args.lab);
+    let mut session = start_session(&args.lab)?;
+    let mut terminal = setup_terminal()?;
+    loop {
+        run_app(&mut terminal, &mut session.app)?;
+        session.should_quit.store(true, Ordering::Relaxed);
+
+        let Some(next_lab) = session.app.switch_lab.take() else {
+            break;
+        };
+        session = match start_session(&next_lab) {
+            Ok(next) => next,
+            Err(e) => {
+                restore_terminal(&mut terminal)?;
+                return Err(e);
+            }
+        };
+        if let Some(name) = &session.app.workspace_name {
+            let recorded = workspace::load_workspace().and_then(|mut workspace| {
+                workspace.set_active(name)?;
+                workspace::save_workspace(&workspace)
+            });
+            if let Err(e) = recorded {
+                tracing::warn!("Failed to record active workspace lab: {}", e);
+            }
+        }
+        terminal.clear()?;
+    }
+
+    restore_terminal(&mut terminal)?;
+
+    Ok(())
+}
+
+struct Session {
+    app: App,
+    should_quit: Arc<AtomicBool>,
+}
+
+fn spawn_health_checks(
+    client: Arc<Client>,
+    should_quit: Arc<AtomicBool>,
+) -> mpsc::Receiver<(String, TargetHealth)> {
+    let (health_tx, health_rx) = mpsc::channel();
+    thread::spawn(move || loop {
+        if should_quit.load(Ordering::Relaxed) {
+            break;
+        }
+        let names: Vec<String> = client.config().targets.keys().cloned().collect();
+        thread::scope(|scope| {
+            for name in names {
+                let Some(target) = client.get_target(&name) else {
+                    continue;
+                };
+                let health_tx = health_tx.clone();
+                scope.spawn(move || {
+                    let _ = health_tx.send((name, target.health()));
+                });
+            }
+        });
+        for _ in 0..HEALTH_CHECK_INTERVAL_SECS {
+            if should_quit.load(Ordering::Relaxed) {
+                return;
+            }
+            thread::sleep(Duration::from_secs(1));
+        }
+    });
+    health_rx
+}
+
+fn start_session(lab: &Path) -> Result<Session, TuiError> {
+    let lab_source = repx_core::lab::LabSource::from_path(lab);
     let config = config::load_config()?;
     let theme = theme::load_theme(&config)?;
     let resources = config::load_resources(None)?;
@@ -85,6 +159,7 @@ pub fn run(args: TuiArgs) -> Result<(), TuiError> {
             log_summary: e.to_string(),
         }
     })?);
+    client.enable_status_agents();
 
     let (status_tx, status_rx) = mpsc::channel();
     let status_client_clone = Arc::clone(&client);
@@ -109,6 +184,9 @@ pub fn run(args: TuiArgs) -> Result<(), TuiError> {
     let active_scheduler = Arc::new(Mutex::new(initial_active_scheduler.clone()));
     let active_scheduler_clone_for_status = active_scheduler.clone();
 
+    let aggregated = Arc::new(AtomicBool::new(false));
+    let aggregated_clone_for_status = aggregated.clone();
+
     thread::spawn(move || loop {
         if should_quit_clone_for_status.load(Ordering::Relaxed) {
             break;
@@ -124,9 +202,16 @@ pub fn run(args: TuiArgs) -> Result<(), TuiError> {
             .clone();
         let scheduler_type: Option<SchedulerType> = scheduler_name.parse().ok();
 
-        let statuses = status_client_clone
-            .get_statuses_for_active_target(&target_name, scheduler_type)
-            .map(|job_statuses| (target_name, job_statuses));
+        let statuses = if aggregated_clone_for_status.load(Ordering::Relaxed) {
+            Ok((
+                ALL_TARGETS.to_string(),
+                status_client_clone.get_federated_status("").combined,
+            ))
+        } else {
+            status_client_clone
+                .get_statuses_for_active_target(&target_name, scheduler_type)
+                .map(|job_statuses| (target_name, job_statuses))
+        };
         if status_tx.send(statuses).is_err() {
             break;
         }
@@ -221,7 +306,17 @@ pub fn run(args: TuiArgs) -> Result<(), TuiError> {
                         50,
                         repx_client::client::LogType::Auto,
                     );
-                    if log_result_tx.send((job_id.clone(), log_result)).is_err() {
+                    let progress = log_client_clone
+                        .get_scatter_progress(job_id, &target_name)
+                        .unwrap_or_else(|e| {
+                            tracing::debug!("Failed to fetch progress of '{}': {}", job_id, e);
+                            None
+                        })
+                        .map(|progress| progress.summary());
+                    if log_result_tx
+                        .send((job_id.clone(), log_result, progress))
+                        .is_err()
+                    {
                         break;
                     }
                     last_fetch = Instant::now();
@@ -232,6 +327,7 @@ pub fn run(args: TuiArgs) -> Result<(), TuiError> {
         }
     });
     let (submission_tx, submission_rx) = mpsc::channel::<SubmissionResult>();
+    let health_rx = spawn_health_checks(Arc::clone(&client), should_quit.clone());
 
     let mut app = App::new(
         client,
@@ -246,6 +342,7 @@ pub fn run(args: TuiArgs) -> Result<(), TuiError> {
         initial_active_target,
         active_target,
         active_scheduler,
+        aggregated,
     )
     .map_err(|e| TuiError::ExecutionFailed {
         message: "TUI app initialization failed".to_string(),
@@ -253,13 +350,19 @@ pub fn run(args: TuiArgs) -> Result<(), TuiError> {
         log_summary: e.to_string(),
     })?;
 
-    let mut terminal = setup_terminal()?;
-    run_app(&mut terminal, &mut app)?;
-
-    should_quit.store(true, Ordering::Relaxed);
-    restore_terminal(&mut terminal)?;
-
-    Ok(())
+    let workspace = workspace::load_workspace().unwrap_or_else(|e| {
+        tracing::warn!("Failed to load workspace: {}", e);
+        Workspace::default()
+    });
+    app.health_rx = Some(health_rx);
+    app.workspace_name = workspace.name_for_path(lab).map(str::to_string);
+    app.workspace_labs = workspace
+        .labs
+        .iter()
+        .map(|(name, entry)| (name.clone(), entry.path.clone()))
+        .collect();
+
+    Ok(Session { app, should_quit })
 }
 
 fn setup_terminal() -> io::Result<Terminal<CrosstermBackend<Stdout>>> {
@@ -332,6 +435,7 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) ->
         app.check_for_log_updates();
         app.check_for_submission_updates();
         app.check_for_system_log_updates();
+        app.check_for_health_updates();
         app.process_pending_context_update();
 
         if last_tick.elapsed() >= app.tick_rate {
@@ -421,6 +525,7 @@ fn run_screenshot(args: &TuiArgs, output_path: PathBuf) -> Result<(), TuiError>
 
     let active_target = Arc::new(Mutex::new(initial_active_target.clone()));
     let active_scheduler = Arc::new(Mutex::new(initial_active_scheduler));
+    let aggregated = Arc::new(AtomicBool::new(false));
 
     let scheduler_type: Option<repx_core::model::SchedulerType> = client
         .config()
@@ -446,6 +551,7 @@ fn run_screenshot(args: &TuiArgs, output_path: PathBuf) -> Result<(), TuiError>
         initial_active_target,
         active_target,
         active_scheduler,
+        aggregated,
     )
     .map_err(|e| TuiError::ExecutionFailed {
         message: "TUI app initialization failed".to_string(),
@@ -1,4 +1,5 @@
-use repx_core::model::{JobId, RunId};
+use repx_client::targets::TargetHealth;
+use repx_core::model::{FailureClass, JobId, RunId};
 use serde::Deserialize;
 use serde::Serialize;
 use std::{collections::HashMap, fmt, str::FromStr};
@@ -9,6 +10,9 @@ use std::{collections::HashMap, fmt, str::FromStr};
 pub enum JobStatus {
     Succeeded,
     Failed,
+    TimedOut,
+    Cancelled,
+    Lost,
     SubmitFailed,
     Running,
     Pending,
@@ -24,6 +28,9 @@ impl JobStatus {
         match self {
             JobStatus::Succeeded => "Succeeded",
             JobStatus::Failed => "Failed",
+            JobStatus::TimedOut => "Timed Out",
+            JobStatus::Cancelled => "Cancelled",
+            JobStatus::Lost => "Lost",
             JobStatus::SubmitFailed => "Submit Failed",
             JobStatus::Running => "Running",
             JobStatus::Pending => "Pending",
@@ -58,12 +65,18 @@ pub struct StatusCounts {
 pub enum TuiScheduler {
     Local,
     Slurm,
+    Sge,
+    Flux,
+    AwsBatch,
 }
 impl TuiScheduler {
     pub fn as_str(&self) -> &'static str {
         match self {
             TuiScheduler::Local => "local",
             TuiScheduler::Slurm => "slurm",
+            TuiScheduler::Sge => "sge",
+            TuiScheduler::Flux => "flux",
+            TuiScheduler::AwsBatch => "aws-batch",
         }
     }
 }
@@ -73,6 +86,9 @@ impl FromStr for TuiScheduler {
         match s {
             "local" => Ok(TuiScheduler::Local),
             "slurm" => Ok(TuiScheduler::Slurm),
+            "sge" => Ok(TuiScheduler::Sge),
+            "flux" => Ok(TuiScheduler::Flux),
+            "aws-batch" => Ok(TuiScheduler::AwsBatch),
             _ => Err(()),
         }
     }
@@ -83,6 +99,9 @@ impl From<repx_core::model::SchedulerType> for TuiScheduler {
         match st {
             repx_core::model::SchedulerType::Local => TuiScheduler::Local,
             repx_core::model::SchedulerType::Slurm => TuiScheduler::Slurm,
+            repx_core::model::SchedulerType::Sge => TuiScheduler::Sge,
+            repx_core::model::SchedulerType::Flux => TuiScheduler::Flux,
+            repx_core::model::SchedulerType::AwsBatch => TuiScheduler::AwsBatch,
         }
     }
 }
@@ -93,6 +112,7 @@ pub enum TuiExecutor {
     Podman,
     Docker,
     Bwrap,
+    Proot,
 }
 impl TuiExecutor {
     pub fn as_str(&self) -> &'static str {
@@ -101,6 +121,7 @@ impl TuiExecutor {
             TuiExecutor::Podman => "podman",
             TuiExecutor::Docker => "docker",
             TuiExecutor::Bwrap => "bwrap",
+            TuiExecutor::Proot => "proot",
         }
     }
 }
@@ -112,6 +133,7 @@ impl FromStr for TuiExecutor {
             "podman" => Ok(TuiExecutor::Podman),
             "docker" => Ok(TuiExecutor::Docker),
             "bwrap" => Ok(TuiExecutor::Bwrap),
+            "proot" => Ok(TuiExecutor::Proot),
             _ => Err(()),
         }
     }
@@ -124,6 +146,7 @@ impl From<repx_core::model::ExecutionType> for TuiExecutor {
             repx_core::model::ExecutionType::Bwrap => TuiExecutor::Bwrap,
             repx_core::model::ExecutionType::Podman => TuiExecutor::Podman,
             repx_core::model::ExecutionType::Docker => TuiExecutor::Docker,
+            repx_core::model::ExecutionType::Proot => TuiExecutor::Proot,
         }
     }
 }
@@ -138,9 +161,15 @@ pub struct TuiJob {
     #[serde(default)]
     pub params_str: String,
     pub status: JobStatus,
+    #[serde(default)]
+    pub failure_class: Option<FailureClass>,
+    #[serde(default)]
+    pub failure_reason: Option<String>,
     pub context_depends_on: String,
     pub context_dependents: String,
     pub logs: Vec<String>,
+    #[serde(default)]
+    pub progress: Option<String>,
     #[serde(skip)]
     pub id_lower: String,
     #[serde(skip)]
@@ -249,6 +278,7 @@ pub struct TuiTarget {
     pub available_executors: HashMap<TuiScheduler, Vec<TuiExecutor>>,
     pub selected_scheduler_idx: usize,
     pub selected_executor_idx: usize,
+    pub health: Option<TargetHealth>,
 }
 
 impl TuiTarget {
@@ -302,7 +332,13 @@ mod tests {
 
     #[test]
     fn test_tui_scheduler_roundtrip() {
-        for scheduler in [TuiScheduler::Local, TuiScheduler::Slurm] {
+        for scheduler in [
+            TuiScheduler::Local,
+            TuiScheduler::Slurm,
+            TuiScheduler::Sge,
+            TuiScheduler::Flux,
+            TuiScheduler::AwsBatch,
+        ] {
             let s = scheduler.as_str();
             let parsed: TuiScheduler = s.parse().expect("roundtrip parse must succeed");
             assert_eq!(scheduler, parsed);
@@ -315,6 +351,7 @@ mod tests {
         assert_eq!("podman".parse::<TuiExecutor>(), Ok(TuiExecutor::Podman));
         assert_eq!("docker".parse::<TuiExecutor>(), Ok(TuiExecutor::Docker));
         assert_eq!("bwrap".parse::<TuiExecutor>(), Ok(TuiExecutor::Bwrap));
+        assert_eq!("proot".parse::<TuiExecutor>(), Ok(TuiExecutor::Proot));
     }
 
     #[test]
@@ -330,6 +367,7 @@ mod tests {
         assert_eq!(TuiExecutor::Podman.as_str(), "podman");
         assert_eq!(TuiExecutor::Docker.as_str(), "docker");
         assert_eq!(TuiExecutor::Bwrap.as_str(), "bwrap");
+        assert_eq!(TuiExecutor::Proot.as_str(), "proot");
     }
 
     #[test]
@@ -339,6 +377,7 @@ mod tests {
             TuiExecutor::Podman,
             TuiExecutor::Docker,
             TuiExecutor::Bwrap,
+            TuiExecutor::Proot,
         ] {
             let s = executor.as_str();
             let parsed: TuiExecutor = s.parse().expect("roundtrip parse must succeed");
@@ -364,6 +403,7 @@ mod tests {
             available_executors,
             selected_scheduler_idx: 0,
             selected_executor_idx: 0,
+            health: None,
         }
     }
 
@@ -424,6 +464,7 @@ mod tests {
             available_executors: HashMap::new(),
             selected_scheduler_idx: 0,
             selected_executor_idx: 0,
+            health: None,
         };
         assert_eq!(target.get_selected_scheduler(), TuiScheduler::Local);
         assert_eq!(target.get_selected_executor(), TuiExecutor::Native);
@@ -439,9 +480,12 @@ mod tests {
             params: serde_json::json!({"key": "value", "count": 42}),
             params_str: "count=42,key=value".to_string(),
             status: JobStatus::Pending,
+            failure_class: None,
+            failure_reason: None,
             context_depends_on: "job-122".to_string(),
             context_dependents: "job-124, job-125".to_string(),
             logs: vec!["log line 1".to_string(), "log line 2".to_string()],
+            progress: None,
             id_lower: String::new(),
             name_lower: String::new(),
             run_lower: String::new(),
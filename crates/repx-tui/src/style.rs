@@ -30,7 +30,9 @@ pub fn get_style(app: &App, element: &ElementStyle) -> Style {
 pub fn status_style(app: &App, status: &JobStatus) -> Style {
     match status {
         JobStatus::Succeeded => get_style(app, &app.theme.elements.job_status.succeeded),
-        JobStatus::Failed => get_style(app, &app.theme.elements.job_status.failed),
+        JobStatus::Failed | JobStatus::TimedOut | JobStatus::Cancelled | JobStatus::Lost => {
+            get_style(app, &app.theme.elements.job_status.failed)
+        }
         JobStatus::SubmitFailed => get_style(app, &app.theme.elements.job_status.submit_failed),
         JobStatus::Pending => get_style(app, &app.theme.elements.job_status.pending),
         JobStatus::Running => get_style(app, &app.theme.elements.job_status.running),
@@ -1,6 +1,6 @@
 use crate::{
     app::App,
-    model::{TuiDisplayRow, TuiRowItem},
+    model::{TuiDisplayRow, TuiJob, TuiRowItem},
     style::{get_style, status_style},
 };
 use ratatui::{
@@ -58,6 +58,13 @@ fn selector_cell<'a>(app: &App, is_selected: bool) -> Cell<'a> {
     }
 }
 
+fn failure_cell<'a>(job: &TuiJob) -> Cell<'a> {
+    match &job.failure_reason {
+        Some(reason) => Cell::from(reason.clone()),
+        None => Cell::from(job.failure_class.map_or("", |class| class.as_str())),
+    }
+}
+
 pub fn build_flat_rows<'a>(
     app: &App,
     display_rows: &'a [TuiDisplayRow],
@@ -89,6 +96,7 @@ pub fn build_flat_rows<'a>(
                 Cell::from(job.run.as_str()),
                 Cell::from(job.params_str.as_str()),
                 status,
+                failure_cell(job),
             ])
         })
         .collect()
@@ -120,6 +128,7 @@ pub fn build_tree_rows<'a>(
                     ])),
                     Cell::from(""),
                     Cell::from(""),
+                    Cell::from(""),
                 ]));
             }
 
@@ -135,6 +144,7 @@ pub fn build_tree_rows<'a>(
                     ])),
                     Cell::from(""),
                     Cell::from(""),
+                    Cell::from(""),
                 ]));
             }
 
@@ -153,6 +163,7 @@ pub fn build_tree_rows<'a>(
                     ])),
                     Cell::from(job.params_str.as_str()),
                     status,
+                    failure_cell(job),
                 ]));
             }
         }
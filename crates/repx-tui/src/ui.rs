@@ -1,9 +1,10 @@
 use crate::{
-    app::{App, InputMode, PanelFocus},
+    app::{resubmit::ResubmitField, App, InputMode, PanelFocus},
+    dag_view,
     model::{JobStatus, TargetState, TuiRowItem},
-    style::{get_color, get_style},
+    style::{get_color, get_style, status_style},
     tree_view::{build_flat_rows, build_tree_rows, shorten_nix_store_path},
-    widgets::{color, BrailleGraph, GraphDirection, StackedBarChart},
+    widgets::{color, dag_graph, BrailleGraph, DagGraph, GraphDirection, StackedBarChart},
 };
 use chrono::Local;
 use ratatui::{
@@ -13,6 +14,8 @@ use ratatui::{
         ScrollbarState, Table,
     },
 };
+use repx_client::targets::HealthStatus;
+use repx_core::fs_utils::format_bytes;
 use std::collections::BTreeMap;
 
 pub fn draw(f: &mut Frame, app: &mut App) {
@@ -37,6 +40,10 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         draw_g_menu_popup(f, f.area(), app);
     } else if app.input_mode == InputMode::ZMenu {
         draw_z_menu_popup(f, f.area(), app);
+    } else if app.input_mode == InputMode::Resubmit {
+        draw_resubmit_popup(f, f.area(), app);
+    } else if app.input_mode == InputMode::ConfirmCancel {
+        draw_cancel_popup(f, f.area(), app);
     }
 }
 
@@ -44,6 +51,15 @@ fn draw_overview_panel(f: &mut Frame, area: Rect, app: &mut App) {
     let overview_border_style = get_style(app, &app.theme.elements.panels.overview);
     let targets_border_style = get_style(app, &app.theme.elements.panels.targets);
     let loading_indicator = if app.is_loading { " [Updating...]" } else { "" };
+    let upload_indicator = match app.sync_progress {
+        Some((done, total)) if total > 0 => format!(
+            " [Uploading {}% {}/{}]",
+            done.saturating_mul(100) / total,
+            format_bytes(done, true),
+            format_bytes(total, true)
+        ),
+        _ => String::new(),
+    };
     let pinned_indicator = if app.is_pinned { " [Pinned]" } else { "" };
     let store_path_str = {
         let active_target_name = app.targets_state.get_active_target_name();
@@ -59,6 +75,16 @@ fn draw_overview_panel(f: &mut Frame, area: Rect, app: &mut App) {
     } else {
         app.lab.git_hash.chars().take(13).collect::<String>()
     };
+    let lab_label = app
+        .workspace_name
+        .as_ref()
+        .map(|name| format!("lab: @{} ", name))
+        .unwrap_or_default();
+    let aggregated_label = if app.targets_state.is_aggregated() {
+        "targets: all "
+    } else {
+        ""
+    };
     let rate_text = format!("{}ms", app.tick_rate.as_millis());
     let current_time = Local::now().format("%H:%M:%S").to_string();
     let overview_block = Block::default()
@@ -76,6 +102,18 @@ fn draw_overview_panel(f: &mut Frame, area: Rect, app: &mut App) {
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::styled("┌┐", overview_border_style),
+                Span::styled(
+                    lab_label,
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::DIM),
+                ),
+                Span::styled(
+                    aggregated_label,
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
                 Span::styled("store: ", Style::default().fg(Color::White)),
                 Span::styled(
                     format!("{} ", store_path_str),
@@ -86,7 +124,7 @@ fn draw_overview_panel(f: &mut Frame, area: Rect, app: &mut App) {
                 Span::styled("┌─┐", overview_border_style),
                 Span::styled("githash: ", Style::default().fg(Color::White)),
                 Span::styled(
-                    format!("{}{}", githash_short, loading_indicator),
+                    format!("{}{}{}", githash_short, loading_indicator, upload_indicator),
                     Style::default()
                         .fg(Color::White)
                         .add_modifier(Modifier::DIM),
@@ -306,15 +344,30 @@ fn draw_targets(f: &mut Frame, area: Rect, app: &mut App, border_style: Style) {
             .map(|(i, target)| {
                 let is_selected_row = selected_row_idx == Some(i);
 
-                let (state_text, state_style) = match target.state {
-                    TargetState::Active => (
-                        "[ACTIVE]",
-                        get_style(app, &app.theme.elements.target_states.active),
+                let (health_text, health_style) = match target.health.as_ref().map(|h| h.status()) {
+                    Some(HealthStatus::Up) => (
+                        "[UP]",
+                        get_style(app, &app.theme.elements.job_status.succeeded),
                     ),
-                    TargetState::Inactive => (
-                        "[INACTIVE]",
-                        get_style(app, &app.theme.elements.target_states.inactive),
+                    Some(HealthStatus::Degraded) => (
+                        "[DEGRADED]",
+                        get_style(app, &app.theme.elements.job_status.pending),
                     ),
+                    Some(HealthStatus::Down) => (
+                        "[DOWN]",
+                        get_style(app, &app.theme.elements.job_status.failed),
+                    ),
+                    None => (
+                        "[CHECKING]",
+                        get_style(app, &app.theme.elements.job_status.unknown),
+                    ),
+                };
+                let name_cell = match target.state {
+                    TargetState::Active => Cell::from(Span::styled(
+                        format!("* {}", target.name),
+                        get_style(app, &app.theme.elements.target_states.active),
+                    )),
+                    TargetState::Inactive => Cell::from(format!("  {}", target.name)),
                 };
                 let mut executor_text = target.get_selected_executor().as_str().to_string();
                 if is_selected_row
@@ -332,10 +385,10 @@ fn draw_targets(f: &mut Frame, area: Rect, app: &mut App, border_style: Style) {
                     scheduler_text = format!("← {} →", scheduler_text);
                 }
                 let mut cells = vec![
-                    Cell::from(target.name.clone()),
+                    name_cell,
                     Cell::from(executor_text),
                     Cell::from(scheduler_text),
-                    Cell::from(Span::styled(state_text, state_style)),
+                    Cell::from(Span::styled(health_text, health_style)),
                 ];
 
                 if is_selected_row {
@@ -386,7 +439,11 @@ fn draw_left_column(f: &mut Frame, area: Rect, app: &App) {
         .split(area);
 
     draw_context_panel(f, left_chunks[0], app);
-    draw_logs_panel(f, left_chunks[1], app);
+    if app.show_dag {
+        draw_dag_panel(f, left_chunks[1], app);
+    } else {
+        draw_logs_panel(f, left_chunks[1], app);
+    }
     draw_system_logs_panel(f, left_chunks[2], app);
 }
 
@@ -541,10 +598,16 @@ fn draw_logs_panel(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(block, area);
 
     let content = if let Some(job) = selected_job {
+        let progress = job.progress.as_deref().map(|progress| {
+            Line::from(Span::styled(
+                progress,
+                Style::default().add_modifier(Modifier::BOLD),
+            ))
+        });
         Paragraph::new(
-            job.logs
-                .iter()
-                .map(|log| Line::from(log.as_str()))
+            progress
+                .into_iter()
+                .chain(job.logs.iter().map(|log| Line::from(log.as_str())))
                 .collect::<Vec<Line>>(),
         )
     } else {
@@ -552,6 +615,67 @@ fn draw_logs_panel(f: &mut Frame, area: Rect, app: &App) {
     };
     f.render_widget(content, inner_area);
 }
+fn draw_dag_panel(f: &mut Frame, area: Rect, app: &App) {
+    let dag_border_style = get_style(app, &app.theme.elements.panels.logs);
+    let selected_job = app
+        .jobs_state
+        .table_state
+        .selected()
+        .and_then(|i| app.jobs_state.display_rows.get(i))
+        .and_then(|row| {
+            if let TuiRowItem::Job { job } = &row.item {
+                Some(job)
+            } else {
+                None
+            }
+        });
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(dag_border_style)
+        .title_top(Line::from(vec![
+            Span::styled("─┐", dag_border_style),
+            Span::styled("⁵", Style::default().add_modifier(Modifier::DIM)),
+            Span::styled(
+                "DEPENDENCIES",
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("┌─", dag_border_style),
+        ]));
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let Some(job) = selected_job else {
+        f.render_widget(
+            Paragraph::new("Select a job to see its dependencies."),
+            inner_area,
+        );
+        return;
+    };
+
+    let layers = (inner_area.height + 1) / dag_graph::LAYER_HEIGHT;
+    let depth = (layers.saturating_sub(1) / 2).clamp(1, 3) as usize;
+    let max_per_layer = (inner_area.width / dag_graph::MIN_NODE_WIDTH).max(1) as usize;
+    let graph = dag_view::neighborhood(
+        &app.lab,
+        &app.jobs_state,
+        &job.full_id,
+        depth,
+        max_per_layer,
+    );
+    let status_style = |status: &JobStatus| status_style(app, status);
+    f.render_widget(
+        DagGraph {
+            graph: &graph,
+            status_style: &status_style,
+            edge_style: Style::default().add_modifier(Modifier::DIM),
+        },
+        inner_area,
+    );
+}
+
 fn draw_right_column(f: &mut Frame, area: Rect, app: &mut App) {
     let runs_jobs_border_style = get_style(app, &app.theme.elements.panels.runs_jobs);
     let filtered_count = app.jobs_state.display_rows.len();
@@ -622,7 +746,12 @@ fn draw_right_column(f: &mut Frame, area: Rect, app: &mut App) {
 
             left_title_spans.push(Span::styled(final_text, Style::default()));
         }
-        InputMode::Normal | InputMode::SpaceMenu | InputMode::GMenu | InputMode::ZMenu => {
+        InputMode::Normal
+        | InputMode::SpaceMenu
+        | InputMode::GMenu
+        | InputMode::ZMenu
+        | InputMode::Resubmit
+        | InputMode::ConfirmCancel => {
             if !app.jobs_state.filter_text.is_empty() {
                 let text_to_truncate = &app.jobs_state.filter_text;
                 let char_count = text_to_truncate.chars().count();
@@ -792,14 +921,22 @@ fn draw_right_column(f: &mut Frame, area: Rect, app: &mut App) {
         .with_offset(adjusted_offset);
 
     let jobs_table = if app.jobs_state.is_tree_view {
-        let header = Row::new(vec!["", "jobid:", "Item:", "Parameters:", "Status:"])
-            .style(Style::default().add_modifier(Modifier::BOLD));
+        let header = Row::new(vec![
+            "",
+            "jobid:",
+            "Item:",
+            "Parameters:",
+            "Status:",
+            "Failure:",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD));
         let constraints = [
             Constraint::Length(1),
             Constraint::Length(8),
             Constraint::Length(35),
             Constraint::Min(20),
             Constraint::Length(10),
+            Constraint::Length(22),
         ];
         Table::new(rows, constraints)
             .header(header.height(1))
@@ -820,6 +957,7 @@ fn draw_right_column(f: &mut Frame, area: Rect, app: &mut App) {
             "Run:",
             "Parameters:",
             "Status:",
+            "Failure:",
         ])
         .style(Style::default().add_modifier(Modifier::BOLD));
         let constraints = [
@@ -829,6 +967,7 @@ fn draw_right_column(f: &mut Frame, area: Rect, app: &mut App) {
             Constraint::Length(15),
             Constraint::Min(20),
             Constraint::Length(10),
+            Constraint::Length(22),
         ];
         Table::new(rows, constraints)
             .header(header.height(1))
@@ -924,6 +1063,121 @@ fn draw_menu_popup(f: &mut Frame, area: Rect, app: &App, title: &str, shortcuts:
     f.render_widget(table, inner_area);
 }
 
+fn centered_popup(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    }
+}
+
+fn draw_cancel_popup(f: &mut Frame, area: Rect, app: &App) {
+    let Some(plan) = &app.cancel_plan else {
+        return;
+    };
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    let width = area.width.saturating_sub(8).clamp(40, 90);
+    let max_jobs = (area.height.saturating_sub(8) as usize).max(1);
+
+    let mut lines: Vec<Line> = plan
+        .iter()
+        .take(max_jobs)
+        .map(|(job_id, report)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<24}", job_id.short_id()),
+                    get_style(app, &app.theme.elements.popups.key_fg),
+                ),
+                Span::raw(report.describe_handles()),
+            ])
+        })
+        .collect();
+    if plan.len() > max_jobs {
+        lines.push(Line::from(Span::styled(
+            format!("+{} more", plan.len() - max_jobs),
+            dim,
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Enter/y cancel these jobs  Esc/n keep them",
+        dim,
+    )));
+
+    let popup_area = centered_popup(area, width, lines.len() as u16 + 2);
+    let block = Block::default()
+        .title(format!(" Cancel {} job(s)? ", plan.len()))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(get_style(app, &app.theme.elements.popups.border));
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+fn draw_resubmit_popup(f: &mut Frame, area: Rect, app: &App) {
+    let Some(form) = &app.resubmit else {
+        return;
+    };
+    let key_style = get_style(app, &app.theme.elements.popups.key_fg)
+        .bg(get_color(app, &app.theme.elements.popups.key_bg.color));
+    let dim = Style::default().add_modifier(Modifier::DIM);
+
+    let shown_ids = 3;
+    let mut jobs: Vec<String> = form
+        .job_ids
+        .iter()
+        .take(shown_ids)
+        .map(|id| id.short_id())
+        .collect();
+    if form.job_ids.len() > shown_ids {
+        jobs.push(format!("+{} more", form.job_ids.len() - shown_ids));
+    }
+
+    let mut lines = vec![
+        Line::from(Span::styled(jobs.join(", "), dim)),
+        Line::from(""),
+    ];
+    for field in ResubmitField::ALL {
+        let focused = field == form.focused;
+        let value = match field {
+            ResubmitField::Runtime => format!("< {} >", form.value(field)),
+            _ if focused => format!("{}_", form.value(field)),
+            _ => form.value(field).to_string(),
+        };
+        let label_style = if focused { key_style } else { Style::default() };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {:<9} ", field.label()), label_style),
+            Span::raw(" "),
+            Span::raw(value),
+        ]));
+    }
+    lines.push(Line::from(""));
+    if let Some(error) = &form.error {
+        lines.push(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        "Tab next  ←/→ runtime  Enter submit  Esc cancel",
+        dim,
+    )));
+
+    let popup_area = centered_popup(area, 56, lines.len() as u16 + 2);
+    let block = Block::default()
+        .title(format!(" Resubmit {} job(s) ", form.job_ids.len()))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(get_style(app, &app.theme.elements.popups.border));
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
 fn draw_space_menu_popup(f: &mut Frame, area: Rect, app: &App) {
     let pin_label = if app.is_pinned {
         "Unpin GC Root"
@@ -938,6 +1192,7 @@ fn draw_space_menu_popup(f: &mut Frame, area: Rect, app: &App) {
         &[
             ("r", "Run Selected"),
             ("c", "Cancel Selected"),
+            ("s", "Resubmit Failed"),
             ("y", "Yank Path"),
             ("e", "Explore (Yazi)"),
             ("p", pin_label),
@@ -958,6 +1213,7 @@ fn draw_g_menu_popup(f: &mut Frame, area: Rect, app: &App) {
             ("e", "Go to End"),
             ("d", "Definition"),
             ("l", "Logs"),
+            ("w", "Next Workspace Lab"),
             ("ESC", "Close Menu"),
         ],
     );
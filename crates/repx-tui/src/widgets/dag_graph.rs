@@ -0,0 +1,120 @@
+use crate::dag_view::Neighborhood;
+use crate::model::JobStatus;
+use ratatui::{
+    prelude::{Buffer, Modifier, Rect, Style},
+    widgets::Widget,
+};
+use std::collections::HashMap;
+
+pub const LAYER_HEIGHT: u16 = 3;
+
+pub const MIN_NODE_WIDTH: u16 = 10;
+
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+fn line_symbol(bits: u8) -> &'static str {
+    match bits {
+        b if b == LEFT | RIGHT | UP | DOWN => "┼",
+        b if b == LEFT | RIGHT | DOWN => "┬",
+        b if b == LEFT | RIGHT | UP => "┴",
+        b if b == UP | DOWN | RIGHT => "├",
+        b if b == UP | DOWN | LEFT => "┤",
+        b if b == DOWN | RIGHT => "┌",
+        b if b == DOWN | LEFT => "┐",
+        b if b == UP | RIGHT => "└",
+        b if b == UP | LEFT => "┘",
+        b if b & (LEFT | RIGHT) != 0 => "─",
+        _ => "│",
+    }
+}
+
+pub struct DagGraph<'a> {
+    pub graph: &'a Neighborhood,
+    pub status_style: &'a dyn Fn(&JobStatus) -> Style,
+    pub edge_style: Style,
+}
+
+impl Widget for DagGraph<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < MIN_NODE_WIDTH || area.height < 1 {
+            return;
+        }
+
+        let mut centres: Vec<Vec<u16>> = Vec::with_capacity(self.graph.layers.len());
+        for (depth, layer) in self.graph.layers.iter().enumerate() {
+            let slots = layer.nodes.len() + usize::from(layer.hidden > 0);
+            let slot_width = area.width / slots.max(1) as u16;
+            let y = area.top() + depth as u16 * LAYER_HEIGHT;
+            let mut layer_centres = Vec::with_capacity(layer.nodes.len());
+
+            let labels = layer
+                .nodes
+                .iter()
+                .map(|node| {
+                    let mut style = (self.status_style)(&node.status);
+                    if depth == self.graph.focus {
+                        style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+                    }
+                    (format!("● {}", node.label), style)
+                })
+                .chain((layer.hidden > 0).then(|| {
+                    (
+                        format!("+{} more", layer.hidden),
+                        self.edge_style.add_modifier(Modifier::ITALIC),
+                    )
+                }));
+            for (slot, (label, style)) in labels.enumerate() {
+                let width = label
+                    .chars()
+                    .count()
+                    .min(slot_width.saturating_sub(1) as usize);
+                let label: String = label.chars().take(width).collect();
+                let slot_start = area.left() + slot as u16 * slot_width;
+                let x = slot_start + (slot_width - width as u16) / 2;
+                if y < area.bottom() {
+                    buf.set_stringn(x, y, &label, width, style);
+                }
+                layer_centres.push(x + width as u16 / 2);
+            }
+            centres.push(layer_centres);
+        }
+
+        let mut lines: HashMap<(u16, u16), u8> = HashMap::new();
+        let mut arrows = Vec::new();
+        for &(layer, upper, lower) in &self.graph.edges {
+            let (Some(&from), Some(&to)) = (
+                centres.get(layer).and_then(|c| c.get(upper)),
+                centres.get(layer + 1).and_then(|c| c.get(lower)),
+            ) else {
+                continue;
+            };
+            let y = area.top() + layer as u16 * LAYER_HEIGHT + 1;
+            if from == to {
+                *lines.entry((from, y)).or_default() |= UP | DOWN;
+            } else {
+                let toward = if to > from { RIGHT } else { LEFT };
+                let back = if to > from { LEFT } else { RIGHT };
+                *lines.entry((from, y)).or_default() |= UP | toward;
+                for x in from.min(to) + 1..from.max(to) {
+                    *lines.entry((x, y)).or_default() |= LEFT | RIGHT;
+                }
+                *lines.entry((to, y)).or_default() |= back | DOWN;
+            }
+            arrows.push((to, y + 1));
+        }
+
+        for ((x, y), bits) in lines {
+            if y < area.bottom() && x < area.right() {
+                buf.set_string(x, y, line_symbol(bits), self.edge_style);
+            }
+        }
+        for (x, y) in arrows {
+            if y < area.bottom() && x < area.right() {
+                buf.set_string(x, y, "▼", self.edge_style);
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 pub mod braille_graph;
 pub mod color;
+pub mod dag_graph;
 pub mod stacked_bar_chart;
-pub mod tree_prefix;
 
 pub use braille_graph::{BrailleGraph, GraphDirection};
+pub use dag_graph::DagGraph;
+pub use repx_core::tree_prefix;
 pub use stacked_bar_chart::StackedBarChart;
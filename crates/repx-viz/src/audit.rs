@@ -0,0 +1,93 @@
+use repx_core::model::{JobId, Lab};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Default)]
+pub(crate) struct LabIssues {
+    pub detached: Vec<JobId>,
+    pub dangling: Vec<String>,
+}
+
+impl LabIssues {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.detached.is_empty() && self.dangling.is_empty()
+    }
+
+    pub(crate) fn messages(&self) -> Vec<String> {
+        let mut messages: Vec<String> = self
+            .detached
+            .iter()
+            .map(|jid| format!("Job '{}' is not part of any run.", jid))
+            .collect();
+        messages.extend(self.dangling.iter().cloned());
+        messages
+    }
+}
+
+pub(crate) fn audit_lab(lab: &Lab) -> LabIssues {
+    let in_run: BTreeSet<&JobId> = lab.runs.values().flat_map(|run| &run.jobs).collect();
+    let mut detached: Vec<JobId> = lab
+        .jobs
+        .keys()
+        .filter(|jid| !in_run.contains(jid))
+        .cloned()
+        .collect();
+    detached.sort();
+
+    let mut dangling = BTreeSet::new();
+    for (run_id, run) in &lab.runs {
+        for jid in &run.jobs {
+            if !lab.jobs.contains_key(jid) {
+                dangling.insert(format!(
+                    "Run '{}' lists job '{}', which is not in the lab.",
+                    run_id, jid
+                ));
+            }
+        }
+    }
+    for (jid, job) in &lab.jobs {
+        for exe in job.executables.values() {
+            for source in exe.inputs.iter().filter_map(|m| m.job_id.as_ref()) {
+                if !lab.jobs.contains_key(source) {
+                    dangling.insert(format!(
+                        "Job '{}' takes input from job '{}', which is not in the lab.",
+                        jid, source
+                    ));
+                }
+            }
+        }
+    }
+
+    LabIssues {
+        detached,
+        dangling: dangling.into_iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{job, lab};
+
+    #[test]
+    fn test_clean_lab_has_no_issues() {
+        assert!(audit_lab(&lab()).is_empty());
+    }
+
+    #[test]
+    fn test_detached_and_dangling_jobs_are_reported() {
+        let mut lab = lab();
+        lab.jobs
+            .insert(JobId::from("x1"), job("stray", Some("gone")));
+        if let Some(run) = lab.runs.values_mut().next() {
+            run.jobs.push(JobId::from("missing"));
+        }
+
+        let issues = audit_lab(&lab);
+        assert_eq!(issues.detached, vec![JobId::from("x1")]);
+        assert_eq!(issues.dangling.len(), 2);
+        assert!(issues
+            .messages()
+            .iter()
+            .any(|m| m.contains("'x1' takes input from job 'gone'")));
+    }
+}
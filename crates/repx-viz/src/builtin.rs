@@ -0,0 +1,594 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::generator::{Topology, VizGenerator};
+use crate::helpers::*;
+use crate::style::VizStyle;
+use crate::VizArgs;
+
+const CHAR_WIDTH: f64 = 7.0;
+const LINE_HEIGHT: f64 = 16.0;
+const NODE_PAD_X: f64 = 14.0;
+const NODE_PAD_Y: f64 = 10.0;
+const LAYER_GAP: f64 = 70.0;
+const NODE_GAP: f64 = 24.0;
+const MARGIN: f64 = 24.0;
+const CROSSING_SWEEPS: usize = 8;
+const EDGE_COLOR: &str = "#64748B";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EdgeStyle {
+    Solid,
+    Dashed,
+    Dotted,
+    Critical,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Node {
+    pub id: String,
+    pub lines: Vec<String>,
+    pub fill: String,
+    pub border: String,
+    pub penwidth: f64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub style: EdgeStyle,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Graph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    index: HashMap<String, usize>,
+}
+
+impl Graph {
+    pub fn add_node(&mut self, node: Node) -> usize {
+        if let Some(&idx) = self.index.get(&node.id) {
+            return idx;
+        }
+        let idx = self.nodes.len();
+        self.index.insert(node.id.clone(), idx);
+        self.nodes.push(node);
+        idx
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str, style: EdgeStyle) {
+        if let (Some(&from), Some(&to)) = (self.index.get(from), self.index.get(to)) {
+            if from != to {
+                self.edges.push(Edge { from, to, style });
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+#[derive(Debug)]
+pub(crate) struct Layout {
+    boxes: Vec<Rect>,
+    routes: Vec<Vec<(f64, f64)>>,
+    width: f64,
+    height: f64,
+}
+
+fn node_size(node: &Node) -> (f64, f64) {
+    let longest = node
+        .lines
+        .iter()
+        .map(|l| l.chars().count())
+        .max()
+        .unwrap_or(0);
+    (
+        longest as f64 * CHAR_WIDTH + 2.0 * NODE_PAD_X,
+        node.lines.len().max(1) as f64 * LINE_HEIGHT + 2.0 * NODE_PAD_Y,
+    )
+}
+
+fn acyclic_edges(graph: &Graph) -> Vec<(usize, usize)> {
+    let n = graph.nodes.len();
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for e in &graph.edges {
+        adjacency[e.from].push(e.to);
+    }
+
+    let mut state = vec![0u8; n];
+    let mut reversed: Vec<(usize, usize)> = Vec::new();
+    for root in 0..n {
+        if state[root] != 0 {
+            continue;
+        }
+        let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+        state[root] = 1;
+        while let Some((node, next)) = stack.pop() {
+            if let Some(&succ) = adjacency[node].get(next) {
+                stack.push((node, next + 1));
+                match state[succ] {
+                    0 => {
+                        state[succ] = 1;
+                        stack.push((succ, 0));
+                    }
+                    1 => reversed.push((node, succ)),
+                    _ => {}
+                }
+            } else {
+                state[node] = 2;
+            }
+        }
+    }
+
+    graph
+        .edges
+        .iter()
+        .map(|e| {
+            if reversed.contains(&(e.from, e.to)) {
+                (e.to, e.from)
+            } else {
+                (e.from, e.to)
+            }
+        })
+        .collect()
+}
+
+fn assign_layers(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut indegree = vec![0usize; n];
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for &(from, to) in edges {
+        indegree[to] += 1;
+        successors[from].push(to);
+    }
+    let mut layers = vec![0usize; n];
+    let mut ready: Vec<usize> = (0..n).filter(|&i| indegree[i] == 0).collect();
+    while let Some(node) = ready.pop() {
+        for &succ in &successors[node] {
+            layers[succ] = layers[succ].max(layers[node] + 1);
+            indegree[succ] -= 1;
+            if indegree[succ] == 0 {
+                ready.push(succ);
+            }
+        }
+    }
+    layers
+}
+
+fn barycenter(neighbors: &[usize], position: &[f64]) -> Option<f64> {
+    if neighbors.is_empty() {
+        return None;
+    }
+    Some(neighbors.iter().map(|&n| position[n]).sum::<f64>() / neighbors.len() as f64)
+}
+
+fn reduce_crossings(
+    order: &mut [Vec<usize>],
+    upper: &[Vec<usize>],
+    lower: &[Vec<usize>],
+    total: usize,
+) {
+    let mut position = vec![0.0; total];
+    let reindex = |order: &[Vec<usize>], position: &mut [f64]| {
+        for layer in order {
+            for (i, &v) in layer.iter().enumerate() {
+                position[v] = i as f64;
+            }
+        }
+    };
+    reindex(order, &mut position);
+
+    for sweep in 0..CROSSING_SWEEPS {
+        let downward = sweep % 2 == 0;
+        let layer_ids: Vec<usize> = if downward {
+            (1..order.len()).collect()
+        } else {
+            (0..order.len().saturating_sub(1)).rev().collect()
+        };
+        for l in layer_ids {
+            let neighbors = if downward { upper } else { lower };
+            let mut keyed: Vec<(f64, usize)> = order[l]
+                .iter()
+                .map(|&v| {
+                    (
+                        barycenter(&neighbors[v], &position).unwrap_or(position[v]),
+                        v,
+                    )
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            order[l] = keyed.into_iter().map(|(_, v)| v).collect();
+            for (i, &v) in order[l].iter().enumerate() {
+                position[v] = i as f64;
+            }
+        }
+    }
+}
+
+pub(crate) fn layout(graph: &Graph) -> Layout {
+    let real = graph.nodes.len();
+    let edges = acyclic_edges(graph);
+    let layers = assign_layers(real, &edges);
+
+    let mut node_layer = layers.clone();
+    let mut upper: Vec<Vec<usize>> = vec![Vec::new(); real];
+    let mut lower: Vec<Vec<usize>> = vec![Vec::new(); real];
+    let mut chains: Vec<Vec<usize>> = Vec::with_capacity(edges.len());
+    for &(from, to) in &edges {
+        let mut chain = vec![from];
+        let mut prev = from;
+        for layer in layers[from] + 1..layers[to] {
+            let dummy = node_layer.len();
+            node_layer.push(layer);
+            upper.push(vec![prev]);
+            lower.push(Vec::new());
+            lower[prev].push(dummy);
+            chain.push(dummy);
+            prev = dummy;
+        }
+        upper[to].push(prev);
+        lower[prev].push(to);
+        chain.push(to);
+        chains.push(chain);
+    }
+
+    let layer_count = node_layer.iter().max().map_or(0, |m| m + 1);
+    let mut order: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+    for (v, &l) in node_layer.iter().enumerate() {
+        order[l].push(v);
+    }
+    reduce_crossings(&mut order, &upper, &lower, node_layer.len());
+
+    let sizes: Vec<(f64, f64)> = (0..node_layer.len())
+        .map(|v| graph.nodes.get(v).map(node_size).unwrap_or((0.0, 0.0)))
+        .collect();
+    let column_heights: Vec<f64> = order
+        .iter()
+        .map(|layer| {
+            layer.iter().map(|&v| sizes[v].1).sum::<f64>()
+                + NODE_GAP * layer.len().saturating_sub(1) as f64
+        })
+        .collect();
+    let height = column_heights.iter().cloned().fold(0.0, f64::max);
+
+    let mut boxes = vec![
+        Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 0.0,
+            h: 0.0
+        };
+        node_layer.len()
+    ];
+    let mut x = MARGIN;
+    for (l, layer) in order.iter().enumerate() {
+        let column_width = layer.iter().map(|&v| sizes[v].0).fold(0.0, f64::max);
+        let mut y = MARGIN + (height - column_heights[l]) / 2.0;
+        for &v in layer {
+            let (w, h) = sizes[v];
+            boxes[v] = Rect {
+                x: x + (column_width - w) / 2.0,
+                y,
+                w,
+                h,
+            };
+            y += h + NODE_GAP;
+        }
+        x += column_width + LAYER_GAP;
+    }
+    let width = (x - LAYER_GAP + MARGIN).max(2.0 * MARGIN);
+
+    let routes = graph
+        .edges
+        .iter()
+        .zip(&chains)
+        .map(|(edge, chain)| {
+            let mut points: Vec<(f64, f64)> = Vec::with_capacity(chain.len());
+            for (i, &v) in chain.iter().enumerate() {
+                let r = boxes[v];
+                let cy = r.y + r.h / 2.0;
+                if i == 0 {
+                    points.push((r.x + r.w, cy));
+                } else {
+                    points.push((r.x, cy));
+                }
+            }
+            if chain.first() != Some(&edge.from) {
+                points.reverse();
+            }
+            points
+        })
+        .collect();
+
+    Layout {
+        boxes: boxes.into_iter().take(real).collect(),
+        routes,
+        width,
+        height: height + 2.0 * MARGIN,
+    }
+}
+
+pub(crate) fn render_svg(graph: &Graph, layout: &Layout, style: &VizStyle) -> String {
+    let mut svg = String::new();
+    dot_writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" viewBox=\"0 0 {:.0} {:.0}\" font-family=\"{}\" font-size=\"{}\">",
+        layout.width,
+        layout.height,
+        layout.width,
+        layout.height,
+        style.fonts.family,
+        style.fonts.stage_size
+    );
+    svg.push_str("  <defs>\n");
+    dot_writeln!(
+        svg,
+        "    <marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"7\" markerHeight=\"7\" orient=\"auto\"><path d=\"M0,0 L10,5 L0,10 z\" fill=\"{}\"/></marker>",
+        EDGE_COLOR
+    );
+    svg.push_str("  </defs>\n");
+    svg.push_str("  <rect width=\"100%\" height=\"100%\" fill=\"#FFFFFF\"/>\n");
+
+    for (edge, route) in graph.edges.iter().zip(&layout.routes) {
+        let points = route
+            .iter()
+            .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let (color, width, dash) = match edge.style {
+            EdgeStyle::Solid => (EDGE_COLOR, "1.2", ""),
+            EdgeStyle::Dashed => (EDGE_COLOR, "1.2", " stroke-dasharray=\"6,4\""),
+            EdgeStyle::Dotted => (EDGE_COLOR, "1.2", " stroke-dasharray=\"2,3\""),
+            EdgeStyle::Critical => (CRITICAL_COLOR, CRITICAL_PENWIDTH, ""),
+        };
+        dot_writeln!(
+            svg,
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"{} marker-end=\"url(#arrow)\"/>",
+            points,
+            color,
+            width,
+            dash
+        );
+    }
+
+    for (node, r) in graph.nodes.iter().zip(&layout.boxes) {
+        dot_writeln!(
+            svg,
+            "  <g id=\"{}\"><rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" rx=\"6\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+            escape_xml(&node.id),
+            r.x,
+            r.y,
+            r.w,
+            r.h,
+            escape_xml(&node.fill),
+            escape_xml(&node.border),
+            node.penwidth
+        );
+        for (i, line) in node.lines.iter().enumerate() {
+            dot_writeln!(
+                svg,
+                "    <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"middle\">{}</text>",
+                r.x + r.w / 2.0,
+                r.y + NODE_PAD_Y + LINE_HEIGHT * (i as f64 + 0.5),
+                escape_xml(line)
+            );
+        }
+        svg.push_str("  </g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+impl VizGenerator<'_> {
+    pub(crate) fn build_graph(&self, args: &VizArgs) -> Graph {
+        let Topology {
+            job_to_run,
+            pipeline_jobs,
+            pipeline_representative: _,
+            run_pipelines,
+        } = self.topology();
+        let mut graph = Graph::default();
+
+        if args.show_pipelines {
+            for (pipeline_name, job_ids) in &pipeline_jobs {
+                let clean_pipe = self.stage_node(pipeline_name);
+                let mut lines = vec![pipeline_name.clone()];
+                if self.aggregates() {
+                    lines.push(format!("(x{})", job_ids.len()));
+                }
+                if self.is_scatter_gather(&clean_pipe) {
+                    lines.push("scatter-gather".to_string());
+                }
+                lines.extend(self.stage_timing(job_ids));
+                let critical = self.is_critical_stage(&clean_pipe);
+                let stage_fill = self.style().stage_fill(pipeline_name);
+                let (fill, border, penwidth) = match self.stage_overlay(job_ids) {
+                    Some(overlay) => {
+                        lines.push(overlay.note);
+                        (
+                            overlay.fill,
+                            overlay.border,
+                            if critical { 3.5 } else { 2.0 },
+                        )
+                    }
+                    None if critical => (stage_fill, CRITICAL_COLOR, 3.5),
+                    None => (stage_fill, self.style().clusters.border.as_str(), 1.0),
+                };
+                graph.add_node(Node {
+                    id: format!("pipe_{}", clean_pipe),
+                    lines,
+                    fill: fill.to_string(),
+                    border: border.to_string(),
+                    penwidth,
+                });
+
+                if args.show_params {
+                    for (p_key, p_vals) in self.stage_params(job_ids) {
+                        let param_id = format!("pparam_{}_{}", clean_pipe, clean_id(&p_key));
+                        let vals: Vec<String> = p_vals
+                            .iter()
+                            .map(|v| smart_truncate(v, PARAM_MAX_WIDTH))
+                            .collect();
+                        graph.add_node(Node {
+                            id: param_id.clone(),
+                            lines: vec![format!("{}:", p_key), vals.join(", ")],
+                            fill: self.style().params.fill.clone(),
+                            border: self.style().params.border.clone(),
+                            penwidth: 0.8,
+                        });
+                        graph.add_edge(
+                            &param_id,
+                            &format!("pipe_{}", clean_pipe),
+                            EdgeStyle::Dotted,
+                        );
+                    }
+                }
+            }
+
+            if args.show_intra_edges {
+                for (clean_src, clean_tgt) in self.collect_intra_edges() {
+                    let style = if self.is_critical_edge(&clean_src, &clean_tgt) {
+                        EdgeStyle::Critical
+                    } else {
+                        EdgeStyle::Solid
+                    };
+                    graph.add_edge(
+                        &format!("pipe_{}", clean_src),
+                        &format!("pipe_{}", clean_tgt),
+                        style,
+                    );
+                }
+            }
+            if args.show_inter_edges {
+                for (clean_src, clean_tgt, style) in self.collect_inter_run_edges(&job_to_run) {
+                    let style = if self.is_critical_edge(&clean_src, &clean_tgt) {
+                        EdgeStyle::Critical
+                    } else if style == "dashed" {
+                        EdgeStyle::Dashed
+                    } else {
+                        EdgeStyle::Solid
+                    };
+                    graph.add_edge(
+                        &format!("pipe_{}", clean_src),
+                        &format!("pipe_{}", clean_tgt),
+                        style,
+                    );
+                }
+            }
+        }
+
+        if args.show_runs {
+            let run_to_group: BTreeMap<String, String> = if args.show_groups {
+                self.run_to_group().into_iter().collect()
+            } else {
+                BTreeMap::new()
+            };
+            for (run_name, pipelines) in &run_pipelines {
+                let mut lines = Vec::new();
+                if let Some(group) = run_to_group.get(run_name) {
+                    lines.push(format!("@{}", group));
+                }
+                lines.push(run_name.clone());
+                lines.push(format!(
+                    "pipelines: {}",
+                    pipelines.iter().cloned().collect::<Vec<_>>().join(", ")
+                ));
+                let run_node = format!("run_{}", self.run_node(run_name));
+                let (fill, border, penwidth) = if self.is_detached_run(run_name) {
+                    (DETACHED_FILL, DETACHED_COLOR, 2.0)
+                } else {
+                    (
+                        self.style().palette.run_fill.as_str(),
+                        self.style().clusters.border.as_str(),
+                        1.5,
+                    )
+                };
+                graph.add_node(Node {
+                    id: run_node.clone(),
+                    lines,
+                    fill: fill.to_string(),
+                    border: border.to_string(),
+                    penwidth,
+                });
+                if args.show_pipelines {
+                    for pipeline_name in pipelines {
+                        graph.add_edge(
+                            &run_node,
+                            &format!("pipe_{}", self.stage_node(pipeline_name)),
+                            EdgeStyle::Dashed,
+                        );
+                    }
+                }
+            }
+        }
+
+        graph
+    }
+
+    pub fn generate_svg(&self, args: &VizArgs) -> String {
+        let graph = self.build_graph(args);
+        render_svg(&graph, &layout(&graph), self.style())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            lines: vec![id.to_string()],
+            fill: "#FFFFFF".to_string(),
+            border: "#000000".to_string(),
+            penwidth: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_layers_follow_longest_path() {
+        let mut graph = Graph::default();
+        for id in ["a", "b", "c", "d"] {
+            graph.add_node(node(id));
+        }
+        graph.add_edge("a", "b", EdgeStyle::Solid);
+        graph.add_edge("b", "c", EdgeStyle::Solid);
+        graph.add_edge("a", "c", EdgeStyle::Solid);
+        graph.add_edge("d", "c", EdgeStyle::Dashed);
+
+        let layers = assign_layers(graph.nodes.len(), &acyclic_edges(&graph));
+        assert_eq!(layers, vec![0, 1, 2, 0]);
+        assert_eq!(layout(&graph).routes[2].len(), 3);
+    }
+
+    #[test]
+    fn test_cycles_are_laid_out() {
+        let mut graph = Graph::default();
+        for id in ["a", "b"] {
+            graph.add_node(node(id));
+        }
+        graph.add_edge("a", "b", EdgeStyle::Solid);
+        graph.add_edge("b", "a", EdgeStyle::Solid);
+
+        let layers = assign_layers(graph.nodes.len(), &acyclic_edges(&graph));
+        assert_ne!(layers[0], layers[1]);
+        assert_eq!(layout(&graph).routes.len(), 2);
+    }
+
+    #[test]
+    fn test_svg_escapes_labels() {
+        let mut graph = Graph::default();
+        graph.add_node(node("a<b>&c"));
+        let svg = render_svg(&graph, &layout(&graph), &VizStyle::default());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("a&lt;b&gt;&amp;c"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
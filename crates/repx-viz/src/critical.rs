@@ -0,0 +1,196 @@
+use repx_core::model::{Job, JobId, Lab};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+const FALLBACK_DURATION: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CriticalPath {
+    pub jobs: Vec<JobId>,
+    pub total: Duration,
+    stages: HashSet<String>,
+    stage_edges: HashSet<(String, String)>,
+}
+
+impl CriticalPath {
+    pub(crate) fn contains_stage(&self, clean_name: &str) -> bool {
+        self.stages.contains(clean_name)
+    }
+
+    pub(crate) fn contains_edge(&self, clean_src: &str, clean_tgt: &str) -> bool {
+        self.stage_edges
+            .contains(&(clean_src.to_string(), clean_tgt.to_string()))
+    }
+}
+
+fn estimated_duration(job: &Job) -> Option<Duration> {
+    job.resource_hints
+        .iter()
+        .chain(
+            job.executables
+                .values()
+                .filter_map(|exe| exe.resource_hints.as_ref()),
+        )
+        .filter_map(|hints| hints.time.as_ref()?.to_seconds())
+        .max()
+        .map(Duration::from_secs)
+}
+
+pub(crate) fn job_duration(
+    job_id: &JobId,
+    job: &Job,
+    recorded: &HashMap<JobId, Duration>,
+) -> Duration {
+    recorded
+        .get(job_id)
+        .copied()
+        .or_else(|| estimated_duration(job))
+        .unwrap_or(FALLBACK_DURATION)
+}
+
+pub(crate) fn critical_path(
+    lab: &Lab,
+    recorded: &HashMap<JobId, Duration>,
+    stage_name: impl Fn(&JobId) -> String,
+) -> CriticalPath {
+    let mut ids: Vec<&JobId> = lab.jobs.keys().collect();
+    ids.sort();
+
+    let mut finish: HashMap<&JobId, (Duration, Option<&JobId>)> = HashMap::new();
+    let mut visiting: HashSet<&JobId> = HashSet::new();
+    for root in &ids {
+        let mut stack: Vec<(&JobId, bool)> = vec![(root, false)];
+        while let Some((jid, expanded)) = stack.pop() {
+            if finish.contains_key(jid) {
+                continue;
+            }
+            let job = &lab.jobs[jid];
+            let mut deps: Vec<&JobId> = job
+                .all_dependencies()
+                .filter(|dep| lab.jobs.contains_key(*dep) && *dep != jid)
+                .collect();
+            deps.sort();
+            deps.dedup();
+
+            if !expanded {
+                if !visiting.insert(jid) {
+                    continue;
+                }
+                stack.push((jid, true));
+                for dep in deps {
+                    if !finish.contains_key(dep) && !visiting.contains(dep) {
+                        stack.push((dep, false));
+                    }
+                }
+                continue;
+            }
+
+            let mut best: Option<(Duration, &JobId)> = None;
+            for dep in deps {
+                if let Some((dep_finish, _)) = finish.get(dep) {
+                    if best.is_none_or(|(current, _)| *dep_finish > current) {
+                        best = Some((*dep_finish, dep));
+                    }
+                }
+            }
+            let start = best.map(|(d, _)| d).unwrap_or_default();
+            finish.insert(
+                jid,
+                (
+                    start + job_duration(jid, job, recorded),
+                    best.map(|(_, d)| d),
+                ),
+            );
+            visiting.remove(jid);
+        }
+    }
+
+    let mut end: Option<(&JobId, Duration)> = None;
+    for jid in &ids {
+        let (total, _) = finish[jid];
+        if end.is_none_or(|(_, best)| total > best) {
+            end = Some((jid, total));
+        }
+    }
+    let Some((end, total)) = end else {
+        return CriticalPath::default();
+    };
+
+    let mut jobs = vec![end.clone()];
+    let mut current = end;
+    while let Some(prev) = finish[current].1 {
+        jobs.push(prev.clone());
+        current = prev;
+    }
+    jobs.reverse();
+
+    let stages = jobs.iter().map(&stage_name).collect();
+    let stage_edges = jobs
+        .windows(2)
+        .map(|pair| (stage_name(&pair[0]), stage_name(&pair[1])))
+        .filter(|(src, tgt)| src != tgt)
+        .collect();
+
+    CriticalPath {
+        jobs,
+        total,
+        stages,
+        stage_edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{job, lab};
+
+    fn stage_names(lab: &Lab) -> impl Fn(&JobId) -> String + '_ {
+        |jid| lab.jobs[jid].name.clone().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_critical_path_follows_longest_chain() {
+        let mut lab = lab();
+        lab.jobs
+            .insert(JobId::from("side"), job("side", Some("p1")));
+
+        let recorded = HashMap::from([
+            (JobId::from("p1"), Duration::from_secs(10)),
+            (JobId::from("c1"), Duration::from_secs(20)),
+            (JobId::from("r1"), Duration::from_secs(5)),
+            (JobId::from("side"), Duration::from_secs(20)),
+        ]);
+        let path = critical_path(&lab, &recorded, stage_names(&lab));
+        assert_eq!(
+            path.jobs,
+            vec![JobId::from("p1"), JobId::from("c1"), JobId::from("r1")]
+        );
+        assert_eq!(path.total, Duration::from_secs(35));
+        assert!(path.contains_stage("consumer"));
+        assert!(!path.contains_stage("side"));
+        assert!(path.contains_edge("producer", "consumer"));
+        assert!(!path.contains_edge("producer", "side"));
+
+        let recorded = HashMap::from([(JobId::from("side"), Duration::from_secs(300))]);
+        let path = critical_path(&lab, &recorded, stage_names(&lab));
+        assert_eq!(path.jobs, vec![JobId::from("p1"), JobId::from("side")]);
+    }
+
+    #[test]
+    fn test_duration_falls_back_to_time_hint() {
+        let mut hinted = job("hinted", None);
+        hinted.resource_hints = Some(repx_core::model::ResourceHints {
+            time: Some("01:00:00".into()),
+            ..Default::default()
+        });
+        let none = HashMap::new();
+        assert_eq!(
+            job_duration(&JobId::from("h"), &hinted, &none),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            job_duration(&JobId::from("x"), &job("plain", None), &none),
+            FALLBACK_DURATION
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use repx_core::model::{Job, JobId, Lab};
+use std::collections::HashMap;
+
+use crate::helpers::StageOverlay;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum JobChange {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+impl JobChange {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            JobChange::Added => "added",
+            JobChange::Removed => "removed",
+            JobChange::Changed => "changed",
+            JobChange::Unchanged => "unchanged",
+        }
+    }
+}
+
+fn match_key(job: &Job) -> (Option<&str>, String) {
+    (job.name.as_deref(), job.params.to_string())
+}
+
+pub(crate) fn diff_labs(new: &Lab, old: &Lab) -> (Lab, HashMap<JobId, JobChange>) {
+    let mut changes: HashMap<JobId, JobChange> = HashMap::new();
+    let mut renamed: HashMap<JobId, JobId> = HashMap::new();
+
+    let mut old_by_key: HashMap<(Option<&str>, String), Vec<&JobId>> = HashMap::new();
+    for (jid, job) in &old.jobs {
+        if !new.jobs.contains_key(jid) {
+            old_by_key.entry(match_key(job)).or_default().push(jid);
+        }
+    }
+    for ids in old_by_key.values_mut() {
+        ids.sort();
+    }
+
+    let mut new_ids: Vec<&JobId> = new.jobs.keys().collect();
+    new_ids.sort();
+    for jid in new_ids {
+        if old.jobs.contains_key(jid) {
+            changes.insert(jid.clone(), JobChange::Unchanged);
+            continue;
+        }
+        let matched = old_by_key
+            .get_mut(&match_key(&new.jobs[jid]))
+            .filter(|ids| !ids.is_empty())
+            .map(|ids| ids.remove(0));
+        match matched {
+            Some(old_id) => {
+                renamed.insert(old_id.clone(), jid.clone());
+                changes.insert(jid.clone(), JobChange::Changed);
+            }
+            None => {
+                changes.insert(jid.clone(), JobChange::Added);
+            }
+        }
+    }
+
+    let mut merged = new.clone();
+    for (jid, job) in &old.jobs {
+        if changes.contains_key(jid) || renamed.contains_key(jid) {
+            continue;
+        }
+        let mut job = job.clone();
+        for exe in job.executables.values_mut() {
+            for input in &mut exe.inputs {
+                if let Some(target) = input.job_id.as_ref().and_then(|dep| renamed.get(dep)) {
+                    input.job_id = Some(target.clone());
+                }
+            }
+        }
+        merged.jobs.insert(jid.clone(), job);
+        changes.insert(jid.clone(), JobChange::Removed);
+    }
+
+    for (run_id, run) in &old.runs {
+        let removed: Vec<JobId> = run
+            .jobs
+            .iter()
+            .filter(|jid| changes.get(*jid) == Some(&JobChange::Removed))
+            .cloned()
+            .collect();
+        match merged.runs.get_mut(run_id) {
+            Some(existing) => existing.jobs.extend(removed),
+            None if !removed.is_empty() => {
+                let mut run = run.clone();
+                run.jobs = removed;
+                merged.runs.insert(run_id.clone(), run);
+            }
+            None => {}
+        }
+    }
+
+    for (group, runs) in &old.groups {
+        let entry = merged.groups.entry(group.clone()).or_default();
+        for run_id in runs {
+            if merged.runs.contains_key(run_id) && !entry.contains(run_id) {
+                entry.push(run_id.clone());
+            }
+        }
+    }
+
+    (merged, changes)
+}
+
+pub(crate) fn diff_overlay<'a>(
+    changes: impl IntoIterator<Item = Option<&'a JobChange>>,
+) -> Option<StageOverlay> {
+    let (mut added, mut removed, mut changed, mut unchanged) = (0, 0, 0, 0);
+    for change in changes {
+        match change {
+            Some(JobChange::Added) => added += 1,
+            Some(JobChange::Removed) => removed += 1,
+            Some(JobChange::Changed) => changed += 1,
+            Some(JobChange::Unchanged) | None => unchanged += 1,
+        }
+    }
+    if added + removed + changed == 0 {
+        return None;
+    }
+
+    let mut note = Vec::new();
+    if added > 0 {
+        note.push(format!("+{}", added));
+    }
+    if removed > 0 {
+        note.push(format!("-{}", removed));
+    }
+    if changed > 0 {
+        note.push(format!("~{}", changed));
+    }
+    let note = note.join(" ");
+
+    let overlay = if added > 0 && removed + changed + unchanged == 0 {
+        StageOverlay {
+            class: "added",
+            fill: "#DCFCE7",
+            border: "#16A34A",
+            note,
+        }
+    } else if removed > 0 && added + changed + unchanged == 0 {
+        StageOverlay {
+            class: "removed",
+            fill: "#FEE2E2",
+            border: "#DC2626",
+            note,
+        }
+    } else {
+        StageOverlay {
+            class: "changed",
+            fill: "#FEF9C3",
+            border: "#CA8A04",
+            note,
+        }
+    };
+    Some(overlay)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{job, lab};
+    use repx_core::model::RunId;
+
+    #[test]
+    fn test_diff_labs_classifies_jobs() {
+        let old = lab();
+        let mut new = lab();
+
+        let consumer = new.jobs.remove(&JobId::from("c1")).unwrap();
+        new.jobs.insert(JobId::from("c2"), consumer);
+        new.jobs
+            .insert(JobId::from("r1"), job("report", Some("c2")));
+        new.jobs.remove(&JobId::from("p1"));
+        new.jobs.insert(JobId::from("p9"), job("fresh", None));
+        new.runs.insert(
+            RunId::from("sim"),
+            repx_core::model::Run {
+                image: None,
+                jobs: vec![JobId::from("p9"), JobId::from("c2")],
+                dependencies: HashMap::new(),
+            },
+        );
+
+        let (merged, changes) = diff_labs(&new, &old);
+        assert_eq!(changes[&JobId::from("c2")], JobChange::Changed);
+        assert_eq!(changes[&JobId::from("r1")], JobChange::Unchanged);
+        assert_eq!(changes[&JobId::from("p9")], JobChange::Added);
+        assert_eq!(changes[&JobId::from("p1")], JobChange::Removed);
+        assert!(!changes.contains_key(&JobId::from("c1")));
+        assert!(merged.jobs.contains_key(&JobId::from("p1")));
+        assert!(merged.runs[&RunId::from("sim")]
+            .jobs
+            .contains(&JobId::from("p1")));
+    }
+
+    #[test]
+    fn test_diff_overlay_colors() {
+        assert_eq!(diff_overlay([Some(&JobChange::Unchanged), None]), None);
+
+        let added = diff_overlay([Some(&JobChange::Added), Some(&JobChange::Added)]);
+        assert_eq!(
+            added.map(|o| (o.class, o.note)),
+            Some(("added", "+2".to_string()))
+        );
+
+        let removed = diff_overlay([Some(&JobChange::Removed)]);
+        assert_eq!(removed.map(|o| o.class), Some("removed"));
+
+        let mixed = diff_overlay([
+            Some(&JobChange::Added),
+            Some(&JobChange::Removed),
+            Some(&JobChange::Unchanged),
+        ]);
+        assert_eq!(
+            mixed.map(|o| (o.class, o.note)),
+            Some(("changed", "+1 -1".to_string()))
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use repx_core::engine::JobStatus;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::generator::VizGenerator;
+use crate::helpers::escape_xml;
+use crate::Result;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GraphNode {
+    pub id: String,
+    pub name: Option<String>,
+    pub run: Option<String>,
+    pub stage_type: String,
+    pub params: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub dependency: String,
+    pub input: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GraphExport {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+fn status_name(status: &JobStatus) -> &'static str {
+    match status {
+        JobStatus::Succeeded { .. } => "succeeded",
+        JobStatus::Failed { .. } => "failed",
+        JobStatus::TimedOut { .. } => "timed-out",
+        JobStatus::Cancelled { .. } => "cancelled",
+        JobStatus::Lost { .. } => "lost",
+        JobStatus::Running => "running",
+        JobStatus::Queued => "queued",
+        JobStatus::Pending => "pending",
+        JobStatus::Blocked { .. } => "blocked",
+    }
+}
+
+impl VizGenerator<'_> {
+    pub(crate) fn export_graph(&self) -> GraphExport {
+        let mut job_to_run: BTreeMap<&str, &str> = BTreeMap::new();
+        for (run_id, run) in &self.lab.runs {
+            for jid in &run.jobs {
+                job_to_run.insert(jid.as_str(), run_id.as_str());
+            }
+        }
+
+        let mut job_ids: Vec<_> = self.lab.jobs.keys().collect();
+        job_ids.sort();
+
+        let mut nodes = Vec::with_capacity(job_ids.len());
+        let mut edges: BTreeSet<(String, String, String, String)> = BTreeSet::new();
+        for jid in job_ids {
+            let job = &self.lab.jobs[jid];
+            nodes.push(GraphNode {
+                id: jid.to_string(),
+                name: job.name.clone(),
+                run: job_to_run.get(jid.as_str()).map(|r| r.to_string()),
+                stage_type: job.stage_type.to_string(),
+                params: job.params.clone(),
+                status: self.job_status(jid).map(status_name),
+                change: self.job_change(jid).map(|c| c.name()),
+            });
+
+            for exe in job.executables.values() {
+                for mapping in &exe.inputs {
+                    let dependency = mapping
+                        .dependency_type
+                        .map(|d| d.to_string())
+                        .unwrap_or_else(|| "hard".to_string());
+                    if let Some(src) = &mapping.job_id {
+                        if self.lab.jobs.contains_key(src) {
+                            edges.insert((
+                                src.to_string(),
+                                jid.to_string(),
+                                dependency.clone(),
+                                mapping.target_input.clone(),
+                            ));
+                        }
+                    }
+                    if let Some(run) = mapping
+                        .source_run
+                        .as_ref()
+                        .and_then(|r| self.lab.runs.get(r))
+                    {
+                        for src in &run.jobs {
+                            let Some(src_job) = self.lab.jobs.get(src) else {
+                                continue;
+                            };
+                            if let Some(filter) = &mapping.source_stage_filter {
+                                if src_job.name.as_deref() != Some(filter.as_str()) {
+                                    continue;
+                                }
+                            }
+                            edges.insert((
+                                src.to_string(),
+                                jid.to_string(),
+                                dependency.clone(),
+                                mapping.target_input.clone(),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        GraphExport {
+            nodes,
+            edges: edges
+                .into_iter()
+                .map(|(source, target, dependency, input)| GraphEdge {
+                    source,
+                    target,
+                    dependency,
+                    input,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl GraphExport {
+    pub(crate) fn to_json(&self) -> Result<String> {
+        let mut json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        json.push('\n');
+        Ok(json)
+    }
+
+    pub(crate) fn to_graphml(&self) -> String {
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        for (id, domain, name) in [
+            ("name", "node", "name"),
+            ("run", "node", "run"),
+            ("stage_type", "node", "stage_type"),
+            ("params", "node", "params"),
+            ("status", "node", "status"),
+            ("change", "node", "change"),
+            ("dependency", "edge", "dependency"),
+            ("input", "edge", "input"),
+        ] {
+            dot_writeln!(
+                xml,
+                "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"string\"/>",
+                id,
+                domain,
+                name
+            );
+        }
+        xml.push_str("  <graph id=\"repx\" edgedefault=\"directed\">\n");
+        for node in &self.nodes {
+            dot_writeln!(xml, "    <node id=\"{}\">", escape_xml(&node.id));
+            let params = serde_json::to_string(&node.params).unwrap_or_default();
+            let fields = [
+                ("name", node.name.as_deref()),
+                ("run", node.run.as_deref()),
+                ("stage_type", Some(node.stage_type.as_str())),
+                ("params", Some(params.as_str())),
+                ("status", node.status),
+                ("change", node.change),
+            ];
+            for (key, value) in fields {
+                if let Some(value) = value {
+                    dot_writeln!(
+                        xml,
+                        "      <data key=\"{}\">{}</data>",
+                        key,
+                        escape_xml(value)
+                    );
+                }
+            }
+            xml.push_str("    </node>\n");
+        }
+        for (i, edge) in self.edges.iter().enumerate() {
+            dot_writeln!(
+                xml,
+                "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">",
+                i,
+                escape_xml(&edge.source),
+                escape_xml(&edge.target)
+            );
+            dot_writeln!(
+                xml,
+                "      <data key=\"dependency\">{}</data>",
+                escape_xml(&edge.dependency)
+            );
+            dot_writeln!(
+                xml,
+                "      <data key=\"input\">{}</data>",
+                escape_xml(&edge.input)
+            );
+            xml.push_str("    </edge>\n");
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::fixtures::lab;
+
+    #[test]
+    fn test_export_has_job_nodes_and_dependency_edges() {
+        let lab = lab();
+        let graph = VizGenerator::new(&lab).export_graph();
+
+        let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["c1", "p1", "r1"]);
+        assert_eq!(graph.nodes[1].run.as_deref(), Some("sim"));
+        assert_eq!(graph.edges.len(), 2);
+        assert!(graph
+            .edges
+            .iter()
+            .any(|e| e.source == "p1" && e.target == "c1" && e.dependency == "hard"));
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+        assert!(json["nodes"][0].get("status").is_none());
+    }
+
+    #[test]
+    fn test_graphml_is_well_formed() {
+        let lab = lab();
+        let xml = VizGenerator::new(&lab).export_graph().to_graphml();
+        assert!(xml.contains("<node id=\"p1\">"));
+        assert!(xml.contains("<edge id=\"e0\""));
+        assert_eq!(
+            xml.matches("<node ").count(),
+            xml.matches("</node>").count()
+        );
+        assert!(xml.trim_end().ends_with("</graphml>"));
+    }
+}
@@ -0,0 +1,145 @@
+use regex::Regex;
+use repx_core::model::{JobId, Lab};
+use std::collections::HashSet;
+
+use crate::{Result, VizArgs, VizError};
+
+pub(crate) fn is_filtered(args: &VizArgs) -> bool {
+    args.filter_run.is_some() || args.filter_group.is_some() || args.filter_job.is_some()
+}
+
+fn jobs_in_runs<'a>(lab: &'a Lab, runs: impl IntoIterator<Item = &'a str>) -> HashSet<&'a JobId> {
+    let mut jobs = HashSet::new();
+    for run_name in runs {
+        if let Some((_, run)) = lab.runs.iter().find(|(id, _)| id.as_str() == run_name) {
+            jobs.extend(run.jobs.iter());
+        }
+    }
+    jobs
+}
+
+fn matching_jobs<'a>(lab: &'a Lab, args: &VizArgs) -> Result<HashSet<&'a JobId>> {
+    let mut matched: HashSet<&JobId> = lab.jobs.keys().collect();
+
+    if let Some(run_name) = &args.filter_run {
+        if !lab.runs.keys().any(|id| id.as_str() == run_name) {
+            return Err(VizError::Usage(format!(
+                "Run '{}' not found in lab.",
+                run_name
+            )));
+        }
+        let in_run = jobs_in_runs(lab, [run_name.as_str()]);
+        matched.retain(|jid| in_run.contains(jid));
+    }
+
+    if let Some(group_name) = &args.filter_group {
+        let runs = lab
+            .groups
+            .get(group_name)
+            .ok_or_else(|| VizError::Usage(format!("Group '{}' not found in lab.", group_name)))?;
+        let in_group = jobs_in_runs(lab, runs.iter().map(|r| r.as_str()));
+        matched.retain(|jid| in_group.contains(jid));
+    }
+
+    if let Some(pattern) = &args.filter_job {
+        let re = Regex::new(pattern).map_err(|e| {
+            VizError::Usage(format!("Invalid --filter-job pattern '{}': {}", pattern, e))
+        })?;
+        matched.retain(|jid| {
+            re.is_match(jid.as_str())
+                || lab.jobs[*jid]
+                    .name
+                    .as_deref()
+                    .is_some_and(|name| re.is_match(name))
+        });
+    }
+
+    Ok(matched)
+}
+
+pub(crate) fn filter_lab(lab: &Lab, args: &VizArgs) -> Result<Lab> {
+    let matched = matching_jobs(lab, args)?;
+    if matched.is_empty() {
+        return Err(VizError::Usage(
+            "No jobs match the given filters.".to_string(),
+        ));
+    }
+
+    let mut visible: HashSet<JobId> = matched.iter().map(|jid| (*jid).clone()).collect();
+    for (jid, job) in &lab.jobs {
+        let depends_on_match = job.all_dependencies().any(|dep| matched.contains(dep));
+        if depends_on_match {
+            visible.insert(jid.clone());
+        }
+        if matched.contains(jid) {
+            visible.extend(job.all_dependencies().cloned());
+        }
+    }
+
+    let mut filtered = lab.clone();
+    filtered.jobs.retain(|jid, _| visible.contains(jid));
+    for run in filtered.runs.values_mut() {
+        run.jobs.retain(|jid| visible.contains(jid));
+    }
+    filtered.runs.retain(|_, run| !run.jobs.is_empty());
+    for runs in filtered.groups.values_mut() {
+        runs.retain(|rid| filtered.runs.contains_key(rid));
+    }
+    filtered.groups.retain(|_, runs| !runs.is_empty());
+    Ok(filtered)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{lab, viz_args};
+
+    fn job_names(lab: &Lab) -> Vec<String> {
+        let mut names: Vec<String> = lab.jobs.keys().map(|j| j.to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_filter_job_keeps_direct_neighbors() {
+        let lab = lab();
+        let mut args = viz_args();
+        args.filter_job = Some("^report$".to_string());
+
+        let filtered = filter_lab(&lab, &args).unwrap();
+        assert_eq!(job_names(&filtered), vec!["c1", "r1"]);
+        assert!(filtered.runs.contains_key(&"sim".into()));
+    }
+
+    #[test]
+    fn test_filter_group_and_run() {
+        let lab = lab();
+        let mut args = viz_args();
+        args.filter_group = Some("reports".to_string());
+        let filtered = filter_lab(&lab, &args).unwrap();
+        assert_eq!(job_names(&filtered), vec!["c1", "r1"]);
+
+        let mut args = viz_args();
+        args.filter_run = Some("sim".to_string());
+        let filtered = filter_lab(&lab, &args).unwrap();
+        assert_eq!(job_names(&filtered), vec!["c1", "p1", "r1"]);
+        assert_eq!(filtered.groups.len(), 1);
+    }
+
+    #[test]
+    fn test_filter_errors() {
+        let lab = lab();
+        let mut args = viz_args();
+        args.filter_run = Some("missing".to_string());
+        assert!(filter_lab(&lab, &args).is_err());
+
+        let mut args = viz_args();
+        args.filter_job = Some("(".to_string());
+        assert!(filter_lab(&lab, &args).is_err());
+
+        let mut args = viz_args();
+        args.filter_job = Some("nothing-matches".to_string());
+        assert!(filter_lab(&lab, &args).is_err());
+    }
+}
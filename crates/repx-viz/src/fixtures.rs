@@ -0,0 +1,107 @@
+use repx_core::model::{Executable, InputMapping, Job, JobId, Lab, Run, RunId, StageType};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::{GraphvizLayout, RankDir, VizArgs, VizEngine};
+
+pub(crate) fn job(name: &str, input: Option<&str>) -> Job {
+    let inputs = input
+        .map(|src| InputMapping {
+            job_id: Some(JobId::from(src)),
+            source_output: None,
+            target_input: "x".to_string(),
+            source: None,
+            source_key: None,
+            mapping_type: None,
+            dependency_type: None,
+            source_run: None,
+            source_stage_filter: None,
+        })
+        .into_iter()
+        .collect();
+    Job {
+        name: Some(name.to_string()),
+        params: serde_json::Value::Null,
+        path_in_lab: PathBuf::new(),
+        stage_type: StageType::Simple,
+        executables: HashMap::from([(
+            "main".to_string(),
+            Executable {
+                path: PathBuf::from("echo"),
+                inputs,
+                outputs: HashMap::new(),
+                resource_hints: None,
+                deps: vec![],
+                interpreter: None,
+                args: vec![],
+                image: None,
+                runtime: None,
+            },
+        )]),
+        resource_hints: None,
+        data_mounts: vec![],
+    }
+}
+
+fn run(jobs: &[&str]) -> Run {
+    Run {
+        image: None,
+        jobs: jobs.iter().map(|j| JobId::from(*j)).collect(),
+        dependencies: HashMap::new(),
+    }
+}
+
+pub(crate) fn lab() -> Lab {
+    Lab {
+        repx_version: "0.5.0".to_string(),
+        lab_version: "1.0.0".to_string(),
+        git_hash: "abc".to_string(),
+        content_hash: "abc".to_string(),
+        runs: HashMap::from([
+            (RunId::from("sim"), run(&["p1", "c1"])),
+            (RunId::from("analysis"), run(&["r1"])),
+        ]),
+        jobs: HashMap::from([
+            (JobId::from("p1"), job("producer", None)),
+            (JobId::from("c1"), job("consumer", Some("p1"))),
+            (JobId::from("r1"), job("report", Some("c1"))),
+        ]),
+        groups: HashMap::from([("reports".to_string(), vec![RunId::from("analysis")])]),
+        host_tools_path: PathBuf::new(),
+        host_tools_dir_name: "tools".to_string(),
+        referenced_files: vec![],
+        tar_dir_name: None,
+    }
+}
+
+pub(crate) fn viz_args() -> VizArgs {
+    VizArgs {
+        lab: PathBuf::from("./result"),
+        output: None,
+        format: None,
+        engine: VizEngine::Graphviz,
+        graphviz_layout: GraphvizLayout::Dot,
+        rankdir: RankDir::LeftRight,
+        nodesep: None,
+        ranksep: None,
+        show_pipelines: true,
+        show_runs: false,
+        show_groups: false,
+        show_params: false,
+        aggregate: true,
+        max_nodes: None,
+        show_intra_edges: true,
+        show_inter_edges: true,
+        edge_labels: false,
+        filter_run: None,
+        filter_group: None,
+        filter_job: None,
+        job_statuses: None,
+        diff: None,
+        style: None,
+        critical_path: false,
+        job_durations: None,
+        job_timings: None,
+        strict: false,
+    }
+}
@@ -1,8 +1,16 @@
-use repx_core::model::{Job, JobId, Lab, StageType};
+use repx_core::engine::JobStatus;
+use repx_core::model::{InputMapping, Job, JobId, Lab, StageType};
+use repx_core::store::outcomes::JobTiming;
 use serde_json::Value;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::Duration;
 
+use crate::critical::{critical_path, CriticalPath};
+use crate::diff::{diff_overlay, JobChange};
 use crate::helpers::*;
+use crate::lod::collapse_runs;
+use crate::registry::NodeRegistry;
+use crate::style::VizStyle;
 use crate::VizArgs;
 
 fn pipe_node(clean_name: &str) -> String {
@@ -37,13 +45,39 @@ fn resolve_sg_target(clean_name: &str, is_sg: bool) -> String {
     }
 }
 
+fn mapping_label(mapping: &InputMapping) -> String {
+    match mapping.source_output.as_deref() {
+        Some(output) if output != mapping.target_input => {
+            format!("{} → {}", output, mapping.target_input)
+        }
+        _ => mapping.target_input.clone(),
+    }
+}
+
+fn dot_edge_label(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| escape_dot_label(name))
+        .collect::<Vec<_>>()
+        .join("\\n")
+}
+
+fn write_edge_label(dot: &mut String, style: &VizStyle, label: Option<&str>) {
+    if let Some(label) = label {
+        dot_writeln!(dot, "        label=\"{}\",", label);
+        dot_writeln!(dot, "        fontsize=\"{}\",", style.fonts.param_size);
+        dot_writeln!(dot, "        fontcolor=\"{}\",", style.params.font_color);
+    }
+}
+
 fn write_sg_edge(
     dot: &mut String,
-    clean_src: &str,
-    clean_tgt: &str,
-    src_is_sg: bool,
-    dst_is_sg: bool,
+    viz_style: &VizStyle,
+    (clean_src, src_is_sg): (&str, bool),
+    (clean_tgt, dst_is_sg): (&str, bool),
     style: &str,
+    critical: bool,
+    label: Option<&str>,
 ) {
     let src = resolve_sg_source(clean_src, src_is_sg);
     let dst = resolve_sg_target(clean_tgt, dst_is_sg);
@@ -54,49 +88,194 @@ fn write_sg_edge(
     if dst_is_sg {
         dot_writeln!(dot, "        lhead=\"cluster_pipe_{}_sg\",", clean_tgt);
     }
+    write_edge_label(dot, viz_style, label);
     dot_writeln!(dot, "        style=\"{}\",", style);
-    dot.push_str("        color=\"#64748B\"\n");
+    if critical {
+        dot_writeln!(dot, "        penwidth=\"{}\",", CRITICAL_PENWIDTH);
+        dot_writeln!(dot, "        color=\"{}\"", CRITICAL_COLOR);
+    } else {
+        dot.push_str("        color=\"#64748B\"\n");
+    }
     dot.push_str("    ];\n");
 }
 
+pub(crate) struct Topology<'a> {
+    pub job_to_run: HashMap<JobId, String>,
+    pub pipeline_jobs: BTreeMap<String, Vec<&'a JobId>>,
+    pub pipeline_representative: HashMap<String, &'a Job>,
+    pub run_pipelines: BTreeMap<String, BTreeSet<String>>,
+}
+
 pub(crate) struct VizGenerator<'a> {
     pub lab: &'a Lab,
     scatter_gather_clean_names: HashSet<String>,
+    statuses: Option<&'a HashMap<JobId, JobStatus>>,
+    diff: Option<&'a HashMap<JobId, JobChange>>,
+    critical: Option<CriticalPath>,
+    timings: Option<&'a HashMap<JobId, JobTiming>>,
+    style: VizStyle,
+    aggregate: bool,
+    collapsed: HashMap<JobId, String>,
+    registry: NodeRegistry,
 }
 
 impl<'a> VizGenerator<'a> {
     pub fn new(lab: &'a Lab) -> Self {
-        let scatter_gather_clean_names = lab
+        let mut generator = Self {
+            lab,
+            scatter_gather_clean_names: HashSet::new(),
+            statuses: None,
+            diff: None,
+            critical: None,
+            timings: None,
+            style: VizStyle::default(),
+            aggregate: true,
+            collapsed: HashMap::new(),
+            registry: NodeRegistry::default(),
+        };
+        generator.rebuild_nodes();
+        generator
+    }
+
+    fn rebuild_nodes(&mut self) {
+        self.registry = NodeRegistry::build(self.lab, |jid, job| self.stage_name(jid, job));
+        self.scatter_gather_clean_names = self
+            .lab
             .jobs
-            .values()
-            .filter(|job| job.stage_type == StageType::ScatterGather)
-            .filter_map(|job| job.name.as_ref())
-            .map(|name| clean_id(name))
+            .iter()
+            .filter(|(jid, job)| {
+                job.stage_type == StageType::ScatterGather && !self.collapsed.contains_key(*jid)
+            })
+            .filter_map(|(jid, _)| self.registry.job(jid))
+            .map(str::to_string)
             .collect();
+    }
 
-        Self {
-            lab,
-            scatter_gather_clean_names,
+    pub fn with_aggregation(mut self, aggregate: bool) -> Self {
+        self.aggregate = aggregate;
+        self.rebuild_nodes();
+        self
+    }
+
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.collapsed.clear();
+        let collapsed = collapse_runs(self.lab, max_nodes, |jid, job| self.stage_name(jid, job));
+        self.collapsed = collapsed;
+        self.rebuild_nodes();
+        self
+    }
+
+    pub(crate) fn stage_node(&self, stage_name: &str) -> String {
+        self.registry.stage(stage_name)
+    }
+
+    pub(crate) fn run_node(&self, run_name: &str) -> String {
+        self.registry.run(run_name)
+    }
+
+    fn job_node(&self, job_id: &JobId) -> String {
+        self.registry
+            .job(job_id)
+            .map(str::to_string)
+            .unwrap_or_else(|| clean_id(job_id.as_str()))
+    }
+
+    pub(crate) fn collapsed_runs(&self) -> BTreeSet<&str> {
+        self.collapsed.values().map(String::as_str).collect()
+    }
+
+    pub(crate) fn aggregates(&self) -> bool {
+        self.aggregate
+    }
+
+    pub(crate) fn stage_name(&self, job_id: &JobId, job: &Job) -> String {
+        if let Some(run) = self.collapsed.get(job_id) {
+            return format!("{} [run]", run);
+        }
+        let name = job.name.clone().unwrap_or_else(|| job_id.to_string());
+        if self.aggregate {
+            name
+        } else {
+            format!("{} [{}]", name, job_id.short_id())
         }
     }
 
-    #[allow(clippy::expect_used)]
-    pub fn generate_dot(&mut self, args: &VizArgs) -> String {
-        let mut dot = String::new();
-        dot.push_str("digraph \"RepX Topology\" {\n");
+    pub fn with_statuses(mut self, statuses: &'a HashMap<JobId, JobStatus>) -> Self {
+        self.statuses = Some(statuses);
+        self
+    }
 
-        if args.format.as_deref() != Some("svg") {
-            dot_writeln!(dot, "    dpi=\"{}\";", DPI);
+    pub fn with_diff(mut self, diff: &'a HashMap<JobId, JobChange>) -> Self {
+        self.diff = Some(diff);
+        self
+    }
+
+    pub fn with_timings(mut self, timings: &'a HashMap<JobId, JobTiming>) -> Self {
+        self.timings = Some(timings);
+        self
+    }
+
+    pub(crate) fn stage_timing(&self, job_ids: &[&JobId]) -> Option<String> {
+        let timings = self.timings?;
+        timing_note(job_ids.iter().filter_map(|jid| timings.get(*jid)))
+    }
+
+    pub fn with_style(mut self, style: VizStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub(crate) fn style(&self) -> &VizStyle {
+        &self.style
+    }
+
+    pub fn with_critical_path(mut self, recorded: &HashMap<JobId, Duration>) -> Self {
+        self.critical = Some(critical_path(self.lab, recorded, |jid| self.job_node(jid)));
+        self
+    }
+
+    pub(crate) fn critical_path(&self) -> Option<&CriticalPath> {
+        self.critical.as_ref()
+    }
+
+    pub(crate) fn is_detached_run(&self, run_name: &str) -> bool {
+        run_name == DETACHED_RUN && !self.lab.runs.keys().any(|r| r.as_str() == run_name)
+    }
+
+    pub(crate) fn is_critical_stage(&self, clean_name: &str) -> bool {
+        self.critical
+            .as_ref()
+            .is_some_and(|path| path.contains_stage(clean_name))
+    }
+
+    pub(crate) fn is_critical_edge(&self, clean_src: &str, clean_tgt: &str) -> bool {
+        self.critical
+            .as_ref()
+            .is_some_and(|path| path.contains_edge(clean_src, clean_tgt))
+    }
+
+    pub(crate) fn job_change(&self, job_id: &JobId) -> Option<JobChange> {
+        self.diff.and_then(|diff| diff.get(job_id)).copied()
+    }
+
+    pub(crate) fn job_status(&self, job_id: &JobId) -> Option<&'a JobStatus> {
+        self.statuses.and_then(|statuses| statuses.get(job_id))
+    }
+
+    pub(crate) fn is_scatter_gather(&self, clean_name: &str) -> bool {
+        self.scatter_gather_clean_names.contains(clean_name)
+    }
+
+    pub(crate) fn stage_overlay(&self, job_ids: &[&JobId]) -> Option<StageOverlay> {
+        if let Some(diff) = self.diff {
+            return diff_overlay(job_ids.iter().map(|jid| diff.get(*jid)));
         }
-        dot.push_str("    compound=\"true\";\n");
-        dot.push_str("    rankdir=\"LR\";\n");
-        dot.push_str("    bgcolor=\"#FFFFFF\";\n");
-        dot_writeln!(dot, "    pad=\"{}\";", GRAPH_PAD);
-        dot_writeln!(dot, "    nodesep=\"{}\";", NODE_SEP);
-        dot_writeln!(dot, "    ranksep=\"{}\";", RANK_SEP);
-        dot_writeln!(dot, "    node [fontname=\"{}\"];", FONT_NAME);
-        dot.push_str("    edge [color=\"#000000\", penwidth=\"1.2\", arrowsize=\"0.7\"];\n\n");
+        let statuses = self.statuses?;
+        let (status, succeeded) = summarize_statuses(job_ids.iter().map(|jid| statuses.get(*jid)));
+        Some(status.overlay(succeeded, job_ids.len()))
+    }
 
+    pub(crate) fn topology(&self) -> Topology<'a> {
         let mut job_to_run: HashMap<JobId, String> = HashMap::new();
         for (run_id, run) in &self.lab.runs {
             for jid in &run.jobs {
@@ -108,7 +287,7 @@ impl<'a> VizGenerator<'a> {
         let mut pipeline_representative: HashMap<String, &Job> = HashMap::new();
 
         for (jid, job) in &self.lab.jobs {
-            let name = job.name.clone().unwrap_or_else(|| jid.to_string());
+            let name = self.stage_name(jid, job);
             pipeline_jobs.entry(name.clone()).or_default().push(jid);
             pipeline_representative.entry(name).or_insert(job);
         }
@@ -118,41 +297,79 @@ impl<'a> VizGenerator<'a> {
             let run_name = job_to_run
                 .get(jid)
                 .cloned()
-                .unwrap_or_else(|| "detached".to_string());
-            let pipeline_name = job.name.clone().unwrap_or_else(|| jid.to_string());
+                .unwrap_or_else(|| DETACHED_RUN.to_string());
+            let pipeline_name = self.stage_name(jid, job);
             run_pipelines
                 .entry(run_name)
                 .or_default()
                 .insert(pipeline_name);
         }
 
+        Topology {
+            job_to_run,
+            pipeline_jobs,
+            pipeline_representative,
+            run_pipelines,
+        }
+    }
+
+    pub(crate) fn run_to_group(&self) -> HashMap<String, String> {
+        let mut run_to_group: HashMap<String, String> = HashMap::new();
+        for (group_name, run_ids) in &self.lab.groups {
+            for rid in run_ids {
+                run_to_group.insert(rid.to_string(), group_name.clone());
+            }
+        }
+        run_to_group
+    }
+
+    #[allow(clippy::expect_used)]
+    pub fn generate_dot(&mut self, args: &VizArgs) -> String {
+        let mut dot = String::new();
+        dot.push_str("digraph \"RepX Topology\" {\n");
+
+        if args.format.as_deref() != Some("svg") {
+            dot_writeln!(dot, "    dpi=\"{}\";", self.style.graph.dpi);
+        }
+        dot.push_str("    compound=\"true\";\n");
+        dot_writeln!(dot, "    rankdir=\"{}\";", args.rankdir.as_str());
+        dot.push_str("    bgcolor=\"#FFFFFF\";\n");
+        dot_writeln!(dot, "    pad=\"{}\";", self.style.graph.pad);
+        dot_writeln!(dot, "    nodesep=\"{}\";", self.style.graph.node_sep);
+        dot_writeln!(dot, "    ranksep=\"{}\";", self.style.graph.rank_sep);
+        dot_writeln!(dot, "    node [fontname=\"{}\"];", self.style.fonts.family);
+        dot.push_str("    edge [color=\"#000000\", penwidth=\"1.2\", arrowsize=\"0.7\"];\n\n");
+
+        let Topology {
+            job_to_run,
+            pipeline_jobs,
+            pipeline_representative,
+            run_pipelines,
+        } = self.topology();
+
         if args.show_pipelines {
             self.render_pipeline_layer(&mut dot, args, &pipeline_jobs, &pipeline_representative);
         }
 
         if args.show_runs {
-            let mut run_to_group: HashMap<String, String> = HashMap::new();
-            if args.show_groups {
-                for (group_name, run_ids) in &self.lab.groups {
-                    for rid in run_ids {
-                        run_to_group.insert(rid.to_string(), group_name.clone());
-                    }
-                }
-            }
+            let run_to_group = if args.show_groups {
+                self.run_to_group()
+            } else {
+                HashMap::new()
+            };
 
             self.render_run_layer(&mut dot, args, &run_pipelines, &run_to_group);
         }
 
         if args.show_pipelines && args.show_inter_edges {
-            self.render_inter_run_edges(&mut dot, &job_to_run);
+            self.render_inter_run_edges(&mut dot, args, &job_to_run);
         }
 
         if args.show_pipelines && args.show_runs {
             for (run_name, pipelines) in &run_pipelines {
-                let clean_run = clean_id(run_name);
-                let run_node = format!("run_{}", clean_run);
+                let run_node = format!("run_{}", self.run_node(run_name));
                 for pipeline_name in pipelines {
-                    let clean_pipe = clean_id(pipeline_name);
+                    let clean_pipe = self.stage_node(pipeline_name);
                     let is_sg = self.scatter_gather_clean_names.contains(&clean_pipe);
                     let target = resolve_sg_target(&clean_pipe, is_sg);
                     dot_writeln!(dot, "    {} -> {} [", run_node, target);
@@ -178,7 +395,7 @@ impl<'a> VizGenerator<'a> {
     ) {
         for (pipeline_name, job_ids) in pipeline_jobs {
             let count = job_ids.len();
-            let clean_pipe = clean_id(pipeline_name);
+            let clean_pipe = self.stage_node(pipeline_name);
             let node_id = pipe_node(&clean_pipe);
 
             let is_sg = pipeline_representative
@@ -195,27 +412,60 @@ impl<'a> VizGenerator<'a> {
                     dot,
                     pipeline_name,
                     &node_id,
-                    count,
+                    job_ids,
                     rep,
                     "    ",
                 );
             } else {
-                let job_label = format!("{}\\n(x{})", escape_dot_label(pipeline_name), count);
-                let fill_color = get_fill_color(pipeline_name);
+                let mut job_label = escape_dot_label(pipeline_name);
+                if self.aggregate {
+                    job_label.push_str(&format!("\\n(x{})", count));
+                }
+                if let Some(timing) = self.stage_timing(job_ids) {
+                    job_label.push_str(&format!("\\n{}", escape_dot_label(&timing)));
+                }
+                let critical = self.is_critical_stage(&clean_pipe);
 
                 dot_writeln!(dot, "    {} [", node_id);
-                dot_writeln!(dot, "        label=\"{}\",", job_label);
-                dot.push_str("        shape=\"box\",\n");
-                dot.push_str("        style=\"filled,rounded\",\n");
-                dot_writeln!(dot, "        fontsize=\"{}\",", JOB_FONT_SIZE);
-                dot_writeln!(dot, "        fillcolor=\"{}\",", fill_color);
-                dot.push_str("        penwidth=\"1\"\n");
+                match self.stage_overlay(job_ids) {
+                    Some(overlay) => {
+                        job_label.push_str(&format!("\\n{}", escape_dot_label(&overlay.note)));
+                        dot_writeln!(dot, "        label=\"{}\",", job_label);
+                        dot_writeln!(dot, "        shape=\"{}\",", self.style.shapes.stage);
+                        dot.push_str("        style=\"filled,rounded\",\n");
+                        dot_writeln!(dot, "        fontsize=\"{}\",", self.style.fonts.stage_size);
+                        dot_writeln!(dot, "        fillcolor=\"{}\",", overlay.fill);
+                        dot_writeln!(dot, "        color=\"{}\",", overlay.border);
+                        let penwidth = if critical {
+                            CRITICAL_PENWIDTH
+                        } else {
+                            STATUS_PENWIDTH
+                        };
+                        dot_writeln!(dot, "        penwidth=\"{}\"", penwidth);
+                    }
+                    None => {
+                        dot_writeln!(dot, "        label=\"{}\",", job_label);
+                        dot_writeln!(dot, "        shape=\"{}\",", self.style.shapes.stage);
+                        dot.push_str("        style=\"filled,rounded\",\n");
+                        dot_writeln!(dot, "        fontsize=\"{}\",", self.style.fonts.stage_size);
+                        dot_writeln!(
+                            dot,
+                            "        fillcolor=\"{}\",",
+                            self.style.stage_fill(pipeline_name)
+                        );
+                        if critical {
+                            dot_writeln!(dot, "        color=\"{}\",", CRITICAL_COLOR);
+                            dot_writeln!(dot, "        penwidth=\"{}\"", CRITICAL_PENWIDTH);
+                        } else {
+                            dot.push_str("        penwidth=\"1\"\n");
+                        }
+                    }
+                }
                 dot.push_str("    ];\n");
             }
 
             if args.show_params {
-                let varying = self.get_varying_params(job_ids);
-                for (p_key, p_vals) in varying {
+                for (p_key, p_vals) in self.stage_params(job_ids) {
                     let clean_key = clean_id(&p_key);
                     let param_node_id = format!("pparam_{}_{}", clean_pipe, clean_key);
 
@@ -237,12 +487,16 @@ impl<'a> VizGenerator<'a> {
 
                     dot_writeln!(dot, "    {} [", param_node_id);
                     dot_writeln!(dot, "        label=\"{}\",", label);
-                    dot_writeln!(dot, "        shape=\"{}\",", PARAM_SHAPE);
+                    dot_writeln!(dot, "        shape=\"{}\",", self.style.shapes.param);
                     dot.push_str("        style=\"filled\",\n");
-                    dot_writeln!(dot, "        fillcolor=\"{}\",", PARAM_FILL);
-                    dot_writeln!(dot, "        color=\"{}\",", PARAM_BORDER);
-                    dot_writeln!(dot, "        fontcolor=\"{}\",", PARAM_FONT_COLOR);
-                    dot_writeln!(dot, "        fontsize=\"{}\",", PARAM_FONT_SIZE);
+                    dot_writeln!(dot, "        fillcolor=\"{}\",", self.style.params.fill);
+                    dot_writeln!(dot, "        color=\"{}\",", self.style.params.border);
+                    dot_writeln!(
+                        dot,
+                        "        fontcolor=\"{}\",",
+                        self.style.params.font_color
+                    );
+                    dot_writeln!(dot, "        fontsize=\"{}\",", self.style.fonts.param_size);
                     dot.push_str("        margin=\"0.1,0.05\",\n");
                     dot.push_str("        penwidth=\"0.8\"\n");
                     dot.push_str("    ];\n");
@@ -250,7 +504,7 @@ impl<'a> VizGenerator<'a> {
                     let target = resolve_sg_target(&clean_pipe, is_sg);
                     dot_writeln!(dot, "    {} -> {} [", param_node_id, target);
                     dot.push_str("        style=\"dotted\",\n");
-                    dot_writeln!(dot, "        color=\"{}\",", PARAM_BORDER);
+                    dot_writeln!(dot, "        color=\"{}\",", self.style.params.border);
                     dot.push_str("        arrowhead=\"dot\",\n");
                     dot.push_str("        arrowsize=\"0.5\",\n");
                     dot.push_str("        penwidth=\"1.0\"\n");
@@ -260,51 +514,79 @@ impl<'a> VizGenerator<'a> {
         }
 
         if args.show_intra_edges {
-            let mut drawn: HashSet<(String, String)> = HashSet::new();
+            let labels = if args.edge_labels {
+                self.collect_edge_labels()
+            } else {
+                HashMap::new()
+            };
+            for (clean_src, clean_tgt) in self.collect_intra_edges() {
+                let src_is_sg = self.scatter_gather_clean_names.contains(&clean_src);
+                let dst_is_sg = self.scatter_gather_clean_names.contains(&clean_tgt);
+                let actual_src = resolve_sg_source(&clean_src, src_is_sg);
+                let actual_dst = resolve_sg_target(&clean_tgt, dst_is_sg);
+
+                if self.is_critical_edge(&clean_src, &clean_tgt) {
+                    dot_write!(
+                        dot,
+                        "    {} -> {} [penwidth=\"{}\", color=\"{}\"",
+                        actual_src,
+                        actual_dst,
+                        CRITICAL_PENWIDTH,
+                        CRITICAL_COLOR
+                    );
+                } else {
+                    dot_write!(
+                        dot,
+                        "    {} -> {} [penwidth=\"1.2\"",
+                        actual_src,
+                        actual_dst
+                    );
+                }
+                if src_is_sg {
+                    dot_write!(dot, ", ltail=\"cluster_pipe_{}_sg\"", clean_src);
+                }
+                if dst_is_sg {
+                    dot_write!(dot, ", lhead=\"cluster_pipe_{}_sg\"", clean_tgt);
+                }
+                if let Some(names) = labels.get(&(clean_src.clone(), clean_tgt.clone())) {
+                    dot_write!(
+                        dot,
+                        ", label=\"{}\", fontsize=\"{}\", fontcolor=\"{}\"",
+                        dot_edge_label(names),
+                        self.style.fonts.param_size,
+                        self.style.params.font_color
+                    );
+                }
+                dot.push_str("];\n");
+            }
+        }
+    }
 
-            for job in self.lab.jobs.values() {
-                let tgt_name = job.name.clone().unwrap_or_default();
-                let clean_tgt = clean_id(&tgt_name);
+    pub(crate) fn collect_intra_edges(&self) -> Vec<(String, String)> {
+        let mut drawn: HashSet<(String, String)> = HashSet::new();
+        let mut edges = Vec::new();
 
-                for mapping in Self::get_job_inputs(job) {
-                    if let Some(sid) = &mapping.job_id {
-                        if let Some(src_job) = self.lab.jobs.get(sid) {
-                            let src_name = src_job.name.clone().unwrap_or_default();
-                            let clean_src = clean_id(&src_name);
+        for (jid, job) in &self.lab.jobs {
+            let clean_tgt = self.job_node(jid);
 
-                            if clean_src == clean_tgt {
-                                continue;
-                            }
+            for mapping in Self::get_job_inputs(job) {
+                if let Some(sid) = &mapping.job_id {
+                    if self.lab.jobs.contains_key(sid) {
+                        let clean_src = self.job_node(sid);
 
-                            let key = (clean_src.clone(), clean_tgt.clone());
-                            if drawn.contains(&key) {
-                                continue;
-                            }
-                            drawn.insert(key);
+                        if clean_src == clean_tgt {
+                            continue;
+                        }
 
-                            let src_is_sg = self.scatter_gather_clean_names.contains(&clean_src);
-                            let dst_is_sg = self.scatter_gather_clean_names.contains(&clean_tgt);
-                            let actual_src = resolve_sg_source(&clean_src, src_is_sg);
-                            let actual_dst = resolve_sg_target(&clean_tgt, dst_is_sg);
-
-                            dot_write!(
-                                dot,
-                                "    {} -> {} [penwidth=\"1.2\"",
-                                actual_src,
-                                actual_dst
-                            );
-                            if src_is_sg {
-                                dot_write!(dot, ", ltail=\"cluster_pipe_{}_sg\"", clean_src);
-                            }
-                            if dst_is_sg {
-                                dot_write!(dot, ", lhead=\"cluster_pipe_{}_sg\"", clean_tgt);
-                            }
-                            dot.push_str("];\n");
+                        let key = (clean_src, clean_tgt.clone());
+                        if drawn.insert(key.clone()) {
+                            edges.push(key);
                         }
                     }
                 }
             }
         }
+        edges
     }
 
     fn render_run_layer(
@@ -336,8 +618,12 @@ impl<'a> VizGenerator<'a> {
                 dot_writeln!(dot, "    subgraph cluster_group_{} {{", clean_group);
                 dot_writeln!(dot, "        label=\"@{}\";", escape_dot_label(&group_name));
                 dot.push_str("        style=\"solid,rounded\";\n");
-                dot_writeln!(dot, "        color=\"{}\";", COLOR_GROUP_BORDER);
-                dot_writeln!(dot, "        fontsize=\"{}\";", GROUP_FONT_SIZE);
+                dot_writeln!(
+                    dot,
+                    "        color=\"{}\";",
+                    self.style.clusters.group_border
+                );
+                dot_writeln!(dot, "        fontsize=\"{}\";", self.style.fonts.group_size);
                 dot.push_str("        penwidth=\"2\";\n");
                 dot.push_str("        margin=\"20\";\n\n");
 
@@ -374,8 +660,7 @@ impl<'a> VizGenerator<'a> {
         pipelines: &BTreeSet<String>,
         indent: &str,
     ) {
-        let clean_run = clean_id(run_name);
-        let node_id = format!("run_{}", clean_run);
+        let node_id = format!("run_{}", self.run_node(run_name));
 
         let pipe_list = pipelines
             .iter()
@@ -388,24 +673,122 @@ impl<'a> VizGenerator<'a> {
             escape_dot_label(run_name),
             pipe_list
         );
+        let (style, fill, border) = if self.is_detached_run(run_name) {
+            ("filled,rounded,dashed", DETACHED_FILL, DETACHED_COLOR)
+        } else {
+            (
+                "filled,rounded",
+                self.style.palette.run_fill.as_str(),
+                self.style.clusters.border.as_str(),
+            )
+        };
 
         dot_writeln!(dot, "{}{} [", indent, node_id);
         dot_writeln!(dot, "{}    label=\"{}\",", indent, label);
         dot.push_str(&format!("{}    shape=\"record\",\n", indent));
-        dot.push_str(&format!("{}    style=\"filled,rounded\",\n", indent));
-        dot_writeln!(dot, "{}    fillcolor=\"{}\",", indent, RUN_FILL);
-        dot_writeln!(dot, "{}    color=\"{}\",", indent, COLOR_CLUSTER_BORDER);
-        dot_writeln!(dot, "{}    fontsize=\"{}\",", indent, JOB_FONT_SIZE);
+        dot_writeln!(dot, "{}    style=\"{}\",", indent, style);
+        dot_writeln!(dot, "{}    fillcolor=\"{}\",", indent, fill);
+        dot_writeln!(dot, "{}    color=\"{}\",", indent, border);
+        dot_writeln!(
+            dot,
+            "{}    fontsize=\"{}\",",
+            indent,
+            self.style.fonts.stage_size
+        );
         dot.push_str(&format!("{}    penwidth=\"1.5\"\n", indent));
         dot_writeln!(dot, "{}];", indent);
     }
 
-    fn render_inter_run_edges(&self, dot: &mut String, job_to_run: &HashMap<JobId, String>) {
+    fn render_inter_run_edges(
+        &self,
+        dot: &mut String,
+        args: &VizArgs,
+        job_to_run: &HashMap<JobId, String>,
+    ) {
+        let labels = if args.edge_labels {
+            self.collect_edge_labels()
+        } else {
+            HashMap::new()
+        };
+        for (clean_src, clean_tgt, style) in self.collect_inter_run_edges(job_to_run) {
+            let src_is_sg = self.scatter_gather_clean_names.contains(&clean_src);
+            let dst_is_sg = self.scatter_gather_clean_names.contains(&clean_tgt);
+            let critical = self.is_critical_edge(&clean_src, &clean_tgt);
+            let label = labels
+                .get(&(clean_src.clone(), clean_tgt.clone()))
+                .map(|names| dot_edge_label(names));
+            write_sg_edge(
+                dot,
+                &self.style,
+                (&clean_src, src_is_sg),
+                (&clean_tgt, dst_is_sg),
+                style,
+                critical,
+                label.as_deref(),
+            );
+        }
+    }
+
+    pub(crate) fn collect_edge_labels(&self) -> HashMap<(String, String), Vec<String>> {
+        let mut labels: HashMap<(String, String), BTreeSet<String>> = HashMap::new();
+
+        for (jid, job) in &self.lab.jobs {
+            let clean_tgt = self.job_node(jid);
+
+            for mapping in Self::get_job_inputs(job) {
+                let mut sources: BTreeSet<String> = BTreeSet::new();
+                if let Some(sid) = mapping
+                    .job_id
+                    .as_ref()
+                    .filter(|sid| self.lab.jobs.contains_key(*sid))
+                {
+                    sources.insert(self.job_node(sid));
+                }
+                if let Some(run) = mapping
+                    .source_run
+                    .as_ref()
+                    .and_then(|r| self.lab.runs.get(r))
+                {
+                    sources.extend(
+                        run.jobs
+                            .iter()
+                            .filter_map(|sid| Some((sid, self.lab.jobs.get(sid)?)))
+                            .filter(|(_, j)| {
+                                mapping
+                                    .source_stage_filter
+                                    .as_deref()
+                                    .is_none_or(|filter| j.name.as_deref() == Some(filter))
+                            })
+                            .map(|(sid, _)| self.job_node(sid)),
+                    );
+                }
+
+                for clean_src in sources {
+                    if clean_src != clean_tgt {
+                        labels
+                            .entry((clean_src, clean_tgt.clone()))
+                            .or_default()
+                            .insert(mapping_label(mapping));
+                    }
+                }
+            }
+        }
+
+        labels
+            .into_iter()
+            .map(|(key, names)| (key, names.into_iter().collect()))
+            .collect()
+    }
+
+    pub(crate) fn collect_inter_run_edges(
+        &self,
+        job_to_run: &HashMap<JobId, String>,
+    ) -> Vec<(String, String, &'static str)> {
         let mut drawn: HashSet<(String, String, String)> = HashSet::new();
+        let mut edges = Vec::new();
 
-        for job in self.lab.jobs.values() {
-            let tgt_name = job.name.clone().unwrap_or_default();
-            let clean_tgt = clean_id(&tgt_name);
+        for (jid, job) in &self.lab.jobs {
+            let clean_tgt = self.job_node(jid);
 
             for mapping in Self::get_job_inputs(job) {
                 if let Some(srun) = &mapping.source_run {
@@ -414,59 +797,48 @@ impl<'a> VizGenerator<'a> {
                         .map(|d| d.to_string())
                         .unwrap_or_else(|| "hard".to_string());
 
-                    let src_pipelines: BTreeSet<String> = self
+                    let filtered: BTreeSet<String> = self
                         .lab
                         .runs
                         .get(srun)
                         .map(|run| {
                             run.jobs
                                 .iter()
-                                .filter_map(|jid| self.lab.jobs.get(jid))
-                                .filter_map(|j| j.name.clone())
+                                .filter_map(|sid| Some((sid, self.lab.jobs.get(sid)?)))
+                                .filter(|(_, j)| j.name.is_some())
+                                .filter(|(_, j)| {
+                                    mapping
+                                        .source_stage_filter
+                                        .as_ref()
+                                        .is_none_or(|filter| j.name.as_ref() == Some(filter))
+                                })
+                                .map(|(sid, _)| self.job_node(sid))
                                 .collect()
                         })
                         .unwrap_or_default();
 
-                    let filtered: BTreeSet<String> =
-                        if let Some(filter) = &mapping.source_stage_filter {
-                            src_pipelines.into_iter().filter(|n| n == filter).collect()
-                        } else {
-                            src_pipelines
-                        };
-
-                    for src_pipeline in filtered {
-                        let clean_src = clean_id(&src_pipeline);
+                    for clean_src in filtered {
                         let key = (clean_src.clone(), clean_tgt.clone(), dtype.clone());
                         if drawn.contains(&key) {
                             continue;
                         }
                         drawn.insert(key);
 
-                        let src_is_sg = self.scatter_gather_clean_names.contains(&clean_src);
-                        let dst_is_sg = self.scatter_gather_clean_names.contains(&clean_tgt);
-
                         let style = if dtype == "soft" { "dashed" } else { "solid" };
-                        write_sg_edge(dot, &clean_src, &clean_tgt, src_is_sg, dst_is_sg, style);
+                        edges.push((clean_src, clean_tgt.clone(), style));
                     }
                 }
 
                 if let Some(sid) = &mapping.job_id {
-                    if let Some(src_job) = self.lab.jobs.get(sid) {
-                        let src_name = src_job.name.clone().unwrap_or_default();
-                        let clean_src = clean_id(&src_name);
+                    if self.lab.jobs.contains_key(sid) {
+                        let clean_src = self.job_node(sid);
 
                         if clean_src == clean_tgt {
                             continue;
                         }
 
                         let src_run = job_to_run.get(sid);
-                        let tgt_run = self.lab.jobs.iter().find_map(|(jid, j)| {
-                            if j.name.as_deref().map(clean_id).as_deref() == Some(&clean_tgt) {
-                                job_to_run.get(jid)
-                            } else {
-                                None
-                            }
-                        });
+                        let tgt_run = job_to_run.get(jid);
 
                         if src_run != tgt_run {
                             let dtype = "hard".to_string();
@@ -476,16 +848,13 @@ impl<'a> VizGenerator<'a> {
                             }
                             drawn.insert(key);
 
-                            let src_is_sg = self.scatter_gather_clean_names.contains(&clean_src);
-                            let dst_is_sg = self.scatter_gather_clean_names.contains(&clean_tgt);
-                            write_sg_edge(
-                                dot, &clean_src, &clean_tgt, src_is_sg, dst_is_sg, "solid",
-                            );
+                            edges.push((clean_src, clean_tgt.clone(), "solid"));
                         }
                     }
                 }
             }
         }
+        edges
     }
 
     #[allow(clippy::expect_used)]
@@ -494,10 +863,12 @@ impl<'a> VizGenerator<'a> {
         dot: &mut String,
         job_name: &str,
         unique_node_id: &str,
-        count: usize,
+        job_ids: &[&JobId],
         representative_job: &Job,
         indent: &str,
     ) {
+        let count = job_ids.len();
+        let overlay = self.stage_overlay(job_ids);
         let cluster_id = format!("{}_sg", unique_node_id);
         let scatter_id = format!("{}_sg_scatter", unique_node_id);
         let gather_id = format!("{}_sg_gather", unique_node_id);
@@ -515,32 +886,111 @@ impl<'a> VizGenerator<'a> {
         step_names.sort();
 
         dot_writeln!(dot, "{}subgraph cluster_{} {{", indent, cluster_id);
+        let progress: String = self
+            .stage_timing(job_ids)
+            .into_iter()
+            .chain(overlay.as_ref().map(|overlay| overlay.note.clone()))
+            .map(|note| format!("\\n{}", escape_dot_label(&note)))
+            .collect();
+        let count = if self.aggregate {
+            format!("\\n(x{})", count)
+        } else {
+            String::new()
+        };
         dot_writeln!(
             dot,
-            "{}    label=\"{}\\n(x{})\";",
+            "{}    label=\"{}{}{}\";",
             indent,
             escape_dot_label(job_name),
-            count
+            count,
+            progress
         );
         dot_writeln!(dot, "{}    style=\"filled,rounded,bold\";", indent);
-        dot_writeln!(dot, "{}    color=\"{}\";", indent, SG_CLUSTER_BORDER);
-        dot_writeln!(dot, "{}    fillcolor=\"{}\";", indent, SG_CLUSTER_BG);
-        dot_writeln!(dot, "{}    fontsize=\"{}\";", indent, JOB_FONT_SIZE);
-        dot_writeln!(dot, "{}    penwidth=\"1.5\";", indent);
+        let critical = self.is_critical_stage(&self.stage_node(job_name));
+        match &overlay {
+            Some(overlay) => {
+                dot_writeln!(dot, "{}    color=\"{}\";", indent, overlay.border);
+                dot_writeln!(dot, "{}    fillcolor=\"{}\";", indent, overlay.fill);
+                dot_writeln!(
+                    dot,
+                    "{}    fontsize=\"{}\";",
+                    indent,
+                    self.style.fonts.stage_size
+                );
+                let penwidth = if critical {
+                    CRITICAL_PENWIDTH
+                } else {
+                    STATUS_PENWIDTH
+                };
+                dot_writeln!(dot, "{}    penwidth=\"{}\";", indent, penwidth);
+            }
+            None if critical => {
+                dot_writeln!(dot, "{}    color=\"{}\";", indent, CRITICAL_COLOR);
+                dot_writeln!(
+                    dot,
+                    "{}    fillcolor=\"{}\";",
+                    indent,
+                    self.style.clusters.sg_background
+                );
+                dot_writeln!(
+                    dot,
+                    "{}    fontsize=\"{}\";",
+                    indent,
+                    self.style.fonts.stage_size
+                );
+                dot_writeln!(dot, "{}    penwidth=\"{}\";", indent, CRITICAL_PENWIDTH);
+            }
+            None => {
+                dot_writeln!(
+                    dot,
+                    "{}    color=\"{}\";",
+                    indent,
+                    self.style.clusters.sg_border
+                );
+                dot_writeln!(
+                    dot,
+                    "{}    fillcolor=\"{}\";",
+                    indent,
+                    self.style.clusters.sg_background
+                );
+                dot_writeln!(
+                    dot,
+                    "{}    fontsize=\"{}\";",
+                    indent,
+                    self.style.fonts.stage_size
+                );
+                dot_writeln!(dot, "{}    penwidth=\"1.5\";", indent);
+            }
+        }
         dot_writeln!(dot, "{}    margin=\"12\";", indent);
         dot.push('\n');
 
         dot_writeln!(dot, "{}    {} [", indent, scatter_id);
         dot_writeln!(dot, "{}        label=\"scatter\",", indent);
-        dot_writeln!(dot, "{}        shape=\"trapezium\",", indent);
+        dot_writeln!(
+            dot,
+            "{}        shape=\"{}\",",
+            indent,
+            self.style.shapes.scatter
+        );
         dot_writeln!(dot, "{}        style=\"filled\",", indent);
-        dot_writeln!(dot, "{}        fillcolor=\"{}\",", indent, SG_SCATTER_FILL);
-        dot_writeln!(dot, "{}        color=\"{}\",", indent, SG_CLUSTER_BORDER);
+        dot_writeln!(
+            dot,
+            "{}        fillcolor=\"{}\",",
+            indent,
+            self.style.clusters.sg_scatter_fill
+        );
+        dot_writeln!(
+            dot,
+            "{}        color=\"{}\",",
+            indent,
+            self.style.clusters.sg_border
+        );
         dot_writeln!(
             dot,
             "{}        fontsize=\"{}\",",
             indent,
-            SG_PHASE_FONT_SIZE
+            self.style.fonts.sg_phase_size
         );
         dot_writeln!(dot, "{}        penwidth=\"1\"", indent);
         dot_writeln!(dot, "{}    ];", indent);
@@ -554,26 +1004,61 @@ impl<'a> VizGenerator<'a> {
                 indent,
                 escape_dot_label(step_name)
             );
-            dot_writeln!(dot, "{}        shape=\"box\",", indent);
+            dot_writeln!(
+                dot,
+                "{}        shape=\"{}\",",
+                indent,
+                self.style.shapes.step
+            );
             dot_writeln!(dot, "{}        style=\"filled,rounded\",", indent);
-            dot_writeln!(dot, "{}        fillcolor=\"{}\",", indent, SG_STEP_FILL);
-            dot_writeln!(dot, "{}        color=\"{}\",", indent, SG_STEP_BORDER);
-            dot_writeln!(dot, "{}        fontsize=\"{}\",", indent, SG_STEP_FONT_SIZE);
+            dot_writeln!(
+                dot,
+                "{}        fillcolor=\"{}\",",
+                indent,
+                self.style.clusters.sg_step_fill
+            );
+            dot_writeln!(
+                dot,
+                "{}        color=\"{}\",",
+                indent,
+                self.style.clusters.sg_step_border
+            );
+            dot_writeln!(
+                dot,
+                "{}        fontsize=\"{}\",",
+                indent,
+                self.style.fonts.sg_step_size
+            );
             dot_writeln!(dot, "{}        penwidth=\"1\"", indent);
             dot_writeln!(dot, "{}    ];", indent);
         }
 
         dot_writeln!(dot, "{}    {} [", indent, gather_id);
         dot_writeln!(dot, "{}        label=\"gather\",", indent);
-        dot_writeln!(dot, "{}        shape=\"invtrapezium\",", indent);
+        dot_writeln!(
+            dot,
+            "{}        shape=\"{}\",",
+            indent,
+            self.style.shapes.gather
+        );
         dot_writeln!(dot, "{}        style=\"filled\",", indent);
-        dot_writeln!(dot, "{}        fillcolor=\"{}\",", indent, SG_GATHER_FILL);
-        dot_writeln!(dot, "{}        color=\"{}\",", indent, SG_CLUSTER_BORDER);
+        dot_writeln!(
+            dot,
+            "{}        fillcolor=\"{}\",",
+            indent,
+            self.style.clusters.sg_gather_fill
+        );
+        dot_writeln!(
+            dot,
+            "{}        color=\"{}\",",
+            indent,
+            self.style.clusters.sg_border
+        );
         dot_writeln!(
             dot,
             "{}        fontsize=\"{}\",",
             indent,
-            SG_PHASE_FONT_SIZE
+            self.style.fonts.sg_phase_size
         );
         dot_writeln!(dot, "{}        penwidth=\"1\"", indent);
         dot_writeln!(dot, "{}    ];", indent);
@@ -613,7 +1098,7 @@ impl<'a> VizGenerator<'a> {
                 indent,
                 scatter_id,
                 step_id,
-                SG_INTERNAL_EDGE_COLOR
+                self.style.clusters.sg_edge
             );
         }
 
@@ -629,7 +1114,7 @@ impl<'a> VizGenerator<'a> {
                         indent,
                         dep_id,
                         step_id,
-                        SG_INTERNAL_EDGE_COLOR
+                        self.style.clusters.sg_edge
                     );
                 }
             }
@@ -643,7 +1128,7 @@ impl<'a> VizGenerator<'a> {
                 indent,
                 step_id,
                 gather_id,
-                SG_INTERNAL_EDGE_COLOR
+                self.style.clusters.sg_edge
             );
         }
 
@@ -666,7 +1151,25 @@ impl<'a> VizGenerator<'a> {
         }
     }
 
-    fn get_varying_params(&self, job_ids: &[&JobId]) -> BTreeMap<String, Vec<Value>> {
+    pub(crate) fn stage_params(&self, job_ids: &[&JobId]) -> BTreeMap<String, Vec<Value>> {
+        if self.aggregate {
+            return self.get_varying_params(job_ids);
+        }
+        let mut params: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+        for jid in job_ids {
+            if let Some(Value::Object(values)) = self.lab.jobs.get(jid).map(|job| &job.params) {
+                for (key, value) in values {
+                    params
+                        .entry(key.clone())
+                        .or_default()
+                        .push(Value::String(canonical_json(value)));
+                }
+            }
+        }
+        params
+    }
+
+    pub(crate) fn get_varying_params(&self, job_ids: &[&JobId]) -> BTreeMap<String, Vec<Value>> {
         if job_ids.is_empty() {
             return BTreeMap::new();
         }
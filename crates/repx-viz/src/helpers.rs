@@ -1,50 +1,105 @@
+use chrono::Local;
+use repx_core::engine::JobStatus;
+use repx_core::store::outcomes::JobTiming;
 use serde_json::Value;
 
-pub(crate) const DPI: &str = "300";
-pub(crate) const FONT_NAME: &str = "Helvetica, Arial, sans-serif";
-pub(crate) const RANK_SEP: &str = "0.6";
-pub(crate) const NODE_SEP: &str = "0.4";
-pub(crate) const GRAPH_PAD: &str = "0.5";
-pub(crate) const JOB_FONT_SIZE: &str = "12";
-pub(crate) const COLOR_CLUSTER_BORDER: &str = "#334155";
-pub(crate) const COLOR_GROUP_BORDER: &str = "#1e40af";
-pub(crate) const GROUP_FONT_SIZE: &str = "16";
-pub(crate) const PARAM_SHAPE: &str = "note";
-pub(crate) const PARAM_FILL: &str = "#FFFFFF";
-pub(crate) const PARAM_BORDER: &str = "#94a3b8";
-pub(crate) const PARAM_FONT_COLOR: &str = "#475569";
-pub(crate) const PARAM_FONT_SIZE: &str = "9";
 pub(crate) const PARAM_MAX_WIDTH: usize = 20;
 
-pub(crate) const SG_CLUSTER_BORDER: &str = "#6366f1";
-pub(crate) const SG_CLUSTER_BG: &str = "#EEF2FF";
-pub(crate) const SG_SCATTER_FILL: &str = "#C7D2FE";
-pub(crate) const SG_GATHER_FILL: &str = "#C7D2FE";
-pub(crate) const SG_STEP_FILL: &str = "#E0E7FF";
-pub(crate) const SG_STEP_BORDER: &str = "#818CF8";
-pub(crate) const SG_INTERNAL_EDGE_COLOR: &str = "#6366f1";
-pub(crate) const SG_PHASE_FONT_SIZE: &str = "10";
-pub(crate) const SG_STEP_FONT_SIZE: &str = "9";
-
-const DEFAULT_FILL: &str = "#F8FAFC";
-
-pub(crate) const RUN_FILL: &str = "#F1F5F9";
-
-pub(crate) fn get_fill_color(name: &str) -> &'static str {
-    let name_lower = name.to_lowercase();
-    if name_lower.contains("producer") {
-        "#EFF6FF"
-    } else if name_lower.contains("consumer") || name_lower.contains("worker") {
-        "#ECFDF5"
-    } else if name_lower.contains("partial") {
-        "#FFFBEB"
-    } else if name_lower.contains("total") {
-        "#FFF1F2"
-    } else {
-        DEFAULT_FILL
+pub(crate) const STATUS_PENWIDTH: &str = "2";
+pub(crate) const CRITICAL_PENWIDTH: &str = "3.5";
+pub(crate) const CRITICAL_COLOR: &str = "#EA580C";
+
+pub(crate) const DETACHED_RUN: &str = "detached";
+pub(crate) const DETACHED_FILL: &str = "#FEF2F2";
+pub(crate) const DETACHED_COLOR: &str = "#DC2626";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StageStatus {
+    Succeeded,
+    Failed,
+    Running,
+    Pending,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StageOverlay {
+    pub class: &'static str,
+    pub fill: &'static str,
+    pub border: &'static str,
+    pub note: String,
+}
+
+impl StageStatus {
+    pub(crate) fn overlay(self, succeeded: usize, total: usize) -> StageOverlay {
+        StageOverlay {
+            class: self.class(),
+            fill: self.fill(),
+            border: self.border(),
+            note: format!("{}/{} done", succeeded, total),
+        }
+    }
+
+    pub(crate) fn class(self) -> &'static str {
+        match self {
+            StageStatus::Succeeded => "succeeded",
+            StageStatus::Failed => "failed",
+            StageStatus::Running => "running",
+            StageStatus::Pending => "pending",
+        }
+    }
+
+    pub(crate) fn fill(self) -> &'static str {
+        match self {
+            StageStatus::Succeeded => "#DCFCE7",
+            StageStatus::Failed => "#FEE2E2",
+            StageStatus::Running => "#DBEAFE",
+            StageStatus::Pending => "#F1F5F9",
+        }
+    }
+
+    pub(crate) fn border(self) -> &'static str {
+        match self {
+            StageStatus::Succeeded => "#16A34A",
+            StageStatus::Failed => "#DC2626",
+            StageStatus::Running => "#2563EB",
+            StageStatus::Pending => "#94A3B8",
+        }
     }
 }
 
+pub(crate) fn summarize_statuses<'s>(
+    statuses: impl IntoIterator<Item = Option<&'s JobStatus>>,
+) -> (StageStatus, usize) {
+    let mut succeeded = 0;
+    let mut total = 0;
+    let mut failed = false;
+    let mut running = false;
+    for status in statuses {
+        total += 1;
+        match status {
+            Some(JobStatus::Succeeded { .. }) => succeeded += 1,
+            Some(
+                JobStatus::Failed { .. }
+                | JobStatus::TimedOut { .. }
+                | JobStatus::Cancelled { .. }
+                | JobStatus::Lost { .. },
+            ) => failed = true,
+            Some(JobStatus::Running) | Some(JobStatus::Queued) => running = true,
+            _ => {}
+        }
+    }
+    let stage = if failed {
+        StageStatus::Failed
+    } else if running {
+        StageStatus::Running
+    } else if total > 0 && succeeded == total {
+        StageStatus::Succeeded
+    } else {
+        StageStatus::Pending
+    };
+    (stage, succeeded)
+}
+
 pub(crate) fn escape_dot_label(s: &str) -> String {
     s.replace('\\', "\\\\")
         .replace('"', "\\\"")
@@ -54,6 +109,52 @@ pub(crate) fn escape_dot_label(s: &str) -> String {
         .replace('}', "\\}")
 }
 
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn escape_mermaid_label(s: &str) -> String {
+    s.replace('&', "#amp;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+pub(crate) fn escape_plantuml_label(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "'")
+        .replace('\n', "\\n")
+}
+
+pub(crate) fn format_duration(duration: std::time::Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, (secs % 3600) / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, _) => format!("{}h {:02}m", h, m),
+    }
+}
+
+pub(crate) fn timing_note<'a>(timings: impl IntoIterator<Item = &'a JobTiming>) -> Option<String> {
+    let mut count = 0;
+    let mut longest = None;
+    let mut latest = None;
+    for timing in timings {
+        count += 1;
+        longest = longest.max(timing.elapsed);
+        latest = latest.max(Some(timing.finished_at));
+    }
+    let finished = latest?.with_timezone(&Local).format("%m-%d %H:%M");
+    Some(match longest {
+        Some(elapsed) if count == 1 => format!("{}, done {}", format_duration(elapsed), finished),
+        Some(elapsed) => format!("max {}, last {}", format_duration(elapsed), finished),
+        None => format!("done {}", finished),
+    })
+}
+
 pub(crate) fn clean_id(s: &str) -> String {
     s.chars()
         .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
@@ -98,16 +199,46 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_get_fill_color() {
-        assert_eq!(get_fill_color("stage-producer-abc"), "#EFF6FF");
-        assert_eq!(get_fill_color("stage-consumer-xyz"), "#ECFDF5");
-        assert_eq!(get_fill_color("data-worker-123"), "#ECFDF5");
-        assert_eq!(get_fill_color("partial-sum-stage"), "#FFFBEB");
-        assert_eq!(get_fill_color("total-sum-stage"), "#FFF1F2");
-        assert_eq!(get_fill_color("random-stage-name"), DEFAULT_FILL);
-        assert_eq!(get_fill_color("STAGE-PRODUCER"), "#EFF6FF");
-        assert_eq!(get_fill_color("Stage-Consumer"), "#ECFDF5");
-        assert_eq!(get_fill_color(""), DEFAULT_FILL);
+    fn test_summarize_statuses() {
+        let done = JobStatus::Succeeded {
+            location: "local".to_string(),
+        };
+        let failed = JobStatus::Failed {
+            location: "local".to_string(),
+            class: None,
+            reason: None,
+        };
+
+        assert_eq!(
+            summarize_statuses([Some(&done), Some(&done)]),
+            (StageStatus::Succeeded, 2)
+        );
+        assert_eq!(
+            summarize_statuses([Some(&done), Some(&JobStatus::Running), None]),
+            (StageStatus::Running, 1)
+        );
+        assert_eq!(
+            summarize_statuses([Some(&failed), Some(&JobStatus::Queued)]),
+            (StageStatus::Failed, 0)
+        );
+        assert_eq!(
+            summarize_statuses([Some(&done), Some(&JobStatus::Pending)]),
+            (StageStatus::Pending, 1)
+        );
+    }
+
+    #[test]
+    fn test_escape_mermaid_label() {
+        assert_eq!(escape_mermaid_label("plain"), "plain");
+        assert_eq!(escape_mermaid_label("a \"b\""), "a #quot;b#quot;");
+        assert_eq!(escape_mermaid_label("<x & y>"), "#lt;x #amp; y#gt;");
+    }
+
+    #[test]
+    fn test_escape_plantuml_label() {
+        assert_eq!(escape_plantuml_label("plain"), "plain");
+        assert_eq!(escape_plantuml_label("a \"b\""), "a 'b'");
+        assert_eq!(escape_plantuml_label("x\\y\nz"), "x\\\\y\\nz");
     }
 
     #[test]
@@ -152,4 +283,49 @@ mod tests {
         let res = smart_truncate(&boundary, 10);
         assert!(res.len() <= 10);
     }
+
+    #[test]
+    fn test_format_duration() {
+        use std::time::Duration;
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(200)), "3m 20s");
+        assert_eq!(format_duration(Duration::from_secs(7500)), "2h 05m");
+    }
+
+    #[test]
+    fn test_timing_note() {
+        use chrono::{TimeZone, Utc};
+        use repx_core::store::outcomes::JobOutcome;
+        use std::time::Duration;
+
+        let early = Utc
+            .with_ymd_and_hms(2024, 3, 1, 9, 0, 0)
+            .single()
+            .expect("timestamp");
+        let late = Utc
+            .with_ymd_and_hms(2024, 3, 1, 10, 30, 0)
+            .single()
+            .expect("timestamp");
+        let finished =
+            |at: chrono::DateTime<Utc>| at.with_timezone(&Local).format("%m-%d %H:%M").to_string();
+        let timing = |at, secs: Option<u64>| JobTiming {
+            outcome: JobOutcome::Succeeded,
+            finished_at: at,
+            elapsed: secs.map(Duration::from_secs),
+        };
+
+        assert_eq!(timing_note([]), None);
+        assert_eq!(
+            timing_note([&timing(early, Some(200))]),
+            Some(format!("3m 20s, done {}", finished(early)))
+        );
+        assert_eq!(
+            timing_note([&timing(late, Some(5)), &timing(early, Some(90))]),
+            Some(format!("max 1m 30s, last {}", finished(late)))
+        );
+        assert_eq!(
+            timing_note([&timing(early, None)]),
+            Some(format!("done {}", finished(early)))
+        );
+    }
 }
@@ -1,13 +1,33 @@
 #[macro_use]
 mod dot;
+mod audit;
+mod builtin;
+mod critical;
+mod diff;
+mod export;
+mod filter;
+#[cfg(test)]
+mod fixtures;
 mod generator;
 mod helpers;
+mod lod;
+mod mermaid;
+mod plantuml;
+mod registry;
+pub mod style;
+mod term;
 
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::str::FromStr;
+use std::time::Duration;
 
 use generator::VizGenerator;
+use helpers::format_duration;
+use repx_core::{engine::JobStatus, model::JobId, store::outcomes::JobTiming};
 
 #[derive(Debug, thiserror::Error)]
 pub enum VizError {
@@ -22,23 +42,166 @@ pub enum VizError {
 
     #[error("Graphviz 'dot' command failed with {0}")]
     GraphvizFailed(std::process::ExitStatus),
+
+    #[error("Graphviz 'dot' was not found on PATH. Install Graphviz or use '--engine builtin'.")]
+    GraphvizNotFound,
+
+    #[error("Found {0} detached or dangling job reference(s) with --strict.")]
+    Strict(usize),
 }
 
+pub const MERMAID_FORMAT: &str = "mermaid";
+pub const PLANTUML_FORMAT: &str = "plantuml";
+pub const GRAPHML_FORMAT: &str = "graphml";
+pub const JSON_FORMAT: &str = "json";
+pub const DOT_FORMAT: &str = "dot";
+pub const TERM_FORMAT: &str = "term";
+pub const STDOUT_PATH: &str = "-";
+
 pub type Result<T> = std::result::Result<T, VizError>;
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VizEngine {
+    #[default]
+    Graphviz,
+    Builtin,
+}
+
+impl FromStr for VizEngine {
+    type Err = VizError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "graphviz" | "dot" => Ok(VizEngine::Graphviz),
+            "builtin" => Ok(VizEngine::Builtin),
+            other => Err(VizError::Usage(format!(
+                "Unknown viz engine '{}'. Expected 'graphviz' or 'builtin'.",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RankDir {
+    #[default]
+    LeftRight,
+    TopBottom,
+    RightLeft,
+    BottomTop,
+}
+
+impl RankDir {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RankDir::LeftRight => "LR",
+            RankDir::TopBottom => "TB",
+            RankDir::RightLeft => "RL",
+            RankDir::BottomTop => "BT",
+        }
+    }
+}
+
+impl FromStr for RankDir {
+    type Err = VizError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_uppercase().as_str() {
+            "LR" => Ok(RankDir::LeftRight),
+            "TB" => Ok(RankDir::TopBottom),
+            "RL" => Ok(RankDir::RightLeft),
+            "BT" => Ok(RankDir::BottomTop),
+            _ => Err(VizError::Usage(format!(
+                "Unknown rank direction '{}'. Expected LR, TB, RL or BT.",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GraphvizLayout {
+    #[default]
+    Dot,
+    Neato,
+    Fdp,
+}
+
+impl GraphvizLayout {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GraphvizLayout::Dot => "dot",
+            GraphvizLayout::Neato => "neato",
+            GraphvizLayout::Fdp => "fdp",
+        }
+    }
+}
+
+impl FromStr for GraphvizLayout {
+    type Err = VizError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "dot" => Ok(GraphvizLayout::Dot),
+            "neato" => Ok(GraphvizLayout::Neato),
+            "fdp" => Ok(GraphvizLayout::Fdp),
+            other => Err(VizError::Usage(format!(
+                "Unknown Graphviz layout engine '{}'. Expected 'dot', 'neato' or 'fdp'.",
+                other
+            ))),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct VizArgs {
     pub lab: PathBuf,
     pub output: Option<PathBuf>,
     pub format: Option<String>,
+    pub engine: VizEngine,
+    pub graphviz_layout: GraphvizLayout,
+    pub rankdir: RankDir,
+    pub nodesep: Option<f64>,
+    pub ranksep: Option<f64>,
 
     pub show_pipelines: bool,
     pub show_runs: bool,
     pub show_groups: bool,
 
     pub show_params: bool,
+    pub aggregate: bool,
+    pub max_nodes: Option<usize>,
     pub show_intra_edges: bool,
     pub show_inter_edges: bool,
+    pub edge_labels: bool,
+
+    pub filter_run: Option<String>,
+    pub filter_group: Option<String>,
+    pub filter_job: Option<String>,
+
+    pub job_statuses: Option<HashMap<JobId, JobStatus>>,
+    pub diff: Option<PathBuf>,
+    pub style: Option<PathBuf>,
+
+    pub critical_path: bool,
+    pub job_durations: Option<HashMap<JobId, Duration>>,
+    pub job_timings: Option<HashMap<JobId, JobTiming>>,
+    pub strict: bool,
+}
+
+fn write_stdout(content: &str) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(content.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn notice(to_stdout: bool, message: &str) {
+    if to_stdout {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
 }
 
 pub fn run(args: VizArgs) -> Result<()> {
@@ -48,15 +211,161 @@ pub fn run(args: VizArgs) -> Result<()> {
         ));
     }
 
-    let lab = repx_core::lab::load_from_path(&args.lab)?;
+    if args.diff.is_some() && args.job_statuses.is_some() {
+        return Err(VizError::Usage(
+            "--diff cannot be combined with --with-status.".to_string(),
+        ));
+    }
 
-    let mut generator = VizGenerator::new(&lab);
-    let dot_content = generator.generate_dot(&args);
+    let to_stdout = match args.output.as_deref() {
+        Some(path) => path == Path::new(STDOUT_PATH),
+        None => args.format.as_deref() == Some(TERM_FORMAT),
+    };
+    let is_text_format = matches!(
+        args.format.as_deref(),
+        Some(
+            MERMAID_FORMAT
+                | PLANTUML_FORMAT
+                | GRAPHML_FORMAT
+                | JSON_FORMAT
+                | DOT_FORMAT
+                | TERM_FORMAT
+        )
+    );
+    if to_stdout && !is_text_format && args.engine != VizEngine::Builtin {
+        return Err(VizError::Usage(
+            "'-o -' writes to stdout and needs a text format: dot, mermaid, plantuml, graphml, json or term."
+                .to_string(),
+        ));
+    }
+
+    let mut lab = repx_core::lab::load_from_path(&args.lab)?;
+    let issues = audit::audit_lab(&lab);
+    let messages = issues.messages();
+    for message in &messages {
+        eprintln!("Warning: {}", message);
+    }
+    if args.strict && !issues.is_empty() {
+        return Err(VizError::Strict(messages.len()));
+    }
+    let mut changes = None;
+    if let Some(other) = &args.diff {
+        let old = repx_core::lab::load_from_path(other)?;
+        let (merged, diff) = diff::diff_labs(&lab, &old);
+        lab = merged;
+        changes = Some(diff);
+    }
+    if filter::is_filtered(&args) {
+        lab = filter::filter_lab(&lab, &args)?;
+    }
+
+    let mut style = style::load_style(args.style.as_deref())?;
+    if let Some(nodesep) = args.nodesep {
+        style.graph.node_sep = nodesep.to_string();
+    }
+    if let Some(ranksep) = args.ranksep {
+        style.graph.rank_sep = ranksep.to_string();
+    }
+    let mut generator = VizGenerator::new(&lab)
+        .with_aggregation(args.aggregate)
+        .with_style(style);
+    if let Some(max_nodes) = args.max_nodes {
+        generator = generator.with_max_nodes(max_nodes);
+        let collapsed = generator.collapsed_runs();
+        if !collapsed.is_empty() {
+            notice(
+                to_stdout,
+                &format!(
+                    "Collapsed {} run(s) into summary nodes to stay under {} nodes: {}.",
+                    collapsed.len(),
+                    max_nodes,
+                    collapsed.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+            );
+        }
+    }
+    if let Some(statuses) = &args.job_statuses {
+        generator = generator.with_statuses(statuses);
+    }
+    if let Some(changes) = &changes {
+        generator = generator.with_diff(changes);
+    }
+    if let Some(timings) = &args.job_timings {
+        generator = generator.with_timings(timings);
+    }
+    if args.critical_path {
+        let recorded = args.job_durations.clone().unwrap_or_default();
+        generator = generator.with_critical_path(&recorded);
+        if let Some(path) = generator.critical_path() {
+            notice(
+                to_stdout,
+                &format!(
+                    "Critical path: {} jobs, {} end to end.",
+                    path.jobs.len(),
+                    format_duration(path.total)
+                ),
+            );
+        }
+    }
 
     let output_base = args
         .output
         .clone()
         .unwrap_or_else(|| PathBuf::from("topology"));
+
+    let text_output = match args.format.as_deref() {
+        Some(MERMAID_FORMAT) => Some(("mmd", generator.generate_mermaid(&args))),
+        Some(PLANTUML_FORMAT) => Some(("puml", generator.generate_plantuml(&args))),
+        Some(GRAPHML_FORMAT) => Some(("graphml", generator.export_graph().to_graphml())),
+        Some(JSON_FORMAT) => Some(("json", generator.export_graph().to_json()?)),
+        Some(TERM_FORMAT) => Some(("txt", generator.generate_term(&args))),
+        Some(DOT_FORMAT) if to_stdout => Some(("dot", generator.generate_dot(&args))),
+        _ => None,
+    };
+    if let Some((extension, content)) = text_output {
+        if to_stdout {
+            return write_stdout(&content);
+        }
+        let output_file = output_base.with_extension(extension);
+        if let Some(parent) = output_file.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        fs::write(&output_file, content)?;
+        println!("Wrote {}.", output_file.display());
+        return Ok(());
+    }
+
+    if args.engine == VizEngine::Builtin {
+        if args.rankdir != RankDir::LeftRight || args.graphviz_layout != GraphvizLayout::Dot {
+            return Err(VizError::Usage(
+                "The builtin engine always lays out left to right. Use '--engine graphviz' for --rankdir or --graphviz-engine.".to_string(),
+            ));
+        }
+        let format = args.format.as_deref().unwrap_or("svg");
+        if format != "svg" {
+            return Err(VizError::Usage(format!(
+                "The builtin engine only renders svg (requested '{}'). Use '--engine graphviz' for other formats.",
+                format
+            )));
+        }
+        if to_stdout {
+            return write_stdout(&generator.generate_svg(&args));
+        }
+        let output_file = output_base.with_extension("svg");
+        if let Some(parent) = output_file.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        println!("Rendering {}...", output_file.display());
+        fs::write(&output_file, generator.generate_svg(&args))?;
+        println!("Done.");
+        return Ok(());
+    }
+
+    let dot_content = generator.generate_dot(&args);
     let format = args.format.unwrap_or_else(|| "png".to_string());
 
     let dot_path = if let Some(parent) = output_base.parent() {
@@ -75,11 +384,16 @@ pub fn run(args: VizArgs) -> Result<()> {
     let output_file = output_base.with_extension(&format);
 
     let status = Command::new("dot")
+        .arg(format!("-K{}", args.graphviz_layout.as_str()))
         .arg(format!("-T{}", format))
         .arg(&dot_path)
         .arg("-o")
         .arg(&output_file)
-        .status()?;
+        .status()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => VizError::GraphvizNotFound,
+            _ => VizError::Io(e),
+        })?;
 
     if !status.success() {
         return Err(VizError::GraphvizFailed(status));
@@ -0,0 +1,115 @@
+use repx_core::model::{Job, JobId, Lab};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+fn job_runs(lab: &Lab) -> HashMap<&JobId, &str> {
+    let mut run_ids: Vec<_> = lab.runs.keys().collect();
+    run_ids.sort();
+
+    let mut job_runs = HashMap::new();
+    for run_id in run_ids {
+        for jid in &lab.runs[run_id].jobs {
+            job_runs.entry(jid).or_insert(run_id.as_str());
+        }
+    }
+    job_runs
+}
+
+fn node_count(
+    lab: &Lab,
+    job_runs: &HashMap<&JobId, &str>,
+    collapsed: &BTreeSet<&str>,
+    stage_name: &impl Fn(&JobId, &Job) -> String,
+) -> usize {
+    let mut nodes: HashSet<String> = collapsed.iter().map(|run| run.to_string()).collect();
+    for (jid, job) in &lab.jobs {
+        let in_collapsed = job_runs.get(jid).is_some_and(|run| collapsed.contains(run));
+        if !in_collapsed {
+            nodes.insert(format!("stage:{}", stage_name(jid, job)));
+        }
+    }
+    nodes.len()
+}
+
+pub(crate) fn collapse_runs(
+    lab: &Lab,
+    max_nodes: usize,
+    stage_name: impl Fn(&JobId, &Job) -> String,
+) -> HashMap<JobId, String> {
+    let job_runs = job_runs(lab);
+    let mut collapsed: BTreeSet<&str> = BTreeSet::new();
+    if node_count(lab, &job_runs, &collapsed, &stage_name) <= max_nodes {
+        return HashMap::new();
+    }
+
+    let mut candidates: Vec<(usize, &str)> = lab
+        .runs
+        .keys()
+        .map(|run_id| {
+            let stages: HashSet<String> = job_runs
+                .iter()
+                .filter(|(_, run)| **run == run_id.as_str())
+                .filter_map(|(jid, _)| Some(stage_name(jid, lab.jobs.get(*jid)?)))
+                .collect();
+            (stages.len(), run_id.as_str())
+        })
+        .filter(|(stages, _)| *stages > 1)
+        .collect();
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+
+    for (_, run) in candidates {
+        collapsed.insert(run);
+        if node_count(lab, &job_runs, &collapsed, &stage_name) <= max_nodes {
+            break;
+        }
+    }
+
+    job_runs
+        .into_iter()
+        .filter(|(_, run)| collapsed.contains(run))
+        .map(|(jid, run)| (jid.clone(), run.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{job, lab};
+    use repx_core::model::{Run, RunId};
+
+    fn per_job(jid: &JobId, _: &Job) -> String {
+        jid.to_string()
+    }
+
+    #[test]
+    fn test_small_lab_is_not_collapsed() {
+        let lab = lab();
+        assert!(collapse_runs(&lab, 10, per_job).is_empty());
+    }
+
+    #[test]
+    fn test_largest_run_collapses_first() {
+        let mut lab = lab();
+        let mut sweep = Vec::new();
+        for i in 0..5 {
+            let jid = JobId::from(format!("s{}", i));
+            lab.jobs.insert(jid.clone(), job("sweep", Some("p1")));
+            sweep.push(jid);
+        }
+        lab.runs.insert(
+            RunId::from("sweep"),
+            Run {
+                image: None,
+                jobs: sweep,
+                dependencies: HashMap::new(),
+            },
+        );
+
+        let collapsed = collapse_runs(&lab, 6, per_job);
+        assert_eq!(collapsed.len(), 5);
+        assert!(collapsed.values().all(|run| run == "sweep"));
+
+        let collapsed = collapse_runs(&lab, 2, per_job);
+        assert_eq!(collapsed.len(), 7);
+        assert!(!collapsed.contains_key(&JobId::from("r1")));
+    }
+}
@@ -0,0 +1,454 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::generator::{Topology, VizGenerator};
+use crate::helpers::*;
+use crate::VizArgs;
+
+fn edge_label(
+    labels: &HashMap<(String, String), Vec<String>>,
+    clean_src: &str,
+    clean_tgt: &str,
+) -> String {
+    labels
+        .get(&(clean_src.to_string(), clean_tgt.to_string()))
+        .map(|names| {
+            let names: Vec<String> = names.iter().map(|n| escape_mermaid_label(n)).collect();
+            format!("|\"{}\"|", names.join("<br/>"))
+        })
+        .unwrap_or_default()
+}
+
+fn pipe_ref(generator: &VizGenerator, clean_name: &str) -> String {
+    if generator.is_scatter_gather(clean_name) {
+        format!("pipe_{}_sg", clean_name)
+    } else {
+        format!("pipe_{}", clean_name)
+    }
+}
+
+impl VizGenerator<'_> {
+    pub fn generate_mermaid(&self, args: &VizArgs) -> String {
+        let mut out = String::new();
+        dot_writeln!(out, "flowchart {}", args.rankdir.as_str());
+
+        let Topology {
+            job_to_run,
+            pipeline_jobs,
+            pipeline_representative,
+            run_pipelines,
+        } = self.topology();
+
+        let mut classes: BTreeMap<&'static str, (&'static str, &'static str, Vec<String>)> =
+            BTreeMap::new();
+        let mut critical_nodes = Vec::new();
+        let mut detached_nodes = Vec::new();
+        let labels = if args.edge_labels {
+            self.collect_edge_labels()
+        } else {
+            HashMap::new()
+        };
+
+        if args.show_pipelines {
+            for (pipeline_name, job_ids) in &pipeline_jobs {
+                let clean_pipe = self.stage_node(pipeline_name);
+                let node_id = format!("pipe_{}", clean_pipe);
+                let overlay = self.stage_overlay(job_ids);
+                let mut label = escape_mermaid_label(pipeline_name);
+                if self.aggregates() {
+                    label.push_str(&format!("<br/>(x{})", job_ids.len()));
+                }
+                if let Some(timing) = self.stage_timing(job_ids) {
+                    label.push_str(&format!("<br/>{}", escape_mermaid_label(&timing)));
+                }
+                if let Some(overlay) = &overlay {
+                    label.push_str(&format!("<br/>{}", escape_mermaid_label(&overlay.note)));
+                }
+
+                if self.is_critical_stage(&clean_pipe) {
+                    critical_nodes.push(pipe_ref(self, &clean_pipe));
+                }
+
+                if self.is_scatter_gather(&clean_pipe) {
+                    self.write_scatter_gather(
+                        &mut out,
+                        &node_id,
+                        &label,
+                        pipeline_representative.get(pipeline_name).copied(),
+                    );
+                    if let Some(overlay) = overlay {
+                        classes
+                            .entry(overlay.class)
+                            .or_insert((overlay.fill, overlay.border, Vec::new()))
+                            .2
+                            .push(format!("{}_sg", node_id));
+                    }
+                } else {
+                    dot_writeln!(out, "    {}(\"{}\")", node_id, label);
+                    if let Some(overlay) = overlay {
+                        classes
+                            .entry(overlay.class)
+                            .or_insert((overlay.fill, overlay.border, Vec::new()))
+                            .2
+                            .push(node_id.clone());
+                    }
+                }
+
+                if args.show_params {
+                    for (p_key, p_vals) in self.stage_params(job_ids) {
+                        let param_id = format!("pparam_{}_{}", clean_pipe, clean_id(&p_key));
+                        let vals: Vec<String> = p_vals
+                            .iter()
+                            .map(|v| smart_truncate(v, PARAM_MAX_WIDTH))
+                            .collect();
+                        dot_writeln!(
+                            out,
+                            "    {}[/\"{}:<br/>{}\"/]",
+                            param_id,
+                            escape_mermaid_label(&p_key),
+                            escape_mermaid_label(&vals.join(", "))
+                        );
+                        dot_writeln!(out, "    {} -.- {}", param_id, pipe_ref(self, &clean_pipe));
+                    }
+                }
+            }
+
+            if args.show_intra_edges {
+                for (clean_src, clean_tgt) in self.collect_intra_edges() {
+                    let arrow = if self.is_critical_edge(&clean_src, &clean_tgt) {
+                        "==>"
+                    } else {
+                        "-->"
+                    };
+                    dot_writeln!(
+                        out,
+                        "    {} {}{} {}",
+                        pipe_ref(self, &clean_src),
+                        arrow,
+                        edge_label(&labels, &clean_src, &clean_tgt),
+                        pipe_ref(self, &clean_tgt)
+                    );
+                }
+            }
+        }
+
+        if args.show_runs {
+            let run_to_group = if args.show_groups {
+                self.run_to_group()
+            } else {
+                HashMap::new()
+            };
+            let mut grouped: BTreeMap<&str, Vec<&String>> = BTreeMap::new();
+            for run_name in run_pipelines.keys() {
+                let group = run_to_group.get(run_name).map(String::as_str).unwrap_or("");
+                grouped.entry(group).or_default().push(run_name);
+            }
+            for (group, runs) in grouped {
+                let indent = if group.is_empty() {
+                    "    "
+                } else {
+                    dot_writeln!(
+                        out,
+                        "    subgraph group_{}[\"@{}\"]",
+                        clean_id(group),
+                        escape_mermaid_label(group)
+                    );
+                    "        "
+                };
+                for run_name in runs {
+                    if self.is_detached_run(run_name) {
+                        detached_nodes.push(format!("run_{}", self.run_node(run_name)));
+                    }
+                    write_run_node(
+                        &mut out,
+                        (&self.run_node(run_name), run_name),
+                        &run_pipelines[run_name],
+                        indent,
+                    );
+                }
+                if !group.is_empty() {
+                    out.push_str("    end\n");
+                }
+            }
+        }
+
+        if args.show_pipelines && args.show_inter_edges {
+            for (clean_src, clean_tgt, style) in self.collect_inter_run_edges(&job_to_run) {
+                let arrow = if self.is_critical_edge(&clean_src, &clean_tgt) {
+                    "==>"
+                } else if style == "dashed" {
+                    "-.->"
+                } else {
+                    "-->"
+                };
+                dot_writeln!(
+                    out,
+                    "    {} {}{} {}",
+                    pipe_ref(self, &clean_src),
+                    arrow,
+                    edge_label(&labels, &clean_src, &clean_tgt),
+                    pipe_ref(self, &clean_tgt)
+                );
+            }
+        }
+
+        if args.show_pipelines && args.show_runs {
+            for (run_name, pipelines) in &run_pipelines {
+                for pipeline_name in pipelines {
+                    dot_writeln!(
+                        out,
+                        "    run_{} -.-> {}",
+                        self.run_node(run_name),
+                        pipe_ref(self, &self.stage_node(pipeline_name))
+                    );
+                }
+            }
+        }
+
+        for (class, (fill, border, nodes)) in classes {
+            dot_writeln!(
+                out,
+                "    classDef {} fill:{},stroke:{},stroke-width:2px",
+                class,
+                fill,
+                border
+            );
+            dot_writeln!(out, "    class {} {}", nodes.join(","), class);
+        }
+        if !detached_nodes.is_empty() {
+            dot_writeln!(
+                out,
+                "    classDef detached fill:{},stroke:{},stroke-dasharray:5 5",
+                DETACHED_FILL,
+                DETACHED_COLOR
+            );
+            dot_writeln!(out, "    class {} detached", detached_nodes.join(","));
+        }
+        if !critical_nodes.is_empty() {
+            dot_writeln!(
+                out,
+                "    classDef critical stroke:{},stroke-width:4px",
+                CRITICAL_COLOR
+            );
+            dot_writeln!(out, "    class {} critical", critical_nodes.join(","));
+        }
+
+        out
+    }
+
+    fn write_scatter_gather(
+        &self,
+        out: &mut String,
+        node_id: &str,
+        label: &str,
+        representative: Option<&repx_core::model::Job>,
+    ) {
+        dot_writeln!(out, "    subgraph {}_sg[\"{}\"]", node_id, label);
+        dot_writeln!(out, "        {}_sg_scatter[/\"scatter\"\\]", node_id);
+        dot_writeln!(out, "        {}_sg_gather[\\\"gather\"/]", node_id);
+
+        let steps: BTreeMap<String, Vec<String>> = representative
+            .map(|job| {
+                job.executables
+                    .iter()
+                    .filter_map(|(key, exe)| {
+                        key.strip_prefix("step-")
+                            .map(|name| (name.to_string(), exe.deps.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let depended_on: BTreeSet<&String> = steps.values().flatten().collect();
+
+        for (step_name, deps) in &steps {
+            let step_id = format!("{}_sg_step_{}", node_id, clean_id(step_name));
+            dot_writeln!(
+                out,
+                "        {}(\"{}\")",
+                step_id,
+                escape_mermaid_label(step_name)
+            );
+            if deps.is_empty() {
+                dot_writeln!(out, "        {}_sg_scatter --> {}", node_id, step_id);
+            }
+            for dep in deps {
+                dot_writeln!(
+                    out,
+                    "        {}_sg_step_{} --> {}",
+                    node_id,
+                    clean_id(dep),
+                    step_id
+                );
+            }
+            if !depended_on.contains(step_name) {
+                dot_writeln!(out, "        {} --> {}_sg_gather", step_id, node_id);
+            }
+        }
+        out.push_str("    end\n");
+    }
+}
+
+fn write_run_node(
+    out: &mut String,
+    (node_id, run_name): (&str, &str),
+    pipelines: &BTreeSet<String>,
+    indent: &str,
+) {
+    let pipe_list = pipelines
+        .iter()
+        .map(|p| escape_mermaid_label(p))
+        .collect::<Vec<_>>()
+        .join(", ");
+    dot_writeln!(
+        out,
+        "{}run_{}[\"{}<br/>pipelines: {}\"]",
+        indent,
+        node_id,
+        escape_mermaid_label(run_name),
+        pipe_list
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{job, lab, viz_args};
+    use repx_core::engine::JobStatus;
+    use repx_core::model::JobId;
+
+    #[test]
+    fn test_mermaid_flowchart_has_nodes_and_edges() {
+        let lab = lab();
+        let out = VizGenerator::new(&lab).generate_mermaid(&viz_args());
+
+        assert!(out.starts_with("flowchart LR\n"));
+        assert!(out.contains("pipe_producer(\"producer<br/>(x1)\")"));
+        assert!(out.contains("pipe_producer --> pipe_consumer"));
+        assert!(!out.contains("classDef"));
+    }
+
+    #[test]
+    fn test_mermaid_status_classes() {
+        let lab = lab();
+        let statuses = HashMap::from([
+            (
+                JobId::from("p1"),
+                JobStatus::Succeeded {
+                    location: "local".to_string(),
+                },
+            ),
+            (JobId::from("c1"), JobStatus::Running),
+        ]);
+        let out = VizGenerator::new(&lab)
+            .with_statuses(&statuses)
+            .generate_mermaid(&viz_args());
+
+        assert!(out.contains("1/1 done"));
+        assert!(out.contains("class pipe_producer succeeded"));
+        assert!(out.contains("class pipe_consumer running"));
+    }
+
+    #[test]
+    fn test_mermaid_marks_detached_run() {
+        let mut lab = lab();
+        lab.jobs.insert(JobId::from("x1"), job("stray", None));
+        let mut args = viz_args();
+        args.show_runs = true;
+        let out = VizGenerator::new(&lab).generate_mermaid(&args);
+
+        assert!(out.contains("run_detached[\"detached<br/>pipelines: stray\"]"));
+        assert!(out.contains("class run_detached detached"));
+        assert!(!out.contains("class run_sim detached"));
+    }
+
+    #[test]
+    fn test_mermaid_edge_labels() {
+        let mut lab = lab();
+        if let Some(exe) = lab
+            .jobs
+            .get_mut(&JobId::from("c1"))
+            .and_then(|job| job.executables.get_mut("main"))
+        {
+            exe.inputs[0].source_output = Some("data".to_string());
+        }
+        let mut args = viz_args();
+        args.edge_labels = true;
+        let out = VizGenerator::new(&lab).generate_mermaid(&args);
+
+        assert!(out.contains("pipe_producer -->|\"data → x\"| pipe_consumer"));
+        assert!(out.contains("pipe_consumer -->|\"x\"| pipe_report"));
+    }
+
+    #[test]
+    fn test_mermaid_follows_rankdir() {
+        let lab = lab();
+        let mut args = viz_args();
+        args.rankdir = "tb".parse().unwrap_or_default();
+        let out = VizGenerator::new(&lab).generate_mermaid(&args);
+
+        assert!(out.starts_with("flowchart TB\n"));
+    }
+
+    #[test]
+    fn test_mermaid_per_instance_nodes() {
+        let mut lab = lab();
+        let mut second = job("producer", None);
+        second.params = serde_json::json!({"seed": 2});
+        lab.jobs.insert(JobId::from("p2"), second);
+        if let Some(first) = lab.jobs.get_mut(&JobId::from("p1")) {
+            first.params = serde_json::json!({"seed": 1});
+        }
+        let mut args = viz_args();
+        args.show_params = true;
+        let out = VizGenerator::new(&lab)
+            .with_aggregation(false)
+            .generate_mermaid(&args);
+
+        assert!(out.contains("pipe_producerp1(\"producer [p1]\")"));
+        assert!(out.contains("pipe_producerp2(\"producer [p2]\")"));
+        assert!(out.contains("pipe_producerp1 --> pipe_consumerc1"));
+        assert!(!out.contains("pipe_producerp2 --> pipe_consumerc1"));
+        assert!(out.contains("pparam_producerp2_seed"));
+        assert!(!out.contains("(x"));
+    }
+
+    #[test]
+    fn test_mermaid_timing_annotations() {
+        use chrono::{TimeZone, Utc};
+        use repx_core::store::outcomes::{JobOutcome, JobTiming};
+
+        let lab = lab();
+        let finished_at = Utc
+            .with_ymd_and_hms(2024, 3, 1, 9, 0, 0)
+            .single()
+            .expect("timestamp");
+        let timings = HashMap::from([(
+            JobId::from("p1"),
+            JobTiming {
+                outcome: JobOutcome::Succeeded,
+                finished_at,
+                elapsed: Some(std::time::Duration::from_secs(75)),
+            },
+        )]);
+        let out = VizGenerator::new(&lab)
+            .with_timings(&timings)
+            .generate_mermaid(&viz_args());
+
+        assert!(out.contains("producer<br/>(x1)<br/>1m 15s, done "));
+        assert!(out.contains("pipe_consumer(\"consumer<br/>(x1)\")"));
+    }
+
+    #[test]
+    fn test_mermaid_keeps_colliding_stage_names_apart() {
+        let mut lab = lab();
+        lab.jobs
+            .insert(JobId::from("c2"), job("con-sumer", Some("p1")));
+        let out = VizGenerator::new(&lab).generate_mermaid(&viz_args());
+
+        assert!(out.contains("pipe_consumer(\"con-sumer<br/>(x1)\")"));
+        assert!(out.contains("pipe_consumer_2(\"consumer<br/>(x1)\")"));
+        assert!(out.contains("pipe_producer --> pipe_consumer\n"));
+        assert!(out.contains("pipe_producer --> pipe_consumer_2\n"));
+        assert!(out.contains("pipe_consumer_2 --> pipe_report"));
+        assert!(!out.contains("pipe_consumer --> pipe_report"));
+    }
+}
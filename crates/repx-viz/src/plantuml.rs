@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::generator::{Topology, VizGenerator};
+use crate::helpers::*;
+use crate::{RankDir, VizArgs};
+
+fn color(value: &str) -> &str {
+    value.trim_start_matches('#')
+}
+
+fn edge_label(
+    labels: &HashMap<(String, String), Vec<String>>,
+    clean_src: &str,
+    clean_tgt: &str,
+) -> String {
+    labels
+        .get(&(clean_src.to_string(), clean_tgt.to_string()))
+        .map(|names| {
+            let names: Vec<String> = names.iter().map(|n| escape_plantuml_label(n)).collect();
+            format!(" : {}", names.join("\\n"))
+        })
+        .unwrap_or_default()
+}
+
+fn pipe_ref(generator: &VizGenerator, clean_name: &str) -> String {
+    if generator.is_scatter_gather(clean_name) {
+        format!("pipe_{}_sg", clean_name)
+    } else {
+        format!("pipe_{}", clean_name)
+    }
+}
+
+impl VizGenerator<'_> {
+    fn plantuml_arrow(&self, clean_src: &str, clean_tgt: &str, style: &str) -> String {
+        if self.is_critical_edge(clean_src, clean_tgt) {
+            format!("-[#{},bold]->", color(CRITICAL_COLOR))
+        } else if style == "dashed" {
+            "..>".to_string()
+        } else {
+            "-->".to_string()
+        }
+    }
+
+    pub fn generate_plantuml(&self, args: &VizArgs) -> String {
+        let mut out = String::new();
+        out.push_str("@startuml\n");
+        if matches!(args.rankdir, RankDir::LeftRight | RankDir::RightLeft) {
+            out.push_str("left to right direction\n");
+        }
+        let font = self
+            .style()
+            .fonts
+            .family
+            .split(',')
+            .next()
+            .unwrap_or_default()
+            .trim();
+        dot_writeln!(out, "skinparam defaultFontName \"{}\"", font);
+        out.push_str("skinparam shadowing false\n");
+
+        let Topology {
+            job_to_run,
+            pipeline_jobs,
+            pipeline_representative,
+            run_pipelines,
+        } = self.topology();
+
+        let labels = if args.edge_labels {
+            self.collect_edge_labels()
+        } else {
+            HashMap::new()
+        };
+
+        if args.show_pipelines {
+            for (pipeline_name, job_ids) in &pipeline_jobs {
+                let clean_pipe = self.stage_node(pipeline_name);
+                let node_id = format!("pipe_{}", clean_pipe);
+                let overlay = self.stage_overlay(job_ids);
+
+                let mut lines = vec![escape_plantuml_label(pipeline_name)];
+                if self.aggregates() {
+                    lines.push(format!("(x{})", job_ids.len()));
+                }
+                if let Some(timing) = self.stage_timing(job_ids) {
+                    lines.push(escape_plantuml_label(&timing));
+                }
+                if let Some(overlay) = &overlay {
+                    lines.push(escape_plantuml_label(&overlay.note));
+                }
+                let label = lines.join("\\n");
+
+                let (fill, border) = match &overlay {
+                    Some(overlay) => (overlay.fill, overlay.border),
+                    None => (
+                        self.style().stage_fill(pipeline_name),
+                        self.style().clusters.border.as_str(),
+                    ),
+                };
+                let mut colors = format!("#back:{};line:{}", color(fill), color(border));
+                if self.is_critical_stage(&clean_pipe) {
+                    colors = format!(
+                        "#back:{};line:{};line.bold",
+                        color(fill),
+                        color(CRITICAL_COLOR)
+                    );
+                }
+
+                if self.is_scatter_gather(&clean_pipe) {
+                    self.write_plantuml_scatter_gather(
+                        &mut out,
+                        (&node_id, &label, &colors),
+                        pipeline_representative.get(pipeline_name).copied(),
+                    );
+                } else {
+                    dot_writeln!(out, "rectangle \"{}\" as {} {}", label, node_id, colors);
+                }
+
+                if args.show_params {
+                    for (p_key, p_vals) in self.stage_params(job_ids) {
+                        let param_id = format!("pparam_{}_{}", clean_pipe, clean_id(&p_key));
+                        let vals: Vec<String> = p_vals
+                            .iter()
+                            .map(|v| smart_truncate(v, PARAM_MAX_WIDTH))
+                            .collect();
+                        dot_writeln!(
+                            out,
+                            "note \"{}:\\n{}\" as {}",
+                            escape_plantuml_label(&p_key),
+                            escape_plantuml_label(&vals.join(", ")),
+                            param_id
+                        );
+                        dot_writeln!(out, "{} .. {}", param_id, pipe_ref(self, &clean_pipe));
+                    }
+                }
+            }
+
+            if args.show_intra_edges {
+                for (clean_src, clean_tgt) in self.collect_intra_edges() {
+                    dot_writeln!(
+                        out,
+                        "{} {} {}{}",
+                        pipe_ref(self, &clean_src),
+                        self.plantuml_arrow(&clean_src, &clean_tgt, "solid"),
+                        pipe_ref(self, &clean_tgt),
+                        edge_label(&labels, &clean_src, &clean_tgt)
+                    );
+                }
+            }
+        }
+
+        if args.show_runs {
+            let run_to_group = if args.show_groups {
+                self.run_to_group()
+            } else {
+                HashMap::new()
+            };
+            let mut grouped: BTreeMap<&str, Vec<&String>> = BTreeMap::new();
+            for run_name in run_pipelines.keys() {
+                let group = run_to_group.get(run_name).map(String::as_str).unwrap_or("");
+                grouped.entry(group).or_default().push(run_name);
+            }
+            for (group, runs) in grouped {
+                if !group.is_empty() {
+                    dot_writeln!(out, "package \"@{}\" {{", escape_plantuml_label(group));
+                }
+                for run_name in runs {
+                    self.write_plantuml_run(&mut out, run_name, &run_pipelines[run_name]);
+                }
+                if !group.is_empty() {
+                    out.push_str("}\n");
+                }
+            }
+        }
+
+        if args.show_pipelines && args.show_inter_edges {
+            for (clean_src, clean_tgt, style) in self.collect_inter_run_edges(&job_to_run) {
+                dot_writeln!(
+                    out,
+                    "{} {} {}{}",
+                    pipe_ref(self, &clean_src),
+                    self.plantuml_arrow(&clean_src, &clean_tgt, style),
+                    pipe_ref(self, &clean_tgt),
+                    edge_label(&labels, &clean_src, &clean_tgt)
+                );
+            }
+        }
+
+        if args.show_pipelines && args.show_runs {
+            for (run_name, pipelines) in &run_pipelines {
+                for pipeline_name in pipelines {
+                    dot_writeln!(
+                        out,
+                        "run_{} ..> {}",
+                        self.run_node(run_name),
+                        pipe_ref(self, &self.stage_node(pipeline_name))
+                    );
+                }
+            }
+        }
+
+        out.push_str("@enduml\n");
+        out
+    }
+
+    fn write_plantuml_run(&self, out: &mut String, run_name: &str, pipelines: &BTreeSet<String>) {
+        let colors = if self.is_detached_run(run_name) {
+            format!(
+                "#back:{};line:{};line.dashed",
+                color(DETACHED_FILL),
+                color(DETACHED_COLOR)
+            )
+        } else {
+            format!(
+                "#back:{};line:{}",
+                color(&self.style().palette.run_fill),
+                color(&self.style().clusters.border)
+            )
+        };
+        let pipe_list = pipelines
+            .iter()
+            .map(|p| escape_plantuml_label(p))
+            .collect::<Vec<_>>()
+            .join(", ");
+        dot_writeln!(
+            out,
+            "card \"{}\\npipelines: {}\" as run_{} {}",
+            escape_plantuml_label(run_name),
+            pipe_list,
+            self.run_node(run_name),
+            colors
+        );
+    }
+
+    fn write_plantuml_scatter_gather(
+        &self,
+        out: &mut String,
+        (node_id, label, colors): (&str, &str, &str),
+        representative: Option<&repx_core::model::Job>,
+    ) {
+        let clusters = &self.style().clusters;
+        dot_writeln!(
+            out,
+            "rectangle \"{}\" as {}_sg {} {{",
+            label,
+            node_id,
+            colors
+        );
+        dot_writeln!(
+            out,
+            "  rectangle \"scatter\" as {}_sg_scatter #back:{}",
+            node_id,
+            color(&clusters.sg_scatter_fill)
+        );
+        dot_writeln!(
+            out,
+            "  rectangle \"gather\" as {}_sg_gather #back:{}",
+            node_id,
+            color(&clusters.sg_gather_fill)
+        );
+
+        let steps: BTreeMap<String, Vec<String>> = representative
+            .map(|job| {
+                job.executables
+                    .iter()
+                    .filter_map(|(key, exe)| {
+                        key.strip_prefix("step-")
+                            .map(|name| (name.to_string(), exe.deps.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let depended_on: BTreeSet<&String> = steps.values().flatten().collect();
+
+        for step_name in steps.keys() {
+            dot_writeln!(
+                out,
+                "  rectangle \"{}\" as {}_sg_step_{} #back:{};line:{}",
+                escape_plantuml_label(step_name),
+                node_id,
+                clean_id(step_name),
+                color(&clusters.sg_step_fill),
+                color(&clusters.sg_step_border)
+            );
+        }
+        out.push_str("}\n");
+
+        for (step_name, deps) in &steps {
+            let step_id = format!("{}_sg_step_{}", node_id, clean_id(step_name));
+            if deps.is_empty() {
+                dot_writeln!(out, "{}_sg_scatter --> {}", node_id, step_id);
+            }
+            for dep in deps {
+                dot_writeln!(out, "{}_sg_step_{} --> {}", node_id, clean_id(dep), step_id);
+            }
+            if !depended_on.contains(step_name) {
+                dot_writeln!(out, "{} --> {}_sg_gather", step_id, node_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{lab, viz_args};
+    use repx_core::engine::JobStatus;
+    use repx_core::model::JobId;
+
+    #[test]
+    fn test_plantuml_has_nodes_and_edges() {
+        let lab = lab();
+        let mut args = viz_args();
+        args.show_runs = true;
+        let out = VizGenerator::new(&lab).generate_plantuml(&args);
+
+        assert!(out.starts_with("@startuml\nleft to right direction\n"));
+        assert!(out.trim_end().ends_with("@enduml"));
+        assert!(
+            out.contains("rectangle \"producer\\n(x1)\" as pipe_producer #back:EFF6FF;line:334155")
+        );
+        assert!(out.contains("pipe_producer --> pipe_consumer\n"));
+        assert!(out.contains("card \"sim\\npipelines: consumer, producer\" as run_sim"));
+        assert!(out.contains("run_sim ..> pipe_producer"));
+    }
+
+    #[test]
+    fn test_plantuml_status_colors() {
+        let lab = lab();
+        let statuses = HashMap::from([(JobId::from("c1"), JobStatus::Running)]);
+        let mut args = viz_args();
+        args.rankdir = RankDir::TopBottom;
+        let out = VizGenerator::new(&lab)
+            .with_statuses(&statuses)
+            .generate_plantuml(&args);
+
+        assert!(!out.contains("left to right direction"));
+        assert!(out.contains("as pipe_consumer #back:DBEAFE;line:2563EB"));
+    }
+}
@@ -0,0 +1,108 @@
+use repx_core::model::{Job, JobId, Lab};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::helpers::{clean_id, DETACHED_RUN};
+
+fn assign_keys<'n>(
+    names: impl IntoIterator<Item = &'n String>,
+    fallback: &str,
+) -> HashMap<String, String> {
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut keys = HashMap::new();
+    for name in names {
+        let mut base = clean_id(name);
+        if base.is_empty() {
+            base = fallback.to_string();
+        }
+        let mut key = base.clone();
+        let mut n = 2;
+        while !taken.insert(key.clone()) {
+            key = format!("{}_{}", base, n);
+            n += 1;
+        }
+        keys.insert(name.clone(), key);
+    }
+    keys
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct NodeRegistry {
+    stages: HashMap<String, String>,
+    jobs: HashMap<JobId, String>,
+    runs: HashMap<String, String>,
+}
+
+impl NodeRegistry {
+    pub(crate) fn build(lab: &Lab, stage_name: impl Fn(&JobId, &Job) -> String) -> Self {
+        let job_stages: HashMap<&JobId, String> = lab
+            .jobs
+            .iter()
+            .map(|(jid, job)| (jid, stage_name(jid, job)))
+            .collect();
+        let stage_names: BTreeSet<&String> = job_stages.values().collect();
+        let stages = assign_keys(stage_names, "stage");
+
+        let jobs = job_stages
+            .into_iter()
+            .map(|(jid, name)| (jid.clone(), stages[&name].clone()))
+            .collect();
+
+        let mut run_names: BTreeSet<String> = lab.runs.keys().map(|r| r.to_string()).collect();
+        run_names.insert(DETACHED_RUN.to_string());
+        let runs = assign_keys(&run_names, "run");
+
+        Self { stages, jobs, runs }
+    }
+
+    pub(crate) fn stage(&self, name: &str) -> String {
+        self.stages
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| clean_id(name))
+    }
+
+    pub(crate) fn job(&self, job_id: &JobId) -> Option<&str> {
+        self.jobs.get(job_id).map(String::as_str)
+    }
+
+    pub(crate) fn run(&self, name: &str) -> String {
+        self.runs
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| clean_id(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{job, lab};
+    use repx_core::model::{Run, RunId};
+
+    fn by_name(jid: &JobId, job: &Job) -> String {
+        job.name.clone().unwrap_or_else(|| jid.to_string())
+    }
+
+    #[test]
+    fn test_colliding_names_get_distinct_keys() {
+        let mut lab = lab();
+        lab.jobs
+            .insert(JobId::from("c2"), job("con-sumer", Some("p1")));
+        lab.runs.insert(
+            RunId::from("s-im"),
+            Run {
+                image: None,
+                jobs: vec![JobId::from("c2")],
+                dependencies: HashMap::new(),
+            },
+        );
+
+        let registry = NodeRegistry::build(&lab, by_name);
+        assert_eq!(registry.stage("con-sumer"), "consumer");
+        assert_eq!(registry.stage("consumer"), "consumer_2");
+        assert_eq!(registry.job(&JobId::from("c1")), Some("consumer_2"));
+        assert_eq!(registry.job(&JobId::from("c2")), Some("consumer"));
+        assert_eq!(registry.job(&JobId::from("p1")), Some("producer"));
+        assert_ne!(registry.run("s-im"), registry.run("sim"));
+    }
+}
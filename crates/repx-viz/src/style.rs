@@ -0,0 +1,204 @@
+use repx_core::{config, errors::CoreError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub const STYLE_FILE_NAME: &str = "viz.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontStyles {
+    pub family: String,
+    pub stage_size: String,
+    pub group_size: String,
+    pub param_size: String,
+    pub sg_phase_size: String,
+    pub sg_step_size: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphStyles {
+    pub dpi: String,
+    pub pad: String,
+    pub node_sep: String,
+    pub rank_sep: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShapeStyles {
+    pub stage: String,
+    pub param: String,
+    pub scatter: String,
+    pub gather: String,
+    pub step: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaletteStyles {
+    pub default_fill: String,
+    pub run_fill: String,
+    pub stages: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterStyles {
+    pub border: String,
+    pub group_border: String,
+    pub sg_border: String,
+    pub sg_background: String,
+    pub sg_scatter_fill: String,
+    pub sg_gather_fill: String,
+    pub sg_step_fill: String,
+    pub sg_step_border: String,
+    pub sg_edge: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParamStyles {
+    pub fill: String,
+    pub border: String,
+    pub font_color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VizStyle {
+    pub fonts: FontStyles,
+    pub graph: GraphStyles,
+    pub shapes: ShapeStyles,
+    pub palette: PaletteStyles,
+    pub clusters: ClusterStyles,
+    pub params: ParamStyles,
+}
+
+impl Default for VizStyle {
+    fn default() -> Self {
+        Self {
+            fonts: FontStyles {
+                family: "Helvetica, Arial, sans-serif".to_string(),
+                stage_size: "12".to_string(),
+                group_size: "16".to_string(),
+                param_size: "9".to_string(),
+                sg_phase_size: "10".to_string(),
+                sg_step_size: "9".to_string(),
+            },
+            graph: GraphStyles {
+                dpi: "300".to_string(),
+                pad: "0.5".to_string(),
+                node_sep: "0.4".to_string(),
+                rank_sep: "0.6".to_string(),
+            },
+            shapes: ShapeStyles {
+                stage: "box".to_string(),
+                param: "note".to_string(),
+                scatter: "trapezium".to_string(),
+                gather: "invtrapezium".to_string(),
+                step: "box".to_string(),
+            },
+            palette: PaletteStyles {
+                default_fill: "#F8FAFC".to_string(),
+                run_fill: "#F1F5F9".to_string(),
+                stages: BTreeMap::from([
+                    ("producer".to_string(), "#EFF6FF".to_string()),
+                    ("consumer".to_string(), "#ECFDF5".to_string()),
+                    ("worker".to_string(), "#ECFDF5".to_string()),
+                    ("partial".to_string(), "#FFFBEB".to_string()),
+                    ("total".to_string(), "#FFF1F2".to_string()),
+                ]),
+            },
+            clusters: ClusterStyles {
+                border: "#334155".to_string(),
+                group_border: "#1e40af".to_string(),
+                sg_border: "#6366f1".to_string(),
+                sg_background: "#EEF2FF".to_string(),
+                sg_scatter_fill: "#C7D2FE".to_string(),
+                sg_gather_fill: "#C7D2FE".to_string(),
+                sg_step_fill: "#E0E7FF".to_string(),
+                sg_step_border: "#818CF8".to_string(),
+                sg_edge: "#6366f1".to_string(),
+            },
+            params: ParamStyles {
+                fill: "#FFFFFF".to_string(),
+                border: "#94a3b8".to_string(),
+                font_color: "#475569".to_string(),
+            },
+        }
+    }
+}
+
+impl VizStyle {
+    pub fn stage_fill(&self, name: &str) -> &str {
+        let name_lower = name.to_lowercase();
+        self.palette
+            .stages
+            .iter()
+            .filter(|(keyword, _)| name_lower.contains(&keyword.to_lowercase()))
+            .max_by_key(|(keyword, _)| keyword.len())
+            .map(|(_, fill)| fill.as_str())
+            .unwrap_or(&self.palette.default_fill)
+    }
+}
+
+fn merge_style_file(base: &mut toml::Value, path: &Path) -> Result<(), CoreError> {
+    tracing::debug!("Loading viz style from: {}", path.display());
+    let content = fs::read_to_string(path).map_err(|e| CoreError::path_io(path, e))?;
+    let value: toml::Value = toml::from_str(&content).map_err(|e| CoreError::toml_path(path, e))?;
+    config::merge_toml_values(base, &value);
+    Ok(())
+}
+
+pub fn load_style(extra_path: Option<&Path>) -> Result<VizStyle, CoreError> {
+    let mut style_value = toml::Value::try_from(VizStyle::default()).map_err(|e| {
+        CoreError::SerializationError(format!("Failed to serialize default viz style: {}", e))
+    })?;
+
+    if let Some(path) = repx_core::xdg_dirs().find_config_file(STYLE_FILE_NAME) {
+        merge_style_file(&mut style_value, &path)?;
+    }
+    if let Some(path) = extra_path {
+        merge_style_file(&mut style_value, path)?;
+    }
+
+    style_value.try_into::<VizStyle>().map_err(|e| {
+        CoreError::SerializationError(format!("Failed to deserialize merged viz style: {}", e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_fill() {
+        let style = VizStyle::default();
+        let default_fill = style.palette.default_fill.as_str();
+        assert_eq!(style.stage_fill("stage-producer-abc"), "#EFF6FF");
+        assert_eq!(style.stage_fill("stage-consumer-xyz"), "#ECFDF5");
+        assert_eq!(style.stage_fill("data-worker-123"), "#ECFDF5");
+        assert_eq!(style.stage_fill("partial-sum-stage"), "#FFFBEB");
+        assert_eq!(style.stage_fill("total-sum-stage"), "#FFF1F2");
+        assert_eq!(style.stage_fill("random-stage-name"), default_fill);
+        assert_eq!(style.stage_fill("STAGE-PRODUCER"), "#EFF6FF");
+        assert_eq!(style.stage_fill("Stage-Consumer"), "#ECFDF5");
+        assert_eq!(style.stage_fill(""), default_fill);
+    }
+
+    #[test]
+    fn test_style_file_overrides_defaults() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join(STYLE_FILE_NAME);
+        fs::write(
+            &path,
+            "[fonts]\nfamily = \"Fira Sans\"\n\n[palette.stages]\nsimulate = \"#123456\"\n",
+        )
+        .expect("write style");
+
+        let mut value = toml::Value::try_from(VizStyle::default()).expect("serialize");
+        merge_style_file(&mut value, &path).expect("merge");
+        let style: VizStyle = value.try_into().expect("deserialize");
+
+        assert_eq!(style.fonts.family, "Fira Sans");
+        assert_eq!(style.fonts.stage_size, "12");
+        assert_eq!(style.stage_fill("simulate-big"), "#123456");
+        assert_eq!(style.stage_fill("producer"), "#EFF6FF");
+    }
+}
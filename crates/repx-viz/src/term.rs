@@ -0,0 +1,274 @@
+use repx_core::model::JobId;
+use repx_core::tree_prefix::tree_prefix;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::generator::{Topology, VizGenerator};
+use crate::VizArgs;
+
+const EXPANDED: &str = "[-]";
+const REPEATED: &str = "[+]";
+const LEAF: &str = "───";
+
+struct TreeRow {
+    depth: usize,
+    is_last: bool,
+    marker: &'static str,
+    label: String,
+}
+
+struct StageTree<'t> {
+    pipeline_jobs: &'t BTreeMap<String, Vec<&'t JobId>>,
+    upstream: HashMap<String, BTreeSet<String>>,
+    by_clean: HashMap<String, &'t String>,
+}
+
+impl VizGenerator<'_> {
+    fn term_stage_label(&self, name: &str, job_ids: &[&JobId]) -> String {
+        let clean = self.stage_node(name);
+        let mut label = name.to_string();
+        if self.aggregates() {
+            label.push_str(&format!(" (x{})", job_ids.len()));
+        }
+        if self.is_scatter_gather(&clean) {
+            label.push_str(" scatter-gather");
+        }
+        if let Some(timing) = self.stage_timing(job_ids) {
+            label.push_str(&format!(" [{}]", timing));
+        }
+        if let Some(overlay) = self.stage_overlay(job_ids) {
+            label.push_str(&format!(" [{}: {}]", overlay.class, overlay.note));
+        }
+        if self.is_critical_stage(&clean) {
+            label.push_str(" *critical*");
+        }
+        label
+    }
+
+    fn push_stage(
+        &self,
+        rows: &mut Vec<TreeRow>,
+        tree: &StageTree,
+        run_stages: &BTreeSet<String>,
+        expanded: &mut HashSet<String>,
+        (clean, depth, is_last): (&str, usize, bool),
+    ) {
+        let Some(name) = tree.by_clean.get(clean) else {
+            return;
+        };
+        let job_ids = tree
+            .pipeline_jobs
+            .get(*name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let mut label = self.term_stage_label(name, job_ids);
+
+        let in_run = run_stages.contains(clean);
+        let parents: Vec<&String> = tree
+            .upstream
+            .get(clean)
+            .map(|set| set.iter().collect())
+            .unwrap_or_default();
+        let marker = if !in_run {
+            label.push_str(" (other run)");
+            LEAF
+        } else if parents.is_empty() {
+            LEAF
+        } else if !expanded.insert(clean.to_string()) {
+            REPEATED
+        } else {
+            EXPANDED
+        };
+        rows.push(TreeRow {
+            depth,
+            is_last,
+            marker,
+            label,
+        });
+
+        if marker == EXPANDED {
+            let count = parents.len();
+            for (i, parent) in parents.into_iter().enumerate() {
+                self.push_stage(
+                    rows,
+                    tree,
+                    run_stages,
+                    expanded,
+                    (parent, depth + 1, i + 1 == count),
+                );
+            }
+        }
+    }
+
+    fn push_run(
+        &self,
+        rows: &mut Vec<TreeRow>,
+        tree: Option<&StageTree>,
+        run_name: &str,
+        pipelines: &BTreeSet<String>,
+        (depth, is_last): (usize, bool),
+    ) {
+        let mut label = run_name.to_string();
+        if self.is_detached_run(run_name) {
+            label.push_str(" (not in any run)");
+        }
+        let Some(tree) = tree else {
+            rows.push(TreeRow {
+                depth,
+                is_last,
+                marker: LEAF,
+                label,
+            });
+            return;
+        };
+
+        let run_stages: BTreeSet<String> = pipelines.iter().map(|p| self.stage_node(p)).collect();
+        let feeds_run: HashSet<&String> = run_stages
+            .iter()
+            .filter_map(|stage| tree.upstream.get(stage))
+            .flatten()
+            .filter(|parent| run_stages.contains(*parent))
+            .collect();
+        let mut roots: Vec<&String> = run_stages
+            .iter()
+            .filter(|stage| !feeds_run.contains(stage))
+            .collect();
+        if roots.is_empty() {
+            roots = run_stages.iter().collect();
+        }
+
+        rows.push(TreeRow {
+            depth,
+            is_last,
+            marker: if roots.is_empty() { LEAF } else { EXPANDED },
+            label,
+        });
+        let mut expanded = HashSet::new();
+        let count = roots.len();
+        for (i, root) in roots.into_iter().enumerate() {
+            self.push_stage(
+                rows,
+                tree,
+                &run_stages,
+                &mut expanded,
+                (root, depth + 1, i + 1 == count),
+            );
+        }
+    }
+
+    pub(crate) fn generate_term(&self, args: &VizArgs) -> String {
+        let Topology {
+            job_to_run,
+            pipeline_jobs,
+            run_pipelines,
+            ..
+        } = self.topology();
+
+        let mut upstream: HashMap<String, BTreeSet<String>> = HashMap::new();
+        if args.show_intra_edges {
+            for (src, tgt) in self.collect_intra_edges() {
+                upstream.entry(tgt).or_default().insert(src);
+            }
+        }
+        if args.show_inter_edges {
+            for (src, tgt, _) in self.collect_inter_run_edges(&job_to_run) {
+                upstream.entry(tgt).or_default().insert(src);
+            }
+        }
+        let tree = StageTree {
+            pipeline_jobs: &pipeline_jobs,
+            upstream,
+            by_clean: pipeline_jobs
+                .keys()
+                .map(|name| (self.stage_node(name), name))
+                .collect(),
+        };
+        let tree = args.show_pipelines.then_some(&tree);
+
+        let run_to_group = if args.show_groups {
+            self.run_to_group()
+        } else {
+            HashMap::new()
+        };
+        let mut groups: BTreeMap<&str, Vec<&String>> = BTreeMap::new();
+        let mut ungrouped: Vec<&String> = Vec::new();
+        for run_name in run_pipelines.keys() {
+            match run_to_group.get(run_name) {
+                Some(group) => groups.entry(group.as_str()).or_default().push(run_name),
+                None => ungrouped.push(run_name),
+            }
+        }
+
+        let mut rows = Vec::new();
+        let top_count = groups.len() + ungrouped.len();
+        let mut top_index = 0;
+        for (group, runs) in &groups {
+            top_index += 1;
+            rows.push(TreeRow {
+                depth: 0,
+                is_last: top_index == top_count,
+                marker: EXPANDED,
+                label: format!("@{}", group),
+            });
+            for (i, run_name) in runs.iter().enumerate() {
+                self.push_run(
+                    &mut rows,
+                    tree,
+                    run_name,
+                    &run_pipelines[*run_name],
+                    (1, i + 1 == runs.len()),
+                );
+            }
+        }
+        for run_name in ungrouped {
+            top_index += 1;
+            self.push_run(
+                &mut rows,
+                tree,
+                run_name,
+                &run_pipelines[run_name],
+                (0, top_index == top_count),
+            );
+        }
+
+        let mut out = String::new();
+        let mut ancestor_is_last: Vec<bool> = Vec::new();
+        for row in rows {
+            ancestor_is_last.truncate(row.depth);
+            out.push_str(&tree_prefix(
+                &ancestor_is_last,
+                row.depth,
+                row.is_last,
+                row.marker,
+            ));
+            out.push_str(&row.label);
+            out.push('\n');
+            ancestor_is_last.push(row.is_last);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{lab, viz_args};
+
+    #[test]
+    fn test_term_tree_follows_dependencies() {
+        let lab = lab();
+        let mut args = viz_args();
+        args.show_groups = true;
+        let out = VizGenerator::new(&lab).generate_term(&args);
+
+        assert_eq!(
+            out,
+            "[-] @reports\n \
+             └[-] analysis\n   \
+             └[-] report (x1)\n     \
+             └─── consumer (x1) (other run)\n\
+             [-] sim\n \
+             └[-] consumer (x1)\n   \
+             └─── producer (x1)\n"
+        );
+    }
+}
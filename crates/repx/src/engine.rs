@@ -0,0 +1,392 @@
+use crate::error::{Error, Result};
+use crate::event::Event;
+use crate::status::StatusReport;
+use repx_client::{Client, SubmitOptions};
+use repx_core::{
+    config::{self, Config, Resources, SchedulerConfig, Target},
+    constants::targets,
+    lab::LabSource,
+    model::{ArtifactStore, JobId, RunId, SchedulerType},
+};
+use std::path::{Path, PathBuf};
+use std::sync::{atomic::AtomicBool, mpsc, Arc};
+use std::thread;
+
+enum ConfigSource {
+    User,
+    Toml(String),
+    File(PathBuf),
+    LocalOnly(PathBuf),
+}
+
+/// Configures how an [`Engine`] finds its lab and configuration.
+pub struct EngineBuilder {
+    lab: PathBuf,
+    config: ConfigSource,
+    resources: Option<PathBuf>,
+    target: Option<String>,
+}
+
+impl EngineBuilder {
+    /// Use a `config.toml` document instead of the user's XDG configuration.
+    pub fn config_toml(mut self, toml: impl Into<String>) -> Self {
+        self.config = ConfigSource::Toml(toml.into());
+        self
+    }
+
+    pub fn config_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config = ConfigSource::File(path.into());
+        self
+    }
+
+    /// Use a single local target rooted at `base_path` and ignore any user configuration.
+    pub fn local_only(mut self, base_path: impl Into<PathBuf>) -> Self {
+        self.config = ConfigSource::LocalOnly(base_path.into());
+        self
+    }
+
+    pub fn resources_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.resources = Some(path.into());
+        self
+    }
+
+    /// Default target for submissions that do not name one.
+    pub fn target(mut self, name: impl Into<String>) -> Self {
+        self.target = Some(name.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Engine> {
+        let resources = if matches!(self.config, ConfigSource::User) || self.resources.is_some() {
+            config::load_resources(self.resources.as_deref())?
+        } else {
+            None
+        };
+        let config = match self.config {
+            ConfigSource::User => config::load_config()?,
+            ConfigSource::Toml(s) => parse_config(&s)?,
+            ConfigSource::File(path) => {
+                let s = std::fs::read_to_string(&path)?;
+                parse_config(&s)?
+            }
+            ConfigSource::LocalOnly(base_path) => local_only_config(base_path),
+        };
+        let default_target = self
+            .target
+            .or_else(|| config.submission_target.clone())
+            .unwrap_or_else(|| targets::LOCAL.to_string());
+
+        let client = Client::new(config, LabSource::from_path(&self.lab))?;
+        Ok(Engine {
+            client,
+            resources,
+            default_target,
+        })
+    }
+}
+
+/// A loaded lab bound to a set of execution targets.
+#[derive(Clone)]
+pub struct Engine {
+    client: Client,
+    resources: Option<Resources>,
+    default_target: String,
+}
+
+/// Parameters for a single call to [`Engine::submit`].
+#[derive(Default)]
+pub struct Submission {
+    run_specs: Vec<String>,
+    target: Option<String>,
+    scheduler: Option<SchedulerType>,
+    jobs: Option<usize>,
+    continue_on_failure: bool,
+    ignore_windows: bool,
+    artifact_store: Option<ArtifactStore>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    events: Option<mpsc::Sender<Event>>,
+}
+
+impl Submission {
+    /// `run_specs` accepts the same run names, job ids and `@group` references as `repx run`.
+    pub fn new<I, S>(run_specs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            run_specs: run_specs.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn target(mut self, name: impl Into<String>) -> Self {
+        self.target = Some(name.into());
+        self
+    }
+
+    pub fn scheduler(mut self, scheduler: SchedulerType) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
+    pub fn continue_on_failure(mut self, yes: bool) -> Self {
+        self.continue_on_failure = yes;
+        self
+    }
+
+    /// Submit even if the target's submission windows are closed.
+    pub fn ignore_windows(mut self, yes: bool) -> Self {
+        self.ignore_windows = yes;
+        self
+    }
+
+    pub fn artifact_store(mut self, store: ArtifactStore) -> Self {
+        self.artifact_store = Some(store);
+        self
+    }
+
+    /// Setting the flag to `true` stops a running local submission.
+    pub fn cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(flag);
+        self
+    }
+
+    pub fn events(mut self, sender: mpsc::Sender<Event>) -> Self {
+        self.events = Some(sender);
+        self
+    }
+}
+
+/// Result of a completed [`Engine::submit`] call.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SubmitOutcome {
+    pub target: String,
+    pub scheduler: SchedulerType,
+    pub summary: String,
+}
+
+impl Engine {
+    /// Open a lab directory or tar using the user's repx configuration.
+    pub fn open(lab: impl AsRef<Path>) -> Result<Self> {
+        Self::builder(lab).build()
+    }
+
+    pub fn builder(lab: impl AsRef<Path>) -> EngineBuilder {
+        EngineBuilder {
+            lab: lab.as_ref().to_path_buf(),
+            config: ConfigSource::User,
+            resources: None,
+            target: None,
+        }
+    }
+
+    pub fn lab_id(&self) -> &str {
+        &self.client.lab().content_hash
+    }
+
+    pub fn lab_version(&self) -> &str {
+        &self.client.lab().lab_version
+    }
+
+    pub fn runs(&self) -> Vec<RunId> {
+        let mut runs: Vec<_> = self.client.lab().runs.keys().cloned().collect();
+        runs.sort();
+        runs
+    }
+
+    pub fn jobs(&self) -> Vec<JobId> {
+        let mut jobs: Vec<_> = self.client.lab().jobs.keys().cloned().collect();
+        jobs.sort();
+        jobs
+    }
+
+    pub fn targets(&self) -> Vec<String> {
+        self.client.config().targets.keys().cloned().collect()
+    }
+
+    pub fn default_target(&self) -> &str {
+        &self.default_target
+    }
+
+    /// Submit runs and block until the target scheduler has accepted them,
+    /// or, for the local scheduler, until they finish.
+    pub fn submit(&self, submission: Submission) -> Result<SubmitOutcome> {
+        if submission.run_specs.is_empty() {
+            return Err(Error::Submission("no run or job specified".to_string()));
+        }
+        let target_name = submission
+            .target
+            .unwrap_or_else(|| self.default_target.clone());
+        let target_config = self
+            .client
+            .config()
+            .targets
+            .get(&target_name)
+            .ok_or_else(|| Error::TargetNotFound(target_name.clone()))?;
+        let scheduler = submission
+            .scheduler
+            .or(target_config.default_scheduler)
+            .or(self.client.config().default_scheduler)
+            .unwrap_or(SchedulerType::Slurm);
+        let num_jobs = if scheduler == SchedulerType::Local {
+            Some(
+                submission
+                    .jobs
+                    .or_else(|| {
+                        target_config
+                            .local
+                            .as_ref()
+                            .and_then(|c| c.local_concurrency)
+                    })
+                    .unwrap_or_else(num_cpus::get),
+            )
+        } else {
+            None
+        };
+        let artifact_store = submission
+            .artifact_store
+            .or(target_config.artifact_store)
+            .unwrap_or_default();
+
+        let (event_sender, forwarder) = match submission.events {
+            Some(events) => {
+                let (tx, rx) = mpsc::channel();
+                let handle = thread::spawn(move || {
+                    for event in rx {
+                        if let Some(event) = Event::from_client(event) {
+                            if events.send(event).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                });
+                (Some(tx), Some(handle))
+            }
+            None => (None, None),
+        };
+
+        let options = SubmitOptions {
+            resources: self.resources.clone(),
+            num_jobs,
+            event_sender,
+            continue_on_failure: submission.continue_on_failure,
+            cancel_flag: submission.cancel_flag,
+            artifact_store,
+            ignore_submission_windows: submission.ignore_windows,
+            ..Default::default()
+        };
+        let result =
+            self.client
+                .submit_batch_run(submission.run_specs, &target_name, scheduler, options);
+        if let Some(handle) = forwarder {
+            if handle.join().is_err() {
+                tracing::warn!("event forwarding thread panicked");
+            }
+        }
+
+        Ok(SubmitOutcome {
+            target: target_name,
+            scheduler,
+            summary: result?,
+        })
+    }
+
+    pub fn status(&self) -> Result<StatusReport> {
+        let (runs, jobs) = self.client.get_statuses()?;
+        Ok(StatusReport::new(runs, jobs))
+    }
+
+    /// Copy the output directory of a job from `target` (or the default target) into `dest`.
+    pub fn fetch_outputs(
+        &self,
+        job_id: &JobId,
+        target: Option<&str>,
+        dest: impl AsRef<Path>,
+    ) -> Result<PathBuf> {
+        if !self.client.lab().jobs.contains_key(job_id) {
+            return Err(Error::UnknownJob(job_id.to_string()));
+        }
+        let target_name = target.unwrap_or(&self.default_target);
+        let mut report =
+            self.client
+                .fetch_outputs(std::slice::from_ref(job_id), target_name, dest.as_ref())?;
+        if let Some((_, e)) = report.failed.pop() {
+            return Err(e.into());
+        }
+        Ok(dest.as_ref().join(job_id.as_str()))
+    }
+
+    pub fn log_tail(&self, job_id: &JobId, lines: u32) -> Result<Vec<String>> {
+        Ok(self.client.get_log_tail(
+            job_id.clone(),
+            &self.default_target,
+            lines,
+            repx_client::client::LogType::Auto,
+        )?)
+    }
+
+    pub fn cancel(&self, job_id: &JobId) -> Result<()> {
+        self.client.cancel_job(job_id.clone())?;
+        Ok(())
+    }
+}
+
+fn parse_config(s: &str) -> Result<Config> {
+    toml::from_str(s).map_err(|e| Error::Config(e.to_string()))
+}
+
+fn local_only_config(base_path: PathBuf) -> Config {
+    let mut config = Config {
+        submission_target: Some(targets::LOCAL.to_string()),
+        default_scheduler: Some(SchedulerType::Local),
+        ..Default::default()
+    };
+    config.targets.insert(
+        targets::LOCAL.to_string(),
+        Target {
+            address: None,
+            base_path,
+            node_local_path: None,
+            default_scheduler: Some(SchedulerType::Local),
+            default_execution_type: None,
+            artifact_store: None,
+            mount_host_paths: false,
+            mount_paths: Vec::new(),
+            network: None,
+            seccomp: None,
+            pre_run: None,
+            post_run: None,
+            image_cache_max_size: None,
+            heartbeat_interval_secs: None,
+            ssh_control_persist_secs: None,
+            sync_concurrency: None,
+            rsync: None,
+            content_addressed_store: false,
+            object_store: None,
+            retry: None,
+            failure_rules: Vec::new(),
+            structured_logs: false,
+            auto_prune_steps: false,
+            env: Default::default(),
+            env_passthrough: Vec::new(),
+            local: Some(SchedulerConfig::default()),
+            slurm: None,
+            sge: None,
+            flux: None,
+            aws_batch: None,
+            container: None,
+            setup_commands: Vec::new(),
+            max_in_flight: None,
+            agent: false,
+            submission_windows: None,
+        },
+    );
+    config
+}
@@ -0,0 +1,61 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("configuration error: {0}")]
+    Config(String),
+
+    #[error("lab error: {0}")]
+    Lab(String),
+
+    #[error("target '{0}' is not configured")]
+    TargetNotFound(String),
+
+    #[error("job '{0}' is not part of this lab")]
+    UnknownJob(String),
+
+    #[error("submission failed: {0}")]
+    Submission(String),
+
+    #[error("transfer failed: {0}")]
+    Transfer(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl From<repx_core::errors::CoreError> for Error {
+    fn from(e: repx_core::errors::CoreError) -> Self {
+        use repx_core::errors::CoreError;
+        match e {
+            CoreError::Io(io) => Error::Io(io),
+            CoreError::TargetNotConfigured { name } => Error::TargetNotFound(name),
+            CoreError::LabNotFound(_)
+            | CoreError::MetadataNotFound(_)
+            | CoreError::IntegrityError(_)
+            | CoreError::IntegrityHashMismatch { .. }
+            | CoreError::IntegrityFileMissing(_)
+            | CoreError::InconsistentMetadata { .. }
+            | CoreError::MissingExecutable { .. }
+            | CoreError::StepError { .. } => Error::Lab(e.to_string()),
+            CoreError::CommandFailed(msg) => Error::Transfer(msg),
+            other => Error::Config(other.to_string()),
+        }
+    }
+}
+
+impl From<repx_client::error::ClientError> for Error {
+    fn from(e: repx_client::error::ClientError) -> Self {
+        use repx_client::error::ClientError;
+        match e {
+            ClientError::Config(core) => core.into(),
+            ClientError::Io(io) => Error::Io(io),
+            ClientError::TargetNotFound(name) => Error::TargetNotFound(name),
+            ClientError::Domain(d) => Error::Lab(d.to_string()),
+            other => Error::Submission(other.to_string()),
+        }
+    }
+}
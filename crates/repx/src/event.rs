@@ -0,0 +1,107 @@
+use repx_client::ClientEvent;
+use repx_core::model::JobId;
+use std::time::Duration;
+
+/// Progress notification emitted while a [`crate::Submission`] is running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event {
+    Stage(String),
+    JobSubmitted {
+        job_id: JobId,
+        scheduler_id: u64,
+    },
+    JobStarted {
+        job_id: JobId,
+    },
+    JobSucceeded {
+        job_id: JobId,
+        wall_time: Option<Duration>,
+    },
+    JobFailed {
+        job_id: JobId,
+        wall_time: Option<Duration>,
+    },
+    JobBlocked {
+        job_id: JobId,
+        blocked_by: JobId,
+    },
+    Progress {
+        running: usize,
+        succeeded: usize,
+        failed: usize,
+        pending: usize,
+        total: usize,
+    },
+    /// The target's submission window is closed; the submission resumes at `opens_at`.
+    Held {
+        target: String,
+        reason: String,
+        opens_at: chrono::DateTime<chrono::Local>,
+    },
+}
+
+impl Event {
+    pub(crate) fn from_client(event: ClientEvent) -> Option<Self> {
+        let stage = |s: &str| Some(Event::Stage(s.to_string()));
+        match event {
+            ClientEvent::DeployingBinary => stage("deploying repx binary"),
+            ClientEvent::CreatingLabTar => stage("creating lab tar"),
+            ClientEvent::SyncingLabTar => stage("syncing lab tar"),
+            ClientEvent::CheckingJobStatuses => stage("checking job statuses"),
+            ClientEvent::PreparingInputs { .. } => stage("preparing inputs"),
+            ClientEvent::GeneratingSlurmScripts { .. } => stage("generating slurm scripts"),
+            ClientEvent::ExecutingOrchestrator => stage("executing orchestrator"),
+            ClientEvent::SyncingArtifacts { .. } => stage("syncing artifacts"),
+            ClientEvent::SubmittingJobs { .. } => stage("submitting jobs"),
+            ClientEvent::JobSubmitted {
+                job_id, slurm_id, ..
+            } => Some(Event::JobSubmitted {
+                job_id,
+                scheduler_id: slurm_id,
+            }),
+            ClientEvent::JobStarted { job_id, phase, .. } if phase.is_none() => {
+                Some(Event::JobStarted { job_id })
+            }
+            ClientEvent::JobSucceeded {
+                job_id,
+                phase,
+                wall_time,
+            } if phase.is_none() => Some(Event::JobSucceeded { job_id, wall_time }),
+            ClientEvent::JobFailed {
+                job_id,
+                phase,
+                wall_time,
+            } if phase.is_none() => Some(Event::JobFailed { job_id, wall_time }),
+            ClientEvent::JobBlocked {
+                job_id,
+                blocked_by,
+                phase,
+            } if phase.is_none() => Some(Event::JobBlocked { job_id, blocked_by }),
+            ClientEvent::LocalProgress {
+                running,
+                succeeded,
+                failed,
+                pending,
+                total,
+                ..
+            } => Some(Event::Progress {
+                running,
+                succeeded,
+                failed,
+                pending,
+                total,
+            }),
+            ClientEvent::SubmissionHeld {
+                target,
+                reason,
+                opens_at,
+            } => Some(Event::Held {
+                target,
+                reason,
+                opens_at,
+            }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Embedding API for repx.
+//!
+//! This crate is the supported way to drive repx from other Rust programs.
+//! It wraps lab loading, submission, status queries and output retrieval
+//! behind a small set of types whose shape follows semver, so callers do not
+//! depend on the internal `repx-*` crates directly.
+//!
+//! ```no_run
+//! use repx::{Engine, Submission};
+//!
+//! let engine = Engine::open("./result")?;
+//! let outcome = engine.submit(Submission::new(["my-run"]))?;
+//! println!("{}", outcome.summary);
+//!
+//! let report = engine.status()?;
+//! for (job, status) in report.jobs() {
+//!     println!("{job}: {status:?}");
+//! }
+//! # Ok::<(), repx::Error>(())
+//! ```
+
+mod engine;
+mod error;
+mod event;
+mod status;
+
+pub use engine::{Engine, EngineBuilder, Submission, SubmitOutcome};
+pub use error::{Error, Result};
+pub use event::Event;
+pub use status::{JobState, StatusReport};
+
+pub use repx_core::builder::{ExecutableBuilder, JobBuilder, LabBuilder, RunBuilder};
+pub use repx_core::model::{ArtifactStore, FailureClass, JobId, RunId, SchedulerType};
@@ -0,0 +1,113 @@
+use repx_core::engine::JobStatus;
+use repx_core::model::{FailureClass, JobId, RunId};
+use std::collections::{BTreeMap, HashMap};
+
+/// State of a single job as observed on the configured targets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JobState {
+    Succeeded {
+        location: String,
+    },
+    Failed {
+        location: String,
+        class: Option<FailureClass>,
+        reason: Option<String>,
+    },
+    TimedOut {
+        location: String,
+    },
+    Cancelled {
+        location: String,
+    },
+    Lost {
+        location: String,
+    },
+    Pending,
+    Queued,
+    Running,
+    Blocked {
+        missing_deps: Vec<JobId>,
+    },
+}
+
+impl JobState {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            JobState::Succeeded { .. }
+                | JobState::Failed { .. }
+                | JobState::TimedOut { .. }
+                | JobState::Cancelled { .. }
+                | JobState::Lost { .. }
+        )
+    }
+}
+
+impl From<JobStatus> for JobState {
+    fn from(status: JobStatus) -> Self {
+        match status {
+            JobStatus::Succeeded { location } => JobState::Succeeded { location },
+            JobStatus::Failed {
+                location,
+                class,
+                reason,
+            } => JobState::Failed {
+                location,
+                class,
+                reason,
+            },
+            JobStatus::TimedOut { location } => JobState::TimedOut { location },
+            JobStatus::Cancelled { location } => JobState::Cancelled { location },
+            JobStatus::Lost { location } => JobState::Lost { location },
+            JobStatus::Pending => JobState::Pending,
+            JobStatus::Queued => JobState::Queued,
+            JobStatus::Running => JobState::Running,
+            JobStatus::Blocked { missing_deps } => {
+                let mut missing_deps: Vec<_> = missing_deps.into_iter().collect();
+                missing_deps.sort();
+                JobState::Blocked { missing_deps }
+            }
+        }
+    }
+}
+
+/// Snapshot of run and job states returned by [`crate::Engine::status`].
+#[derive(Debug, Clone, Default)]
+pub struct StatusReport {
+    runs: BTreeMap<RunId, JobState>,
+    jobs: BTreeMap<JobId, JobState>,
+}
+
+impl StatusReport {
+    pub(crate) fn new(runs: BTreeMap<RunId, JobStatus>, jobs: HashMap<JobId, JobStatus>) -> Self {
+        Self {
+            runs: runs.into_iter().map(|(k, v)| (k, v.into())).collect(),
+            jobs: jobs.into_iter().map(|(k, v)| (k, v.into())).collect(),
+        }
+    }
+
+    pub fn runs(&self) -> impl Iterator<Item = (&RunId, &JobState)> {
+        self.runs.iter()
+    }
+
+    pub fn jobs(&self) -> impl Iterator<Item = (&JobId, &JobState)> {
+        self.jobs.iter()
+    }
+
+    pub fn run(&self, run: &RunId) -> Option<&JobState> {
+        self.runs.get(run)
+    }
+
+    pub fn job(&self, job: &JobId) -> Option<&JobState> {
+        self.jobs.get(job)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.jobs.values().all(JobState::is_finished)
+    }
+
+    pub fn count(&self, predicate: impl Fn(&JobState) -> bool) -> usize {
+        self.jobs.values().filter(|s| predicate(s)).count()
+    }
+}
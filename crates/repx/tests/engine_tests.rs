@@ -0,0 +1,74 @@
+#![allow(clippy::expect_used)]
+
+use repx::{Engine, Error, ExecutableBuilder, JobBuilder, JobId, JobState, LabBuilder, RunBuilder};
+
+fn write_lab(dir: &std::path::Path) {
+    LabBuilder::new("1.0")
+        .run(
+            RunBuilder::new("pipeline")
+                .job(JobBuilder::new("aaaa-first-1.0").main(
+                    ExecutableBuilder::script("run.sh", "#!/bin/sh\n").output("data", "$out/data"),
+                ))
+                .job(JobBuilder::new("bbbb-second-1.0").main(
+                    ExecutableBuilder::script("run.sh", "#!/bin/sh\n").input_from(
+                        "aaaa-first-1.0",
+                        "data",
+                        "data",
+                    ),
+                )),
+        )
+        .write_to(dir)
+        .expect("lab must be written");
+}
+
+#[test]
+fn test_engine_reports_pending_jobs_for_fresh_lab() {
+    let lab = tempfile::tempdir().expect("tempdir creation must succeed");
+    let store = tempfile::tempdir().expect("tempdir creation must succeed");
+    write_lab(lab.path());
+
+    let engine = Engine::builder(lab.path())
+        .local_only(store.path())
+        .build()
+        .expect("engine must open");
+
+    assert_eq!(engine.runs().len(), 1);
+    assert_eq!(engine.default_target(), "local");
+
+    let report = engine.status().expect("status must succeed");
+    assert!(!report.is_complete());
+    assert_eq!(
+        report.job(&JobId::from("aaaa-first-1.0")),
+        Some(&JobState::Pending)
+    );
+}
+
+#[test]
+fn test_engine_fetches_job_outputs() {
+    let lab = tempfile::tempdir().expect("tempdir creation must succeed");
+    let store = tempfile::tempdir().expect("tempdir creation must succeed");
+    let dest = tempfile::tempdir().expect("tempdir creation must succeed");
+    write_lab(lab.path());
+
+    let out_dir = store.path().join("outputs/aaaa-first-1.0/out");
+    std::fs::create_dir_all(&out_dir).expect("dir creation must succeed");
+    std::fs::write(out_dir.join("data"), "42").expect("file write must succeed");
+
+    let engine = Engine::builder(lab.path())
+        .local_only(store.path())
+        .build()
+        .expect("engine must open");
+
+    let fetched = engine
+        .fetch_outputs(&JobId::from("aaaa-first-1.0"), None, dest.path())
+        .expect("fetch must succeed");
+    assert_eq!(
+        std::fs::read_to_string(fetched.join("data")).expect("fetched file must exist"),
+        "42"
+    );
+
+    assert!(matches!(
+        engine.fetch_outputs(&JobId::from("missing"), None, dest.path()),
+        Err(Error::UnknownJob(_))
+    ));
+}
@@ -63,6 +63,9 @@ pub struct StageTemplate {
     pub resources: Option<BTreeMap<String, serde_json::Value>>,
     pub executables: BTreeMap<String, ExecutableTemplate>,
 
+    #[serde(default)]
+    pub data_mounts: Vec<String>,
+
     #[serde(default)]
     pub parameter_defaults: BTreeMap<String, serde_json::Value>,
 
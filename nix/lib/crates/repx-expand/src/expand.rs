@@ -22,6 +22,7 @@ pub struct ExpandedJob {
     pub input_mappings: Vec<InputMapping>,
     pub executables: BTreeMap<String, ExpandedExecutable>,
     pub resources: Option<BTreeMap<String, serde_json::Value>>,
+    pub data_mounts: Vec<String>,
     pub script_sources: Vec<ScriptSource>,
 }
 
@@ -159,6 +160,7 @@ fn expand_stage(
         input_mappings: stage.input_mappings.clone(),
         executables,
         resources: stage.resources.clone(),
+        data_mounts: stage.data_mounts.clone(),
         script_sources,
     }
 }
@@ -18,6 +18,8 @@ struct JobMetadata {
     executables: BTreeMap<String, Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     resource_hints: Option<BTreeMap<String, Value>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    data_mounts: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -94,6 +96,7 @@ pub fn write_all_metadata(lab: &ExpandedLab, output_dir: &Path) -> Result<Vec<Fi
                     params: job.resolved_parameters.clone(),
                     executables,
                     resource_hints: job.resources.clone(),
+                    data_mounts: job.data_mounts.clone(),
                 },
             );
         }
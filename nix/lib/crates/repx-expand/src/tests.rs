@@ -56,6 +56,7 @@ mod integration {
                 );
                 m
             },
+            data_mounts: vec![],
             parameter_defaults,
             script_drv: Some(script_drv.into()),
             scatter_drv: None,
@@ -16,6 +16,7 @@ let
         "parameters"
         "passthru"
         "resources"
+        "dataMounts"
         "override"
         "overrideDerivation"
       ];
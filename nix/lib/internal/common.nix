@@ -4,6 +4,8 @@ let
     "cpus"
     "time"
     "partition"
+    "disk"
+    "priority"
     "sbatch_opts"
   ];
 in
@@ -61,6 +61,10 @@ let
       pkg = pkgs.pkgsStatic.bubblewrap;
       bins = [ "bwrap" ];
     }
+    {
+      pkg = pkgs.pkgsStatic.proot;
+      bins = [ "proot" ];
+    }
     {
       pkg = pkgs.pkgsStatic.openssh;
       bins = null;
@@ -373,5 +373,6 @@ else
         resources = stageDef.resources or null;
         contextStr = "scatter-gather stage '${groupPname}' resources";
       };
+      data_mounts = stageDef.dataMounts or [ ];
     };
   }
@@ -49,6 +49,7 @@ in
     outputs = outputsDef;
     input_mappings = stageDef.inputMappings or [ ];
     resources = stageDef.resources or null;
+    data_mounts = stageDef.dataMounts or [ ];
     parameter_defaults = stageDef.parameters or { };
     executables = {
       main = {